@@ -0,0 +1,13 @@
+fn main() {
+    // Recompile when a migration is added, since sqlx::migrate! embeds them at build time
+    println!("cargo:rerun-if-changed=migrations");
+
+    // The gRPC feed's messages and service are generated from its protobuf definition
+    // (requires protoc)
+    #[cfg(feature = "grpc")]
+    {
+        println!("cargo:rerun-if-changed=proto/liquidation_feed.proto");
+        tonic_build::compile_protos("proto/liquidation_feed.proto")
+            .expect("failed to compile proto/liquidation_feed.proto");
+    }
+}
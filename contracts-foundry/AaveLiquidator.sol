@@ -12,6 +12,23 @@ import {Ownable} from "@openzeppelin/contracts/access/Ownable.sol";
 import {ReentrancyGuard} from "@openzeppelin/contracts/security/ReentrancyGuard.sol";
 import {ISwapRouter} from "@uniswap/v3-periphery/contracts/interfaces/ISwapRouter.sol";
 
+interface IBalancerVault {
+    function flashLoan(
+        address recipient,
+        IERC20[] memory tokens,
+        uint256[] memory amounts,
+        bytes memory userData
+    ) external;
+}
+
+interface IMorpho {
+    function flashLoan(
+        address token,
+        uint256 assets,
+        bytes calldata data
+    ) external;
+}
+
 /**
  * @title AaveLiquidator
  * @notice Flash loan liquidation contract for Aave v3 on Base
@@ -30,6 +47,17 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
     uint24 public defaultSwapFee = 3000; // 0.3% default fee tier, now configurable
     uint256 public minProfitThreshold = 5 * 1e8;
 
+    // Flash loan lenders accepted by liquidateWithFlashLoan
+    uint8 public constant FLASH_LOAN_AAVE = 0;
+    uint8 public constant FLASH_LOAN_BALANCER = 1;
+    uint8 public constant FLASH_LOAN_MORPHO = 2;
+    address public balancerVault = 0xBA12222222228d8Ba445958a75a0704d566BF2C8;
+    address public morpho = 0xBBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb;
+
+    // Hash of the params of the Balancer/Morpho loan in flight; their callbacks carry no
+    // initiator, so this stops anyone else's loan from driving a liquidation through us
+    bytes32 private pendingFlashLoan;
+
     struct LiquidationParams {
         address user;
         address collateralAsset;
@@ -38,6 +66,14 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
         bool receiveAToken;
         uint256 collateralAssetId;
         uint256 debtAssetId;
+        // Least profit, in debt asset units after repaying the flash loan, to accept
+        uint256 minProfit;
+        // Aggregator (1inch / 0x) swap of the seized collateral; empty swapData falls back
+        // to the Uniswap V3 router
+        address swapTarget;
+        address swapSpender;
+        bytes swapData;
+        uint256 swapMinAmountOut;
     }
 
     event LiquidationExecuted(
@@ -96,40 +132,161 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
         uint16 collateralAssetId,
         uint16 debtAssetId
     ) external onlyOwner nonReentrant {
-        LiquidationParams memory params = LiquidationParams({
-            user: user,
-            collateralAsset: collateralAsset,
-            debtAsset: debtAsset,
-            debtToCover: debtToCover,
-            receiveAToken: receiveAToken,
-            collateralAssetId: collateralAssetId,
-            debtAssetId: debtAssetId
-        });
+        _liquidateWithFlashLoan(
+            FLASH_LOAN_AAVE,
+            LiquidationParams({
+                user: user,
+                collateralAsset: collateralAsset,
+                debtAsset: debtAsset,
+                debtToCover: debtToCover,
+                receiveAToken: receiveAToken,
+                collateralAssetId: collateralAssetId,
+                debtAssetId: debtAssetId,
+                minProfit: 0,
+                swapTarget: address(0),
+                swapSpender: address(0),
+                swapData: "",
+                swapMinAmountOut: 0
+            })
+        );
+    }
+
+    /**
+     * @notice Same as liquidate, borrowing the debt asset from the given lender
+     * @param flashLoanSource FLASH_LOAN_AAVE, FLASH_LOAN_BALANCER or FLASH_LOAN_MORPHO
+     */
+    function liquidateWithFlashLoan(
+        uint8 flashLoanSource,
+        address user,
+        address collateralAsset,
+        address debtAsset,
+        uint256 debtToCover,
+        bool receiveAToken,
+        uint16 collateralAssetId,
+        uint16 debtAssetId
+    ) external onlyOwner nonReentrant {
+        _liquidateWithFlashLoan(
+            flashLoanSource,
+            LiquidationParams({
+                user: user,
+                collateralAsset: collateralAsset,
+                debtAsset: debtAsset,
+                debtToCover: debtToCover,
+                receiveAToken: receiveAToken,
+                collateralAssetId: collateralAssetId,
+                debtAssetId: debtAssetId,
+                minProfit: 0,
+                swapTarget: address(0),
+                swapSpender: address(0),
+                swapData: "",
+                swapMinAmountOut: 0
+            })
+        );
+    }
+
+    /**
+     * @notice Same as liquidateWithFlashLoan, reverting unless the liquidation clears at least
+     *         minProfit of the debt asset once the flash loan is repaid. Guards against the
+     *         market moving between simulation and inclusion.
+     * @param minProfit Least profit to accept, in debt asset units
+     */
+    function liquidateWithMinProfit(
+        uint8 flashLoanSource,
+        address user,
+        address collateralAsset,
+        address debtAsset,
+        uint256 debtToCover,
+        bool receiveAToken,
+        uint16 collateralAssetId,
+        uint16 debtAssetId,
+        uint256 minProfit
+    ) external onlyOwner nonReentrant {
+        _liquidateWithFlashLoan(
+            flashLoanSource,
+            LiquidationParams({
+                user: user,
+                collateralAsset: collateralAsset,
+                debtAsset: debtAsset,
+                debtToCover: debtToCover,
+                receiveAToken: receiveAToken,
+                collateralAssetId: collateralAssetId,
+                debtAssetId: debtAssetId,
+                minProfit: minProfit,
+                swapTarget: address(0),
+                swapSpender: address(0),
+                swapData: "",
+                swapMinAmountOut: 0
+            })
+        );
+    }
 
+    /**
+     * @notice Same as liquidateWithMinProfit, converting the seized collateral with the
+     *         aggregator calldata in params instead of the Uniswap V3 router
+     * @param params Liquidation, minimum profit and the swap quoted for the expected collateral
+     */
+    function liquidateWithSwap(
+        uint8 flashLoanSource,
+        LiquidationParams calldata params
+    ) external onlyOwner nonReentrant {
+        require(params.swapData.length > 0, "Missing swap calldata");
+        _liquidateWithFlashLoan(flashLoanSource, params);
+    }
+
+    function _liquidateWithFlashLoan(
+        uint8 flashLoanSource,
+        LiquidationParams memory params
+    ) internal {
         bytes memory paramsBytes = abi.encode(params);
 
-        uint256 actualDebtToCover = debtToCover;
-        if (debtToCover == type(uint256).max) {
-            uint256 assetDebt = _getUserAssetDebt(debtAsset, user);
+        uint256 actualDebtToCover = params.debtToCover;
+        if (params.debtToCover == type(uint256).max) {
+            uint256 assetDebt = _getUserAssetDebt(params.debtAsset, params.user);
             actualDebtToCover = assetDebt / 2;
         }
 
-        address[] memory assets = new address[](1);
-        assets[0] = debtAsset;
-        uint256[] memory amounts = new uint256[](1);
-        amounts[0] = actualDebtToCover;
-        uint256[] memory modes = new uint256[](1);
-        modes[0] = 0;
-
-        IPool(POOL_ADDRESS).flashLoan(
-            address(this),
-            assets,
-            amounts,
-            modes,
-            address(this),
-            paramsBytes,
-            0
-        );
+        if (flashLoanSource == FLASH_LOAN_AAVE) {
+            address[] memory assets = new address[](1);
+            assets[0] = params.debtAsset;
+            uint256[] memory amounts = new uint256[](1);
+            amounts[0] = actualDebtToCover;
+            uint256[] memory modes = new uint256[](1);
+            modes[0] = 0;
+
+            IPool(POOL_ADDRESS).flashLoan(
+                address(this),
+                assets,
+                amounts,
+                modes,
+                address(this),
+                paramsBytes,
+                0
+            );
+        } else if (flashLoanSource == FLASH_LOAN_BALANCER) {
+            IERC20[] memory tokens = new IERC20[](1);
+            tokens[0] = IERC20(params.debtAsset);
+            uint256[] memory amounts = new uint256[](1);
+            amounts[0] = actualDebtToCover;
+
+            pendingFlashLoan = keccak256(paramsBytes);
+            IBalancerVault(balancerVault).flashLoan(
+                address(this),
+                tokens,
+                amounts,
+                paramsBytes
+            );
+            pendingFlashLoan = bytes32(0);
+        } else if (flashLoanSource == FLASH_LOAN_MORPHO) {
+            pendingFlashLoan = keccak256(paramsBytes);
+            IMorpho(morpho).flashLoan(
+                params.debtAsset,
+                actualDebtToCover,
+                paramsBytes
+            );
+            pendingFlashLoan = bytes32(0);
+        } else {
+            revert("Unknown flash loan source");
+        }
     }
 
     function executeOperation(
@@ -143,30 +300,83 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
         require(initiator == address(this), "Invalid initiator");
 
         LiquidationParams memory p = abi.decode(params, (LiquidationParams));
-        address debtAsset = assets[0];
-        uint256 amount = amounts[0];
-        uint256 premium = premiums[0];
+        uint256 totalRepay = _liquidateAndRepay(
+            p,
+            assets[0],
+            amounts[0],
+            premiums[0]
+        );
+        // The pool pulls the repayment
+        IERC20(assets[0]).safeApprove(POOL_ADDRESS, totalRepay);
+        return true;
+    }
+
+    /// @notice Balancer Vault flash loan callback
+    function receiveFlashLoan(
+        IERC20[] calldata tokens,
+        uint256[] calldata amounts,
+        uint256[] calldata feeAmounts,
+        bytes calldata userData
+    ) external {
+        require(msg.sender == balancerVault, "Caller must be Balancer Vault");
+        require(
+            pendingFlashLoan == keccak256(userData),
+            "Unexpected flash loan"
+        );
+
+        LiquidationParams memory p = abi.decode(userData, (LiquidationParams));
+        uint256 totalRepay = _liquidateAndRepay(
+            p,
+            address(tokens[0]),
+            amounts[0],
+            feeAmounts[0]
+        );
+        // Balancer expects the repayment transferred back before the callback returns
+        tokens[0].safeTransfer(balancerVault, totalRepay);
+    }
+
+    /// @notice Morpho Blue flash loan callback
+    function onMorphoFlashLoan(uint256 assets, bytes calldata data) external {
+        require(msg.sender == morpho, "Caller must be Morpho");
+        require(pendingFlashLoan == keccak256(data), "Unexpected flash loan");
 
+        LiquidationParams memory p = abi.decode(data, (LiquidationParams));
+        uint256 totalRepay = _liquidateAndRepay(p, p.debtAsset, assets, 0);
+        // Morpho pulls the repayment
+        IERC20(p.debtAsset).safeApprove(morpho, totalRepay);
+    }
+
+    /// @dev Liquidate with the borrowed `amount` of `debtAsset`, swap the seized collateral
+    ///      back and return what the lender must be repaid
+    function _liquidateAndRepay(
+        LiquidationParams memory p,
+        address debtAsset,
+        uint256 amount,
+        uint256 premium
+    ) internal returns (uint256 totalRepay) {
         IERC20(debtAsset).safeApprove(POOL_ADDRESS, amount);
         _executeLiquidation(p, amount);
+        IERC20(debtAsset).safeApprove(POOL_ADDRESS, 0);
 
         uint256 collateralBalance = IERC20(p.collateralAsset).balanceOf(
             address(this)
         );
         uint256 debtAssetBalance = IERC20(debtAsset).balanceOf(address(this));
         if (p.collateralAsset != debtAsset && collateralBalance > 0) {
-            debtAssetBalance += _swapCollateralToDebt(
-                p.collateralAsset,
-                debtAsset,
-                collateralBalance
-            );
+            debtAssetBalance += p.swapData.length > 0
+                ? _swapWithAggregator(p, debtAsset, collateralBalance)
+                : _swapCollateralToDebt(
+                    p.collateralAsset,
+                    debtAsset,
+                    collateralBalance
+                );
         }
 
-        uint256 totalRepay = amount + premium;
+        totalRepay = amount + premium;
         require(debtAssetBalance >= totalRepay, "Insufficient funds");
-        IERC20(debtAsset).safeApprove(POOL_ADDRESS, totalRepay);
 
         uint256 profit = debtAssetBalance - totalRepay;
+        require(profit >= p.minProfit, "Profit below minimum");
         emit LiquidationExecuted(
             p.user,
             p.collateralAsset,
@@ -175,7 +385,6 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
             collateralBalance,
             profit
         );
-        return true;
     }
 
     function _executeLiquidation(
@@ -247,6 +456,32 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
         IERC20(inToken).safeApprove(SWAP_ROUTER, 0);
     }
 
+    /// @dev Run the aggregator calldata against up to `amountIn` of collateral and return the
+    ///      debt asset it delivered
+    function _swapWithAggregator(
+        LiquidationParams memory p,
+        address outToken,
+        uint256 amountIn
+    ) internal returns (uint256 amountOut) {
+        require(
+            p.swapTarget != address(0) &&
+                p.swapTarget != POOL_ADDRESS &&
+                p.swapTarget != p.collateralAsset &&
+                p.swapTarget != outToken,
+            "Invalid swap target"
+        );
+
+        uint256 balanceBefore = IERC20(outToken).balanceOf(address(this));
+        IERC20(p.collateralAsset).safeApprove(p.swapSpender, amountIn);
+        (bool success, ) = p.swapTarget.call(p.swapData);
+        require(success, "Aggregator swap failed");
+        // Clean up approval
+        IERC20(p.collateralAsset).safeApprove(p.swapSpender, 0);
+
+        amountOut = IERC20(outToken).balanceOf(address(this)) - balanceBefore;
+        require(amountOut >= p.swapMinAmountOut, "Slippage tolerance exceeded");
+    }
+
     function withdraw(
         address asset,
         uint256 amount,
@@ -294,6 +529,16 @@ contract AaveLiquidator is IFlashLoanReceiver, Ownable, ReentrancyGuard {
         defaultSwapFee = _fee;
     }
 
+    function setBalancerVault(address _balancerVault) external onlyOwner {
+        require(_balancerVault != address(0), "Invalid Balancer Vault");
+        balancerVault = _balancerVault;
+    }
+
+    function setMorpho(address _morpho) external onlyOwner {
+        require(_morpho != address(0), "Invalid Morpho address");
+        morpho = _morpho;
+    }
+
     function emergencyApprove(
         address token,
         address spender,
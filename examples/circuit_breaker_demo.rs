@@ -20,7 +20,6 @@ fn create_demo_config() -> BotConfig {
             .to_string(),
         liquidator_contract: None,
         min_profit_threshold: U256::from(1000000000000000000u64), // 1 ETH
-        gas_price_multiplier: 2,
         target_user: None,
         database_url: "sqlite::memory:".to_string(),
         health_factor_threshold: U256::from(1100000000000000000u64), // 1.1
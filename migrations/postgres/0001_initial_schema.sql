@@ -0,0 +1,144 @@
+-- Baseline schema. Every statement is idempotent so databases created before versioned
+-- migrations can be adopted by recording this migration as applied.
+
+-- user_positions
+CREATE TABLE IF NOT EXISTS user_positions (
+    address VARCHAR PRIMARY KEY,
+    total_collateral_base VARCHAR NOT NULL,
+    total_debt_base VARCHAR NOT NULL,
+    available_borrows_base VARCHAR NOT NULL,
+    current_liquidation_threshold VARCHAR NOT NULL,
+    ltv VARCHAR NOT NULL,
+    health_factor VARCHAR NOT NULL,
+    last_updated TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    is_at_risk BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+-- liquidation_events
+CREATE TABLE IF NOT EXISTS liquidation_events (
+    id SERIAL PRIMARY KEY,
+    user_address VARCHAR NOT NULL,
+    collateral_asset VARCHAR NOT NULL,
+    debt_asset VARCHAR NOT NULL,
+    debt_covered VARCHAR NOT NULL,
+    collateral_received VARCHAR NOT NULL,
+    profit VARCHAR NOT NULL,
+    tx_hash VARCHAR,
+    block_number BIGINT,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    strategy_label VARCHAR NOT NULL DEFAULT 'default',
+    requested_debt VARCHAR,
+    tx_index BIGINT,
+    gas_used VARCHAR,
+    effective_gas_price VARCHAR,
+    succeeded BOOLEAN
+);
+
+-- liquidation_audit (intent + settlement records linked by tx hash)
+CREATE TABLE IF NOT EXISTS liquidation_audit (
+    id SERIAL PRIMARY KEY,
+    tx_hash VARCHAR NOT NULL,
+    phase VARCHAR NOT NULL,
+    user_address VARCHAR,
+    nonce BIGINT,
+    gas_price VARCHAR,
+    gas_limit VARCHAR,
+    estimated_profit VARCHAR,
+    block_number BIGINT,
+    tx_index BIGINT,
+    gas_used VARCHAR,
+    realized_profit VARCHAR,
+    succeeded BOOLEAN,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- liquidation_queue (pending priority liquidations, survives restarts)
+CREATE TABLE IF NOT EXISTS liquidation_queue (
+    user_address VARCHAR PRIMARY KEY,
+    queued_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- profitability_divergences (shadow vs live profitability model)
+CREATE TABLE IF NOT EXISTS profitability_divergences (
+    id SERIAL PRIMARY KEY,
+    user_address VARCHAR NOT NULL,
+    collateral_asset VARCHAR NOT NULL,
+    debt_asset VARCHAR NOT NULL,
+    live_model VARCHAR NOT NULL,
+    shadow_model VARCHAR NOT NULL,
+    live_profit VARCHAR NOT NULL,
+    shadow_profit VARCHAR NOT NULL,
+    divergence_bps BIGINT NOT NULL,
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- competitors (other liquidators seen racing in the mempool)
+CREATE TABLE IF NOT EXISTS competitors (
+    liquidator_address VARCHAR PRIMARY KEY,
+    sightings BIGINT NOT NULL DEFAULT 0,
+    races BIGINT NOT NULL DEFAULT 0,
+    last_user_address VARCHAR NOT NULL,
+    last_tx_hash VARCHAR NOT NULL,
+    last_priority_fee VARCHAR NOT NULL,
+    first_seen TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_seen TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- price_feeds (oracle price history; timestamps are unix seconds so
+-- downsampling buckets are computed the same way on both backends)
+CREATE TABLE IF NOT EXISTS price_feeds (
+    id SERIAL PRIMARY KEY,
+    asset_address VARCHAR NOT NULL,
+    resolution VARCHAR NOT NULL DEFAULT 'raw',
+    price VARCHAR NOT NULL,
+    min_price VARCHAR NOT NULL,
+    max_price VARCHAR NOT NULL,
+    sample_count BIGINT NOT NULL DEFAULT 1,
+    timestamp BIGINT NOT NULL
+);
+
+-- protocol_positions (per-protocol health snapshots; the same address
+-- can hold positions on several protocols)
+CREATE TABLE IF NOT EXISTS protocol_positions (
+    protocol VARCHAR NOT NULL,
+    address VARCHAR NOT NULL,
+    collateral_value VARCHAR NOT NULL,
+    debt_value VARCHAR NOT NULL,
+    health_factor VARCHAR NOT NULL,
+    is_liquidatable BOOLEAN NOT NULL DEFAULT FALSE,
+    last_updated TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (protocol, address)
+);
+
+-- sync_state (last fully processed block per log subscription)
+CREATE TABLE IF NOT EXISTS sync_state (
+    subscription VARCHAR PRIMARY KEY,
+    last_processed_block BIGINT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- pnl (accounted P&L per liquidation plus the running total)
+CREATE TABLE IF NOT EXISTS pnl (
+    id SERIAL PRIMARY KEY,
+    tx_hash VARCHAR NOT NULL,
+    user_address VARCHAR NOT NULL,
+    collateral_asset VARCHAR NOT NULL,
+    debt_asset VARCHAR NOT NULL,
+    block_number BIGINT,
+    collateral_value VARCHAR NOT NULL,
+    debt_value VARCHAR NOT NULL,
+    flash_loan_fee VARCHAR NOT NULL,
+    gas_cost VARCHAR NOT NULL,
+    realized_pnl VARCHAR NOT NULL,
+    unrealized_pnl VARCHAR NOT NULL,
+    cumulative_pnl VARCHAR NOT NULL,
+    timestamp BIGINT NOT NULL
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_user_positions_health_factor ON user_positions(health_factor);
+CREATE INDEX IF NOT EXISTS idx_user_positions_is_at_risk ON user_positions(is_at_risk);
+CREATE INDEX IF NOT EXISTS idx_liquidation_events_timestamp ON liquidation_events(timestamp);
+CREATE INDEX IF NOT EXISTS idx_liquidation_audit_tx_hash ON liquidation_audit(tx_hash);
+CREATE INDEX IF NOT EXISTS idx_price_feeds_asset_timestamp ON price_feeds(asset_address, timestamp);
+CREATE INDEX IF NOT EXISTS idx_pnl_timestamp ON pnl(timestamp);
@@ -0,0 +1,14 @@
+-- position_history (health factor snapshot of every persisted position update; timestamps
+-- are unix seconds)
+CREATE TABLE position_history (
+    id SERIAL PRIMARY KEY,
+    address VARCHAR NOT NULL,
+    health_factor VARCHAR NOT NULL,
+    total_collateral_base VARCHAR NOT NULL,
+    total_debt_base VARCHAR NOT NULL,
+    is_at_risk BOOLEAN NOT NULL,
+    timestamp BIGINT NOT NULL
+);
+
+CREATE INDEX idx_position_history_address_timestamp ON position_history(address, timestamp);
+CREATE INDEX idx_position_history_timestamp ON position_history(timestamp);
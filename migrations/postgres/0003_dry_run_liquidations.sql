@@ -0,0 +1,15 @@
+-- dry_run_liquidations (liquidations the bot would have broadcast in EXECUTION_MODE=dry_run)
+CREATE TABLE dry_run_liquidations (
+    id SERIAL PRIMARY KEY,
+    user_address VARCHAR NOT NULL,
+    collateral_asset VARCHAR NOT NULL,
+    debt_asset VARCHAR NOT NULL,
+    debt_to_cover VARCHAR NOT NULL,
+    estimated_profit VARCHAR NOT NULL,
+    simulated_profit VARCHAR NOT NULL,
+    gas_used BIGINT,
+    strategy_label VARCHAR NOT NULL DEFAULT 'default',
+    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_dry_run_liquidations_timestamp ON dry_run_liquidations(timestamp);
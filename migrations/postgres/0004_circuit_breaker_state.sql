@@ -0,0 +1,6 @@
+-- circuit_breaker_state (breaker state and statistics saved at shutdown, JSON encoded)
+CREATE TABLE circuit_breaker_state (
+    name VARCHAR PRIMARY KEY,
+    snapshot VARCHAR NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,13 @@
+-- user_reserves (per-reserve balances of tracked users in the reserve's native units, one
+-- row per reserve the user supplies or borrows; timestamps are unix seconds)
+CREATE TABLE user_reserves (
+    user_address VARCHAR NOT NULL,
+    reserve VARCHAR NOT NULL,
+    collateral_balance VARCHAR NOT NULL,
+    debt_balance VARCHAR NOT NULL,
+    usage_as_collateral_enabled BOOLEAN NOT NULL,
+    updated_at BIGINT NOT NULL,
+    PRIMARY KEY (user_address, reserve)
+);
+
+CREATE INDEX idx_user_reserves_reserve ON user_reserves(reserve);
@@ -0,0 +1,42 @@
+-- chain_id on every table, so several chains can share one database. Rows written before
+-- multi-chain support belong to Base (8453). Tables keyed by address are re-keyed per chain.
+ALTER TABLE user_positions ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE user_positions DROP CONSTRAINT user_positions_pkey;
+ALTER TABLE user_positions ADD PRIMARY KEY (chain_id, address);
+
+ALTER TABLE liquidation_queue ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE liquidation_queue DROP CONSTRAINT liquidation_queue_pkey;
+ALTER TABLE liquidation_queue ADD PRIMARY KEY (chain_id, user_address);
+
+ALTER TABLE competitors ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE competitors DROP CONSTRAINT competitors_pkey;
+ALTER TABLE competitors ADD PRIMARY KEY (chain_id, liquidator_address);
+
+ALTER TABLE protocol_positions ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE protocol_positions DROP CONSTRAINT protocol_positions_pkey;
+ALTER TABLE protocol_positions ADD PRIMARY KEY (chain_id, protocol, address);
+
+ALTER TABLE sync_state ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE sync_state DROP CONSTRAINT sync_state_pkey;
+ALTER TABLE sync_state ADD PRIMARY KEY (chain_id, subscription);
+
+ALTER TABLE circuit_breaker_state ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE circuit_breaker_state DROP CONSTRAINT circuit_breaker_state_pkey;
+ALTER TABLE circuit_breaker_state ADD PRIMARY KEY (chain_id, name);
+
+ALTER TABLE user_reserves ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE user_reserves DROP CONSTRAINT user_reserves_pkey;
+ALTER TABLE user_reserves ADD PRIMARY KEY (chain_id, user_address, reserve);
+
+ALTER TABLE liquidation_events ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE liquidation_audit ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE profitability_divergences ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE price_feeds ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE pnl ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE position_history ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+ALTER TABLE dry_run_liquidations ADD COLUMN chain_id BIGINT NOT NULL DEFAULT 8453;
+
+DROP INDEX idx_price_feeds_asset_timestamp;
+CREATE INDEX idx_price_feeds_asset_timestamp ON price_feeds(chain_id, asset_address, timestamp);
+DROP INDEX idx_position_history_address_timestamp;
+CREATE INDEX idx_position_history_address_timestamp ON position_history(chain_id, address, timestamp);
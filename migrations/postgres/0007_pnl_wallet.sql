@@ -0,0 +1,3 @@
+-- Executor wallet that sent each liquidation, for per-wallet P&L. Rows written before
+-- multi-wallet support have no wallet recorded.
+ALTER TABLE pnl ADD COLUMN wallet VARCHAR;
@@ -0,0 +1,16 @@
+-- liquidation_latency (when each liquidation opportunity passed each pipeline stage, unix ms)
+CREATE TABLE liquidation_latency (
+    id SERIAL PRIMARY KEY,
+    user_address VARCHAR NOT NULL,
+    tx_hash VARCHAR,
+    outcome VARCHAR NOT NULL,
+    log_received_ms BIGINT,
+    hf_confirmed_ms BIGINT,
+    profitability_done_ms BIGINT,
+    tx_sent_ms BIGINT,
+    tx_mined_ms BIGINT,
+    recorded_at_ms BIGINT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_liquidation_latency_recorded_at ON liquidation_latency(chain_id, recorded_at_ms);
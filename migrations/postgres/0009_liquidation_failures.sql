@@ -0,0 +1,11 @@
+-- liquidation_failures (category and error of each failed liquidation attempt, unix ms)
+CREATE TABLE liquidation_failures (
+    id SERIAL PRIMARY KEY,
+    user_address VARCHAR NOT NULL,
+    category VARCHAR NOT NULL,
+    error VARCHAR NOT NULL,
+    recorded_at_ms BIGINT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_liquidation_failures_recorded_at ON liquidation_failures(chain_id, recorded_at_ms);
@@ -0,0 +1,4 @@
+-- Attempt number of each failed liquidation and what the retry policy decided
+-- (retry, abandon or exhausted)
+ALTER TABLE liquidation_failures ADD COLUMN attempt INTEGER NOT NULL DEFAULT 1;
+ALTER TABLE liquidation_failures ADD COLUMN decision VARCHAR NOT NULL DEFAULT 'abandon';
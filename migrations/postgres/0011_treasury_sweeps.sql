@@ -0,0 +1,14 @@
+-- treasury_sweeps (profits moved from executor wallets to the cold wallet, unix ms)
+CREATE TABLE treasury_sweeps (
+    id SERIAL PRIMARY KEY,
+    wallet_address VARCHAR NOT NULL,
+    cold_address VARCHAR NOT NULL,
+    asset VARCHAR NOT NULL,
+    token_address VARCHAR,
+    amount VARCHAR NOT NULL,
+    tx_hash VARCHAR NOT NULL,
+    swept_at_ms BIGINT NOT NULL,
+    chain_id BIGINT NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_treasury_sweeps_swept_at ON treasury_sweeps(chain_id, swept_at_ms);
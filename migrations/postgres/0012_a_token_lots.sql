@@ -0,0 +1,14 @@
+-- a_token_lots (collateral received as aTokens, held until unwound, unix ms)
+CREATE TABLE a_token_lots (
+    id SERIAL PRIMARY KEY,
+    wallet_address VARCHAR NOT NULL,
+    asset VARCHAR NOT NULL,
+    amount VARCHAR NOT NULL,
+    tx_hash VARCHAR NOT NULL,
+    acquired_at_ms BIGINT NOT NULL,
+    unwound_at_ms BIGINT,
+    unwind_tx_hash VARCHAR,
+    chain_id BIGINT NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_a_token_lots_open ON a_token_lots(chain_id, unwound_at_ms, acquired_at_ms);
@@ -0,0 +1,144 @@
+-- Baseline schema. Every statement is idempotent so databases created before versioned
+-- migrations can be adopted by recording this migration as applied.
+
+-- user_positions
+CREATE TABLE IF NOT EXISTS user_positions (
+    address TEXT PRIMARY KEY,
+    total_collateral_base TEXT NOT NULL,
+    total_debt_base TEXT NOT NULL,
+    available_borrows_base TEXT NOT NULL,
+    current_liquidation_threshold TEXT NOT NULL,
+    ltv TEXT NOT NULL,
+    health_factor TEXT NOT NULL,
+    last_updated DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    is_at_risk BOOLEAN NOT NULL DEFAULT 0
+);
+
+-- liquidation_events
+CREATE TABLE IF NOT EXISTS liquidation_events (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_address TEXT NOT NULL,
+    collateral_asset TEXT NOT NULL,
+    debt_asset TEXT NOT NULL,
+    debt_covered TEXT NOT NULL,
+    collateral_received TEXT NOT NULL,
+    profit TEXT NOT NULL,
+    tx_hash TEXT,
+    block_number INTEGER,
+    timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    strategy_label TEXT NOT NULL DEFAULT 'default',
+    requested_debt TEXT,
+    tx_index INTEGER,
+    gas_used TEXT,
+    effective_gas_price TEXT,
+    succeeded BOOLEAN
+);
+
+-- liquidation_audit (intent + settlement records linked by tx hash)
+CREATE TABLE IF NOT EXISTS liquidation_audit (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tx_hash TEXT NOT NULL,
+    phase TEXT NOT NULL,
+    user_address TEXT,
+    nonce INTEGER,
+    gas_price TEXT,
+    gas_limit TEXT,
+    estimated_profit TEXT,
+    block_number INTEGER,
+    tx_index INTEGER,
+    gas_used TEXT,
+    realized_profit TEXT,
+    succeeded BOOLEAN,
+    timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- liquidation_queue (pending priority liquidations, survives restarts)
+CREATE TABLE IF NOT EXISTS liquidation_queue (
+    user_address TEXT PRIMARY KEY,
+    queued_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- profitability_divergences (shadow vs live profitability model)
+CREATE TABLE IF NOT EXISTS profitability_divergences (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_address TEXT NOT NULL,
+    collateral_asset TEXT NOT NULL,
+    debt_asset TEXT NOT NULL,
+    live_model TEXT NOT NULL,
+    shadow_model TEXT NOT NULL,
+    live_profit TEXT NOT NULL,
+    shadow_profit TEXT NOT NULL,
+    divergence_bps INTEGER NOT NULL,
+    timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- competitors (other liquidators seen racing in the mempool)
+CREATE TABLE IF NOT EXISTS competitors (
+    liquidator_address TEXT PRIMARY KEY,
+    sightings INTEGER NOT NULL DEFAULT 0,
+    races INTEGER NOT NULL DEFAULT 0,
+    last_user_address TEXT NOT NULL,
+    last_tx_hash TEXT NOT NULL,
+    last_priority_fee TEXT NOT NULL,
+    first_seen DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- price_feeds (oracle price history; timestamps are unix seconds so
+-- downsampling buckets are computed the same way on both backends)
+CREATE TABLE IF NOT EXISTS price_feeds (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    asset_address TEXT NOT NULL,
+    resolution TEXT NOT NULL DEFAULT 'raw',
+    price TEXT NOT NULL,
+    min_price TEXT NOT NULL,
+    max_price TEXT NOT NULL,
+    sample_count INTEGER NOT NULL DEFAULT 1,
+    timestamp INTEGER NOT NULL
+);
+
+-- protocol_positions (per-protocol health snapshots; the same address
+-- can hold positions on several protocols)
+CREATE TABLE IF NOT EXISTS protocol_positions (
+    protocol TEXT NOT NULL,
+    address TEXT NOT NULL,
+    collateral_value TEXT NOT NULL,
+    debt_value TEXT NOT NULL,
+    health_factor TEXT NOT NULL,
+    is_liquidatable BOOLEAN NOT NULL DEFAULT 0,
+    last_updated DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (protocol, address)
+);
+
+-- sync_state (last fully processed block per log subscription)
+CREATE TABLE IF NOT EXISTS sync_state (
+    subscription TEXT PRIMARY KEY,
+    last_processed_block INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+-- pnl (accounted P&L per liquidation plus the running total)
+CREATE TABLE IF NOT EXISTS pnl (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tx_hash TEXT NOT NULL,
+    user_address TEXT NOT NULL,
+    collateral_asset TEXT NOT NULL,
+    debt_asset TEXT NOT NULL,
+    block_number INTEGER,
+    collateral_value TEXT NOT NULL,
+    debt_value TEXT NOT NULL,
+    flash_loan_fee TEXT NOT NULL,
+    gas_cost TEXT NOT NULL,
+    realized_pnl TEXT NOT NULL,
+    unrealized_pnl TEXT NOT NULL,
+    cumulative_pnl TEXT NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+-- Indexes
+CREATE INDEX IF NOT EXISTS idx_user_positions_health_factor ON user_positions(health_factor);
+CREATE INDEX IF NOT EXISTS idx_user_positions_is_at_risk ON user_positions(is_at_risk);
+CREATE INDEX IF NOT EXISTS idx_liquidation_events_timestamp ON liquidation_events(timestamp);
+CREATE INDEX IF NOT EXISTS idx_liquidation_audit_tx_hash ON liquidation_audit(tx_hash);
+CREATE INDEX IF NOT EXISTS idx_price_feeds_asset_timestamp ON price_feeds(asset_address, timestamp);
+CREATE INDEX IF NOT EXISTS idx_pnl_timestamp ON pnl(timestamp);
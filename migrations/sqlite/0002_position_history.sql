@@ -0,0 +1,14 @@
+-- position_history (health factor snapshot of every persisted position update; timestamps
+-- are unix seconds)
+CREATE TABLE position_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    address TEXT NOT NULL,
+    health_factor TEXT NOT NULL,
+    total_collateral_base TEXT NOT NULL,
+    total_debt_base TEXT NOT NULL,
+    is_at_risk BOOLEAN NOT NULL,
+    timestamp INTEGER NOT NULL
+);
+
+CREATE INDEX idx_position_history_address_timestamp ON position_history(address, timestamp);
+CREATE INDEX idx_position_history_timestamp ON position_history(timestamp);
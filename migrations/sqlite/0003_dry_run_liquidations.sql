@@ -0,0 +1,15 @@
+-- dry_run_liquidations (liquidations the bot would have broadcast in EXECUTION_MODE=dry_run)
+CREATE TABLE dry_run_liquidations (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_address TEXT NOT NULL,
+    collateral_asset TEXT NOT NULL,
+    debt_asset TEXT NOT NULL,
+    debt_to_cover TEXT NOT NULL,
+    estimated_profit TEXT NOT NULL,
+    simulated_profit TEXT NOT NULL,
+    gas_used INTEGER,
+    strategy_label TEXT NOT NULL DEFAULT 'default',
+    timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX idx_dry_run_liquidations_timestamp ON dry_run_liquidations(timestamp);
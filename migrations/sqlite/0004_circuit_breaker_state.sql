@@ -0,0 +1,6 @@
+-- circuit_breaker_state (breaker state and statistics saved at shutdown, JSON encoded)
+CREATE TABLE circuit_breaker_state (
+    name TEXT PRIMARY KEY,
+    snapshot TEXT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
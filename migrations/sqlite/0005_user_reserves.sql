@@ -0,0 +1,13 @@
+-- user_reserves (per-reserve balances of tracked users in the reserve's native units, one
+-- row per reserve the user supplies or borrows; timestamps are unix seconds)
+CREATE TABLE user_reserves (
+    user_address TEXT NOT NULL,
+    reserve TEXT NOT NULL,
+    collateral_balance TEXT NOT NULL,
+    debt_balance TEXT NOT NULL,
+    usage_as_collateral_enabled BOOLEAN NOT NULL,
+    updated_at INTEGER NOT NULL,
+    PRIMARY KEY (user_address, reserve)
+);
+
+CREATE INDEX idx_user_reserves_reserve ON user_reserves(reserve);
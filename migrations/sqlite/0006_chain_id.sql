@@ -0,0 +1,132 @@
+-- chain_id on every table, so several chains can share one database. Rows written before
+-- multi-chain support belong to Base (8453). Tables keyed by address are re-keyed per chain;
+-- SQLite can't change a primary key in place, so those tables are rebuilt.
+CREATE TABLE user_positions_new (
+    address TEXT NOT NULL,
+    total_collateral_base TEXT NOT NULL,
+    total_debt_base TEXT NOT NULL,
+    available_borrows_base TEXT NOT NULL,
+    current_liquidation_threshold TEXT NOT NULL,
+    ltv TEXT NOT NULL,
+    health_factor TEXT NOT NULL,
+    last_updated DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    is_at_risk BOOLEAN NOT NULL DEFAULT 0,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, address)
+);
+INSERT INTO user_positions_new (address, total_collateral_base, total_debt_base,
+    available_borrows_base, current_liquidation_threshold, ltv, health_factor, last_updated,
+    is_at_risk)
+SELECT address, total_collateral_base, total_debt_base, available_borrows_base,
+    current_liquidation_threshold, ltv, health_factor, last_updated, is_at_risk
+FROM user_positions;
+DROP TABLE user_positions;
+ALTER TABLE user_positions_new RENAME TO user_positions;
+CREATE INDEX idx_user_positions_health_factor ON user_positions(health_factor);
+CREATE INDEX idx_user_positions_is_at_risk ON user_positions(is_at_risk);
+
+CREATE TABLE liquidation_queue_new (
+    user_address TEXT NOT NULL,
+    queued_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, user_address)
+);
+INSERT INTO liquidation_queue_new (user_address, queued_at)
+SELECT user_address, queued_at FROM liquidation_queue;
+DROP TABLE liquidation_queue;
+ALTER TABLE liquidation_queue_new RENAME TO liquidation_queue;
+
+CREATE TABLE competitors_new (
+    liquidator_address TEXT NOT NULL,
+    sightings INTEGER NOT NULL DEFAULT 0,
+    races INTEGER NOT NULL DEFAULT 0,
+    last_user_address TEXT NOT NULL,
+    last_tx_hash TEXT NOT NULL,
+    last_priority_fee TEXT NOT NULL,
+    first_seen DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    last_seen DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, liquidator_address)
+);
+INSERT INTO competitors_new (liquidator_address, sightings, races, last_user_address,
+    last_tx_hash, last_priority_fee, first_seen, last_seen)
+SELECT liquidator_address, sightings, races, last_user_address, last_tx_hash,
+    last_priority_fee, first_seen, last_seen
+FROM competitors;
+DROP TABLE competitors;
+ALTER TABLE competitors_new RENAME TO competitors;
+
+CREATE TABLE protocol_positions_new (
+    protocol TEXT NOT NULL,
+    address TEXT NOT NULL,
+    collateral_value TEXT NOT NULL,
+    debt_value TEXT NOT NULL,
+    health_factor TEXT NOT NULL,
+    is_liquidatable BOOLEAN NOT NULL DEFAULT 0,
+    last_updated DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, protocol, address)
+);
+INSERT INTO protocol_positions_new (protocol, address, collateral_value, debt_value,
+    health_factor, is_liquidatable, last_updated)
+SELECT protocol, address, collateral_value, debt_value, health_factor, is_liquidatable,
+    last_updated
+FROM protocol_positions;
+DROP TABLE protocol_positions;
+ALTER TABLE protocol_positions_new RENAME TO protocol_positions;
+
+CREATE TABLE sync_state_new (
+    subscription TEXT NOT NULL,
+    last_processed_block INTEGER NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, subscription)
+);
+INSERT INTO sync_state_new (subscription, last_processed_block, updated_at)
+SELECT subscription, last_processed_block, updated_at FROM sync_state;
+DROP TABLE sync_state;
+ALTER TABLE sync_state_new RENAME TO sync_state;
+
+CREATE TABLE circuit_breaker_state_new (
+    name TEXT NOT NULL,
+    snapshot TEXT NOT NULL,
+    updated_at DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, name)
+);
+INSERT INTO circuit_breaker_state_new (name, snapshot, updated_at)
+SELECT name, snapshot, updated_at FROM circuit_breaker_state;
+DROP TABLE circuit_breaker_state;
+ALTER TABLE circuit_breaker_state_new RENAME TO circuit_breaker_state;
+
+CREATE TABLE user_reserves_new (
+    user_address TEXT NOT NULL,
+    reserve TEXT NOT NULL,
+    collateral_balance TEXT NOT NULL,
+    debt_balance TEXT NOT NULL,
+    usage_as_collateral_enabled BOOLEAN NOT NULL,
+    updated_at INTEGER NOT NULL,
+    chain_id INTEGER NOT NULL DEFAULT 8453,
+    PRIMARY KEY (chain_id, user_address, reserve)
+);
+INSERT INTO user_reserves_new (user_address, reserve, collateral_balance, debt_balance,
+    usage_as_collateral_enabled, updated_at)
+SELECT user_address, reserve, collateral_balance, debt_balance, usage_as_collateral_enabled,
+    updated_at
+FROM user_reserves;
+DROP TABLE user_reserves;
+ALTER TABLE user_reserves_new RENAME TO user_reserves;
+CREATE INDEX idx_user_reserves_reserve ON user_reserves(reserve);
+
+ALTER TABLE liquidation_events ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+ALTER TABLE liquidation_audit ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+ALTER TABLE profitability_divergences ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+ALTER TABLE price_feeds ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+ALTER TABLE pnl ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+ALTER TABLE position_history ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+ALTER TABLE dry_run_liquidations ADD COLUMN chain_id INTEGER NOT NULL DEFAULT 8453;
+
+DROP INDEX idx_price_feeds_asset_timestamp;
+CREATE INDEX idx_price_feeds_asset_timestamp ON price_feeds(chain_id, asset_address, timestamp);
+DROP INDEX idx_position_history_address_timestamp;
+CREATE INDEX idx_position_history_address_timestamp ON position_history(chain_id, address, timestamp);
@@ -0,0 +1,16 @@
+-- liquidation_latency (when each liquidation opportunity passed each pipeline stage, unix ms)
+CREATE TABLE liquidation_latency (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_address TEXT NOT NULL,
+    tx_hash TEXT,
+    outcome TEXT NOT NULL,
+    log_received_ms INTEGER,
+    hf_confirmed_ms INTEGER,
+    profitability_done_ms INTEGER,
+    tx_sent_ms INTEGER,
+    tx_mined_ms INTEGER,
+    recorded_at_ms INTEGER NOT NULL,
+    chain_id INTEGER NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_liquidation_latency_recorded_at ON liquidation_latency(chain_id, recorded_at_ms);
@@ -0,0 +1,11 @@
+-- liquidation_failures (category and error of each failed liquidation attempt, unix ms)
+CREATE TABLE liquidation_failures (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_address TEXT NOT NULL,
+    category TEXT NOT NULL,
+    error TEXT NOT NULL,
+    recorded_at_ms INTEGER NOT NULL,
+    chain_id INTEGER NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_liquidation_failures_recorded_at ON liquidation_failures(chain_id, recorded_at_ms);
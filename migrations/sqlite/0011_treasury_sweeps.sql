@@ -0,0 +1,14 @@
+-- treasury_sweeps (profits moved from executor wallets to the cold wallet, unix ms)
+CREATE TABLE treasury_sweeps (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_address TEXT NOT NULL,
+    cold_address TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    token_address TEXT,
+    amount TEXT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    swept_at_ms INTEGER NOT NULL,
+    chain_id INTEGER NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_treasury_sweeps_swept_at ON treasury_sweeps(chain_id, swept_at_ms);
@@ -0,0 +1,14 @@
+-- a_token_lots (collateral received as aTokens, held until unwound, unix ms)
+CREATE TABLE a_token_lots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    wallet_address TEXT NOT NULL,
+    asset TEXT NOT NULL,
+    amount TEXT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    acquired_at_ms INTEGER NOT NULL,
+    unwound_at_ms INTEGER,
+    unwind_tx_hash TEXT,
+    chain_id INTEGER NOT NULL DEFAULT 8453
+);
+
+CREATE INDEX idx_a_token_lots_open ON a_token_lots(chain_id, unwound_at_ms, acquired_at_ms);
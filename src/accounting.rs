@@ -0,0 +1,495 @@
+use alloy_primitives::{Address, I256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, TransactionRequest};
+use alloy_sol_types::{sol, SolCall};
+use chrono::{DateTime, Duration as ChronoDuration, NaiveTime, Utc};
+use eyre::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::database::{self, DatabasePool};
+use crate::models::{
+    LiquidationAssetConfig, LiquidationFill, LiquidationOpportunity, LiquidationReceipt, PnlEntry,
+    PnlSummary,
+};
+use crate::monitoring::alerts::{AlertEvent, AlertNotifier};
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::monitoring::value_at_risk::format_base_currency;
+use crate::networks::NetworkAddresses;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IERC20Balance {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+const ETH_DECIMALS: u8 = 18;
+
+/// Decimals assumed for assets missing from the liquidation asset configs
+const DEFAULT_TOKEN_DECIMALS: u8 = 18;
+
+/// Balances a liquidation's proceeds and costs flow through, at one block: gas leaves the
+/// operator wallet, the liquidator contract receives collateral and keeps the profit in the
+/// debt asset.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BalanceSnapshot {
+    pub operator_eth: U256,
+    pub collateral: U256,
+    pub debt: U256,
+}
+
+/// Oracle prices (base currency, 8 decimals) used to value one liquidation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountingPrices {
+    pub collateral: U256,
+    pub debt: U256,
+    pub eth: U256,
+}
+
+/// A mined liquidation and the amounts it moved, in token units
+#[derive(Debug, Clone)]
+pub struct MinedLiquidation {
+    pub tx_hash: String,
+    pub user: Address,
+    /// Executor wallet that sent the liquidation and paid its gas
+    pub wallet: Address,
+    pub collateral_asset: Address,
+    pub debt_asset: Address,
+    pub collateral_decimals: u8,
+    pub debt_decimals: u8,
+    pub block_number: Option<u64>,
+    pub debt_covered: U256,
+    pub collateral_received: U256,
+    pub flash_loan_fee: U256,
+    pub gas_cost_wei: U256,
+}
+
+impl MinedLiquidation {
+    /// Amounts actually filled when the receipt was decoded, the opportunity's otherwise. The
+    /// flash loan fee scales with the debt actually borrowed.
+    pub fn new(
+        opportunity: &LiquidationOpportunity,
+        fill: Option<&LiquidationFill>,
+        receipt: &LiquidationReceipt,
+        asset_configs: &HashMap<Address, LiquidationAssetConfig>,
+        wallet: Address,
+    ) -> Self {
+        let decimals = |asset: &Address| {
+            asset_configs
+                .get(asset)
+                .map_or(DEFAULT_TOKEN_DECIMALS, |config| config.decimals)
+        };
+        let (debt_covered, collateral_received) = match fill {
+            Some(fill) => (fill.debt_covered, fill.collateral_received),
+            None => (
+                opportunity.debt_to_cover,
+                opportunity.expected_collateral_received,
+            ),
+        };
+        let flash_loan_fee = if opportunity.debt_to_cover.is_zero() {
+            opportunity.flash_loan_fee
+        } else {
+            opportunity.flash_loan_fee.saturating_mul(debt_covered) / opportunity.debt_to_cover
+        };
+
+        Self {
+            tx_hash: receipt.tx_hash.clone(),
+            user: opportunity.user,
+            wallet,
+            collateral_asset: opportunity.collateral_asset,
+            debt_asset: opportunity.debt_asset,
+            collateral_decimals: decimals(&opportunity.collateral_asset),
+            debt_decimals: decimals(&opportunity.debt_asset),
+            block_number: receipt.block_number,
+            debt_covered,
+            collateral_received,
+            flash_loan_fee,
+            gas_cost_wei: receipt.gas_used.saturating_mul(receipt.effective_gas_price),
+        }
+    }
+}
+
+/// Value of `amount` token units (with `decimals` decimals) at `price` in the base currency
+pub fn base_value(amount: U256, price: U256, decimals: u8) -> U256 {
+    amount.saturating_mul(price) / U256::from(10u64).pow(U256::from(decimals))
+}
+
+fn to_signed(value: U256) -> I256 {
+    I256::try_from(value).unwrap_or(I256::MAX)
+}
+
+/// Base currency value of the change from `before` to `after`
+fn signed_change(before: U256, after: U256, price: U256, decimals: u8) -> I256 {
+    if after >= before {
+        to_signed(base_value(after - before, price, decimals))
+    } else {
+        -to_signed(base_value(before - after, price, decimals))
+    }
+}
+
+/// Account for one liquidation. With balance snapshots from just before and after its block,
+/// the debt asset and ETH balance changes are the realized P&L and collateral still held is
+/// unrealized; without them the P&L is the seized collateral less the repaid debt, flash loan
+/// fee and gas, all counted as realized.
+pub fn compute_pnl(
+    liquidation: &MinedLiquidation,
+    prices: &AccountingPrices,
+    balances: Option<(BalanceSnapshot, BalanceSnapshot)>,
+    timestamp: u64,
+) -> PnlEntry {
+    let collateral_value = base_value(
+        liquidation.collateral_received,
+        prices.collateral,
+        liquidation.collateral_decimals,
+    );
+    let debt_value = base_value(liquidation.debt_covered, prices.debt, liquidation.debt_decimals);
+    let flash_loan_fee = base_value(
+        liquidation.flash_loan_fee,
+        prices.debt,
+        liquidation.debt_decimals,
+    );
+    let gas_cost = base_value(liquidation.gas_cost_wei, prices.eth, ETH_DECIMALS);
+
+    let (realized_pnl, unrealized_pnl) = match balances {
+        Some((before, after)) => {
+            let debt_change =
+                signed_change(before.debt, after.debt, prices.debt, liquidation.debt_decimals);
+            let eth_change =
+                signed_change(before.operator_eth, after.operator_eth, prices.eth, ETH_DECIMALS);
+            let realized = debt_change.saturating_add(eth_change);
+            // Same-asset liquidations leave everything in the debt asset balance
+            let unrealized = if liquidation.collateral_asset == liquidation.debt_asset {
+                I256::ZERO
+            } else {
+                signed_change(
+                    before.collateral,
+                    after.collateral,
+                    prices.collateral,
+                    liquidation.collateral_decimals,
+                )
+            };
+            (realized, unrealized)
+        }
+        None => {
+            let costs = debt_value
+                .saturating_add(flash_loan_fee)
+                .saturating_add(gas_cost);
+            (to_signed(collateral_value) - to_signed(costs), I256::ZERO)
+        }
+    };
+
+    PnlEntry {
+        tx_hash: liquidation.tx_hash.clone(),
+        user: liquidation.user,
+        wallet: liquidation.wallet,
+        collateral_asset: liquidation.collateral_asset,
+        debt_asset: liquidation.debt_asset,
+        block_number: liquidation.block_number,
+        collateral_value,
+        debt_value,
+        flash_loan_fee,
+        gas_cost,
+        realized_pnl,
+        unrealized_pnl,
+        timestamp,
+    }
+}
+
+/// Format a signed base currency amount as USD, e.g. `-$1.50`
+pub fn format_pnl(amount: I256) -> String {
+    let sign = if amount.is_negative() { "-" } else { "+" };
+    format!("{}{}", sign, format_base_currency(amount.unsigned_abs()))
+}
+
+/// ERC-20 balance of `account` at `block`
+async fn token_balance_at<P>(
+    provider: &P,
+    token: Address,
+    account: Address,
+    block: BlockId,
+) -> Result<U256>
+where
+    P: Provider,
+{
+    let call = IERC20Balance::balanceOfCall { account };
+    let result = provider
+        .call(
+            &TransactionRequest::default()
+                .to(token)
+                .input(call.abi_encode().into()),
+        )
+        .block(block)
+        .await?;
+    Ok(IERC20Balance::balanceOfCall::abi_decode_returns(&result, true)?._0)
+}
+
+/// Operator ETH and liquidator contract token balances at `block`
+async fn fetch_balances<P>(
+    provider: &P,
+    operator: Address,
+    liquidator_contract: Address,
+    collateral_asset: Address,
+    debt_asset: Address,
+    block: u64,
+) -> Result<BalanceSnapshot>
+where
+    P: Provider,
+{
+    let block = BlockId::number(block);
+    Ok(BalanceSnapshot {
+        operator_eth: provider.get_balance(operator).block_id(block).await?,
+        collateral: token_balance_at(provider, collateral_asset, liquidator_contract, block)
+            .await?,
+        debt: token_balance_at(provider, debt_asset, liquidator_contract, block).await?,
+    })
+}
+
+/// Value a mined liquidation at current oracle prices, compare the sending wallet's and the
+/// liquidator contract's balances just before and after its block, and record the result in
+/// the `pnl` table
+pub async fn record_liquidation_pnl<P>(
+    provider: Arc<P>,
+    network: &NetworkAddresses,
+    db_pool: &DatabasePool,
+    liquidation: &MinedLiquidation,
+    liquidator_contract: Address,
+) -> Result<PnlEntry>
+where
+    P: Provider,
+{
+    // Gas is paid in ETH, valued at the WETH oracle price
+    let prices = fetch_oracle_prices(
+        provider.as_ref(),
+        network,
+        &[liquidation.collateral_asset, liquidation.debt_asset, network.weth],
+    )
+    .await?;
+    let prices = AccountingPrices {
+        collateral: prices[0],
+        debt: prices[1],
+        eth: prices[2],
+    };
+
+    let balances = match liquidation.block_number {
+        Some(block) => {
+            let snapshot = |block| {
+                fetch_balances(
+                    provider.as_ref(),
+                    liquidation.wallet,
+                    liquidator_contract,
+                    liquidation.collateral_asset,
+                    liquidation.debt_asset,
+                    block,
+                )
+            };
+            match tokio::try_join!(snapshot(block.saturating_sub(1)), snapshot(block)) {
+                Ok(balances) => Some(balances),
+                Err(e) => {
+                    warn!(
+                        "Could not read balances around block {} for {}: {}. Accounting from oracle valuation.",
+                        block, liquidation.tx_hash, e
+                    );
+                    None
+                }
+            }
+        }
+        None => None,
+    };
+
+    let entry = compute_pnl(
+        liquidation,
+        &prices,
+        balances,
+        Utc::now().timestamp().max(0) as u64,
+    );
+    let cumulative = database::record_pnl_entry(db_pool, &entry).await?;
+    info!(
+        "📒 P&L for {}: realized {}, unrealized {} (gas {}, flash loan fee {}), cumulative {}",
+        entry.tx_hash,
+        format_pnl(entry.realized_pnl),
+        format_pnl(entry.unrealized_pnl),
+        format_base_currency(entry.gas_cost),
+        format_base_currency(entry.flash_loan_fee),
+        format_pnl(cumulative)
+    );
+    Ok(entry)
+}
+
+/// One-line daily report for the log and alert channels
+pub fn format_daily_report(day: &str, summary: &PnlSummary) -> String {
+    format!(
+        "P&L for {}: total {} over {} liquidation(s) (realized {}, unrealized {}), gas {}, flash loan fees {}, cumulative {}",
+        day,
+        format_pnl(summary.total_pnl()),
+        summary.liquidations,
+        format_pnl(summary.realized_pnl),
+        format_pnl(summary.unrealized_pnl),
+        format_base_currency(summary.gas_costs),
+        format_base_currency(summary.flash_loan_fees),
+        format_pnl(summary.cumulative_pnl)
+    )
+}
+
+/// Start of the UTC day after `now`
+fn next_midnight(now: DateTime<Utc>) -> DateTime<Utc> {
+    (now.date_naive() + ChronoDuration::days(1))
+        .and_time(NaiveTime::MIN)
+        .and_utc()
+}
+
+/// After every UTC midnight, summarize the day that just ended and send it to the alert
+/// channels
+pub async fn run_daily_pnl_report(
+    db_pool: DatabasePool,
+    alert_notifier: AlertNotifier,
+) -> Result<()> {
+    loop {
+        let day_end = next_midnight(Utc::now());
+        let wait = (day_end - Utc::now()).to_std().unwrap_or_default();
+        tokio::time::sleep(wait).await;
+
+        let day_start = day_end - ChronoDuration::days(1);
+        let day = day_start.format("%Y-%m-%d").to_string();
+        match database::get_pnl_summary(
+            &db_pool,
+            day_start.timestamp() as u64,
+            day_end.timestamp() as u64,
+        )
+        .await
+        {
+            Ok(summary) => {
+                let report = format_daily_report(&day, &summary);
+                info!("📒 {}", report);
+                alert_notifier.spawn_notify(AlertEvent::DailyPnlReport { report });
+            }
+            Err(e) => warn!("Failed to build the daily P&L report for {}: {}", day, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tests::create_test_pool;
+
+    // 8-decimal base currency: $1 = 100_000_000
+    const DOLLAR: u64 = 100_000_000;
+
+    fn liquidation(collateral_asset: Address, debt_asset: Address) -> MinedLiquidation {
+        MinedLiquidation {
+            tx_hash: "0xaa".to_string(),
+            user: Address::repeat_byte(0x01),
+            wallet: Address::repeat_byte(0x0e),
+            collateral_asset,
+            debt_asset,
+            collateral_decimals: 18,
+            debt_decimals: 6,
+            block_number: Some(100),
+            // 1,000 USDC repaid for 0.42 WETH
+            debt_covered: U256::from(1_000_000_000u64),
+            collateral_received: U256::from(420_000_000_000_000_000u64),
+            // 0.5 USDC
+            flash_loan_fee: U256::from(500_000u64),
+            // 0.0001 ETH
+            gas_cost_wei: U256::from(100_000_000_000_000u64),
+        }
+    }
+
+    fn prices() -> AccountingPrices {
+        AccountingPrices {
+            collateral: U256::from(2_500 * DOLLAR),
+            debt: U256::from(DOLLAR),
+            eth: U256::from(2_500 * DOLLAR),
+        }
+    }
+
+    #[test]
+    fn test_oracle_valuation_without_balances() {
+        let liquidation = liquidation(Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let entry = compute_pnl(&liquidation, &prices(), None, 1_700_000_000);
+
+        assert_eq!(entry.collateral_value, U256::from(1_050 * DOLLAR));
+        assert_eq!(entry.debt_value, U256::from(1_000 * DOLLAR));
+        assert_eq!(entry.flash_loan_fee, U256::from(DOLLAR / 2));
+        assert_eq!(entry.gas_cost, U256::from(DOLLAR / 4));
+        // $1,050 - $1,000 - $0.50 - $0.25
+        assert_eq!(entry.realized_pnl, I256::try_from(4_925_000_000u64).unwrap());
+        assert_eq!(entry.unrealized_pnl, I256::ZERO);
+    }
+
+    #[test]
+    fn test_balance_changes_split_realized_and_unrealized() {
+        let liquidation = liquidation(Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let before = BalanceSnapshot {
+            operator_eth: U256::from(1_000_000_000_000_000_000u64),
+            collateral: U256::ZERO,
+            debt: U256::from(5_000_000u64),
+        };
+        // Half the collateral was swapped into 30 USDC of profit, 0.01 WETH is still held
+        let after = BalanceSnapshot {
+            operator_eth: before.operator_eth - liquidation.gas_cost_wei,
+            collateral: U256::from(10_000_000_000_000_000u64),
+            debt: U256::from(35_000_000u64),
+        };
+
+        let entry = compute_pnl(&liquidation, &prices(), Some((before, after)), 1_700_000_000);
+
+        // +$30 profit in USDC, -$0.25 gas
+        assert_eq!(entry.realized_pnl, I256::try_from(2_975_000_000u64).unwrap());
+        // 0.01 WETH at $2,500
+        assert_eq!(entry.unrealized_pnl, I256::try_from(25 * DOLLAR).unwrap());
+        assert_eq!(entry.total_pnl(), I256::try_from(5_475_000_000u64).unwrap());
+        assert_eq!(format_pnl(entry.realized_pnl), "+$29.75");
+        assert_eq!(format_pnl(-entry.unrealized_pnl), "-$25.00");
+    }
+
+    #[tokio::test]
+    async fn test_cumulative_pnl_and_daily_summary() {
+        let db_pool = create_test_pool().await;
+
+        let day = 1_700_006_400; // 2023-11-15 00:00 UTC
+        let liquidation = liquidation(Address::repeat_byte(0x02), Address::repeat_byte(0x03));
+        let mut entry = compute_pnl(&liquidation, &prices(), None, day - 60);
+        assert_eq!(
+            database::record_pnl_entry(&db_pool, &entry).await.unwrap(),
+            I256::try_from(4_925_000_000u64).unwrap()
+        );
+        entry.timestamp = day + 60;
+        entry.realized_pnl = -I256::try_from(10 * DOLLAR).unwrap();
+        database::record_pnl_entry(&db_pool, &entry).await.unwrap();
+
+        let summary = database::get_pnl_summary(&db_pool, day, day + 86_400)
+            .await
+            .unwrap();
+        assert_eq!(summary.liquidations, 1);
+        assert_eq!(summary.realized_pnl, -I256::try_from(10 * DOLLAR).unwrap());
+        assert_eq!(summary.gas_costs, U256::from(DOLLAR / 4));
+        assert_eq!(summary.cumulative_pnl, I256::try_from(3_925_000_000u64).unwrap());
+        assert_eq!(
+            format_daily_report("2023-11-15", &summary),
+            "P&L for 2023-11-15: total -$10.00 over 1 liquidation(s) (realized -$10.00, unrealized +$0.00), gas $0.25, flash loan fees $0.50, cumulative +$39.25"
+        );
+
+        // A liquidation sent from a second wallet is totalled separately
+        entry.wallet = Address::repeat_byte(0x0f);
+        entry.realized_pnl = I256::try_from(5 * DOLLAR).unwrap();
+        database::record_pnl_entry(&db_pool, &entry).await.unwrap();
+        assert_eq!(
+            database::get_pnl_by_wallet(&db_pool).await.unwrap(),
+            vec![
+                (Address::repeat_byte(0x0e), I256::try_from(3_925_000_000u64).unwrap()),
+                (Address::repeat_byte(0x0f), I256::try_from(5 * DOLLAR).unwrap()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_next_midnight() {
+        let now = DateTime::parse_from_rfc3339("2024-03-01T13:45:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(next_midnight(now).to_rfc3339(), "2024-03-02T00:00:00+00:00");
+    }
+}
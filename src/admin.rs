@@ -0,0 +1,660 @@
+use alloy_primitives::Address;
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use dashmap::DashMap;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerState, CircuitBreakerStatusReport};
+use crate::events::{BotEvent, EventSender};
+use crate::inventory::{InventoryReport, InventoryTracker};
+use crate::liquidation::{WalletPool, WalletStatus};
+use crate::models::UserPosition;
+use crate::monitoring::scanner;
+
+/// Shared state for the admin HTTP server
+#[derive(Clone)]
+pub struct AdminState {
+    /// Bearer token required on every request (None = all requests rejected)
+    admin_token: Option<Arc<String>>,
+    event_tx: EventSender,
+    started_at: Instant,
+    user_positions: Option<Arc<DashMap<Address, UserPosition>>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    wallets: Option<Arc<WalletPool>>,
+    inventory: Option<Arc<InventoryTracker>>,
+}
+
+impl AdminState {
+    pub fn new(admin_token: Option<String>, event_tx: EventSender) -> Self {
+        Self {
+            admin_token: admin_token.map(Arc::new),
+            event_tx,
+            started_at: Instant::now(),
+            user_positions: None,
+            circuit_breaker: None,
+            wallets: None,
+            inventory: None,
+        }
+    }
+
+    /// Serve status and at-risk listings from the bot's tracked positions
+    pub fn with_user_positions(mut self, user_positions: Arc<DashMap<Address, UserPosition>>) -> Self {
+        self.user_positions = Some(user_positions);
+        self
+    }
+
+    /// Expose circuit breaker state and the execution on/off switch
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Report executor wallet balances and activity in `GET /status`
+    pub fn with_wallets(mut self, wallets: Arc<WalletPool>) -> Self {
+        self.wallets = Some(wallets);
+        self
+    }
+
+    /// Report the latest inventory valuation and exposure in `GET /status`
+    pub fn with_inventory(mut self, inventory: Arc<InventoryTracker>) -> Self {
+        self.inventory = Some(inventory);
+        self
+    }
+
+    /// Check the `Authorization: Bearer <token>` header against the configured admin token
+    fn is_authorized(&self, headers: &HeaderMap) -> bool {
+        let expected = match &self.admin_token {
+            Some(token) => token,
+            None => return false,
+        };
+
+        let provided = headers
+            .get(axum::http::header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.strip_prefix("Bearer "));
+
+        match provided {
+            Some(provided) => constant_time_eq(provided.as_bytes(), expected.as_bytes()),
+            None => false,
+        }
+    }
+}
+
+/// Compare two byte strings without short-circuiting on the first mismatch
+pub(crate) fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b.iter()).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Request body for an externally flagged liquidation
+#[derive(Debug, Deserialize)]
+pub struct ForceLiquidationRequest {
+    pub user: Address,
+}
+
+/// Request body for a single-user rescan
+#[derive(Debug, Deserialize)]
+pub struct RescanUserRequest {
+    pub user: Address,
+}
+
+/// Request body for turning liquidation execution on or off
+#[derive(Debug, Deserialize)]
+pub struct ExecutionRequest {
+    pub enabled: bool,
+}
+
+/// Snapshot returned by `GET /status`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BotStatus {
+    pub uptime_secs: u64,
+    pub tracked_positions: usize,
+    pub at_risk_positions: usize,
+    pub liquidatable_positions: usize,
+    pub circuit_breaker_state: Option<CircuitBreakerState>,
+    /// False once an operator has disabled execution through the circuit breaker
+    pub execution_enabled: bool,
+    /// Executor wallet health (empty when the admin server isn't given the wallet pool)
+    #[serde(default)]
+    pub wallets: Vec<WalletStatus>,
+    /// Latest inventory valuation (None when tracking is disabled or hasn't run yet)
+    #[serde(default)]
+    pub inventory: Option<InventoryReport>,
+}
+
+/// One entry of `GET /positions/at-risk`; amounts are decimal strings in base currency units
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AtRiskPosition {
+    pub address: Address,
+    pub health_factor: String,
+    pub total_collateral_base: String,
+    pub total_debt_base: String,
+    pub liquidatable: bool,
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&UserPosition> for AtRiskPosition {
+    fn from(position: &UserPosition) -> Self {
+        Self {
+            address: position.address,
+            health_factor: position.health_factor.to_string(),
+            total_collateral_base: position.total_collateral_base.to_string(),
+            total_debt_base: position.total_debt_base.to_string(),
+            liquidatable: scanner::is_liquidatable(position),
+            last_updated: position.last_updated,
+        }
+    }
+}
+
+/// Generic JSON response returned by admin endpoints
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AdminResponse {
+    pub ok: bool,
+    pub message: String,
+}
+
+impl AdminResponse {
+    fn ok(message: impl Into<String>) -> (StatusCode, Json<Self>) {
+        (
+            StatusCode::OK,
+            Json(Self {
+                ok: true,
+                message: message.into(),
+            }),
+        )
+    }
+
+    fn error(status: StatusCode, message: impl Into<String>) -> (StatusCode, Json<Self>) {
+        (
+            status,
+            Json(Self {
+                ok: false,
+                message: message.into(),
+            }),
+        )
+    }
+}
+
+type AdminError = (StatusCode, Json<AdminResponse>);
+
+/// Reject the request unless it carries the admin bearer token
+fn authorize(state: &AdminState, headers: &HeaderMap, action: &str) -> Result<(), AdminError> {
+    if state.is_authorized(headers) {
+        Ok(())
+    } else {
+        warn!("🚫 Rejected unauthorized admin request: {}", action);
+        Err(AdminResponse::error(StatusCode::UNAUTHORIZED, "unauthorized"))
+    }
+}
+
+fn unavailable(component: &str) -> AdminError {
+    AdminResponse::error(
+        StatusCode::SERVICE_UNAVAILABLE,
+        format!("{} not available", component),
+    )
+}
+
+/// Build the admin router
+pub fn admin_router(state: AdminState) -> Router {
+    Router::new()
+        .route("/liquidate", post(force_liquidation))
+        .route("/status", get(bot_status))
+        .route("/positions/at-risk", get(at_risk_positions))
+        .route("/circuit-breaker", get(circuit_breaker_status))
+        .route("/execution", post(set_execution))
+        .route("/rescan", post(full_rescan))
+        .route("/rescan/user", post(rescan_user))
+        .with_state(state)
+}
+
+/// Bind the admin server to `listen_addr` and serve until the process exits
+pub async fn start_admin_server(listen_addr: &str, state: AdminState) -> Result<()> {
+    if state.admin_token.is_none() {
+        warn!("⚠️ ADMIN_TOKEN not configured - all admin API requests will be rejected");
+    }
+
+    let listener = TcpListener::bind(listen_addr).await?;
+    info!("🛠️ Admin API listening on {}", listener.local_addr()?);
+    serve_admin(listener, state).await
+}
+
+/// Serve the admin router on an already bound listener
+pub async fn serve_admin(listener: TcpListener, state: AdminState) -> Result<()> {
+    axum::serve(listener, admin_router(state)).await?;
+    Ok(())
+}
+
+/// Accept an externally flagged user and push it straight into the liquidation pipeline,
+/// bypassing discovery and scan gating
+async fn force_liquidation(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(request): Json<ForceLiquidationRequest>,
+) -> (StatusCode, Json<AdminResponse>) {
+    if !state.is_authorized(&headers) {
+        warn!(
+            "🚫 Rejected unauthorized external liquidation signal for user: {:?}",
+            request.user
+        );
+        return AdminResponse::error(StatusCode::UNAUTHORIZED, "unauthorized");
+    }
+
+    info!(
+        "📡 External liquidation signal accepted for user: {:?}",
+        request.user
+    );
+
+    match state
+        .event_tx
+        .send(BotEvent::ExternalLiquidationSignal(request.user))
+    {
+        Ok(()) => AdminResponse::ok(format!("liquidation check queued for {}", request.user)),
+        Err(e) => AdminResponse::error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("event pipeline unavailable: {}", e),
+        ),
+    }
+}
+
+/// Uptime, position counts and whether liquidations may currently execute
+async fn bot_status(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Result<Json<BotStatus>, AdminError> {
+    authorize(&state, &headers, "status")?;
+
+    let (mut tracked, mut at_risk, mut liquidatable) = (0, 0, 0);
+    if let Some(user_positions) = &state.user_positions {
+        for entry in user_positions.iter() {
+            tracked += 1;
+            if entry.value().is_at_risk {
+                at_risk += 1;
+            }
+            if scanner::is_liquidatable(entry.value()) {
+                liquidatable += 1;
+            }
+        }
+    }
+
+    let circuit_breaker_state = state
+        .circuit_breaker
+        .as_ref()
+        .map(|circuit_breaker| circuit_breaker.get_state());
+    let execution_enabled = circuit_breaker_state != Some(CircuitBreakerState::Disabled);
+
+    Ok(Json(BotStatus {
+        uptime_secs: state.started_at.elapsed().as_secs(),
+        tracked_positions: tracked,
+        at_risk_positions: at_risk,
+        liquidatable_positions: liquidatable,
+        circuit_breaker_state,
+        execution_enabled,
+        wallets: state
+            .wallets
+            .as_ref()
+            .map(|wallets| wallets.status())
+            .unwrap_or_default(),
+        inventory: state
+            .inventory
+            .as_ref()
+            .and_then(|inventory| inventory.latest()),
+    }))
+}
+
+/// Tracked at-risk positions, lowest health factor first
+async fn at_risk_positions(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Result<Json<Vec<AtRiskPosition>>, AdminError> {
+    authorize(&state, &headers, "list at-risk positions")?;
+    let user_positions = state
+        .user_positions
+        .as_ref()
+        .ok_or_else(|| unavailable("position tracking"))?;
+
+    let mut positions: Vec<UserPosition> = user_positions
+        .iter()
+        .filter(|entry| entry.value().is_at_risk)
+        .map(|entry| entry.value().clone())
+        .collect();
+    positions.sort_by_key(|position| position.health_factor);
+
+    Ok(Json(positions.iter().map(AtRiskPosition::from).collect()))
+}
+
+/// Full circuit breaker status report
+async fn circuit_breaker_status(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Result<Json<CircuitBreakerStatusReport>, AdminError> {
+    authorize(&state, &headers, "circuit breaker status")?;
+    let circuit_breaker = state
+        .circuit_breaker
+        .as_ref()
+        .ok_or_else(|| unavailable("circuit breaker"))?;
+
+    Ok(Json(circuit_breaker.get_status_report()))
+}
+
+/// Turn liquidation execution on or off. Disabling puts the circuit breaker in its manual
+/// `Disabled` state, which blocks every liquidation; enabling closes it again.
+async fn set_execution(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(request): Json<ExecutionRequest>,
+) -> Result<(StatusCode, Json<AdminResponse>), AdminError> {
+    authorize(&state, &headers, "set execution")?;
+    let circuit_breaker = state
+        .circuit_breaker
+        .as_ref()
+        .ok_or_else(|| unavailable("circuit breaker"))?;
+
+    let result = if request.enabled {
+        circuit_breaker.enable().await
+    } else {
+        circuit_breaker.disable().await
+    };
+
+    let action = if request.enabled { "enabled" } else { "disabled" };
+    match result {
+        Ok(()) => {
+            info!("🛠️ Liquidation execution {} via admin API", action);
+            Ok(AdminResponse::ok(format!("liquidation execution {}", action)))
+        }
+        Err(e) => Err(AdminResponse::error(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("failed to update circuit breaker: {}", e),
+        )),
+    }
+}
+
+/// Queue a health check for one user
+async fn rescan_user(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+    Json(request): Json<RescanUserRequest>,
+) -> Result<(StatusCode, Json<AdminResponse>), AdminError> {
+    authorize(&state, &headers, "rescan user")?;
+
+    info!("🔍 Rescan of {:?} requested via admin API", request.user);
+    send_event(&state, BotEvent::UserPositionChanged(request.user))?;
+    Ok(AdminResponse::ok(format!("rescan queued for {}", request.user)))
+}
+
+/// Queue a health check for every known user
+async fn full_rescan(
+    State(state): State<AdminState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<AdminResponse>), AdminError> {
+    authorize(&state, &headers, "full rescan")?;
+
+    info!("🔄 Full rescan requested via admin API");
+    send_event(&state, BotEvent::FullRescan)?;
+    Ok(AdminResponse::ok("full rescan queued"))
+}
+
+fn send_event(state: &AdminState, event: BotEvent) -> Result<(), AdminError> {
+    state.event_tx.send(event).map_err(|e| {
+        AdminResponse::error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            format!("event pipeline unavailable: {}", e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::circuit_breaker::tests::create_test_config;
+    use crate::events::EventReceiver;
+    use alloy_primitives::U256;
+    use std::time::Duration;
+
+    async fn spawn_test_server(
+        admin_token: Option<&str>,
+    ) -> (String, EventReceiver) {
+        let (event_tx, event_rx) = crate::events::channel(10, 10);
+        let state = AdminState::new(admin_token.map(|t| t.to_string()), event_tx);
+        (spawn_admin(state).await, event_rx)
+    }
+
+    async fn spawn_admin(state: AdminState) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_admin(listener, state));
+        format!("http://{}", addr)
+    }
+
+    fn test_position(byte: u8, health_factor: u64, is_at_risk: bool) -> UserPosition {
+        UserPosition {
+            address: Address::from([byte; 20]),
+            total_collateral_base: U256::from(10_000u64),
+            total_debt_base: U256::from(8_000u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_500u64),
+            ltv: U256::from(8_000u64),
+            health_factor: U256::from(health_factor),
+            last_updated: chrono::Utc::now(),
+            is_at_risk,
+        }
+    }
+
+    /// Admin server wired to tracked positions and a circuit breaker, like the running bot
+    async fn spawn_bot_server() -> (
+        String,
+        EventReceiver,
+        Arc<CircuitBreaker>,
+    ) {
+        let (event_tx, event_rx) = crate::events::channel(10, 10);
+        let user_positions = Arc::new(DashMap::new());
+        for position in [
+            test_position(1, 1_050_000_000_000_000_000, true),
+            test_position(2, 950_000_000_000_000_000, true),
+            test_position(3, 2_000_000_000_000_000_000, false),
+        ] {
+            user_positions.insert(position.address, position);
+        }
+        let circuit_breaker = Arc::new(CircuitBreaker::new(create_test_config()));
+        let state = AdminState::new(Some("secret".to_string()), event_tx)
+            .with_user_positions(user_positions)
+            .with_circuit_breaker(circuit_breaker.clone());
+        (spawn_admin(state).await, event_rx, circuit_breaker)
+    }
+
+    #[tokio::test]
+    async fn test_pushed_address_triggers_liquidation_pipeline() {
+        let (base_url, mut event_rx) = spawn_test_server(Some("secret")).await;
+        let user = Address::from([7u8; 20]);
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/liquidate", base_url))
+            .bearer_auth("secret")
+            .json(&serde_json::json!({ "user": user }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+
+        let event = tokio::time::timeout(Duration::from_secs(1), event_rx.recv())
+            .await
+            .expect("event should be queued")
+            .expect("channel open");
+        match event {
+            BotEvent::ExternalLiquidationSignal(addr) => assert_eq!(addr, user),
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_signal_rejected_without_valid_token() {
+        let (base_url, mut event_rx) = spawn_test_server(Some("secret")).await;
+        let client = reqwest::Client::new();
+        let body = serde_json::json!({ "user": Address::from([7u8; 20]) });
+
+        let wrong_token = client
+            .post(format!("{}/liquidate", base_url))
+            .bearer_auth("wrong")
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(wrong_token.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        let no_token = client
+            .post(format!("{}/liquidate", base_url))
+            .json(&body)
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(no_token.status(), reqwest::StatusCode::UNAUTHORIZED);
+
+        assert!(event_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signal_rejected_when_token_not_configured() {
+        let (base_url, mut event_rx) = spawn_test_server(None).await;
+
+        let response = reqwest::Client::new()
+            .post(format!("{}/liquidate", base_url))
+            .bearer_auth("anything")
+            .json(&serde_json::json!({ "user": Address::from([7u8; 20]) }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::UNAUTHORIZED);
+        assert!(event_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_status_and_at_risk_listing() {
+        let (base_url, _event_rx, _circuit_breaker) = spawn_bot_server().await;
+        let client = reqwest::Client::new();
+
+        let status: BotStatus = client
+            .get(format!("{}/status", base_url))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(status.tracked_positions, 3);
+        assert_eq!(status.at_risk_positions, 2);
+        assert_eq!(status.liquidatable_positions, 1);
+        assert_eq!(status.circuit_breaker_state, Some(CircuitBreakerState::Closed));
+        assert!(status.execution_enabled);
+        assert!(status.wallets.is_empty());
+        assert!(status.inventory.is_none());
+
+        let positions: Vec<AtRiskPosition> = client
+            .get(format!("{}/positions/at-risk", base_url))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        let addresses: Vec<Address> = positions.iter().map(|p| p.address).collect();
+        assert_eq!(addresses, vec![Address::from([2u8; 20]), Address::from([1u8; 20])]);
+        assert!(positions[0].liquidatable);
+        assert!(!positions[1].liquidatable);
+
+        let unauthorized = client
+            .get(format!("{}/status", base_url))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(unauthorized.status(), reqwest::StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_execution_switch_drives_circuit_breaker() {
+        let (base_url, _event_rx, circuit_breaker) = spawn_bot_server().await;
+        let client = reqwest::Client::new();
+
+        let response = client
+            .post(format!("{}/execution", base_url))
+            .bearer_auth("secret")
+            .json(&serde_json::json!({ "enabled": false }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Disabled);
+        assert!(!circuit_breaker.is_liquidation_allowed());
+
+        let report: serde_json::Value = client
+            .get(format!("{}/circuit-breaker", base_url))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(report["state"], "Disabled");
+
+        client
+            .post(format!("{}/execution", base_url))
+            .bearer_auth("secret")
+            .json(&serde_json::json!({ "enabled": true }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_rescan_endpoints_queue_events() {
+        let (base_url, mut event_rx, _circuit_breaker) = spawn_bot_server().await;
+        let client = reqwest::Client::new();
+        let user = Address::from([9u8; 20]);
+
+        let response = client
+            .post(format!("{}/rescan/user", base_url))
+            .bearer_auth("secret")
+            .json(&serde_json::json!({ "user": user }))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        match event_rx.try_recv() {
+            Ok(BotEvent::UserPositionChanged(addr)) => assert_eq!(addr, user),
+            other => panic!("unexpected event: {:?}", other),
+        }
+
+        let response = client
+            .post(format!("{}/rescan", base_url))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(matches!(event_rx.try_recv(), Ok(BotEvent::FullRescan)));
+    }
+
+    #[tokio::test]
+    async fn test_bot_endpoints_unavailable_without_bot_state() {
+        let (base_url, _event_rx) = spawn_test_server(Some("secret")).await;
+
+        let response = reqwest::Client::new()
+            .get(format!("{}/positions/at-risk", base_url))
+            .bearer_auth("secret")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::SERVICE_UNAVAILABLE);
+    }
+}
@@ -0,0 +1,48 @@
+use clap::Parser;
+use eyre::Result;
+use liquidation_bot::{config, database};
+use liquidation_bot::web_dashboard::{self, WebDashboardState};
+use tracing_subscriber::EnvFilter;
+
+/// Serve the web dashboard from the bot's database without running the bot. Positions are
+/// shown as last persisted and circuit breaker status is unavailable.
+#[derive(Parser)]
+#[command(name = "dashboard")]
+#[command(about = "Web dashboard over the liquidation bot's database", long_about = None)]
+struct Cli {
+    /// Database to read (defaults to DATABASE_URL, then sqlite:liquidation_bot.db)
+    #[arg(long)]
+    database_url: Option<String>,
+
+    /// Chain whose rows to show (defaults to CHAIN_ID, then Base)
+    #[arg(long)]
+    chain_id: Option<u64>,
+
+    /// Address to serve the dashboard on
+    #[arg(long, default_value = "127.0.0.1:8090")]
+    listen: String,
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    dotenvy::dotenv().ok();
+    let cli = Cli::parse();
+
+    tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::new("info"))
+        .with_target(false)
+        .init();
+
+    let database_url = cli
+        .database_url
+        .or_else(|| std::env::var("DATABASE_URL").ok())
+        .unwrap_or_else(|| "sqlite:liquidation_bot.db".to_string());
+
+    let chain_id = cli
+        .chain_id
+        .or_else(|| std::env::var("CHAIN_ID").ok()?.parse().ok())
+        .unwrap_or(config::DEFAULT_CHAIN_ID);
+
+    let db_pool = database::init_database(&database_url, chain_id).await?;
+    web_dashboard::start_web_dashboard(&cli.listen, WebDashboardState::new(db_pool)).await
+}
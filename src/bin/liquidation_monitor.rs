@@ -1,3 +1,4 @@
+use alloy_primitives::Address;
 use clap::{Parser, Subcommand};
 use eyre::Result;
 use liquidation_bot::monitoring::{LiquidationMonitor, LiquidationMonitorConfig};
@@ -264,17 +265,17 @@ async fn run_historical_analysis(cli: &Cli, from_block: u64, to_block: Option<u6
     .await?;
 
     // Fetch and analyze historical events
-    analyze_historical_events(Arc::new(monitor), from_block, to_block).await?;
+    analyze_historical_events(Arc::new(monitor), config.pool_address, from_block, to_block).await?;
 
     Ok(())
 }
 
 async fn analyze_historical_events(
     monitor: Arc<LiquidationMonitor>,
+    pool_address: Address,
     from_block: u64,
     to_block: Option<u64>,
 ) -> Result<()> {
-    use alloy_primitives::Address;
     use alloy_provider::{Provider, ProviderBuilder};
     use alloy_rpc_types::{BlockNumberOrTag, Filter};
     use alloy_sol_types::SolEvent;
@@ -293,7 +294,6 @@ async fn analyze_historical_events(
 
     // Create filter for historical events
     let event_signature = LiquidationCall::SIGNATURE_HASH;
-    let pool_address: Address = "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5".parse()?;
     let filter = Filter::new()
         .address(pool_address)
         .event_signature(event_signature)
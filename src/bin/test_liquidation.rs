@@ -1,7 +1,7 @@
 use alloy_primitives::{Address, U256};
 use chrono::Utc;
 use liquidation_bot::models::{
-    GasEstimate, LiquidationAssetConfig, LiquidationOpportunity, UserPosition,
+    FlashLoanSource, GasEstimate, LiquidationAssetConfig, LiquidationOpportunity, UserPosition,
 };
 use std::str::FromStr;
 
@@ -87,6 +87,7 @@ fn simulate_liquidation_with_custom_slippage(
         expected_collateral_received: expected_collateral,
         liquidation_bonus,
         flash_loan_fee,
+        flash_loan_source: FlashLoanSource::AaveV3,
         gas_cost: gas_estimate.total_cost,
         swap_slippage,
         estimated_profit,
@@ -137,6 +138,7 @@ fn simulate_liquidation(
         expected_collateral_received: expected_collateral,
         liquidation_bonus,
         flash_loan_fee,
+        flash_loan_source: FlashLoanSource::AaveV3,
         gas_cost: gas_estimate.total_cost,
         swap_slippage,
         estimated_profit,
@@ -2,60 +2,151 @@ use crate::database::DatabasePool;
 use alloy_contract::{ContractInstance, Interface};
 use alloy_primitives::{Address, U256};
 use alloy_provider::Provider;
-use alloy_signer_local::PrivateKeySigner;
 use dashmap::DashMap;
+use futures::stream::{FuturesUnordered, StreamExt};
 use eyre::Result;
 use parking_lot::RwLock as SyncRwLock;
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
 
+use crate::accounting;
+use crate::admin;
 use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerState};
-use crate::config::{AssetLoadingMethod, BotConfig};
+use crate::config::{self, AssetLoadingMethod, BotConfig, SharedConfig};
 use crate::database;
-use crate::events::BotEvent;
+use crate::event_bus;
+use crate::events::{self, BotEvent, EventLane, EventReceiver, EventSender};
+use crate::feed;
+use crate::gas;
+use crate::inventory;
 use crate::liquidation;
 use crate::models::{
-    AssetConfig, HardhatArtifact, LiquidationAssetConfig, LiquidationResult, PriceFeed, UserPosition,
+    AssetConfig, ExecutionMode, HardhatArtifact, LiquidationAssetConfig, LiquidationResult,
+    NotNeededReason, PriceFeed, SimulationMode, SubmissionStrategy, UserPosition,
 };
-use crate::monitoring::{discovery, oracle, scanner, websocket};
+use crate::monitoring::{
+    alerts, block_scanner, config_reload, discovery, health_engine, heartbeat, latency, mempool, metrics, operator_balance, oracle, pool_pause, price_history,
+    price_trigger, protocol_monitor, refresh_throttle, scan_tiers, scanner, value_at_risk, wallet_balance, websocket, ProviderSet,
+};
+use crate::protocols;
+use crate::replay;
+use crate::shutdown::{InFlightGuard, ShutdownCoordinator};
+use crate::signer::BotSigner;
+use crate::sim;
+use crate::treasury;
+use crate::web_dashboard;
+use crate::webhook_sink;
+
+/// Minimum time between liquidatable-user alerts for the same user
+const LIQUIDATABLE_ALERT_COOLDOWN: Duration = Duration::from_secs(15 * 60);
+
+/// How often the database is probed for the unreachable-database alert
+const DATABASE_HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// How often position history older than the retention window is deleted
+const POSITION_HISTORY_PRUNE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// How often held aToken lots are checked for unwinding
+const A_TOKEN_UNWIND_CHECK_INTERVAL: Duration = Duration::from_secs(600);
+
+/// How long a retry waits for the fee-bumped replacement of a timed-out liquidation
+const LIQUIDATION_REPLACEMENT_TIMEOUT: Duration = Duration::from_secs(120);
 
 // Main bot struct with event monitoring capabilities
 pub struct LiquidationBot<P> {
     provider: Arc<P>,
-    ws_provider: Arc<dyn Provider>,
-    signer: PrivateKeySigner,
+    providers: ProviderSet<P>, // Call + real-time providers and whether real-time is available
+    wallets: Arc<liquidation::WalletPool>, // Executor wallets liquidations are spread across
     pub config: BotConfig,
+    live_config: SharedConfig, // Hot-reloadable view of `config`; read thresholds from here
     pool_contract: ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    pool_version: liquidation::PoolVersion, // Aave release of the pool, for close factor and dust rules
     _liquidator_contract: Option<ContractInstance<alloy_transport::BoxTransport, Arc<P>>>,
     db_pool: DatabasePool,
     user_positions: Arc<DashMap<Address, UserPosition>>,
+    persisted_positions: Arc<scanner::PersistedPositions>, // What was last written, for the persist threshold
     processing_users: Arc<SyncRwLock<HashSet<Address>>>,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
-    event_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<BotEvent>>>,
+    refresh_throttle: refresh_throttle::RefreshThrottle, // Per-user limit on UserPositionChanged refreshes
+    scan_tiers: Arc<scan_tiers::TierSchedule>, // When each position is next re-checked, by health factor tier
+    event_tx: EventSender,
+    event_rx: Arc<tokio::sync::Mutex<EventReceiver>>,
     // High-priority liquidation pipeline
-    priority_liquidation_tx: mpsc::UnboundedSender<Address>,
-    priority_liquidation_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<Address>>>,
+    priority_liquidation_tx: mpsc::Sender<Address>,
+    priority_liquidation_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<Address>>>,
+    queued_liquidation_tx: mpsc::Sender<Address>, // persisted, awaiting processing
+    queued_liquidation_rx: Arc<tokio::sync::Mutex<mpsc::Receiver<Address>>>,
     // Oracle price monitoring
     price_feeds: Arc<DashMap<Address, PriceFeed>>,
     asset_configs: HashMap<Address, AssetConfig>,
     users_by_collateral: Arc<DashMap<Address, HashSet<Address>>>, // asset -> users holding it as collateral
+    users_by_debt: Arc<DashMap<Address, HashSet<Address>>>, // asset -> users borrowing it
+    health_engine: Option<Arc<health_engine::HealthFactorEngine>>, // Local HF recomputation on price ticks (None = RPC for every user)
+    price_triggers: Arc<price_trigger::PriceTriggerIndex>, // Collateral fall and debt rise each borrower can withstand
     // Liquidation functionality
     liquidation_assets: HashMap<Address, LiquidationAssetConfig>,
+    reserve_configs: Arc<liquidation::ReserveConfigCache>, // Live bonus, thresholds and flags overlaid on liquidation_assets
+    emode: Arc<liquidation::EModeTracker>, // Users' e-mode categories, whose bonus replaces the reserve's
     liquidator_contract_address: Option<Address>,
     // Circuit breaker for extreme market conditions
     circuit_breaker: Arc<CircuitBreaker>,
+    // Minimum position age gating
+    position_age_tracker: Arc<liquidation::PositionAgeTracker>,
+    // Governance pause detection
+    pool_pause_guard: Arc<pool_pause::PoolPauseGuard>,
+    // Stale, zero or disagreeing price feed detection
+    oracle_guard: Arc<oracle::OracleGuard>,
+    // Total liquidatable value across tracked positions
+    value_at_risk: Arc<value_at_risk::ValueAtRiskGauge>,
+    // Signer native balance alerting
+    operator_balance_guard: Arc<operator_balance::OperatorBalanceGuard>,
+    // Raw log / event recording for offline replay
+    event_recorder: Option<Arc<replay::EventRecorder>>,
+    // Kafka/NATS/Redis mirror of processor events and execution results
+    event_bus: Option<Arc<event_bus::EventBus>>,
+    // Services and policy every liquidation runs with: the opportunity webhook, profit recheck,
+    // private relay, aggregator swaps, WETH top-ups, flash loan lenders, wallet funding,
+    // pre-flight simulation and priority fee racing
+    liquidation_ctx: liquidation::LiquidationContext<P>,
+    // Severity-routed alert webhooks
+    alert_notifier: alerts::AlertNotifier,
+    liquidatable_alerted: DashMap<Address, Instant>, // Last liquidatable-user alert per user
+    consecutive_liquidation_failures: AtomicU64,
+    // Users with a liquidation in flight, so concurrent executions never target one account
+    liquidating_users: liquidation::UserLocks,
+    // Retries of transient liquidation failures, and the failed attempts so far per user
+    retry_policy: liquidation::RetryPolicy,
+    failed_attempts: DashMap<Address, u32>,
+    collateral_swapper: Option<Arc<liquidation::CollateralSwapper>>,
+    // Profit sweeps to the cold wallet (None = disabled), woken early after executed liquidations
+    treasury: Option<Arc<treasury::Treasury>>,
+    treasury_wake: tokio::sync::Notify,
+    inventory: Option<Arc<inventory::InventoryTracker>>, // Inventory valuation and rebalancing (None = disabled)
+    a_token_unwinder: Option<Arc<liquidation::ATokenUnwinder>>, // Withdraws aTokens held from liquidations (None = never)
+    // Fee history, per-profile EIP-1559 fees and the circuit breaker's gas baseline
+    gas_oracle: Arc<gas::GasOracle>,
+    // Competing liquidations seen pending in the mempool, by target user
+    competitor_tracker: Arc<mempool::CompetitorTracker>,
+    // Stops new work and tracks in-flight liquidations on SIGTERM
+    shutdown: ShutdownCoordinator,
 }
 
 impl<P> LiquidationBot<P>
 where
     P: Provider + 'static,
 {
-    /// Get a reference to the signer for transaction signing
-    pub fn signer(&self) -> &PrivateKeySigner {
-        &self.signer
+    /// Get a reference to the primary signer for transaction signing
+    pub fn signer(&self) -> &BotSigner {
+        self.wallets.primary()
+    }
+
+    /// Executor wallets liquidations are spread across
+    pub fn wallets(&self) -> &Arc<liquidation::WalletPool> {
+        &self.wallets
     }
 
     /// Get circuit breaker status and statistics
@@ -71,6 +162,21 @@ where
         )
     }
 
+    /// Total value currently extractable from liquidatable positions (base currency, 8 decimals)
+    pub fn get_total_value_at_risk(&self) -> U256 {
+        self.value_at_risk.get()
+    }
+
+    /// Whether real-time (WebSocket) monitoring is active, as opposed to HTTP polling
+    pub fn is_real_time_enabled(&self) -> bool {
+        self.providers.is_real_time_enabled()
+    }
+
+    /// Handle that stops the bot gracefully when triggered (e.g. from a signal handler)
+    pub fn shutdown_handle(&self) -> ShutdownCoordinator {
+        self.shutdown.clone()
+    }
+
     /// Manually control circuit breaker state (for emergency situations)
     pub async fn disable_circuit_breaker(&self) -> Result<()> {
         self.circuit_breaker.disable().await
@@ -81,182 +187,1362 @@ where
         self.circuit_breaker.enable().await
     }
 
-    /// Run high-priority liquidation processor
+    /// Record the current block as an observation of the user for position age gating
+    async fn record_position_observation(&self, user: Address) {
+        if !self.position_age_tracker.is_enabled() {
+            return;
+        }
+
+        match self.provider.get_block_number().await {
+            Ok(block) => self.position_age_tracker.record_observation(user, block),
+            Err(e) => debug!("Failed to get block number for position observation: {}", e),
+        }
+    }
+
+    /// Check whether the user's position has existed long enough to be liquidated
+    async fn is_position_old_enough(&self, user: Address) -> bool {
+        if !self.position_age_tracker.is_enabled() {
+            return true;
+        }
+
+        match self.provider.get_block_number().await {
+            Ok(block) => self.position_age_tracker.is_eligible(user, block),
+            Err(e) => {
+                // Don't hold up liquidations on a transient RPC error
+                warn!("Failed to get block number for position age check: {}", e);
+                true
+            }
+        }
+    }
+
+    /// Persist priority liquidations as they are queued so a crash doesn't lose them, then
+    /// hand them to the liquidation processor
+    async fn run_liquidation_queue_persister(&self) -> Result<()> {
+        let mut priority_rx = self.priority_liquidation_rx.lock().await;
+
+        while let Some(user_address) = priority_rx.recv().await {
+            self.notify_liquidatable_user(user_address);
+
+            if self.config.persistent_liquidation_queue_enabled {
+                if let Err(e) =
+                    database::enqueue_pending_liquidation(&self.db_pool, user_address).await
+                {
+                    warn!(
+                        "Failed to persist queued liquidation for user {:?}: {}",
+                        user_address, e
+                    );
+                }
+            }
+
+            self.queued_liquidation_tx
+                .send(user_address)
+                .await
+                .map_err(|e| eyre::eyre!("Liquidation processor channel closed: {}", e))?;
+        }
+
+        Ok(())
+    }
+
+    /// Re-validate liquidations persisted by a previous run and re-queue those still liquidatable
+    async fn restore_liquidation_queue(&self) -> Result<()> {
+        if !self.config.persistent_liquidation_queue_enabled {
+            return Ok(());
+        }
+
+        let pool_address = *self.pool_contract.address();
+        let requeue = liquidation::restore_persisted_queue(&self.db_pool, |user| {
+            let provider = self.provider.clone();
+            async move {
+                let position = scanner::check_user_health(&provider, pool_address, user, 3).await?;
+                Ok(scanner::is_liquidatable(&position))
+            }
+        })
+        .await?;
+
+        if !requeue.is_empty() {
+            info!("📥 Re-queuing {} persisted liquidation(s)", requeue.len());
+        }
+        // Nothing drains the priority queue yet; whatever doesn't fit waits on the event queue
+        for user in requeue {
+            if self.priority_liquidation_tx.try_send(user).is_err() {
+                self.event_tx.send(BotEvent::LiquidationOpportunity(user))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Run high-priority liquidation processor. Liquidations of different users run
+    /// concurrently, up to LIQUIDATION_PARALLELISM at once, each on its own executor wallet
+    /// when there are enough of them.
     async fn run_liquidation_processor(&self) -> Result<()> {
-        info!("🚀 Starting high-priority liquidation processor...");
+        let parallelism = self.config.liquidation_parallelism.max(1);
+        info!(
+            "🚀 Starting high-priority liquidation processor ({} concurrent liquidations)...",
+            parallelism
+        );
         
-        let mut priority_rx = self.priority_liquidation_rx.lock().await;
+        let mut queued_rx = self.queued_liquidation_rx.lock().await;
+        let mut liquidations = FuturesUnordered::new();
         
-        while let Some(user_address) = priority_rx.recv().await {
-            info!("⚡ Processing priority liquidation for user: {:?}", user_address);
-            
-            // Check circuit breaker before processing liquidation
-            // IMPORTANT: Capture state BEFORE liquidation to avoid TOCTOU bug
-            let circuit_breaker_state_before = self.circuit_breaker.get_state();
+        loop {
+            let user_address = tokio::select! {
+                user = queued_rx.recv(), if liquidations.len() < parallelism => match user {
+                    Some(user) => user,
+                    None => break,
+                },
+                Some(()) = liquidations.next(), if !liquidations.is_empty() => continue,
+            };
+            // Still persisted, so the next run picks it up
+            let Some(in_flight) = self.shutdown.begin_liquidation() else {
+                info!(
+                    "🛑 Not starting priority liquidation for user {:?} - shutting down",
+                    user_address
+                );
+                break;
+            };
+            liquidations.push(self.run_queued_liquidation(user_address, in_flight));
+        }
+
+        // Let the liquidations already started finish
+        while liquidations.next().await.is_some() {}
+        Ok(())
+    }
+
+    /// Execute one queued liquidation and drop it from the persisted queue
+    async fn run_queued_liquidation(&self, user_address: Address, _in_flight: InFlightGuard) {
+        // The liquidation already in flight removes the user from the persisted queue
+        let Some(_user_lock) = self.liquidating_users.try_lock(user_address) else {
+            info!(
+                "🔒 Skipping priority liquidation for user {:?} - already being liquidated",
+                user_address
+            );
+            return;
+        };
+        self.process_priority_liquidation(user_address).await;
 
-            if !self.circuit_breaker.is_liquidation_allowed() {
+        if self.config.persistent_liquidation_queue_enabled {
+            if let Err(e) =
+                database::dequeue_pending_liquidation(&self.db_pool, user_address).await
+            {
                 warn!(
-                    "🚫 Priority liquidation blocked by circuit breaker (state: {:?}) for user: {:?}",
-                    circuit_breaker_state_before, user_address
+                    "Failed to remove processed liquidation for user {:?} from queue: {}",
+                    user_address, e
                 );
-                self.circuit_breaker.record_blocked_liquidation();
+            }
+        }
+    }
 
-                // Record the blocked attempt for frequency monitoring
-                if let Err(e) = self
-                    .circuit_breaker
-                    .record_liquidation_attempt(false, None)
-                    .await
-                {
-                    warn!("Failed to record blocked priority liquidation attempt: {}", e);
-                }
-                continue;
+    /// Re-check the executor wallets' native balances after spending gas on a liquidation.
+    /// The operator balance guard follows the best-funded wallet: liquidations can continue
+    /// while any wallet can pay for gas.
+    async fn refresh_operator_balance(&self) {
+        let previous_level = self.operator_balance_guard.level();
+        match self
+            .wallets
+            .refresh_balances(self.provider.as_ref())
+            .await
+            .map(|highest| self.operator_balance_guard.record_balance(highest))
+        {
+            Ok(level) if level != previous_level => {
+                self.alert_notifier
+                    .spawn_notify(alerts::AlertEvent::OperatorBalanceChanged {
+                        level,
+                        min_balance: self.config.min_operator_balance_wei,
+                    });
             }
+            Ok(_) => {}
+            Err(e) => warn!("Failed to refresh operator balance: {}", e),
+        }
+    }
 
-            // Determine if this is a test liquidation based on state BEFORE execution
-            let is_test_liquidation = circuit_breaker_state_before
-                == crate::circuit_breaker::CircuitBreakerState::HalfOpen;
+    /// Profit and health factor thresholds from the hot-reloadable config
+    fn liquidation_thresholds(&self) -> liquidation::LiquidationThresholds {
+        let live = self.live_config.load();
+        liquidation::LiquidationThresholds {
+            min_profit_threshold: live.min_profit_threshold,
+            min_profit_pct_of_debt_bps: live.min_profit_pct_of_debt_bps,
+            liquidation_hf_margin: live.liquidation_hf_margin,
+        }
+    }
 
-            // Get current gas price for circuit breaker monitoring
-            let current_gas_price = match self.provider.get_gas_price().await {
-                Ok(price) => Some(alloy_primitives::U256::from(price)),
-                Err(e) => {
-                    warn!("Failed to get current gas price for priority liquidation: {}", e);
-                    None
-                }
-            };
+    /// Asset configs for liquidating `user`: live reserve configurations, with the bonus of
+    /// the user's e-mode category on the assets belonging to it
+    async fn liquidation_assets_for(
+        &self,
+        user: Address,
+    ) -> HashMap<Address, LiquidationAssetConfig> {
+        let mut assets = self.reserve_configs.overlay(&self.liquidation_assets);
+        if let Err(e) = self
+            .emode
+            .refresh_user(self.provider.as_ref(), *self.pool_contract.address(), user)
+            .await
+        {
+            warn!("Failed to read e-mode category of {:?}: {}", user, e);
+        }
+        self.emode.apply(user, &mut assets, &self.reserve_configs);
+        assets
+    }
+
+    /// Alert that `user` is liquidatable, at most once per cooldown so rescans don't repeat it
+    fn notify_liquidatable_user(&self, user: Address) {
+        let health_factor = self
+            .user_positions
+            .get(&user)
+            .map(|position| position.health_factor);
+        feed::publish(
+            self.config.chain_id,
+            feed::FeedEventKind::Opportunity { user, health_factor },
+        );
+
+        let now = Instant::now();
+        self.liquidatable_alerted
+            .retain(|_, alerted_at| now.duration_since(*alerted_at) < LIQUIDATABLE_ALERT_COOLDOWN);
+        if self.liquidatable_alerted.contains_key(&user) {
+            return;
+        }
+        self.liquidatable_alerted.insert(user, now);
+        self.alert_notifier
+            .spawn_notify(alerts::AlertEvent::LiquidatableUserDetected {
+                user,
+                health_factor,
+            });
+    }
+
+    /// Alert on a failure streak once it reaches the repeated-failure threshold (successes are
+    /// alerted with their profit by the liquidation handler)
+    fn notify_liquidation_outcome(&self, result: &Result<LiquidationResult>) {
+        let error = match result {
+            Ok(LiquidationResult::Executed(_)) => {
+                metrics::record_liquidation_outcome("executed");
+                self.consecutive_liquidation_failures.store(0, Ordering::Relaxed);
+                return;
+            }
+            Ok(LiquidationResult::NotNeeded(NotNeededReason::DryRun)) => {
+                metrics::record_liquidation_outcome("dry_run");
+                return;
+            }
+            Ok(LiquidationResult::NotNeeded(_)) => return,
+            Ok(LiquidationResult::Failed(error)) => error.clone(),
+            Err(e) => e.to_string(),
+        };
+        metrics::record_liquidation_outcome("failed");
+
+        let consecutive_failures =
+            self.consecutive_liquidation_failures.fetch_add(1, Ordering::Relaxed) + 1;
+        if consecutive_failures == alerts::REPEATED_FAILURE_ALERT_THRESHOLD {
+            self.alert_notifier
+                .spawn_notify(alerts::AlertEvent::RepeatedLiquidationFailures {
+                    consecutive_failures,
+                    last_error: error,
+                });
+        }
+    }
+
+    /// Persist the stage timestamps of `user`'s opportunity now that it has been handled
+    async fn record_latency(&self, user: Address, result: &Result<LiquidationResult>) {
+        let Some(trace) = latency::global().finish(user) else {
+            return;
+        };
+        let (outcome, tx_hash) = match result {
+            Ok(LiquidationResult::Executed(tx_hash)) => ("executed", Some(tx_hash.as_str())),
+            Ok(LiquidationResult::NotNeeded(NotNeededReason::DryRun)) => ("dry_run", None),
+            Ok(LiquidationResult::NotNeeded(_)) => ("not_needed", None),
+            Ok(LiquidationResult::Failed(_)) => ("failed", None),
+            Err(_) => ("error", None),
+        };
+        if let Err(e) = database::record_liquidation_latency(
+            &self.db_pool,
+            user,
+            tx_hash,
+            outcome,
+            &trace,
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .await
+        {
+            warn!("Failed to record liquidation latency for {:?}: {}", user, e);
+        }
+    }
 
-            // Execute liquidation first, then record success/failure
-            let liquidation_result = liquidation::handle_liquidation_opportunity(
-                self.provider.clone(),
-                &self.db_pool,
-                user_address,
-                self.config.min_profit_threshold,
-                self.liquidator_contract_address,
-                Some(self.signer.clone()),
-                &self.pool_contract,
-                &self.liquidation_assets,
-                &self.config.rpc_url,
+    /// Categorize and persist a failed attempt and apply the retry policy: transient failures
+    /// are queued again after a backoff with freshly quoted gas (replacing the transaction
+    /// if it is still pending), permanent ones are dropped.
+    /// Returns the category, or None if the attempt didn't fail.
+    async fn classify_liquidation_failure(
+        &self,
+        user: Address,
+        result: &Result<LiquidationResult>,
+    ) -> Option<liquidation::FailureCategory> {
+        let error = match result {
+            Ok(LiquidationResult::Failed(error)) => error.clone(),
+            Err(e) => e.to_string(),
+            Ok(_) => {
+                self.failed_attempts.remove(&user);
+                return None;
+            }
+        };
+        let category = liquidation::FailureCategory::classify(&error);
+        let attempt = {
+            let mut attempts = self.failed_attempts.entry(user).or_insert(0);
+            *attempts += 1;
+            *attempts
+        };
+        let decision = self.retry_policy.decide(category, attempt);
+        metrics::record_liquidation_failure(category.as_str());
+        metrics::record_liquidation_retry_decision(decision.as_str());
+        if let Err(e) = database::record_liquidation_failure(
+            &self.db_pool,
+            user,
+            category,
+            &error,
+            attempt,
+            decision,
+            chrono::Utc::now().timestamp_millis(),
+        )
+        .await
+        {
+            warn!("Failed to record liquidation failure for {:?}: {}", user, e);
+        }
+
+        match decision {
+            liquidation::RetryDecision::Retry(delay) => {
+                info!(
+                    "🔁 Liquidation attempt {}/{} for {:?} failed ({}) - retrying in {:?}",
+                    attempt,
+                    self.retry_policy.max_attempts,
+                    user,
+                    category.as_str(),
+                    delay
+                );
+                let liquidation_tx = self.priority_liquidation_tx.clone();
+                let provider = self.provider.clone();
+                let gas_oracle = self.gas_oracle.clone();
+                tokio::spawn(async move {
+                    tokio::time::sleep(delay).await;
+                    // The next attempt bids off the current fee market, not the quote that failed
+                    if let Err(e) = gas_oracle.refresh(provider.as_ref()).await {
+                        warn!("Failed to refresh gas quotes before retrying {:?}: {}", user, e);
+                    }
+                    let _ = liquidation_tx.send(user).await;
+                });
+            }
+            liquidation::RetryDecision::Abandon | liquidation::RetryDecision::Exhausted => {
+                info!(
+                    "🛑 Giving up on liquidating {:?} after {} attempt(s): {} ({})",
+                    user,
+                    attempt,
+                    category.as_str(),
+                    decision.as_str()
+                );
+                self.failed_attempts.remove(&user);
+            }
+        }
+        Some(category)
+    }
+
+    /// Replace a liquidation of `user` that is still pending from an earlier attempt with a
+    /// fee-bumped copy on the same nonce and wait for it, so a retry never races it with a
+    /// second transaction. None if no wallet has one pending.
+    async fn replace_pending_liquidation(
+        &self,
+        user: Address,
+    ) -> Option<Result<LiquidationResult>> {
+        let (signer, tx_manager, nonce) = self.wallets.pending_liquidation(user)?;
+        info!(
+            "⛽ Liquidation of {:?} still pending with nonce {} from {} - replacing it with higher fees",
+            user,
+            nonce,
+            signer.address()
+        );
+        let wallet = alloy_network::EthereumWallet::from(signer.clone());
+        if let Err(e) = tx_manager.replace(self.provider.as_ref(), &wallet, nonce).await {
+            // Possibly mined meanwhile; waiting below finds out
+            warn!("Failed to replace pending liquidation of {:?}: {}", user, e);
+        }
+
+        let receipt = tx_manager
+            .wait_for_receipt(
+                self.provider.as_ref(),
+                signer,
+                nonce,
+                LIQUIDATION_REPLACEMENT_TIMEOUT,
             )
             .await;
+        Some(Ok(match receipt {
+            Ok(receipt) if receipt.status() => {
+                LiquidationResult::Executed(format!("0x{:x}", receipt.transaction_hash))
+            }
+            Ok(receipt) => LiquidationResult::Failed(format!(
+                "Transaction 0x{:x} reverted",
+                receipt.transaction_hash
+            )),
+            Err(e) => LiquidationResult::Failed(format!("Transaction confirmation timeout: {}", e)),
+        }))
+    }
+
+    /// Gate and execute a single priority liquidation
+    async fn process_priority_liquidation(&self, user_address: Address) {
+        info!("⚡ Processing priority liquidation for user: {:?}", user_address);
+
+        if !self.is_position_old_enough(user_address).await {
+            info!(
+                "⏳ Skipping priority liquidation for user {:?} - position younger than {} block(s)",
+                user_address, self.config.min_position_age_blocks
+            );
+            return;
+        }
+
+        if !self.pool_pause_guard.is_liquidation_allowed() {
+            warn!(
+                "⏸️ Priority liquidation suspended for user {:?} - pool is paused by governance",
+                user_address
+            );
+            return;
+        }
+
+        if !self.oracle_guard.is_liquidation_allowed() {
+            warn!(
+                "🔮 Priority liquidation suspended for user {:?} - a price feed failed its checks",
+                user_address
+            );
+            return;
+        }
 
-            let liquidation_succeeded = matches!(liquidation_result, Ok(LiquidationResult::Executed(_)));
+        if !self.operator_balance_guard.is_liquidation_allowed() {
+            warn!(
+                "⛽ Priority liquidation halted for user {:?} - operator balance below minimum",
+                user_address
+            );
+            return;
+        }
+        
+        // Check circuit breaker before processing liquidation
+        // IMPORTANT: Capture state BEFORE liquidation to avoid TOCTOU bug
+        let circuit_breaker_state_before = self.circuit_breaker.get_state();
+
+        if !self.circuit_breaker.is_liquidation_allowed() {
+            warn!(
+                "🚫 Priority liquidation blocked by circuit breaker (state: {:?}) for user: {:?}",
+                circuit_breaker_state_before, user_address
+            );
+            self.circuit_breaker.record_blocked_liquidation();
+
+            // Record the blocked attempt for frequency monitoring
+            if let Err(e) = self
+                .circuit_breaker
+                .record_liquidation_attempt(false, None)
+                .await
+            {
+                warn!("Failed to record blocked priority liquidation attempt: {}", e);
+            }
+            return;
+        }
+
+        // Determine if this is a test liquidation based on state BEFORE execution
+        let is_test_liquidation = circuit_breaker_state_before
+            == crate::circuit_breaker::CircuitBreakerState::HalfOpen;
+
+        // Get current gas price for circuit breaker monitoring
+        let current_gas_price = match self.provider.get_gas_price().await {
+            Ok(price) => Some(alloy_primitives::U256::from(price)),
+            Err(e) => {
+                warn!("Failed to get current gas price for priority liquidation: {}", e);
+                None
+            }
+        };
+
+        if let Some(liquidation_result) = self.replace_pending_liquidation(user_address).await {
+            if let Ok(LiquidationResult::Executed(tx_hash)) = &liquidation_result {
+                self.treasury_wake.notify_one();
+                info!(
+                    "✅ Replacement liquidation mined for user: {:?}, TX: {}",
+                    user_address, tx_hash
+                );
+            }
+            self.classify_liquidation_failure(user_address, &liquidation_result)
+                .await;
+            self.notify_liquidation_outcome(&liquidation_result);
+            self.record_latency(user_address, &liquidation_result).await;
+            if let Some(bus) = &self.event_bus {
+                bus.publish_execution(user_address, &liquidation_result);
+            }
+            self.refresh_operator_balance().await;
+            return;
+        }
+
+        let Some(wallet) = self.wallets.acquire() else {
+            warn!(
+                "⛽ Priority liquidation skipped for user {:?} - no executor wallet above the gas reserve",
+                user_address
+            );
+            return;
+        };
+
+        // Execute liquidation first, then record success/failure
+        let liquidation_result = liquidation::handle_liquidation_opportunity(
+            &self.liquidation_ctx,
+            user_address,
+            Some(wallet.signer().clone()),
+            Some(wallet.tx_manager()),
+            &self.liquidation_assets_for(user_address).await,
+            self.liquidation_thresholds(),
+        )
+        .await;
+
+        let liquidation_succeeded = matches!(liquidation_result, Ok(LiquidationResult::Executed(_)));
+
+        // Handle liquidation failure with fallback
+        match &liquidation_result {
+            Ok(LiquidationResult::Executed(tx_hash)) => {
+                wallet.record_executed();
+                self.treasury_wake.notify_one();
+                info!(
+                    "✅ Priority liquidation executed successfully for user: {:?}, TX: {} (wallet {})",
+                    user_address, tx_hash, wallet.address()
+                );
+            }
+            Ok(LiquidationResult::NotNeeded(reason)) => {
+                info!("ℹ️ Priority liquidation not needed for user: {:?}, reason: {:?}", user_address, reason);
+            }
+            Ok(LiquidationResult::Failed(error)) => {
+                warn!("❌ Priority liquidation failed for user: {:?}, error: {}", user_address, error);
+            }
+            Err(e) => {
+                error!(
+                    "Failed to handle priority liquidation opportunity for {:?}: {}",
+                    user_address, e
+                );
 
-            // Handle liquidation failure with fallback
-            match &liquidation_result {
-                Ok(LiquidationResult::Executed(tx_hash)) => {
-                    info!("✅ Priority liquidation executed successfully for user: {:?}, TX: {}", user_address, tx_hash);
+                // Fallback to legacy handler for logging
+                if let Err(legacy_err) = liquidation::handle_liquidation_opportunity_legacy(
+                    &self.db_pool,
+                    user_address,
+                    self.live_config.load().min_profit_threshold,
+                )
+                .await
+                {
+                    error!("Legacy liquidation handler also failed for priority liquidation: {}", legacy_err);
                 }
-                Ok(LiquidationResult::NotNeeded(reason)) => {
-                    info!("ℹ️ Priority liquidation not needed for user: {:?}, reason: {:?}", user_address, reason);
+            }
+        }
+
+        let failure = self
+            .classify_liquidation_failure(user_address, &liquidation_result)
+            .await;
+
+        // Record ALL liquidation attempts (both successful and failed) for frequency monitoring.
+        // Losing a race to another liquidator isn't a malfunction, so it doesn't trip the breaker.
+        if failure.is_some_and(|category| category.is_lost_race()) {
+            debug!("Not counting lost liquidation race for {:?} in the circuit breaker", user_address);
+        } else if let Err(e) = self
+            .circuit_breaker
+            .record_liquidation_attempt(liquidation_succeeded, current_gas_price)
+            .await
+        {
+            warn!(
+                "Failed to record priority liquidation attempt for circuit breaker: {}",
+                e
+            );
+        }
+
+        self.notify_liquidation_outcome(&liquidation_result);
+        self.record_latency(user_address, &liquidation_result).await;
+        if let Some(bus) = &self.event_bus {
+            bus.publish_execution(user_address, &liquidation_result);
+        }
+
+        // Executed and reverted transactions both spend gas
+        if matches!(
+            liquidation_result,
+            Ok(LiquidationResult::Executed(_) | LiquidationResult::Failed(_))
+        ) {
+            self.refresh_operator_balance().await;
+        }
+
+        // Record test liquidation if this was a half-open state test (determined before execution)
+        if is_test_liquidation && liquidation_succeeded {
+            self.circuit_breaker.record_test_liquidation();
+            info!(
+                "📊 Recorded successful test liquidation (priority path, state was half-open before attempt) for user: {:?}",
+                user_address
+            );
+        }
+    }
+
+    /// Alert when the database stops answering, and again once it recovers
+    async fn run_database_health_check(&self) -> Result<()> {
+        let mut interval = tokio::time::interval(DATABASE_HEALTH_CHECK_INTERVAL);
+        let mut unreachable = false;
+        loop {
+            interval.tick().await;
+            match self.db_pool.execute("SELECT 1").await {
+                Ok(_) if unreachable => {
+                    info!("✅ Database reachable again");
+                    unreachable = false;
+                    self.alert_notifier
+                        .spawn_notify(alerts::AlertEvent::DatabaseRecovered);
                 }
-                Ok(LiquidationResult::Failed(error)) => {
-                    warn!("❌ Priority liquidation failed for user: {:?}, error: {}", user_address, error);
+                Ok(_) => {}
+                Err(e) if !unreachable => {
+                    error!("🗄️ Database unreachable: {}", e);
+                    unreachable = true;
+                    self.alert_notifier
+                        .spawn_notify(alerts::AlertEvent::DatabaseUnreachable {
+                            error: e.to_string(),
+                        });
                 }
-                Err(e) => {
-                    error!(
-                        "Failed to handle priority liquidation opportunity for {:?}: {}",
-                        user_address, e
-                    );
+                Err(e) => debug!("Database still unreachable: {}", e),
+            }
+        }
+    }
+
+    /// Periodically re-read the executor wallets' balances. Native balances drive the operator
+    /// balance guard and the gas reserve, so execution pauses while no wallet can pay for gas
+    /// and monitoring carries on; debt asset balances are published and checked against their
+    /// configured minimums.
+    async fn run_wallet_balance_monitor(&self) -> Result<()> {
+        if self.config.balance_check_interval_secs == 0 {
+            debug!("Periodic wallet balance checks disabled - BALANCE_CHECK_INTERVAL_SECS is 0");
+            return Ok(());
+        }
+
+        let monitor =
+            wallet_balance::TokenBalanceMonitor::new(self.config.min_token_balances.clone());
+        let debt_assets: Vec<LiquidationAssetConfig> = self
+            .liquidation_assets
+            .values()
+            .filter(|asset| asset.is_borrowable)
+            .cloned()
+            .collect();
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.balance_check_interval_secs));
+        let mut paused = false;
+        loop {
+            interval.tick().await;
+
+            self.refresh_operator_balance().await;
+            let statuses = self.wallets.status();
+            wallet_balance::record_native_balance_metrics(metrics::global(), &statuses);
+            let out_of_gas = statuses.iter().all(|wallet| !wallet.funded);
+            if out_of_gas && !paused {
+                error!("⏸️ Liquidation execution paused: no executor wallet above the gas reserve - monitoring continues");
+            } else if paused && !out_of_gas {
+                info!("▶️ Liquidation execution resumed: an executor wallet is funded again");
+            }
+            paused = out_of_gas;
+
+            for alert in wallet_balance::check_token_balances(
+                self.provider.as_ref(),
+                metrics::global(),
+                &monitor,
+                &self.wallets.addresses(),
+                &debt_assets,
+            )
+            .await
+            {
+                self.alert_notifier.spawn_notify(alert);
+            }
+        }
+    }
+
+    /// Sweep executor wallet profits over their thresholds to the cold wallet, on an interval
+    /// and after each executed liquidation. Wallets with a liquidation in flight are left
+    /// alone until the next round so sweeps don't compete with them for nonces.
+    async fn run_treasury_sweeper(&self) -> Result<()> {
+        let Some(treasury) = self.treasury.clone() else {
+            debug!("Treasury sweeps disabled - TREASURY_COLD_WALLET not set");
+            return Ok(());
+        };
+
+        let assets: Vec<LiquidationAssetConfig> =
+            self.liquidation_assets.values().cloned().collect();
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.treasury_sweep_interval_secs));
+        loop {
+            tokio::select! {
+                _ = interval.tick() => {}
+                _ = self.treasury_wake.notified() => {}
+            }
 
-                    // Fallback to legacy handler for logging
-                    if let Err(legacy_err) = liquidation::handle_liquidation_opportunity_legacy(
+            let busy: HashSet<Address> = self
+                .wallets
+                .status()
+                .into_iter()
+                .filter(|wallet| wallet.in_flight > 0)
+                .map(|wallet| wallet.address)
+                .collect();
+            for (signer, tx_manager) in self.wallets.senders() {
+                if busy.contains(&signer.address()) {
+                    debug!("Skipping sweep of {}: liquidation in flight", signer.address());
+                    continue;
+                }
+                for sweep in treasury
+                    .sweep_wallet(self.provider.as_ref(), signer, tx_manager, &assets)
+                    .await
+                {
+                    if let Err(e) = database::record_treasury_sweep(
                         &self.db_pool,
-                        user_address,
-                        self.config.min_profit_threshold,
+                        &sweep,
+                        chrono::Utc::now().timestamp_millis(),
                     )
                     .await
                     {
-                        error!("Legacy liquidation handler also failed for priority liquidation: {}", legacy_err);
+                        warn!("Failed to record treasury sweep {}: {}", sweep.tx_hash, e);
                     }
+                    self.alert_notifier.spawn_notify(alerts::AlertEvent::TreasurySweep {
+                        wallet: sweep.wallet,
+                        symbol: sweep.symbol,
+                        amount: sweep.amount,
+                        cold_wallet: sweep.cold_wallet,
+                        tx_hash: sweep.tx_hash,
+                    });
                 }
             }
+        }
+    }
 
-            // Record ALL liquidation attempts (both successful and failed) for frequency monitoring
-            if let Err(e) = self
-                .circuit_breaker
-                .record_liquidation_attempt(liquidation_succeeded, current_gas_price)
+    /// Value the executor wallets' inventory against the target asset on an interval, and swap
+    /// assets whose share exceeds INVENTORY_MAX_DEVIATION_BPS into it through the collateral
+    /// swapper. Assets without a route to the target are only reported.
+    async fn run_inventory_tracker(&self) -> Result<()> {
+        let Some(inventory) = self.inventory.clone() else {
+            debug!("Inventory tracking disabled - INVENTORY_TARGET_ASSET not set");
+            return Ok(());
+        };
+
+        let assets: Vec<LiquidationAssetConfig> =
+            self.liquidation_assets.values().cloned().collect();
+        let Some(target) = assets
+            .iter()
+            .find(|asset| asset.symbol.to_uppercase() == inventory.target_symbol())
+            .map(|asset| asset.address)
+        else {
+            warn!(
+                "Inventory target {} is not a configured asset - inventory tracking disabled",
+                inventory.target_symbol()
+            );
+            return Ok(());
+        };
+
+        let mut interval =
+            tokio::time::interval(Duration::from_secs(self.config.inventory_check_interval_secs));
+        loop {
+            interval.tick().await;
+
+            let (report, holdings) = match inventory
+                .refresh(self.provider.as_ref(), &self.wallets.addresses(), &assets)
                 .await
             {
-                warn!(
-                    "Failed to record priority liquidation attempt for circuit breaker: {}",
-                    e
-                );
+                Ok(snapshot) => snapshot,
+                Err(e) => {
+                    warn!("Failed to value inventory: {}", e);
+                    continue;
+                }
+            };
+            for asset in &report.assets {
+                metrics::record_inventory_value(&asset.symbol, asset.value_base);
             }
 
-            // Record test liquidation if this was a half-open state test (determined before execution)
-            if is_test_liquidation && liquidation_succeeded {
-                self.circuit_breaker.record_test_liquidation();
-                info!(
-                    "📊 Recorded successful test liquidation (priority path, state was half-open before attempt) for user: {:?}",
-                    user_address
-                );
+            let busy: HashSet<Address> = self
+                .wallets
+                .status()
+                .into_iter()
+                .filter(|wallet| wallet.in_flight > 0)
+                .map(|wallet| wallet.address)
+                .collect();
+            for swap in inventory.plan_rebalance(&report, &holdings) {
+                if busy.contains(&swap.wallet) {
+                    debug!("Skipping rebalance of {}: liquidation in flight", swap.wallet);
+                    continue;
+                }
+                let Some((signer, tx_manager)) = self
+                    .wallets
+                    .senders()
+                    .find(|(signer, _)| signer.address() == swap.wallet)
+                else {
+                    continue;
+                };
+                let Some(swapper) = self.collateral_swapper.as_ref().filter(|swapper| {
+                    swapper
+                        .route(swap.token)
+                        .is_some_and(|route| route.token_out() == target)
+                }) else {
+                    metrics::record_inventory_rebalance(&swap.symbol, "no_route");
+                    debug!(
+                        "No swap route from {} to {} - {} stays over-exposed",
+                        swap.symbol,
+                        inventory.target_symbol(),
+                        swap.symbol
+                    );
+                    continue;
+                };
+                match swapper
+                    .swap_collateral(
+                        self.provider.as_ref(),
+                        signer,
+                        tx_manager,
+                        swap.token,
+                        swap.amount,
+                    )
+                    .await
+                {
+                    Ok(Some(outcome)) => {
+                        metrics::record_inventory_rebalance(&swap.symbol, "swapped");
+                        info!(
+                            "⚖️ Rebalanced {} {} of {} into {} (min out {}): {}",
+                            swap.amount,
+                            swap.symbol,
+                            swap.wallet,
+                            inventory.target_symbol(),
+                            outcome.min_amount_out,
+                            outcome.tx_hash
+                        );
+                    }
+                    Ok(None) => metrics::record_inventory_rebalance(&swap.symbol, "no_route"),
+                    Err(e) => {
+                        metrics::record_inventory_rebalance(&swap.symbol, "failed");
+                        warn!(
+                            "Failed to rebalance {} {} of {}: {}",
+                            swap.amount, swap.symbol, swap.wallet, e
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    /// Withdraw aTokens received from liquidations once they've been held for
+    /// A_TOKEN_UNWIND_AFTER_SECS, then swap the underlying like any other seized collateral
+    async fn run_a_token_unwinder(&self) -> Result<()> {
+        let Some(unwinder) = self.a_token_unwinder.clone() else {
+            debug!("aToken unwinding disabled");
+            return Ok(());
+        };
+
+        let hold_ms = (self.config.a_token_unwind_after_secs as i64).saturating_mul(1000);
+        let mut interval = tokio::time::interval(A_TOKEN_UNWIND_CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let now_ms = chrono::Utc::now().timestamp_millis();
+            let lots = match database::get_open_a_token_lots(&self.db_pool, now_ms - hold_ms).await
+            {
+                Ok(lots) => lots,
+                Err(e) => {
+                    warn!("Failed to load aToken lots: {}", e);
+                    continue;
+                }
+            };
+            for lot in lots {
+                let Some((signer, tx_manager)) = self
+                    .wallets
+                    .senders()
+                    .find(|(signer, _)| signer.address() == lot.wallet)
+                else {
+                    debug!("aToken lot {} belongs to unknown wallet {}", lot.id, lot.wallet);
+                    continue;
+                };
+                let unwind = unwinder
+                    .unwind(self.provider.as_ref(), signer, tx_manager, &lot)
+                    .await;
+                let unwind_tx_hash = match unwind {
+                    Ok(Some(unwind)) => {
+                        if let Some(swapper) = &self.collateral_swapper {
+                            if let Err(e) = swapper
+                                .swap_collateral(
+                                    self.provider.as_ref(),
+                                    signer,
+                                    tx_manager,
+                                    lot.asset,
+                                    unwind.amount,
+                                )
+                                .await
+                            {
+                                warn!(
+                                    "Failed to swap unwound {} of lot {}: {}",
+                                    lot.asset, lot.id, e
+                                );
+                            }
+                        }
+                        unwind.tx_hash
+                    }
+                    // Nothing left to withdraw; close the lot
+                    Ok(None) => String::new(),
+                    Err(e) => {
+                        warn!("Failed to unwind aToken lot {}: {}", lot.id, e);
+                        continue;
+                    }
+                };
+                if let Err(e) = database::mark_a_token_lot_unwound(
+                    &self.db_pool,
+                    lot.id,
+                    &unwind_tx_hash,
+                    chrono::Utc::now().timestamp_millis(),
+                )
+                .await
+                {
+                    warn!("Failed to close aToken lot {}: {}", lot.id, e);
+                }
             }
         }
-        
-        Ok(())
     }
 
-    /// Start periodic circuit breaker status reporting
+    /// Start periodic circuit breaker status reporting and trigger rule evaluation
     async fn run_circuit_breaker_status_reporter(&self) -> Result<()> {
         let circuit_breaker = self.circuit_breaker.clone();
         let mut interval = tokio::time::interval(Duration::from_secs(300)); // Report every 5 minutes
+        // Rules like oracle staleness must fire even when no new data is recorded
+        let mut rule_interval = tokio::time::interval(Duration::from_secs(15));
 
+        loop {
+            tokio::select! {
+                _ = interval.tick() => circuit_breaker.log_status(),
+                _ = rule_interval.tick() => {
+                    if let Err(e) = circuit_breaker.evaluate_rules().await {
+                        warn!("Failed to evaluate circuit breaker rules: {}", e);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Ping the external watchdog, if one is configured
+    async fn run_heartbeat(&self) -> Result<()> {
+        match &self.config.heartbeat_url {
+            Some(url) => {
+                heartbeat::start_heartbeat(
+                    self.provider.clone(),
+                    url.clone(),
+                    self.config.heartbeat_interval_secs,
+                    Duration::from_secs(self.config.heartbeat_stall_secs),
+                )
+                .await
+            }
+            None => {
+                debug!("Heartbeat disabled - HEARTBEAT_URL not configured");
+                Ok(())
+            }
+        }
+    }
+
+    /// Watch the pool's global pause state, if enabled
+    async fn run_pool_pause_monitor(&self) -> Result<()> {
+        if !self.config.pool_pause_check_enabled {
+            debug!("Pool pause monitoring disabled in configuration");
+            return Ok(());
+        }
+
+        pool_pause::run_pool_pause_monitor(
+            &self.pool_contract,
+            &self.db_pool,
+            self.pool_pause_guard.clone(),
+            self.config.pool_pause_check_interval_secs,
+        )
+        .await
+    }
+
+    /// Keep the reserve configuration cache current
+    async fn run_reserve_config_refresher(&self) -> Result<()> {
+        liquidation::reserve_config::run_reserve_config_refresher(
+            &self.pool_contract,
+            self.reserve_configs.clone(),
+            self.config.reserve_config_refresh_secs,
+        )
+        .await
+    }
+
+    /// Check price feeds for staleness and deviation, if enabled
+    async fn run_oracle_guard(&self) -> Result<()> {
+        if !self.config.oracle_guard_enabled {
+            debug!("Oracle guard disabled in configuration");
+            return Ok(());
+        }
+
+        oracle::run_oracle_guard(
+            self.provider.clone(),
+            self.config.network,
+            self.asset_configs.clone(),
+            self.config.oracle_secondary_feeds.clone(),
+            self.oracle_guard.clone(),
+            oracle::OracleGuardPolicy::from_config(&self.config),
+            self.db_pool.clone(),
+            self.alert_notifier.clone(),
+        )
+        .await
+    }
+
+    /// Downsample and prune recorded oracle prices, if price history is enabled
+    async fn run_price_history_maintenance(&self) -> Result<()> {
+        if !self.config.price_history_enabled {
+            debug!("Price history recording disabled in configuration");
+            return Ok(());
+        }
+
+        price_history::run_price_history_maintenance(
+            self.db_pool.clone(),
+            price_history::PriceHistoryPolicy::from_config(&self.config),
+        )
+        .await
+    }
+
+    /// Delete health factor snapshots older than the configured retention window
+    async fn run_position_history_retention(&self) -> Result<()> {
+        let retention_days = self.config.position_history_retention_days;
+        if retention_days == 0 {
+            debug!("Position history retention disabled - keeping every snapshot");
+            return Ok(());
+        }
+
+        let mut interval = tokio::time::interval(POSITION_HISTORY_PRUNE_INTERVAL);
         loop {
             interval.tick().await;
-            circuit_breaker.log_status();
+            let now = chrono::Utc::now().timestamp().max(0) as u64;
+            let cutoff = now.saturating_sub(retention_days * 86_400);
+            match database::prune_position_history(&self.db_pool, cutoff).await {
+                Ok(0) => debug!("Position history retention: nothing to prune"),
+                Ok(pruned) => info!(
+                    "🧹 Pruned {} position history snapshots older than {} days",
+                    pruned, retention_days
+                ),
+                Err(e) => error!("Position history pruning failed: {}", e),
+            }
+        }
+    }
+
+    /// Monitor and liquidate on the Compound V3 market, if one is configured
+    async fn run_compound_v3_monitor(&self) -> Result<()> {
+        let comet = match self.config.compound_v3_comet_address {
+            Some(address) => address,
+            None => {
+                debug!("Compound V3 monitoring disabled - COMPOUND_V3_COMET_ADDRESS not configured");
+                return Ok(());
+            }
+        };
+
+        let protocol: Arc<dyn protocols::Protocol> = Arc::new(
+            protocols::CompoundV3Protocol::new(self.provider.clone(), comet),
+        );
+        protocol_monitor::run_protocol_monitoring(
+            protocol,
+            protocol_monitor::ProtocolMonitorContext {
+                provider: self.provider.clone(),
+                db_pool: self.db_pool.clone(),
+                circuit_breaker: self.circuit_breaker.clone(),
+                signer: self.wallets.primary().clone(),
+                tx_manager: self.wallets.primary_tx_manager().clone(),
+                execution_mode: self.config.execution_mode,
+                broadcast: self.config.broadcasts_transactions(),
+                position_age_tracker: liquidation::PositionAgeTracker::new(
+                    self.config.min_position_age_blocks,
+                ),
+                oracle_guard: self.oracle_guard.clone(),
+                operator_balance_guard: self.operator_balance_guard.clone(),
+                max_gas_cost_wei: self.config.compound_v3_max_absorb_gas_cost_wei,
+                audit: self.config.liquidation_audit_enabled,
+                discovery_blocks: self.config.compound_v3_discovery_blocks,
+                interval_secs: self.config.periodic_scan_interval_secs,
+            },
+        )
+        .await
+    }
+
+    /// Hot-reload thresholds from the config file, if the configuration came from one
+    async fn run_config_reload(&self) -> Result<()> {
+        match &self.config.config_file {
+            Some(path) if self.config.config_reload_interval_secs > 0 => {
+                config_reload::run_config_watcher(
+                    path.into(),
+                    self.live_config.clone(),
+                    self.config.config_reload_interval_secs,
+                )
+                .await
+            }
+            _ => {
+                debug!("Config hot reload disabled - no config file or CONFIG_RELOAD_INTERVAL_SECS=0");
+                Ok(())
+            }
+        }
+    }
+
+    /// Serve Prometheus metrics and keep the sampled gauges fresh, if a listen address is configured
+    async fn run_metrics_exporter(&self) -> Result<()> {
+        let listen_addr = match &self.config.metrics_listen_addr {
+            Some(listen_addr) => listen_addr,
+            None => {
+                debug!("Metrics exporter disabled - METRICS_LISTEN_ADDR not configured");
+                return Ok(());
+            }
+        };
+
+        let bot_metrics = metrics::global().clone();
+        tokio::try_join!(
+            metrics::start_metrics_server(listen_addr, bot_metrics.clone()),
+            metrics::run_event_loop_lag_monitor(bot_metrics.clone()),
+            self.run_metrics_sampler(bot_metrics.clone()),
+        )?;
+        Ok(())
+    }
+
+    /// Periodically copy tracked-position counts and breaker state into the metrics gauges
+    async fn run_metrics_sampler(&self, bot_metrics: Arc<metrics::BotMetrics>) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(15)); // Sample every 15 seconds
+        loop {
+            interval.tick().await;
+
+            let (mut at_risk, mut liquidatable) = (0usize, 0usize);
+            for entry in self.user_positions.iter() {
+                if entry.value().is_at_risk {
+                    at_risk += 1;
+                }
+                if scanner::is_liquidatable(entry.value()) {
+                    liquidatable += 1;
+                }
+            }
+
+            bot_metrics.set_gauge(crate::metrics::POSITIONS_TRACKED, self.user_positions.len() as f64);
+            bot_metrics.set_gauge(crate::metrics::POSITIONS_AT_RISK, at_risk as f64);
+            bot_metrics.set_gauge(crate::metrics::POSITIONS_LIQUIDATABLE, liquidatable as f64);
+            bot_metrics.set_gauge(
+                crate::metrics::VALUE_AT_RISK,
+                self.value_at_risk.get().to_string().parse::<f64>().unwrap_or(0.0),
+            );
+            bot_metrics.set_gauge(
+                crate::metrics::CIRCUIT_BREAKER_STATE,
+                metrics::circuit_breaker_state_value(&self.circuit_breaker.get_state()),
+            );
+            for lane in EventLane::ALL {
+                bot_metrics.set_labelled_gauge(
+                    crate::metrics::EVENT_QUEUE_DEPTH,
+                    &[("lane", lane.as_str())],
+                    self.event_tx.depth(lane) as f64,
+                );
+            }
+            let queued_liquidations = [&self.priority_liquidation_tx, &self.queued_liquidation_tx]
+                .iter()
+                .map(|tx| tx.max_capacity() - tx.capacity())
+                .sum::<usize>();
+            bot_metrics.set_gauge(crate::metrics::LIQUIDATION_QUEUE_DEPTH, queued_liquidations as f64);
+
+            if self.scan_tiers.is_enabled() {
+                self.scan_tiers.prune(&self.user_positions);
+                for (tier, positions) in self.scan_tiers.tier_sizes(&self.user_positions) {
+                    bot_metrics.set_labelled_gauge(
+                        crate::metrics::SCAN_TIER_POSITIONS,
+                        &[("tier", &tier)],
+                        positions as f64,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Serve the admin API, if a listen address is configured
+    async fn run_admin_server(&self) -> Result<()> {
+        match &self.config.admin_listen_addr {
+            Some(listen_addr) => {
+                let mut state =
+                    admin::AdminState::new(self.config.admin_token.clone(), self.event_tx.clone())
+                        .with_user_positions(self.user_positions.clone())
+                        .with_circuit_breaker(self.circuit_breaker.clone())
+                        .with_wallets(self.wallets.clone());
+                if let Some(inventory) = &self.inventory {
+                    state = state.with_inventory(inventory.clone());
+                }
+                admin::start_admin_server(listen_addr, state).await
+            }
+            None => {
+                debug!("Admin API disabled - ADMIN_LISTEN_ADDR not configured");
+                Ok(())
+            }
+        }
+    }
+
+    /// Stream opportunities, liquidations and circuit breaker changes over gRPC, if a listen
+    /// address is configured
+    async fn run_grpc_server(&self) -> Result<()> {
+        match &self.config.grpc_listen_addr {
+            #[cfg(feature = "grpc")]
+            Some(listen_addr) => {
+                crate::grpc::start_grpc_server(
+                    listen_addr,
+                    self.config.chain_id,
+                    self.config.admin_token.clone(),
+                )
+                .await
+            }
+            #[cfg(not(feature = "grpc"))]
+            Some(_) => {
+                warn!("⚠️ GRPC_LISTEN_ADDR is set but this build lacks the grpc feature - gRPC feed disabled");
+                Ok(())
+            }
+            None => {
+                debug!("gRPC feed disabled - GRPC_LISTEN_ADDR not configured");
+                Ok(())
+            }
+        }
+    }
+
+    /// Post liquidatable positions and executed liquidations to the event webhook, if configured
+    async fn run_event_webhook(&self) -> Result<()> {
+        match &self.config.event_webhook_url {
+            Some(url) => {
+                let webhook = webhook_sink::EventWebhook::new(
+                    url.clone(),
+                    self.config.event_webhook_secret.clone(),
+                    self.config.event_webhook_max_retries,
+                )?;
+                info!(
+                    "📤 Posting liquidatable positions and executed liquidations to {} ({})",
+                    url,
+                    if self.config.event_webhook_secret.is_some() { "signed" } else { "unsigned" }
+                );
+                webhook_sink::run_event_webhook(Arc::new(webhook), self.config.chain_id).await
+            }
+            None => {
+                debug!("Event webhook disabled - EVENT_WEBHOOK_URL not configured");
+                Ok(())
+            }
+        }
+    }
+
+    /// Serve the operator web dashboard over the live positions and circuit breaker
+    async fn run_web_dashboard(&self) -> Result<()> {
+        match &self.config.dashboard_listen_addr {
+            Some(listen_addr) => {
+                let state = web_dashboard::WebDashboardState::new(self.db_pool.clone())
+                    .with_user_positions(self.user_positions.clone())
+                    .with_circuit_breaker(self.circuit_breaker.clone());
+                web_dashboard::start_web_dashboard(listen_addr, state).await
+            }
+            None => {
+                debug!("Web dashboard disabled - DASHBOARD_LISTEN_ADDR not configured");
+                Ok(())
+            }
         }
     }
     pub async fn new(
         provider: Arc<P>,
         config: BotConfig,
-        signer: PrivateKeySigner,
+        signers: Vec<BotSigner>,
     ) -> Result<Self> {
         // Load ABI of L2Pool from Hardhat artifact
         let artifact_str = include_str!("../abi/L2Pool.json");
         let artifact: HardhatArtifact = serde_json::from_str(artifact_str)?;
         let interface = Interface::new(artifact.abi);
 
-        // Aave V3 Pool address on Base mainnet
-        let pool_addr: Address = "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5".parse()?;
+        // Aave V3 Pool of the configured chain
+        let pool_addr = config.network.pool;
         let pool_contract = interface.connect(pool_addr, provider.clone());
+        let pool_version =
+            liquidation::pool_version::detect_pool_version(provider.as_ref(), pool_addr).await;
 
         // Try to create WebSocket provider for event monitoring
-        let ws_provider = match websocket::try_connect_websocket(&config.ws_url).await {
-            Ok(provider) => {
-                info!("✅ WebSocket connection established successfully!");
-                provider
-            }
-            Err(e) => {
-                info!("⚠️ WebSocket connection failed: {}", e);
-                info!("Falling back to HTTP provider for polling mode");
-                info!("To enable real-time monitoring, configure WS_URL with a proper WebSocket RPC endpoint");
-                provider.clone() as Arc<dyn Provider>
-            }
-        };
+        let ws_urls: Vec<String> = std::iter::once(config.ws_url.clone())
+            .chain(config.ws_fallback_urls.iter().cloned())
+            .collect();
+        let providers = ProviderSet::connect(
+            provider.clone(),
+            &ws_urls,
+            config.real_time_monitoring_enabled,
+        )
+        .await;
 
         // Initialize database
-        let db_pool = database::init_database(&config.database_url).await?;
+        let db_pool = database::init_database(&config.database_url, config.chain_id).await?;
 
         // For now, liquidator contract is optional
         let _liquidator_contract = None;
 
         // Create event channels for internal communication
-        let (event_tx, event_rx) = mpsc::unbounded_channel();
+        let (event_tx, event_rx) =
+            events::channel(config.liquidation_queue_capacity, config.event_queue_capacity);
         
         // Create high-priority liquidation channels
-        let (priority_liquidation_tx, priority_liquidation_rx) = mpsc::unbounded_channel();
+        let (priority_liquidation_tx, priority_liquidation_rx) =
+            mpsc::channel(config.liquidation_queue_capacity);
+        let (queued_liquidation_tx, queued_liquidation_rx) =
+            mpsc::channel(config.liquidation_queue_capacity);
 
-        // Initialize asset configurations for Base Sepolia
-        let asset_configs = oracle::init_asset_configs();
+        // Initialize the oracle feeds watched on this chain
+        let asset_configs = oracle::init_asset_configs(config.chain_id);
 
         // Initialize circuit breaker
-        let circuit_breaker = Arc::new(CircuitBreaker::new(config.clone()));
+        let alert_notifier = alerts::AlertNotifier::from_config(&config)?;
+        let providers = providers.with_alert_notifier(alert_notifier.clone());
+        if let Some(url) = &config.info_webhook_url {
+            info!("🔔 Info alerts will be posted to {}", url);
+        }
+        if let Some(url) = &config.critical_webhook_url {
+            info!("🔔 Warning/critical alerts will be posted to {}", url);
+        }
+
+        let gas_oracle = Arc::new(gas::GasOracle::new(
+            config.gas_fee_history_blocks,
+            config.gas_fee_profile,
+            config.gas_conservative_profit_multiple,
+            config.gas_aggressive_profit_multiple,
+        ));
+        match config.gas_fee_profile {
+            Some(profile) => info!(
+                "⛽ Bidding on every liquidation with the {} fee profile",
+                profile.as_str()
+            ),
+            None => info!(
+                "⛽ Fee profile by profit: conservative below {}x the minimum, aggressive from {}x",
+                config.gas_conservative_profit_multiple, config.gas_aggressive_profit_multiple
+            ),
+        }
+
+        let racing = Arc::new(liquidation::RacingStrategy::new(
+            config.racing_max_profit_share_bps,
+            config.racing_win_rate_window,
+        ));
+        if racing.is_enabled() {
+            info!(
+                "🏁 Racing liquidations with up to {} bps of expected profit in priority fees",
+                config.racing_max_profit_share_bps
+            );
+        }
+
+        let live_config = config::shared(config.clone());
+        let circuit_breaker = Arc::new(
+            CircuitBreaker::from_shared_config(live_config.clone())
+                .with_alert_notifier(alert_notifier.clone())
+                .with_gas_oracle(gas_oracle.clone())
+                .with_state_store(db_pool.clone()),
+        );
+
+        let profit_recheck = Arc::new(
+            liquidation::ProfitRecheckMonitor::new(config.realized_profit_alert_bps)
+                .with_alert_notifier(alert_notifier.clone()),
+        );
+        if profit_recheck.is_enabled() {
+            info!(
+                "📉 Alerting when realized profit is repeatedly below {} bps of the estimate",
+                config.realized_profit_alert_bps
+            );
+        }
+
+        // Track when users are first observed so brand-new positions aren't liquidated
+        let position_age_tracker = Arc::new(liquidation::PositionAgeTracker::new(
+            config.min_position_age_blocks,
+        ));
+        if position_age_tracker.is_enabled() {
+            info!(
+                "⏳ Positions must be observed for {} block(s) before liquidation",
+                config.min_position_age_blocks
+            );
+        }
 
-        // Initialize liquidation asset configurations based on configuration
+        // Initialize liquidation asset configurations based on configuration. The hardcoded
+        // fallback only exists for Base mainnet; other chains fail startup instead of running
+        // with Base's assets.
+        let chain_id = config.chain_id;
         let liquidation_assets = match &config.asset_loading_method {
             AssetLoadingMethod::FullyDynamic => {
                 info!("🔄 Loading all assets dynamically from Aave protocol...");
-                match liquidation::assets::init_assets_from_protocol(&*provider).await {
+                match liquidation::assets::init_assets_from_protocol(&*provider, &config.network).await {
                     Ok(assets) => {
                         info!(
                             "✅ Successfully loaded {} assets dynamically from Aave protocol",
@@ -270,13 +1556,16 @@ where
                             e
                         );
                         error!("🔄 Falling back to hardcoded asset configurations");
-                        liquidation::assets::init_base_mainnet_assets()
+                        liquidation::assets::hardcoded_assets(chain_id)?
                     }
                 }
             }
             AssetLoadingMethod::FromFile(file_path) => {
                 info!("📁 Loading assets from config file: {}", file_path);
-                match liquidation::assets::init_assets_from_file(&*provider, file_path).await {
+                let assets =
+                    liquidation::assets::init_assets_from_file(&*provider, &config.network, file_path)
+                        .await;
+                match assets {
                     Ok(assets) => {
                         info!(
                             "✅ Successfully loaded {} assets from config file",
@@ -287,7 +1576,7 @@ where
                     Err(e) => {
                         error!("❌ Failed to load assets from config file: {}", e);
                         error!("🔄 Falling back to hardcoded asset configurations");
-                        liquidation::assets::init_base_mainnet_assets()
+                        liquidation::assets::hardcoded_assets(chain_id)?
                     }
                 }
             }
@@ -295,11 +1584,13 @@ where
                 info!("🔧 Using hardcoded asset configurations");
                 warn!("⚠️  IMPORTANT: Using hardcoded asset configurations");
                 warn!("⚠️  Asset IDs may become incorrect if Aave's reserve list changes!");
-                liquidation::assets::init_base_mainnet_assets()
+                liquidation::assets::hardcoded_assets(chain_id)?
             }
             AssetLoadingMethod::DynamicWithFallback => {
                 info!("🔄 Loading assets with dynamic metadata and fallback support...");
-                match liquidation::assets::init_base_mainnet_assets_async(&*provider).await {
+                match liquidation::assets::init_assets_dynamic(&*provider, &config.network, chain_id)
+                    .await
+                {
                     Ok(assets) => {
                         info!("✅ Successfully loaded asset configurations with dynamic data from Aave protocol");
                         assets
@@ -312,11 +1603,12 @@ where
                         warn!("⚠️  if Aave's reserve list ordering changes over time!");
                         warn!("🔍 To fix this issue:");
                         warn!(
-                            "   1. Verify the correct Aave V3 contract addresses for Base mainnet"
+                            "   1. Verify the correct Aave V3 contract addresses for {}",
+                            config.chain_name
                         );
-                        warn!("   2. Update BASE_POOL_ADDRESSES_PROVIDER and BASE_UI_POOL_DATA_PROVIDER");
-                        warn!("   3. Check if Aave V3 is actually deployed on Base network");
-                        liquidation::assets::init_base_mainnet_assets()
+                        warn!("   2. Update POOL_ADDRESS and DATA_PROVIDER_ADDRESS");
+                        warn!("   3. Check if Aave V3 is actually deployed on this network");
+                        liquidation::assets::hardcoded_assets(chain_id)?
                     }
                 }
             }
@@ -325,220 +1617,878 @@ where
         // Get liquidator contract address from config
         let liquidator_contract_address = config.liquidator_contract;
 
-        if let Some(addr) = liquidator_contract_address {
-            info!("✅ Liquidator contract configured at: {:?}", addr);
-        } else {
-            warn!("⚠️ Liquidator contract not configured - liquidation execution will be disabled");
+        if let Some(addr) = liquidator_contract_address {
+            info!("✅ Liquidator contract configured at: {:?}", addr);
+        } else {
+            warn!("⚠️ Liquidator contract not configured - liquidation execution will be disabled");
+        }
+
+        let operator_balance_guard = Arc::new(operator_balance::OperatorBalanceGuard::new(
+            config.min_operator_balance_wei,
+            config.operator_balance_halt_enabled,
+        ));
+
+        let event_recorder = match &config.event_record_path {
+            Some(path) => match replay::EventRecorder::create(Path::new(path)) {
+                Ok(recorder) => {
+                    info!("📼 Recording logs and events to {}", path);
+                    Some(Arc::new(recorder))
+                }
+                Err(e) => {
+                    warn!("Failed to open event recording {}: {}. Recording disabled.", path, e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let event_bus = match &config.event_bus_url {
+            Some(url) => match event_bus::EventBus::connect(
+                url,
+                config.chain_id,
+                config.event_bus_events_topic.clone(),
+                config.event_bus_executions_topic.clone(),
+            )
+            .await
+            {
+                Ok(bus) => Some(Arc::new(bus)),
+                Err(e) => {
+                    warn!("Failed to connect to the message bus: {}. Publishing disabled.", e);
+                    None
+                }
+            },
+            None => None,
+        };
+
+        let opportunity_webhook = match &config.opportunity_webhook_url {
+            Some(url) => {
+                info!(
+                    "📤 Forwarding liquidation opportunities to {} (local execution: {})",
+                    url, config.opportunity_webhook_execute
+                );
+                Some(liquidation::OpportunityWebhook::new(
+                    url.clone(),
+                    config.opportunity_webhook_execute,
+                )?)
+            }
+            None => None,
+        };
+
+        let private_relay = match (config.submission_strategy, &config.private_relay_url) {
+            (SubmissionStrategy::Public, _) | (_, None) => None,
+            (strategy, Some(url)) => {
+                info!(
+                    "🔒 Submitting liquidations via private relay {} (strategy: {}, resubmit for up to {} blocks)",
+                    url,
+                    strategy.as_str(),
+                    config.private_relay_max_blocks
+                );
+                Some(Arc::new(liquidation::PrivateRelaySubmitter::new(
+                    url.clone(),
+                    strategy,
+                    config.private_relay_max_blocks,
+                )?))
+            }
+        };
+
+        let swap_aggregator = match config.swap_aggregator {
+            Some(kind) => {
+                info!(
+                    "🔁 Building collateral swaps with the {} API ({} bps slippage)",
+                    kind.as_str(),
+                    config.collateral_swap_slippage_bps
+                );
+                Some(Arc::new(liquidation::AggregatorClient::new(
+                    kind,
+                    config.chain_id,
+                    config.swap_aggregator_api_url.clone(),
+                    config.swap_aggregator_api_key.clone(),
+                    config.collateral_swap_slippage_bps,
+                    config.swap_quote_cache_secs,
+                )?))
+            }
+            None => None,
+        };
+
+        let collateral_swapper = if config.collateral_swap_routes.is_empty() {
+            None
+        } else {
+            info!(
+                "🔁 Converting received collateral via {} Uniswap V3 route(s) ({} bps slippage)",
+                config.collateral_swap_routes.len(),
+                config.collateral_swap_slippage_bps
+            );
+            let mut swapper = liquidation::CollateralSwapper::new(
+                &config.collateral_swap_routes,
+                config.collateral_swap_slippage_bps,
+                config.network,
+                config.broadcasts_transactions(),
+            )
+            .with_approval_mode(config.swap_approval_mode);
+            if let Some(aggregator) = &swap_aggregator {
+                swapper = swapper.with_aggregator(aggregator.clone());
+            }
+            Some(Arc::new(swapper))
+        };
+
+        let weth_balancer = config.weth_gas_target_wei.map(|gas_target_wei| {
+            info!(
+                "⛽ Unwrapping WETH to keep executor wallets at {} wei of gas{}",
+                gas_target_wei,
+                config
+                    .weth_wrap_above_wei
+                    .map(|ceiling| format!(", wrapping native ETH above {} wei", ceiling))
+                    .unwrap_or_default()
+            );
+            Arc::new(liquidation::WethBalancer::new(
+                config.network.weth,
+                liquidation::WethPolicy {
+                    gas_target_wei,
+                    wrap_above_wei: config.weth_wrap_above_wei,
+                },
+                config.broadcasts_transactions(),
+            ))
+        });
+
+        let wallet_funding = config.wallet_funded_liquidations_enabled.then(|| {
+            info!("💳 Paying liquidations from executor wallet balances when cheaper than a flash loan");
+            Arc::new(liquidation::WalletFunding::new(
+                config.network.pool,
+                config.min_token_balances.clone(),
+                config.swap_approval_mode,
+            ))
+        });
+
+        let a_token_unwinder = (config.liquidation_receive_a_token
+            && config.a_token_unwind_after_secs > 0)
+            .then(|| {
+                info!(
+                    "🪙 Unwinding aTokens received from liquidations after {}s",
+                    config.a_token_unwind_after_secs
+                );
+                Arc::new(liquidation::ATokenUnwinder::new(
+                    config.network.pool,
+                    config.network.data_provider,
+                    config.broadcasts_transactions(),
+                ))
+            });
+
+        let treasury = config.treasury_cold_wallet.map(|cold_wallet| {
+            info!(
+                "🏦 Sweeping executor wallet profits to {} above {:?}, keeping {} wei for gas",
+                cold_wallet, config.treasury_sweep_thresholds, config.treasury_gas_reserve_wei
+            );
+            Arc::new(treasury::Treasury::new(
+                cold_wallet,
+                config.treasury_sweep_thresholds.clone(),
+                config.min_token_balances.clone(),
+                config.treasury_gas_reserve_wei,
+                config.broadcasts_transactions(),
+            ))
+        });
+
+        let inventory = config.inventory_target_asset.clone().map(|target| {
+            info!(
+                "📦 Tracking executor wallet inventory against {}, rebalancing assets above {}bps",
+                target, config.inventory_max_deviation_bps
+            );
+            Arc::new(inventory::InventoryTracker::new(
+                target,
+                config.inventory_max_deviation_bps,
+                config.min_token_balances.clone(),
+                config.network,
+            ))
+        });
+
+        let flash_loan_providers = liquidation::build_flash_loan_providers(
+            provider.clone(),
+            &config.network,
+            &config.flash_loan_sources,
+        )?;
+        info!(
+            "⚡ Flash loan lenders: {}",
+            config
+                .flash_loan_sources
+                .iter()
+                .map(|source| source.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        );
+
+        let simulator = match config.simulation_mode {
+            // Dry runs are only meaningful if the transaction would not have reverted
+            SimulationMode::Off if config.execution_mode == ExecutionMode::DryRun => {
+                info!("🧪 Dry run: simulating liquidations with eth_call");
+                Some(Arc::new(sim::Simulator::eth_call()))
+            }
+            SimulationMode::Off => None,
+            SimulationMode::EthCall => {
+                info!("🧪 Simulating liquidations with eth_call before broadcasting");
+                Some(Arc::new(sim::Simulator::eth_call()))
+            }
+            SimulationMode::AnvilFork => {
+                let fork = sim::AnvilFork::spawn(
+                    &config.anvil_path,
+                    &config.rpc_url,
+                    config.anvil_port,
+                )
+                .await?;
+                info!("🧪 Simulating liquidations on an Anvil fork before broadcasting");
+                Some(Arc::new(sim::Simulator::anvil(fork)))
+            }
+        };
+
+        // Each executor wallet gets its own nonce sequence and stuck transaction replacement
+        let wallets = Arc::new(liquidation::WalletPool::new(
+            signers,
+            liquidation::EscalationPolicy {
+                stuck_after: Duration::from_secs(config.tx_stuck_after_secs),
+                bump_pct: config.tx_fee_bump_pct,
+                max_bumps: config.tx_max_fee_bumps,
+            },
+            config.wallet_gas_reserve_wei,
+        ));
+
+        info!(
+            "✅ Bot initialized with {} executor wallet(s) for transaction signing",
+            wallets.len()
+        );
+        if config.execution_mode != ExecutionMode::Live {
+            warn!(
+                "📝 Execution mode is {} - liquidations will not be broadcast",
+                config.execution_mode.as_str()
+            );
         }
 
-        info!("✅ Bot initialized with signer for transaction signing capability");
+        // Built before `config` moves into the bot
+        let refresh_throttle = refresh_throttle::RefreshThrottle::new(Duration::from_secs(
+            config.user_refresh_min_interval_secs,
+        ));
+        let scan_tiers = Arc::new(scan_tiers::TierSchedule::new(config.scan_tiers.clone()));
+        let health_engine = config
+            .local_health_factor_enabled
+            .then(|| Arc::new(health_engine::HealthFactorEngine::new(config.network)));
+        let retry_policy = liquidation::RetryPolicy::new(
+            config.liquidation_retry_max_attempts,
+            Duration::from_millis(config.liquidation_retry_base_delay_ms),
+            Duration::from_millis(config.liquidation_retry_max_delay_ms),
+        );
+
+        let competitor_tracker = Arc::new(mempool::CompetitorTracker::new());
+        let mut liquidation_ctx = liquidation::LiquidationContext::new(
+            provider.clone(),
+            db_pool.clone(),
+            pool_contract.clone(),
+            pool_version,
+            config.network,
+            config.execution_mode,
+            config.profitability_model,
+        )
+        .with_strategy_label(config.strategy_label.clone())
+        .with_flash_loan_providers(flash_loan_providers)
+        .with_circuit_breaker(circuit_breaker.clone())
+        .with_gas_oracle(gas_oracle.clone())
+        .with_competitor_tracker(competitor_tracker.clone())
+        .with_alert_notifier(alert_notifier.clone());
+        if let Some(address) = liquidator_contract_address {
+            liquidation_ctx = liquidation_ctx.with_liquidator_contract(address);
+        }
+        if config.broadcasts_transactions() {
+            liquidation_ctx = liquidation_ctx.with_broadcast();
+        }
+        if let Some(model) = config.shadow_profitability {
+            liquidation_ctx = liquidation_ctx
+                .with_shadow_profitability(model, config.shadow_divergence_threshold_bps);
+        }
+        if let Some(margin_bps) = config.onchain_min_profit_margin_bps {
+            liquidation_ctx = liquidation_ctx.with_min_profit_guard(margin_bps);
+        }
+        if config.liquidation_audit_enabled {
+            liquidation_ctx = liquidation_ctx.with_audit_log();
+        }
+        if config.liquidation_receive_a_token {
+            liquidation_ctx = liquidation_ctx.with_receive_a_token();
+        }
+        if let Some(webhook) = opportunity_webhook {
+            liquidation_ctx = liquidation_ctx.with_opportunity_webhook(webhook);
+        }
+        if profit_recheck.is_enabled() {
+            liquidation_ctx = liquidation_ctx.with_profit_recheck(profit_recheck);
+        }
+        if let Some(relay) = private_relay {
+            liquidation_ctx = liquidation_ctx.with_private_relay(relay);
+        }
+        if let Some(swapper) = &collateral_swapper {
+            liquidation_ctx = liquidation_ctx.with_collateral_swap(swapper.clone());
+        }
+        if let Some(aggregator) = swap_aggregator {
+            liquidation_ctx = liquidation_ctx.with_swap_aggregator(aggregator);
+        }
+        if let Some(balancer) = weth_balancer {
+            liquidation_ctx = liquidation_ctx.with_weth_balancer(balancer);
+        }
+        if let Some(funding) = wallet_funding {
+            liquidation_ctx = liquidation_ctx.with_wallet_funding(funding);
+        }
+        if let Some(simulator) = simulator {
+            liquidation_ctx = liquidation_ctx.with_simulator(simulator);
+        }
+        if racing.is_enabled() {
+            liquidation_ctx = liquidation_ctx.with_racing(racing);
+        }
 
         Ok(Self {
             provider,
-            ws_provider,
-            signer,
+            providers,
+            wallets,
             config,
+            live_config,
             pool_contract,
+            pool_version,
             _liquidator_contract,
             db_pool,
             user_positions: Arc::new(DashMap::new()),
+            persisted_positions: Arc::new(scanner::PersistedPositions::default()),
             processing_users: Arc::new(SyncRwLock::new(HashSet::new())),
+            refresh_throttle,
+            scan_tiers,
             event_tx,
             event_rx: Arc::new(tokio::sync::Mutex::new(event_rx)),
             priority_liquidation_tx,
             priority_liquidation_rx: Arc::new(tokio::sync::Mutex::new(priority_liquidation_rx)),
+            queued_liquidation_tx,
+            queued_liquidation_rx: Arc::new(tokio::sync::Mutex::new(queued_liquidation_rx)),
             // Oracle price monitoring
             price_feeds: Arc::new(DashMap::new()),
             asset_configs,
             users_by_collateral: Arc::new(DashMap::new()),
+            users_by_debt: Arc::new(DashMap::new()),
+            health_engine,
+            price_triggers: Arc::new(price_trigger::PriceTriggerIndex::new()),
             // Liquidation functionality
             liquidation_assets,
+            reserve_configs: Arc::new(liquidation::ReserveConfigCache::new()),
+            emode: Arc::new(liquidation::EModeTracker::new()),
             liquidator_contract_address,
             circuit_breaker,
+            position_age_tracker,
+            pool_pause_guard: Arc::new(pool_pause::PoolPauseGuard::new()),
+            oracle_guard: Arc::new(oracle::OracleGuard::new()),
+            value_at_risk: Arc::new(value_at_risk::ValueAtRiskGauge::new()),
+            operator_balance_guard,
+            event_recorder,
+            event_bus,
+            liquidation_ctx,
+            alert_notifier,
+            liquidatable_alerted: DashMap::new(),
+            consecutive_liquidation_failures: AtomicU64::new(0),
+            liquidating_users: liquidation::UserLocks::new(),
+            retry_policy,
+            failed_attempts: DashMap::new(),
+            collateral_swapper,
+            treasury,
+            treasury_wake: tokio::sync::Notify::new(),
+            inventory,
+            a_token_unwinder,
+            gas_oracle,
+            competitor_tracker,
+            shutdown: ShutdownCoordinator::new(),
         })
     }
 
+    /// Handle bus events. Position refreshes run concurrently, up to HEALTH_CHECK_PARALLELISM
+    /// at once, so one slow RPC call doesn't hold up the rest; every other event is handled in
+    /// order while the refreshes already started keep running.
     async fn run_event_processor(&self) -> Result<()> {
-        info!("Starting event processor...");
+        let parallelism = self.config.health_check_parallelism.max(1);
+        info!("Starting event processor ({} concurrent health checks)...", parallelism);
 
         let mut event_rx = self.event_rx.lock().await;
+        let mut refreshes = FuturesUnordered::new();
 
-        while let Some(event) = event_rx.recv().await {
-            match event {
-                BotEvent::UserPositionChanged(user) => {
-                    debug!(
-                        "🔍 Processing UserPositionChanged event for user: {:?}",
-                        user
-                    );
-                    if let Err(e) = scanner::update_user_position(
-                        self.provider.clone(),
-                        &self.pool_contract,
-                        &self.db_pool,
-                        self.user_positions.clone(),
-                        self.processing_users.clone(),
-                        self.event_tx.clone(),
-                        self.config.health_factor_threshold,
-                        user,
-                        Some(self.users_by_collateral.clone()),
-                        Some(&self.asset_configs),
-                        None, // No priority channel for regular event processing to avoid double-processing
-                    )
-                    .await
-                    {
-                        error!("Failed to update user position for {:?}: {}", user, e);
-                    } else {
-                        debug!("✅ Completed health check for user: {:?}", user);
-                    }
+        loop {
+            let event = tokio::select! {
+                event = event_rx.recv(), if refreshes.len() < parallelism => match event {
+                    Some(event) => event,
+                    None => break,
+                },
+                Some(()) = refreshes.next(), if !refreshes.is_empty() => continue,
+            };
+            if let Some(recorder) = &self.event_recorder {
+                // Whatever raised the opportunity, the position it was decided on comes first
+                if let BotEvent::LiquidationOpportunity(user) = event {
+                    self.record_position_read(user);
                 }
-                BotEvent::LiquidationOpportunity(user) => {
-                    // Check circuit breaker before processing liquidation
-                    // IMPORTANT: Capture state BEFORE liquidation to avoid TOCTOU bug
-                    let circuit_breaker_state_before = self.circuit_breaker.get_state();
+                recorder.record_event(&event);
+            }
+            if let Some(bus) = &self.event_bus {
+                bus.publish_event(&event);
+            }
 
-                    if !self.circuit_breaker.is_liquidation_allowed() {
-                        warn!(
-                            "🚫 Liquidation blocked by circuit breaker (state: {:?}) for user: {:?}",
-                            circuit_breaker_state_before, user
-                        );
-                        self.circuit_breaker.record_blocked_liquidation();
+            if matches!(event, BotEvent::UserPositionChanged(_)) {
+                refreshes.push(self.process_event(event));
+                continue;
+            }
+            let handled = self.process_event(event);
+            tokio::pin!(handled);
+            loop {
+                tokio::select! {
+                    () = &mut handled => break,
+                    Some(()) = refreshes.next(), if !refreshes.is_empty() => {}
+                }
+            }
+        }
 
-                        // Record the blocked attempt for frequency monitoring
-                        if let Err(e) = self
-                            .circuit_breaker
-                            .record_liquidation_attempt(false, None)
-                            .await
-                        {
-                            warn!("Failed to record blocked liquidation attempt: {}", e);
-                        }
-                        continue;
+        // Let the refreshes already started finish
+        while refreshes.next().await.is_some() {}
+        Ok(())
+    }
+
+    /// Record the position `user` was last read at, so a replay can reproduce the decision
+    /// made on it without reading the chain
+    fn record_position_read(&self, user: Address) {
+        if let (Some(recorder), Some(position)) =
+            (&self.event_recorder, self.user_positions.get(&user))
+        {
+            recorder.record(&replay::RecordedEntry::PositionRead {
+                position: position.clone(),
+            });
+        }
+    }
+
+    async fn process_event(&self, event: BotEvent) {
+        match event {
+            BotEvent::UserPositionChanged(user) => {
+                // Users at risk (or not tracked yet) are always read straight away
+                let near_liquidation = self
+                    .user_positions
+                    .get(&user)
+                    .map_or(true, |position| position.is_at_risk);
+                match self.refresh_throttle.check(user, near_liquidation) {
+                    refresh_throttle::RefreshDecision::Refresh => {}
+                    refresh_throttle::RefreshDecision::Defer(delay) => {
+                        metrics::record_refresh_throttled();
+                        let event_tx = self.event_tx.clone();
+                        tokio::spawn(async move {
+                            tokio::time::sleep(delay).await;
+                            let _ = event_tx.send(BotEvent::UserPositionChanged(user));
+                        });
+                        return;
+                    }
+                    refresh_throttle::RefreshDecision::Skip => {
+                        metrics::record_refresh_throttled();
+                        return;
                     }
+                }
 
-                    info!("🎯 Processing liquidation opportunity for user: {:?}", user);
+                debug!(
+                    "🔍 Processing UserPositionChanged event for user: {:?}",
+                    user
+                );
+                self.record_position_observation(user).await;
+                if let Err(e) = scanner::update_user_position(
+                    self.provider.clone(),
+                    &self.pool_contract,
+                    &self.db_pool,
+                    self.user_positions.clone(),
+                    &self.persisted_positions,
+                    self.processing_users.clone(),
+                    self.event_tx.clone(),
+                    self.live_config.load().health_factor_threshold,
+                    self.live_config.load().min_hf_change_to_persist_bps,
+                    user,
+                    Some(self.users_by_collateral.clone()),
+                    Some(self.users_by_debt.clone()),
+                    Some(&self.asset_configs),
+                    None, // No priority channel for regular event processing to avoid double-processing
+                )
+                .await
+                {
+                    error!("Failed to update user position for {:?}: {}", user, e);
+                } else {
+                    debug!("✅ Completed health check for user: {:?}", user);
+                    self.record_position_read(user);
+                    self.refresh_price_triggers(user);
+                    self.refresh_user_reserves(user).await;
+                }
+            }
+            BotEvent::LiquidationOpportunity(user) => {
+                self.notify_liquidatable_user(user);
 
-                    // Determine if this is a test liquidation based on state BEFORE execution
-                    let is_test_liquidation = circuit_breaker_state_before
-                        == crate::circuit_breaker::CircuitBreakerState::HalfOpen;
+                let Some(_in_flight) = self.shutdown.begin_liquidation() else {
+                    info!("🛑 Skipping liquidation for user {:?} - shutting down", user);
+                    return;
+                };
 
-                    // Get current gas price for circuit breaker monitoring
-                    let current_gas_price = match self.provider.get_gas_price().await {
-                        Ok(price) => Some(alloy_primitives::U256::from(price)),
-                        Err(e) => {
-                            warn!("Failed to get current gas price: {}", e);
-                            None
-                        }
-                    };
+                let Some(_user_lock) = self.liquidating_users.try_lock(user) else {
+                    info!(
+                        "🔒 Skipping liquidation for user {:?} - already being liquidated",
+                        user
+                    );
+                    return;
+                };
 
-                    // Execute liquidation first, then record success/failure
-                    let liquidation_result = liquidation::handle_liquidation_opportunity(
-                        self.provider.clone(),
-                        &self.db_pool,
-                        user,
-                        self.config.min_profit_threshold,
-                        self.liquidator_contract_address,
-                        Some(self.signer.clone()),
-                        &self.pool_contract,
-                        &self.liquidation_assets,
-                        &self.config.rpc_url,
-                    )
-                    .await;
+                if !self.is_position_old_enough(user).await {
+                    info!(
+                        "⏳ Skipping liquidation for user {:?} - position younger than {} block(s)",
+                        user, self.config.min_position_age_blocks
+                    );
+                    return;
+                }
 
-                    let liquidation_succeeded = matches!(liquidation_result, Ok(LiquidationResult::Executed(_)));
+                if !self.pool_pause_guard.is_liquidation_allowed() {
+                    warn!(
+                        "⏸️ Liquidation suspended for user {:?} - pool is paused by governance",
+                        user
+                    );
+                    return;
+                }
 
-                    // Handle liquidation failure with fallback
-                    match &liquidation_result {
-                        Ok(LiquidationResult::Executed(tx_hash)) => {
-                            info!("✅ Liquidation executed successfully for user: {:?}, TX: {}", user, tx_hash);
-                        }
-                        Ok(LiquidationResult::NotNeeded(reason)) => {
-                            info!("ℹ️ Liquidation not needed for user: {:?}, reason: {:?}", user, reason);
-                        }
-                        Ok(LiquidationResult::Failed(error)) => {
-                            warn!("❌ Liquidation failed for user: {:?}, error: {}", user, error);
-                        }
-                        Err(e) => {
-                            error!(
-                                "Failed to handle liquidation opportunity for {:?}: {}",
-                                user, e
-                            );
+                if !self.oracle_guard.is_liquidation_allowed() {
+                    warn!(
+                        "🔮 Liquidation suspended for user {:?} - a price feed failed its checks",
+                        user
+                    );
+                    return;
+                }
 
-                            // Fallback to legacy handler for logging
-                            if let Err(legacy_err) = liquidation::handle_liquidation_opportunity_legacy(
-                                &self.db_pool,
-                                user,
-                                self.config.min_profit_threshold,
-                            )
-                            .await
-                            {
-                                error!("Legacy liquidation handler also failed: {}", legacy_err);
-                            }
-                        }
-                    }
+                if !self.operator_balance_guard.is_liquidation_allowed() {
+                    warn!(
+                        "⛽ Liquidation halted for user {:?} - operator balance below minimum",
+                        user
+                    );
+                    return;
+                }
 
-                    // Record ALL liquidation attempts (both successful and failed) for frequency monitoring
+                // Check circuit breaker before processing liquidation
+                // IMPORTANT: Capture state BEFORE liquidation to avoid TOCTOU bug
+                let circuit_breaker_state_before = self.circuit_breaker.get_state();
+
+                if !self.circuit_breaker.is_liquidation_allowed() {
+                    warn!(
+                        "🚫 Liquidation blocked by circuit breaker (state: {:?}) for user: {:?}",
+                        circuit_breaker_state_before, user
+                    );
+                    self.circuit_breaker.record_blocked_liquidation();
+
+                    // Record the blocked attempt for frequency monitoring
                     if let Err(e) = self
                         .circuit_breaker
-                        .record_liquidation_attempt(liquidation_succeeded, current_gas_price)
+                        .record_liquidation_attempt(false, None)
                         .await
                     {
-                        warn!(
-                            "Failed to record liquidation attempt for circuit breaker: {}",
-                            e
-                        );
+                        warn!("Failed to record blocked liquidation attempt: {}", e);
+                    }
+                    return;
+                }
+
+                if self.wallets.pending_liquidation(user).is_some() {
+                    // Left to the retry, which replaces it on the same nonce
+                    info!(
+                        "⏳ Liquidation skipped for user {:?} - an earlier one is still pending",
+                        user
+                    );
+                    return;
+                }
+
+                info!("🎯 Processing liquidation opportunity for user: {:?}", user);
+
+                // Determine if this is a test liquidation based on state BEFORE execution
+                let is_test_liquidation = circuit_breaker_state_before
+                    == crate::circuit_breaker::CircuitBreakerState::HalfOpen;
+
+                // Get current gas price for circuit breaker monitoring
+                let current_gas_price = match self.provider.get_gas_price().await {
+                    Ok(price) => Some(alloy_primitives::U256::from(price)),
+                    Err(e) => {
+                        warn!("Failed to get current gas price: {}", e);
+                        None
                     }
+                };
 
-                    // Record test liquidation if this was a half-open state test (determined before execution)
-                    if is_test_liquidation && liquidation_succeeded {
-                        self.circuit_breaker.record_test_liquidation();
+                let Some(wallet) = self.wallets.acquire() else {
+                    warn!(
+                        "⛽ Liquidation skipped for user {:?} - no executor wallet above the gas reserve",
+                        user
+                    );
+                    return;
+                };
+
+                // Execute liquidation first, then record success/failure
+                let liquidation_result = liquidation::handle_liquidation_opportunity(
+                    &self.liquidation_ctx,
+                    user,
+                    Some(wallet.signer().clone()),
+                    Some(wallet.tx_manager()),
+                    &self.liquidation_assets_for(user).await,
+                    self.liquidation_thresholds(),
+                )
+                .await;
+
+                let liquidation_succeeded = matches!(liquidation_result, Ok(LiquidationResult::Executed(_)));
+
+                // Handle liquidation failure with fallback
+                match &liquidation_result {
+                    Ok(LiquidationResult::Executed(tx_hash)) => {
+                        wallet.record_executed();
+                        self.treasury_wake.notify_one();
                         info!(
-                            "📊 Recorded successful test liquidation (state was half-open before attempt) for user: {:?}",
-                            user
+                            "✅ Liquidation executed successfully for user: {:?}, TX: {} (wallet {})",
+                            user, tx_hash, wallet.address()
                         );
                     }
-                }
-                BotEvent::PriceUpdate(asset, _old_price, _new_price) => {
-                    debug!("Price update detected for asset: {:?}", asset);
-                    // Could trigger a broader scan of users holding this asset
-                }
-                BotEvent::DatabaseSync(positions) => {
-                    debug!("Database sync requested for {} positions", positions.len());
-                    for position in positions {
-                        if let Err(e) = database::save_user_position(&self.db_pool, &position).await
+                    Ok(LiquidationResult::NotNeeded(reason)) => {
+                        info!("ℹ️ Liquidation not needed for user: {:?}, reason: {:?}", user, reason);
+                    }
+                    Ok(LiquidationResult::Failed(error)) => {
+                        warn!("❌ Liquidation failed for user: {:?}, error: {}", user, error);
+                    }
+                    Err(e) => {
+                        error!(
+                            "Failed to handle liquidation opportunity for {:?}: {}",
+                            user, e
+                        );
+
+                        // Fallback to legacy handler for logging
+                        if let Err(legacy_err) = liquidation::handle_liquidation_opportunity_legacy(
+                            &self.db_pool,
+                            user,
+                            self.live_config.load().min_profit_threshold,
+                        )
+                        .await
                         {
-                            error!("Failed to sync position for {:?}: {}", position.address, e);
+                            error!("Legacy liquidation handler also failed: {}", legacy_err);
                         }
                     }
                 }
-                BotEvent::OraclePriceChanged(asset, new_price) => {
-                    debug!("Oracle price changed for asset: {:?}", asset);
 
-                    // Record price change for circuit breaker monitoring with current gas price
-                    let current_gas_price = match self.provider.get_gas_price().await {
-                        Ok(price) => Some(alloy_primitives::U256::from(price)),
-                        Err(e) => {
-                            warn!("Failed to get current gas price for price update: {}", e);
-                            None
-                        }
-                    };
+                let failure = self.classify_liquidation_failure(user, &liquidation_result).await;
 
-                    if let Err(e) = self
-                        .circuit_breaker
-                        .record_price_update(
-                            Some(new_price),
-                            current_gas_price,
-                        )
-                        .await
+                // Record ALL liquidation attempts (both successful and failed) for frequency monitoring.
+                // Losing a race to another liquidator isn't a malfunction, so it doesn't trip the breaker.
+                if failure.is_some_and(|category| category.is_lost_race()) {
+                    debug!("Not counting lost liquidation race for {:?} in the circuit breaker", user);
+                } else if let Err(e) = self
+                    .circuit_breaker
+                    .record_liquidation_attempt(liquidation_succeeded, current_gas_price)
+                    .await
+                {
+                    warn!(
+                        "Failed to record liquidation attempt for circuit breaker: {}",
+                        e
+                    );
+                }
+
+                self.notify_liquidation_outcome(&liquidation_result);
+                self.record_latency(user, &liquidation_result).await;
+                if let Some(bus) = &self.event_bus {
+                    bus.publish_execution(user, &liquidation_result);
+                }
+
+                // Executed and reverted transactions both spend gas
+                if matches!(
+                    liquidation_result,
+                    Ok(LiquidationResult::Executed(_) | LiquidationResult::Failed(_))
+                ) {
+                    self.refresh_operator_balance().await;
+                }
+
+                // Record test liquidation if this was a half-open state test (determined before execution)
+                if is_test_liquidation && liquidation_succeeded {
+                    self.circuit_breaker.record_test_liquidation();
+                    info!(
+                        "📊 Recorded successful test liquidation (state was half-open before attempt) for user: {:?}",
+                        user
+                    );
+                }
+            }
+            BotEvent::PriceUpdate(asset, _old_price, _new_price) => {
+                debug!("Price update detected for asset: {:?}", asset);
+                // Could trigger a broader scan of users holding this asset
+            }
+            BotEvent::DatabaseSync(positions) => {
+                debug!("Database sync requested for {} positions", positions.len());
+                for position in positions {
+                    if let Err(e) = database::save_user_position(&self.db_pool, &position).await
                     {
-                        warn!("Failed to record price change for circuit breaker: {}", e);
+                        error!("Failed to sync position for {:?}: {}", position.address, e);
                     }
+                }
+            }
+            BotEvent::OraclePriceChanged(asset, new_price) => {
+                debug!("Oracle price changed for asset: {:?}", asset);
 
-                    if let Err(e) = self.handle_oracle_price_change(asset, new_price).await {
-                        error!("Error handling oracle price change: {}", e);
+                // Record price change for circuit breaker monitoring with current gas price
+                let current_gas_price = match self.provider.get_gas_price().await {
+                    Ok(price) => Some(alloy_primitives::U256::from(price)),
+                    Err(e) => {
+                        warn!("Failed to get current gas price for price update: {}", e);
+                        None
                     }
+                };
+
+                if let Err(e) = self
+                    .circuit_breaker
+                    .record_asset_price_update(
+                        Some(asset),
+                        Some(new_price),
+                        current_gas_price,
+                    )
+                    .await
+                {
+                    warn!("Failed to record price change for circuit breaker: {}", e);
+                }
+
+                if let Err(e) = self.handle_oracle_price_change(asset, new_price).await {
+                    error!("Error handling oracle price change: {}", e);
+                }
+            }
+            BotEvent::ExternalLiquidationSignal(user) => {
+                info!(
+                    "📡 Forcing health check for externally flagged user: {:?}",
+                    user
+                );
+
+                // Bypass discovery and scan gating: refresh the position immediately and route
+                // liquidatable users to the priority processor, which still applies the
+                // circuit breaker and profitability checks
+                if let Err(e) = scanner::update_user_position(
+                    self.provider.clone(),
+                    &self.pool_contract,
+                    &self.db_pool,
+                    self.user_positions.clone(),
+                    &self.persisted_positions,
+                    self.processing_users.clone(),
+                    self.event_tx.clone(),
+                    self.live_config.load().health_factor_threshold,
+                    self.live_config.load().min_hf_change_to_persist_bps,
+                    user,
+                    Some(self.users_by_collateral.clone()),
+                    Some(self.users_by_debt.clone()),
+                    Some(&self.asset_configs),
+                    Some(self.priority_liquidation_tx.clone()),
+                )
+                .await
+                {
+                    error!(
+                        "Failed to process external liquidation signal for {:?}: {}",
+                        user, e
+                    );
+                } else {
+                    self.refresh_price_triggers(user);
+                }
+            }
+            BotEvent::ReserveDataUpdated(reserve) => {
+                if let Err(e) = self
+                    .reserve_configs
+                    .refresh_reserve(&self.pool_contract, reserve)
+                    .await
+                {
+                    debug!("Failed to refresh configuration of reserve {:?}: {}", reserve, e);
+                }
+            }
+            BotEvent::FullRescan => {
+                // Every tracked user plus everyone persisted, deduplicated
+                let mut users: HashSet<Address> =
+                    self.user_positions.iter().map(|entry| *entry.key()).collect();
+                match database::get_all_user_positions(&self.db_pool).await {
+                    Ok(stored) => users.extend(stored.into_iter().map(|position| position.address)),
+                    Err(e) => warn!("Failed to load stored users for full rescan: {}", e),
+                }
+
+                info!("🔄 Full rescan requested - queueing {} users", users.len());
+                let dropped = users
+                    .into_iter()
+                    .filter(|user| self.event_tx.send(BotEvent::UserPositionChanged(*user)).is_err())
+                    .count();
+                if dropped > 0 {
+                    warn!(
+                        "⚠️ Event queue full - {} user(s) left for the periodic scan to recheck",
+                        dropped
+                    );
                 }
             }
         }
+    }
 
-        Ok(())
+    /// Re-index the collateral and debt prices at which a borrower has to be re-checked
+    fn refresh_price_triggers(&self, user: Address) {
+        let health_factor = match self.user_positions.get(&user) {
+            Some(position) if position.total_debt_base > U256::ZERO => position.health_factor,
+            _ => {
+                self.price_triggers.remove_user(user);
+                return;
+            }
+        };
+
+        // Assets without a known price can't be indexed; those holders are re-checked on
+        // every tick of that asset instead
+        let priced_assets = |holders: &DashMap<Address, HashSet<Address>>| {
+            holders
+                .iter()
+                .filter(|entry| entry.value().contains(&user))
+                .filter_map(|entry| {
+                    self.price_feeds
+                        .get(entry.key())
+                        .filter(|feed| feed.last_price > U256::ZERO)
+                        .map(|feed| (*entry.key(), feed.last_price))
+                })
+                .collect::<Vec<(Address, U256)>>()
+        };
+        let collateral_prices = priced_assets(&self.users_by_collateral);
+        let debt_prices = priced_assets(&self.users_by_debt);
+        self.price_triggers
+            .update_user(user, health_factor, &collateral_prices, &debt_prices);
+    }
+
+    /// Re-read a borrower's per-reserve balances, store them in `user_reserves` and re-cache
+    /// them in the local health factor engine
+    async fn refresh_user_reserves(&self, user: Address) {
+        let has_debt = self
+            .user_positions
+            .get(&user)
+            .is_some_and(|position| position.total_debt_base > U256::ZERO);
+        if !has_debt {
+            if let Some(engine) = &self.health_engine {
+                engine.set_user_balances(user, HashMap::new());
+            }
+            if let Err(e) = database::save_user_reserves(&self.db_pool, user, &[]).await {
+                warn!("Failed to clear reserve balances of {:?}: {}", user, e);
+            }
+            return;
+        }
+
+        let reserves = self.reserve_configs.reserves();
+        if reserves.is_empty() {
+            debug!("Reserve list not loaded yet - not refreshing balances of {:?}", user);
+            return;
+        }
+        let user_reserves = match health_engine::fetch_user_reserves(
+            self.provider.as_ref(),
+            &self.config.network,
+            user,
+            &reserves,
+        )
+        .await
+        {
+            Ok(user_reserves) => user_reserves,
+            Err(e) => {
+                warn!("Failed to read reserve balances of {:?}: {}", user, e);
+                return;
+            }
+        };
+
+        if let Err(e) = database::save_user_reserves(&self.db_pool, user, &user_reserves).await {
+            warn!("Failed to store reserve balances of {:?}: {}", user, e);
+        }
+        if let Some(engine) = &self.health_engine {
+            if let Err(e) = engine
+                .set_user_reserves(self.provider.as_ref(), user, &user_reserves)
+                .await
+            {
+                warn!("Failed to cache reserve balances for {:?}: {}", user, e);
+            }
+        }
     }
 
     async fn handle_oracle_price_change(
@@ -546,6 +2496,37 @@ where
         asset_address: Address,
         new_price: U256,
     ) -> Result<()> {
+        // With local health factors only users crossing 1.0 are confirmed on-chain
+        if let Some(engine) = &self.health_engine {
+            if let Some(mut feed) = self.price_feeds.get_mut(&asset_address) {
+                feed.last_price = new_price;
+                feed.last_updated = chrono::Utc::now();
+            }
+
+            // The Chainlink update is only the trigger: the pool prices positions with the
+            // Aave oracle, so re-read every reserve from it and fall back to the feed price
+            let to_confirm = match engine.refresh_prices(self.provider.as_ref()).await {
+                Ok(to_confirm) => to_confirm,
+                Err(e) => {
+                    warn!(
+                        "⚠️ Aave oracle refresh failed, using the Chainlink price for {:?}: {}",
+                        asset_address, e
+                    );
+                    engine.on_price_change(asset_address, new_price)
+                }
+            };
+            info!(
+                "🧮 Price change for {:?}: {} of {} cached borrowers need on-chain confirmation",
+                asset_address,
+                to_confirm.len(),
+                engine.tracked_users()
+            );
+            for user in to_confirm {
+                let _ = self.event_tx.send(BotEvent::UserPositionChanged(user));
+            }
+            return Ok(());
+        }
+
         // Update the price feed
         if let Some(mut feed) = self.price_feeds.get_mut(&asset_address) {
             let old_price = feed.last_price;
@@ -557,40 +2538,37 @@ where
                 feed.asset_symbol, old_price, new_price
             );
 
-            // Since any asset price change can affect user health factors (due to cross-collateral effects),
-            // we need to check all users who have ANY collateral, not just users with this specific asset
-            let mut users_to_check = HashSet::new();
-
-            // First, collect all users who have this specific asset as collateral
-            if let Some(asset_users) = self.users_by_collateral.get(&asset_address) {
-                for user in asset_users.iter() {
-                    users_to_check.insert(*user);
-                }
-                info!(
-                    "📊 Found {} users directly holding {} as collateral",
-                    asset_users.len(),
-                    feed.asset_symbol
-                );
-            }
-
-            // Additionally, collect users from all other tracked collateral assets since price changes
-            // can affect health factors through cross-collateral effects
-            for entry in self.users_by_collateral.iter() {
-                for user in entry.value().iter() {
-                    users_to_check.insert(*user);
+            if !self.users_by_collateral.is_empty() {
+                // Only collateral holders whose fall trigger and borrowers whose rise trigger
+                // was crossed can have dropped below 1.0; holders and borrowers without a
+                // trigger for this asset can't be ruled out
+                let mut users_to_check: HashSet<Address> = self
+                    .price_triggers
+                    .crossed(asset_address, new_price)
+                    .into_iter()
+                    .collect();
+                let crossed_count = users_to_check.len();
+                for (holders, side) in [
+                    (&self.users_by_collateral, price_trigger::TriggerSide::Collateral),
+                    (&self.users_by_debt, price_trigger::TriggerSide::Debt),
+                ] {
+                    if let Some(asset_users) = holders.get(&asset_address) {
+                        users_to_check.extend(asset_users.iter().copied().filter(|user| {
+                            !self.price_triggers.has_trigger(*user, asset_address, side)
+                        }));
+                    }
                 }
-            }
 
-            if !users_to_check.is_empty() {
                 info!(
-                    "🔍 Triggering health checks for {} total users affected by {} price change",
-                    users_to_check.len(),
-                    feed.asset_symbol
+                    "🎯 {} price change crossed {} trigger prices ({} unindexed holders) - {} users to check",
+                    feed.asset_symbol,
+                    crossed_count,
+                    users_to_check.len() - crossed_count,
+                    users_to_check.len()
                 );
 
-                // Trigger health factor recalculation for all affected users
                 for user in users_to_check {
-                    info!("🔍 Triggering health check for user: {:?}", user);
+                    debug!("🔍 Triggering health check for user: {:?}", user);
                     let _ = self.event_tx.send(BotEvent::UserPositionChanged(user));
                 }
             } else {
@@ -643,15 +2621,60 @@ where
     pub async fn run(&self) -> Result<()> {
         info!("🚀 Starting Aave v3 Liquidation Bot with Real-Time WebSocket Monitoring");
 
-        // First, perform initial user discovery to populate the database
-        info!("🔍 Performing initial user discovery...");
         let pool_address = *self.pool_contract.address();
 
+        // Make sure account data calls will hit a real pool rather than parse empty responses
+        if self.config.pool_code_check_enabled {
+            scanner::verify_pool_has_code(&self.provider, pool_address).await?;
+        }
+
+        // Catch a typo'd or undeployed liquidator address before any liquidation reverts
+        if self.config.liquidator_contract_check_enabled {
+            if let Some(contract_address) = self.liquidator_contract_address {
+                liquidation::executor::verify_liquidator_contract(&self.provider, contract_address)
+                    .await?;
+            }
+        }
+
+        // Establish the starting balance level so a low balance alerts before the first liquidation
+        self.refresh_operator_balance().await;
+
+        // Let the swap router pull received collateral before the first swap needs it
+        if let Some(swapper) = &self.collateral_swapper {
+            for (signer, tx_manager) in self.wallets.senders() {
+                if let Err(e) = swapper
+                    .prepare_approvals(self.provider.as_ref(), signer, tx_manager)
+                    .await
+                {
+                    warn!("Failed to set swap router allowances for {}: {}", signer.address(), e);
+                }
+            }
+        }
+
+        // Reserve parameters and prices for local health factor recomputation, loaded before
+        // discovery so subgraph balances can be evaluated; other borrowers are cached as their
+        // positions are checked
+        if let Some(engine) = &self.health_engine {
+            if let Err(e) = engine.load_reserves(self.provider.as_ref()).await {
+                warn!("Failed to load reserves for local health factors: {}", e);
+            }
+        }
+
+        // First, perform initial user discovery to populate the database
+        info!(
+            "🔍 Performing initial user discovery ({})...",
+            self.config.discovery_method.as_str()
+        );
+
         match discovery::discover_initial_users(
             self.provider.clone(),
             pool_address,
             &self.db_pool,
             self.event_tx.clone(),
+            self.config.catch_up_prioritize_by_debt,
+            self.config.discovery_method,
+            self.config.discovery_subgraph_url.as_deref(),
+            self.health_engine.as_deref(),
         )
         .await
         {
@@ -672,41 +2695,168 @@ where
             warn!("Failed to populate initial collateral mapping: {}", e);
         }
 
+        // Recover liquidations that were still queued when the previous run stopped
+        if let Err(e) = self.restore_liquidation_queue().await {
+            warn!("Failed to restore persisted liquidation queue: {}", e);
+        }
+
+        // Resume the circuit breaker where the previous run left it, cooldown included
+        if let Err(e) = self.circuit_breaker.restore_persisted_state().await {
+            warn!("Failed to restore circuit breaker state: {}", e);
+        }
+
+        // Scans buffer their position updates and write them in batches
+        let position_writer = database::PositionWriter::spawn(
+            self.db_pool.clone(),
+            self.config.position_write_batch_size,
+            Duration::from_millis(self.config.position_write_flush_interval_ms),
+        );
+
         // Start all monitoring services including circuit breaker and priority liquidation processor
-        tokio::try_join!(
-            websocket::start_event_monitoring(
-                self.provider.clone(),
-                self.ws_provider.clone(),
-                &self.config.ws_url,
-                self.event_tx.clone(),
-                if self.config.ws_fast_path_enabled { Some(self.priority_liquidation_tx.clone()) } else { None },
-            ),
-            oracle::start_oracle_monitoring(
-                self.provider.clone(),
-                self.ws_provider.clone(),
-                &self.config.ws_url,
-                self.event_tx.clone(),
-                self.asset_configs.clone(),
-                self.price_feeds.clone(),
-            ),
-            self.run_event_processor(),
-            self.run_liquidation_processor(),
-            scanner::run_periodic_scan(
-                self.provider.clone(),
-                pool_address,
-                self.db_pool.clone(),
-                self.event_tx.clone(),
-                self.config.clone(),
-                self.asset_configs.clone(),
-                self.user_positions.clone(),
-                if self.config.ws_fast_path_enabled { Some(self.priority_liquidation_tx.clone()) } else { None },
-            ),
-            scanner::start_status_reporter(self.db_pool.clone(), self.user_positions.clone(),),
-            self.circuit_breaker.run_alert_processor(),
-            self.run_circuit_breaker_status_reporter(),
-        )?;
+        let services = async {
+            tokio::try_join!(
+                websocket::start_event_monitoring(
+                    self.providers.clone(),
+                    pool_address,
+                    self.db_pool.clone(),
+                    self.event_tx.clone(),
+                    if self.config.ws_fast_path_enabled { Some(self.priority_liquidation_tx.clone()) } else { None },
+                    self.event_recorder.clone(),
+                    self.shutdown.clone(),
+                ),
+                oracle::start_oracle_monitoring(
+                    self.providers.clone(),
+                    self.event_tx.clone(),
+                    self.asset_configs.clone(),
+                    self.price_feeds.clone(),
+                    self.config.verbose_per_item_logging,
+                    self.config.price_history_enabled.then(|| self.db_pool.clone()),
+                ),
+                self.run_event_processor(),
+                self.run_liquidation_queue_persister(),
+                self.run_liquidation_processor(),
+                scanner::run_periodic_scan(
+                    self.provider.clone(),
+                    pool_address,
+                    self.db_pool.clone(),
+                    self.event_tx.clone(),
+                    self.live_config.clone(),
+                    self.asset_configs.clone(),
+                    self.user_positions.clone(),
+                    self.persisted_positions.clone(),
+                    self.value_at_risk.clone(),
+                    if self.config.ws_fast_path_enabled { Some(self.priority_liquidation_tx.clone()) } else { None },
+                    self.circuit_breaker.clone(),
+                    position_writer.clone(),
+                ),
+                scan_tiers::run_tiered_scan(
+                    self.provider.clone(),
+                    pool_address,
+                    self.live_config.clone(),
+                    self.scan_tiers.clone(),
+                    self.user_positions.clone(),
+                    self.persisted_positions.clone(),
+                    self.event_tx.clone(),
+                    if self.config.ws_fast_path_enabled { Some(self.priority_liquidation_tx.clone()) } else { None },
+                    position_writer.clone(),
+                ),
+                block_scanner::run_block_scanner(
+                    self.providers.clone(),
+                    pool_address,
+                    self.live_config.clone(),
+                    self.scan_tiers.clone(),
+                    self.user_positions.clone(),
+                    self.persisted_positions.clone(),
+                    self.event_tx.clone(),
+                    if self.config.ws_fast_path_enabled { Some(self.priority_liquidation_tx.clone()) } else { None },
+                    position_writer.clone(),
+                ),
+                scanner::start_status_reporter(
+                    self.db_pool.clone(),
+                    self.user_positions.clone(),
+                    self.value_at_risk.clone(),
+                    self.price_triggers.clone(),
+                    self.wallets.clone(),
+                ),
+                self.circuit_breaker.run_alert_processor(),
+                self.gas_oracle.run_updater(self.provider.clone()),
+                mempool::start_mempool_monitoring(
+                    self.providers.clone(),
+                    self.config.mempool_source,
+                    pool_address,
+                    self.wallets.addresses(),
+                    self.liquidation_assets
+                        .values()
+                        .map(|asset| (asset.asset_id, asset.address))
+                        .collect(),
+                    self.competitor_tracker.clone(),
+                    self.db_pool.clone(),
+                ),
+                self.run_circuit_breaker_status_reporter(),
+                self.run_database_health_check(),
+                self.run_wallet_balance_monitor(),
+                self.run_treasury_sweeper(),
+                self.run_a_token_unwinder(),
+                self.run_inventory_tracker(),
+                accounting::run_daily_pnl_report(self.db_pool.clone(), self.alert_notifier.clone()),
+                self.run_heartbeat(),
+                self.run_admin_server(),
+                self.run_web_dashboard(),
+                self.run_grpc_server(),
+                self.run_event_webhook(),
+                self.run_pool_pause_monitor(),
+                self.run_oracle_guard(),
+                self.run_reserve_config_refresher(),
+                self.run_price_history_maintenance(),
+                self.run_position_history_retention(),
+                self.run_compound_v3_monitor(),
+                self.run_metrics_exporter(),
+                self.run_config_reload(),
+            )?;
+            Ok::<(), eyre::Report>(())
+        };
 
-        Ok(())
+        // In-flight liquidations run inside the services, so they keep being driven until
+        // shutdown has waited for them
+        let result = tokio::select! {
+            result = services => result,
+            () = self.drain_for_shutdown() => Ok(()),
+        };
+        self.finish_shutdown(&position_writer).await;
+        result
+    }
+
+    /// Resolves once shutdown was requested and in-flight liquidations and event
+    /// subscriptions have wound down, or the shutdown timeout passed
+    async fn drain_for_shutdown(&self) {
+        self.shutdown.cancelled().await;
+
+        let timeout = Duration::from_secs(self.config.shutdown_timeout_secs);
+        let in_flight = self.shutdown.in_flight_liquidations();
+        if in_flight > 0 {
+            info!("⏳ Waiting for {} in-flight liquidation(s) to finish...", in_flight);
+        }
+        if !self.shutdown.wait_for_in_flight(timeout).await {
+            warn!(
+                "⚠️ {} liquidation(s) still in flight after {:?} - stopping anyway",
+                self.shutdown.in_flight_liquidations(),
+                timeout
+            );
+        }
+        if !self.shutdown.wait_for_tasks(timeout).await {
+            warn!("⚠️ Event subscriptions did not close within {:?}", timeout);
+        }
+    }
+
+    /// Write out state that would otherwise be lost when the process exits
+    async fn finish_shutdown(&self, position_writer: &database::PositionWriter) {
+        if let Err(e) = position_writer.flush().await {
+            warn!("Failed to flush pending position writes: {}", e);
+        }
+        if let Err(e) = self.circuit_breaker.persist_state().await {
+            warn!("Failed to persist circuit breaker state: {}", e);
+        }
+        info!("👋 Liquidation bot stopped");
     }
 
     /// Populate users_by_collateral mapping for all users in the database
@@ -1,14 +1,19 @@
-use alloy_primitives::U256;
+use alloy_primitives::{Address, U256};
 use eyre::Result;
 use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
-use std::collections::VecDeque;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
-use crate::config::BotConfig;
+use crate::config::{self, BotConfig, SharedConfig};
+use crate::database::{self, DatabasePool};
+use crate::gas::GasOracle;
+use crate::models::{TriggerAction, TriggerMetric, TriggerRule};
+use crate::monitoring::alerts::{AlertEvent, AlertNotifier};
+use crate::monitoring::metrics;
 
 /// Circuit breaker states following the circuit breaker pattern
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -34,6 +39,8 @@ pub enum MarketCondition {
     GasPriceSpike { gas_multiplier: u64 },
     /// Multiple conditions triggered simultaneously
     MultipleConditions { conditions: Vec<String> },
+    /// A configured trigger rule on a metric other than volatility, liquidations or gas
+    RuleTriggered { rule: String, value: f64 },
 }
 
 /// Market data point for tracking conditions
@@ -41,6 +48,7 @@ pub enum MarketCondition {
 pub struct MarketDataPoint {
     pub timestamp: Instant,
     pub price: Option<U256>,
+    pub asset: Option<Address>, // Asset the price belongs to (None = untagged price series)
     pub liquidation_occurred: bool,  // Successful liquidation
     pub liquidation_attempted: bool, // Any liquidation attempt (successful or failed)
     pub gas_price_wei: Option<U256>,
@@ -59,22 +67,49 @@ pub struct CircuitBreakerAlert {
 pub struct CircuitBreaker {
     /// Current state of the circuit breaker
     state: Arc<RwLock<CircuitBreakerState>>,
-    /// Configuration settings
-    config: BotConfig,
+    /// Live configuration settings (thresholds follow hot reloads)
+    config: SharedConfig,
     /// Market data history for analysis
     market_data: Arc<RwLock<VecDeque<MarketDataPoint>>>,
     /// Time when circuit breaker was last activated
     last_activation: Arc<RwLock<Option<Instant>>>,
     /// Time when last test liquidation was allowed in half-open state
     last_test_liquidation: Arc<RwLock<Option<Instant>>>,
+    /// Start of the current run of normal volatility and gas while open (fast recovery)
+    stable_since: Arc<RwLock<Option<Instant>>>,
     /// Alert sender for notifications
     alert_tx: mpsc::UnboundedSender<CircuitBreakerAlert>,
     /// Alert receiver for notifications
     alert_rx: Arc<tokio::sync::Mutex<mpsc::UnboundedReceiver<CircuitBreakerAlert>>>,
     /// Statistics tracking
     stats: Arc<RwLock<CircuitBreakerStats>>,
+    /// Severity-routed webhook notifications for state changes
+    alert_notifier: Option<AlertNotifier>,
+    /// Fee history supplying the gas spike baseline (None = fixed 20 Gwei)
+    gas_oracle: Option<Arc<GasOracle>>,
+    /// Database the state is saved to on every state change (None = in memory only)
+    state_store: Option<DatabasePool>,
+    /// When each asset's price last changed, for oracle staleness rules
+    price_updated_at: Arc<RwLock<HashMap<Address, Instant>>>,
+    /// Assets paused by pause_asset rules, with the time each pause ends
+    paused_assets: Arc<RwLock<HashMap<Address, Instant>>>,
+    /// When each alert-only breach last notified, so a lasting breach alerts once per cooldown
+    rule_alerted_at: Arc<RwLock<HashMap<String, Instant>>>,
 }
 
+/// Key the circuit breaker snapshot is saved under
+const STATE_STORE_NAME: &str = "default";
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+/// Gas spike baseline used until the gas oracle has sampled any blocks
+const FALLBACK_BASELINE_GAS_PRICE_WEI: u64 = 20_000_000_000; // 20 Gwei
+
 /// Statistics for circuit breaker performance
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct CircuitBreakerStats {
@@ -83,10 +118,40 @@ pub struct CircuitBreakerStats {
     pub volatility_triggers: u64,
     pub liquidation_flood_triggers: u64,
     pub gas_spike_triggers: u64,
+    pub fast_recoveries: u64,
+    #[serde(default)]
+    pub rule_triggers: u64, // Activations by rules on metrics other than the built-in three
+    #[serde(default)]
+    pub asset_pauses: u64,
+    #[serde(default)]
+    pub rule_alerts: u64,
     pub average_activation_duration_secs: f64,
     pub last_activation_reason: Option<String>,
 }
 
+/// State, statistics and market data window carried over a restart
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CircuitBreakerSnapshot {
+    pub state: CircuitBreakerState,
+    pub stats: CircuitBreakerStats,
+    /// Unix time of the last activation, from which the remaining cooldown is derived
+    #[serde(default)]
+    pub last_activation: Option<u64>,
+    #[serde(default)]
+    pub market_data: Vec<MarketDataSnapshot>,
+}
+
+/// A market data point with its timestamp as unix seconds
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarketDataSnapshot {
+    pub timestamp: u64,
+    pub price: Option<U256>,
+    pub asset: Option<Address>,
+    pub liquidation_occurred: bool,
+    pub liquidation_attempted: bool,
+    pub gas_price_wei: Option<U256>,
+}
+
 /// Circuit breaker status report for monitoring and dashboards
 #[derive(Debug, Serialize, Deserialize)]
 pub struct CircuitBreakerStatusReport {
@@ -97,6 +162,8 @@ pub struct CircuitBreakerStatusReport {
     pub monitoring_window_secs: u64,
     pub cooldown_secs: u64,
     pub thresholds: CircuitBreakerThresholds,
+    pub rules: Vec<String>, // Trigger rules in effect, built-in thresholds included
+    pub paused_assets: Vec<Address>,
     pub current_conditions: CurrentMarketConditions,
 }
 
@@ -121,7 +188,13 @@ pub struct CurrentMarketConditions {
 impl CircuitBreaker {
     /// Create a new circuit breaker instance
     pub fn new(config: BotConfig) -> Self {
+        Self::from_shared_config(config::shared(config))
+    }
+
+    /// Create a circuit breaker that reads its thresholds from the live configuration
+    pub fn from_shared_config(live_config: SharedConfig) -> Self {
         let (alert_tx, alert_rx) = mpsc::unbounded_channel();
+        let config = live_config.load();
 
         let initial_state = if config.circuit_breaker_enabled {
             CircuitBreakerState::Closed
@@ -143,16 +216,41 @@ impl CircuitBreaker {
 
         Self {
             state: Arc::new(RwLock::new(initial_state)),
-            config,
+            config: live_config.clone(),
             market_data: Arc::new(RwLock::new(VecDeque::new())),
             last_activation: Arc::new(RwLock::new(None)),
             last_test_liquidation: Arc::new(RwLock::new(None)),
+            stable_since: Arc::new(RwLock::new(None)),
             alert_tx,
             alert_rx: Arc::new(tokio::sync::Mutex::new(alert_rx)),
             stats: Arc::new(RwLock::new(CircuitBreakerStats::default())),
+            alert_notifier: None,
+            gas_oracle: None,
+            state_store: None,
+            price_updated_at: Arc::new(RwLock::new(HashMap::new())),
+            paused_assets: Arc::new(RwLock::new(HashMap::new())),
+            rule_alerted_at: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
+    /// Save the state to `db_pool` on every state change so a restart resumes it
+    pub fn with_state_store(mut self, db_pool: DatabasePool) -> Self {
+        self.state_store = Some(db_pool);
+        self
+    }
+
+    /// Notify webhooks when the breaker opens or recovers
+    pub fn with_alert_notifier(mut self, notifier: AlertNotifier) -> Self {
+        self.alert_notifier = Some(notifier);
+        self
+    }
+
+    /// Measure gas spikes against the oracle's recent median instead of a fixed 20 Gwei
+    pub fn with_gas_oracle(mut self, gas_oracle: Arc<GasOracle>) -> Self {
+        self.gas_oracle = Some(gas_oracle);
+        self
+    }
+
     /// Check if liquidations are currently allowed
     pub fn is_liquidation_allowed(&self) -> bool {
         let state = self.state.read();
@@ -178,7 +276,7 @@ impl CircuitBreaker {
     /// # Example
     /// ```rust,no_run
     /// # use liquidation_bot::circuit_breaker::CircuitBreaker;
-    /// # use alloy_primitives::U256;
+    /// # use alloy_primitives::{Address, U256};
     /// # async fn example(circuit_breaker: &CircuitBreaker) -> eyre::Result<()> {
     /// # let gas_price = U256::from(20_000_000_000u64);
     /// // Record a failed liquidation attempt
@@ -194,13 +292,14 @@ impl CircuitBreaker {
         liquidation_succeeded: bool,
         gas_price_wei: Option<U256>,
     ) -> Result<()> {
-        if !self.config.circuit_breaker_enabled {
+        if !self.config.load().circuit_breaker_enabled {
             return Ok(());
         }
 
         let data_point = MarketDataPoint {
             timestamp: Instant::now(),
             price: None, // Price updates are handled separately
+            asset: None,
             liquidation_occurred: liquidation_succeeded,
             liquidation_attempted: true, // Always true for any attempt
             gas_price_wei,
@@ -213,7 +312,7 @@ impl CircuitBreaker {
 
             // Keep only data within monitoring window
             let cutoff_time = Instant::now()
-                - Duration::from_secs(self.config.circuit_breaker_monitoring_window_secs);
+                - Duration::from_secs(self.config.load().circuit_breaker_monitoring_window_secs);
             while let Some(front) = market_data.front() {
                 if front.timestamp < cutoff_time {
                     market_data.pop_front();
@@ -241,7 +340,7 @@ impl CircuitBreaker {
     /// # Example
     /// ```rust,no_run
     /// # use liquidation_bot::circuit_breaker::CircuitBreaker;
-    /// # use alloy_primitives::U256;
+    /// # use alloy_primitives::{Address, U256};
     /// # async fn example(circuit_breaker: &CircuitBreaker) -> eyre::Result<()> {
     /// # let new_price = U256::from(50000 * 10u128.pow(18));
     /// # let gas_price = U256::from(20_000_000_000u64);
@@ -255,13 +354,29 @@ impl CircuitBreaker {
         price: Option<U256>,
         gas_price_wei: Option<U256>,
     ) -> Result<()> {
-        if !self.config.circuit_breaker_enabled {
+        self.record_asset_price_update(None, price, gas_price_wei).await
+    }
+
+    /// Record a price update for a specific asset. Volatility is measured per asset, so prices
+    /// of different assets never count as a move against each other.
+    pub async fn record_asset_price_update(
+        &self,
+        asset: Option<Address>,
+        price: Option<U256>,
+        gas_price_wei: Option<U256>,
+    ) -> Result<()> {
+        if !self.config.load().circuit_breaker_enabled {
             return Ok(());
         }
 
+        if let (Some(asset), Some(_)) = (asset, price) {
+            self.price_updated_at.write().insert(asset, Instant::now());
+        }
+
         let data_point = MarketDataPoint {
             timestamp: Instant::now(),
             price,
+            asset,
             liquidation_occurred: false,
             liquidation_attempted: false, // No liquidation attempt for price updates
             gas_price_wei,
@@ -274,7 +389,7 @@ impl CircuitBreaker {
 
             // Keep only data within monitoring window
             let cutoff_time = Instant::now()
-                - Duration::from_secs(self.config.circuit_breaker_monitoring_window_secs);
+                - Duration::from_secs(self.config.load().circuit_breaker_monitoring_window_secs);
             while let Some(front) = market_data.front() {
                 if front.timestamp < cutoff_time {
                     market_data.pop_front();
@@ -316,13 +431,14 @@ impl CircuitBreaker {
         liquidation_occurred: bool,
         gas_price_wei: Option<U256>,
     ) -> Result<()> {
-        if !self.config.circuit_breaker_enabled {
+        if !self.config.load().circuit_breaker_enabled {
             return Ok(());
         }
 
         let data_point = MarketDataPoint {
             timestamp: Instant::now(),
             price,
+            asset: None,
             liquidation_occurred,
             liquidation_attempted: liquidation_occurred, // ⚠️ CRITICAL BUG: This is incorrect!
             // When liquidation_occurred is false, we cannot distinguish between
@@ -343,7 +459,7 @@ impl CircuitBreaker {
 
             // Keep only data within monitoring window
             let cutoff_time = Instant::now()
-                - Duration::from_secs(self.config.circuit_breaker_monitoring_window_secs);
+                - Duration::from_secs(self.config.load().circuit_breaker_monitoring_window_secs);
             while let Some(front) = market_data.front() {
                 if front.timestamp < cutoff_time {
                     market_data.pop_front();
@@ -361,70 +477,386 @@ impl CircuitBreaker {
 
     /// Check for extreme market conditions and activate circuit breaker if needed
     async fn check_extreme_conditions(&self) -> Result<()> {
-        // Collect all data and perform checks within a scope to release locks before await
-        let triggered_conditions = {
+        // While open, only watch for an early recovery
+        if *self.state.read() == CircuitBreakerState::Open {
+            return self.check_fast_recovery();
+        }
+
+        let triggered_conditions = self.evaluate_trigger_rules();
+
+        // Re-check state before making transition decisions to avoid race conditions
+        let current_state = self.state.read().clone();
+
+        // Activate circuit breaker if conditions are met
+        if !triggered_conditions.is_empty() {
+            // Only activate if not already open (double-check to prevent race)
+            if current_state != CircuitBreakerState::Open {
+                self.activate_circuit_breaker(triggered_conditions).await?;
+            }
+        } else if current_state == CircuitBreakerState::HalfOpen {
+            // Conditions look good, transition back to closed
+            self.close_circuit_breaker().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Trigger rules in effect: the configured rules, plus a pause_all rule for each built-in
+    /// threshold (volatility, liquidations per minute, gas) no configured rule overrides
+    pub fn effective_rules(config: &BotConfig) -> Vec<TriggerRule> {
+        let builtin = [
+            (TriggerMetric::Volatility, config.max_price_volatility_threshold),
+            (
+                TriggerMetric::LiquidationsPerMinute,
+                config.max_liquidations_per_minute as f64,
+            ),
+            (TriggerMetric::GasMultiplier, config.max_gas_price_multiplier as f64),
+        ];
+
+        let mut rules: Vec<TriggerRule> = builtin
+            .into_iter()
+            .filter(|(metric, _)| {
+                !config
+                    .circuit_breaker_rules
+                    .iter()
+                    .any(|rule| rule.metric == *metric)
+            })
+            .map(|(metric, threshold)| TriggerRule {
+                metric,
+                window_secs: None,
+                threshold,
+                action: TriggerAction::PauseAll,
+            })
+            .collect();
+        rules.extend(config.circuit_breaker_rules.iter().cloned());
+        rules
+    }
+
+    /// Evaluate every trigger rule, pausing assets and raising alerts for the rules that ask
+    /// for it. Returns the conditions that should open the circuit breaker.
+    fn evaluate_trigger_rules(&self) -> Vec<MarketCondition> {
+        let config = self.config.load();
+
+        // Measure within a scope to release the market data lock before acting
+        let breaches = {
             let market_data = self.market_data.read();
+            Self::effective_rules(&config)
+                .into_iter()
+                .filter_map(|rule| {
+                    let breaches: Vec<(Option<Address>, f64)> = self
+                        .measure(&rule, &market_data, &config)
+                        .into_iter()
+                        .filter(|(_, value)| *value > rule.threshold)
+                        .collect();
+                    (!breaches.is_empty()).then_some((rule, breaches))
+                })
+                .collect::<Vec<_>>()
+        };
 
-            // Check current state first to skip if already open
-            {
-                let state = self.state.read();
-                if *state == CircuitBreakerState::Open {
-                    return Ok(());
+        let mut triggered_conditions = Vec::new();
+        for (rule, breaches) in breaches {
+            match rule.action {
+                TriggerAction::PauseAll => {
+                    // One condition per rule, from the asset that moved the most
+                    let worst = breaches
+                        .iter()
+                        .map(|(_, value)| *value)
+                        .fold(f64::MIN, f64::max);
+                    triggered_conditions.push(Self::rule_condition(&rule, worst));
+                }
+                TriggerAction::PauseAsset => {
+                    for (asset, value) in breaches {
+                        match asset {
+                            Some(asset) => self.pause_asset(asset, &rule, value),
+                            // Prices recorded without an asset can't pause one; alert instead
+                            None => self.alert_rule_breach(&rule, None, value),
+                        }
+                    }
+                }
+                TriggerAction::AlertOnly => {
+                    for (asset, value) in breaches {
+                        self.alert_rule_breach(&rule, asset, value);
+                    }
                 }
             }
+        }
+
+        triggered_conditions
+    }
 
-            let mut triggered_conditions = Vec::new();
+    /// Current value of `rule`'s metric, per asset for per-asset metrics (None = not tied to
+    /// an asset). Windows are capped at the monitoring window, the data kept in memory.
+    fn measure(
+        &self,
+        rule: &TriggerRule,
+        market_data: &VecDeque<MarketDataPoint>,
+        config: &BotConfig,
+    ) -> Vec<(Option<Address>, f64)> {
+        let window_secs = rule
+            .window_secs
+            .unwrap_or(config.circuit_breaker_monitoring_window_secs)
+            .min(config.circuit_breaker_monitoring_window_secs)
+            .max(1);
+        let cutoff = Instant::now() - Duration::from_secs(window_secs);
+        let windowed = || -> VecDeque<MarketDataPoint> {
+            market_data
+                .iter()
+                .filter(|point| point.timestamp >= cutoff)
+                .cloned()
+                .collect()
+        };
 
-            // Check price volatility
-            if let Some(volatility) = self.calculate_price_volatility(&market_data) {
-                if volatility > self.config.max_price_volatility_threshold {
-                    triggered_conditions.push(MarketCondition::ExtremeVolatility {
-                        volatility_percent: volatility,
-                    });
-                }
+        match rule.metric {
+            TriggerMetric::Volatility => self.asset_volatilities(&windowed()),
+            TriggerMetric::LiquidationsPerMinute => {
+                let attempts = self.count_recent_liquidations(&windowed());
+                let per_minute = (attempts as f64 * 60.0 / window_secs as f64).round();
+                vec![(None, per_minute)]
             }
+            TriggerMetric::GasMultiplier => self
+                .get_current_gas_multiplier(market_data)
+                .map(|multiplier| (None, multiplier as f64))
+                .into_iter()
+                .collect(),
+            TriggerMetric::ConsecutiveReverts => {
+                let reverts = market_data
+                    .iter()
+                    .rev()
+                    .filter(|point| point.liquidation_attempted)
+                    .take_while(|point| !point.liquidation_occurred)
+                    .count();
+                vec![(None, reverts as f64)]
+            }
+            TriggerMetric::FailedLiquidations => {
+                let failures = windowed()
+                    .iter()
+                    .filter(|point| point.liquidation_attempted && !point.liquidation_occurred)
+                    .count();
+                vec![(None, failures as f64)]
+            }
+            TriggerMetric::OracleStalenessSecs => self
+                .price_updated_at
+                .read()
+                .iter()
+                .map(|(asset, updated_at)| (Some(*asset), updated_at.elapsed().as_secs_f64()))
+                .collect(),
+        }
+    }
+
+    /// Condition reported when a pause_all rule opens the breaker; the built-in metrics keep
+    /// their own conditions so their trigger counts stay comparable
+    fn rule_condition(rule: &TriggerRule, value: f64) -> MarketCondition {
+        match rule.metric {
+            TriggerMetric::Volatility => MarketCondition::ExtremeVolatility {
+                volatility_percent: value,
+            },
+            TriggerMetric::LiquidationsPerMinute => MarketCondition::LiquidationFlood {
+                liquidations_per_minute: value as u64,
+            },
+            TriggerMetric::GasMultiplier => MarketCondition::GasPriceSpike {
+                gas_multiplier: value as u64,
+            },
+            _ => MarketCondition::RuleTriggered {
+                rule: rule.label(),
+                value,
+            },
+        }
+    }
 
-            // Check liquidation frequency with floating-point precision
-            let liquidation_count = self.count_recent_liquidations(&market_data);
-            let liquidations_per_minute = (liquidation_count as f64 * 60.0
-                / self.config.circuit_breaker_monitoring_window_secs as f64)
-                .round() as u64;
+    /// Hold back liquidations touching `asset` for the cooldown, unless it is already paused
+    fn pause_asset(&self, asset: Address, rule: &TriggerRule, value: f64) {
+        let cooldown_secs = self.config.load().circuit_breaker_cooldown_secs;
+        let now = Instant::now();
+        {
+            let mut paused_assets = self.paused_assets.write();
+            if paused_assets.get(&asset).is_some_and(|until| *until > now) {
+                return;
+            }
+            paused_assets.insert(asset, now + Duration::from_secs(cooldown_secs));
+        }
+        self.stats.write().asset_pauses += 1;
+
+        let message = format!(
+            "⏸️ Circuit breaker paused liquidations of {} for {} seconds - rule {} measured {:.2}",
+            asset,
+            cooldown_secs,
+            rule.label(),
+            value
+        );
+        warn!("{}", message);
+        self.notify_rule_breach(message);
+    }
 
-            if liquidations_per_minute > self.config.max_liquidations_per_minute {
-                triggered_conditions.push(MarketCondition::LiquidationFlood {
-                    liquidations_per_minute,
-                });
+    /// Notify operators of an alert-only breach, at most once per cooldown per rule and asset
+    fn alert_rule_breach(&self, rule: &TriggerRule, asset: Option<Address>, value: f64) {
+        let label = rule.label();
+        let key = match asset {
+            Some(asset) => format!("{}:{}", label, asset),
+            None => label.clone(),
+        };
+        let cooldown = Duration::from_secs(self.config.load().circuit_breaker_cooldown_secs);
+        let now = Instant::now();
+        {
+            let mut alerted_at = self.rule_alerted_at.write();
+            if alerted_at
+                .get(&key)
+                .is_some_and(|at| now.duration_since(*at) < cooldown)
+            {
+                return;
             }
+            alerted_at.insert(key, now);
+        }
+        self.stats.write().rule_alerts += 1;
+
+        let subject = asset
+            .map(|asset| format!(" for {}", asset))
+            .unwrap_or_default();
+        let message = format!(
+            "🔔 Circuit breaker rule {} triggered{} - measured {:.2} (alert only)",
+            label, subject, value
+        );
+        warn!("{}", message);
+        self.notify_rule_breach(message);
+    }
 
-            // Check gas price conditions
-            if let Some(current_gas_multiplier) = self.get_current_gas_multiplier(&market_data) {
-                if current_gas_multiplier > self.config.max_gas_price_multiplier {
-                    triggered_conditions.push(MarketCondition::GasPriceSpike {
-                        gas_multiplier: current_gas_multiplier,
-                    });
-                }
+    /// Send a rule breach that leaves the breaker state unchanged to the alert webhooks
+    fn notify_rule_breach(&self, message: String) {
+        let Some(notifier) = self.alert_notifier.clone() else {
+            return;
+        };
+        tokio::spawn(async move {
+            let event = AlertEvent::CircuitBreakerRuleTriggered { message };
+            if let Err(e) = notifier.notify(&event).await {
+                error!("Failed to send circuit breaker rule alert: {}", e);
             }
+        });
+    }
 
-            triggered_conditions
-        }; // market_data lock is released here
+    /// Whether a pause_asset rule is currently holding back liquidations touching `asset`
+    pub fn is_asset_paused(&self, asset: Address) -> bool {
+        self.paused_assets
+            .read()
+            .get(&asset)
+            .is_some_and(|until| *until > Instant::now())
+    }
 
-        // Re-check state before making transition decisions to avoid race conditions
-        let current_state = self.state.read().clone();
+    /// Assets currently paused by pause_asset rules
+    pub fn paused_assets(&self) -> Vec<Address> {
+        let now = Instant::now();
+        self.paused_assets
+            .read()
+            .iter()
+            .filter(|(_, until)| **until > now)
+            .map(|(asset, _)| *asset)
+            .collect()
+    }
 
-        // Activate circuit breaker if conditions are met
-        if !triggered_conditions.is_empty() {
-            // Only activate if not already open (double-check to prevent race)
-            if current_state != CircuitBreakerState::Open {
-                self.activate_circuit_breaker(triggered_conditions).await?;
+    /// Re-evaluate the trigger rules without new market data, so rules such as oracle
+    /// staleness fire even when nothing is being recorded. Only runs while closed.
+    pub async fn evaluate_rules(&self) -> Result<()> {
+        if self.get_state() != CircuitBreakerState::Closed {
+            return Ok(());
+        }
+        self.check_extreme_conditions().await
+    }
+
+    /// Transition to half-open before the cooldown elapses once volatility and gas have
+    /// stayed within their thresholds for `fast_recovery_stable_secs`
+    fn check_fast_recovery(&self) -> Result<()> {
+        if self.config.load().fast_recovery_stable_secs == 0 {
+            return Ok(());
+        }
+
+        let recovered = {
+            let mut market_data = self.market_data.write();
+            let mut stable_since = self.stable_since.write();
+
+            let latest = match market_data.back() {
+                Some(point) => point.timestamp,
+                None => return Ok(()),
+            };
+            let since = *stable_since.get_or_insert(latest);
+
+            // Volatility is measured only over the stable period so the crash itself
+            // doesn't count against recovery
+            let stable_period: VecDeque<MarketDataPoint> = market_data
+                .iter()
+                .filter(|point| point.timestamp >= since)
+                .cloned()
+                .collect();
+            let volatility_normal = match self.calculate_price_volatility(&stable_period) {
+                Some(volatility) => volatility <= self.config.load().max_price_volatility_threshold,
+                None => true,
+            };
+            let gas_normal = match self.get_current_gas_multiplier(&market_data) {
+                Some(multiplier) => multiplier <= self.config.load().max_gas_price_multiplier,
+                None => true,
+            };
+
+            if !volatility_normal || !gas_normal {
+                // Start a new stable period from the latest data point
+                *stable_since = Some(latest);
+                false
+            } else if latest.duration_since(since)
+                >= Duration::from_secs(self.config.load().fast_recovery_stable_secs)
+            {
+                // Drop crash-era prices so they don't immediately re-trip the breaker
+                for point in market_data
+                    .iter_mut()
+                    .filter(|point| point.timestamp < since)
+                {
+                    point.price = None;
+                }
+                *stable_since = None;
+                true
+            } else {
+                false
+            }
+        };
+
+        if recovered {
+            let message = format!(
+                "🟡 Circuit breaker HALF-OPEN early - Market stable for {} seconds (fast recovery)",
+                self.config.load().fast_recovery_stable_secs
+            );
+            if Self::transition_to_half_open(&self.state, &self.alert_tx, message) {
+                self.stats.write().fast_recoveries += 1;
             }
-        } else if current_state == CircuitBreakerState::HalfOpen {
-            // Conditions look good, transition back to closed
-            self.close_circuit_breaker().await?;
         }
 
         Ok(())
     }
 
+    /// Move an open circuit breaker to half-open, returning false if it was no longer open
+    fn transition_to_half_open(
+        state: &RwLock<CircuitBreakerState>,
+        alert_tx: &mpsc::UnboundedSender<CircuitBreakerAlert>,
+        message: String,
+    ) -> bool {
+        let mut state_guard = state.write();
+        if *state_guard != CircuitBreakerState::Open {
+            return false;
+        }
+        *state_guard = CircuitBreakerState::HalfOpen;
+
+        let alert = CircuitBreakerAlert {
+            timestamp: SystemTime::now(),
+            condition: MarketCondition::ExtremeVolatility {
+                volatility_percent: 0.0,
+            }, // Placeholder
+            state_change: CircuitBreakerState::HalfOpen,
+            message,
+        };
+
+        warn!("{}", alert.message);
+
+        if let Err(e) = alert_tx.send(alert) {
+            error!("Failed to send half-open transition alert: {}", e);
+        }
+
+        true
+    }
+
     /// Activate the circuit breaker due to extreme conditions
     async fn activate_circuit_breaker(&self, conditions: Vec<MarketCondition>) -> Result<()> {
         {
@@ -434,6 +866,7 @@ impl CircuitBreaker {
 
             *state = CircuitBreakerState::Open;
             *last_activation = Some(Instant::now());
+            *self.stable_since.write() = None;
             stats.total_activations += 1;
 
             // Update condition-specific stats
@@ -444,6 +877,7 @@ impl CircuitBreaker {
                         stats.liquidation_flood_triggers += 1
                     }
                     MarketCondition::GasPriceSpike { .. } => stats.gas_spike_triggers += 1,
+                    MarketCondition::RuleTriggered { .. } => stats.rule_triggers += 1,
                     MarketCondition::MultipleConditions { .. } => {
                         // Multiple conditions already counted individually
                     }
@@ -465,7 +899,7 @@ impl CircuitBreaker {
             state_change: CircuitBreakerState::Open,
             message: format!(
                 "🚨 CIRCUIT BREAKER ACTIVATED: {:?} - All liquidations suspended for {} seconds",
-                condition, self.config.circuit_breaker_cooldown_secs
+                condition, self.config.load().circuit_breaker_cooldown_secs
             ),
         };
 
@@ -482,7 +916,9 @@ impl CircuitBreaker {
         }
 
         // Schedule automatic transition to half-open after cooldown
-        self.schedule_half_open_transition().await;
+        self.schedule_half_open_transition(Duration::from_secs(
+            self.config.load().circuit_breaker_cooldown_secs,
+        ));
 
         Ok(())
     }
@@ -512,48 +948,55 @@ impl CircuitBreaker {
         Ok(())
     }
 
-    /// Schedule transition to half-open state after cooldown period
-    async fn schedule_half_open_transition(&self) {
+    /// Schedule transition to half-open state once `cooldown_duration` has passed
+    fn schedule_half_open_transition(&self, cooldown_duration: Duration) {
         let state = self.state.clone();
-        let cooldown_duration = Duration::from_secs(self.config.circuit_breaker_cooldown_secs);
         let alert_tx = self.alert_tx.clone();
 
         tokio::spawn(async move {
             tokio::time::sleep(cooldown_duration).await;
 
             // Transition to half-open if still in open state
-            {
-                let mut state_guard = state.write();
-                if *state_guard == CircuitBreakerState::Open {
-                    *state_guard = CircuitBreakerState::HalfOpen;
-
-                    let alert = CircuitBreakerAlert {
-                        timestamp: SystemTime::now(),
-                        condition: MarketCondition::ExtremeVolatility {
-                            volatility_percent: 0.0,
-                        }, // Placeholder
-                        state_change: CircuitBreakerState::HalfOpen,
-                        message: "🟡 Circuit breaker HALF-OPEN - Testing market conditions"
-                            .to_string(),
-                    };
-
-                    warn!("{}", alert.message);
-
-                    if let Err(e) = alert_tx.send(alert) {
-                        error!("Failed to send half-open transition alert: {}", e);
-                    }
-                }
-            }
+            Self::transition_to_half_open(
+                &state,
+                &alert_tx,
+                "🟡 Circuit breaker HALF-OPEN - Testing market conditions".to_string(),
+            );
         });
     }
 
-    /// Calculate price volatility over the monitoring window
+    /// Calculate price volatility over the monitoring window: the largest move of any
+    /// asset's price series against its own first price in the window
     fn calculate_price_volatility(&self, market_data: &VecDeque<MarketDataPoint>) -> Option<f64> {
-        let prices: Vec<f64> = market_data
-            .iter()
-            .filter_map(|point| point.price.map(|p| p.to::<u128>() as f64))
-            .collect();
+        self.asset_volatilities(market_data)
+            .into_iter()
+            .map(|(_, volatility)| volatility)
+            .reduce(f64::max)
+    }
+
+    /// Volatility (percent) of each asset's price series over `market_data`
+    fn asset_volatilities(
+        &self,
+        market_data: &VecDeque<MarketDataPoint>,
+    ) -> Vec<(Option<Address>, f64)> {
+        let mut series: HashMap<Option<Address>, Vec<f64>> = HashMap::new();
+        for point in market_data {
+            if let Some(price) = point.price {
+                series
+                    .entry(point.asset)
+                    .or_default()
+                    .push(f64::from(price));
+            }
+        }
+
+        series
+            .into_iter()
+            .filter_map(|(asset, prices)| Some((asset, Self::series_volatility(&prices)?)))
+            .collect()
+    }
 
+    /// Largest move (percent) of a single price series against its first price
+    fn series_volatility(prices: &[f64]) -> Option<f64> {
         if prices.len() < 2 {
             return None;
         }
@@ -596,10 +1039,14 @@ impl CircuitBreaker {
             .sum()
     }
 
-    /// Calculate gas multiplier relative to baseline (uses 20 Gwei as baseline)
+    /// Calculate gas multiplier relative to the recent median gas price (20 Gwei until the
+    /// gas oracle has data)
     fn calculate_gas_multiplier(&self, gas_price_wei: U256) -> u64 {
-        // Define baseline gas price as 20 Gwei (typical normal gas price)
-        let baseline_gas_price_wei = U256::from(20_000_000_000u64); // 20 Gwei in wei
+        let baseline_gas_price_wei = self
+            .gas_oracle
+            .as_ref()
+            .and_then(|oracle| oracle.baseline_gas_price())
+            .unwrap_or(U256::from(FALLBACK_BASELINE_GAS_PRICE_WEI));
 
         // Calculate multiplier: current_gas_price / baseline_gas_price
         if baseline_gas_price_wei.is_zero() {
@@ -644,6 +1091,11 @@ impl CircuitBreaker {
         self.state.read().clone()
     }
 
+    /// Current price volatility (percent) over the monitoring window, if enough data exists
+    pub fn current_volatility_percent(&self) -> Option<f64> {
+        self.calculate_price_volatility(&self.market_data.read())
+    }
+
     /// Get circuit breaker statistics
     pub fn get_stats(&self) -> CircuitBreakerStats {
         self.stats.read().clone()
@@ -703,6 +1155,7 @@ impl CircuitBreaker {
     pub fn record_blocked_liquidation(&self) {
         let mut stats = self.stats.write();
         stats.total_liquidations_blocked += 1;
+        metrics::record_liquidation_blocked();
     }
 
     /// Record that a test liquidation was allowed in half-open state
@@ -739,40 +1192,49 @@ impl CircuitBreaker {
                 }
             }
 
-            // Here you could add additional alerting mechanisms:
-            // - Send to Slack/Discord webhook
-            // - Send email notification
-            // - Write to monitoring system
-            // - Update dashboard
+            crate::feed::publish(
+                self.config.load().chain_id,
+                crate::feed::FeedEventKind::CircuitBreaker {
+                    state: alert.state_change.clone(),
+                    message: alert.message.clone(),
+                },
+            );
 
-            // Example: Send to external monitoring system
             if let Err(e) = self.send_external_alert(&alert).await {
                 error!("Failed to send external alert: {}", e);
             }
+
+            if let Err(e) = self.persist_state().await {
+                warn!("Failed to persist circuit breaker state: {}", e);
+            }
         }
 
         Ok(())
     }
 
-    /// Send alert to external monitoring system (placeholder implementation)
+    /// Send alert to the configured severity-routed webhooks and chat channels
     async fn send_external_alert(&self, alert: &CircuitBreakerAlert) -> Result<()> {
-        // Placeholder for external alerting - could integrate with:
-        // - Slack webhook
-        // - PagerDuty
-        // - Email service
-        // - Custom monitoring dashboard
+        let notifier = match &self.alert_notifier {
+            Some(notifier) => notifier,
+            None => return Ok(()),
+        };
 
-        info!(
-            "External alert sent: {} at {}",
-            alert.message,
-            alert
-                .timestamp
-                .duration_since(UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
-        );
+        let event = match alert.state_change {
+            CircuitBreakerState::Open => AlertEvent::CircuitBreakerOpened {
+                message: alert.message.clone(),
+            },
+            CircuitBreakerState::Closed => AlertEvent::CircuitBreakerRecovered {
+                message: alert.message.clone(),
+            },
+            CircuitBreakerState::HalfOpen => AlertEvent::CircuitBreakerHalfOpen {
+                message: alert.message.clone(),
+            },
+            CircuitBreakerState::Disabled => AlertEvent::CircuitBreakerDisabled {
+                message: alert.message.clone(),
+            },
+        };
 
-        Ok(())
+        notifier.notify(&event).await
     }
 
     /// Get comprehensive status report for monitoring and dashboards
@@ -800,13 +1262,18 @@ impl CircuitBreaker {
             stats,
             last_activation_timestamp,
             time_since_last_activation_secs,
-            monitoring_window_secs: self.config.circuit_breaker_monitoring_window_secs,
-            cooldown_secs: self.config.circuit_breaker_cooldown_secs,
+            monitoring_window_secs: self.config.load().circuit_breaker_monitoring_window_secs,
+            cooldown_secs: self.config.load().circuit_breaker_cooldown_secs,
             thresholds: CircuitBreakerThresholds {
-                max_price_volatility_threshold: self.config.max_price_volatility_threshold,
-                max_liquidations_per_minute: self.config.max_liquidations_per_minute,
-                max_gas_price_multiplier: self.config.max_gas_price_multiplier,
+                max_price_volatility_threshold: self.config.load().max_price_volatility_threshold,
+                max_liquidations_per_minute: self.config.load().max_liquidations_per_minute,
+                max_gas_price_multiplier: self.config.load().max_gas_price_multiplier,
             },
+            rules: Self::effective_rules(&self.config.load())
+                .iter()
+                .map(TriggerRule::label)
+                .collect(),
+            paused_assets: self.paused_assets(),
             current_conditions,
         }
     }
@@ -822,7 +1289,7 @@ impl CircuitBreaker {
         let current_liquidations_per_minute = {
             let liquidation_count = self.count_recent_liquidations(market_data);
             (liquidation_count as f64 * 60.0
-                / self.config.circuit_breaker_monitoring_window_secs as f64)
+                / self.config.load().circuit_breaker_monitoring_window_secs as f64)
                 .round() as u64
         };
 
@@ -830,7 +1297,7 @@ impl CircuitBreaker {
         let current_successful_liquidations_per_minute = {
             let successful_count = self.count_successful_liquidations(market_data);
             (successful_count as f64 * 60.0
-                / self.config.circuit_breaker_monitoring_window_secs as f64)
+                / self.config.load().circuit_breaker_monitoring_window_secs as f64)
                 .round() as u64
         };
 
@@ -854,7 +1321,7 @@ impl CircuitBreaker {
             let mut last_test_liquidation = self.last_test_liquidation.write();
             let mut market_data = self.market_data.write();
 
-            *state = if self.config.circuit_breaker_enabled {
+            *state = if self.config.load().circuit_breaker_enabled {
                 CircuitBreakerState::Closed
             } else {
                 CircuitBreakerState::Disabled
@@ -862,6 +1329,7 @@ impl CircuitBreaker {
 
             *last_activation = None;
             *last_test_liquidation = None;
+            *self.stable_since.write() = None;
             market_data.clear();
         }
 
@@ -883,6 +1351,107 @@ impl CircuitBreaker {
         Ok(())
     }
 
+    /// State, statistics and market data window to persist
+    pub fn snapshot(&self) -> CircuitBreakerSnapshot {
+        let now = Instant::now();
+        let now_unix = unix_now();
+        let to_unix =
+            |instant: Instant| now_unix.saturating_sub(now.duration_since(instant).as_secs());
+
+        CircuitBreakerSnapshot {
+            state: self.get_state(),
+            stats: self.get_stats(),
+            last_activation: self.last_activation.read().map(to_unix),
+            market_data: self
+                .market_data
+                .read()
+                .iter()
+                .map(|point| MarketDataSnapshot {
+                    timestamp: to_unix(point.timestamp),
+                    price: point.price,
+                    asset: point.asset,
+                    liquidation_occurred: point.liquidation_occurred,
+                    liquidation_attempted: point.liquidation_attempted,
+                    gas_price_wei: point.gas_price_wei,
+                })
+                .collect(),
+        }
+    }
+
+    /// Resume from a snapshot saved by a previous run. A breaker that was open or half-open
+    /// reopens for whatever remains of its cooldown; a breaker disabled by configuration
+    /// stays disabled. Market data older than the monitoring window is dropped.
+    pub fn restore(&self, snapshot: CircuitBreakerSnapshot) {
+        let config = self.config.load();
+        let now = Instant::now();
+        let now_unix = unix_now();
+        let from_unix = |timestamp: u64| {
+            now.checked_sub(Duration::from_secs(now_unix.saturating_sub(timestamp)))
+        };
+
+        *self.stats.write() = snapshot.stats;
+        let window = Duration::from_secs(config.circuit_breaker_monitoring_window_secs);
+        *self.market_data.write() = snapshot
+            .market_data
+            .into_iter()
+            .filter_map(|point| {
+                let timestamp = from_unix(point.timestamp)?;
+                (now.duration_since(timestamp) <= window).then_some(MarketDataPoint {
+                    timestamp,
+                    price: point.price,
+                    asset: point.asset,
+                    liquidation_occurred: point.liquidation_occurred,
+                    liquidation_attempted: point.liquidation_attempted,
+                    gas_price_wei: point.gas_price_wei,
+                })
+            })
+            .collect();
+        let last_activation = snapshot.last_activation.and_then(from_unix);
+        *self.last_activation.write() = last_activation;
+
+        if !config.circuit_breaker_enabled {
+            return;
+        }
+        let state = match snapshot.state {
+            CircuitBreakerState::Open | CircuitBreakerState::HalfOpen => {
+                let cooldown = Duration::from_secs(config.circuit_breaker_cooldown_secs);
+                let elapsed = last_activation.map_or(Duration::ZERO, |at| now.duration_since(at));
+                let remaining = cooldown.saturating_sub(elapsed);
+                info!(
+                    "🔒 Circuit breaker restored OPEN - {}s of cooldown remaining",
+                    remaining.as_secs()
+                );
+                *self.state.write() = CircuitBreakerState::Open;
+                self.schedule_half_open_transition(remaining);
+                return;
+            }
+            state => state,
+        };
+        info!("🔒 Circuit breaker restored in state: {:?}", state);
+        *self.state.write() = state;
+    }
+
+    /// Save the current snapshot to the state store, if one is configured
+    pub async fn persist_state(&self) -> Result<()> {
+        let Some(db_pool) = &self.state_store else {
+            return Ok(());
+        };
+        let snapshot = serde_json::to_string(&self.snapshot())?;
+        database::save_circuit_breaker_state(db_pool, STATE_STORE_NAME, &snapshot).await
+    }
+
+    /// Restore the snapshot saved in the state store by a previous run, if any
+    pub async fn restore_persisted_state(&self) -> Result<()> {
+        let Some(db_pool) = &self.state_store else {
+            return Ok(());
+        };
+        let saved = database::get_circuit_breaker_state(db_pool, STATE_STORE_NAME).await?;
+        if let Some(snapshot) = saved {
+            self.restore(serde_json::from_str(&snapshot)?);
+        }
+        Ok(())
+    }
+
     /// Check if conditions are improving (for monitoring dashboards)
     pub fn are_conditions_improving(&self) -> bool {
         let market_data = self.market_data.read();
@@ -941,19 +1510,19 @@ impl CircuitBreaker {
         // Deduct points for current volatility
         if let Some(volatility) = current_conditions.current_volatility_percent {
             let volatility_penalty =
-                ((volatility / self.config.max_price_volatility_threshold) * 30.0) as u8;
+                ((volatility / self.config.load().max_price_volatility_threshold) * 30.0) as u8;
             score = score.saturating_sub(volatility_penalty.min(30));
         }
 
         // Deduct points for liquidation frequency
         let liquidation_ratio = current_conditions.current_liquidations_per_minute as f64
-            / self.config.max_liquidations_per_minute as f64;
+            / self.config.load().max_liquidations_per_minute as f64;
         let liquidation_penalty = (liquidation_ratio * 30.0) as u8;
         score = score.saturating_sub(liquidation_penalty.min(30));
 
         // Deduct points for gas prices
         if let Some(gas_multiplier) = current_conditions.current_gas_multiplier {
-            let gas_ratio = gas_multiplier as f64 / self.config.max_gas_price_multiplier as f64;
+            let gas_ratio = gas_multiplier as f64 / self.config.load().max_gas_price_multiplier as f64;
             let gas_penalty = (gas_ratio * 20.0) as u8;
             score = score.saturating_sub(gas_penalty.min(20));
         }
@@ -1022,7 +1591,7 @@ impl CircuitBreaker {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
     use std::time::Duration;
     use tokio::time::sleep;
@@ -1034,33 +1603,172 @@ mod tests {
         baseline_gas_price_wei * U256::from(multiplier)
     }
 
-    fn create_test_config() -> BotConfig {
+    pub(crate) fn create_test_config() -> BotConfig {
         BotConfig {
+            chain_id: config::DEFAULT_CHAIN_ID,
+            chain_name: "base".to_string(),
+            network: crate::networks::base(),
             rpc_url: "http://localhost:8545".to_string(),
             ws_url: "ws://localhost:8546".to_string(),
+            rpc_fallback_urls: Vec::new(),
+            ws_fallback_urls: Vec::new(),
+            provider_health_check_interval_secs: 0,
+            rpc_rate_limit_rps: 0,
+            rpc_endpoint_rate_limits: std::collections::BTreeMap::new(),
+            signer_backend: config::SignerBackend::PrivateKey,
+            wallet_gas_reserve_wei: U256::ZERO,
+            weth_gas_target_wei: None,
+            weth_wrap_above_wei: None,
             private_key: "0x0000000000000000000000000000000000000000000000000000000000000001"
                 .to_string(),
             liquidator_contract: None,
             min_profit_threshold: U256::from(1000000000000000000u64), // 1 ETH
-            gas_price_multiplier: 2,
+            min_profit_pct_of_debt_bps: 0,
+            onchain_min_profit_margin_bps: None,
+            liquidation_hf_margin: U256::ZERO,
+            profitability_model: crate::models::ProfitabilityModel::FlatSlippage,
+            shadow_profitability: None,
+            shadow_divergence_threshold_bps: 500,
+            gas_fee_profile: None,
+            gas_fee_history_blocks: 20,
+            gas_conservative_profit_multiple: 2,
+            gas_aggressive_profit_multiple: 10,
+            racing_max_profit_share_bps: 1_000,
+            racing_win_rate_window: 50,
+            mempool_source: crate::models::MempoolSource::Off,
             target_user: None,
             database_url: "sqlite::memory:".to_string(),
             health_factor_threshold: U256::from(1100000000000000000u64), // 1.1
+            min_hf_change_to_persist_bps: 100,
+            position_write_batch_size: 100,
+            position_write_flush_interval_ms: 1000,
+            event_queue_capacity: 10_000,
+            liquidation_queue_capacity: 1_000,
+            user_refresh_min_interval_secs: 5,
+            health_check_parallelism: 32,
+            liquidation_parallelism: 1,
+            liquidation_retry_max_attempts: 3,
+            liquidation_retry_base_delay_ms: 2000,
+            liquidation_retry_max_delay_ms: 30000,
+            shutdown_timeout_secs: 60,
             monitoring_interval_secs: 60,
+            periodic_scan_interval_secs: 360,
+            adaptive_scan_interval_enabled: false,
+            min_scan_interval_secs: 30,
+            max_scan_interval_secs: 360,
             asset_loading_method: crate::config::AssetLoadingMethod::Hardcoded,
             at_risk_scan_limit: Some(100),
             full_rescan_interval_minutes: 30,
+            scan_tiers: Vec::new(),
+            block_scan_enabled: false,
+            block_scan_top_n: 100,
+            block_scan_every_n_blocks: 1,
+            block_scan_budget_ms: 1500,
+            catch_up_prioritize_by_debt: true,
+            discovery_method: crate::models::DiscoveryMethod::EventScan,
+            discovery_subgraph_url: None,
             archive_zero_debt_users: false,
             zero_debt_cooldown_hours: 24,
+            archival_run_interval_secs: None,
             safe_health_factor_threshold: U256::from(10000000000000000000u64), // 10.0
+            price_history_enabled: false,
+            local_health_factor_enabled: false,
+            price_history_minute_after_hours: 1,
+            price_history_hour_after_hours: 24,
+            price_history_retention_days: 30,
+            position_history_retention_days: 30,
             circuit_breaker_enabled: true,
             max_price_volatility_threshold: 5.0, // 5% for testing
             max_liquidations_per_minute: 3,      // Low threshold for testing
             circuit_breaker_monitoring_window_secs: 60,
             circuit_breaker_cooldown_secs: 5, // Short cooldown for testing
+            fast_recovery_stable_secs: 0,
             min_gas_price_multiplier: 1,
             max_gas_price_multiplier: 3, // Low threshold for testing
+            circuit_breaker_rules: Vec::new(),
+            real_time_monitoring_enabled: true,
             ws_fast_path_enabled: true,  // Enable fast path for testing
+            min_position_age_blocks: 0,
+            pool_pause_check_enabled: true,
+            pool_pause_check_interval_secs: 60,
+            reserve_config_refresh_secs: 300,
+            oracle_guard_enabled: true,
+            oracle_guard_interval_secs: 30,
+            oracle_staleness_grace_secs: 300,
+            oracle_max_deviation_bps: 200,
+            oracle_secondary_feeds: Vec::new(),
+            pool_code_check_enabled: true,
+            liquidator_contract_check_enabled: true,
+            liquidation_audit_enabled: true,
+            persistent_liquidation_queue_enabled: false,
+            value_at_risk_bonus_bps: 500,
+            value_at_risk_alert_threshold: None,
+            strategy_label: "default".to_string(),
+            verbose_per_item_logging: false,
+            event_record_path: None,
+            min_operator_balance_wei: U256::ZERO,
+            operator_balance_halt_enabled: false,
+            balance_check_interval_secs: 0,
+            min_token_balances: std::collections::BTreeMap::new(),
+            realized_profit_alert_bps: 0,
+            treasury_cold_wallet: None,
+            treasury_sweep_thresholds: std::collections::BTreeMap::new(),
+            treasury_gas_reserve_wei: U256::from(20_000_000_000_000_000u64),
+            treasury_sweep_interval_secs: 300,
+            inventory_target_asset: None,
+            inventory_max_deviation_bps: 2_000,
+            inventory_check_interval_secs: 900,
+            opportunity_webhook_url: None,
+            opportunity_webhook_execute: false,
+            submission_strategy: crate::models::SubmissionStrategy::Public,
+            private_relay_url: None,
+            private_relay_max_blocks: 25,
+            collateral_swap_routes: Vec::new(),
+            collateral_swap_slippage_bps: 50,
+            swap_aggregator: None,
+            swap_aggregator_api_url: None,
+            swap_aggregator_api_key: None,
+            swap_quote_cache_secs: 10,
+            swap_approval_mode: crate::models::ApprovalMode::Exact,
+            flash_loan_sources: vec![crate::models::FlashLoanSource::AaveV3],
+            wallet_funded_liquidations_enabled: false,
+            liquidation_receive_a_token: false,
+            a_token_unwind_after_secs: 86_400,
+            execution_mode: crate::models::ExecutionMode::Live,
+            real_execution: false,
+            simulation_mode: crate::models::SimulationMode::Off,
+            anvil_path: "anvil".to_string(),
+            anvil_port: 8546,
+            tx_stuck_after_secs: 30,
+            tx_fee_bump_pct: 12,
+            tx_max_fee_bumps: 3,
+            compound_v3_comet_address: None,
+            compound_v3_discovery_blocks: 10_000,
+            compound_v3_max_absorb_gas_cost_wei: U256::from(1_000_000_000_000_000u64),
+            info_webhook_url: None,
+            critical_webhook_url: None,
+            telegram_bot_token: None,
+            telegram_chat_id: None,
+            discord_webhook_url: None,
+            slack_webhook_url: None,
+            pagerduty_routing_key: None,
+            alert_routes: Vec::new(),
+            heartbeat_url: None,
+            heartbeat_interval_secs: 60,
+            heartbeat_stall_secs: 20,
+            admin_listen_addr: None,
+            admin_token: None,
+            metrics_listen_addr: None,
+            dashboard_listen_addr: None,
+            grpc_listen_addr: None,
+            event_bus_url: None,
+            event_bus_events_topic: "liquidation-bot.events".to_string(),
+            event_bus_executions_topic: "liquidation-bot.executions".to_string(),
+            event_webhook_url: None,
+            event_webhook_secret: None,
+            event_webhook_max_retries: 5,
+            config_file: None,
+            config_reload_interval_secs: 0,
         }
     }
 
@@ -1118,6 +1826,50 @@ mod tests {
         assert_eq!(stats.volatility_triggers, 1);
     }
 
+    #[tokio::test]
+    async fn test_rising_volatility_shortens_scan_interval() {
+        use crate::monitoring::scanner::adaptive_scan_interval_secs;
+
+        let mut config = create_test_config();
+        config.adaptive_scan_interval_enabled = true;
+        let circuit_breaker = CircuitBreaker::new(config.clone());
+
+        let effective_interval = |circuit_breaker: &CircuitBreaker| {
+            adaptive_scan_interval_secs(
+                circuit_breaker.current_volatility_percent(),
+                config.max_price_volatility_threshold,
+                config.periodic_scan_interval_secs,
+                config.min_scan_interval_secs,
+                config.max_scan_interval_secs,
+            )
+        };
+
+        // No price history yet: the base interval applies
+        assert_eq!(circuit_breaker.current_volatility_percent(), None);
+        let mut intervals = vec![effective_interval(&circuit_breaker)];
+
+        // Prices drift further from the baseline, staying under the 5% trip threshold
+        let price = |usd: u128| Some(U256::from(usd * 10u128.pow(18)));
+        for usd in [50000, 50500, 51500, 52000] {
+            circuit_breaker
+                .record_price_update(price(usd), Some(gas_multiplier_to_wei(2)))
+                .await
+                .unwrap();
+            if circuit_breaker.current_volatility_percent().is_some() {
+                intervals.push(effective_interval(&circuit_breaker));
+            }
+        }
+
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
+        assert_eq!(intervals[0], config.periodic_scan_interval_secs);
+        assert!(
+            intervals.windows(2).all(|pair| pair[1] < pair[0]),
+            "intervals should shrink as volatility rises: {:?}",
+            intervals
+        );
+        assert!(*intervals.last().unwrap() >= config.min_scan_interval_secs);
+    }
+
     #[tokio::test]
     async fn test_liquidation_flood_trigger() {
         let config = create_test_config();
@@ -1367,6 +2119,170 @@ mod tests {
         assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
     }
 
+    #[tokio::test]
+    async fn test_volatility_is_measured_per_asset() {
+        let circuit_breaker = CircuitBreaker::new(create_test_config());
+        let (weth, usdc) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let price = |usd: u128| Some(U256::from(usd * 100_000_000));
+
+        // WETH and USDC interleaved are far apart, but neither moves on its own
+        for _ in 0..2 {
+            for (asset, usd) in [(weth, 2000), (usdc, 1)] {
+                circuit_breaker
+                    .record_asset_price_update(Some(asset), price(usd), Some(gas_multiplier_to_wei(2)))
+                    .await
+                    .unwrap();
+            }
+        }
+        assert_eq!(circuit_breaker.current_volatility_percent(), Some(0.0));
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
+
+        // A real move in one asset still trips the breaker
+        circuit_breaker
+            .record_asset_price_update(Some(weth), price(1600), Some(gas_multiplier_to_wei(2)))
+            .await
+            .unwrap();
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_consecutive_reverts_rule_pauses_all() {
+        let mut config = create_test_config();
+        config.max_liquidations_per_minute = 100;
+        config.circuit_breaker_rules =
+            vec![TriggerRule::parse("consecutive_reverts>2=pause_all").unwrap()];
+        let circuit_breaker = CircuitBreaker::new(config);
+
+        // A success in between resets the run of reverts
+        for succeeded in [false, false, true, false, false] {
+            circuit_breaker
+                .record_liquidation_attempt(succeeded, Some(gas_multiplier_to_wei(1)))
+                .await
+                .unwrap();
+        }
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
+
+        circuit_breaker
+            .record_liquidation_attempt(false, Some(gas_multiplier_to_wei(1)))
+            .await
+            .unwrap();
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
+        assert_eq!(circuit_breaker.get_stats().rule_triggers, 1);
+    }
+
+    #[tokio::test]
+    async fn test_pause_asset_rule_leaves_other_assets_running() {
+        let mut config = create_test_config();
+        config.circuit_breaker_rules = vec![
+            TriggerRule::parse("volatility>5=pause_asset").unwrap(),
+            TriggerRule::parse("gas_multiplier>3=alert_only").unwrap(),
+        ];
+        let circuit_breaker = CircuitBreaker::new(config);
+        let (weth, usdc) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let price = |usd: u128| Some(U256::from(usd * 100_000_000));
+
+        for (asset, usd) in [(weth, 2000), (usdc, 1), (weth, 1600)] {
+            circuit_breaker
+                .record_asset_price_update(Some(asset), price(usd), Some(gas_multiplier_to_wei(10)))
+                .await
+                .unwrap();
+        }
+
+        // Volatility and gas would have opened the breaker under the built-in thresholds
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Closed);
+        assert!(circuit_breaker.is_liquidation_allowed());
+        assert!(circuit_breaker.is_asset_paused(weth));
+        assert!(!circuit_breaker.is_asset_paused(usdc));
+
+        let stats = circuit_breaker.get_stats();
+        assert_eq!(stats.asset_pauses, 1);
+        // The gas alert fires once per cooldown, not on every data point
+        assert_eq!(stats.rule_alerts, 1);
+    }
+
+    #[tokio::test]
+    async fn test_fast_recovery_shortens_cooldown() {
+        let mut config = create_test_config();
+        config.circuit_breaker_cooldown_secs = 300; // Far longer than the test runs
+        config.fast_recovery_stable_secs = 1;
+
+        let fast = CircuitBreaker::new(config.clone());
+        config.fast_recovery_stable_secs = 0;
+        let slow = CircuitBreaker::new(config);
+
+        let price = |usd: u128| Some(U256::from(usd * 10u128.pow(18)));
+
+        for circuit_breaker in [&fast, &slow] {
+            // Flash crash: -20% trips the breaker
+            circuit_breaker
+                .record_price_update(price(50000), Some(gas_multiplier_to_wei(2)))
+                .await
+                .unwrap();
+            circuit_breaker
+                .record_price_update(price(40000), Some(gas_multiplier_to_wei(2)))
+                .await
+                .unwrap();
+            assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
+        }
+
+        // Prices recover quickly and stay stable with normal gas for 1.2s
+        for usd in [49000, 49100, 49050] {
+            for circuit_breaker in [&fast, &slow] {
+                circuit_breaker
+                    .record_price_update(price(usd), Some(gas_multiplier_to_wei(2)))
+                    .await
+                    .unwrap();
+            }
+            sleep(Duration::from_millis(600)).await;
+        }
+
+        // Fast recovery goes half-open long before the 300s cooldown; without it we stay open
+        assert_eq!(fast.get_state(), CircuitBreakerState::HalfOpen);
+        assert_eq!(fast.get_stats().fast_recoveries, 1);
+        assert_eq!(slow.get_state(), CircuitBreakerState::Open);
+        assert_eq!(slow.get_stats().fast_recoveries, 0);
+
+        // The crash prices no longer count, so normal conditions close the breaker
+        fast.record_price_update(price(49010), Some(gas_multiplier_to_wei(2)))
+            .await
+            .unwrap();
+        assert_eq!(fast.get_state(), CircuitBreakerState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_fast_recovery_resets_on_renewed_volatility() {
+        let mut config = create_test_config();
+        config.circuit_breaker_cooldown_secs = 300;
+        config.fast_recovery_stable_secs = 1;
+        let circuit_breaker = CircuitBreaker::new(config);
+
+        let price = |usd: u128| Some(U256::from(usd * 10u128.pow(18)));
+
+        for usd in [50000, 40000, 49000] {
+            circuit_breaker
+                .record_price_update(price(usd), Some(gas_multiplier_to_wei(2)))
+                .await
+                .unwrap();
+        }
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
+
+        sleep(Duration::from_millis(700)).await;
+        // Another swing restarts the stable period
+        circuit_breaker
+            .record_price_update(price(44000), Some(gas_multiplier_to_wei(2)))
+            .await
+            .unwrap();
+
+        sleep(Duration::from_millis(700)).await;
+        circuit_breaker
+            .record_price_update(price(44100), Some(gas_multiplier_to_wei(2)))
+            .await
+            .unwrap();
+
+        // Only 0.7s of stability since the last swing - still open
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
+    }
+
     #[tokio::test]
     async fn test_blocked_liquidation_counting() {
         let config = create_test_config();
@@ -1562,4 +2478,41 @@ mod tests {
         sleep(Duration::from_secs(21)).await; // Total 31 seconds
         assert!(circuit_breaker.is_liquidation_allowed());
     }
+
+    #[tokio::test]
+    async fn test_restored_breaker_honors_remaining_cooldown() {
+        let config = create_test_config(); // 5 second cooldown
+        let previous = CircuitBreaker::new(config.clone());
+        *previous.state.write() = CircuitBreakerState::Open;
+        *previous.last_activation.write() = Some(Instant::now() - Duration::from_secs(3));
+        previous.stats.write().total_activations = 3;
+        previous.market_data.write().push_back(MarketDataPoint {
+            timestamp: Instant::now(),
+            price: Some(U256::from(2_000u64)),
+            asset: None,
+            liquidation_occurred: false,
+            liquidation_attempted: true,
+            gas_price_wei: None,
+        });
+        let snapshot: CircuitBreakerSnapshot =
+            serde_json::from_str(&serde_json::to_string(&previous.snapshot()).unwrap()).unwrap();
+
+        let circuit_breaker = CircuitBreaker::new(config.clone());
+        circuit_breaker.restore(snapshot.clone());
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Open);
+        assert_eq!(circuit_breaker.get_stats().total_activations, 3);
+        assert_eq!(circuit_breaker.market_data.read().len(), 1);
+        assert!(!circuit_breaker.is_liquidation_allowed());
+
+        // Only the remaining ~2 seconds of the cooldown are waited out, not a fresh 5
+        sleep(Duration::from_millis(3_500)).await;
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::HalfOpen);
+
+        // Configuration disabling the breaker wins over the saved state
+        let mut disabled_config = config;
+        disabled_config.circuit_breaker_enabled = false;
+        let circuit_breaker = CircuitBreaker::new(disabled_config);
+        circuit_breaker.restore(snapshot);
+        assert_eq!(circuit_breaker.get_state(), CircuitBreakerState::Disabled);
+    }
 }
@@ -1,7 +1,314 @@
 use alloy_primitives::{Address, U256};
+use arc_swap::ArcSwap;
 use eyre::Result;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env::VarError;
+use std::fmt::Display;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::networks::{self, NetworkAddresses};
+use crate::models::{
+    AggregatorKind, AlertRoute, ApprovalMode, BusKind, DiscoveryMethod, ExecutionMode, FeeProfile, FlashLoanSource,
+    MempoolSource, ProfitabilityModel, ScanTier, SecondaryPriceFeed, SimulationMode,
+    SubmissionStrategy, SwapRoute, TriggerRule, NOTIFICATION_CHANNELS,
+};
 use tracing::{info, warn};
 
+/// Config file picked up from the working directory when no path is given
+pub const DEFAULT_CONFIG_FILE: &str = "config.toml";
+
+/// Chain run when none is configured (Base mainnet)
+pub const DEFAULT_CHAIN_ID: u64 = 8453;
+
+/// Block time assumed for chains without built-in addresses
+const DEFAULT_BLOCK_TIME_SECS: u64 = 12;
+
+/// Blocks the chain head may go without advancing before heartbeats report failure
+const HEARTBEAT_STALL_BLOCKS: u64 = 10;
+
+/// Placeholder printed instead of secrets
+const REDACTED: &str = "<redacted>";
+
+/// Live configuration shared by the running components; hot reloads swap in a new value
+pub type SharedConfig = Arc<ArcSwap<BotConfig>>;
+
+pub fn shared(config: BotConfig) -> SharedConfig {
+    Arc::new(ArcSwap::from_pointee(config))
+}
+
+/// Copy each listed field from `$new` into `$current` when it differs, recording its name
+macro_rules! reload_fields {
+    ($current:expr, $new:expr, $changed:expr, [$($field:ident),* $(,)?]) => {
+        $(
+            if $current.$field != $new.$field {
+                $current.$field = $new.$field.clone();
+                $changed.push(stringify!($field));
+            }
+        )*
+    };
+}
+
+/// Names of the listed fields that differ between two configurations
+macro_rules! changed_fields {
+    ($current:expr, $new:expr, [$($field:ident),* $(,)?]) => {{
+        let mut changed = Vec::new();
+        $(
+            if $current.$field != $new.$field {
+                changed.push(stringify!($field));
+            }
+        )*
+        changed
+    }};
+}
+
+/// Log a configuration problem and remember it for strict validation
+macro_rules! config_warn {
+    ($source:expr, $($arg:tt)*) => {{
+        let message = format!($($arg)*);
+        warn!("{}", message);
+        $source.problem(message);
+    }};
+}
+
+/// Where configuration values come from: environment variables, layered over an optional
+/// TOML file whose keys are the lowercase variable names (`min_profit_threshold = "..."`).
+/// A file can list several chains as `[[chains]]` sections; each section's keys take
+/// precedence over both the environment and the file's top-level keys for that chain.
+#[derive(Debug, Default)]
+pub struct ConfigSource {
+    file_path: Option<PathBuf>,
+    file_values: BTreeMap<String, String>, // Keyed by environment variable name
+    chain_values: BTreeMap<String, String>, // Keys of the chain section this source resolves
+    chain_sections: Vec<BTreeMap<String, String>>, // Every [[chains]] section of the file
+    requested: RefCell<BTreeSet<String>>,
+    problems: RefCell<Vec<String>>,
+}
+
+/// Flatten a TOML table of scalar values, keyed by environment variable name
+fn scalar_values(table: toml::Table) -> Result<BTreeMap<String, String>> {
+    let mut values = BTreeMap::new();
+    for (key, value) in table {
+        let value = match value {
+            toml::Value::String(value) => value,
+            toml::Value::Integer(value) => value.to_string(),
+            toml::Value::Float(value) => value.to_string(),
+            toml::Value::Boolean(value) => value.to_string(),
+            other => {
+                return Err(eyre::eyre!(
+                    "key '{}' must be a string, number or boolean, found {}",
+                    key,
+                    other.type_str()
+                ))
+            }
+        };
+        values.insert(key.to_uppercase(), value);
+    }
+    Ok(values)
+}
+
+impl ConfigSource {
+    pub fn from_env_only() -> Self {
+        Self::default()
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read_to_string(path)
+            .map_err(|e| eyre::eyre!("Failed to read config file {}: {}", path.display(), e))?;
+        let mut source = Self::from_toml_str(&contents)
+            .map_err(|e| eyre::eyre!("Invalid config file {}: {}", path.display(), e))?;
+        source.file_path = Some(path.to_path_buf());
+        info!("📄 Loaded configuration file {}", path.display());
+        Ok(source)
+    }
+
+    /// Parse a flat TOML table of scalar values, plus optional `[[chains]]` sections of them
+    pub fn from_toml_str(contents: &str) -> Result<Self> {
+        let mut table: toml::Table = contents.parse()?;
+
+        let mut chain_sections = Vec::new();
+        if let Some(chains) = table.remove("chains") {
+            let toml::Value::Array(sections) = chains else {
+                return Err(eyre::eyre!("'chains' must be a list of [[chains]] sections"));
+            };
+            for section in sections {
+                let toml::Value::Table(section) = section else {
+                    return Err(eyre::eyre!("'chains' must be a list of [[chains]] sections"));
+                };
+                chain_sections.push(
+                    scalar_values(section).map_err(|e| eyre::eyre!("[[chains]] {}", e))?,
+                );
+            }
+        }
+
+        Ok(Self {
+            file_values: scalar_values(table)?,
+            chain_sections,
+            ..Self::default()
+        })
+    }
+
+    /// One source per `[[chains]]` section, or this source alone when the file has none
+    pub fn chain_sources(self) -> Vec<Self> {
+        if self.chain_sections.is_empty() {
+            return vec![self];
+        }
+        self.chain_sections
+            .iter()
+            .map(|section| Self {
+                file_path: self.file_path.clone(),
+                file_values: self.file_values.clone(),
+                chain_values: section.clone(),
+                ..Self::default()
+            })
+            .collect()
+    }
+
+    /// Value of `key`: the chain section entry, then the environment variable, then the
+    /// config file's top-level entry
+    pub fn var(&self, key: &str) -> std::result::Result<String, VarError> {
+        self.requested.borrow_mut().insert(key.to_string());
+        if let Some(value) = self.chain_values.get(key) {
+            return Ok(value.clone());
+        }
+        match std::env::var(key) {
+            Err(VarError::NotPresent) => self
+                .file_values
+                .get(key)
+                .cloned()
+                .ok_or(VarError::NotPresent),
+            result => result,
+        }
+    }
+
+    /// `key` parsed as a bool (case-insensitive), or `default` when unset. An invalid value is
+    /// reported and falls back to `default`.
+    pub fn bool_var(&self, key: &str, default: bool) -> bool {
+        let fallback = format!("Using default {}", default);
+        self.parse_checked(key, |value| value.to_lowercase().parse(), "", |_| true, &fallback)
+            .unwrap_or(default)
+    }
+
+    /// `key` parsed as a number (or any other `T`), or `default` when unset. An invalid value is
+    /// reported and falls back to `default`.
+    pub fn parsed_var<T>(&self, key: &str, default: T) -> T
+    where
+        T: FromStr + Display,
+        T::Err: Display,
+    {
+        self.checked_var(key, default, "", |_| true)
+    }
+
+    /// Like `parsed_var`, but zero is reported as invalid too
+    pub fn nonzero_var<T>(&self, key: &str, default: T) -> T
+    where
+        T: FromStr + Display + Default + PartialEq,
+        T::Err: Display,
+    {
+        self.checked_var(key, default, "must be greater than zero", |value| {
+            *value != T::default()
+        })
+    }
+
+    /// Like `parsed_var`, but a value failing `valid` is reported as invalid too, with
+    /// `requirement` saying what a valid one looks like
+    pub fn checked_var<T>(
+        &self,
+        key: &str,
+        default: T,
+        requirement: &str,
+        valid: impl Fn(&T) -> bool,
+    ) -> T
+    where
+        T: FromStr + Display,
+        T::Err: Display,
+    {
+        let fallback = format!("Using default {}", default);
+        self.parse_checked(key, str::parse, requirement, valid, &fallback)
+            .unwrap_or(default)
+    }
+
+    /// `key` parsed as `T`, or None when unset. An invalid value is reported and left unset.
+    pub fn optional_var<T>(&self, key: &str) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.optional_checked_var(key, "", |_| true)
+    }
+
+    /// Like `optional_var`, but zero is reported as invalid too
+    pub fn optional_nonzero_var<T>(&self, key: &str) -> Option<T>
+    where
+        T: FromStr + Default + PartialEq,
+        T::Err: Display,
+    {
+        self.optional_checked_var(key, "must be greater than zero", |value| {
+            *value != T::default()
+        })
+    }
+
+    /// Like `optional_var`, but a value failing `valid` is reported as invalid too, with
+    /// `requirement` saying what a valid one looks like
+    pub fn optional_checked_var<T>(
+        &self,
+        key: &str,
+        requirement: &str,
+        valid: impl Fn(&T) -> bool,
+    ) -> Option<T>
+    where
+        T: FromStr,
+        T::Err: Display,
+    {
+        self.parse_checked(key, str::parse, requirement, valid, "Leaving it unset")
+    }
+
+    /// The trimmed value of `key` run through `parse`, when set and valid. Anything else set is
+    /// reported along with the `fallback` the caller uses instead, and yields None.
+    fn parse_checked<T, E: Display>(
+        &self,
+        key: &str,
+        parse: impl Fn(&str) -> std::result::Result<T, E>,
+        requirement: &str,
+        valid: impl Fn(&T) -> bool,
+        fallback: &str,
+    ) -> Option<T> {
+        let value = self.var(key).ok()?;
+        let reason = match parse(value.trim()) {
+            Ok(parsed) if valid(&parsed) => return Some(parsed),
+            Ok(_) => requirement.to_string(),
+            Err(e) => e.to_string(),
+        };
+        config_warn!(self, "Invalid {} '{}': {}. {}.", key, value, reason, fallback);
+        None
+    }
+
+    fn problem(&self, message: String) {
+        self.problems.borrow_mut().push(message);
+    }
+
+    /// Invalid values reported while resolving, then config file keys nothing reads
+    pub fn problems(&self) -> Vec<String> {
+        let mut problems = self.problems.borrow().clone();
+        let requested = self.requested.borrow();
+        for key in self.file_values.keys().chain(self.chain_values.keys()) {
+            if !requested.contains(key) {
+                problems.push(format!(
+                    "Unknown key '{}' in {}",
+                    key.to_lowercase(),
+                    self.file_path
+                        .as_ref()
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|| "config file".to_string())
+                ));
+            }
+        }
+        problems
+    }
+}
+
 /// Helper function to convert wei to ETH as f64 for display
 fn wei_to_eth_f64(wei: U256) -> f64 {
     const ETH_DECIMALS: U256 = U256::from_limbs([1_000_000_000_000_000_000u64, 0, 0, 0]); // 10^18
@@ -10,8 +317,46 @@ fn wei_to_eth_f64(wei: U256) -> f64 {
     eth_u128 as f64 + (remainder as f64 / ETH_DECIMALS.to::<u128>() as f64)
 }
 
+/// Split a comma-separated URL list, dropping blanks
+fn parse_url_list(value: Option<String>) -> Vec<String> {
+    value
+        .map(|list| {
+            list.split(',')
+                .map(|url| url.trim().to_string())
+                .filter(|url| !url.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Settings that must differ between chains run by one process: the chain id keys the shared
+/// database, and each chain's servers need their own listen address
+fn shared_chain_settings(configs: &[BotConfig]) -> Vec<String> {
+    let mut problems = Vec::new();
+    let mut seen = BTreeSet::new();
+    for config in configs {
+        let listen_addrs = [
+            ("ADMIN_LISTEN_ADDR", &config.admin_listen_addr),
+            ("METRICS_LISTEN_ADDR", &config.metrics_listen_addr),
+            ("DASHBOARD_LISTEN_ADDR", &config.dashboard_listen_addr),
+            ("GRPC_LISTEN_ADDR", &config.grpc_listen_addr),
+        ];
+        if !seen.insert(("CHAIN_ID", config.chain_id.to_string())) {
+            problems.push(format!("CHAIN_ID {} is configured by more than one chain", config.chain_id));
+        }
+        for (key, addr) in listen_addrs {
+            if let Some(addr) = addr {
+                if !seen.insert((key, addr.clone())) {
+                    problems.push(format!("{} {} is used by more than one chain", key, addr));
+                }
+            }
+        }
+    }
+    problems
+}
+
 // Asset loading method configuration
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum AssetLoadingMethod {
     /// Load from Aave protocol with fallback to hardcoded values
     DynamicWithFallback,
@@ -23,27 +368,106 @@ pub enum AssetLoadingMethod {
     Hardcoded,
 }
 
+/// Where the bot's signing keys live; every listed key is one executor wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignerBackend {
+    /// Plaintext hex keys from PRIVATE_KEY
+    PrivateKey,
+    /// Encrypted JSON keystores sharing one password, from KEYSTORE_PASSWORD or a prompt at startup
+    Keystore { paths: Vec<PathBuf>, password: Option<String> },
+    /// AWS KMS secp256k1 keys; credentials and region come from the standard AWS environment
+    AwsKms { key_ids: Vec<String> },
+    /// Ledger device accounts at Ledger Live derivation indices
+    Ledger { indices: Vec<usize> },
+}
+
+impl SignerBackend {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignerBackend::PrivateKey => "private_key",
+            SignerBackend::Keystore { .. } => "keystore",
+            SignerBackend::AwsKms { .. } => "aws_kms",
+            SignerBackend::Ledger { .. } => "ledger",
+        }
+    }
+}
+
 // Configuration struct
 #[derive(Debug, Clone)]
 pub struct BotConfig {
+    pub chain_id: u64, // Chain this instance runs on; scopes its rows in the shared database
+    pub chain_name: String, // Label for this chain in logs
+    pub network: NetworkAddresses, // Aave v3 and token contracts on this chain (built-in per chain, overridable)
     pub rpc_url: String,
     pub ws_url: String,
-    pub private_key: String,
+    pub rpc_fallback_urls: Vec<String>, // Extra HTTP endpoints the provider pool fails over to and spreads reads across
+    pub ws_fallback_urls: Vec<String>, // WebSocket endpoints tried in order when WS_URL can't be connected
+    pub provider_health_check_interval_secs: u64, // How often every RPC endpoint is probed (0 = only passive failure tracking)
+    pub rpc_rate_limit_rps: u32, // Requests per second allowed to each RPC endpoint (0 = unlimited)
+    pub rpc_endpoint_rate_limits: BTreeMap<String, u32>, // Per-endpoint overrides of RPC_RATE_LIMIT_RPS, by URL
+    pub signer_backend: SignerBackend, // Where the signing keys live (private_key, keystore, aws_kms or ledger)
+    pub private_key: String, // Comma-separated hex keys for the private_key backend (empty otherwise)
+    pub wallet_gas_reserve_wei: U256, // Executor wallets with less native balance are skipped (0 = never skip)
+    pub weth_gas_target_wei: Option<U256>, // Unwrap WETH after liquidations until a wallet holds this much native ETH (None = never)
+    pub weth_wrap_above_wei: Option<U256>, // Wrap native ETH above the gas target once a wallet holds more than this (None = never)
     pub liquidator_contract: Option<Address>,
     pub min_profit_threshold: U256,
-    pub gas_price_multiplier: u64,
+    pub min_profit_pct_of_debt_bps: u64, // Minimum profit as basis points of debt covered (0 = disabled)
+    pub onchain_min_profit_margin_bps: Option<u64>, // Share of estimated net profit the market may take before the contract reverts (None = no on-chain check)
+    pub liquidation_hf_margin: U256, // Only execute when HF < 1.0 - margin (18 decimals, 0 = disabled)
+    pub profitability_model: ProfitabilityModel, // Live model that drives liquidation decisions
+    pub shadow_profitability: Option<ProfitabilityModel>, // Model priced alongside the live one for comparison only (None = disabled)
+    pub shadow_divergence_threshold_bps: u64, // Record shadow/live profit disagreements larger than this
+    pub gas_fee_profile: Option<FeeProfile>, // Fee profile for every liquidation (None = chosen by profit size)
+    pub gas_fee_history_blocks: u64, // Blocks sampled per eth_feeHistory call for priority fee percentiles
+    pub gas_conservative_profit_multiple: u64, // Profit below this multiple of the required minimum bids conservatively
+    pub gas_aggressive_profit_multiple: u64, // Profit at or above this multiple of the required minimum bids aggressively
+    pub racing_max_profit_share_bps: u64, // Most of a liquidation's expected profit its priority fee may spend (0 = no racing)
+    pub racing_win_rate_window: usize, // Recent liquidations whose win rate scales racing bids
+    pub mempool_source: MempoolSource, // Pending transaction feed watched for competing liquidations (off = not watched)
     pub target_user: Option<Address>,
     pub database_url: String,
     pub health_factor_threshold: U256, // Alert/at-risk threshold (e.g., 1.1)
                                         // Should be > 1.0 (liquidation threshold) for early warning
+    pub min_hf_change_to_persist_bps: u64, // Health factor moves smaller than this are neither persisted nor logged
+    pub position_write_batch_size: usize, // Scanned positions buffered before a batched database write
+    pub position_write_flush_interval_ms: u64, // Longest a buffered position waits before being written
+    pub event_queue_capacity: usize, // Events each of the position refresh and control lanes holds before dropping
+    pub liquidation_queue_capacity: usize, // Liquidations the event and priority liquidation queues hold
+    pub user_refresh_min_interval_secs: u64, // Shortest gap between refreshes of a user not at risk (0 = refresh on every event)
+    pub health_check_parallelism: usize, // Position refreshes the event processor runs at once
+    pub liquidation_parallelism: usize, // Queued liquidations of different users executed at once
+    pub liquidation_retry_max_attempts: u32, // Attempts per opportunity on transient failures, including the first
+    pub liquidation_retry_base_delay_ms: u64, // Delay before the first retry; doubles per retry
+    pub liquidation_retry_max_delay_ms: u64, // Upper bound on the retry delay
+    pub shutdown_timeout_secs: u64, // Longest shutdown waits for in-flight liquidations before exiting anyway
     pub monitoring_interval_secs: u64,
+    pub periodic_scan_interval_secs: u64, // Base interval of the periodic at-risk scan
+    pub adaptive_scan_interval_enabled: bool, // Scale the periodic scan interval with market volatility
+    pub min_scan_interval_secs: u64, // Fastest adaptive scan interval (at/above the volatility threshold)
+    pub max_scan_interval_secs: u64, // Slowest adaptive scan interval
     pub asset_loading_method: AssetLoadingMethod,
     pub at_risk_scan_limit: Option<usize>, // Max users to check per scan cycle (None = unlimited)
     pub full_rescan_interval_minutes: u64, // How often to do a full rescan in minutes
+    pub scan_tiers: Vec<ScanTier>, // Health factor tiers re-checked at their own interval, lowest first (empty = single at-risk scan interval)
+    pub block_scan_enabled: bool, // Re-check the riskiest positions on every new block instead of the at-risk scan timer (needs WS_URL)
+    pub block_scan_top_n: usize, // Positions re-checked per block, lowest health factor first
+    pub block_scan_every_n_blocks: u64, // Scan only every Nth block (1 = every block)
+    pub block_scan_budget_ms: u64, // Longest one block's scan may run; users not reached wait for the next block
+    pub catch_up_prioritize_by_debt: bool, // Check discovered users largest known debt first on startup
+    pub discovery_method: DiscoveryMethod, // Bootstrap borrowers by scanning pool logs or from an Aave V3 subgraph
+    pub discovery_subgraph_url: Option<String>, // GraphQL endpoint queried when discovery_method = subgraph
     // User archival configuration
     pub archive_zero_debt_users: bool, // Whether to archive users with zero debt
     pub zero_debt_cooldown_hours: u64, // Hours to wait before archiving users with zero debt
+    pub archival_run_interval_secs: Option<u64>, // Seconds between archival runs (None = derived from cooldown)
     pub safe_health_factor_threshold: U256, // Health factor threshold above which users are considered "safe" (e.g., 10.0)
+    pub price_history_enabled: bool, // Record every polled oracle price in the price_feeds table
+    pub price_history_minute_after_hours: u64, // Raw price samples older than this are downsampled to per-minute rows
+    pub price_history_hour_after_hours: u64, // Per-minute price rows older than this are downsampled to per-hour rows
+    pub price_history_retention_days: u64, // Price history older than this is deleted (0 = keep forever)
+    pub position_history_retention_days: u64, // Health factor snapshots older than this are deleted (0 = keep forever)
+    pub local_health_factor_enabled: bool, // Recompute health factors locally on price ticks; only confirm users crossing 1.0 via RPC
     
     // Circuit breaker configuration for extreme market conditions
     pub circuit_breaker_enabled: bool, // Enable/disable circuit breaker functionality
@@ -51,132 +475,648 @@ pub struct BotConfig {
     pub max_liquidations_per_minute: u64, // Maximum liquidations per minute before triggering circuit breaker
     pub circuit_breaker_monitoring_window_secs: u64, // Time window for monitoring market conditions (e.g., 300 for 5 minutes)
     pub circuit_breaker_cooldown_secs: u64, // Time to wait before resuming operations after circuit breaker activation
+    pub fast_recovery_stable_secs: u64, // Go half-open early once volatility and gas stay normal this long (0 = disabled)
     pub min_gas_price_multiplier: u64, // Minimum gas price multiplier to consider extreme conditions
     pub max_gas_price_multiplier: u64, // Maximum gas price multiplier to trigger circuit breaker
+    pub circuit_breaker_rules: Vec<TriggerRule>, // Extra trigger rules; one on volatility, liquidations/min or gas replaces that built-in check
     
     // High-priority liquidation pipeline configuration
+    pub real_time_monitoring_enabled: bool, // Use WebSocket subscriptions when WS_URL allows (false = always poll)
     pub ws_fast_path_enabled: bool, // Enable WebSocket fast path for immediate liquidation detection
+    pub min_position_age_blocks: u64, // Blocks a user must be observed before liquidation (0 = disabled)
+    pub pool_pause_check_enabled: bool, // Suspend liquidations while the pool is paused by governance
+    pub pool_pause_check_interval_secs: u64, // How often to check the pool's global pause state
+    pub reserve_config_refresh_secs: u64, // Re-read every reserve's configuration (bonus, thresholds, flags, caps) this often (0 = startup and reserve updates only)
+    pub oracle_guard_enabled: bool, // Suspend liquidations while a price feed is stale, zero or disagrees with other sources
+    pub oracle_guard_interval_secs: u64, // How often feeds are checked for staleness and deviation
+    pub oracle_staleness_grace_secs: u64, // Slack past a feed's heartbeat before its price counts as stale
+    pub oracle_max_deviation_bps: u64, // Largest allowed gap between Chainlink and the Aave oracle or a secondary feed
+    pub oracle_secondary_feeds: Vec<SecondaryPriceFeed>, // Extra Chainlink-compatible feeds per asset, e.g. asset=feed
+    pub pool_code_check_enabled: bool, // Refuse to start if the pool address has no contract code
+    pub liquidator_contract_check_enabled: bool, // Refuse to start if the liquidator contract isn't deployed or lacks liquidate()
+    pub liquidation_audit_enabled: bool, // Record intent/settlement audit entries for each liquidation
+    pub persistent_liquidation_queue_enabled: bool, // Persist queued liquidations so they survive restarts
+    pub value_at_risk_bonus_bps: u64, // Expected liquidation bonus used to compute total value at risk
+    pub value_at_risk_alert_threshold: Option<U256>, // Alert when total value at risk exceeds this (base currency, 8 decimals)
+    pub strategy_label: String, // Tag stamped onto every recorded liquidation for per-strategy PnL
+    pub verbose_per_item_logging: bool, // Log every asset/user in polling and scan loops at info level (otherwise debug)
+    pub event_record_path: Option<String>, // Append raw logs and processor events to this JSON-lines file for replay
+    pub min_operator_balance_wei: U256, // Signer native balance alerts escalate as it approaches this (0 = disabled)
+    pub operator_balance_halt_enabled: bool, // Halt liquidations while the signer balance is below the minimum
+    pub balance_check_interval_secs: u64, // How often wallet native and debt asset balances are re-read (0 = only after liquidations)
+    pub min_token_balances: BTreeMap<String, U256>, // Per-symbol minimum executor wallet token balances (base units) to warn below
+    pub realized_profit_alert_bps: u64, // Alert when realized profit repeatedly falls below this share of the estimate (0 = disabled)
+
+    // Treasury sweep configuration
+    pub treasury_cold_wallet: Option<Address>, // Sweep executor wallet profits here (None = disabled)
+    pub treasury_sweep_thresholds: BTreeMap<String, U256>, // Per-symbol balances (base units, ETH = native) above which a wallet is swept
+    pub treasury_gas_reserve_wei: U256, // Native ETH left in each wallet for gas when sweeping ETH
+    pub treasury_sweep_interval_secs: u64, // How often wallets are checked for sweeping, besides after each executed liquidation
+
+    // Inventory configuration
+    pub inventory_target_asset: Option<String>, // Symbol the executor wallets' inventory should be held in (None = tracking disabled)
+    pub inventory_max_deviation_bps: u64, // Swap a non-target asset into the target once its share of inventory value exceeds this
+    pub inventory_check_interval_secs: u64, // How often inventory is valued and rebalanced
+
+    // External executor webhook configuration
+    pub opportunity_webhook_url: Option<String>, // POST validated liquidation opportunities here (None = disabled)
+    pub opportunity_webhook_execute: bool, // Also execute locally when the webhook is configured (false = webhook only)
+
+    // Transaction submission configuration
+    pub submission_strategy: SubmissionStrategy, // Send liquidations to the public mempool, a private relay, or both
+    pub private_relay_url: Option<String>, // Flashbots Protect / MEV-Share style relay endpoint for bundles
+    pub private_relay_max_blocks: u64, // Resubmit a missed bundle for this many blocks before giving up
+
+    // Collateral swap configuration
+    pub collateral_swap_routes: Vec<SwapRoute>, // Uniswap V3 routes converting seized collateral left with the signer (empty = disabled)
+    pub collateral_swap_slippage_bps: u64, // Minimum swap output is the quoter's amount less this tolerance
+    pub swap_aggregator: Option<AggregatorKind>, // Swap collateral with the 1inch or 0x API, inside flash-loan liquidations too (None = Uniswap only)
+    pub swap_aggregator_api_url: Option<String>, // Aggregator API base URL (None = the aggregator's public endpoint)
+    pub swap_aggregator_api_key: Option<String>, // Aggregator API key
+    pub swap_quote_cache_secs: u64, // Reuse an aggregator swap plan for the same pair and amount this long
+    pub swap_approval_mode: ApprovalMode, // Approve exactly each swap's input, or the maximum once per token and spender
+
+    // Flash loan configuration
+    pub flash_loan_sources: Vec<FlashLoanSource>, // Lenders the liquidator contract may borrow from; the cheapest with enough liquidity wins
+    pub wallet_funded_liquidations_enabled: bool, // Repay debt from the executor wallet's balance instead of a flash loan when cheaper
+    pub liquidation_receive_a_token: bool, // Take wallet-funded liquidations' collateral as aTokens instead of swapping it
+    pub a_token_unwind_after_secs: u64, // Withdraw aTokens received from liquidations after holding them this long (0 = never)
+
+    // Execution mode
+    pub execution_mode: ExecutionMode, // live broadcasts; dry_run records what would have been broadcast; simulate stops after profitability checks
+    pub real_execution: bool, // Sign and send transactions (LIQUIDATION_REAL_EXECUTION); otherwise every send is mocked
+
+    // Pre-flight simulation configuration
+    pub simulation_mode: SimulationMode, // Simulate liquidations via eth_call or on an Anvil fork before broadcasting (off = never)
+    pub anvil_path: String, // Anvil binary used for fork simulations
+    pub anvil_port: u16, // Local port the Anvil fork listens on
+    pub tx_stuck_after_secs: u64, // Replace a pending liquidation with higher fees after this many seconds
+    pub tx_fee_bump_pct: u64, // Fee increase per replacement, in percent (at least 10)
+    pub tx_max_fee_bumps: u32, // Replacements per liquidation before it is left pending
+
+    // Additional protocol configuration
+    pub compound_v3_comet_address: Option<Address>, // Also monitor and liquidate this Compound V3 (Comet) market (None = Aave only)
+    pub compound_v3_discovery_blocks: u64, // Blocks of Comet history searched for borrowers on startup
+    pub compound_v3_max_absorb_gas_cost_wei: U256, // Skip absorbs costing more gas than this; Comet pays the absorber no reward
+
+    // Alert notification configuration
+    pub info_webhook_url: Option<String>, // Info-level alerts (successful liquidations, recoveries) are posted here
+    pub critical_webhook_url: Option<String>, // Warning/critical alerts (breaker open, repeated failures, low balance), e.g. a pager
+    pub telegram_bot_token: Option<String>, // Bot that pushes every alert to TELEGRAM_CHAT_ID (None = no Telegram)
+    pub telegram_chat_id: Option<String>, // Chat (user, group or channel id) Telegram alerts are sent to
+    pub discord_webhook_url: Option<String>, // Discord channel webhook every alert is pushed to (None = no Discord)
+    pub slack_webhook_url: Option<String>, // Slack incoming webhook every alert is pushed to (None = no Slack)
+    pub pagerduty_routing_key: Option<String>, // Events API v2 key; critical alerts open incidents (None = no PagerDuty)
+    pub alert_routes: Vec<AlertRoute>, // Per-event-kind channel overrides, e.g. liquidation_succeeded=slack
+
+    // External watchdog heartbeat configuration
+    pub heartbeat_url: Option<String>, // URL pinged periodically to prove liveness (None = disabled)
+    pub heartbeat_interval_secs: u64, // Seconds between heartbeat pings
+    pub heartbeat_stall_secs: u64, // Seconds the chain head may stand still before pings report failure
+
+    // Admin API configuration
+    pub admin_listen_addr: Option<String>, // Address for the admin HTTP API (None = disabled)
+    pub admin_token: Option<String>, // Bearer token required by the admin API
+
+    // Metrics exporter configuration
+    pub metrics_listen_addr: Option<String>, // Address serving Prometheus metrics on /metrics (None = disabled)
+
+    // Web dashboard configuration
+    pub dashboard_listen_addr: Option<String>, // Address serving the operator web dashboard (None = disabled)
+
+    // gRPC feed configuration
+    pub grpc_listen_addr: Option<String>, // Address streaming opportunities, liquidations and breaker changes over gRPC (None = disabled)
+
+    // Message bus configuration
+    pub event_bus_url: Option<String>, // kafka://, nats:// or redis:// URL processor events and execution results are mirrored to (None = disabled)
+    pub event_bus_events_topic: String, // Kafka topic, NATS subject or Redis stream processor events are published to
+    pub event_bus_executions_topic: String, // Kafka topic, NATS subject or Redis stream liquidation results are published to
+
+    // Event webhook configuration
+    pub event_webhook_url: Option<String>, // POST liquidatable positions and executed liquidations here (None = disabled)
+    pub event_webhook_secret: Option<String>, // HMAC-SHA256 key signing each webhook body (None = unsigned)
+    pub event_webhook_max_retries: u32, // Retries of a delivery failing with a transport error, 429 or 5xx
+
+    // Hot reload configuration
+    pub config_file: Option<String>, // File the configuration was loaded from (None = environment only)
+    pub config_reload_interval_secs: u64, // How often the config file is checked for changes (0 = no hot reload)
 }
 
 impl BotConfig {
+    /// Whether transactions are actually signed and sent: only with real execution enabled and
+    /// in live mode. Every send site (liquidations, swaps, approvals, WETH conversions, treasury
+    /// sweeps, aToken unwinds, protocol liquidations) checks this one gate and mocks otherwise.
+    pub fn broadcasts_transactions(&self) -> bool {
+        self.real_execution && self.execution_mode == ExecutionMode::Live
+    }
+
+    /// Load configuration from the environment only, falling back to defaults (with a warning)
+    /// for invalid values
     pub fn from_env() -> Result<Self> {
         dotenvy::dotenv().ok();
+        Self::from_source(&ConfigSource::from_env_only())
+    }
+
+    /// Load one configuration per chain from the environment layered over `config_path` (or
+    /// `config.toml` in the working directory when it exists): one per `[[chains]]` section, or
+    /// a single configuration when the file has none. Returns the resolved configurations along
+    /// with every problem found: invalid values that fell back to a default, unknown file keys
+    /// and settings two chains can't share.
+    pub fn load_chains_with_problems(
+        config_path: Option<&Path>,
+    ) -> Result<(Vec<Self>, Vec<String>)> {
+        dotenvy::dotenv().ok();
 
-        let rpc_url = std::env::var("RPC_URL")
-            .map_err(|_| eyre::eyre!("RPC_URL environment variable not set"))?;
+        let default_path = Path::new(DEFAULT_CONFIG_FILE);
+        let source = match config_path {
+            Some(path) => ConfigSource::from_file(path)?,
+            None if default_path.exists() => ConfigSource::from_file(default_path)?,
+            None => ConfigSource::from_env_only(),
+        };
+
+        let mut configs = Vec::new();
+        let mut problems: Vec<String> = Vec::new();
+        for source in source.chain_sources() {
+            configs.push(Self::from_source(&source)?);
+            // Top-level problems are reported by every chain; keep one copy
+            for problem in source.problems() {
+                if !problems.contains(&problem) {
+                    problems.push(problem);
+                }
+            }
+        }
+        problems.extend(shared_chain_settings(&configs));
+
+        Ok((configs, problems))
+    }
+
+    /// Load configurations like `load_chains_with_problems`, rejecting any problem when a config
+    /// file is in use or CONFIG_STRICT=true. Without either, problems are only logged as before.
+    pub fn load_chains(config_path: Option<&Path>) -> Result<Vec<Self>> {
+        let (configs, problems) = Self::load_chains_with_problems(config_path)?;
+
+        let file_in_use = config_path.is_some() || Path::new(DEFAULT_CONFIG_FILE).exists();
+        // Only the environment can ask for strictness; the file's problems are what it judges
+        let strict = file_in_use || ConfigSource::default().bool_var("CONFIG_STRICT", false);
+
+        if strict && !problems.is_empty() {
+            return Err(eyre::eyre!(
+                "Invalid configuration ({} problem{}):\n  - {}",
+                problems.len(),
+                if problems.len() == 1 { "" } else { "s" },
+                problems.join("\n  - ")
+            ));
+        }
+        Ok(configs)
+    }
+
+    /// Apply the settings that can change while the bot runs (profit and health thresholds,
+    /// circuit breaker limits, scan tuning) from `new`. Everything else keeps its startup value;
+    /// asset lists in particular are not reloadable (see `restart_only_changes`). Returns the
+    /// names of the settings that changed.
+    pub fn apply_reloadable(&mut self, new: &BotConfig) -> Vec<&'static str> {
+        let mut changed = Vec::new();
+        reload_fields!(
+            self,
+            new,
+            changed,
+            [
+                min_profit_threshold,
+                min_profit_pct_of_debt_bps,
+                liquidation_hf_margin,
+                health_factor_threshold,
+                min_hf_change_to_persist_bps,
+                adaptive_scan_interval_enabled,
+                min_scan_interval_secs,
+                max_scan_interval_secs,
+                at_risk_scan_limit,
+                block_scan_top_n,
+                block_scan_every_n_blocks,
+                block_scan_budget_ms,
+                archive_zero_debt_users,
+                zero_debt_cooldown_hours,
+                safe_health_factor_threshold,
+                max_price_volatility_threshold,
+                max_liquidations_per_minute,
+                circuit_breaker_monitoring_window_secs,
+                circuit_breaker_cooldown_secs,
+                fast_recovery_stable_secs,
+                max_gas_price_multiplier,
+                circuit_breaker_rules,
+                value_at_risk_bonus_bps,
+                value_at_risk_alert_threshold,
+                verbose_per_item_logging,
+            ]
+        );
+        changed
+    }
+
+    /// Settings in `new` that differ from these but are only read at startup, so changing them
+    /// takes a restart: endpoints, the signer, execution mode, loop intervals and the asset
+    /// lists. Assets, their swap routes, feeds and balance floors are loaded once into the
+    /// caches every module shares and are not hot-reloaded. Returns their names.
+    pub fn restart_only_changes(&self, new: &BotConfig) -> Vec<&'static str> {
+        changed_fields!(
+            self,
+            new,
+            [
+                rpc_url,
+                ws_url,
+                rpc_fallback_urls,
+                ws_fallback_urls,
+                database_url,
+                signer_backend,
+                private_key,
+                liquidator_contract,
+                execution_mode,
+                real_execution,
+                monitoring_interval_secs,
+                periodic_scan_interval_secs,
+                full_rescan_interval_minutes,
+                scan_tiers,
+                block_scan_enabled,
+                asset_loading_method,
+                oracle_secondary_feeds,
+                collateral_swap_routes,
+                flash_loan_sources,
+                min_token_balances,
+                treasury_sweep_thresholds,
+                min_gas_price_multiplier,
+            ]
+        )
+    }
+
+    /// Copy of the configuration safe to print: secrets are replaced with a placeholder
+    pub fn redacted(&self) -> Self {
+        let mut config = self.clone();
+        config.private_key = REDACTED.to_string();
+        if let SignerBackend::Keystore { password, .. } = &mut config.signer_backend {
+            *password = password.as_ref().map(|_| REDACTED.to_string());
+        }
+        config.admin_token = config.admin_token.map(|_| REDACTED.to_string());
+        config.swap_aggregator_api_key = config
+            .swap_aggregator_api_key
+            .map(|_| REDACTED.to_string());
+        config.event_webhook_secret = config.event_webhook_secret.map(|_| REDACTED.to_string());
+        config
+    }
+
+    fn from_source(source: &ConfigSource) -> Result<Self> {
+
+        let chain_id = source.parsed_var::<u64>("CHAIN_ID", DEFAULT_CHAIN_ID);
+
+        let builtin_network = networks::builtin(chain_id);
+        let chain_name = source.var("CHAIN_NAME")
+            .ok()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or_else(|| match builtin_network {
+                Some(network) => network.name.to_string(),
+                None => format!("chain-{}", chain_id),
+            });
+
+        // Built-in addresses of known chains can be overridden one by one; chains without
+        // built-ins must configure every address
+        let network_address = |key: &str, builtin: Option<Address>| -> Result<Address> {
+            match source.var(key) {
+                Ok(addr_str) => addr_str
+                    .parse::<Address>()
+                    .map_err(|e| eyre::eyre!("Invalid {} '{}': {}", key, addr_str, e)),
+                Err(_) => builtin.ok_or_else(|| {
+                    eyre::eyre!(
+                        "{} not set for chain {}, which has no built-in addresses (environment or config file key {})",
+                        key,
+                        chain_id,
+                        key.to_lowercase()
+                    )
+                }),
+            }
+        };
+        let builtin_addresses = builtin_network.map(|network| network.addresses);
+        let network = NetworkAddresses {
+            pool: network_address("POOL_ADDRESS", builtin_addresses.map(|n| n.pool))?,
+            pool_addresses_provider: network_address(
+                "POOL_ADDRESSES_PROVIDER",
+                builtin_addresses.map(|n| n.pool_addresses_provider),
+            )?,
+            oracle: network_address("ORACLE_ADDRESS", builtin_addresses.map(|n| n.oracle))?,
+            data_provider: network_address(
+                "DATA_PROVIDER_ADDRESS",
+                builtin_addresses.map(|n| n.data_provider),
+            )?,
+            multicall: network_address("MULTICALL_ADDRESS", Some(networks::MULTICALL3))?,
+            weth: network_address("WETH_ADDRESS", builtin_addresses.map(|n| n.weth))?,
+            swap_router: network_address(
+                "SWAP_ROUTER_ADDRESS",
+                builtin_addresses.map(|n| n.swap_router),
+            )?,
+            swap_quoter: network_address(
+                "SWAP_QUOTER_ADDRESS",
+                builtin_addresses.map(|n| n.swap_quoter),
+            )?,
+            // Only needed when FLASH_LOAN_PROVIDERS includes morpho
+            morpho_blue: match source.var("MORPHO_BLUE_ADDRESS") {
+                Ok(_) => Some(network_address("MORPHO_BLUE_ADDRESS", None)?),
+                Err(_) => builtin_addresses.and_then(|n| n.morpho_blue),
+            },
+        };
+
+        let rpc_url = source.var("RPC_URL")
+            .map_err(|_| eyre::eyre!("RPC_URL not set (environment or config file key rpc_url)"))?;
 
         // Try to derive WebSocket URL from HTTP URL if not explicitly set
-        let ws_url = std::env::var("WS_URL").unwrap_or_else(|_| {
+        let ws_url = source.var("WS_URL").unwrap_or_else(|_| {
             rpc_url
                 .replace("http://", "ws://")
                 .replace("https://", "wss://")
         });
 
-        let private_key = std::env::var("PRIVATE_KEY")
-            .map_err(|_| eyre::eyre!("PRIVATE_KEY environment variable not set"))?;
+        let rpc_fallback_urls = parse_url_list(source.var("RPC_FALLBACK_URLS").ok());
+        let ws_fallback_urls = parse_url_list(source.var("WS_FALLBACK_URLS").ok());
 
-        let liquidator_contract = match std::env::var("LIQUIDATOR_CONTRACT") {
-            Ok(addr_str) => match addr_str.parse::<Address>() {
-                Ok(addr) => Some(addr),
-                Err(e) => {
-                    warn!(
-                        "Invalid LIQUIDATOR_CONTRACT address '{}': {}. Using None.",
-                        addr_str, e
-                    );
-                    None
+        let provider_health_check_interval_secs =
+            source.parsed_var::<u64>("PROVIDER_HEALTH_CHECK_INTERVAL_SECS", 30);
+
+        let rpc_rate_limit_rps = source.parsed_var::<u32>("RPC_RATE_LIMIT_RPS", 0);
+
+        // url=rps pairs, e.g. https://base.example/v2/key=25. The URL may itself hold '=', so
+        // the limit is taken from after the last one.
+        let mut rpc_endpoint_rate_limits = BTreeMap::new();
+        for entry in parse_url_list(source.var("RPC_ENDPOINT_RATE_LIMITS").ok()) {
+            match entry
+                .rsplit_once('=')
+                .map(|(url, rps)| (url.trim(), rps.trim().parse::<u32>()))
+            {
+                Some((url, Ok(rps))) if !url.is_empty() => {
+                    rpc_endpoint_rate_limits.insert(url.to_string(), rps);
                 }
-            },
-            Err(_) => None,
-        };
+                _ => config_warn!(
+                    source,
+                    "Invalid RPC_ENDPOINT_RATE_LIMITS entry '{}': expected url=rps. Ignoring it.",
+                    entry
+                ),
+            }
+        }
 
-        let min_profit_threshold = match std::env::var("MIN_PROFIT_THRESHOLD") {
-            Ok(threshold_str) => match threshold_str.parse::<U256>() {
-                Ok(threshold) => {
-                    info!("💰 Using MIN_PROFIT_THRESHOLD from environment: {} wei ({:.6} ETH)", threshold, wei_to_eth_f64(threshold));
-                    threshold
+        let signer_backend = match source
+            .var("SIGNER_BACKEND")
+            .map(|name| name.trim().to_ascii_lowercase())
+            .as_deref()
+        {
+            Ok("private_key") | Err(_) => SignerBackend::PrivateKey,
+            Ok("keystore") => {
+                let paths: Vec<PathBuf> = parse_url_list(source.var("KEYSTORE_PATH").ok())
+                    .into_iter()
+                    .map(PathBuf::from)
+                    .collect();
+                if paths.is_empty() {
+                    return Err(eyre::eyre!("SIGNER_BACKEND=keystore requires KEYSTORE_PATH"));
                 }
-                Err(e) => {
-                    warn!(
-                        "Invalid MIN_PROFIT_THRESHOLD '{}': {}. Using default 0.01 ETH.",
-                        threshold_str, e
-                    );
-                    U256::from(10000000000000000u64) // 0.01 ETH wei default
+                SignerBackend::Keystore {
+                    paths,
+                    password: source.var("KEYSTORE_PASSWORD").ok(),
                 }
-            },
-            Err(_) => {
-                let default_threshold = U256::from(10000000000000000u64); // 0.01 ETH wei default
-                info!("💰 Using default MIN_PROFIT_THRESHOLD: {} wei ({:.6} ETH)", default_threshold, wei_to_eth_f64(default_threshold));
-                default_threshold
             }
+            Ok("aws_kms") => {
+                let key_ids = parse_url_list(source.var("AWS_KMS_KEY_ID").ok());
+                if key_ids.is_empty() {
+                    return Err(eyre::eyre!("SIGNER_BACKEND=aws_kms requires AWS_KMS_KEY_ID"));
+                }
+                SignerBackend::AwsKms { key_ids }
+            }
+            Ok("ledger") => {
+                let mut indices = Vec::new();
+                for index in parse_url_list(source.var("LEDGER_INDEX").ok()) {
+                    indices.push(index.parse::<usize>().map_err(|e| {
+                        eyre::eyre!("Invalid LEDGER_INDEX '{}': {}", index, e)
+                    })?);
+                }
+                if indices.is_empty() {
+                    indices.push(0);
+                }
+                SignerBackend::Ledger { indices }
+            }
+            Ok(other) => {
+                return Err(eyre::eyre!(
+                    "Unknown SIGNER_BACKEND '{}' (expected private_key, keystore, aws_kms or ledger)",
+                    other
+                ))
+            }
+        };
+
+        // Only the private_key backend needs a plaintext key
+        let private_key = match (source.var("PRIVATE_KEY"), &signer_backend) {
+            (Ok(key), SignerBackend::PrivateKey) => key,
+            (Err(_), SignerBackend::PrivateKey) => {
+                return Err(eyre::eyre!(
+                    "PRIVATE_KEY not set (environment or config file key private_key)"
+                ))
+            }
+            (Ok(_), backend) => {
+                config_warn!(
+                    source,
+                    "PRIVATE_KEY is ignored with SIGNER_BACKEND={}",
+                    backend.as_str()
+                );
+                String::new()
+            }
+            (Err(_), _) => String::new(),
         };
 
-        let gas_price_multiplier = match std::env::var("GAS_PRICE_MULTIPLIER") {
-            Ok(multiplier_str) => match multiplier_str.parse::<u64>() {
-                Ok(multiplier) => multiplier,
-                Err(e) => {
-                    warn!(
-                        "Invalid GAS_PRICE_MULTIPLIER '{}': {}. Using default 2.",
-                        multiplier_str, e
+        let wallet_gas_reserve_wei =
+            source.parsed_var::<U256>("WALLET_GAS_RESERVE_WEI", U256::ZERO);
+
+        let weth_gas_target_wei = source.optional_nonzero_var::<U256>("WETH_GAS_TARGET_WEI");
+
+        let weth_wrap_above_wei = match weth_gas_target_wei {
+            Some(target) => source.optional_checked_var::<U256>(
+                "WETH_WRAP_ABOVE_WEI",
+                "must be at least WETH_GAS_TARGET_WEI",
+                |ceiling| *ceiling >= target,
+            ),
+            None => {
+                if source.var("WETH_WRAP_ABOVE_WEI").is_ok() {
+                    config_warn!(
+                        source,
+                        "WETH_WRAP_ABOVE_WEI requires WETH_GAS_TARGET_WEI; ignoring it."
                     );
-                    2
                 }
-            },
-            Err(_) => 2,
+                None
+            }
         };
 
-        let target_user = match std::env::var("TARGET_USER") {
-            Ok(addr_str) => match addr_str.parse::<Address>() {
-                Ok(addr) => Some(addr),
-                Err(e) => {
-                    warn!(
-                        "Invalid TARGET_USER address '{}': {}. Using None.",
-                        addr_str, e
+        let liquidator_contract = source.optional_var::<Address>("LIQUIDATOR_CONTRACT");
+
+        // 0.01 ETH by default
+        let min_profit_threshold = source
+            .parsed_var::<U256>("MIN_PROFIT_THRESHOLD", U256::from(10_000_000_000_000_000u64));
+        info!(
+            "💰 Using MIN_PROFIT_THRESHOLD: {} wei ({:.6} ETH)",
+            min_profit_threshold,
+            wei_to_eth_f64(min_profit_threshold)
+        );
+
+        // Default to absolute threshold only
+        let min_profit_pct_of_debt_bps = source.parsed_var::<u64>("MIN_PROFIT_PCT_OF_DEBT_BPS", 0);
+
+        // Contracts deployed before liquidateWithMinProfit lack the entrypoint, so unset by default
+        let onchain_min_profit_margin_bps = source.optional_checked_var::<u64>(
+            "ONCHAIN_MIN_PROFIT_MARGIN_BPS",
+            "must be between 0 and 10000",
+            |bps| *bps <= 10_000,
+        );
+
+        // Execute everything below 1.0 by default
+        let liquidation_hf_margin = source.parsed_var::<U256>("LIQUIDATION_HF_MARGIN", U256::ZERO);
+
+        let profitability_model = match source.var("PROFITABILITY_MODEL") {
+            Ok(name) => ProfitabilityModel::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid PROFITABILITY_MODEL '{}'. Using default 'flat'.",
+                    name
+                );
+                ProfitabilityModel::FlatSlippage
+            }),
+            Err(_) => ProfitabilityModel::FlatSlippage,
+        };
+
+        let shadow_profitability = match source.var("SHADOW_PROFITABILITY_MODEL") {
+            Ok(name) if name.trim().is_empty() => None,
+            Ok(name) => match ProfitabilityModel::from_name(&name) {
+                Some(model) if model == profitability_model => {
+                    config_warn!(
+                        source,
+                        "SHADOW_PROFITABILITY_MODEL '{}' matches the live model. Shadow comparison disabled.",
+                        name
+                    );
+                    None
+                }
+                Some(model) => Some(model),
+                None => {
+                    config_warn!(
+                        source,
+                        "Invalid SHADOW_PROFITABILITY_MODEL '{}'. Shadow comparison disabled.",
+                        name
                     );
                     None
                 }
             },
-            Err(_) => None,
+            Err(_) => None, // Shadow comparison disabled by default
         };
 
-        let database_url = std::env::var("DATABASE_URL")
-            .unwrap_or_else(|_| "sqlite:liquidation_bot.db".to_string());
+        let shadow_divergence_threshold_bps =
+            source.parsed_var::<u64>("SHADOW_DIVERGENCE_THRESHOLD_BPS", 500);
 
-        let health_factor_threshold = match std::env::var("HEALTH_FACTOR_THRESHOLD") {
-            Ok(threshold_str) => match threshold_str.parse::<U256>() {
-                Ok(threshold) => threshold,
-                Err(e) => {
-                    warn!(
-                        "Invalid HEALTH_FACTOR_THRESHOLD '{}': {}. Using default 1.1.",
-                        threshold_str, e
+        let gas_fee_profile = match source.var("GAS_FEE_PROFILE") {
+            Ok(name) if name.trim().is_empty() || name.trim().eq_ignore_ascii_case("auto") => None,
+            Ok(name) => match FeeProfile::from_name(&name) {
+                Some(profile) => Some(profile),
+                None => {
+                    config_warn!(
+                        source,
+                        "Invalid GAS_FEE_PROFILE '{}'. Expected 'conservative', 'normal', 'aggressive' or 'auto'; choosing by profit size.",
+                        name
                     );
-                    U256::from(1100000000000000000u64) // 1.1 ETH wei default
+                    None
                 }
             },
-            Err(_) => U256::from(1100000000000000000u64), // 1.1 ETH wei default
+            Err(_) => None,
         };
 
-        let monitoring_interval_secs = match std::env::var("MONITORING_INTERVAL_SECS") {
-            Ok(interval_str) => match interval_str.parse::<u64>() {
-                Ok(interval) => {
-                    if interval == 0 {
-                        warn!("MONITORING_INTERVAL_SECS cannot be 0. Using default 5 seconds.");
-                        5
-                    } else {
-                        interval
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        "Invalid MONITORING_INTERVAL_SECS '{}': {}. Using default 5 seconds.",
-                        interval_str, e
-                    );
-                    5
-                }
-            },
-            Err(_) => 5,
+        // eth_feeHistory caps block_count at 1024 on most nodes
+        let gas_fee_history_blocks = source.checked_var::<u64>(
+            "GAS_FEE_HISTORY_BLOCKS",
+            20,
+            "must be between 1 and 1024",
+            |blocks| (1..=1024).contains(blocks),
+        );
+
+        let gas_conservative_profit_multiple =
+            source.parsed_var::<u64>("GAS_CONSERVATIVE_PROFIT_MULTIPLE", 2);
+
+        let gas_aggressive_profit_multiple = source.checked_var::<u64>(
+            "GAS_AGGRESSIVE_PROFIT_MULTIPLE",
+            gas_conservative_profit_multiple.max(10),
+            "must be at least GAS_CONSERVATIVE_PROFIT_MULTIPLE",
+            |multiple| *multiple >= gas_conservative_profit_multiple,
+        );
+
+        let target_user = source.optional_var::<Address>("TARGET_USER");
+
+        let database_url = source.var("DATABASE_URL")
+            .unwrap_or_else(|_| "sqlite:liquidation_bot.db".to_string());
+
+        // 1.1 ETH wei default
+        let health_factor_threshold = source.parsed_var::<U256>(
+            "HEALTH_FACTOR_THRESHOLD",
+            U256::from(1100000000000000000u64),
+        );
+
+        // 1% default
+        let min_hf_change_to_persist_bps =
+            source.parsed_var::<u64>("MIN_HF_CHANGE_TO_PERSIST_BPS", 100);
+
+        let position_write_batch_size =
+            source.nonzero_var::<usize>("POSITION_WRITE_BATCH_SIZE", 100);
+
+        let position_write_flush_interval_ms =
+            source.nonzero_var::<u64>("POSITION_WRITE_FLUSH_INTERVAL_MS", 1000);
+
+        let event_queue_capacity = source.nonzero_var::<usize>("EVENT_QUEUE_CAPACITY", 10_000);
+
+        let liquidation_queue_capacity =
+            source.nonzero_var::<usize>("LIQUIDATION_QUEUE_CAPACITY", 1_000);
+
+        let user_refresh_min_interval_secs =
+            source.parsed_var::<u64>("USER_REFRESH_MIN_INTERVAL_SECS", 5);
+
+        let health_check_parallelism = source.nonzero_var::<usize>("HEALTH_CHECK_PARALLELISM", 32);
+
+        // One liquidation at a time by default
+        let liquidation_parallelism = source.nonzero_var::<usize>("LIQUIDATION_PARALLELISM", 1);
+
+        // The first attempt and two retries
+        let liquidation_retry_max_attempts =
+            source.nonzero_var::<u32>("LIQUIDATION_RETRY_MAX_ATTEMPTS", 3);
+
+        let liquidation_retry_base_delay_ms =
+            source.parsed_var::<u64>("LIQUIDATION_RETRY_BASE_DELAY_MS", 2000);
+
+        let liquidation_retry_max_delay_ms = source.checked_var::<u64>(
+            "LIQUIDATION_RETRY_MAX_DELAY_MS",
+            30000u64.max(liquidation_retry_base_delay_ms),
+            "must be at least LIQUIDATION_RETRY_BASE_DELAY_MS",
+            |delay| *delay >= liquidation_retry_base_delay_ms,
+        );
+
+        let shutdown_timeout_secs = source.parsed_var::<u64>("SHUTDOWN_TIMEOUT_SECS", 60);
+
+        let monitoring_interval_secs = source.nonzero_var::<u64>("MONITORING_INTERVAL_SECS", 5);
+
+        // Periodic at-risk scan runs slower than event-driven updates by default
+        let periodic_scan_interval_secs = source
+            .nonzero_var::<u64>("PERIODIC_SCAN_INTERVAL_SECS", monitoring_interval_secs * 6);
+
+        let adaptive_scan_interval_enabled =
+            source.bool_var("ADAPTIVE_SCAN_INTERVAL_ENABLED", false);
+
+        let min_scan_interval_secs = source.nonzero_var::<u64>("MIN_SCAN_INTERVAL_SECS", 10);
+
+        let max_scan_interval_secs =
+            source.parsed_var::<u64>("MAX_SCAN_INTERVAL_SECS", periodic_scan_interval_secs);
+
+        let max_scan_interval_secs = if max_scan_interval_secs < min_scan_interval_secs {
+            config_warn!(
+                source,
+                "MAX_SCAN_INTERVAL_SECS ({}) is below MIN_SCAN_INTERVAL_SECS ({}). Using the minimum for both.",
+                max_scan_interval_secs, min_scan_interval_secs
+            );
+            min_scan_interval_secs
+        } else {
+            max_scan_interval_secs
         };
 
-        let asset_loading_method = match std::env::var("ASSET_LOADING_METHOD") {
+        let asset_loading_method = match source.var("ASSET_LOADING_METHOD") {
             Ok(method_str) => match method_str.to_lowercase().as_str() {
                 "dynamic" | "dynamic_with_fallback" => AssetLoadingMethod::DynamicWithFallback,
                 "fully_dynamic" | "full_dynamic" => AssetLoadingMethod::FullyDynamic,
@@ -185,194 +1125,1297 @@ impl BotConfig {
                     AssetLoadingMethod::FromFile(path.strip_prefix("file:").unwrap().to_string())
                 }
                 _ => {
-                    warn!("Unknown ASSET_LOADING_METHOD '{}'. Using default 'dynamic_with_fallback'.", method_str);
+                    config_warn!(source, "Unknown ASSET_LOADING_METHOD '{}'. Using default 'dynamic_with_fallback'.", method_str);
                     AssetLoadingMethod::DynamicWithFallback
                 }
             },
             Err(_) => AssetLoadingMethod::DynamicWithFallback,
         };
 
-        let at_risk_scan_limit = match std::env::var("AT_RISK_SCAN_LIMIT") {
-            Ok(limit_str) => match limit_str.parse::<usize>() {
-                Ok(limit) => {
-                    if limit == 0 {
-                        warn!("AT_RISK_SCAN_LIMIT cannot be 0. Using unlimited scanning.");
-                        None
-                    } else {
-                        Some(limit)
-                    }
+        // Unlimited scanning by default
+        let at_risk_scan_limit = source.optional_nonzero_var::<usize>("AT_RISK_SCAN_LIMIT");
+
+        let full_rescan_interval_minutes =
+            source.nonzero_var::<u64>("FULL_RESCAN_INTERVAL_MINUTES", 60);
+
+        // health_factor:interval pairs, e.g. 1.02:block,1.1:5,1.5:60; "off" keeps the single
+        // at-risk scan interval
+        let scan_tiers = match source.var("SCAN_TIERS") {
+            Ok(tiers_str) if tiers_str.trim().eq_ignore_ascii_case("off") => Vec::new(),
+            Ok(tiers_str) => {
+                let mut tiers = parse_url_list(Some(tiers_str))
+                    .into_iter()
+                    .filter_map(|entry| {
+                        let parsed = ScanTier::parse(&entry);
+                        if parsed.is_none() {
+                            config_warn!(
+                                source,
+                                "Invalid SCAN_TIERS entry '{}'. Expected health_factor:seconds or health_factor:block; skipping.",
+                                entry
+                            );
+                        }
+                        parsed
+                    })
+                    .collect::<Vec<_>>();
+                tiers.sort_by_key(|tier| tier.max_health_factor);
+                tiers
+            }
+            Err(_) => ScanTier::default_tiers(),
+        };
+
+        // Default to the at-risk scan timer
+        let block_scan_enabled = source.bool_var("BLOCK_SCAN_ENABLED", false);
+
+        let block_scan_top_n = source.nonzero_var::<usize>("BLOCK_SCAN_TOP_N", 100);
+
+        let block_scan_every_n_blocks = source.nonzero_var::<u64>("BLOCK_SCAN_EVERY_N_BLOCKS", 1);
+
+        let block_scan_budget_ms = source.nonzero_var::<u64>("BLOCK_SCAN_BUDGET_MS", 1500);
+
+        let catch_up_prioritize_by_debt = source.bool_var("CATCH_UP_PRIORITIZE_BY_DEBT", true);
+
+        let discovery_subgraph_url = source.var("DISCOVERY_SUBGRAPH_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let discovery_method = match source.var("DISCOVERY_METHOD") {
+            Ok(name) => DiscoveryMethod::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid DISCOVERY_METHOD '{}'. Expected 'events' or 'subgraph'; using events.",
+                    name
+                );
+                DiscoveryMethod::EventScan
+            }),
+            Err(_) => DiscoveryMethod::EventScan,
+        };
+        let discovery_method = match discovery_method {
+            DiscoveryMethod::Subgraph if discovery_subgraph_url.is_none() => {
+                config_warn!(
+                    source,
+                    "DISCOVERY_METHOD is 'subgraph' but DISCOVERY_SUBGRAPH_URL is not set; using events."
+                );
+                DiscoveryMethod::EventScan
+            }
+            method => method,
+        };
+
+        let archive_zero_debt_users = source.bool_var("ARCHIVE_ZERO_DEBT_USERS", false);
+
+        let zero_debt_cooldown_hours = source.parsed_var::<u64>("ZERO_DEBT_COOLDOWN_HOURS", 24);
+
+        // Derived from the zero-debt cooldown by default, for compatibility
+        let archival_run_interval_secs =
+            source.optional_nonzero_var::<u64>("ARCHIVAL_RUN_INTERVAL_SECS");
+
+        // 10.0 ETH wei default
+        let safe_health_factor_threshold = source.parsed_var::<U256>(
+            "SAFE_HEALTH_FACTOR_THRESHOLD",
+            U256::from(10000000000000000000u64),
+        );
+
+        let price_history_enabled = source.bool_var("PRICE_HISTORY_ENABLED", false);
+
+        // Price ticks recheck every affected user via RPC by default
+        let local_health_factor_enabled = source.bool_var("LOCAL_HEALTH_FACTOR_ENABLED", false);
+
+        let price_history_minute_after_hours =
+            source.parsed_var::<u64>("PRICE_HISTORY_MINUTE_AFTER_HOURS", 1);
+
+        let price_history_hour_after_hours =
+            source.parsed_var::<u64>("PRICE_HISTORY_HOUR_AFTER_HOURS", 24);
+
+        let price_history_hour_after_hours =
+            if price_history_hour_after_hours < price_history_minute_after_hours {
+                config_warn!(
+                    source,
+                    "PRICE_HISTORY_HOUR_AFTER_HOURS ({}) is below PRICE_HISTORY_MINUTE_AFTER_HOURS ({}). Using {} hours.",
+                    price_history_hour_after_hours,
+                    price_history_minute_after_hours,
+                    price_history_minute_after_hours
+                );
+                price_history_minute_after_hours
+            } else {
+                price_history_hour_after_hours
+            };
+
+        let price_history_retention_days =
+            source.parsed_var::<u64>("PRICE_HISTORY_RETENTION_DAYS", 30);
+
+        let position_history_retention_days =
+            source.parsed_var::<u64>("POSITION_HISTORY_RETENTION_DAYS", 30);
+
+        let circuit_breaker_enabled = source.bool_var("CIRCUIT_BREAKER_ENABLED", false);
+
+        let max_price_volatility_threshold =
+            source.parsed_var::<f64>("MAX_PRICE_VOLATILITY_THRESHOLD", 10.0);
+
+        let max_liquidations_per_minute =
+            source.parsed_var::<u64>("MAX_LIQUIDATIONS_PER_MINUTE", 10);
+
+        let circuit_breaker_monitoring_window_secs =
+            source.nonzero_var::<u64>("CIRCUIT_BREAKER_MONITORING_WINDOW_SECS", 300);
+
+        let circuit_breaker_cooldown_secs =
+            source.nonzero_var::<u64>("CIRCUIT_BREAKER_COOLDOWN_SECS", 300);
+
+        // Default to waiting out the full cooldown
+        let fast_recovery_stable_secs = source.parsed_var::<u64>("FAST_RECOVERY_STABLE_SECS", 0);
+
+        let min_gas_price_multiplier = source.parsed_var::<u64>("MIN_GAS_PRICE_MULTIPLIER", 1);
+
+        let max_gas_price_multiplier = source.parsed_var::<u64>("MAX_GAS_PRICE_MULTIPLIER", 5);
+
+        let circuit_breaker_rules = parse_url_list(source.var("CIRCUIT_BREAKER_RULES").ok())
+            .into_iter()
+            .filter_map(|rule| {
+                let parsed = TriggerRule::parse(&rule);
+                if parsed.is_none() {
+                    config_warn!(
+                        source,
+                        "Invalid CIRCUIT_BREAKER_RULES entry '{}'. Expected metric[@window_secs]>threshold=pause_all|pause_asset|alert_only; skipping.",
+                        rule
+                    );
                 }
-                Err(e) => {
-                    warn!(
-                        "Invalid AT_RISK_SCAN_LIMIT '{}': {}. Using unlimited scanning.",
-                        limit_str, e
+                parsed
+            })
+            .collect::<Vec<_>>();
+
+        let real_time_monitoring_enabled = source.bool_var("REAL_TIME_MONITORING_ENABLED", true);
+
+        let ws_fast_path_enabled = source.bool_var("WS_FAST_PATH", true);
+
+        // Default to acting immediately
+        let min_position_age_blocks = source.parsed_var::<u64>("MIN_POSITION_AGE_BLOCKS", 0);
+
+        let pool_pause_check_enabled = source.bool_var("POOL_PAUSE_CHECK_ENABLED", true);
+
+        let pool_pause_check_interval_secs =
+            source.nonzero_var::<u64>("POOL_PAUSE_CHECK_INTERVAL_SECS", 60);
+
+        let oracle_guard_enabled = source.bool_var("ORACLE_GUARD_ENABLED", true);
+
+        let reserve_config_refresh_secs =
+            source.parsed_var::<u64>("RESERVE_CONFIG_REFRESH_SECS", 300);
+
+        let oracle_guard_interval_secs =
+            source.nonzero_var::<u64>("ORACLE_GUARD_INTERVAL_SECS", 30);
+
+        let oracle_staleness_grace_secs =
+            source.parsed_var::<u64>("ORACLE_STALENESS_GRACE_SECS", 300);
+
+        // 2%
+        let oracle_max_deviation_bps = source.nonzero_var::<u64>("ORACLE_MAX_DEVIATION_BPS", 200);
+
+        let oracle_secondary_feeds = parse_url_list(source.var("ORACLE_SECONDARY_FEEDS").ok())
+            .into_iter()
+            .filter_map(|entry| {
+                let parsed = SecondaryPriceFeed::parse(&entry);
+                if parsed.is_none() {
+                    config_warn!(
+                        source,
+                        "Invalid ORACLE_SECONDARY_FEEDS entry '{}'. Expected asset=feed; skipping.",
+                        entry
                     );
-                    None
                 }
-            },
-            Err(_) => None, // Default to unlimited scanning
+                parsed
+            })
+            .collect::<Vec<_>>();
+
+        let pool_code_check_enabled = source.bool_var("POOL_CODE_CHECK_ENABLED", true);
+
+        let liquidator_contract_check_enabled =
+            source.bool_var("LIQUIDATOR_CONTRACT_CHECK_ENABLED", true);
+
+        let liquidation_audit_enabled = source.bool_var("LIQUIDATION_AUDIT_ENABLED", true);
+
+        let persistent_liquidation_queue_enabled =
+            source.bool_var("PERSISTENT_LIQUIDATION_QUEUE_ENABLED", true);
+
+        // Typical Aave liquidation bonus
+        let value_at_risk_bonus_bps = source.parsed_var::<u64>("VALUE_AT_RISK_BONUS_BPS", 500);
+
+        // No alerting by default
+        let value_at_risk_alert_threshold =
+            source.optional_var::<U256>("VALUE_AT_RISK_ALERT_THRESHOLD");
+
+        let strategy_label = source.var("STRATEGY_LABEL")
+            .ok()
+            .map(|label| label.trim().to_string())
+            .filter(|label| !label.is_empty())
+            .unwrap_or_else(|| "default".to_string());
+
+        // No balance alerting by default
+        let min_operator_balance_wei =
+            source.parsed_var::<U256>("MIN_OPERATOR_BALANCE_WEI", U256::ZERO);
+
+        // Default to alert only
+        let operator_balance_halt_enabled = source.bool_var("OPERATOR_BALANCE_HALT_ENABLED", false);
+
+        // Default to checking every minute
+        let balance_check_interval_secs =
+            source.parsed_var::<u64>("BALANCE_CHECK_INTERVAL_SECS", 60);
+
+        // SYMBOL:amount pairs, e.g. USDC:1000000000,WETH:500000000000000000
+        let mut min_token_balances = BTreeMap::new();
+        for entry in parse_url_list(source.var("MIN_TOKEN_BALANCES").ok()) {
+            match entry
+                .split_once(':')
+                .map(|(symbol, amount)| (symbol.trim(), amount.trim().parse::<U256>()))
+            {
+                Some((symbol, Ok(amount))) if !symbol.is_empty() => {
+                    min_token_balances.insert(symbol.to_uppercase(), amount);
+                }
+                _ => config_warn!(
+                    source,
+                    "Invalid MIN_TOKEN_BALANCES entry '{}': expected SYMBOL:amount. Ignoring it.",
+                    entry
+                ),
+            }
+        }
+
+        // Disabled by default
+        let realized_profit_alert_bps = source.parsed_var::<u64>("REALIZED_PROFIT_ALERT_BPS", 0);
+
+        // No sweeping by default
+        let treasury_cold_wallet = source.optional_nonzero_var::<Address>("TREASURY_COLD_WALLET");
+
+        // SYMBOL:amount pairs like MIN_TOKEN_BALANCES, with ETH for the native balance
+        let mut treasury_sweep_thresholds = BTreeMap::new();
+        for entry in parse_url_list(source.var("TREASURY_SWEEP_THRESHOLDS").ok()) {
+            match entry
+                .split_once(':')
+                .map(|(symbol, amount)| (symbol.trim(), amount.trim().parse::<U256>()))
+            {
+                Some((symbol, Ok(amount))) if !symbol.is_empty() => {
+                    treasury_sweep_thresholds.insert(symbol.to_uppercase(), amount);
+                }
+                _ => config_warn!(
+                    source,
+                    "Invalid TREASURY_SWEEP_THRESHOLDS entry '{}': expected SYMBOL:amount. Ignoring it.",
+                    entry
+                ),
+            }
+        }
+        if treasury_cold_wallet.is_some() && treasury_sweep_thresholds.is_empty() {
+            config_warn!(
+                source,
+                "TREASURY_COLD_WALLET is set but TREASURY_SWEEP_THRESHOLDS is empty; nothing will be swept."
+            );
+        }
+
+        // 0.02 ETH
+        let treasury_gas_reserve_wei = source.parsed_var::<U256>(
+            "TREASURY_GAS_RESERVE_WEI",
+            U256::from(20_000_000_000_000_000u64),
+        );
+
+        // Every 5 minutes
+        let treasury_sweep_interval_secs =
+            source.nonzero_var::<u64>("TREASURY_SWEEP_INTERVAL_SECS", 300);
+
+        let inventory_target_asset = source
+            .var("INVENTORY_TARGET_ASSET")
+            .ok()
+            .map(|symbol| symbol.trim().to_uppercase())
+            .filter(|symbol| !symbol.is_empty());
+
+        // 20% of inventory value in any one non-target asset
+        let inventory_max_deviation_bps = source.checked_var::<u64>(
+            "INVENTORY_MAX_DEVIATION_BPS",
+            2_000,
+            "must be below 10000",
+            |bps| *bps < 10_000,
+        );
+
+        // Every 15 minutes
+        let inventory_check_interval_secs =
+            source.nonzero_var::<u64>("INVENTORY_CHECK_INTERVAL_SECS", 900);
+
+        let event_record_path = source.var("EVENT_RECORD_PATH")
+            .ok()
+            .filter(|path| !path.trim().is_empty()); // No recording by default
+
+        // Default to one summary line per cycle
+        let verbose_per_item_logging = source.bool_var("VERBOSE_PER_ITEM_LOGGING", false);
+
+        let opportunity_webhook_url = source.var("OPPORTUNITY_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        // Default to leaving execution to the external system
+        let opportunity_webhook_execute = source.bool_var("OPPORTUNITY_WEBHOOK_EXECUTE", false);
+
+        let private_relay_url = source.var("PRIVATE_RELAY_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let submission_strategy = match source.var("SUBMISSION_STRATEGY") {
+            Ok(name) => SubmissionStrategy::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid SUBMISSION_STRATEGY '{}'. Using default 'public'.",
+                    name
+                );
+                SubmissionStrategy::Public
+            }),
+            Err(_) => SubmissionStrategy::Public,
         };
+        let submission_strategy = if submission_strategy != SubmissionStrategy::Public
+            && private_relay_url.is_none()
+        {
+            config_warn!(
+                source,
+                "SUBMISSION_STRATEGY '{}' requires PRIVATE_RELAY_URL. Using 'public'.",
+                submission_strategy.as_str()
+            );
+            SubmissionStrategy::Public
+        } else {
+            submission_strategy
+        };
+
+        // Roughly 50 seconds of Base blocks
+        let private_relay_max_blocks = source.nonzero_var::<u64>("PRIVATE_RELAY_MAX_BLOCKS", 25);
 
-        let full_rescan_interval_minutes = match std::env::var("FULL_RESCAN_INTERVAL_MINUTES") {
-            Ok(interval_str) => match interval_str.parse::<u64>() {
-                Ok(interval) => {
-                    if interval == 0 {
-                        warn!("FULL_RESCAN_INTERVAL_MINUTES cannot be 0. Using default 60 minutes.");
-                        60
-                    } else {
-                        interval
-                    }
+        let collateral_swap_routes = parse_url_list(source.var("COLLATERAL_SWAP_ROUTES").ok())
+            .into_iter()
+            .filter_map(|route| {
+                let parsed = SwapRoute::parse(&route);
+                if parsed.is_none() {
+                    config_warn!(
+                        source,
+                        "Invalid COLLATERAL_SWAP_ROUTES entry '{}'. Expected token>fee>token[>fee>token...]; skipping.",
+                        route
+                    );
                 }
-                Err(e) => {
-                    warn!(
-                        "Invalid FULL_RESCAN_INTERVAL_MINUTES '{}': {}. Using default 60 minutes.",
-                        interval_str, e
+                parsed
+            })
+            .collect::<Vec<_>>();
+
+        // 0.5% below the quoted output
+        let collateral_swap_slippage_bps = source.checked_var::<u64>(
+            "COLLATERAL_SWAP_SLIPPAGE_BPS",
+            50,
+            "must be below 10000",
+            |bps| *bps < 10_000,
+        );
+
+        let swap_aggregator = match source.var("SWAP_AGGREGATOR") {
+            Ok(name) if name.trim().is_empty() => None,
+            Ok(name) => {
+                let kind = AggregatorKind::from_name(&name);
+                if kind.is_none() {
+                    config_warn!(
+                        source,
+                        "Invalid SWAP_AGGREGATOR '{}'. Expected '1inch' or '0x'; using Uniswap routes only.",
+                        name
                     );
-                    60
                 }
-            },
-            Err(_) => 60, // Default to 60 minutes
+                kind
+            }
+            Err(_) => None,
         };
 
-        let archive_zero_debt_users = match std::env::var("ARCHIVE_ZERO_DEBT_USERS") {
-            Ok(value) => value.parse::<bool>().unwrap_or(false),
-            Err(_) => false,
-        };
+        let swap_aggregator_api_url = source.var("SWAP_AGGREGATOR_API_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
 
-        let zero_debt_cooldown_hours = match std::env::var("ZERO_DEBT_COOLDOWN_HOURS") {
-            Ok(hours_str) => hours_str.parse::<u64>().unwrap_or(24), // Default to 24 hours
-            Err(_) => 24,
+        let swap_aggregator_api_key = source.var("SWAP_AGGREGATOR_API_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+
+        // Roughly five Base blocks
+        let swap_quote_cache_secs = source.parsed_var::<u64>("SWAP_QUOTE_CACHE_SECS", 10);
+
+        let swap_approval_mode = match source.var("SWAP_APPROVAL_MODE") {
+            Ok(name) => ApprovalMode::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid SWAP_APPROVAL_MODE '{}'. Expected 'exact' or 'infinite'; using exact.",
+                    name
+                );
+                ApprovalMode::Exact
+            }),
+            Err(_) => ApprovalMode::Exact,
         };
 
-        let safe_health_factor_threshold = match std::env::var("SAFE_HEALTH_FACTOR_THRESHOLD") {
-            Ok(threshold_str) => match threshold_str.parse::<U256>() {
-                Ok(threshold) => threshold,
-                Err(e) => {
-                    warn!(
-                        "Invalid SAFE_HEALTH_FACTOR_THRESHOLD '{}': {}. Using default 10.0.",
-                        threshold_str, e
-                    );
-                    U256::from(10000000000000000000u64) // 10.0 ETH wei default
+        let mut flash_loan_sources = Vec::new();
+        for name in parse_url_list(source.var("FLASH_LOAN_PROVIDERS").ok()) {
+            match FlashLoanSource::from_name(&name) {
+                Some(lender) if !flash_loan_sources.contains(&lender) => {
+                    flash_loan_sources.push(lender)
                 }
-            },
-            Err(_) => U256::from(10000000000000000000u64), // 10.0 ETH wei default
-        };
+                Some(_) => {}
+                None => config_warn!(
+                    source,
+                    "Invalid FLASH_LOAN_PROVIDERS entry '{}'. Expected 'aave', 'balancer' or 'morpho'; skipping.",
+                    name
+                ),
+            }
+        }
+        if flash_loan_sources.is_empty() {
+            // Older liquidator deployments only support Aave flash loans
+            flash_loan_sources.push(FlashLoanSource::AaveV3);
+        }
+
+        // Always borrow by default
+        let wallet_funded_liquidations_enabled =
+            source.bool_var("WALLET_FUNDED_LIQUIDATIONS", false);
 
-        let circuit_breaker_enabled = match std::env::var("CIRCUIT_BREAKER_ENABLED") {
-            Ok(value) => value.parse::<bool>().unwrap_or(false),
-            Err(_) => false,
+        // Receive the underlying and swap it by default
+        let liquidation_receive_a_token = source.bool_var("LIQUIDATION_RECEIVE_A_TOKEN", false);
+        if liquidation_receive_a_token && !wallet_funded_liquidations_enabled {
+            // A flash loan has to be repaid in the underlying, so only wallet-funded
+            // liquidations can keep the collateral as aTokens
+            config_warn!(
+                source,
+                "LIQUIDATION_RECEIVE_A_TOKEN has no effect without WALLET_FUNDED_LIQUIDATIONS=true."
+            );
+        }
+
+        // Hold aTokens for a day
+        let a_token_unwind_after_secs =
+            source.parsed_var::<u64>("A_TOKEN_UNWIND_AFTER_SECS", 86_400);
+
+        let simulation_mode = match source.var("SIMULATION_MODE") {
+            Ok(name) => SimulationMode::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid SIMULATION_MODE '{}'. Expected 'off', 'eth_call' or 'anvil'; simulation disabled.",
+                    name
+                );
+                SimulationMode::Off
+            }),
+            Err(_) => SimulationMode::Off,
         };
 
-        let max_price_volatility_threshold = match std::env::var("MAX_PRICE_VOLATILITY_THRESHOLD") {
-            Ok(threshold_str) => threshold_str.parse::<f64>().unwrap_or(10.0), // Default to 10%
-            Err(_) => 10.0,
+        let execution_mode = match source.var("EXECUTION_MODE") {
+            Ok(name) => ExecutionMode::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid EXECUTION_MODE '{}'. Expected 'live', 'dry_run' or 'simulate'; using dry_run.",
+                    name
+                );
+                ExecutionMode::DryRun
+            }),
+            Err(_) => ExecutionMode::Live,
         };
 
-        let max_liquidations_per_minute = match std::env::var("MAX_LIQUIDATIONS_PER_MINUTE") {
-            Ok(limit_str) => limit_str.parse::<u64>().unwrap_or(10), // Default to 10 liquidations/min
-            Err(_) => 10,
+        // Mocked transactions by default
+        let real_execution = source.bool_var("LIQUIDATION_REAL_EXECUTION", false);
+
+        let anvil_path = source.var("ANVIL_PATH")
+            .ok()
+            .filter(|path| !path.trim().is_empty())
+            .unwrap_or_else(|| "anvil".to_string());
+
+        let anvil_port = source.nonzero_var::<u16>("ANVIL_PORT", 8546);
+
+        let racing_max_profit_share_bps = source.checked_var::<u64>(
+            "RACING_MAX_PROFIT_SHARE_BPS",
+            1_000,
+            "must be at most 10000",
+            |bps| *bps <= 10_000,
+        );
+
+        let racing_win_rate_window = source.nonzero_var::<usize>("RACING_WIN_RATE_WINDOW", 50);
+
+        let mempool_source = match source.var("MEMPOOL_MONITORING") {
+            Ok(name) => MempoolSource::from_name(&name).unwrap_or_else(|| {
+                config_warn!(
+                    source,
+                    "Invalid MEMPOOL_MONITORING '{}'. Expected 'off', 'standard' or 'alchemy'; mempool monitoring disabled.",
+                    name
+                );
+                MempoolSource::Off
+            }),
+            Err(_) => MempoolSource::Off,
         };
 
-        let circuit_breaker_monitoring_window_secs = match std::env::var("CIRCUIT_BREAKER_MONITORING_WINDOW_SECS") {
-            Ok(interval_str) => match interval_str.parse::<u64>() {
-                Ok(interval) => {
-                    if interval == 0 {
-                        warn!("CIRCUIT_BREAKER_MONITORING_WINDOW_SECS cannot be 0. Using default 300 seconds.");
-                        300
-                    } else {
-                        interval
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        "Invalid CIRCUIT_BREAKER_MONITORING_WINDOW_SECS '{}': {}. Using default 300 seconds.",
-                        interval_str, e
-                    );
-                    300
-                }
-            },
-            Err(_) => 300, // Default to 5 minutes
+        let tx_stuck_after_secs = source.nonzero_var::<u64>("TX_STUCK_AFTER_SECS", 30);
+
+        let tx_fee_bump_pct = source.checked_var::<u64>(
+            "TX_FEE_BUMP_PCT",
+            12,
+            "must be between 10 and 1000",
+            |pct| (10..=1000).contains(pct),
+        );
+
+        let tx_max_fee_bumps = source.parsed_var::<u32>("TX_MAX_FEE_BUMPS", 3);
+
+        // Aave only by default; a blank value means the same
+        let compound_v3_comet_address = match source.var("COMPOUND_V3_COMET_ADDRESS") {
+            Ok(addr_str) if addr_str.trim().is_empty() => None,
+            _ => source.optional_var::<Address>("COMPOUND_V3_COMET_ADDRESS"),
         };
 
-        let circuit_breaker_cooldown_secs = match std::env::var("CIRCUIT_BREAKER_COOLDOWN_SECS") {
-            Ok(interval_str) => match interval_str.parse::<u64>() {
-                Ok(interval) => {
-                    if interval == 0 {
-                        warn!("CIRCUIT_BREAKER_COOLDOWN_SECS cannot be 0. Using default 300 seconds.");
-                        300
-                    } else {
-                        interval
-                    }
-                }
-                Err(e) => {
-                    warn!(
-                        "Invalid CIRCUIT_BREAKER_COOLDOWN_SECS '{}': {}. Using default 300 seconds.",
-                        interval_str, e
-                    );
-                    300
-                }
-            },
-            Err(_) => 300, // Default to 5 minutes
+        let compound_v3_discovery_blocks =
+            source.parsed_var::<u64>("COMPOUND_V3_DISCOVERY_BLOCKS", 10_000);
+
+        let compound_v3_max_absorb_gas_cost_wei = source.parsed_var::<U256>(
+            "COMPOUND_V3_MAX_ABSORB_GAS_COST_WEI",
+            U256::from(1_000_000_000_000_000u64), // 0.001 ETH
+        );
+
+        let info_webhook_url = source.var("INFO_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let critical_webhook_url = source.var("CRITICAL_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let telegram_bot_token = source.var("TELEGRAM_BOT_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+
+        let telegram_chat_id = source.var("TELEGRAM_CHAT_ID")
+            .ok()
+            .filter(|chat_id| !chat_id.trim().is_empty());
+
+        if telegram_bot_token.is_some() != telegram_chat_id.is_some() {
+            config_warn!(
+                source,
+                "TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID must both be set; Telegram alerts disabled."
+            );
+        }
+        let (telegram_bot_token, telegram_chat_id) = match (telegram_bot_token, telegram_chat_id) {
+            (Some(token), Some(chat_id)) => (Some(token), Some(chat_id)),
+            _ => (None, None),
         };
 
-        let min_gas_price_multiplier = match std::env::var("MIN_GAS_PRICE_MULTIPLIER") {
-            Ok(multiplier_str) => match multiplier_str.parse::<u64>() {
-                Ok(multiplier) => multiplier,
-                Err(e) => {
-                    warn!(
-                        "Invalid MIN_GAS_PRICE_MULTIPLIER '{}': {}. Using default 1.",
-                        multiplier_str, e
+        let discord_webhook_url = source.var("DISCORD_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let slack_webhook_url = source.var("SLACK_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let pagerduty_routing_key = source.var("PAGERDUTY_ROUTING_KEY")
+            .ok()
+            .filter(|key| !key.trim().is_empty());
+
+        let alert_routes = parse_url_list(source.var("ALERT_ROUTES").ok())
+            .into_iter()
+            .filter_map(|route| {
+                let parsed = AlertRoute::parse(&route);
+                if parsed.is_none() {
+                    config_warn!(
+                        source,
+                        "Invalid ALERT_ROUTES entry '{}'. Expected event=channel[+channel...] with channels from {} (or 'none'); skipping.",
+                        route,
+                        NOTIFICATION_CHANNELS.join(", ")
                     );
-                    1
                 }
-            },
-            Err(_) => 1,
-        };
+                parsed
+            })
+            .collect::<Vec<_>>();
+
+        let heartbeat_url = source.var("HEARTBEAT_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let heartbeat_interval_secs = source.nonzero_var::<u64>("HEARTBEAT_INTERVAL_SECS", 60);
 
-        let max_gas_price_multiplier = match std::env::var("MAX_GAS_PRICE_MULTIPLIER") {
-            Ok(multiplier_str) => match multiplier_str.parse::<u64>() {
-                Ok(multiplier) => multiplier,
-                Err(e) => {
-                    warn!(
-                        "Invalid MAX_GAS_PRICE_MULTIPLIER '{}': {}. Using default 5.",
-                        multiplier_str, e
+        let block_time_secs = builtin_network
+            .map(|network| network.block_time_secs)
+            .unwrap_or(DEFAULT_BLOCK_TIME_SECS);
+        let heartbeat_stall_secs = source.nonzero_var::<u64>(
+            "HEARTBEAT_STALL_SECS",
+            HEARTBEAT_STALL_BLOCKS * block_time_secs,
+        );
+
+        let admin_listen_addr = source.var("ADMIN_LISTEN_ADDR")
+            .ok()
+            .filter(|addr| !addr.trim().is_empty());
+
+        let admin_token = source.var("ADMIN_TOKEN")
+            .ok()
+            .filter(|token| !token.trim().is_empty());
+
+        let metrics_listen_addr = source.var("METRICS_LISTEN_ADDR")
+            .ok()
+            .filter(|addr| !addr.trim().is_empty());
+
+        let dashboard_listen_addr = source.var("DASHBOARD_LISTEN_ADDR")
+            .ok()
+            .filter(|addr| !addr.trim().is_empty());
+
+        let grpc_listen_addr = source.var("GRPC_LISTEN_ADDR")
+            .ok()
+            .filter(|addr| !addr.trim().is_empty());
+
+        let event_bus_url = match source.var("EVENT_BUS_URL") {
+            Ok(url) if url.trim().is_empty() => None,
+            Ok(url) => match BusKind::from_url(&url) {
+                Some(_) => Some(url.trim().to_string()),
+                None => {
+                    config_warn!(
+                        source,
+                        "Invalid EVENT_BUS_URL: expected a kafka://, nats:// or redis:// URL. Message bus publishing disabled."
                     );
-                    5
+                    None
                 }
             },
-            Err(_) => 5,
+            Err(_) => None, // Not published by default
         };
 
-        let ws_fast_path_enabled = match std::env::var("WS_FAST_PATH") {
-            Ok(value) => value.parse::<bool>().unwrap_or(true), // Default to enabled
-            Err(_) => true, // Default to enabled
-        };
+        let event_bus_events_topic = source.var("EVENT_BUS_EVENTS_TOPIC")
+            .ok()
+            .filter(|topic| !topic.trim().is_empty())
+            .unwrap_or_else(|| "liquidation-bot.events".to_string());
+
+        let event_bus_executions_topic = source.var("EVENT_BUS_EXECUTIONS_TOPIC")
+            .ok()
+            .filter(|topic| !topic.trim().is_empty())
+            .unwrap_or_else(|| "liquidation-bot.executions".to_string());
+
+        let event_webhook_url = source.var("EVENT_WEBHOOK_URL")
+            .ok()
+            .filter(|url| !url.trim().is_empty());
+
+        let event_webhook_secret = source.var("EVENT_WEBHOOK_SECRET")
+            .ok()
+            .filter(|secret| !secret.trim().is_empty());
+
+        let event_webhook_max_retries = source.parsed_var::<u32>("EVENT_WEBHOOK_MAX_RETRIES", 5);
+
+        let config_file = source
+            .file_path
+            .as_ref()
+            .map(|path| path.display().to_string());
+
+        let config_reload_interval_secs =
+            source.parsed_var::<u64>("CONFIG_RELOAD_INTERVAL_SECS", 10);
 
         Ok(Self {
+            chain_id,
+            chain_name,
+            network,
             rpc_url,
             ws_url,
+            rpc_fallback_urls,
+            ws_fallback_urls,
+            provider_health_check_interval_secs,
+            rpc_rate_limit_rps,
+            rpc_endpoint_rate_limits,
+            signer_backend,
             private_key,
+            wallet_gas_reserve_wei,
+            weth_gas_target_wei,
+            weth_wrap_above_wei,
             liquidator_contract,
             min_profit_threshold,
-            gas_price_multiplier,
+            min_profit_pct_of_debt_bps,
+            onchain_min_profit_margin_bps,
+            liquidation_hf_margin,
+            profitability_model,
+            shadow_profitability,
+            shadow_divergence_threshold_bps,
+            gas_fee_profile,
+            gas_fee_history_blocks,
+            gas_conservative_profit_multiple,
+            gas_aggressive_profit_multiple,
+            racing_max_profit_share_bps,
+            racing_win_rate_window,
+            mempool_source,
             target_user,
             database_url,
             health_factor_threshold,
+            min_hf_change_to_persist_bps,
+            position_write_batch_size,
+            position_write_flush_interval_ms,
+            event_queue_capacity,
+            liquidation_queue_capacity,
+            user_refresh_min_interval_secs,
+            health_check_parallelism,
+            liquidation_parallelism,
+            liquidation_retry_max_attempts,
+            liquidation_retry_base_delay_ms,
+            liquidation_retry_max_delay_ms,
+            shutdown_timeout_secs,
             monitoring_interval_secs,
+            periodic_scan_interval_secs,
+            adaptive_scan_interval_enabled,
+            min_scan_interval_secs,
+            max_scan_interval_secs,
             asset_loading_method,
             at_risk_scan_limit,
             full_rescan_interval_minutes,
+            scan_tiers,
+            block_scan_enabled,
+            block_scan_top_n,
+            block_scan_every_n_blocks,
+            block_scan_budget_ms,
+            catch_up_prioritize_by_debt,
+            discovery_method,
+            discovery_subgraph_url,
             archive_zero_debt_users,
             zero_debt_cooldown_hours,
+            archival_run_interval_secs,
             safe_health_factor_threshold,
+            price_history_enabled,
+            local_health_factor_enabled,
+            price_history_minute_after_hours,
+            price_history_hour_after_hours,
+            price_history_retention_days,
+            position_history_retention_days,
             circuit_breaker_enabled,
             max_price_volatility_threshold,
             max_liquidations_per_minute,
             circuit_breaker_monitoring_window_secs,
             circuit_breaker_cooldown_secs,
+            fast_recovery_stable_secs,
             min_gas_price_multiplier,
             max_gas_price_multiplier,
+            circuit_breaker_rules,
+            real_time_monitoring_enabled,
             ws_fast_path_enabled,
+            min_position_age_blocks,
+            pool_pause_check_enabled,
+            pool_pause_check_interval_secs,
+            reserve_config_refresh_secs,
+            oracle_guard_enabled,
+            oracle_guard_interval_secs,
+            oracle_staleness_grace_secs,
+            oracle_max_deviation_bps,
+            oracle_secondary_feeds,
+            pool_code_check_enabled,
+            liquidator_contract_check_enabled,
+            liquidation_audit_enabled,
+            persistent_liquidation_queue_enabled,
+            value_at_risk_bonus_bps,
+            value_at_risk_alert_threshold,
+            strategy_label,
+            verbose_per_item_logging,
+            event_record_path,
+            min_operator_balance_wei,
+            operator_balance_halt_enabled,
+            balance_check_interval_secs,
+            min_token_balances,
+            realized_profit_alert_bps,
+            treasury_cold_wallet,
+            treasury_sweep_thresholds,
+            treasury_gas_reserve_wei,
+            treasury_sweep_interval_secs,
+            inventory_target_asset,
+            inventory_max_deviation_bps,
+            inventory_check_interval_secs,
+            opportunity_webhook_url,
+            opportunity_webhook_execute,
+            submission_strategy,
+            private_relay_url,
+            private_relay_max_blocks,
+            collateral_swap_routes,
+            collateral_swap_slippage_bps,
+            swap_aggregator,
+            swap_aggregator_api_url,
+            swap_aggregator_api_key,
+            swap_quote_cache_secs,
+            swap_approval_mode,
+            flash_loan_sources,
+            wallet_funded_liquidations_enabled,
+            liquidation_receive_a_token,
+            a_token_unwind_after_secs,
+            execution_mode,
+            real_execution,
+            simulation_mode,
+            anvil_path,
+            anvil_port,
+            tx_stuck_after_secs,
+            tx_fee_bump_pct,
+            tx_max_fee_bumps,
+            compound_v3_comet_address,
+            compound_v3_discovery_blocks,
+            compound_v3_max_absorb_gas_cost_wei,
+            info_webhook_url,
+            critical_webhook_url,
+            telegram_bot_token,
+            telegram_chat_id,
+            discord_webhook_url,
+            slack_webhook_url,
+            pagerduty_routing_key,
+            alert_routes,
+            heartbeat_url,
+            heartbeat_interval_secs,
+            heartbeat_stall_secs,
+            admin_listen_addr,
+            admin_token,
+            metrics_listen_addr,
+            dashboard_listen_addr,
+            grpc_listen_addr,
+            event_bus_url,
+            event_bus_events_topic,
+            event_bus_executions_topic,
+            event_webhook_url,
+            event_webhook_secret,
+            event_webhook_max_retries,
+            config_file,
+            config_reload_interval_secs,
         })
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{TriggerAction, TriggerMetric};
+
+    const FILE_CONFIG: &str = r#"
+rpc_url = "http://file-node:8545"
+private_key = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+min_profit_threshold = "20000000000000000"
+monitoring_interval_secs = 12
+circuit_breaker_enabled = true
+"#;
+
+    #[test]
+    fn test_config_file_values_resolve() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        assert_eq!(config.rpc_url, "http://file-node:8545");
+        assert_eq!(config.min_profit_threshold, U256::from(20_000_000_000_000_000u64));
+        assert_eq!(config.monitoring_interval_secs, 12);
+        assert!(config.circuit_breaker_enabled);
+        assert!(source.problems().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_values_and_unknown_keys_are_reported() {
+        let contents = format!(
+            "{}\nmin_proft_threshold = \"1\"\ngas_fee_profile = \"turbo\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        // Invalid values still fall back to the default, but are no longer silent
+        assert_eq!(config.gas_fee_profile, None);
+        let problems = source.problems();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("GAS_FEE_PROFILE 'turbo'"));
+        assert!(problems[1].contains("Unknown key 'min_proft_threshold'"));
+    }
+
+    #[test]
+    fn test_invalid_flags_and_numbers_fall_back_and_are_reported() {
+        let contents = format!(
+            "{}ws_fast_path = \"yes\"\nmax_liquidations_per_minute = \"ten\"\nliquidation_real_execution = \"TRUE\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        assert!(config.ws_fast_path_enabled);
+        assert_eq!(config.max_liquidations_per_minute, 10);
+        assert!(config.real_execution);
+        let problems = source.problems();
+        assert_eq!(problems.len(), 2, "{:?}", problems);
+        assert!(problems[0].contains("MAX_LIQUIDATIONS_PER_MINUTE 'ten'"));
+        assert!(problems[1].contains("WS_FAST_PATH 'yes'"));
+    }
+
+    #[test]
+    fn test_fallback_url_lists_are_split() {
+        let contents = format!(
+            "{}rpc_fallback_urls = \"http://backup-a:8545, ,http://backup-b:8545\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        assert_eq!(
+            config.rpc_fallback_urls,
+            vec!["http://backup-a:8545".to_string(), "http://backup-b:8545".to_string()]
+        );
+        assert!(config.ws_fallback_urls.is_empty());
+    }
+
+    #[test]
+    fn test_collateral_swap_routes_are_parsed() {
+        let contents = format!(
+            "{}collateral_swap_routes = \"0x2Ae3F1Ec7F1F5012CFEab0185bfc7aa3cf0DEc22>500>0x4200000000000000000000000000000000000006>500>0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913, 0x4200000000000000000000000000000000000006>3000\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        assert_eq!(config.collateral_swap_routes.len(), 1);
+        let route = &config.collateral_swap_routes[0];
+        assert_eq!(route.fees, vec![500, 500]);
+        assert_eq!(
+            route.token_out(),
+            "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913".parse::<Address>().unwrap()
+        );
+        assert_eq!(config.collateral_swap_slippage_bps, 50);
+        // The truncated route is reported rather than silently dropped
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_flash_loan_providers_are_parsed() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.flash_loan_sources, vec![FlashLoanSource::AaveV3]);
+
+        let contents = format!(
+            "{}flash_loan_providers = \"balancer, morpho, aave, balancer, euler\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        assert_eq!(
+            config.flash_loan_sources,
+            vec![
+                FlashLoanSource::Balancer,
+                FlashLoanSource::Morpho,
+                FlashLoanSource::AaveV3
+            ]
+        );
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_simulation_mode_is_parsed() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.simulation_mode, SimulationMode::Off);
+        assert_eq!(config.anvil_port, 8546);
+
+        let contents = format!("{}simulation_mode = \"anvil\"\nanvil_port = 9545\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.simulation_mode, SimulationMode::AnvilFork);
+        assert_eq!(config.anvil_path, "anvil");
+        assert_eq!(config.anvil_port, 9545);
+
+        let contents = format!("{}simulation_mode = \"tenderly\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.simulation_mode, SimulationMode::Off);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_execution_mode_is_parsed() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.execution_mode, ExecutionMode::Live);
+
+        let contents = format!("{}execution_mode = \"dry_run\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.execution_mode, ExecutionMode::DryRun);
+
+        // A typo must not fall back to broadcasting real transactions
+        let contents = format!("{}execution_mode = \"livee\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.execution_mode, ExecutionMode::DryRun);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_only_live_real_execution_broadcasts() {
+        let contents = format!("{}liquidation_real_execution = \"true\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert!(config.real_execution);
+        assert!(config.broadcasts_transactions());
+
+        // Dry runs and simulations never send, whatever LIQUIDATION_REAL_EXECUTION says
+        for mode in ["dry_run", "simulate"] {
+            let contents = format!(
+                "{}liquidation_real_execution = \"true\"\nexecution_mode = \"{}\"\n",
+                FILE_CONFIG, mode
+            );
+            let source = ConfigSource::from_toml_str(&contents).unwrap();
+            let config = BotConfig::from_source(&source).unwrap();
+            assert!(!config.broadcasts_transactions(), "{} broadcasts", mode);
+        }
+
+        let contents = format!("{}liquidation_real_execution = \"yes\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert!(!config.broadcasts_transactions());
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_signer_backend_is_parsed() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.signer_backend, SignerBackend::PrivateKey);
+
+        let without_key: String = FILE_CONFIG
+            .lines()
+            .filter(|line| !line.starts_with("private_key"))
+            .map(|line| format!("{}\n", line))
+            .collect();
+        let contents = format!(
+            "{}signer_backend = \"keystore\"\nkeystore_path = \"/keys/bot.json\"\nkeystore_password = \"hunter2\"\n",
+            without_key
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(
+            config.signer_backend,
+            SignerBackend::Keystore {
+                paths: vec![PathBuf::from("/keys/bot.json")],
+                password: Some("hunter2".to_string()),
+            }
+        );
+        assert!(config.private_key.is_empty());
+        assert!(!format!("{:?}", config.redacted()).contains("hunter2"));
+
+        // Several key references make several executor wallets
+        let contents = format!("{}signer_backend = \"ledger\"\nledger_index = \"0, 2\"\n", without_key);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.signer_backend, SignerBackend::Ledger { indices: vec![0, 2] });
+
+        // A backend missing its key reference, or an unknown backend, can't start
+        let contents = format!("{}signer_backend = \"aws_kms\"\n", without_key);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        assert!(BotConfig::from_source(&source).is_err());
+        let contents = format!("{}signer_backend = \"hsm\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        assert!(BotConfig::from_source(&source).is_err());
+    }
+
+    #[test]
+    fn test_subgraph_discovery_requires_an_endpoint() {
+        let contents = format!(
+            "{}discovery_method = \"subgraph\"\ndiscovery_subgraph_url = \"http://graph.local\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.discovery_method, DiscoveryMethod::Subgraph);
+        assert_eq!(config.discovery_subgraph_url.as_deref(), Some("http://graph.local"));
+
+        let contents = format!("{}discovery_method = \"subgraph\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.discovery_method, DiscoveryMethod::EventScan);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_gas_fee_profile_settings_are_parsed() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.gas_fee_profile, None);
+        assert_eq!(config.gas_fee_history_blocks, 20);
+        assert_eq!(config.gas_conservative_profit_multiple, 2);
+        assert_eq!(config.gas_aggressive_profit_multiple, 10);
+
+        let contents = format!(
+            "{}gas_fee_profile = \"Aggressive\"\ngas_conservative_profit_multiple = 3\ngas_aggressive_profit_multiple = 1\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.gas_fee_profile, Some(FeeProfile::Aggressive));
+        assert_eq!(config.gas_conservative_profit_multiple, 3);
+        assert_eq!(config.gas_aggressive_profit_multiple, 10);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_racing_profit_share_is_bounded() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.racing_max_profit_share_bps, 1_000);
+        assert_eq!(config.racing_win_rate_window, 50);
+
+        let contents = format!(
+            "{}racing_max_profit_share_bps = 12000\nracing_win_rate_window = 20\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.racing_max_profit_share_bps, 1_000);
+        assert_eq!(config.racing_win_rate_window, 20);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_telegram_needs_token_and_chat_id() {
+        let contents = format!(
+            "{}telegram_bot_token = \"123:abc\"\ntelegram_chat_id = \"-100123\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.telegram_bot_token.as_deref(), Some("123:abc"));
+        assert_eq!(config.telegram_chat_id.as_deref(), Some("-100123"));
+
+        let contents = format!("{}telegram_bot_token = \"123:abc\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.telegram_bot_token, None);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_alert_routes_are_parsed() {
+        let contents = format!(
+            "{}alert_routes = \"liquidation_succeeded=slack, database_unreachable=pagerduty+slack, liquidatable_user_detected=none, connection_lost=pager\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(
+            config.alert_routes,
+            vec![
+                AlertRoute {
+                    event: "liquidation_succeeded".to_string(),
+                    channels: vec!["slack".to_string()],
+                },
+                AlertRoute {
+                    event: "database_unreachable".to_string(),
+                    channels: vec!["pagerduty".to_string(), "slack".to_string()],
+                },
+                AlertRoute {
+                    event: "liquidatable_user_detected".to_string(),
+                    channels: Vec::new(),
+                },
+            ]
+        );
+        // The unknown "pager" channel is reported and its route dropped
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_circuit_breaker_rules_are_parsed() {
+        let contents = format!(
+            "{}circuit_breaker_rules = \"consecutive_reverts>5=pause-all, volatility@120>8=pause_asset, oracle_staleness_secs>600=alert-only, gas_multiplier>4=pause_asset\"\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(
+            config.circuit_breaker_rules,
+            vec![
+                TriggerRule {
+                    metric: TriggerMetric::ConsecutiveReverts,
+                    window_secs: None,
+                    threshold: 5.0,
+                    action: TriggerAction::PauseAll,
+                },
+                TriggerRule {
+                    metric: TriggerMetric::Volatility,
+                    window_secs: Some(120),
+                    threshold: 8.0,
+                    action: TriggerAction::PauseAsset,
+                },
+                TriggerRule {
+                    metric: TriggerMetric::OracleStalenessSecs,
+                    window_secs: None,
+                    threshold: 600.0,
+                    action: TriggerAction::AlertOnly,
+                },
+            ]
+        );
+        // Gas is not measured per asset, so it can't pause one
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_mempool_source_is_parsed() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.mempool_source, MempoolSource::Off);
+
+        let contents = format!("{}mempool_monitoring = \"alchemy\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.mempool_source, MempoolSource::Alchemy);
+
+        let contents = format!("{}mempool_monitoring = \"flashbots\"\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+        assert_eq!(config.mempool_source, MempoolSource::Off);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_fee_bump_below_replacement_minimum_is_rejected() {
+        let contents = format!(
+            "{}tx_stuck_after_secs = 45\ntx_fee_bump_pct = 5\ntx_max_fee_bumps = 0\n",
+            FILE_CONFIG
+        );
+        let source = ConfigSource::from_toml_str(&contents).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        assert_eq!(config.tx_stuck_after_secs, 45);
+        assert_eq!(config.tx_fee_bump_pct, 12);
+        assert_eq!(config.tx_max_fee_bumps, 0);
+        assert_eq!(source.problems().len(), 1);
+    }
+
+    #[test]
+    fn test_nested_tables_are_rejected() {
+        let error = ConfigSource::from_toml_str("[circuit_breaker]\nenabled = true\n").unwrap_err();
+        assert!(error.to_string().contains("circuit_breaker"));
+    }
+
+    #[test]
+    fn test_chain_sections_layer_over_top_level_values() {
+        const CHAINS: &str = r#"
+[[chains]]
+chain_id = 8453
+oracle_address = "0x0000000000000000000000000000000000000001"
+
+[[chains]]
+chain_id = 42161
+chain_name = "arbitrum"
+rpc_url = "http://arbitrum-node:8545"
+pool_address = "0x794a61358D6845594F94dc1DB02A252b5b4814aD"
+"#;
+        let contents = format!("{}{}", FILE_CONFIG, CHAINS);
+        let sources = ConfigSource::from_toml_str(&contents).unwrap().chain_sources();
+        let configs: Vec<BotConfig> = sources
+            .iter()
+            .map(|source| BotConfig::from_source(source).unwrap())
+            .collect();
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(configs[0].chain_name, "base");
+        assert_eq!(configs[0].rpc_url, "http://file-node:8545");
+        assert_eq!(configs[0].network.oracle, Address::with_last_byte(1));
+        assert_eq!(configs[0].network.pool, networks::base().pool);
+        assert_eq!(configs[1].chain_name, "arbitrum");
+        assert_eq!(configs[1].network, networks::builtin(42161).unwrap().addresses);
+        assert_eq!(configs[1].rpc_url, "http://arbitrum-node:8545");
+        assert_eq!(configs[1].min_profit_threshold, configs[0].min_profit_threshold);
+        assert!(sources.iter().all(|source| source.problems().is_empty()));
+        assert!(shared_chain_settings(&configs).is_empty());
+
+        // Chains share the database by chain id and can't share listen addresses
+        let mut clashing = configs.clone();
+        clashing[1].chain_id = 8453;
+        clashing[0].metrics_listen_addr = Some("0.0.0.0:9090".to_string());
+        clashing[1].metrics_listen_addr = Some("0.0.0.0:9090".to_string());
+        assert_eq!(shared_chain_settings(&clashing).len(), 2);
+
+        // Chains without built-in addresses must configure all of them
+        let contents = format!("{}\n[[chains]]\nchain_id = 100\n", FILE_CONFIG);
+        let source = ConfigSource::from_toml_str(&contents).unwrap().chain_sources().remove(0);
+        assert!(BotConfig::from_source(&source).is_err());
+    }
+
+    #[test]
+    fn test_redacted_hides_secrets() {
+        let source = ConfigSource::from_toml_str(FILE_CONFIG).unwrap();
+        let config = BotConfig::from_source(&source).unwrap();
+
+        let printed = format!("{:?}", config.redacted());
+        assert!(!printed.contains("59c6995e"));
+        assert!(printed.contains(REDACTED));
+    }
+}
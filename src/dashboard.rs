@@ -0,0 +1,284 @@
+use eyre::Result;
+use serde_json::{json, Value};
+use std::path::Path;
+
+use crate::metrics;
+
+/// A single dashboard panel plotting one PromQL expression
+struct Panel {
+    title: &'static str,
+    /// Grafana panel type ("stat" or "timeseries")
+    panel_type: &'static str,
+    expr: String,
+}
+
+fn panels() -> Vec<Panel> {
+    vec![
+        Panel {
+            title: "Positions tracked",
+            panel_type: "stat",
+            expr: metrics::POSITIONS_TRACKED.to_string(),
+        },
+        Panel {
+            title: "Positions at risk",
+            panel_type: "timeseries",
+            expr: metrics::POSITIONS_AT_RISK.to_string(),
+        },
+        Panel {
+            title: "Liquidatable positions",
+            panel_type: "timeseries",
+            expr: metrics::POSITIONS_LIQUIDATABLE.to_string(),
+        },
+        Panel {
+            title: "Value at risk",
+            panel_type: "timeseries",
+            expr: format!("{} / 1e8", metrics::VALUE_AT_RISK),
+        },
+        Panel {
+            title: "Circuit breaker state",
+            panel_type: "stat",
+            expr: metrics::CIRCUIT_BREAKER_STATE.to_string(),
+        },
+        Panel {
+            title: "Liquidations by outcome",
+            panel_type: "timeseries",
+            expr: format!("sum by (outcome) (rate({}[5m]))", metrics::LIQUIDATIONS_TOTAL),
+        },
+        Panel {
+            title: "Blocked liquidations",
+            panel_type: "timeseries",
+            expr: format!("rate({}[5m])", metrics::LIQUIDATIONS_BLOCKED_TOTAL),
+        },
+        Panel {
+            title: "PnL by strategy (ETH)",
+            panel_type: "timeseries",
+            expr: format!("sum by (strategy) ({}) / 1e18", metrics::PROFIT_WEI_TOTAL),
+        },
+        Panel {
+            title: "Liquidation queue depth",
+            panel_type: "timeseries",
+            expr: metrics::LIQUIDATION_QUEUE_DEPTH.to_string(),
+        },
+        Panel {
+            title: "RPC errors by source",
+            panel_type: "timeseries",
+            expr: format!("sum by (source) (rate({}[5m]))", metrics::RPC_ERRORS_TOTAL),
+        },
+        Panel {
+            title: "Throttled RPC requests by endpoint",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (endpoint) (rate({}[5m]))",
+                metrics::RPC_REQUESTS_THROTTLED_TOTAL
+            ),
+        },
+        Panel {
+            title: "Event loop lag (s)",
+            panel_type: "timeseries",
+            expr: metrics::EVENT_LOOP_LAG_SECONDS.to_string(),
+        },
+        Panel {
+            title: "Position write queue depth",
+            panel_type: "timeseries",
+            expr: metrics::POSITION_WRITE_QUEUE_DEPTH.to_string(),
+        },
+        Panel {
+            title: "Wallet native balance (ETH)",
+            panel_type: "timeseries",
+            expr: format!("{} / 1e18", metrics::WALLET_NATIVE_BALANCE_WEI),
+        },
+        Panel {
+            title: "Wallet debt asset balances",
+            panel_type: "timeseries",
+            expr: format!("sum by (token) ({})", metrics::WALLET_TOKEN_BALANCE),
+        },
+        Panel {
+            title: "Funded executor wallets",
+            panel_type: "stat",
+            expr: metrics::WALLETS_FUNDED.to_string(),
+        },
+        Panel {
+            title: "Event queue depth by lane",
+            panel_type: "timeseries",
+            expr: format!("sum by (lane) ({})", metrics::EVENT_QUEUE_DEPTH),
+        },
+        Panel {
+            title: "Coalesced events by lane",
+            panel_type: "timeseries",
+            expr: format!("sum by (lane) (rate({}[5m]))", metrics::EVENTS_COALESCED_TOTAL),
+        },
+        Panel {
+            title: "Dropped events by lane",
+            panel_type: "timeseries",
+            expr: format!("sum by (lane) (rate({}[5m]))", metrics::EVENTS_DROPPED_TOTAL),
+        },
+        Panel {
+            title: "Throttled position refreshes",
+            panel_type: "timeseries",
+            expr: format!("rate({}[5m])", metrics::POSITION_REFRESHES_THROTTLED_TOTAL),
+        },
+        Panel {
+            title: "Block scans",
+            panel_type: "timeseries",
+            expr: format!("rate({}[5m])", metrics::BLOCK_SCANS_TOTAL),
+        },
+        Panel {
+            title: "Block scans over budget",
+            panel_type: "timeseries",
+            expr: format!("rate({}[5m])", metrics::BLOCK_SCAN_BUDGET_EXHAUSTED_TOTAL),
+        },
+        Panel {
+            title: "Positions by scan tier",
+            panel_type: "timeseries",
+            expr: format!("sum by (tier) ({})", metrics::SCAN_TIER_POSITIONS),
+        },
+        Panel {
+            title: "Message bus publishes by outcome",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (outcome) (rate({}[5m]))",
+                metrics::EVENT_BUS_MESSAGES_TOTAL
+            ),
+        },
+        Panel {
+            title: "Event webhook deliveries by outcome",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (outcome) (rate({}[5m]))",
+                metrics::WEBHOOK_DELIVERIES_TOTAL
+            ),
+        },
+        Panel {
+            title: "Liquidation failures by category",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (category) (rate({}[5m]))",
+                metrics::LIQUIDATION_FAILURES_TOTAL
+            ),
+        },
+        Panel {
+            title: "Liquidation retry decisions",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (decision) (rate({}[5m]))",
+                metrics::LIQUIDATION_RETRY_DECISIONS_TOTAL
+            ),
+        },
+        Panel {
+            title: "Treasury sweeps",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (asset, outcome) (increase({}[1h]))",
+                metrics::TREASURY_SWEEPS_TOTAL
+            ),
+        },
+        Panel {
+            title: "Inventory value by asset (USD)",
+            panel_type: "timeseries",
+            expr: metrics::INVENTORY_VALUE_USD.to_string(),
+        },
+        Panel {
+            title: "Inventory rebalancing swaps",
+            panel_type: "timeseries",
+            expr: format!(
+                "sum by (asset, outcome) (increase({}[1h]))",
+                metrics::INVENTORY_REBALANCES_TOTAL
+            ),
+        },
+    ]
+}
+
+/// Build a Grafana dashboard (importable JSON model) covering the metrics the bot exports
+pub fn grafana_dashboard() -> Value {
+    let panels: Vec<Value> = panels()
+        .into_iter()
+        .enumerate()
+        .map(|(index, panel)| {
+            // Two panels per row, each half the dashboard width
+            let x = (index % 2) as u64 * 12;
+            let y = (index / 2) as u64 * 8;
+            json!({
+                "id": index + 1,
+                "title": panel.title,
+                "type": panel.panel_type,
+                "datasource": { "type": "prometheus", "uid": "${DS_PROMETHEUS}" },
+                "gridPos": { "h": 8, "w": 12, "x": x, "y": y },
+                "targets": [{ "expr": panel.expr, "refId": "A" }],
+            })
+        })
+        .collect();
+
+    json!({
+        "title": "Liquidation Bot",
+        "uid": "liquidation-bot",
+        "tags": ["liquidation-bot"],
+        "timezone": "browser",
+        "schemaVersion": 39,
+        "refresh": "30s",
+        "time": { "from": "now-6h", "to": "now" },
+        "__inputs": [{
+            "name": "DS_PROMETHEUS",
+            "label": "Prometheus",
+            "type": "datasource",
+            "pluginId": "prometheus",
+        }],
+        "panels": panels,
+    })
+}
+
+/// Write the Grafana dashboard JSON to `path`
+pub fn write_dashboard(path: &Path) -> Result<()> {
+    let dashboard = serde_json::to_string_pretty(&grafana_dashboard())?;
+    std::fs::write(path, dashboard)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Metric names referenced by a PromQL expression
+    fn referenced_metrics(expr: &str) -> Vec<&str> {
+        expr.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .filter(|token| token.starts_with("liquidation_bot_"))
+            .collect()
+    }
+
+    #[test]
+    fn test_every_dashboard_metric_is_registered() {
+        let dashboard = grafana_dashboard();
+        let panels = dashboard["panels"].as_array().unwrap();
+        assert!(!panels.is_empty());
+
+        for panel in panels {
+            for target in panel["targets"].as_array().unwrap() {
+                let expr = target["expr"].as_str().unwrap();
+                let referenced = referenced_metrics(expr);
+                assert!(!referenced.is_empty(), "panel expression '{}' has no metric", expr);
+
+                for name in referenced {
+                    assert!(
+                        metrics::find(name).is_some(),
+                        "dashboard references unregistered metric {}",
+                        name
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_registered_metric_has_a_panel() {
+        let exprs: Vec<String> = panels().into_iter().map(|panel| panel.expr).collect();
+
+        for metric in metrics::REGISTRY {
+            assert!(
+                exprs
+                    .iter()
+                    .any(|expr| referenced_metrics(expr).contains(&metric.name)),
+                "metric {} missing from dashboard",
+                metric.name
+            );
+        }
+    }
+}
@@ -1,25 +1,64 @@
-use crate::models::UserPosition;
-use alloy_primitives::Address;
+use crate::liquidation::{FailureCategory, RetryDecision};
+use crate::models::{
+    ATokenLot, CompetingLiquidation, CompetitorStats, LatencyTrace, LiquidationIntent, LiquidationOpportunity,
+    LiquidationReceipt, LiquidationRecord, LiquidationSettlement, PnlEntry, PnlSummary, PositionSnapshot, PricePoint,
+    PriceResolution, ProfitDivergence, ProfitabilityModel, ProtocolKind, ProtocolPosition,
+    StrategyPnl, UserPosition, UserReserve,
+};
+use crate::monitoring::metrics::record_position_write_queue_depth;
+use crate::monitoring::price_history::aggregate_price_points;
+use crate::protocols::aave_v3::position_from_account_data;
+use crate::treasury::Sweep;
+use alloy_primitives::{Address, I256, U256};
 use eyre::Result;
+use sqlx::migrate::Migrator;
 use sqlx::{Pool, Postgres, Row, Sqlite};
-use tracing::info;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tracing::{debug, error, info, warn};
 
 /// Database connection enum that can hold either PostgreSQL or SQLite connections
 #[derive(Clone)]
-pub enum DatabasePool {
+pub enum DatabaseBackend {
     Postgres(Pool<Postgres>),
     Sqlite(Pool<Sqlite>),
 }
 
+/// Database connection scoped to one chain. Every table carries a `chain_id` column: rows
+/// are written with this pool's chain and queries only see that chain's rows, so several
+/// chains can share one database.
+#[derive(Clone)]
+pub struct DatabasePool {
+    pub backend: DatabaseBackend,
+    pub chain_id: u64,
+}
+
 impl DatabasePool {
+    pub fn new(backend: DatabaseBackend, chain_id: u64) -> Self {
+        Self { backend, chain_id }
+    }
+
+    /// The same connections, scoped to another chain
+    pub fn for_chain(&self, chain_id: u64) -> Self {
+        Self::new(self.backend.clone(), chain_id)
+    }
+
+    /// Chain id as bound into queries
+    fn chain(&self) -> i64 {
+        self.chain_id as i64
+    }
+
     /// Execute a query
     pub async fn execute(&self, query: &str) -> Result<u64, sqlx::Error> {
-        match self {
-            DatabasePool::Postgres(pool) => {
+        match &self.backend {
+            DatabaseBackend::Postgres(pool) => {
                 let result = sqlx::query(query).execute(pool).await?;
                 Ok(result.rows_affected())
             }
-            DatabasePool::Sqlite(pool) => {
+            DatabaseBackend::Sqlite(pool) => {
                 let result = sqlx::query(query).execute(pool).await?;
                 Ok(result.rows_affected())
             }
@@ -41,8 +80,8 @@ fn detect_database_type(database_url: &str) -> Result<&'static str> {
     }
 }
 
-/// Initialize database with automatic type detection
-pub async fn init_database(database_url: &str) -> Result<DatabasePool> {
+/// Initialize database with automatic type detection, scoped to `chain_id`
+pub async fn init_database(database_url: &str, chain_id: u64) -> Result<DatabasePool> {
     let db_type = detect_database_type(database_url)?;
     info!("Detected database type: {}", db_type);
 
@@ -50,12 +89,12 @@ pub async fn init_database(database_url: &str) -> Result<DatabasePool> {
         "postgres" => {
             info!("🐘 Connecting to PostgreSQL database...");
             let pool = Pool::<Postgres>::connect(database_url).await?;
-            DatabasePool::Postgres(pool)
+            DatabasePool::new(DatabaseBackend::Postgres(pool), chain_id)
         }
         "sqlite" => {
             info!("🗄️ Connecting to SQLite database...");
             let pool = Pool::<Sqlite>::connect(database_url).await?;
-            DatabasePool::Sqlite(pool)
+            DatabasePool::new(DatabaseBackend::Sqlite(pool), chain_id)
         }
         _ => return Err(eyre::eyre!("Unsupported database type: {}", db_type)),
     };
@@ -65,124 +104,344 @@ pub async fn init_database(database_url: &str) -> Result<DatabasePool> {
     Ok(pool)
 }
 
-/// Create database tables for both PostgreSQL and SQLite
+/// Private SQLite database held in memory, for runs that must not touch the configured one.
+/// A single connection, since every SQLite connection opens its own in-memory database.
+pub async fn init_in_memory_database(chain_id: u64) -> Result<DatabasePool> {
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await?;
+    let pool = DatabasePool::new(DatabaseBackend::Sqlite(pool), chain_id);
+    create_tables(&pool).await?;
+    Ok(pool)
+}
+
+/// Versioned schema migrations for each backend, embedded at build time
+static POSTGRES_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/postgres");
+static SQLITE_MIGRATIONS: Migrator = sqlx::migrate!("./migrations/sqlite");
+
+/// Bring the database schema up to date by applying any pending migrations
 pub async fn create_tables(db_pool: &DatabasePool) -> Result<()> {
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
-            info!("Creating PostgreSQL tables...");
+    // Databases created before versioned migrations already hold the baseline tables; upgrade
+    // their columns first so the idempotent baseline migration can be recorded on top of them
+    if table_exists(db_pool, "liquidation_events").await?
+        && !table_exists(db_pool, "_sqlx_migrations").await?
+    {
+        info!("📦 Adopting database created before versioned migrations");
+        upgrade_unversioned_schema(db_pool).await?;
+    }
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            info!("Running PostgreSQL migrations...");
+            POSTGRES_MIGRATIONS.run(pool).await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            info!("Running SQLite migrations...");
+            SQLITE_MIGRATIONS.run(pool).await?;
+        }
+    }
+
+    canonicalize_addresses(db_pool).await?;
+
+    info!("✅ Database schema up to date");
+    Ok(())
+}
+
+/// Whether a table exists in the connected database
+async fn table_exists(db_pool: &DatabasePool, table: &str) -> Result<bool> {
+    let count: i64 = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM information_schema.tables WHERE table_schema = current_schema() AND table_name = $1",
+            )
+            .bind(table)
+            .fetch_one(pool)
+            .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_scalar(
+                "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = ?",
+            )
+            .bind(table)
+            .fetch_one(pool)
+            .await?
+        }
+    };
+    Ok(count > 0)
+}
 
-            // Create user_positions table with PostgreSQL syntax
+/// Lowercase every stored address column (run after duplicates have been removed)
+const CANONICALIZE_ADDRESS_STATEMENTS: &[&str] = &[
+    "UPDATE user_positions SET address = LOWER(address) WHERE address <> LOWER(address)",
+    "UPDATE liquidation_queue SET user_address = LOWER(user_address) WHERE user_address <> LOWER(user_address)",
+    "UPDATE liquidation_events SET user_address = LOWER(user_address), collateral_asset = LOWER(collateral_asset), debt_asset = LOWER(debt_asset) WHERE user_address <> LOWER(user_address) OR collateral_asset <> LOWER(collateral_asset) OR debt_asset <> LOWER(debt_asset)",
+    "UPDATE liquidation_audit SET user_address = LOWER(user_address) WHERE user_address <> LOWER(user_address)",
+    "UPDATE profitability_divergences SET user_address = LOWER(user_address), collateral_asset = LOWER(collateral_asset), debt_asset = LOWER(debt_asset) WHERE user_address <> LOWER(user_address) OR collateral_asset <> LOWER(collateral_asset) OR debt_asset <> LOWER(debt_asset)",
+];
+
+/// Receipt columns added to `liquidation_events` as (name, Postgres type, SQLite type)
+const LIQUIDATION_RECEIPT_COLUMNS: [(&str, &str, &str); 4] = [
+    ("tx_index", "BIGINT", "INTEGER"),
+    ("gas_used", "VARCHAR", "TEXT"),
+    ("effective_gas_price", "VARCHAR", "TEXT"),
+    ("succeeded", "BOOLEAN", "BOOLEAN"),
+];
+
+/// Add the columns older unversioned schemas are missing so they match the baseline migration
+async fn upgrade_unversioned_schema(db_pool: &DatabasePool) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            // Strategy attribution for liquidation events
             sqlx::query(
-                r#"
-                CREATE TABLE IF NOT EXISTS user_positions (
-                    address VARCHAR PRIMARY KEY,
-                    total_collateral_base VARCHAR NOT NULL,
-                    total_debt_base VARCHAR NOT NULL,
-                    available_borrows_base VARCHAR NOT NULL,
-                    current_liquidation_threshold VARCHAR NOT NULL,
-                    ltv VARCHAR NOT NULL,
-                    health_factor VARCHAR NOT NULL,
-                    last_updated TIMESTAMPTZ NOT NULL DEFAULT NOW(),
-                    is_at_risk BOOLEAN NOT NULL DEFAULT FALSE
-                );
-                "#,
+                "ALTER TABLE liquidation_events ADD COLUMN IF NOT EXISTS strategy_label VARCHAR NOT NULL DEFAULT 'default';",
             )
             .execute(pool)
             .await?;
 
-            // Create liquidation_events table
+            // Requested debt, so partial fills can be compared against debt_covered
             sqlx::query(
-                r#"
-                CREATE TABLE IF NOT EXISTS liquidation_events (
-                    id SERIAL PRIMARY KEY,
-                    user_address VARCHAR NOT NULL,
-                    collateral_asset VARCHAR NOT NULL,
-                    debt_asset VARCHAR NOT NULL,
-                    debt_covered VARCHAR NOT NULL,
-                    collateral_received VARCHAR NOT NULL,
-                    profit VARCHAR NOT NULL,
-                    tx_hash VARCHAR,
-                    block_number BIGINT,
-                    timestamp TIMESTAMPTZ NOT NULL DEFAULT NOW()
-                );
-                "#,
+                "ALTER TABLE liquidation_events ADD COLUMN IF NOT EXISTS requested_debt VARCHAR;",
             )
             .execute(pool)
             .await?;
 
-            // Create indexes
-            sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_positions_health_factor ON user_positions(health_factor);")
+            // Receipt fields of the liquidation transaction
+            for (column, column_type, _) in LIQUIDATION_RECEIPT_COLUMNS {
+                sqlx::query(&format!(
+                    "ALTER TABLE liquidation_events ADD COLUMN IF NOT EXISTS {} {};",
+                    column, column_type
+                ))
                 .execute(pool)
                 .await?;
-            sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_positions_is_at_risk ON user_positions(is_at_risk);")
-                .execute(pool)
-                .await?;
-            sqlx::query("CREATE INDEX IF NOT EXISTS idx_liquidation_events_timestamp ON liquidation_events(timestamp);")
+            }
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            // SQLite has no ADD COLUMN IF NOT EXISTS, so check the existing columns first
+            let columns: Vec<String> = sqlx::query("PRAGMA table_info(liquidation_events);")
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(|row| row.get::<String, _>("name"))
+                .collect();
+
+            if !columns.iter().any(|name| name == "strategy_label") {
+                sqlx::query(
+                    "ALTER TABLE liquidation_events ADD COLUMN strategy_label TEXT NOT NULL DEFAULT 'default';",
+                )
                 .execute(pool)
                 .await?;
+            }
+
+            if !columns.iter().any(|name| name == "requested_debt") {
+                sqlx::query("ALTER TABLE liquidation_events ADD COLUMN requested_debt TEXT;")
+                    .execute(pool)
+                    .await?;
+            }
+
+            for (column, _, column_type) in LIQUIDATION_RECEIPT_COLUMNS {
+                if !columns.iter().any(|name| name == column) {
+                    sqlx::query(&format!(
+                        "ALTER TABLE liquidation_events ADD COLUMN {} {};",
+                        column, column_type
+                    ))
+                    .execute(pool)
+                    .await?;
+                }
+            }
         }
-        DatabasePool::Sqlite(pool) => {
-            info!("Creating SQLite tables...");
+    }
+
+    Ok(())
+}
 
-            // Create user_positions table with SQLite syntax
+/// Canonicalize addresses stored before lowercasing, keeping the most recently updated row
+/// when the same address exists under several casings
+async fn canonicalize_addresses(db_pool: &DatabasePool) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             sqlx::query(
                 r#"
-                CREATE TABLE IF NOT EXISTS user_positions (
-                    address TEXT PRIMARY KEY,
-                    total_collateral_base TEXT NOT NULL,
-                    total_debt_base TEXT NOT NULL,
-                    available_borrows_base TEXT NOT NULL,
-                    current_liquidation_threshold TEXT NOT NULL,
-                    ltv TEXT NOT NULL,
-                    health_factor TEXT NOT NULL,
-                    last_updated DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP,
-                    is_at_risk BOOLEAN NOT NULL DEFAULT 0
-                );
+                DELETE FROM user_positions a USING user_positions b
+                WHERE a.chain_id = b.chain_id
+                  AND LOWER(a.address) = LOWER(b.address)
+                  AND a.address <> b.address
+                  AND (a.last_updated < b.last_updated
+                       OR (a.last_updated = b.last_updated AND a.address < b.address))
                 "#,
             )
             .execute(pool)
             .await?;
-
-            // Create liquidation_events table
             sqlx::query(
                 r#"
-                CREATE TABLE IF NOT EXISTS liquidation_events (
-                    id INTEGER PRIMARY KEY AUTOINCREMENT,
-                    user_address TEXT NOT NULL,
-                    collateral_asset TEXT NOT NULL,
-                    debt_asset TEXT NOT NULL,
-                    debt_covered TEXT NOT NULL,
-                    collateral_received TEXT NOT NULL,
-                    profit TEXT NOT NULL,
-                    tx_hash TEXT,
-                    block_number INTEGER,
-                    timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP
-                );
+                DELETE FROM liquidation_queue a USING liquidation_queue b
+                WHERE a.chain_id = b.chain_id
+                  AND LOWER(a.user_address) = LOWER(b.user_address)
+                  AND a.user_address <> b.user_address
+                  AND (a.queued_at > b.queued_at
+                       OR (a.queued_at = b.queued_at AND a.user_address < b.user_address))
                 "#,
             )
             .execute(pool)
             .await?;
-
-            // Create indexes
-            sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_positions_health_factor ON user_positions(health_factor);")
-                .execute(pool)
-                .await?;
-            sqlx::query("CREATE INDEX IF NOT EXISTS idx_user_positions_is_at_risk ON user_positions(is_at_risk);")
-                .execute(pool)
-                .await?;
-            sqlx::query("CREATE INDEX IF NOT EXISTS idx_liquidation_events_timestamp ON liquidation_events(timestamp);")
-                .execute(pool)
-                .await?;
+            for statement in CANONICALIZE_ADDRESS_STATEMENTS {
+                sqlx::query(statement).execute(pool).await?;
+            }
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                DELETE FROM user_positions
+                WHERE EXISTS (
+                    SELECT 1 FROM user_positions b
+                    WHERE b.chain_id = user_positions.chain_id
+                      AND LOWER(b.address) = LOWER(user_positions.address)
+                      AND b.address <> user_positions.address
+                      AND (user_positions.last_updated < b.last_updated
+                           OR (user_positions.last_updated = b.last_updated
+                               AND user_positions.address < b.address))
+                )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+            sqlx::query(
+                r#"
+                DELETE FROM liquidation_queue
+                WHERE EXISTS (
+                    SELECT 1 FROM liquidation_queue b
+                    WHERE b.chain_id = liquidation_queue.chain_id
+                      AND LOWER(b.user_address) = LOWER(liquidation_queue.user_address)
+                      AND b.user_address <> liquidation_queue.user_address
+                      AND (liquidation_queue.queued_at > b.queued_at
+                           OR (liquidation_queue.queued_at = b.queued_at
+                               AND liquidation_queue.user_address < b.user_address))
+                )
+                "#,
+            )
+            .execute(pool)
+            .await?;
+            for statement in CANONICALIZE_ADDRESS_STATEMENTS {
+                sqlx::query(statement).execute(pool).await?;
+            }
         }
     }
 
-    info!("✅ Database tables created successfully");
     Ok(())
 }
 
-/// Save or update user position
+/// Tables copied by `copy_sqlite_to_postgres`
+const COPIED_TABLES: [&str; 18] = [
+    "user_positions",
+    "liquidation_events",
+    "liquidation_audit",
+    "liquidation_queue",
+    "profitability_divergences",
+    "competitors",
+    "price_feeds",
+    "protocol_positions",
+    "sync_state",
+    "pnl",
+    "position_history",
+    "dry_run_liquidations",
+    "circuit_breaker_state",
+    "user_reserves",
+    "liquidation_latency",
+    "liquidation_failures",
+    "treasury_sweeps",
+    "a_token_lots",
+];
+
+/// Copy every row of a SQLite database into a Postgres database, both already migrated to the
+/// current schema. Tables that already hold rows in Postgres are skipped so an interrupted copy
+/// can be re-run. Returns the number of rows copied per table.
+pub async fn copy_sqlite_to_postgres(
+    source: &DatabasePool,
+    target: &DatabasePool,
+) -> Result<Vec<(&'static str, u64)>> {
+    let (DatabaseBackend::Sqlite(source), DatabaseBackend::Postgres(target)) =
+        (&source.backend, &target.backend)
+    else {
+        return Err(eyre::eyre!("Data can only be copied from SQLite into PostgreSQL"));
+    };
+
+    let mut copied = Vec::new();
+    for table in COPIED_TABLES {
+        let existing: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {}", table))
+            .fetch_one(target)
+            .await?;
+        if existing > 0 {
+            warn!("⚠️ Skipping {}: target table already holds {} rows", table, existing);
+            copied.push((table, 0));
+            continue;
+        }
+
+        // Auto-increment ids are left to the Postgres sequences
+        let columns: Vec<(String, String)> = sqlx::query(&format!("PRAGMA table_info({});", table))
+            .fetch_all(source)
+            .await?
+            .iter()
+            .map(|row| (row.get::<String, _>("name"), row.get::<String, _>("type").to_uppercase()))
+            .filter(|(name, _)| name != "id")
+            .collect();
+        let column_list = columns
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ");
+        let placeholders = (1..=columns.len())
+            .map(|index| format!("${}", index))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let insert = format!(
+            "INSERT INTO {} ({}) VALUES ({}) ON CONFLICT DO NOTHING",
+            table, column_list, placeholders
+        );
+
+        let rows = sqlx::query(&format!("SELECT {} FROM {} ORDER BY rowid", column_list, table))
+            .fetch_all(source)
+            .await?;
+
+        let mut tx = target.begin().await?;
+        let mut count = 0;
+        for row in &rows {
+            let mut query = sqlx::query(&insert);
+            for (index, (_, column_type)) in columns.iter().enumerate() {
+                query = match column_type.as_str() {
+                    "INTEGER" => query.bind(row.try_get::<Option<i64>, _>(index)?),
+                    "BOOLEAN" => query.bind(row.try_get::<Option<bool>, _>(index)?),
+                    "DATETIME" => {
+                        query.bind(row.try_get::<Option<chrono::DateTime<chrono::Utc>>, _>(index)?)
+                    }
+                    _ => query.bind(row.try_get::<Option<String>, _>(index)?),
+                };
+            }
+            count += query.execute(&mut *tx).await?.rows_affected();
+        }
+        tx.commit().await?;
+
+        info!("📦 Copied {} rows into {}", count, table);
+        copied.push((table, count));
+    }
+
+    Ok(copied)
+}
+
+/// Canonical (lowercase, 0x-prefixed) form every address is stored in, so the same address
+/// can't end up in two rows under different casings
+pub fn canonical_address(address: &Address) -> String {
+    address.to_string().to_lowercase()
+}
+
+/// Parse an address read back from the database, whatever its casing (rows written before
+/// canonicalization are checksummed)
+fn parse_stored_address(address: impl AsRef<str>) -> Result<Address> {
+    Ok(address.as_ref().parse()?)
+}
+
+/// Save or update user position, adding a health factor snapshot to its history
 pub async fn save_user_position(db_pool: &DatabasePool, position: &UserPosition) -> Result<()> {
-    let address_str = position.address.to_string();
+    let address_str = canonical_address(&position.address);
 
     // Convert Uint values to strings for database storage
     let total_collateral_str = position.total_collateral_base.to_string();
@@ -192,15 +451,16 @@ pub async fn save_user_position(db_pool: &DatabasePool, position: &UserPosition)
     let ltv_str = position.ltv.to_string();
     let health_factor_str = position.health_factor.to_string();
 
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             sqlx::query(
                 r#"
                 INSERT INTO user_positions (
                     address, total_collateral_base, total_debt_base, available_borrows_base,
-                    current_liquidation_threshold, ltv, health_factor, last_updated, is_at_risk
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
-                ON CONFLICT (address) 
+                    current_liquidation_threshold, ltv, health_factor, last_updated, is_at_risk,
+                    chain_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                ON CONFLICT (chain_id, address)
                 DO UPDATE SET
                     total_collateral_base = EXCLUDED.total_collateral_base,
                     total_debt_base = EXCLUDED.total_debt_base,
@@ -221,16 +481,36 @@ pub async fn save_user_position(db_pool: &DatabasePool, position: &UserPosition)
             .bind(&health_factor_str)
             .bind(&position.last_updated)
             .bind(&position.is_at_risk)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO position_history (
+                    address, health_factor, total_collateral_base, total_debt_base, is_at_risk,
+                    timestamp, chain_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(&address_str)
+            .bind(&health_factor_str)
+            .bind(&total_collateral_str)
+            .bind(&total_debt_str)
+            .bind(position.is_at_risk)
+            .bind(position.last_updated.timestamp())
+            .bind(db_pool.chain())
             .execute(pool)
             .await?;
         }
-        DatabasePool::Sqlite(pool) => {
+        DatabaseBackend::Sqlite(pool) => {
             sqlx::query(
                 r#"
                 INSERT OR REPLACE INTO user_positions (
                     address, total_collateral_base, total_debt_base, available_borrows_base,
-                    current_liquidation_threshold, ltv, health_factor, last_updated, is_at_risk
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+                    current_liquidation_threshold, ltv, health_factor, last_updated, is_at_risk,
+                    chain_id
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
                 "#,
             )
             .bind(&address_str)
@@ -242,11 +522,322 @@ pub async fn save_user_position(db_pool: &DatabasePool, position: &UserPosition)
             .bind(&health_factor_str)
             .bind(&position.last_updated)
             .bind(&position.is_at_risk)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+
+            sqlx::query(
+                r#"
+                INSERT INTO position_history (
+                    address, health_factor, total_collateral_base, total_debt_base, is_at_risk,
+                    timestamp, chain_id
+                ) VALUES (?, ?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(&address_str)
+            .bind(&health_factor_str)
+            .bind(&total_collateral_str)
+            .bind(&total_debt_str)
+            .bind(position.is_at_risk)
+            .bind(position.last_updated.timestamp())
+            .bind(db_pool.chain())
             .execute(pool)
             .await?;
         }
     }
 
+    // Mirror the account into the cross-protocol view through the Aave V3 adapter
+    save_protocol_position(db_pool, &position_from_account_data(position)).await
+}
+
+/// Columns written by the position upserts, in bind order
+const USER_POSITION_COLUMNS: &str = "address, total_collateral_base, total_debt_base, \
+    available_borrows_base, current_liquidation_threshold, ltv, health_factor, last_updated, \
+    is_at_risk, chain_id";
+
+/// Columns written to `position_history`, in bind order
+const POSITION_HISTORY_COLUMNS: &str = "address, health_factor, total_collateral_base, \
+    total_debt_base, is_at_risk, timestamp, chain_id";
+
+/// Columns written to `protocol_positions`, in bind order
+const PROTOCOL_POSITION_COLUMNS: &str = "protocol, address, collateral_value, debt_value, \
+    health_factor, is_liquidatable, last_updated, chain_id";
+
+/// Rows per multi-row upsert statement, keeping well under SQLite's bound-parameter limit
+const POSITION_UPSERT_CHUNK_ROWS: usize = 100;
+
+/// Save or update many user positions with multi-row upserts in a single transaction, adding a
+/// history snapshot and the Aave V3 `protocol_positions` snapshot for each. Each address may
+/// appear only once (Postgres rejects a statement that updates a row twice).
+pub async fn save_user_positions(db_pool: &DatabasePool, positions: &[UserPosition]) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let mut tx = pool.begin().await?;
+            for chunk in positions.chunks(POSITION_UPSERT_CHUNK_ROWS) {
+                let mut query = sqlx::QueryBuilder::<Postgres>::new(format!(
+                    "INSERT INTO user_positions ({}) ",
+                    USER_POSITION_COLUMNS
+                ));
+                query.push_values(chunk, |mut row, position| {
+                    row.push_bind(canonical_address(&position.address))
+                        .push_bind(position.total_collateral_base.to_string())
+                        .push_bind(position.total_debt_base.to_string())
+                        .push_bind(position.available_borrows_base.to_string())
+                        .push_bind(position.current_liquidation_threshold.to_string())
+                        .push_bind(position.ltv.to_string())
+                        .push_bind(position.health_factor.to_string())
+                        .push_bind(position.last_updated)
+                        .push_bind(position.is_at_risk)
+                        .push_bind(db_pool.chain());
+                });
+                query.push(
+                    r#"
+                    ON CONFLICT (chain_id, address)
+                    DO UPDATE SET
+                        total_collateral_base = EXCLUDED.total_collateral_base,
+                        total_debt_base = EXCLUDED.total_debt_base,
+                        available_borrows_base = EXCLUDED.available_borrows_base,
+                        current_liquidation_threshold = EXCLUDED.current_liquidation_threshold,
+                        ltv = EXCLUDED.ltv,
+                        health_factor = EXCLUDED.health_factor,
+                        last_updated = EXCLUDED.last_updated,
+                        is_at_risk = EXCLUDED.is_at_risk
+                    "#,
+                );
+                query.build().execute(&mut *tx).await?;
+
+                let mut history = sqlx::QueryBuilder::<Postgres>::new(format!(
+                    "INSERT INTO position_history ({}) ",
+                    POSITION_HISTORY_COLUMNS
+                ));
+                history.push_values(chunk, |mut row, position| {
+                    row.push_bind(canonical_address(&position.address))
+                        .push_bind(position.health_factor.to_string())
+                        .push_bind(position.total_collateral_base.to_string())
+                        .push_bind(position.total_debt_base.to_string())
+                        .push_bind(position.is_at_risk)
+                        .push_bind(position.last_updated.timestamp())
+                        .push_bind(db_pool.chain());
+                });
+                history.build().execute(&mut *tx).await?;
+
+                let snapshots: Vec<ProtocolPosition> =
+                    chunk.iter().map(position_from_account_data).collect();
+                let mut protocol = sqlx::QueryBuilder::<Postgres>::new(format!(
+                    "INSERT INTO protocol_positions ({}) ",
+                    PROTOCOL_POSITION_COLUMNS
+                ));
+                protocol.push_values(&snapshots, |mut row, snapshot| {
+                    row.push_bind(snapshot.protocol.as_str())
+                        .push_bind(canonical_address(&snapshot.address))
+                        .push_bind(snapshot.collateral_value.to_string())
+                        .push_bind(snapshot.debt_value.to_string())
+                        .push_bind(snapshot.health_factor.to_string())
+                        .push_bind(snapshot.is_liquidatable)
+                        .push_bind(snapshot.last_updated)
+                        .push_bind(db_pool.chain());
+                });
+                protocol.push(
+                    r#"
+                    ON CONFLICT (chain_id, protocol, address)
+                    DO UPDATE SET
+                        collateral_value = EXCLUDED.collateral_value,
+                        debt_value = EXCLUDED.debt_value,
+                        health_factor = EXCLUDED.health_factor,
+                        is_liquidatable = EXCLUDED.is_liquidatable,
+                        last_updated = EXCLUDED.last_updated
+                    "#,
+                );
+                protocol.build().execute(&mut *tx).await?;
+            }
+            tx.commit().await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            let mut tx = pool.begin().await?;
+            for chunk in positions.chunks(POSITION_UPSERT_CHUNK_ROWS) {
+                let mut query = sqlx::QueryBuilder::<Sqlite>::new(format!(
+                    "INSERT OR REPLACE INTO user_positions ({}) ",
+                    USER_POSITION_COLUMNS
+                ));
+                query.push_values(chunk, |mut row, position| {
+                    row.push_bind(canonical_address(&position.address))
+                        .push_bind(position.total_collateral_base.to_string())
+                        .push_bind(position.total_debt_base.to_string())
+                        .push_bind(position.available_borrows_base.to_string())
+                        .push_bind(position.current_liquidation_threshold.to_string())
+                        .push_bind(position.ltv.to_string())
+                        .push_bind(position.health_factor.to_string())
+                        .push_bind(position.last_updated)
+                        .push_bind(position.is_at_risk)
+                        .push_bind(db_pool.chain());
+                });
+                query.build().execute(&mut *tx).await?;
+
+                let mut history = sqlx::QueryBuilder::<Sqlite>::new(format!(
+                    "INSERT INTO position_history ({}) ",
+                    POSITION_HISTORY_COLUMNS
+                ));
+                history.push_values(chunk, |mut row, position| {
+                    row.push_bind(canonical_address(&position.address))
+                        .push_bind(position.health_factor.to_string())
+                        .push_bind(position.total_collateral_base.to_string())
+                        .push_bind(position.total_debt_base.to_string())
+                        .push_bind(position.is_at_risk)
+                        .push_bind(position.last_updated.timestamp())
+                        .push_bind(db_pool.chain());
+                });
+                history.build().execute(&mut *tx).await?;
+
+                let snapshots: Vec<ProtocolPosition> =
+                    chunk.iter().map(position_from_account_data).collect();
+                let mut protocol = sqlx::QueryBuilder::<Sqlite>::new(format!(
+                    "INSERT OR REPLACE INTO protocol_positions ({}) ",
+                    PROTOCOL_POSITION_COLUMNS
+                ));
+                protocol.push_values(&snapshots, |mut row, snapshot| {
+                    row.push_bind(snapshot.protocol.as_str())
+                        .push_bind(canonical_address(&snapshot.address))
+                        .push_bind(snapshot.collateral_value.to_string())
+                        .push_bind(snapshot.debt_value.to_string())
+                        .push_bind(snapshot.health_factor.to_string())
+                        .push_bind(snapshot.is_liquidatable)
+                        .push_bind(snapshot.last_updated)
+                        .push_bind(db_pool.chain());
+                });
+                protocol.build().execute(&mut *tx).await?;
+            }
+            tx.commit().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Requests handled by the position writer task
+enum PositionWrite {
+    Upsert(UserPosition),
+    Flush(oneshot::Sender<Result<()>>),
+}
+
+/// Buffered writer for position upserts, so scans don't wait on the database for every user.
+/// Queued updates are written in multi-row batches once `batch_size` distinct positions are
+/// pending or `flush_interval` has passed; only the latest update per address is written.
+#[derive(Clone)]
+pub struct PositionWriter {
+    tx: mpsc::UnboundedSender<PositionWrite>,
+    queued: Arc<AtomicUsize>,
+}
+
+impl PositionWriter {
+    /// Start the writer task on the current runtime. It writes whatever is still pending and
+    /// stops once every handle has been dropped.
+    pub fn spawn(db_pool: DatabasePool, batch_size: usize, flush_interval: Duration) -> Self {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let queued = Arc::new(AtomicUsize::new(0));
+        tokio::spawn(run_position_writer(
+            db_pool,
+            rx,
+            queued.clone(),
+            batch_size.max(1),
+            flush_interval,
+        ));
+        Self { tx, queued }
+    }
+
+    /// Queue a position upsert without waiting for it to be written
+    pub fn enqueue(&self, position: UserPosition) -> Result<()> {
+        let depth = self.queued.fetch_add(1, Ordering::Relaxed) + 1;
+        if self.tx.send(PositionWrite::Upsert(position)).is_err() {
+            self.queued.fetch_sub(1, Ordering::Relaxed);
+            return Err(eyre::eyre!("Position writer has stopped"));
+        }
+        record_position_write_queue_depth(depth);
+        Ok(())
+    }
+
+    /// Position updates queued but not yet written
+    pub fn queue_depth(&self) -> usize {
+        self.queued.load(Ordering::Relaxed)
+    }
+
+    /// Write everything queued so far and wait for the write to finish
+    pub async fn flush(&self) -> Result<()> {
+        let (done_tx, done_rx) = oneshot::channel();
+        self.tx
+            .send(PositionWrite::Flush(done_tx))
+            .map_err(|_| eyre::eyre!("Position writer has stopped"))?;
+        done_rx
+            .await
+            .map_err(|_| eyre::eyre!("Position writer stopped before flushing"))?
+    }
+}
+
+/// Positions waiting in the writer, keyed by address so later updates replace earlier ones
+#[derive(Default)]
+struct PendingPositions {
+    positions: HashMap<Address, UserPosition>,
+    /// Updates folded into `positions`, including superseded ones
+    received: usize,
+}
+
+async fn run_position_writer(
+    db_pool: DatabasePool,
+    mut rx: mpsc::UnboundedReceiver<PositionWrite>,
+    queued: Arc<AtomicUsize>,
+    batch_size: usize,
+    flush_interval: Duration,
+) {
+    let mut pending = PendingPositions::default();
+    let mut ticker =
+        tokio::time::interval_at(tokio::time::Instant::now() + flush_interval, flush_interval);
+
+    loop {
+        tokio::select! {
+            request = rx.recv() => match request {
+                Some(PositionWrite::Upsert(position)) => {
+                    pending.received += 1;
+                    pending.positions.insert(position.address, position);
+                    if pending.positions.len() >= batch_size {
+                        let _ = flush_pending_positions(&db_pool, &mut pending, &queued).await;
+                    }
+                }
+                Some(PositionWrite::Flush(done)) => {
+                    let result = flush_pending_positions(&db_pool, &mut pending, &queued).await;
+                    let _ = done.send(result);
+                }
+                None => {
+                    let _ = flush_pending_positions(&db_pool, &mut pending, &queued).await;
+                    return;
+                }
+            },
+            _ = ticker.tick() => {
+                let _ = flush_pending_positions(&db_pool, &mut pending, &queued).await;
+            }
+        }
+    }
+}
+
+/// Write the pending positions; on failure they stay pending and are retried on the next flush
+async fn flush_pending_positions(
+    db_pool: &DatabasePool,
+    pending: &mut PendingPositions,
+    queued: &AtomicUsize,
+) -> Result<()> {
+    if pending.positions.is_empty() {
+        return Ok(());
+    }
+
+    let positions: Vec<UserPosition> = pending.positions.values().cloned().collect();
+    if let Err(e) = save_user_positions(db_pool, &positions).await {
+        error!("Failed to write {} batched user positions: {}", positions.len(), e);
+        return Err(e);
+    }
+
+    debug!("💾 Wrote {} batched user positions", positions.len());
+    let written = std::mem::take(&mut pending.received);
+    pending.positions.clear();
+    let depth = queued.fetch_sub(written, Ordering::Relaxed) - written;
+    record_position_write_queue_depth(depth);
     Ok(())
 }
 
@@ -255,14 +846,16 @@ pub async fn get_user_position(
     db_pool: &DatabasePool,
     address: Address,
 ) -> Result<Option<UserPosition>> {
-    let address_str = address.to_string().to_lowercase();
+    let address_str = canonical_address(&address);
 
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
-            let row_opt = sqlx::query("SELECT * FROM user_positions WHERE LOWER(address) = $1")
-                .bind(&address_str)
-                .fetch_optional(pool)
-                .await?;
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let row_opt =
+                sqlx::query("SELECT * FROM user_positions WHERE address = $1 AND chain_id = $2")
+                    .bind(&address_str)
+                    .bind(db_pool.chain())
+                    .fetch_optional(pool)
+                    .await?;
 
             if let Some(row) = row_opt {
                 let position = UserPosition {
@@ -285,11 +878,13 @@ pub async fn get_user_position(
                 Ok(None)
             }
         }
-        DatabasePool::Sqlite(pool) => {
-            let row_opt = sqlx::query("SELECT * FROM user_positions WHERE LOWER(address) = ?")
-                .bind(&address_str)
-                .fetch_optional(pool)
-                .await?;
+        DatabaseBackend::Sqlite(pool) => {
+            let row_opt =
+                sqlx::query("SELECT * FROM user_positions WHERE address = ? AND chain_id = ?")
+                    .bind(&address_str)
+                    .bind(db_pool.chain())
+                    .fetch_optional(pool)
+                    .await?;
 
             if let Some(row) = row_opt {
                 let position = UserPosition {
@@ -317,15 +912,18 @@ pub async fn get_user_position(
 
 /// Get all user positions (ordered by last_updated)
 pub async fn get_all_user_positions(db_pool: &DatabasePool) -> Result<Vec<UserPosition>> {
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
-            let rows = sqlx::query("SELECT * FROM user_positions ORDER BY last_updated DESC")
-                .fetch_all(pool)
-                .await?;
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let rows = sqlx::query(
+                "SELECT * FROM user_positions WHERE chain_id = $1 ORDER BY last_updated DESC",
+            )
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let position = UserPosition {
                     address,
                     total_collateral_base: row.get::<String, _>("total_collateral_base").parse()?,
@@ -345,14 +943,17 @@ pub async fn get_all_user_positions(db_pool: &DatabasePool) -> Result<Vec<UserPo
             }
             Ok(positions)
         }
-        DatabasePool::Sqlite(pool) => {
-            let rows = sqlx::query("SELECT * FROM user_positions ORDER BY last_updated DESC")
-                .fetch_all(pool)
-                .await?;
+        DatabaseBackend::Sqlite(pool) => {
+            let rows = sqlx::query(
+                "SELECT * FROM user_positions WHERE chain_id = ? ORDER BY last_updated DESC",
+            )
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let position = UserPosition {
                     address,
                     total_collateral_base: row.get::<String, _>("total_collateral_base").parse()?,
@@ -385,23 +986,23 @@ pub async fn get_at_risk_users_with_limit(
     db_pool: &DatabasePool,
     limit: Option<usize>,
 ) -> Result<Vec<UserPosition>> {
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             let query_str = if let Some(limit) = limit {
                 format!(
-                    "SELECT * FROM user_positions WHERE is_at_risk = true ORDER BY health_factor ASC LIMIT {}",
+                    "SELECT * FROM user_positions WHERE is_at_risk = true AND chain_id = $1 ORDER BY health_factor ASC LIMIT {}",
                     limit
                 )
             } else {
-                "SELECT * FROM user_positions WHERE is_at_risk = true ORDER BY health_factor ASC"
+                "SELECT * FROM user_positions WHERE is_at_risk = true AND chain_id = $1 ORDER BY health_factor ASC"
                     .to_string()
             };
 
-            let rows = sqlx::query(&query_str).fetch_all(pool).await?;
+            let rows = sqlx::query(&query_str).bind(db_pool.chain()).fetch_all(pool).await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let position = UserPosition {
                     address,
                     total_collateral_base: row.get::<String, _>("total_collateral_base").parse()?,
@@ -421,22 +1022,22 @@ pub async fn get_at_risk_users_with_limit(
             }
             Ok(positions)
         }
-        DatabasePool::Sqlite(pool) => {
+        DatabaseBackend::Sqlite(pool) => {
             let query_str = if let Some(limit) = limit {
                 format!(
-                    "SELECT * FROM user_positions WHERE is_at_risk = 1 ORDER BY health_factor ASC LIMIT {}",
+                    "SELECT * FROM user_positions WHERE is_at_risk = 1 AND chain_id = ? ORDER BY health_factor ASC LIMIT {}",
                     limit
                 )
             } else {
-                "SELECT * FROM user_positions WHERE is_at_risk = 1 ORDER BY health_factor ASC"
+                "SELECT * FROM user_positions WHERE is_at_risk = 1 AND chain_id = ? ORDER BY health_factor ASC"
                     .to_string()
             };
 
-            let rows = sqlx::query(&query_str).fetch_all(pool).await?;
+            let rows = sqlx::query(&query_str).bind(db_pool.chain()).fetch_all(pool).await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let position = UserPosition {
                     address,
                     total_collateral_base: row.get::<String, _>("total_collateral_base").parse()?,
@@ -461,75 +1062,2130 @@ pub async fn get_at_risk_users_with_limit(
 
 /// Get user position count
 pub async fn get_user_position_count(db_pool: &DatabasePool) -> Result<i64> {
-    let count = match db_pool {
-        DatabasePool::Postgres(pool) => {
-            let row = sqlx::query("SELECT COUNT(*) as count FROM user_positions")
+    let count = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let row = sqlx::query("SELECT COUNT(*) as count FROM user_positions WHERE chain_id = $1")
+                .bind(db_pool.chain())
                 .fetch_one(pool)
                 .await?;
             row.get::<i64, _>("count")
         }
-        DatabasePool::Sqlite(pool) => {
-            let row = sqlx::query("SELECT COUNT(*) as count FROM user_positions")
-                .fetch_one(pool)
-                .await?;
-            row.get::<i32, _>("count") as i64
+        DatabaseBackend::Sqlite(pool) => {
+            let row = sqlx::query("SELECT COUNT(*) as count FROM user_positions WHERE chain_id = ?")
+                .bind(db_pool.chain())
+                .fetch_one(pool)
+                .await?;
+            row.get::<i32, _>("count") as i64
+        }
+    };
+    Ok(count)
+}
+
+/// Record a liquidation event
+pub async fn record_liquidation_event(
+    db_pool: &DatabasePool,
+    user_address: &Address,
+    collateral_asset: &str,
+    debt_asset: &str,
+    debt_covered: &str,
+    requested_debt: Option<&str>,
+    collateral_received: &str,
+    profit: &str,
+    tx_hash: Option<&str>,
+    block_number: Option<i64>,
+    strategy_label: &str,
+) -> Result<()> {
+    let user_str = canonical_address(user_address);
+    let collateral_asset = collateral_asset.to_lowercase();
+    let debt_asset = debt_asset.to_lowercase();
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO liquidation_events (
+                    user_address, collateral_asset, debt_asset, debt_covered,
+                    requested_debt, collateral_received, profit, tx_hash, block_number,
+                    strategy_label, chain_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                "#,
+            )
+            .bind(&user_str)
+            .bind(&collateral_asset)
+            .bind(&debt_asset)
+            .bind(debt_covered)
+            .bind(requested_debt)
+            .bind(collateral_received)
+            .bind(profit)
+            .bind(tx_hash)
+            .bind(block_number)
+            .bind(strategy_label)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO liquidation_events (
+                    user_address, collateral_asset, debt_asset, debt_covered,
+                    requested_debt, collateral_received, profit, tx_hash, block_number,
+                    strategy_label, chain_id, timestamp
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
+                "#,
+            )
+            .bind(&user_str)
+            .bind(&collateral_asset)
+            .bind(&debt_asset)
+            .bind(debt_covered)
+            .bind(requested_debt)
+            .bind(collateral_received)
+            .bind(profit)
+            .bind(tx_hash)
+            .bind(block_number)
+            .bind(strategy_label)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record a liquidation that dry-run mode would have broadcast, with its profit after the
+/// pre-flight simulation
+pub async fn record_dry_run_liquidation(
+    db_pool: &DatabasePool,
+    opportunity: &LiquidationOpportunity,
+    simulated_profit: U256,
+    gas_used: Option<u64>,
+    strategy_label: &str,
+) -> Result<()> {
+    let user_str = canonical_address(&opportunity.user);
+    let collateral_asset = canonical_address(&opportunity.collateral_asset);
+    let debt_asset = canonical_address(&opportunity.debt_asset);
+    let debt_to_cover = opportunity.debt_to_cover.to_string();
+    let estimated_profit = opportunity.estimated_profit.to_string();
+    let simulated_profit = simulated_profit.to_string();
+    let gas_used = gas_used.map(|gas| gas as i64);
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO dry_run_liquidations (
+                    user_address, collateral_asset, debt_asset, debt_to_cover, estimated_profit,
+                    simulated_profit, gas_used, strategy_label, chain_id
+                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                "#,
+            )
+            .bind(&user_str)
+            .bind(&collateral_asset)
+            .bind(&debt_asset)
+            .bind(&debt_to_cover)
+            .bind(&estimated_profit)
+            .bind(&simulated_profit)
+            .bind(gas_used)
+            .bind(strategy_label)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO dry_run_liquidations (
+                    user_address, collateral_asset, debt_asset, debt_to_cover, estimated_profit,
+                    simulated_profit, gas_used, strategy_label, chain_id, timestamp
+                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
+                "#,
+            )
+            .bind(&user_str)
+            .bind(&collateral_asset)
+            .bind(&debt_asset)
+            .bind(&debt_to_cover)
+            .bind(&estimated_profit)
+            .bind(&simulated_profit)
+            .bind(gas_used)
+            .bind(strategy_label)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Liquidation events recorded at or after `from_block` as (row id, tx hash)
+pub async fn get_liquidation_events_from_block(
+    db_pool: &DatabasePool,
+    from_block: u64,
+) -> Result<Vec<(i64, Option<String>)>> {
+    let rows = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(
+                "SELECT CAST(id AS BIGINT), tx_hash FROM liquidation_events WHERE block_number >= $1 AND chain_id = $2 ORDER BY id",
+            )
+            .bind(from_block as i64)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(
+                "SELECT id, tx_hash FROM liquidation_events WHERE block_number >= ? AND chain_id = ? ORDER BY id",
+            )
+            .bind(from_block as i64)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+        }
+    };
+    Ok(rows)
+}
+
+/// Move a liquidation event to the block its transaction was re-included in
+pub async fn update_liquidation_event_block(
+    db_pool: &DatabasePool,
+    id: i64,
+    block_number: u64,
+) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query("UPDATE liquidation_events SET block_number = $1 WHERE id = $2")
+                .bind(block_number as i64)
+                .bind(id as i32)
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query("UPDATE liquidation_events SET block_number = ? WHERE id = ?")
+                .bind(block_number as i64)
+                .bind(id)
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Store a mined transaction's receipt fields on the liquidation event(s) recorded for it
+pub async fn record_liquidation_receipt(
+    db_pool: &DatabasePool,
+    receipt: &LiquidationReceipt,
+) -> Result<()> {
+    let block_number = receipt.block_number.map(|block| block as i64);
+    let tx_index = receipt.tx_index.map(|index| index as i64);
+    let gas_used = receipt.gas_used.to_string();
+    let effective_gas_price = receipt.effective_gas_price.to_string();
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                UPDATE liquidation_events
+                SET block_number = $1, tx_index = $2, gas_used = $3, effective_gas_price = $4,
+                    succeeded = $5
+                WHERE tx_hash = $6
+                "#,
+            )
+            .bind(block_number)
+            .bind(tx_index)
+            .bind(&gas_used)
+            .bind(&effective_gas_price)
+            .bind(receipt.succeeded)
+            .bind(&receipt.tx_hash)
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                UPDATE liquidation_events
+                SET block_number = ?, tx_index = ?, gas_used = ?, effective_gas_price = ?,
+                    succeeded = ?
+                WHERE tx_hash = ?
+                "#,
+            )
+            .bind(block_number)
+            .bind(tx_index)
+            .bind(&gas_used)
+            .bind(&effective_gas_price)
+            .bind(receipt.succeeded)
+            .bind(&receipt.tx_hash)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Delete a liquidation event whose transaction was orphaned by a reorg
+pub async fn delete_liquidation_event(db_pool: &DatabasePool, id: i64) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query("DELETE FROM liquidation_events WHERE id = $1")
+                .bind(id as i32)
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query("DELETE FROM liquidation_events WHERE id = ?")
+                .bind(id)
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Summarize recorded liquidation profit per strategy label, ordered by label.
+///
+/// Profits are stored as decimal strings, so they are summed here rather than in SQL.
+pub async fn get_pnl_by_strategy(db_pool: &DatabasePool) -> Result<Vec<StrategyPnl>> {
+    let query_pg = "SELECT strategy_label, profit FROM liquidation_events WHERE chain_id = $1 ORDER BY strategy_label ASC, id ASC";
+    let query_sqlite = "SELECT strategy_label, profit FROM liquidation_events WHERE chain_id = ? ORDER BY strategy_label ASC, id ASC";
+
+    let rows: Vec<(String, String)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => sqlx::query(query_pg)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| (row.get("strategy_label"), row.get("profit")))
+            .collect(),
+        DatabaseBackend::Sqlite(pool) => sqlx::query(query_sqlite)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| (row.get("strategy_label"), row.get("profit")))
+            .collect(),
+    };
+
+    let mut summary: Vec<StrategyPnl> = Vec::new();
+    for (strategy_label, profit) in rows {
+        let profit = profit.parse::<U256>().unwrap_or_else(|e| {
+            warn!(
+                "Ignoring unparseable profit '{}' for strategy '{}': {}",
+                profit, strategy_label, e
+            );
+            U256::ZERO
+        });
+
+        match summary.last_mut() {
+            Some(entry) if entry.strategy_label == strategy_label => {
+                entry.liquidation_count += 1;
+                entry.total_profit = entry.total_profit.saturating_add(profit);
+            }
+            _ => summary.push(StrategyPnl {
+                strategy_label,
+                liquidation_count: 1,
+                total_profit: profit,
+            }),
+        }
+    }
+
+    Ok(summary)
+}
+
+/// (user, collateral asset, debt asset, debt covered, profit, tx hash, block, strategy,
+/// succeeded, timestamp)
+type LiquidationRecordRow = (
+    String,
+    String,
+    String,
+    String,
+    String,
+    Option<String>,
+    Option<i64>,
+    String,
+    Option<bool>,
+    chrono::DateTime<chrono::Utc>,
+);
+
+/// Most recent `limit` liquidation events, newest first
+pub async fn get_recent_liquidations(
+    db_pool: &DatabasePool,
+    limit: u32,
+) -> Result<Vec<LiquidationRecord>> {
+    let query_pg = r#"
+        SELECT user_address, collateral_asset, debt_asset, debt_covered, profit, tx_hash,
+               block_number, strategy_label, succeeded, timestamp
+        FROM liquidation_events
+        WHERE chain_id = $1
+        ORDER BY id DESC
+        LIMIT $2
+    "#;
+    let query_sqlite = r#"
+        SELECT user_address, collateral_asset, debt_asset, debt_covered, profit, tx_hash,
+               block_number, strategy_label, succeeded, timestamp
+        FROM liquidation_events
+        WHERE chain_id = ?
+        ORDER BY id DESC
+        LIMIT ?
+    "#;
+
+    let rows: Vec<LiquidationRecordRow> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(db_pool.chain())
+                .bind(limit as i64)
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(db_pool.chain())
+                .bind(limit as i64)
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    rows.into_iter()
+        .map(|row| {
+            let (
+                user,
+                collateral_asset,
+                debt_asset,
+                debt_covered,
+                profit,
+                tx_hash,
+                block_number,
+                strategy_label,
+                succeeded,
+                timestamp,
+            ) = row;
+            Ok(LiquidationRecord {
+                user: parse_stored_address(user)?,
+                collateral_asset,
+                debt_asset,
+                debt_covered: debt_covered.parse()?,
+                profit: profit.parse()?,
+                tx_hash,
+                block_number: block_number.map(|block| block as u64),
+                strategy_label,
+                succeeded,
+                timestamp,
+            })
+        })
+        .collect()
+}
+
+fn parse_signed(value: &str) -> Result<I256> {
+    I256::from_dec_str(value).map_err(|e| eyre::eyre!("Invalid signed amount '{}': {}", value, e))
+}
+
+/// Running P&L total as of the latest pnl row recorded before `before` (unix seconds; zero if
+/// there is none)
+pub async fn get_cumulative_pnl(db_pool: &DatabasePool, before: u64) -> Result<I256> {
+    let query_pg = "SELECT cumulative_pnl FROM pnl WHERE timestamp < $1 AND chain_id = $2 ORDER BY id DESC LIMIT 1";
+    let query_sqlite = "SELECT cumulative_pnl FROM pnl WHERE timestamp < ? AND chain_id = ? ORDER BY id DESC LIMIT 1";
+
+    let before = before.min(i64::MAX as u64) as i64;
+    let row: Option<(String,)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(before)
+                .bind(db_pool.chain())
+                .fetch_optional(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(before)
+                .bind(db_pool.chain())
+                .fetch_optional(pool)
+                .await?
+        }
+    };
+    row.map_or(Ok(I256::ZERO), |(cumulative,)| parse_signed(&cumulative))
+}
+
+/// Record one liquidation's P&L and return the running total including it
+pub async fn record_pnl_entry(db_pool: &DatabasePool, entry: &PnlEntry) -> Result<I256> {
+    let cumulative = get_cumulative_pnl(db_pool, u64::MAX)
+        .await?
+        .saturating_add(entry.total_pnl());
+
+    let query_pg = r#"
+        INSERT INTO pnl (tx_hash, user_address, collateral_asset, debt_asset, block_number,
+                         collateral_value, debt_value, flash_loan_fee, gas_cost, realized_pnl,
+                         unrealized_pnl, cumulative_pnl, timestamp, chain_id, wallet)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15)
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO pnl (tx_hash, user_address, collateral_asset, debt_asset, block_number,
+                         collateral_value, debt_value, flash_loan_fee, gas_cost, realized_pnl,
+                         unrealized_pnl, cumulative_pnl, timestamp, chain_id, wallet)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+    let block_number = entry.block_number.map(|block| block as i64);
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(&entry.tx_hash)
+                .bind(canonical_address(&entry.user))
+                .bind(canonical_address(&entry.collateral_asset))
+                .bind(canonical_address(&entry.debt_asset))
+                .bind(block_number)
+                .bind(entry.collateral_value.to_string())
+                .bind(entry.debt_value.to_string())
+                .bind(entry.flash_loan_fee.to_string())
+                .bind(entry.gas_cost.to_string())
+                .bind(entry.realized_pnl.to_string())
+                .bind(entry.unrealized_pnl.to_string())
+                .bind(cumulative.to_string())
+                .bind(entry.timestamp as i64)
+                .bind(db_pool.chain())
+                .bind(canonical_address(&entry.wallet))
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(&entry.tx_hash)
+                .bind(canonical_address(&entry.user))
+                .bind(canonical_address(&entry.collateral_asset))
+                .bind(canonical_address(&entry.debt_asset))
+                .bind(block_number)
+                .bind(entry.collateral_value.to_string())
+                .bind(entry.debt_value.to_string())
+                .bind(entry.flash_loan_fee.to_string())
+                .bind(entry.gas_cost.to_string())
+                .bind(entry.realized_pnl.to_string())
+                .bind(entry.unrealized_pnl.to_string())
+                .bind(cumulative.to_string())
+                .bind(entry.timestamp as i64)
+                .bind(db_pool.chain())
+                .bind(canonical_address(&entry.wallet))
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(cumulative)
+}
+
+/// Total P&L recorded per executor wallet, ordered by wallet. Rows recorded before wallets
+/// were tracked are left out.
+pub async fn get_pnl_by_wallet(db_pool: &DatabasePool) -> Result<Vec<(Address, I256)>> {
+    let query_pg = "SELECT wallet, realized_pnl, unrealized_pnl FROM pnl WHERE wallet IS NOT NULL AND chain_id = $1 ORDER BY wallet ASC";
+    let query_sqlite = "SELECT wallet, realized_pnl, unrealized_pnl FROM pnl WHERE wallet IS NOT NULL AND chain_id = ? ORDER BY wallet ASC";
+
+    let rows: Vec<(String, String, String)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    let mut totals: Vec<(Address, I256)> = Vec::new();
+    for (wallet, realized, unrealized) in rows {
+        let wallet = parse_stored_address(&wallet)?;
+        let pnl = parse_signed(&realized)?.saturating_add(parse_signed(&unrealized)?);
+        match totals.last_mut() {
+            Some((last, total)) if *last == wallet => *total = total.saturating_add(pnl),
+            _ => totals.push((wallet, pnl)),
+        }
+    }
+    Ok(totals)
+}
+
+/// P&L of the liquidations recorded in `[from, to)` (unix seconds)
+pub async fn get_pnl_summary(db_pool: &DatabasePool, from: u64, to: u64) -> Result<PnlSummary> {
+    let query_pg = r#"
+        SELECT realized_pnl, unrealized_pnl, flash_loan_fee, gas_cost
+        FROM pnl
+        WHERE timestamp >= $1 AND timestamp < $2 AND chain_id = $3
+    "#;
+    let query_sqlite = r#"
+        SELECT realized_pnl, unrealized_pnl, flash_loan_fee, gas_cost
+        FROM pnl
+        WHERE timestamp >= ? AND timestamp < ? AND chain_id = ?
+    "#;
+
+    let rows: Vec<(String, String, String, String)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(from as i64)
+                .bind(to as i64)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(from as i64)
+                .bind(to as i64)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    let mut summary = PnlSummary {
+        cumulative_pnl: get_cumulative_pnl(db_pool, to).await?,
+        ..PnlSummary::default()
+    };
+    for (realized, unrealized, flash_loan_fee, gas_cost) in rows {
+        summary.liquidations += 1;
+        summary.realized_pnl = summary.realized_pnl.saturating_add(parse_signed(&realized)?);
+        summary.unrealized_pnl = summary
+            .unrealized_pnl
+            .saturating_add(parse_signed(&unrealized)?);
+        summary.flash_loan_fees = summary
+            .flash_loan_fees
+            .saturating_add(flash_loan_fee.parse()?);
+        summary.gas_costs = summary.gas_costs.saturating_add(gas_cost.parse()?);
+    }
+    Ok(summary)
+}
+
+/// Record how long one handled liquidation opportunity spent in each pipeline stage
+pub async fn record_liquidation_latency(
+    db_pool: &DatabasePool,
+    user: Address,
+    tx_hash: Option<&str>,
+    outcome: &str,
+    trace: &LatencyTrace,
+    recorded_at_ms: i64,
+) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO liquidation_latency (user_address, tx_hash, outcome, log_received_ms,
+                                         hf_confirmed_ms, profitability_done_ms, tx_sent_ms,
+                                         tx_mined_ms, recorded_at_ms, chain_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO liquidation_latency (user_address, tx_hash, outcome, log_received_ms,
+                                         hf_confirmed_ms, profitability_done_ms, tx_sent_ms,
+                                         tx_mined_ms, recorded_at_ms, chain_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(&user))
+                .bind(tx_hash)
+                .bind(outcome)
+                .bind(trace.log_received_ms)
+                .bind(trace.hf_confirmed_ms)
+                .bind(trace.profitability_done_ms)
+                .bind(trace.tx_sent_ms)
+                .bind(trace.tx_mined_ms)
+                .bind(recorded_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(&user))
+                .bind(tx_hash)
+                .bind(outcome)
+                .bind(trace.log_received_ms)
+                .bind(trace.hf_confirmed_ms)
+                .bind(trace.profitability_done_ms)
+                .bind(trace.tx_sent_ms)
+                .bind(trace.tx_mined_ms)
+                .bind(recorded_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Latency traces recorded at or after `since_ms` (unix milliseconds), oldest first
+pub async fn get_latency_traces(db_pool: &DatabasePool, since_ms: i64) -> Result<Vec<LatencyTrace>> {
+    let query_pg = r#"
+        SELECT log_received_ms, hf_confirmed_ms, profitability_done_ms, tx_sent_ms, tx_mined_ms
+        FROM liquidation_latency
+        WHERE recorded_at_ms >= $1 AND chain_id = $2
+        ORDER BY id ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT log_received_ms, hf_confirmed_ms, profitability_done_ms, tx_sent_ms, tx_mined_ms
+        FROM liquidation_latency
+        WHERE recorded_at_ms >= ? AND chain_id = ?
+        ORDER BY id ASC
+    "#;
+
+    type TraceRow = (Option<i64>, Option<i64>, Option<i64>, Option<i64>, Option<i64>);
+    let rows: Vec<TraceRow> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(since_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(since_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    Ok(rows
+        .into_iter()
+        .map(
+            |(log_received_ms, hf_confirmed_ms, profitability_done_ms, tx_sent_ms, tx_mined_ms)| {
+                LatencyTrace {
+                    log_received_ms,
+                    hf_confirmed_ms,
+                    profitability_done_ms,
+                    tx_sent_ms,
+                    tx_mined_ms,
+                }
+            },
+        )
+        .collect())
+}
+
+/// Record the category and error of a failed liquidation attempt, its 1-based attempt number
+/// and the retry policy's decision
+pub async fn record_liquidation_failure(
+    db_pool: &DatabasePool,
+    user: Address,
+    category: FailureCategory,
+    error: &str,
+    attempt: u32,
+    decision: RetryDecision,
+    recorded_at_ms: i64,
+) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO liquidation_failures (user_address, category, error, attempt, decision, recorded_at_ms, chain_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO liquidation_failures (user_address, category, error, attempt, decision, recorded_at_ms, chain_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?)
+    "#;
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(&user))
+                .bind(category.as_str())
+                .bind(error)
+                .bind(attempt as i32)
+                .bind(decision.as_str())
+                .bind(recorded_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(&user))
+                .bind(category.as_str())
+                .bind(error)
+                .bind(attempt as i32)
+                .bind(decision.as_str())
+                .bind(recorded_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Failed liquidation attempts recorded at or after `since_ms` (unix milliseconds), counted
+/// per category, most frequent first
+pub async fn get_liquidation_failure_counts(
+    db_pool: &DatabasePool,
+    since_ms: i64,
+) -> Result<Vec<(FailureCategory, u64)>> {
+    let query_pg = r#"
+        SELECT category, COUNT(*) FROM liquidation_failures
+        WHERE recorded_at_ms >= $1 AND chain_id = $2
+        GROUP BY category
+        ORDER BY COUNT(*) DESC, category ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT category, COUNT(*) FROM liquidation_failures
+        WHERE recorded_at_ms >= ? AND chain_id = ?
+        GROUP BY category
+        ORDER BY COUNT(*) DESC, category ASC
+    "#;
+
+    let rows: Vec<(String, i64)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(since_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(since_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    Ok(rows
+        .into_iter()
+        .map(|(category, count)| {
+            (
+                FailureCategory::from_name(&category).unwrap_or(FailureCategory::Other),
+                count.max(0) as u64,
+            )
+        })
+        .collect())
+}
+
+/// Record a transfer of profits from an executor wallet to the cold wallet
+pub async fn record_treasury_sweep(
+    db_pool: &DatabasePool,
+    sweep: &Sweep,
+    swept_at_ms: i64,
+) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO treasury_sweeps (wallet_address, cold_address, asset, token_address, amount, tx_hash, swept_at_ms, chain_id)
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO treasury_sweeps (wallet_address, cold_address, asset, token_address, amount, tx_hash, swept_at_ms, chain_id)
+        VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+    let token_address = sweep.token.as_ref().map(canonical_address);
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(&sweep.wallet))
+                .bind(canonical_address(&sweep.cold_wallet))
+                .bind(&sweep.symbol)
+                .bind(token_address)
+                .bind(sweep.amount.to_string())
+                .bind(&sweep.tx_hash)
+                .bind(swept_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(&sweep.wallet))
+                .bind(canonical_address(&sweep.cold_wallet))
+                .bind(&sweep.symbol)
+                .bind(token_address)
+                .bind(sweep.amount.to_string())
+                .bind(&sweep.tx_hash)
+                .bind(swept_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Total swept to the cold wallet per asset since `since_ms` (unix milliseconds)
+pub async fn get_treasury_sweep_totals(
+    db_pool: &DatabasePool,
+    since_ms: i64,
+) -> Result<BTreeMap<String, U256>> {
+    let query_pg = r#"
+        SELECT asset, amount FROM treasury_sweeps
+        WHERE swept_at_ms >= $1 AND chain_id = $2
+    "#;
+    let query_sqlite = r#"
+        SELECT asset, amount FROM treasury_sweeps
+        WHERE swept_at_ms >= ? AND chain_id = ?
+    "#;
+
+    // Amounts are stored as decimal text, so they are summed here rather than in SQL
+    let rows: Vec<(String, String)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(since_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(since_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    let mut totals = BTreeMap::new();
+    for (asset, amount) in rows {
+        let amount = amount.parse::<U256>().unwrap_or_default();
+        let total: &mut U256 = totals.entry(asset).or_default();
+        *total = total.saturating_add(amount);
+    }
+    Ok(totals)
+}
+
+/// Record collateral a liquidation left in `wallet` as aTokens of `asset`
+pub async fn record_a_token_lot(
+    db_pool: &DatabasePool,
+    wallet: Address,
+    asset: Address,
+    amount: U256,
+    tx_hash: &str,
+    acquired_at_ms: i64,
+) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO a_token_lots (wallet_address, asset, amount, tx_hash, acquired_at_ms, chain_id)
+        VALUES ($1, $2, $3, $4, $5, $6)
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO a_token_lots (wallet_address, asset, amount, tx_hash, acquired_at_ms, chain_id)
+        VALUES (?, ?, ?, ?, ?, ?)
+    "#;
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(&wallet))
+                .bind(canonical_address(&asset))
+                .bind(amount.to_string())
+                .bind(tx_hash)
+                .bind(acquired_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(&wallet))
+                .bind(canonical_address(&asset))
+                .bind(amount.to_string())
+                .bind(tx_hash)
+                .bind(acquired_at_ms)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// aToken lots not unwound yet that were acquired before `acquired_before_ms`, oldest first
+pub async fn get_open_a_token_lots(
+    db_pool: &DatabasePool,
+    acquired_before_ms: i64,
+) -> Result<Vec<ATokenLot>> {
+    let query_pg = r#"
+        SELECT CAST(id AS BIGINT), wallet_address, asset, amount, tx_hash, acquired_at_ms FROM a_token_lots
+        WHERE unwound_at_ms IS NULL AND acquired_at_ms < $1 AND chain_id = $2
+        ORDER BY acquired_at_ms
+    "#;
+    let query_sqlite = r#"
+        SELECT id, wallet_address, asset, amount, tx_hash, acquired_at_ms FROM a_token_lots
+        WHERE unwound_at_ms IS NULL AND acquired_at_ms < ? AND chain_id = ?
+        ORDER BY acquired_at_ms
+    "#;
+
+    let rows: Vec<(i64, String, String, String, String, i64)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(acquired_before_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(acquired_before_ms)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    rows.into_iter()
+        .map(|(id, wallet, asset, amount, tx_hash, acquired_at_ms)| {
+            Ok(ATokenLot {
+                id,
+                wallet: parse_stored_address(wallet)?,
+                asset: parse_stored_address(asset)?,
+                amount: amount.parse::<U256>().unwrap_or_default(),
+                tx_hash,
+                acquired_at_ms,
+            })
+        })
+        .collect()
+}
+
+/// Close an aToken lot once its collateral was withdrawn from the pool
+pub async fn mark_a_token_lot_unwound(
+    db_pool: &DatabasePool,
+    id: i64,
+    unwind_tx_hash: &str,
+    unwound_at_ms: i64,
+) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                "UPDATE a_token_lots SET unwound_at_ms = $1, unwind_tx_hash = $2 WHERE id = $3",
+            )
+            .bind(unwound_at_ms)
+            .bind(unwind_tx_hash)
+            .bind(id as i32)
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                "UPDATE a_token_lots SET unwound_at_ms = ?, unwind_tx_hash = ? WHERE id = ?",
+            )
+            .bind(unwound_at_ms)
+            .bind(unwind_tx_hash)
+            .bind(id)
+            .execute(pool)
+            .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Record the intent phase of a liquidation (written at submission time)
+pub async fn record_liquidation_intent(
+    db_pool: &DatabasePool,
+    intent: &LiquidationIntent,
+) -> Result<()> {
+    let user_str = canonical_address(&intent.user);
+    let nonce = i64::try_from(intent.nonce)?;
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO liquidation_audit (
+                    tx_hash, phase, user_address, nonce, gas_price, gas_limit, estimated_profit,
+                    chain_id
+                ) VALUES ($1, 'intent', $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(&intent.tx_hash)
+            .bind(&user_str)
+            .bind(nonce)
+            .bind(intent.gas_price.to_string())
+            .bind(intent.gas_limit.to_string())
+            .bind(intent.estimated_profit.to_string())
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO liquidation_audit (
+                    tx_hash, phase, user_address, nonce, gas_price, gas_limit, estimated_profit,
+                    chain_id, timestamp
+                ) VALUES (?, 'intent', ?, ?, ?, ?, ?, ?, datetime('now'))
+                "#,
+            )
+            .bind(&intent.tx_hash)
+            .bind(&user_str)
+            .bind(nonce)
+            .bind(intent.gas_price.to_string())
+            .bind(intent.gas_limit.to_string())
+            .bind(intent.estimated_profit.to_string())
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Record the settlement phase of a liquidation (written once the transaction is confirmed)
+pub async fn record_liquidation_settlement(
+    db_pool: &DatabasePool,
+    settlement: &LiquidationSettlement,
+) -> Result<()> {
+    let block_number = settlement.block_number.map(i64::try_from).transpose()?;
+    let tx_index = settlement.tx_index.map(i64::try_from).transpose()?;
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO liquidation_audit (
+                    tx_hash, phase, block_number, tx_index, gas_used, realized_profit, succeeded,
+                    chain_id
+                ) VALUES ($1, 'settlement', $2, $3, $4, $5, $6, $7)
+                "#,
+            )
+            .bind(&settlement.tx_hash)
+            .bind(block_number)
+            .bind(tx_index)
+            .bind(settlement.gas_used.to_string())
+            .bind(settlement.realized_profit.to_string())
+            .bind(settlement.succeeded)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO liquidation_audit (
+                    tx_hash, phase, block_number, tx_index, gas_used, realized_profit, succeeded,
+                    chain_id, timestamp
+                ) VALUES (?, 'settlement', ?, ?, ?, ?, ?, ?, datetime('now'))
+                "#,
+            )
+            .bind(&settlement.tx_hash)
+            .bind(block_number)
+            .bind(tx_index)
+            .bind(settlement.gas_used.to_string())
+            .bind(settlement.realized_profit.to_string())
+            .bind(settlement.succeeded)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get the audit phases recorded for a transaction, in insertion order
+pub async fn get_liquidation_audit_phases(
+    db_pool: &DatabasePool,
+    tx_hash: &str,
+) -> Result<Vec<String>> {
+    let phases = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query("SELECT phase FROM liquidation_audit WHERE tx_hash = $1 ORDER BY id ASC")
+                .bind(tx_hash)
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(|row| row.get::<String, _>("phase"))
+                .collect()
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query("SELECT phase FROM liquidation_audit WHERE tx_hash = ? ORDER BY id ASC")
+                .bind(tx_hash)
+                .fetch_all(pool)
+                .await?
+                .iter()
+                .map(|row| row.get::<String, _>("phase"))
+                .collect()
+        }
+    };
+    Ok(phases)
+}
+
+/// Get tx hashes of liquidations that were submitted but never confirmed
+pub async fn get_unsettled_liquidation_intents(db_pool: &DatabasePool) -> Result<Vec<String>> {
+    let query_pg = r#"
+        SELECT intent.tx_hash AS tx_hash FROM liquidation_audit intent
+        WHERE intent.phase = 'intent'
+          AND intent.chain_id = $1
+          AND NOT EXISTS (
+              SELECT 1 FROM liquidation_audit settlement
+              WHERE settlement.tx_hash = intent.tx_hash AND settlement.phase = 'settlement'
+          )
+        ORDER BY intent.id ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT intent.tx_hash AS tx_hash FROM liquidation_audit intent
+        WHERE intent.phase = 'intent'
+          AND intent.chain_id = ?
+          AND NOT EXISTS (
+              SELECT 1 FROM liquidation_audit settlement
+              WHERE settlement.tx_hash = intent.tx_hash AND settlement.phase = 'settlement'
+          )
+        ORDER BY intent.id ASC
+    "#;
+
+    let tx_hashes = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => sqlx::query(query_pg)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| row.get::<String, _>("tx_hash"))
+            .collect(),
+        DatabaseBackend::Sqlite(pool) => sqlx::query(query_sqlite)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+            .iter()
+            .map(|row| row.get::<String, _>("tx_hash"))
+            .collect(),
+    };
+    Ok(tx_hashes)
+}
+
+/// Record a disagreement between the live and shadow profitability models
+pub async fn record_profit_divergence(
+    db_pool: &DatabasePool,
+    divergence: &ProfitDivergence,
+) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO profitability_divergences (
+            user_address, collateral_asset, debt_asset, live_model, shadow_model,
+            live_profit, shadow_profit, divergence_bps, chain_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO profitability_divergences (
+            user_address, collateral_asset, debt_asset, live_model, shadow_model,
+            live_profit, shadow_profit, divergence_bps, chain_id
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(&divergence.user))
+                .bind(canonical_address(&divergence.collateral_asset))
+                .bind(canonical_address(&divergence.debt_asset))
+                .bind(divergence.live_model.as_str())
+                .bind(divergence.shadow_model.as_str())
+                .bind(divergence.live_profit.to_string())
+                .bind(divergence.shadow_profit.to_string())
+                .bind(divergence.divergence_bps as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(&divergence.user))
+                .bind(canonical_address(&divergence.collateral_asset))
+                .bind(canonical_address(&divergence.debt_asset))
+                .bind(divergence.live_model.as_str())
+                .bind(divergence.shadow_model.as_str())
+                .bind(divergence.live_profit.to_string())
+                .bind(divergence.shadow_profit.to_string())
+                .bind(divergence.divergence_bps as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get recorded profitability model divergences, oldest first
+pub async fn get_profit_divergences(db_pool: &DatabasePool) -> Result<Vec<ProfitDivergence>> {
+    let query_pg = r#"
+        SELECT user_address, collateral_asset, debt_asset, live_model, shadow_model,
+               live_profit, shadow_profit, divergence_bps
+        FROM profitability_divergences WHERE chain_id = $1 ORDER BY id ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT user_address, collateral_asset, debt_asset, live_model, shadow_model,
+               live_profit, shadow_profit, divergence_bps
+        FROM profitability_divergences WHERE chain_id = ? ORDER BY id ASC
+    "#;
+
+    // (user, collateral, debt, live model, shadow model, live profit, shadow profit, bps)
+    type DivergenceRow = (String, String, String, String, String, String, String, i64);
+
+    let rows: Vec<DivergenceRow> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg).bind(db_pool.chain()).fetch_all(pool).await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite).bind(db_pool.chain()).fetch_all(pool).await?
+        }
+    };
+
+    let parse_model = |name: &str| {
+        ProfitabilityModel::from_name(name)
+            .ok_or_else(|| eyre::eyre!("Unknown profitability model '{}'", name))
+    };
+
+    rows.into_iter()
+        .map(
+            |(user, collateral, debt, live_model, shadow_model, live_profit, shadow_profit, bps)| {
+                Ok(ProfitDivergence {
+                    user: parse_stored_address(user)?,
+                    collateral_asset: parse_stored_address(collateral)?,
+                    debt_asset: parse_stored_address(debt)?,
+                    live_model: parse_model(&live_model)?,
+                    shadow_model: parse_model(&shadow_model)?,
+                    live_profit: live_profit.parse()?,
+                    shadow_profit: shadow_profit.parse()?,
+                    divergence_bps: bps as u64,
+                })
+            },
+        )
+        .collect()
+}
+
+/// Count a pending liquidation seen from another liquidator
+pub async fn record_competitor_sighting(
+    db_pool: &DatabasePool,
+    rival: &CompetingLiquidation,
+) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO competitors (
+            liquidator_address, sightings, last_user_address, last_tx_hash, last_priority_fee,
+            chain_id
+        ) VALUES ($1, 1, $2, $3, $4, $5)
+        ON CONFLICT (chain_id, liquidator_address)
+        DO UPDATE SET
+            sightings = competitors.sightings + 1,
+            last_user_address = EXCLUDED.last_user_address,
+            last_tx_hash = EXCLUDED.last_tx_hash,
+            last_priority_fee = EXCLUDED.last_priority_fee,
+            last_seen = NOW()
+    "#;
+    let query_sqlite = r#"
+        INSERT INTO competitors (
+            liquidator_address, sightings, last_user_address, last_tx_hash, last_priority_fee,
+            chain_id
+        ) VALUES (?, 1, ?, ?, ?, ?)
+        ON CONFLICT (chain_id, liquidator_address)
+        DO UPDATE SET
+            sightings = competitors.sightings + 1,
+            last_user_address = excluded.last_user_address,
+            last_tx_hash = excluded.last_tx_hash,
+            last_priority_fee = excluded.last_priority_fee,
+            last_seen = CURRENT_TIMESTAMP
+    "#;
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(&rival.liquidator))
+                .bind(canonical_address(&rival.user))
+                .bind(&rival.tx_hash)
+                .bind(rival.priority_fee_per_gas.to_string())
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(&rival.liquidator))
+                .bind(canonical_address(&rival.user))
+                .bind(&rival.tx_hash)
+                .bind(rival.priority_fee_per_gas.to_string())
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Count a liquidation of ours that ran into `liquidator`'s pending transaction
+pub async fn record_competitor_race(db_pool: &DatabasePool, liquidator: &Address) -> Result<()> {
+    let query_pg =
+        "UPDATE competitors SET races = races + 1 WHERE liquidator_address = $1 AND chain_id = $2";
+    let query_sqlite =
+        "UPDATE competitors SET races = races + 1 WHERE liquidator_address = ? AND chain_id = ?";
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(canonical_address(liquidator))
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(canonical_address(liquidator))
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get every competing liquidator seen so far, most active first
+pub async fn get_competitor_stats(db_pool: &DatabasePool) -> Result<Vec<CompetitorStats>> {
+    let query_pg = r#"
+        SELECT liquidator_address, sightings, races, last_user_address, last_tx_hash,
+               last_priority_fee
+        FROM competitors WHERE chain_id = $1 ORDER BY sightings DESC, liquidator_address ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT liquidator_address, sightings, races, last_user_address, last_tx_hash,
+               last_priority_fee
+        FROM competitors WHERE chain_id = ? ORDER BY sightings DESC, liquidator_address ASC
+    "#;
+
+    // (liquidator, sightings, races, last user, last tx hash, last priority fee)
+    type CompetitorRow = (String, i64, i64, String, String, String);
+
+    let rows: Vec<CompetitorRow> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg).bind(db_pool.chain()).fetch_all(pool).await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite).bind(db_pool.chain()).fetch_all(pool).await?
+        }
+    };
+
+    rows.into_iter()
+        .map(|(liquidator, sightings, races, last_user, last_tx_hash, last_priority_fee)| {
+            Ok(CompetitorStats {
+                liquidator: parse_stored_address(liquidator)?,
+                sightings: sightings as u64,
+                races: races as u64,
+                last_user: parse_stored_address(last_user)?,
+                last_tx_hash,
+                last_priority_fee: last_priority_fee.parse()?,
+            })
+        })
+        .collect()
+}
+
+/// Insert or update an account's snapshot on one protocol
+pub async fn save_protocol_position(db_pool: &DatabasePool, position: &ProtocolPosition) -> Result<()> {
+    let query_pg = r#"
+        INSERT INTO protocol_positions (
+            protocol, address, collateral_value, debt_value, health_factor, is_liquidatable, last_updated,
+            chain_id
+        ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        ON CONFLICT (chain_id, protocol, address)
+        DO UPDATE SET
+            collateral_value = EXCLUDED.collateral_value,
+            debt_value = EXCLUDED.debt_value,
+            health_factor = EXCLUDED.health_factor,
+            is_liquidatable = EXCLUDED.is_liquidatable,
+            last_updated = EXCLUDED.last_updated
+    "#;
+    let query_sqlite = r#"
+        INSERT OR REPLACE INTO protocol_positions (
+            protocol, address, collateral_value, debt_value, health_factor, is_liquidatable, last_updated,
+            chain_id
+        ) VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+    "#;
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(query_pg)
+                .bind(position.protocol.as_str())
+                .bind(canonical_address(&position.address))
+                .bind(position.collateral_value.to_string())
+                .bind(position.debt_value.to_string())
+                .bind(position.health_factor.to_string())
+                .bind(position.is_liquidatable)
+                .bind(position.last_updated)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(query_sqlite)
+                .bind(position.protocol.as_str())
+                .bind(canonical_address(&position.address))
+                .bind(position.collateral_value.to_string())
+                .bind(position.debt_value.to_string())
+                .bind(position.health_factor.to_string())
+                .bind(position.is_liquidatable)
+                .bind(position.last_updated)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get every stored snapshot for `protocol`, lowest health factor first
+pub async fn get_protocol_positions(
+    db_pool: &DatabasePool,
+    protocol: ProtocolKind,
+) -> Result<Vec<ProtocolPosition>> {
+    let query_pg = r#"
+        SELECT address, collateral_value, debt_value, health_factor, is_liquidatable, last_updated
+        FROM protocol_positions WHERE protocol = $1 AND chain_id = $2
+    "#;
+    let query_sqlite = r#"
+        SELECT address, collateral_value, debt_value, health_factor, is_liquidatable, last_updated
+        FROM protocol_positions WHERE protocol = ? AND chain_id = ?
+    "#;
+
+    // (address, collateral value, debt value, health factor, liquidatable, last updated)
+    type ProtocolPositionRow = (String, String, String, String, bool, chrono::DateTime<chrono::Utc>);
+
+    let rows: Vec<ProtocolPositionRow> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(protocol.as_str())
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(protocol.as_str())
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    // Health factors are stored as decimal strings, so order numerically after parsing
+    let mut positions = rows
+        .into_iter()
+        .map(
+            |(address, collateral_value, debt_value, health_factor, is_liquidatable, last_updated)| {
+                Ok(ProtocolPosition {
+                    protocol,
+                    address: parse_stored_address(address)?,
+                    collateral_value: collateral_value.parse()?,
+                    debt_value: debt_value.parse()?,
+                    health_factor: health_factor.parse()?,
+                    is_liquidatable,
+                    last_updated,
+                })
+            },
+        )
+        .collect::<Result<Vec<_>>>()?;
+    positions.sort_by_key(|position| position.health_factor);
+    Ok(positions)
+}
+
+const INSERT_PRICE_POINT_PG: &str = r#"
+    INSERT INTO price_feeds (asset_address, resolution, price, min_price, max_price, sample_count, timestamp,
+                             chain_id)
+    VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+"#;
+const INSERT_PRICE_POINT_SQLITE: &str = r#"
+    INSERT INTO price_feeds (asset_address, resolution, price, min_price, max_price, sample_count, timestamp,
+                             chain_id)
+    VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+"#;
+
+/// (asset, resolution, price, min price, max price, sample count, timestamp)
+type PricePointRow = (String, String, String, String, String, i64, i64);
+
+fn parse_price_point(row: PricePointRow) -> Result<PricePoint> {
+    let (asset, resolution, price, min_price, max_price, sample_count, timestamp) = row;
+    Ok(PricePoint {
+        asset: parse_stored_address(asset)?,
+        timestamp: timestamp as u64,
+        resolution: PriceResolution::from_name(&resolution)
+            .ok_or_else(|| eyre::eyre!("Unknown price resolution '{}'", resolution))?,
+        price: price.parse()?,
+        min_price: min_price.parse()?,
+        max_price: max_price.parse()?,
+        sample_count: sample_count as u64,
+    })
+}
+
+/// Record one polled oracle price at `timestamp` (unix seconds)
+pub async fn record_price(
+    db_pool: &DatabasePool,
+    asset: Address,
+    price: U256,
+    timestamp: u64,
+) -> Result<()> {
+    let asset_str = canonical_address(&asset);
+    let price_str = price.to_string();
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(INSERT_PRICE_POINT_PG)
+                .bind(&asset_str)
+                .bind(PriceResolution::Raw.as_str())
+                .bind(&price_str)
+                .bind(&price_str)
+                .bind(&price_str)
+                .bind(1i64)
+                .bind(timestamp as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(INSERT_PRICE_POINT_SQLITE)
+                .bind(&asset_str)
+                .bind(PriceResolution::Raw.as_str())
+                .bind(&price_str)
+                .bind(&price_str)
+                .bind(&price_str)
+                .bind(1i64)
+                .bind(timestamp as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Price history for `asset` between `from` and `to` (unix seconds, inclusive), aggregated to
+/// `resolution`. Ranges that were already downsampled further are returned at their stored
+/// resolution.
+pub async fn get_price_history(
+    db_pool: &DatabasePool,
+    asset: Address,
+    from: u64,
+    to: u64,
+    resolution: PriceResolution,
+) -> Result<Vec<PricePoint>> {
+    let query_pg = r#"
+        SELECT asset_address, resolution, price, min_price, max_price, sample_count, timestamp
+        FROM price_feeds
+        WHERE asset_address = $1 AND timestamp >= $2 AND timestamp <= $3 AND chain_id = $4
+        ORDER BY timestamp ASC, id ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT asset_address, resolution, price, min_price, max_price, sample_count, timestamp
+        FROM price_feeds
+        WHERE asset_address = ? AND timestamp >= ? AND timestamp <= ? AND chain_id = ?
+        ORDER BY timestamp ASC, id ASC
+    "#;
+
+    let asset_str = canonical_address(&asset);
+    let rows: Vec<PricePointRow> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(&asset_str)
+                .bind(from as i64)
+                .bind(to as i64)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(&asset_str)
+                .bind(from as i64)
+                .bind(to as i64)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    let points = rows
+        .into_iter()
+        .map(parse_price_point)
+        .collect::<Result<Vec<_>>>()?;
+    Ok(aggregate_price_points(&points, resolution))
+}
+
+/// Assets with recorded price history, ordered by address
+pub async fn get_price_feed_assets(db_pool: &DatabasePool) -> Result<Vec<Address>> {
+    let query_pg = "SELECT DISTINCT asset_address FROM price_feeds WHERE chain_id = $1 ORDER BY asset_address ASC";
+    let query_sqlite = "SELECT DISTINCT asset_address FROM price_feeds WHERE chain_id = ? ORDER BY asset_address ASC";
+    let rows: Vec<(String,)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg).bind(db_pool.chain()).fetch_all(pool).await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite).bind(db_pool.chain()).fetch_all(pool).await?
+        }
+    };
+    rows.into_iter()
+        .map(|(asset,)| parse_stored_address(asset))
+        .collect()
+}
+
+/// Aggregate `from` rows older than `before` (unix seconds, rounded down to a whole `to`
+/// bucket so no bucket is split) into `to` rows. Returns the number of rows removed.
+pub async fn downsample_price_history(
+    db_pool: &DatabasePool,
+    from: PriceResolution,
+    to: PriceResolution,
+    before: u64,
+) -> Result<u64> {
+    let bucket_secs = to
+        .bucket_secs()
+        .ok_or_else(|| eyre::eyre!("Cannot downsample into raw resolution"))?;
+    let before = (before - before % bucket_secs) as i64;
+
+    let select_pg = r#"
+        SELECT asset_address, resolution, price, min_price, max_price, sample_count, timestamp
+        FROM price_feeds WHERE resolution = $1 AND timestamp < $2 AND chain_id = $3
+        ORDER BY asset_address ASC, timestamp ASC, id ASC
+    "#;
+    let select_sqlite = r#"
+        SELECT asset_address, resolution, price, min_price, max_price, sample_count, timestamp
+        FROM price_feeds WHERE resolution = ? AND timestamp < ? AND chain_id = ?
+        ORDER BY asset_address ASC, timestamp ASC, id ASC
+    "#;
+
+    // Read, delete and re-insert in one transaction so a crash can't lose the bucket
+    let (source_count, aggregated_count) = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let mut tx = pool.begin().await?;
+            let rows: Vec<PricePointRow> = sqlx::query_as(select_pg)
+                .bind(from.as_str())
+                .bind(before)
+                .bind(db_pool.chain())
+                .fetch_all(&mut *tx)
+                .await?;
+            let points = rows
+                .into_iter()
+                .map(parse_price_point)
+                .collect::<Result<Vec<_>>>()?;
+            let aggregated = aggregate_price_points(&points, to);
+
+            sqlx::query(
+                "DELETE FROM price_feeds WHERE resolution = $1 AND timestamp < $2 AND chain_id = $3",
+            )
+            .bind(from.as_str())
+            .bind(before)
+            .bind(db_pool.chain())
+            .execute(&mut *tx)
+            .await?;
+            for point in &aggregated {
+                sqlx::query(INSERT_PRICE_POINT_PG)
+                    .bind(canonical_address(&point.asset))
+                    .bind(point.resolution.as_str())
+                    .bind(point.price.to_string())
+                    .bind(point.min_price.to_string())
+                    .bind(point.max_price.to_string())
+                    .bind(point.sample_count as i64)
+                    .bind(point.timestamp as i64)
+                    .bind(db_pool.chain())
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            (points.len(), aggregated.len())
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            let mut tx = pool.begin().await?;
+            let rows: Vec<PricePointRow> = sqlx::query_as(select_sqlite)
+                .bind(from.as_str())
+                .bind(before)
+                .bind(db_pool.chain())
+                .fetch_all(&mut *tx)
+                .await?;
+            let points = rows
+                .into_iter()
+                .map(parse_price_point)
+                .collect::<Result<Vec<_>>>()?;
+            let aggregated = aggregate_price_points(&points, to);
+
+            sqlx::query(
+                "DELETE FROM price_feeds WHERE resolution = ? AND timestamp < ? AND chain_id = ?",
+            )
+            .bind(from.as_str())
+            .bind(before)
+            .bind(db_pool.chain())
+            .execute(&mut *tx)
+            .await?;
+            for point in &aggregated {
+                sqlx::query(INSERT_PRICE_POINT_SQLITE)
+                    .bind(canonical_address(&point.asset))
+                    .bind(point.resolution.as_str())
+                    .bind(point.price.to_string())
+                    .bind(point.min_price.to_string())
+                    .bind(point.max_price.to_string())
+                    .bind(point.sample_count as i64)
+                    .bind(point.timestamp as i64)
+                    .bind(db_pool.chain())
+                    .execute(&mut *tx)
+                    .await?;
+            }
+            tx.commit().await?;
+            (points.len(), aggregated.len())
+        }
+    };
+
+    Ok((source_count - aggregated_count) as u64)
+}
+
+/// Delete price history older than `before` (unix seconds), at every resolution
+pub async fn prune_price_history(db_pool: &DatabasePool, before: u64) -> Result<u64> {
+    let deleted = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query("DELETE FROM price_feeds WHERE timestamp < $1 AND chain_id = $2")
+                .bind(before as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?
+                .rows_affected()
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query("DELETE FROM price_feeds WHERE timestamp < ? AND chain_id = ?")
+                .bind(before as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?
+                .rows_affected()
+        }
+    };
+    Ok(deleted)
+}
+
+/// Recorded health factor snapshots of one user between `from` and `to` (unix seconds,
+/// inclusive), oldest first
+pub async fn get_position_history(
+    db_pool: &DatabasePool,
+    user: Address,
+    from: u64,
+    to: u64,
+) -> Result<Vec<PositionSnapshot>> {
+    let query_pg = r#"
+        SELECT address, timestamp, health_factor, total_collateral_base, total_debt_base, is_at_risk
+        FROM position_history
+        WHERE address = $1 AND timestamp >= $2 AND timestamp <= $3 AND chain_id = $4
+        ORDER BY timestamp ASC, id ASC
+    "#;
+    let query_sqlite = r#"
+        SELECT address, timestamp, health_factor, total_collateral_base, total_debt_base, is_at_risk
+        FROM position_history
+        WHERE address = ? AND timestamp >= ? AND timestamp <= ? AND chain_id = ?
+        ORDER BY timestamp ASC, id ASC
+    "#;
+
+    let user_str = canonical_address(&user);
+    let rows: Vec<(String, i64, String, String, String, bool)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(query_pg)
+                .bind(&user_str)
+                .bind(from as i64)
+                .bind(to as i64)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(query_sqlite)
+                .bind(&user_str)
+                .bind(from as i64)
+                .bind(to as i64)
+                .bind(db_pool.chain())
+                .fetch_all(pool)
+                .await?
+        }
+    };
+
+    rows.into_iter()
+        .map(|(address, timestamp, health_factor, collateral, debt, is_at_risk)| {
+            Ok(PositionSnapshot {
+                address: parse_stored_address(address)?,
+                timestamp: timestamp.max(0) as u64,
+                health_factor: health_factor.parse()?,
+                total_collateral_base: collateral.parse()?,
+                total_debt_base: debt.parse()?,
+                is_at_risk,
+            })
+        })
+        .collect()
+}
+
+/// Delete position snapshots older than `before` (unix seconds). Returns the rows removed.
+pub async fn prune_position_history(db_pool: &DatabasePool, before: u64) -> Result<u64> {
+    let deleted = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query("DELETE FROM position_history WHERE timestamp < $1 AND chain_id = $2")
+                .bind(before as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?
+                .rows_affected()
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query("DELETE FROM position_history WHERE timestamp < ? AND chain_id = ?")
+                .bind(before as i64)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?
+                .rows_affected()
+        }
+    };
+    Ok(deleted)
+}
+
+/// Last fully processed block recorded for a log subscription, if any
+pub async fn get_last_processed_block(
+    db_pool: &DatabasePool,
+    subscription: &str,
+) -> Result<Option<u64>> {
+    let block: Option<i64> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_scalar(
+                "SELECT last_processed_block FROM sync_state WHERE subscription = $1 AND chain_id = $2",
+            )
+            .bind(subscription)
+            .bind(db_pool.chain())
+            .fetch_optional(pool)
+            .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_scalar(
+                "SELECT last_processed_block FROM sync_state WHERE subscription = ? AND chain_id = ?",
+            )
+            .bind(subscription)
+            .bind(db_pool.chain())
+            .fetch_optional(pool)
+            .await?
+        }
+    };
+    Ok(block.map(|block| block as u64))
+}
+
+/// Checkpoint a subscription: every log up to and including `block` has been handled
+pub async fn save_last_processed_block(
+    db_pool: &DatabasePool,
+    subscription: &str,
+    block: u64,
+) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO sync_state (subscription, last_processed_block, updated_at, chain_id)
+                VALUES ($1, $2, NOW(), $3)
+                ON CONFLICT (chain_id, subscription)
+                DO UPDATE SET last_processed_block = EXCLUDED.last_processed_block, updated_at = NOW()
+                "#,
+            )
+            .bind(subscription)
+            .bind(block as i64)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO sync_state (
+                    subscription, last_processed_block, updated_at, chain_id
+                ) VALUES (?, ?, CURRENT_TIMESTAMP, ?)
+                "#,
+            )
+            .bind(subscription)
+            .bind(block as i64)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Circuit breaker snapshot (JSON) saved under `name` by a previous run, if any
+pub async fn get_circuit_breaker_state(
+    db_pool: &DatabasePool,
+    name: &str,
+) -> Result<Option<String>> {
+    let snapshot = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_scalar(
+                "SELECT snapshot FROM circuit_breaker_state WHERE name = $1 AND chain_id = $2",
+            )
+            .bind(name)
+            .bind(db_pool.chain())
+            .fetch_optional(pool)
+            .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_scalar(
+                "SELECT snapshot FROM circuit_breaker_state WHERE name = ? AND chain_id = ?",
+            )
+            .bind(name)
+            .bind(db_pool.chain())
+            .fetch_optional(pool)
+            .await?
+        }
+    };
+    Ok(snapshot)
+}
+
+/// Save a circuit breaker snapshot (JSON) under `name`, replacing the previous one
+pub async fn save_circuit_breaker_state(
+    db_pool: &DatabasePool,
+    name: &str,
+    snapshot: &str,
+) -> Result<()> {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query(
+                r#"
+                INSERT INTO circuit_breaker_state (name, snapshot, updated_at, chain_id)
+                VALUES ($1, $2, NOW(), $3)
+                ON CONFLICT (chain_id, name)
+                DO UPDATE SET snapshot = EXCLUDED.snapshot, updated_at = NOW()
+                "#,
+            )
+            .bind(name)
+            .bind(snapshot)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO circuit_breaker_state (name, snapshot, updated_at, chain_id)
+                VALUES (?, ?, CURRENT_TIMESTAMP, ?)
+                "#,
+            )
+            .bind(name)
+            .bind(snapshot)
+            .bind(db_pool.chain())
+            .execute(pool)
+            .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Columns written to `user_reserves`, in bind order
+const USER_RESERVE_COLUMNS: &str = "user_address, reserve, collateral_balance, debt_balance, \
+    usage_as_collateral_enabled, updated_at, chain_id";
+
+/// Replace the stored per-reserve balances of `user`; an empty slice clears them
+pub async fn save_user_reserves(
+    db_pool: &DatabasePool,
+    user: Address,
+    reserves: &[UserReserve],
+) -> Result<()> {
+    let user_str = canonical_address(&user);
+    let updated_at = chrono::Utc::now().timestamp();
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let mut tx = pool.begin().await?;
+            sqlx::query("DELETE FROM user_reserves WHERE user_address = $1 AND chain_id = $2")
+                .bind(&user_str)
+                .bind(db_pool.chain())
+                .execute(&mut *tx)
+                .await?;
+            if !reserves.is_empty() {
+                let mut query = sqlx::QueryBuilder::<Postgres>::new(format!(
+                    "INSERT INTO user_reserves ({}) ",
+                    USER_RESERVE_COLUMNS
+                ));
+                query.push_values(reserves, |mut row, reserve| {
+                    row.push_bind(user_str.clone())
+                        .push_bind(canonical_address(&reserve.reserve))
+                        .push_bind(reserve.collateral_balance.to_string())
+                        .push_bind(reserve.debt_balance.to_string())
+                        .push_bind(reserve.usage_as_collateral_enabled)
+                        .push_bind(updated_at)
+                        .push_bind(db_pool.chain());
+                });
+                query.build().execute(&mut *tx).await?;
+            }
+            tx.commit().await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            let mut tx = pool.begin().await?;
+            sqlx::query("DELETE FROM user_reserves WHERE user_address = ? AND chain_id = ?")
+                .bind(&user_str)
+                .bind(db_pool.chain())
+                .execute(&mut *tx)
+                .await?;
+            if !reserves.is_empty() {
+                let mut query = sqlx::QueryBuilder::<Sqlite>::new(format!(
+                    "INSERT INTO user_reserves ({}) ",
+                    USER_RESERVE_COLUMNS
+                ));
+                query.push_values(reserves, |mut row, reserve| {
+                    row.push_bind(user_str.clone())
+                        .push_bind(canonical_address(&reserve.reserve))
+                        .push_bind(reserve.collateral_balance.to_string())
+                        .push_bind(reserve.debt_balance.to_string())
+                        .push_bind(reserve.usage_as_collateral_enabled)
+                        .push_bind(updated_at)
+                        .push_bind(db_pool.chain());
+                });
+                query.build().execute(&mut *tx).await?;
+            }
+            tx.commit().await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Stored per-reserve balances of `user`, in reserve order
+pub async fn get_user_reserves(db_pool: &DatabasePool, user: Address) -> Result<Vec<UserReserve>> {
+    let user_str = canonical_address(&user);
+    let rows: Vec<(String, String, String, bool)> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query_as(
+                r#"
+                SELECT reserve, collateral_balance, debt_balance, usage_as_collateral_enabled
+                FROM user_reserves WHERE user_address = $1 AND chain_id = $2 ORDER BY reserve
+                "#,
+            )
+            .bind(&user_str)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query_as(
+                r#"
+                SELECT reserve, collateral_balance, debt_balance, usage_as_collateral_enabled
+                FROM user_reserves WHERE user_address = ? AND chain_id = ? ORDER BY reserve
+                "#,
+            )
+            .bind(&user_str)
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?
         }
     };
-    Ok(count)
+
+    rows.into_iter()
+        .map(|(reserve, collateral, debt, usage_as_collateral_enabled)| {
+            Ok(UserReserve {
+                reserve: parse_stored_address(reserve)?,
+                collateral_balance: collateral.parse()?,
+                debt_balance: debt.parse()?,
+                usage_as_collateral_enabled,
+            })
+        })
+        .collect()
 }
 
-/// Record a liquidation event
-pub async fn record_liquidation_event(
-    db_pool: &DatabasePool,
-    user_address: &Address,
-    collateral_asset: &str,
-    debt_asset: &str,
-    debt_covered: &str,
-    collateral_received: &str,
-    profit: &str,
-    tx_hash: Option<&str>,
-    block_number: Option<i64>,
-) -> Result<()> {
-    let user_str = user_address.to_string();
+/// Persist a pending liquidation so it survives a restart (no-op if already queued)
+pub async fn enqueue_pending_liquidation(db_pool: &DatabasePool, user: Address) -> Result<()> {
+    let address_str = canonical_address(&user);
 
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             sqlx::query(
-                r#"
-                INSERT INTO liquidation_events (
-                    user_address, collateral_asset, debt_asset, debt_covered,
-                    collateral_received, profit, tx_hash, block_number
-                ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
-                "#,
+                "INSERT INTO liquidation_queue (user_address, chain_id) VALUES ($1, $2) ON CONFLICT (chain_id, user_address) DO NOTHING",
             )
-            .bind(&user_str)
-            .bind(collateral_asset)
-            .bind(debt_asset)
-            .bind(debt_covered)
-            .bind(collateral_received)
-            .bind(profit)
-            .bind(tx_hash)
-            .bind(block_number)
+            .bind(&address_str)
+            .bind(db_pool.chain())
             .execute(pool)
             .await?;
         }
-        DatabasePool::Sqlite(pool) => {
+        DatabaseBackend::Sqlite(pool) => {
             sqlx::query(
-                r#"
-                INSERT INTO liquidation_events (
-                    user_address, collateral_asset, debt_asset, debt_covered,
-                    collateral_received, profit, tx_hash, block_number, timestamp
-                ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, datetime('now'))
-                "#,
+                "INSERT OR IGNORE INTO liquidation_queue (user_address, chain_id) VALUES (?, ?)",
             )
-            .bind(&user_str)
-            .bind(collateral_asset)
-            .bind(debt_asset)
-            .bind(debt_covered)
-            .bind(collateral_received)
-            .bind(profit)
-            .bind(tx_hash)
-            .bind(block_number)
+            .bind(&address_str)
+            .bind(db_pool.chain())
             .execute(pool)
             .await?;
         }
@@ -538,6 +3194,59 @@ pub async fn record_liquidation_event(
     Ok(())
 }
 
+/// Remove a pending liquidation once it has been processed
+pub async fn dequeue_pending_liquidation(db_pool: &DatabasePool, user: Address) -> Result<()> {
+    let address_str = canonical_address(&user);
+
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            sqlx::query("DELETE FROM liquidation_queue WHERE user_address = $1 AND chain_id = $2")
+                .bind(&address_str)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+        DatabaseBackend::Sqlite(pool) => {
+            sqlx::query("DELETE FROM liquidation_queue WHERE user_address = ? AND chain_id = ?")
+                .bind(&address_str)
+                .bind(db_pool.chain())
+                .execute(pool)
+                .await?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Get all pending liquidations, oldest first
+pub async fn get_pending_liquidations(db_pool: &DatabasePool) -> Result<Vec<Address>> {
+    let addresses: Vec<String> = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => sqlx::query(
+            "SELECT user_address FROM liquidation_queue WHERE chain_id = $1 ORDER BY queued_at ASC",
+        )
+        .bind(db_pool.chain())
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| row.get::<String, _>("user_address"))
+        .collect(),
+        DatabaseBackend::Sqlite(pool) => sqlx::query(
+            "SELECT user_address FROM liquidation_queue WHERE chain_id = ? ORDER BY queued_at ASC",
+        )
+        .bind(db_pool.chain())
+        .fetch_all(pool)
+        .await?
+        .iter()
+        .map(|row| row.get::<String, _>("user_address"))
+        .collect(),
+    };
+
+    addresses
+        .into_iter()
+        .map(parse_stored_address)
+        .collect()
+}
+
 /// Log monitoring events (simplified for now - just use tracing)
 pub async fn log_monitoring_event(
     _db_pool: &DatabasePool,
@@ -565,15 +3274,18 @@ pub async fn log_monitoring_event(
 
 /// Get all users from the database for full rescan
 pub async fn get_all_users(db_pool: &DatabasePool) -> Result<Vec<UserPosition>> {
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
-            let rows = sqlx::query("SELECT * FROM user_positions ORDER BY health_factor ASC")
-                .fetch_all(pool)
-                .await?;
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
+            let rows = sqlx::query(
+                "SELECT * FROM user_positions WHERE chain_id = $1 ORDER BY health_factor ASC",
+            )
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let position = UserPosition {
                     address,
                     total_collateral_base: row.get::<String, _>("total_collateral_base").parse()?,
@@ -593,14 +3305,17 @@ pub async fn get_all_users(db_pool: &DatabasePool) -> Result<Vec<UserPosition>>
             }
             Ok(positions)
         }
-        DatabasePool::Sqlite(pool) => {
-            let rows = sqlx::query("SELECT * FROM user_positions ORDER BY health_factor ASC")
-                .fetch_all(pool)
-                .await?;
+        DatabaseBackend::Sqlite(pool) => {
+            let rows = sqlx::query(
+                "SELECT * FROM user_positions WHERE chain_id = ? ORDER BY health_factor ASC",
+            )
+            .bind(db_pool.chain())
+            .fetch_all(pool)
+            .await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let position = UserPosition {
                     address,
                     total_collateral_base: row.get::<String, _>("total_collateral_base").parse()?,
@@ -645,8 +3360,8 @@ pub async fn get_users_eligible_for_archival(
     let cooldown_timestamp =
         chrono::Utc::now() - chrono::Duration::hours(safe_cooldown_hours as i64);
 
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             // For PostgreSQL, we'll do the comparison in Rust to avoid precision issues with very large U256 numbers
             // First get all users with zero debt and within cooldown period
             let rows = sqlx::query(
@@ -654,16 +3369,18 @@ pub async fn get_users_eligible_for_archival(
                 SELECT * FROM user_positions 
                 WHERE total_debt_base = '0' 
                   AND last_updated <= $1
+                  AND chain_id = $2
                 ORDER BY last_updated ASC
                 "#,
             )
             .bind(&cooldown_timestamp)
+            .bind(db_pool.chain())
             .fetch_all(pool)
             .await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let health_factor_str = row.get::<String, _>("health_factor");
 
                 // Parse health factor and compare in Rust to avoid database precision issues
@@ -692,7 +3409,7 @@ pub async fn get_users_eligible_for_archival(
             }
             Ok(positions)
         }
-        DatabasePool::Sqlite(pool) => {
+        DatabaseBackend::Sqlite(pool) => {
             // For SQLite, we'll do the comparison in Rust to avoid overflow/precision issues
             // First get all users with zero debt and within cooldown period
             let rows = sqlx::query(
@@ -700,16 +3417,18 @@ pub async fn get_users_eligible_for_archival(
                 SELECT * FROM user_positions 
                 WHERE total_debt_base = '0' 
                   AND last_updated <= ?
+                  AND chain_id = ?
                 ORDER BY last_updated ASC
                 "#,
             )
             .bind(&cooldown_timestamp)
+            .bind(db_pool.chain())
             .fetch_all(pool)
             .await?;
 
             let mut positions = Vec::new();
             for row in rows {
-                let address = Address::parse_checksummed(row.get::<String, _>("address"), None)?;
+                let address = parse_stored_address(row.get::<String, _>("address"))?;
                 let health_factor_str = row.get::<String, _>("health_factor");
 
                 // Parse health factor and compare in Rust to avoid database overflow
@@ -779,10 +3498,10 @@ pub async fn archive_zero_debt_users(
 
     let cooldown_timestamp =
         chrono::Utc::now() - chrono::Duration::hours(safe_cooldown_hours as i64);
-    let address_strings: Vec<String> = user_addresses.iter().map(|addr| addr.to_string()).collect();
+    let address_strings: Vec<String> = user_addresses.iter().map(canonical_address).collect();
 
-    match db_pool {
-        DatabasePool::Postgres(pool) => {
+    match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             // First, query which users actually meet the archival criteria at deletion time
             let placeholders: Vec<String> = (1..=address_strings.len())
                 .map(|i| format!("${}", i))
@@ -792,10 +3511,12 @@ pub async fn archive_zero_debt_users(
                 WHERE address = ANY(ARRAY[{}])
                   AND total_debt_base = '0'
                   AND last_updated <= ${}
-                  AND CAST(health_factor AS NUMERIC) >= CAST(${}::TEXT AS NUMERIC)"#,
+                  AND CAST(health_factor AS NUMERIC) >= CAST(${}::TEXT AS NUMERIC)
+                  AND chain_id = ${}"#,
                 placeholders.join(", "),
                 address_strings.len() + 1,
-                address_strings.len() + 2
+                address_strings.len() + 2,
+                address_strings.len() + 3
             );
 
             let mut select_query_builder = sqlx::query(&select_query);
@@ -805,6 +3526,7 @@ pub async fn archive_zero_debt_users(
             select_query_builder = select_query_builder.bind(&cooldown_timestamp);
             select_query_builder =
                 select_query_builder.bind(safe_health_factor_threshold.to_string());
+            select_query_builder = select_query_builder.bind(db_pool.chain());
 
             let rows = select_query_builder.fetch_all(pool).await?;
 
@@ -812,7 +3534,7 @@ pub async fn archive_zero_debt_users(
             let mut archived_addresses = Vec::new();
             for row in &rows {
                 let address_str = row.get::<String, _>("address");
-                if let Ok(address) = Address::parse_checksummed(&address_str, None) {
+                if let Ok(address) = parse_stored_address(&address_str) {
                     archived_addresses.push(address);
                 }
             }
@@ -830,10 +3552,12 @@ pub async fn archive_zero_debt_users(
                 WHERE address = ANY(ARRAY[{}])
                   AND total_debt_base = '0'
                   AND last_updated <= ${}
-                  AND CAST(health_factor AS NUMERIC) >= CAST(${}::TEXT AS NUMERIC)"#,
+                  AND CAST(health_factor AS NUMERIC) >= CAST(${}::TEXT AS NUMERIC)
+                  AND chain_id = ${}"#,
                 placeholders.join(", "),
                 address_strings.len() + 1,
-                address_strings.len() + 2
+                address_strings.len() + 2,
+                address_strings.len() + 3
             );
 
             let mut delete_query_builder = sqlx::query(&delete_query);
@@ -843,6 +3567,7 @@ pub async fn archive_zero_debt_users(
             delete_query_builder = delete_query_builder.bind(&cooldown_timestamp);
             delete_query_builder =
                 delete_query_builder.bind(safe_health_factor_threshold.to_string());
+            delete_query_builder = delete_query_builder.bind(db_pool.chain());
 
             let result = delete_query_builder.execute(pool).await?;
             Ok(ArchivalResult {
@@ -850,7 +3575,7 @@ pub async fn archive_zero_debt_users(
                 archived_addresses,
             })
         }
-        DatabasePool::Sqlite(pool) => {
+        DatabaseBackend::Sqlite(pool) => {
             // For SQLite, we need to verify health factor in Rust to avoid string comparison issues
             // First, get all users that meet the other criteria
             let placeholders: Vec<String> = (0..address_strings.len())
@@ -860,7 +3585,8 @@ pub async fn archive_zero_debt_users(
                 r#"SELECT address, health_factor FROM user_positions 
                 WHERE address IN ({})
                   AND total_debt_base = '0'
-                  AND last_updated <= ?"#,
+                  AND last_updated <= ?
+                  AND chain_id = ?"#,
                 placeholders.join(", ")
             );
 
@@ -869,6 +3595,7 @@ pub async fn archive_zero_debt_users(
                 select_query_builder = select_query_builder.bind(addr_str);
             }
             select_query_builder = select_query_builder.bind(&cooldown_timestamp);
+            select_query_builder = select_query_builder.bind(db_pool.chain());
 
             let rows = select_query_builder.fetch_all(pool).await?;
 
@@ -881,7 +3608,7 @@ pub async fn archive_zero_debt_users(
                 if let Ok(health_factor) = health_factor_str.parse::<alloy_primitives::U256>() {
                     if health_factor >= safe_health_factor_threshold {
                         eligible_addresses.push(address_str.clone());
-                        if let Ok(address) = Address::parse_checksummed(&address_str, None) {
+                        if let Ok(address) = parse_stored_address(&address_str) {
                             archived_addresses.push(address);
                         }
                     }
@@ -900,7 +3627,7 @@ pub async fn archive_zero_debt_users(
                 .map(|_| "?".to_string())
                 .collect();
             let delete_query = format!(
-                "DELETE FROM user_positions WHERE address IN ({})",
+                "DELETE FROM user_positions WHERE address IN ({}) AND chain_id = ?",
                 delete_placeholders.join(", ")
             );
 
@@ -908,6 +3635,7 @@ pub async fn archive_zero_debt_users(
             for addr_str in &eligible_addresses {
                 delete_query_builder = delete_query_builder.bind(addr_str);
             }
+            delete_query_builder = delete_query_builder.bind(db_pool.chain());
 
             let result = delete_query_builder.execute(pool).await?;
             Ok(ArchivalResult {
@@ -920,19 +3648,23 @@ pub async fn archive_zero_debt_users(
 
 /// Get count of users with zero debt for monitoring purposes
 pub async fn get_zero_debt_user_count(db_pool: &DatabasePool) -> Result<i64> {
-    let count = match db_pool {
-        DatabasePool::Postgres(pool) => {
+    let count = match &db_pool.backend {
+        DatabaseBackend::Postgres(pool) => {
             let row = sqlx::query(
-                "SELECT COUNT(*) as count FROM user_positions WHERE total_debt_base = '0'",
+                "SELECT COUNT(*) as count FROM user_positions \
+                 WHERE total_debt_base = '0' AND chain_id = $1",
             )
+            .bind(db_pool.chain())
             .fetch_one(pool)
             .await?;
             row.get::<i64, _>("count")
         }
-        DatabasePool::Sqlite(pool) => {
+        DatabaseBackend::Sqlite(pool) => {
             let row = sqlx::query(
-                "SELECT COUNT(*) as count FROM user_positions WHERE total_debt_base = '0'",
+                "SELECT COUNT(*) as count FROM user_positions \
+                 WHERE total_debt_base = '0' AND chain_id = ?",
             )
+            .bind(db_pool.chain())
             .fetch_one(pool)
             .await?;
             row.get::<i32, _>("count") as i64
@@ -942,9 +3674,447 @@ pub async fn get_zero_debt_user_count(db_pool: &DatabasePool) -> Result<i64> {
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
+    use super::*;
+    use crate::config::DEFAULT_CHAIN_ID;
     use alloy_primitives::{Address, U256};
 
+    /// In-memory SQLite pool with the schema created (single connection so all
+    /// queries see the same database); shared by every module's database tests
+    pub(crate) async fn create_test_pool() -> DatabasePool {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+        let db_pool = DatabasePool::new(DatabaseBackend::Sqlite(pool), DEFAULT_CHAIN_ID);
+        create_tables(&db_pool).await.unwrap();
+        db_pool
+    }
+
+    #[tokio::test]
+    async fn test_two_phase_audit_records_for_confirmed_liquidation() {
+        let db_pool = create_test_pool().await;
+        let tx_hash = format!("0x{:064x}", 42);
+
+        record_liquidation_intent(
+            &db_pool,
+            &LiquidationIntent {
+                tx_hash: tx_hash.clone(),
+                user: Address::from([1u8; 20]),
+                nonce: 7,
+                gas_price: U256::from(2_000_000_000u64),
+                gas_limit: U256::from(500_000u64),
+                estimated_profit: U256::from(10_000_000_000_000_000u64),
+            },
+        )
+        .await
+        .unwrap();
+
+        // Submitted but not yet confirmed
+        assert_eq!(
+            get_unsettled_liquidation_intents(&db_pool).await.unwrap(),
+            vec![tx_hash.clone()]
+        );
+
+        record_liquidation_settlement(
+            &db_pool,
+            &LiquidationSettlement {
+                tx_hash: tx_hash.clone(),
+                block_number: Some(1_000),
+                tx_index: Some(3),
+                gas_used: U256::from(350_000u64),
+                realized_profit: U256::from(9_000_000_000_000_000u64),
+                succeeded: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(
+            get_liquidation_audit_phases(&db_pool, &tx_hash).await.unwrap(),
+            vec!["intent".to_string(), "settlement".to_string()]
+        );
+        assert!(get_unsettled_liquidation_intents(&db_pool)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_strategy_label_recorded_and_aggregated() {
+        let db_pool = create_test_pool().await;
+        let user = Address::from([1u8; 20]);
+
+        for (profit, strategy) in [
+            ("100", "aggressive"),
+            ("250", "conservative"),
+            ("50", "aggressive"),
+        ] {
+            record_liquidation_event(
+                &db_pool,
+                &user,
+                "0xcollateral",
+                "0xdebt",
+                "1000",
+                Some("1000"),
+                "1100",
+                profit,
+                Some("0xtx"),
+                None,
+                strategy,
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            get_pnl_by_strategy(&db_pool).await.unwrap(),
+            vec![
+                StrategyPnl {
+                    strategy_label: "aggressive".to_string(),
+                    liquidation_count: 2,
+                    total_profit: U256::from(150u64),
+                },
+                StrategyPnl {
+                    strategy_label: "conservative".to_string(),
+                    liquidation_count: 1,
+                    total_profit: U256::from(250u64),
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_strategy_label_migration_is_idempotent() {
+        let db_pool = create_test_pool().await;
+
+        // Running the schema setup again must not try to re-add the column
+        create_tables(&db_pool).await.unwrap();
+
+        if let DatabaseBackend::Sqlite(pool) = &db_pool.backend {
+            sqlx::query(
+                "INSERT INTO liquidation_events (user_address, collateral_asset, debt_asset, debt_covered, collateral_received, profit) VALUES ('0x1', '0x2', '0x3', '1', '1', '7')",
+            )
+            .execute(pool)
+            .await
+            .unwrap();
+        }
+
+        let summary = get_pnl_by_strategy(&db_pool).await.unwrap();
+        assert_eq!(summary.len(), 1);
+        assert_eq!(summary[0].strategy_label, "default");
+        assert_eq!(summary[0].total_profit, U256::from(7u64));
+    }
+
+    #[tokio::test]
+    async fn test_unversioned_database_is_adopted_by_migrations() {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect("sqlite::memory:")
+            .await
+            .unwrap();
+
+        // Schema as created by versions that predate strategy labels and migrations
+        sqlx::query(
+            "CREATE TABLE liquidation_events (id INTEGER PRIMARY KEY AUTOINCREMENT, user_address TEXT NOT NULL, collateral_asset TEXT NOT NULL, debt_asset TEXT NOT NULL, debt_covered TEXT NOT NULL, collateral_received TEXT NOT NULL, profit TEXT NOT NULL, tx_hash TEXT, block_number INTEGER, timestamp DATETIME NOT NULL DEFAULT CURRENT_TIMESTAMP)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO liquidation_events (user_address, collateral_asset, debt_asset, debt_covered, collateral_received, profit) VALUES ('0x1', '0x2', '0x3', '1', '1', '5')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let db_pool = DatabasePool::new(DatabaseBackend::Sqlite(pool), DEFAULT_CHAIN_ID);
+        create_tables(&db_pool).await.unwrap();
+        assert!(table_exists(&db_pool, "_sqlx_migrations").await.unwrap());
+        assert!(table_exists(&db_pool, "pnl").await.unwrap());
+
+        let summary = get_pnl_by_strategy(&db_pool).await.unwrap();
+        assert_eq!(summary[0].strategy_label, "default");
+        assert_eq!(summary[0].total_profit, U256::from(5u64));
+
+        // Later startups find the migration recorded and change nothing
+        create_tables(&db_pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_liquidation_receipt_is_recorded_on_its_event() {
+        let db_pool = create_test_pool().await;
+        let user = Address::repeat_byte(0x01);
+        for tx_hash in ["0xaa", "0xbb"] {
+            record_liquidation_event(
+                &db_pool, &user, "0x01", "0x02", "1", None, "1", "1", Some(tx_hash), None, "default",
+            )
+            .await
+            .unwrap();
+        }
+
+        let receipt = LiquidationReceipt {
+            tx_hash: "0xbb".to_string(),
+            block_number: Some(120),
+            tx_index: Some(3),
+            gas_used: U256::from(412_345u64),
+            effective_gas_price: U256::from(1_500_000_000u64),
+            succeeded: true,
+        };
+        record_liquidation_receipt(&db_pool, &receipt).await.unwrap();
+
+        if let DatabaseBackend::Sqlite(pool) = &db_pool.backend {
+            let rows = sqlx::query(
+                "SELECT tx_hash, block_number, tx_index, gas_used, effective_gas_price, succeeded FROM liquidation_events ORDER BY id",
+            )
+            .fetch_all(pool)
+            .await
+            .unwrap();
+
+            assert_eq!(rows[0].get::<Option<i64>, _>("block_number"), None);
+            assert_eq!(rows[0].get::<Option<String>, _>("gas_used"), None);
+
+            assert_eq!(rows[1].get::<Option<i64>, _>("block_number"), Some(120));
+            assert_eq!(rows[1].get::<Option<i64>, _>("tx_index"), Some(3));
+            assert_eq!(rows[1].get::<Option<String>, _>("gas_used").as_deref(), Some("412345"));
+            assert_eq!(
+                rows[1].get::<Option<String>, _>("effective_gas_price").as_deref(),
+                Some("1500000000")
+            );
+            assert_eq!(rows[1].get::<Option<bool>, _>("succeeded"), Some(true));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recent_liquidations_are_newest_first() {
+        let db_pool = create_test_pool().await;
+        let user = Address::repeat_byte(0x01);
+        for (tx_hash, profit) in [("0xaa", "10"), ("0xbb", "20"), ("0xcc", "30")] {
+            record_liquidation_event(
+                &db_pool, &user, "0x01", "0x02", "5", None, "6", profit, Some(tx_hash), Some(7),
+                "default",
+            )
+            .await
+            .unwrap();
+        }
+        record_liquidation_receipt(
+            &db_pool,
+            &LiquidationReceipt {
+                tx_hash: "0xcc".to_string(),
+                block_number: Some(7),
+                tx_index: Some(0),
+                gas_used: U256::from(400_000u64),
+                effective_gas_price: U256::from(1_000_000_000u64),
+                succeeded: true,
+            },
+        )
+        .await
+        .unwrap();
+
+        let records = get_recent_liquidations(&db_pool, 2).await.unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].tx_hash.as_deref(), Some("0xcc"));
+        assert_eq!(records[0].profit, U256::from(30u64));
+        assert_eq!(records[0].succeeded, Some(true));
+        assert_eq!(records[1].tx_hash.as_deref(), Some("0xbb"));
+        assert_eq!(records[1].succeeded, None);
+        assert_eq!(records[1].user, user);
+    }
+
+    #[tokio::test]
+    async fn test_profit_divergence_round_trip() {
+        let db_pool = create_test_pool().await;
+        let divergence = ProfitDivergence {
+            user: Address::from([1u8; 20]),
+            collateral_asset: Address::from([2u8; 20]),
+            debt_asset: Address::from([3u8; 20]),
+            live_model: ProfitabilityModel::FlatSlippage,
+            shadow_model: ProfitabilityModel::TieredSlippage,
+            live_profit: U256::from(395u64),
+            shadow_profit: U256::from(447u64),
+            divergence_bps: 1_163,
+        };
+
+        record_profit_divergence(&db_pool, &divergence).await.unwrap();
+
+        assert_eq!(get_profit_divergences(&db_pool).await.unwrap(), vec![divergence]);
+    }
+
+    #[tokio::test]
+    async fn test_latency_traces_round_trip_within_window() {
+        let db_pool = create_test_pool().await;
+        let user = Address::from([1u8; 20]);
+        let trace = LatencyTrace {
+            log_received_ms: Some(10_000),
+            hf_confirmed_ms: Some(10_090),
+            profitability_done_ms: Some(10_300),
+            tx_sent_ms: Some(10_350),
+            tx_mined_ms: Some(12_400),
+        };
+
+        record_liquidation_latency(&db_pool, user, Some("0xabc"), "executed", &trace, 12_500)
+            .await
+            .unwrap();
+        // Found by a scan and never sent: only the later stages are stamped
+        let scanned = LatencyTrace {
+            hf_confirmed_ms: Some(20_000),
+            profitability_done_ms: Some(20_150),
+            ..LatencyTrace::default()
+        };
+        record_liquidation_latency(&db_pool, user, None, "not_needed", &scanned, 20_200)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_latency_traces(&db_pool, 0).await.unwrap(),
+            vec![trace, scanned.clone()]
+        );
+        assert_eq!(get_latency_traces(&db_pool, 15_000).await.unwrap(), vec![scanned]);
+    }
+
+    #[tokio::test]
+    async fn test_liquidation_failures_are_counted_by_category() {
+        let db_pool = create_test_pool().await;
+        let user = Address::from([1u8; 20]);
+
+        record_liquidation_failure(
+            &db_pool,
+            user,
+            FailureCategory::Rpc,
+            "timed out",
+            1,
+            RetryDecision::Retry(std::time::Duration::from_secs(1)),
+            1_000,
+        )
+        .await
+        .unwrap();
+        for at in [2_000, 3_000] {
+            record_liquidation_failure(
+                &db_pool,
+                user,
+                FailureCategory::PositionHealthy,
+                "Transaction reverted: 45",
+                2,
+                RetryDecision::Abandon,
+                at,
+            )
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(
+            get_liquidation_failure_counts(&db_pool, 0).await.unwrap(),
+            vec![
+                (FailureCategory::PositionHealthy, 2),
+                (FailureCategory::Rpc, 1)
+            ]
+        );
+        assert_eq!(
+            get_liquidation_failure_counts(&db_pool, 2_500).await.unwrap(),
+            vec![(FailureCategory::PositionHealthy, 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_treasury_sweeps_are_totalled_per_asset() {
+        let db_pool = create_test_pool().await;
+        let sweep = |symbol: &str, token: Option<Address>, amount: u64| Sweep {
+            wallet: Address::from([1u8; 20]),
+            cold_wallet: Address::from([2u8; 20]),
+            symbol: symbol.to_string(),
+            token,
+            amount: U256::from(amount),
+            tx_hash: "mock".to_string(),
+        };
+
+        let usdc = Some(Address::from([3u8; 20]));
+        record_treasury_sweep(&db_pool, &sweep("USDC", usdc, 500), 1_000)
+            .await
+            .unwrap();
+        record_treasury_sweep(&db_pool, &sweep("USDC", usdc, 250), 2_000)
+            .await
+            .unwrap();
+        record_treasury_sweep(&db_pool, &sweep("ETH", None, 7), 3_000)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            get_treasury_sweep_totals(&db_pool, 0).await.unwrap(),
+            BTreeMap::from([
+                ("ETH".to_string(), U256::from(7u64)),
+                ("USDC".to_string(), U256::from(750u64))
+            ])
+        );
+        assert_eq!(
+            get_treasury_sweep_totals(&db_pool, 1_500).await.unwrap(),
+            BTreeMap::from([
+                ("ETH".to_string(), U256::from(7u64)),
+                ("USDC".to_string(), U256::from(250u64))
+            ])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_unwound_a_token_lots_are_closed() {
+        let db_pool = create_test_pool().await;
+        let wallet = Address::from([1u8; 20]);
+        let weth = Address::from([2u8; 20]);
+
+        record_a_token_lot(&db_pool, wallet, weth, U256::from(500u64), "0xaa", 1_000)
+            .await
+            .unwrap();
+        record_a_token_lot(&db_pool, wallet, weth, U256::from(300u64), "0xbb", 2_000)
+            .await
+            .unwrap();
+
+        let lots = get_open_a_token_lots(&db_pool, 1_500).await.unwrap();
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].wallet, wallet);
+        assert_eq!(lots[0].asset, weth);
+        assert_eq!(lots[0].amount, U256::from(500u64));
+        assert_eq!(lots[0].tx_hash, "0xaa");
+
+        mark_a_token_lot_unwound(&db_pool, lots[0].id, "0xcc", 3_000)
+            .await
+            .unwrap();
+        let lots = get_open_a_token_lots(&db_pool, i64::MAX).await.unwrap();
+        assert_eq!(lots.len(), 1);
+        assert_eq!(lots[0].tx_hash, "0xbb");
+    }
+
+    #[tokio::test]
+    async fn test_competitor_sightings_and_races() {
+        let db_pool = create_test_pool().await;
+        let liquidator = Address::from([0xee; 20]);
+        let sighting = |user: u8, tip: u128| CompetingLiquidation {
+            tx_hash: format!("0x{:064x}", tip),
+            liquidator,
+            user: Address::from([user; 20]),
+            collateral_asset: Address::from([2u8; 20]),
+            debt_asset: Address::from([3u8; 20]),
+            debt_to_cover: U256::from(1_000u64),
+            priority_fee_per_gas: tip,
+        };
+
+        record_competitor_sighting(&db_pool, &sighting(1, 100)).await.unwrap();
+        record_competitor_sighting(&db_pool, &sighting(4, 250)).await.unwrap();
+        record_competitor_race(&db_pool, &liquidator).await.unwrap();
+
+        let stats = get_competitor_stats(&db_pool).await.unwrap();
+        assert_eq!(
+            stats,
+            vec![CompetitorStats {
+                liquidator,
+                sightings: 2,
+                races: 1,
+                last_user: Address::from([4u8; 20]),
+                last_tx_hash: format!("0x{:064x}", 250),
+                last_priority_fee: 250,
+            }]
+        );
+    }
+
     #[tokio::test]
     async fn test_archival_functions() {
         // Test basic archival query construction for SQLite
@@ -1087,4 +4257,302 @@ mod tests {
         );
         println!("   Max safe cooldown: {} hours (100 years)", max_safe);
     }
+
+    #[tokio::test]
+    async fn test_position_writer_batches_latest_update_per_user() {
+        let db_pool = create_test_pool().await;
+        let writer = PositionWriter::spawn(db_pool.clone(), 10, Duration::from_secs(3600));
+        let position = |byte: u8, health_factor: u64| UserPosition {
+            address: Address::repeat_byte(byte),
+            total_collateral_base: U256::from(1_000u64),
+            total_debt_base: U256::from(500u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_000u64),
+            ltv: U256::from(7_500u64),
+            health_factor: U256::from(health_factor),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: false,
+        };
+
+        writer.enqueue(position(0x01, 3)).unwrap();
+        writer.enqueue(position(0x02, 5)).unwrap();
+        writer.enqueue(position(0x01, 2)).unwrap();
+        assert_eq!(writer.queue_depth(), 3);
+
+        // Below the batch size and before the interval, nothing is written yet
+        assert_eq!(get_user_position_count(&db_pool).await.unwrap(), 0);
+
+        writer.flush().await.unwrap();
+        assert_eq!(writer.queue_depth(), 0);
+        assert_eq!(get_user_position_count(&db_pool).await.unwrap(), 2);
+        let first = get_user_position(&db_pool, Address::repeat_byte(0x01)).await.unwrap().unwrap();
+        assert_eq!(first.health_factor, U256::from(2u64));
+        let history = get_position_history(&db_pool, first.address, 0, i64::MAX as u64)
+            .await
+            .unwrap();
+        assert_eq!(history.len(), 1);
+
+        // Each batched position is mirrored into the cross-protocol view as well
+        let aave = get_protocol_positions(&db_pool, ProtocolKind::AaveV3).await.unwrap();
+        assert_eq!(aave.len(), 2);
+        assert_eq!(aave[0].address, Address::repeat_byte(0x01));
+        assert_eq!(aave[0].health_factor, U256::from(2u64));
+        assert_eq!(aave[0].collateral_value, U256::from(800u64));
+    }
+
+    #[tokio::test]
+    async fn test_position_history_keeps_every_snapshot_until_pruned() {
+        let db_pool = create_test_pool().await;
+        let user = Address::repeat_byte(0x07);
+        let start = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+        for (hours, health_factor) in [(0, 1_300u64), (1, 1_150), (2, 990)] {
+            let position = UserPosition {
+                address: user,
+                total_collateral_base: U256::from(10_000u64),
+                total_debt_base: U256::from(8_000u64),
+                available_borrows_base: U256::ZERO,
+                current_liquidation_threshold: U256::from(8_500u64),
+                ltv: U256::from(8_000u64),
+                health_factor: U256::from(health_factor),
+                last_updated: start + chrono::Duration::hours(hours),
+                is_at_risk: health_factor < 1_100,
+            };
+            save_user_position(&db_pool, &position).await.unwrap();
+        }
+        assert_eq!(get_user_position_count(&db_pool).await.unwrap(), 1);
+
+        let history = get_position_history(&db_pool, user, 0, i64::MAX as u64).await.unwrap();
+        let health_factors: Vec<U256> = history.iter().map(|s| s.health_factor).collect();
+        assert_eq!(
+            health_factors,
+            vec![U256::from(1_300u64), U256::from(1_150u64), U256::from(990u64)]
+        );
+        assert!(history[2].is_at_risk);
+        assert_eq!(history[0].timestamp, 1_700_000_000);
+
+        let pruned = prune_position_history(&db_pool, 1_700_000_000 + 3_600).await.unwrap();
+        assert_eq!(pruned, 1);
+        assert_eq!(
+            get_position_history(&db_pool, user, 0, i64::MAX as u64).await.unwrap().len(),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_liquidation_is_recorded_with_simulated_profit() {
+        let db_pool = create_test_pool().await;
+        let opportunity = LiquidationOpportunity {
+            user: Address::repeat_byte(0x0d),
+            collateral_asset: Address::repeat_byte(0xc0),
+            debt_asset: Address::repeat_byte(0xde),
+            debt_to_cover: U256::from(1_000u64),
+            expected_collateral_received: U256::from(1_050u64),
+            liquidation_bonus: U256::from(500u64),
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: crate::models::FlashLoanSource::AaveV3,
+            gas_cost: U256::from(10u64),
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::from(40u64),
+            profit_threshold_met: true,
+        };
+        let simulated_profit = U256::from(35u64);
+        record_dry_run_liquidation(&db_pool, &opportunity, simulated_profit, Some(412_000), "paper")
+            .await
+            .unwrap();
+
+        if let DatabaseBackend::Sqlite(pool) = &db_pool.backend {
+            let (user, estimated, simulated, gas_used): (String, String, String, Option<i64>) =
+                sqlx::query_as(
+                    "SELECT user_address, estimated_profit, simulated_profit, gas_used \
+                     FROM dry_run_liquidations",
+                )
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            assert_eq!(user, canonical_address(&opportunity.user));
+            assert_eq!((estimated.as_str(), simulated.as_str()), ("40", "35"));
+            assert_eq!(gas_used, Some(412_000));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_mixed_case_addresses_map_to_one_row() {
+        let db_pool = create_test_pool().await;
+        let user: Address = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed".parse().unwrap();
+        let now = chrono::Utc::now();
+
+        // Rows left behind by older versions under both casings; the checksummed one is newer
+        if let DatabaseBackend::Sqlite(pool) = &db_pool.backend {
+            for (address, health_factor, last_updated) in [
+                (user.to_string(), "2", now),
+                (canonical_address(&user), "1", now - chrono::Duration::hours(1)),
+            ] {
+                sqlx::query(
+                    r#"
+                    INSERT INTO user_positions (
+                        address, total_collateral_base, total_debt_base, available_borrows_base,
+                        current_liquidation_threshold, ltv, health_factor, last_updated, is_at_risk
+                    ) VALUES (?, '0', '0', '0', '0', '0', ?, ?, FALSE)
+                    "#,
+                )
+                .bind(address)
+                .bind(health_factor)
+                .bind(last_updated)
+                .execute(pool)
+                .await
+                .unwrap();
+            }
+        }
+        assert_eq!(get_user_position_count(&db_pool).await.unwrap(), 2);
+
+        // Re-running the schema setup applies the dedupe migration
+        create_tables(&db_pool).await.unwrap();
+        assert_eq!(get_user_position_count(&db_pool).await.unwrap(), 1);
+
+        let position = get_user_position(&db_pool, user).await.unwrap().unwrap();
+        assert_eq!(position.health_factor, U256::from(2u64));
+
+        // Saving again updates the canonical row instead of adding another
+        save_user_position(&db_pool, &position).await.unwrap();
+        assert_eq!(get_user_position_count(&db_pool).await.unwrap(), 1);
+        assert_eq!(get_all_users(&db_pool).await.unwrap()[0].address, user);
+    }
+
+    #[tokio::test]
+    async fn test_positions_are_recorded_per_protocol() {
+        let db_pool = create_test_pool().await;
+        let user = Address::repeat_byte(0x01);
+        let position = |protocol, health_factor: u64, is_liquidatable| ProtocolPosition {
+            protocol,
+            address: user,
+            collateral_value: U256::from(1_000u64),
+            debt_value: U256::from(900u64),
+            health_factor: U256::from(health_factor),
+            is_liquidatable,
+            last_updated: chrono::Utc::now(),
+        };
+
+        // The same account on both protocols is two rows
+        save_protocol_position(&db_pool, &position(ProtocolKind::AaveV3, 2, false))
+            .await
+            .unwrap();
+        save_protocol_position(&db_pool, &position(ProtocolKind::CompoundV3, 1, true))
+            .await
+            .unwrap();
+        // Saving again updates the existing row for that protocol
+        save_protocol_position(&db_pool, &position(ProtocolKind::CompoundV3, 3, false))
+            .await
+            .unwrap();
+
+        let compound = get_protocol_positions(&db_pool, ProtocolKind::CompoundV3)
+            .await
+            .unwrap();
+        assert_eq!(compound.len(), 1);
+        assert_eq!(compound[0].protocol, ProtocolKind::CompoundV3);
+        assert_eq!(compound[0].address, user);
+        assert_eq!(compound[0].health_factor, U256::from(3u64));
+        assert!(!compound[0].is_liquidatable);
+
+        let aave = get_protocol_positions(&db_pool, ProtocolKind::AaveV3).await.unwrap();
+        assert_eq!(aave.len(), 1);
+        assert_eq!(aave[0].health_factor, U256::from(2u64));
+    }
+
+    #[tokio::test]
+    async fn test_sync_checkpoint_round_trip() {
+        let db_pool = create_test_pool().await;
+
+        assert_eq!(get_last_processed_block(&db_pool, "pool_events").await.unwrap(), None);
+
+        save_last_processed_block(&db_pool, "pool_events", 100).await.unwrap();
+        save_last_processed_block(&db_pool, "pool_events", 150).await.unwrap();
+        save_last_processed_block(&db_pool, "other", 7).await.unwrap();
+
+        assert_eq!(get_last_processed_block(&db_pool, "pool_events").await.unwrap(), Some(150));
+        assert_eq!(get_last_processed_block(&db_pool, "other").await.unwrap(), Some(7));
+    }
+
+    #[tokio::test]
+    async fn test_rows_are_scoped_to_their_chain() {
+        let base = create_test_pool().await;
+        let arbitrum = base.for_chain(42161);
+        let user = Address::repeat_byte(0x11);
+        let position = UserPosition {
+            address: user,
+            total_collateral_base: U256::from(1_000u64),
+            total_debt_base: U256::from(900u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_000u64),
+            ltv: U256::from(7_500u64),
+            health_factor: U256::from(10u64).pow(U256::from(18u64)),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: true,
+        };
+
+        save_user_position(&base, &position).await.unwrap();
+        save_last_processed_block(&base, "pool_events", 100).await.unwrap();
+        save_last_processed_block(&arbitrum, "pool_events", 250).await.unwrap();
+        enqueue_pending_liquidation(&arbitrum, user).await.unwrap();
+
+        assert!(get_user_position(&base, user).await.unwrap().is_some());
+        assert!(get_user_position(&arbitrum, user).await.unwrap().is_none());
+        assert_eq!(get_last_processed_block(&base, "pool_events").await.unwrap(), Some(100));
+        assert_eq!(get_last_processed_block(&arbitrum, "pool_events").await.unwrap(), Some(250));
+        assert!(get_pending_liquidations(&base).await.unwrap().is_empty());
+        assert_eq!(get_pending_liquidations(&arbitrum).await.unwrap(), vec![user]);
+    }
+
+    #[tokio::test]
+    async fn test_user_reserves_are_replaced_per_user() {
+        let db_pool = create_test_pool().await;
+        let (user, other) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+        let weth = UserReserve {
+            reserve: Address::repeat_byte(0x01),
+            collateral_balance: U256::from(10u64).pow(U256::from(18u64)),
+            debt_balance: U256::ZERO,
+            usage_as_collateral_enabled: true,
+        };
+        let usdc = UserReserve {
+            reserve: Address::repeat_byte(0x02),
+            collateral_balance: U256::ZERO,
+            debt_balance: U256::from(1_500_000_000u64),
+            usage_as_collateral_enabled: false,
+        };
+
+        save_user_reserves(&db_pool, user, &[weth, usdc]).await.unwrap();
+        save_user_reserves(&db_pool, other, &[usdc]).await.unwrap();
+        assert_eq!(get_user_reserves(&db_pool, user).await.unwrap(), vec![weth, usdc]);
+
+        // A refresh replaces every row of the user; an empty one clears them
+        save_user_reserves(&db_pool, user, &[usdc]).await.unwrap();
+        assert_eq!(get_user_reserves(&db_pool, user).await.unwrap(), vec![usdc]);
+        save_user_reserves(&db_pool, user, &[]).await.unwrap();
+        assert!(get_user_reserves(&db_pool, user).await.unwrap().is_empty());
+        assert_eq!(get_user_reserves(&db_pool, other).await.unwrap(), vec![usdc]);
+    }
+
+    #[tokio::test]
+    async fn test_liquidation_events_from_orphaned_blocks_can_be_rolled_back() {
+        let db_pool = create_test_pool().await;
+        let user = Address::repeat_byte(0x01);
+        for (tx_hash, block) in [("0xaa", 100i64), ("0xbb", 105), ("0xcc", 106)] {
+            record_liquidation_event(
+                &db_pool, &user, "0x01", "0x02", "1", None, "1", "1", Some(tx_hash), Some(block), "default",
+            )
+            .await
+            .unwrap();
+        }
+
+        let orphaned = get_liquidation_events_from_block(&db_pool, 105).await.unwrap();
+        let hashes: Vec<Option<String>> = orphaned.iter().map(|(_, hash)| hash.clone()).collect();
+        assert_eq!(hashes, vec![Some("0xbb".to_string()), Some("0xcc".to_string())]);
+
+        // One transaction was re-mined in the new chain, the other disappeared
+        update_liquidation_event_block(&db_pool, orphaned[0].0, 107).await.unwrap();
+        delete_liquidation_event(&db_pool, orphaned[1].0).await.unwrap();
+
+        let remaining = get_liquidation_events_from_block(&db_pool, 0).await.unwrap();
+        assert_eq!(remaining.len(), 2);
+        assert_eq!(get_liquidation_events_from_block(&db_pool, 107).await.unwrap()[0].1.as_deref(), Some("0xbb"));
+    }
 }
@@ -0,0 +1,396 @@
+use alloy_primitives::Address;
+use async_trait::async_trait;
+use eyre::Result;
+use serde::Serialize;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::events::BotEvent;
+use crate::models::{BusKind, LiquidationResult, NotNeededReason};
+use crate::monitoring::metrics;
+use crate::replay::RecordedEntry;
+
+/// Messages buffered for the bus; while it's slow or unreachable, newer messages are dropped
+/// rather than holding up the event processor
+const EVENT_BUS_CAPACITY: usize = 4096;
+
+/// How long Kafka may keep a message queued before giving up on it
+#[cfg(feature = "kafka")]
+const KAFKA_DELIVERY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Redis streams are trimmed to roughly this many entries
+#[cfg(feature = "redis")]
+const REDIS_STREAM_MAX_LEN: usize = 100_000;
+
+/// A processor event as published to the bus. `event` has the same form as a line of an
+/// event recording, so consumers can store the stream and replay it through the bot.
+#[derive(Debug, Clone, Serialize)]
+pub struct BusEvent {
+    pub chain_id: u64,
+    pub timestamp_ms: i64,
+    pub event: RecordedEntry,
+}
+
+/// The outcome of a liquidation attempt as published to the bus
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExecutionResult {
+    pub chain_id: u64,
+    pub timestamp_ms: i64,
+    pub user: Address,
+    /// "executed", "dry_run", "not_needed", "failed" or "error"
+    pub outcome: &'static str,
+    pub tx_hash: Option<String>,
+    /// Why no liquidation was needed, or what went wrong
+    pub reason: Option<String>,
+}
+
+impl ExecutionResult {
+    pub fn new(chain_id: u64, user: Address, result: &Result<LiquidationResult>) -> Self {
+        let (outcome, tx_hash, reason) = match result {
+            Ok(LiquidationResult::Executed(tx_hash)) => ("executed", Some(tx_hash.clone()), None),
+            Ok(LiquidationResult::NotNeeded(NotNeededReason::DryRun)) => ("dry_run", None, None),
+            Ok(LiquidationResult::NotNeeded(reason)) => {
+                ("not_needed", None, Some(format!("{:?}", reason)))
+            }
+            Ok(LiquidationResult::Failed(error)) => ("failed", None, Some(error.clone())),
+            Err(e) => ("error", None, Some(e.to_string())),
+        };
+        Self {
+            chain_id,
+            timestamp_ms: chrono::Utc::now().timestamp_millis(),
+            user,
+            outcome,
+            tx_hash,
+            reason,
+        }
+    }
+}
+
+/// A serialized message on its way to the bus
+#[derive(Debug, Clone, PartialEq)]
+pub struct BusMessage {
+    /// Kafka topic, NATS subject or Redis stream
+    pub topic: String,
+    /// The user or asset the message is about; Kafka partitions by it so each one's messages
+    /// stay in order
+    pub key: String,
+    pub payload: String,
+}
+
+/// A message bus client
+#[async_trait]
+pub trait BusPublisher: Send + Sync {
+    async fn publish(&self, message: &BusMessage) -> Result<()>;
+}
+
+#[cfg(feature = "kafka")]
+struct KafkaPublisher {
+    producer: rdkafka::producer::FutureProducer,
+}
+
+#[cfg(feature = "kafka")]
+impl KafkaPublisher {
+    /// `url` is `kafka://` followed by a comma-separated broker list
+    fn connect(url: &str) -> Result<Self> {
+        let brokers = url.split_once("://").map_or(url, |(_, brokers)| brokers);
+        let producer = rdkafka::ClientConfig::new()
+            .set("bootstrap.servers", brokers)
+            .set(
+                "message.timeout.ms",
+                KAFKA_DELIVERY_TIMEOUT.as_millis().to_string(),
+            )
+            .create()?;
+        Ok(Self { producer })
+    }
+}
+
+#[cfg(feature = "kafka")]
+#[async_trait]
+impl BusPublisher for KafkaPublisher {
+    async fn publish(&self, message: &BusMessage) -> Result<()> {
+        let record = rdkafka::producer::FutureRecord::to(&message.topic)
+            .key(&message.key)
+            .payload(&message.payload);
+        self.producer
+            .send(record, KAFKA_DELIVERY_TIMEOUT)
+            .await
+            .map_err(|(e, _)| eyre::eyre!("Kafka delivery failed: {}", e))?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "nats")]
+struct NatsPublisher {
+    client: async_nats::Client,
+}
+
+#[cfg(feature = "nats")]
+#[async_trait]
+impl BusPublisher for NatsPublisher {
+    async fn publish(&self, message: &BusMessage) -> Result<()> {
+        self.client
+            .publish(message.topic.clone(), message.payload.clone().into())
+            .await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "redis")]
+struct RedisPublisher {
+    connection: redis::aio::MultiplexedConnection,
+}
+
+#[cfg(feature = "redis")]
+#[async_trait]
+impl BusPublisher for RedisPublisher {
+    async fn publish(&self, message: &BusMessage) -> Result<()> {
+        let mut connection = self.connection.clone();
+        let _: String = redis::cmd("XADD")
+            .arg(&message.topic)
+            .arg("MAXLEN")
+            .arg("~")
+            .arg(REDIS_STREAM_MAX_LEN)
+            .arg("*")
+            .arg("key")
+            .arg(&message.key)
+            .arg("payload")
+            .arg(&message.payload)
+            .query_async(&mut connection)
+            .await?;
+        Ok(())
+    }
+}
+
+/// Connect to the bus at `url`. Each bus needs its cargo feature (`kafka`, `nats` or `redis`).
+#[cfg_attr(
+    not(any(feature = "kafka", feature = "nats", feature = "redis")),
+    allow(unused_variables)
+)]
+async fn connect_publisher(kind: BusKind, url: &str) -> Result<Box<dyn BusPublisher>> {
+    match kind {
+        #[cfg(feature = "kafka")]
+        BusKind::Kafka => Ok(Box::new(KafkaPublisher::connect(url)?)),
+        #[cfg(feature = "nats")]
+        BusKind::Nats => Ok(Box::new(NatsPublisher {
+            client: async_nats::connect(url).await?,
+        })),
+        #[cfg(feature = "redis")]
+        BusKind::Redis => Ok(Box::new(RedisPublisher {
+            connection: redis::Client::open(url)?
+                .get_multiplexed_async_connection()
+                .await?,
+        })),
+        #[allow(unreachable_patterns)]
+        kind => Err(eyre::eyre!(
+            "{} support is not compiled in - rebuild with --features {}",
+            kind.as_str(),
+            kind.as_str()
+        )),
+    }
+}
+
+/// The user or asset an event is about
+fn event_key(entry: &RecordedEntry) -> String {
+    let address = match entry {
+        RecordedEntry::Log(log) => log.inner.address,
+        RecordedEntry::UserPositionChanged { user }
+        | RecordedEntry::LiquidationOpportunity { user }
+        | RecordedEntry::ExternalLiquidationSignal { user } => *user,
+        RecordedEntry::PriceUpdate { asset, .. }
+        | RecordedEntry::OraclePriceChanged { asset, .. } => *asset,
+    };
+    address.to_string()
+}
+
+/// Mirrors processor events and liquidation results to a message bus as JSON. Publishing
+/// never waits on the bus: messages are handed to a background task, and dropped (and
+/// counted) while its queue is full.
+pub struct EventBus {
+    chain_id: u64,
+    events_topic: String,
+    executions_topic: String,
+    sender: mpsc::Sender<BusMessage>,
+}
+
+impl EventBus {
+    /// Connect to the bus at `url` (`kafka://`, `nats://` or `redis://`)
+    pub async fn connect(
+        url: &str,
+        chain_id: u64,
+        events_topic: String,
+        executions_topic: String,
+    ) -> Result<Self> {
+        let kind = BusKind::from_url(url)
+            .ok_or_else(|| eyre::eyre!("unsupported message bus URL scheme"))?;
+        let publisher = connect_publisher(kind, url).await?;
+        info!(
+            "📨 Publishing events to {} topic '{}' and execution results to '{}'",
+            kind.as_str(),
+            events_topic,
+            executions_topic
+        );
+        Ok(Self::start(
+            publisher,
+            chain_id,
+            events_topic,
+            executions_topic,
+        ))
+    }
+
+    /// Start forwarding to an already connected publisher
+    pub fn start(
+        publisher: Box<dyn BusPublisher>,
+        chain_id: u64,
+        events_topic: String,
+        executions_topic: String,
+    ) -> Self {
+        let (sender, receiver) = mpsc::channel(EVENT_BUS_CAPACITY);
+        tokio::spawn(forward_messages(publisher, receiver));
+        Self {
+            chain_id,
+            events_topic,
+            executions_topic,
+            sender,
+        }
+    }
+
+    /// Publish a processor event. Internal bookkeeping events are skipped, like in recordings.
+    pub fn publish_event(&self, event: &BotEvent) {
+        let Some(entry) = RecordedEntry::from_event(event) else {
+            return;
+        };
+        let key = event_key(&entry);
+        self.send(
+            &self.events_topic,
+            key,
+            &BusEvent {
+                chain_id: self.chain_id,
+                timestamp_ms: chrono::Utc::now().timestamp_millis(),
+                event: entry,
+            },
+        );
+    }
+
+    /// Publish the outcome of a liquidation attempt on `user`
+    pub fn publish_execution(&self, user: Address, result: &Result<LiquidationResult>) {
+        self.send(
+            &self.executions_topic,
+            user.to_string(),
+            &ExecutionResult::new(self.chain_id, user, result),
+        );
+    }
+
+    fn send<T: Serialize>(&self, topic: &str, key: String, payload: &T) {
+        let payload = match serde_json::to_string(payload) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize message for {}: {}", topic, e);
+                return;
+            }
+        };
+        let message = BusMessage {
+            topic: topic.to_string(),
+            key,
+            payload,
+        };
+        if self.sender.try_send(message).is_err() {
+            metrics::record_event_bus_message(topic, "dropped");
+        }
+    }
+}
+
+async fn forward_messages(
+    publisher: Box<dyn BusPublisher>,
+    mut receiver: mpsc::Receiver<BusMessage>,
+) {
+    // Only the first failure of an outage is logged; the metric counts the rest
+    let mut failing = false;
+    while let Some(message) = receiver.recv().await {
+        match publisher.publish(&message).await {
+            Ok(()) => {
+                metrics::record_event_bus_message(&message.topic, "published");
+                if failing {
+                    info!("📨 Message bus publishing recovered");
+                    failing = false;
+                }
+            }
+            Err(e) => {
+                metrics::record_event_bus_message(&message.topic, "failed");
+                if !failing {
+                    warn!("⚠️ Failed to publish to {}: {}", message.topic, e);
+                    failing = true;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::U256;
+    use parking_lot::Mutex;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[derive(Default)]
+    struct RecordingPublisher {
+        messages: Arc<Mutex<Vec<BusMessage>>>,
+    }
+
+    #[async_trait]
+    impl BusPublisher for RecordingPublisher {
+        async fn publish(&self, message: &BusMessage) -> Result<()> {
+            self.messages.lock().push(message.clone());
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_and_results_are_published_as_json() {
+        let publisher = RecordingPublisher::default();
+        let messages = publisher.messages.clone();
+        let bus = EventBus::start(
+            Box::new(publisher),
+            8453,
+            "events".to_string(),
+            "executions".to_string(),
+        );
+        let user = Address::repeat_byte(0x01);
+        let asset = Address::repeat_byte(0x02);
+
+        bus.publish_event(&BotEvent::FullRescan); // Not mirrored
+        bus.publish_event(&BotEvent::OraclePriceChanged(asset, U256::from(100u64)));
+        bus.publish_execution(user, &Ok(LiquidationResult::Failed("reverted".to_string())));
+
+        for _ in 0..100 {
+            if messages.lock().len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        let messages = messages.lock().clone();
+        assert_eq!(messages.len(), 2);
+
+        assert_eq!(messages[0].topic, "events");
+        assert_eq!(messages[0].key, asset.to_string());
+        let event: serde_json::Value = serde_json::from_str(&messages[0].payload).unwrap();
+        assert_eq!(event["chain_id"], 8453);
+        // Consumers can turn the payload back into a replayable recording entry
+        let entry: RecordedEntry = serde_json::from_value(event["event"].clone()).unwrap();
+        assert_eq!(
+            entry,
+            RecordedEntry::OraclePriceChanged {
+                asset,
+                new_price: U256::from(100u64)
+            }
+        );
+
+        assert_eq!(messages[1].topic, "executions");
+        assert_eq!(messages[1].key, user.to_string());
+        let result: serde_json::Value = serde_json::from_str(&messages[1].payload).unwrap();
+        assert_eq!(result["outcome"], "failed");
+        assert_eq!(result["reason"], "reverted");
+        assert!(result["tx_hash"].is_null());
+    }
+}
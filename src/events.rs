@@ -1,5 +1,14 @@
 use alloy_primitives::{Address, U256};
+use dashmap::DashSet;
+use std::sync::Arc;
+use tokio::sync::mpsc::{
+    self,
+    error::{TryRecvError, TrySendError},
+};
+use tracing::error;
+
 use crate::models::UserPosition;
+use crate::monitoring::metrics;
 
 // Event types for internal messaging
 #[derive(Debug, Clone)]
@@ -8,5 +17,277 @@ pub enum BotEvent {
     PriceUpdate(Address, U256, U256), // asset address, old_price, new_price
     LiquidationOpportunity(Address),  // user address
     DatabaseSync(Vec<UserPosition>),
-    OraclePriceChanged(Address, U256), // asset address, new price
-}
\ No newline at end of file
+    OraclePriceChanged(Address, U256),  // asset address, new price
+    ExternalLiquidationSignal(Address), // user flagged by an external risk model
+    FullRescan,                         // operator-requested recheck of every known user
+    ReserveDataUpdated(Address),        // the pool reported an update of this reserve
+}
+
+/// Queue an event waits in. The processor drains liquidations first, then control events,
+/// then position refreshes, so an oracle tick fanning out thousands of refreshes can't delay
+/// a liquidation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventLane {
+    Liquidation,
+    Control,
+    Refresh,
+}
+
+impl EventLane {
+    pub const ALL: [EventLane; 3] = [
+        EventLane::Liquidation,
+        EventLane::Control,
+        EventLane::Refresh,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EventLane::Liquidation => "liquidation",
+            EventLane::Control => "control",
+            EventLane::Refresh => "refresh",
+        }
+    }
+}
+
+/// An event still waiting in its lane; a duplicate sent meanwhile is merged into it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum QueuedKey {
+    Refresh(Address),
+    Liquidation(Address),
+    ExternalSignal(Address),
+    Reserve(Address),
+}
+
+impl BotEvent {
+    pub fn lane(&self) -> EventLane {
+        match self {
+            BotEvent::LiquidationOpportunity(_) | BotEvent::ExternalLiquidationSignal(_) => {
+                EventLane::Liquidation
+            }
+            BotEvent::UserPositionChanged(_) => EventLane::Refresh,
+            _ => EventLane::Control,
+        }
+    }
+
+    /// Events that only say "look at this again" are merged while one is queued. Price
+    /// changes carry the price itself and are never merged.
+    fn queued_key(&self) -> Option<QueuedKey> {
+        match self {
+            BotEvent::UserPositionChanged(user) => Some(QueuedKey::Refresh(*user)),
+            BotEvent::LiquidationOpportunity(user) => Some(QueuedKey::Liquidation(*user)),
+            BotEvent::ExternalLiquidationSignal(user) => Some(QueuedKey::ExternalSignal(*user)),
+            BotEvent::ReserveDataUpdated(reserve) => Some(QueuedKey::Reserve(*reserve)),
+            _ => None,
+        }
+    }
+}
+
+/// Why an event wasn't queued
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventSendError {
+    /// The lane is at capacity and the event was dropped
+    Full(EventLane),
+    /// The event processor has stopped
+    Closed,
+}
+
+impl std::fmt::Display for EventSendError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EventSendError::Full(lane) => write!(f, "{} event queue is full", lane.as_str()),
+            EventSendError::Closed => write!(f, "event processor has stopped"),
+        }
+    }
+}
+
+impl std::error::Error for EventSendError {}
+
+/// Sending half of the event bus. Sends never wait: a duplicate of a queued event is merged
+/// into it, and an event for a full lane is dropped. Dropped refreshes are caught up by the
+/// periodic scan.
+#[derive(Clone)]
+pub struct EventSender {
+    liquidation: mpsc::Sender<BotEvent>,
+    control: mpsc::Sender<BotEvent>,
+    refresh: mpsc::Sender<BotEvent>,
+    queued: Arc<DashSet<QueuedKey>>,
+}
+
+impl EventSender {
+    fn lane(&self, lane: EventLane) -> &mpsc::Sender<BotEvent> {
+        match lane {
+            EventLane::Liquidation => &self.liquidation,
+            EventLane::Control => &self.control,
+            EventLane::Refresh => &self.refresh,
+        }
+    }
+
+    pub fn send(&self, event: BotEvent) -> Result<(), EventSendError> {
+        let lane = event.lane();
+        let key = event.queued_key();
+        if let Some(key) = key {
+            if !self.queued.insert(key) {
+                metrics::record_event_coalesced(lane.as_str());
+                return Ok(());
+            }
+        }
+
+        let error = match self.lane(lane).try_send(event) {
+            Ok(()) => return Ok(()),
+            Err(e) => e,
+        };
+        if let Some(key) = key {
+            self.queued.remove(&key);
+        }
+        match error {
+            TrySendError::Full(event) => {
+                metrics::record_event_dropped(lane.as_str());
+                if lane == EventLane::Liquidation {
+                    error!("❌ Liquidation event queue full - dropped {:?}", event);
+                }
+                Err(EventSendError::Full(lane))
+            }
+            TrySendError::Closed(_) => Err(EventSendError::Closed),
+        }
+    }
+
+    /// Events waiting in `lane`
+    pub fn depth(&self, lane: EventLane) -> usize {
+        let sender = self.lane(lane);
+        sender.max_capacity() - sender.capacity()
+    }
+}
+
+/// Receiving half of the event bus
+pub struct EventReceiver {
+    liquidation: mpsc::Receiver<BotEvent>,
+    control: mpsc::Receiver<BotEvent>,
+    refresh: mpsc::Receiver<BotEvent>,
+    queued: Arc<DashSet<QueuedKey>>,
+}
+
+impl EventReceiver {
+    /// Next event from the highest-priority lane holding one; None once every sender is gone
+    pub async fn recv(&mut self) -> Option<BotEvent> {
+        let event = tokio::select! {
+            biased;
+            Some(event) = self.liquidation.recv() => event,
+            Some(event) = self.control.recv() => event,
+            Some(event) = self.refresh.recv() => event,
+            else => return None,
+        };
+        Some(taken(&self.queued, event))
+    }
+
+    /// Next event without waiting, highest-priority lane first
+    pub fn try_recv(&mut self) -> Result<BotEvent, TryRecvError> {
+        let mut result = Err(TryRecvError::Disconnected);
+        for lane in [&mut self.liquidation, &mut self.control, &mut self.refresh] {
+            match lane.try_recv() {
+                Ok(event) => return Ok(taken(&self.queued, event)),
+                Err(TryRecvError::Empty) => result = Err(TryRecvError::Empty),
+                Err(TryRecvError::Disconnected) => {}
+            }
+        }
+        result
+    }
+}
+
+/// From here on a new event for the same key describes a later change, so queue it
+fn taken(queued: &DashSet<QueuedKey>, event: BotEvent) -> BotEvent {
+    if let Some(key) = event.queued_key() {
+        queued.remove(&key);
+    }
+    event
+}
+
+/// Event bus whose liquidation lane holds `liquidation_capacity` events and whose control and
+/// refresh lanes hold `capacity` each
+pub fn channel(liquidation_capacity: usize, capacity: usize) -> (EventSender, EventReceiver) {
+    let (liquidation_tx, liquidation_rx) = mpsc::channel(liquidation_capacity.max(1));
+    let (control_tx, control_rx) = mpsc::channel(capacity.max(1));
+    let (refresh_tx, refresh_rx) = mpsc::channel(capacity.max(1));
+    let queued = Arc::new(DashSet::new());
+    (
+        EventSender {
+            liquidation: liquidation_tx,
+            control: control_tx,
+            refresh: refresh_tx,
+            queued: queued.clone(),
+        },
+        EventReceiver {
+            liquidation: liquidation_rx,
+            control: control_rx,
+            refresh: refresh_rx,
+            queued,
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_liquidations_jump_the_refresh_queue() {
+        let (tx, mut rx) = channel(10, 10);
+        let (alice, bob) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+
+        tx.send(BotEvent::UserPositionChanged(alice)).unwrap();
+        tx.send(BotEvent::UserPositionChanged(bob)).unwrap();
+        tx.send(BotEvent::FullRescan).unwrap();
+        tx.send(BotEvent::LiquidationOpportunity(bob)).unwrap();
+
+        assert!(
+            matches!(rx.recv().await, Some(BotEvent::LiquidationOpportunity(user)) if user == bob)
+        );
+        assert!(matches!(rx.recv().await, Some(BotEvent::FullRescan)));
+        assert!(
+            matches!(rx.recv().await, Some(BotEvent::UserPositionChanged(user)) if user == alice)
+        );
+        assert!(
+            matches!(rx.recv().await, Some(BotEvent::UserPositionChanged(user)) if user == bob)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_refreshes_merge_until_processed() {
+        let (tx, mut rx) = channel(10, 10);
+        let user = Address::repeat_byte(0x01);
+
+        for _ in 0..5 {
+            tx.send(BotEvent::UserPositionChanged(user)).unwrap();
+        }
+        assert_eq!(tx.depth(EventLane::Refresh), 1);
+
+        rx.recv().await.unwrap();
+        // Taken off the queue: a later change queues a fresh refresh
+        tx.send(BotEvent::UserPositionChanged(user)).unwrap();
+        assert_eq!(tx.depth(EventLane::Refresh), 1);
+    }
+
+    #[tokio::test]
+    async fn test_full_lane_drops_without_blocking_others() {
+        let (tx, mut rx) = channel(10, 2);
+
+        for byte in 1..=2 {
+            tx.send(BotEvent::UserPositionChanged(Address::repeat_byte(byte)))
+                .unwrap();
+        }
+        let dropped = Address::repeat_byte(0x03);
+        assert_eq!(
+            tx.send(BotEvent::UserPositionChanged(dropped)).unwrap_err(),
+            EventSendError::Full(EventLane::Refresh)
+        );
+        tx.send(BotEvent::LiquidationOpportunity(dropped)).unwrap();
+        assert!(matches!(
+            rx.recv().await,
+            Some(BotEvent::LiquidationOpportunity(_))
+        ));
+
+        // A dropped refresh isn't left marked as queued
+        rx.recv().await.unwrap();
+        tx.send(BotEvent::UserPositionChanged(dropped)).unwrap();
+        assert_eq!(tx.depth(EventLane::Refresh), 2);
+    }
+}
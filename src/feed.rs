@@ -0,0 +1,105 @@
+use alloy_primitives::{Address, U256};
+use std::sync::OnceLock;
+use tokio::sync::broadcast;
+
+use crate::circuit_breaker::CircuitBreakerState;
+
+/// Events buffered for each subscriber; one that falls further behind misses the oldest
+const FEED_CAPACITY: usize = 1024;
+
+/// What happened, as streamed to downstream consumers
+#[derive(Debug, Clone, PartialEq)]
+pub enum FeedEventKind {
+    /// A user was found liquidatable and handed to the liquidation pipeline
+    Opportunity {
+        user: Address,
+        health_factor: Option<U256>,
+    },
+    /// A liquidation transaction was mined
+    Liquidation {
+        user: Address,
+        tx_hash: String,
+        profit: U256,
+    },
+    /// The circuit breaker changed state
+    CircuitBreaker {
+        state: CircuitBreakerState,
+        message: String,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FeedEvent {
+    pub chain_id: u64,
+    pub timestamp_ms: i64,
+    pub kind: FeedEventKind,
+}
+
+/// Process-wide feed, so detection, execution and the circuit breaker can publish without
+/// threading a handle through; every chain publishes to it with its chain id
+fn sender() -> &'static broadcast::Sender<FeedEvent> {
+    static SENDER: OnceLock<broadcast::Sender<FeedEvent>> = OnceLock::new();
+    SENDER.get_or_init(|| broadcast::channel(FEED_CAPACITY).0)
+}
+
+/// Publish an event to every current subscriber; without subscribers it's dropped
+pub fn publish(chain_id: u64, kind: FeedEventKind) {
+    let _ = sender().send(FeedEvent {
+        chain_id,
+        timestamp_ms: chrono::Utc::now().timestamp_millis(),
+        kind,
+    });
+}
+
+/// Receive every event published from now on
+pub fn subscribe() -> broadcast::Receiver<FeedEvent> {
+    sender().subscribe()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_subscribers_receive_events_published_after_subscribing() {
+        let user = Address::repeat_byte(0x42);
+        publish(
+            1,
+            FeedEventKind::Opportunity {
+                user,
+                health_factor: None,
+            },
+        );
+
+        let mut receiver = subscribe();
+        publish(
+            1,
+            FeedEventKind::Liquidation {
+                user,
+                tx_hash: "0xabc".to_string(),
+                profit: U256::from(7),
+            },
+        );
+
+        // Other tests publish to the same feed, so look for this one
+        loop {
+            let event = receiver.recv().await.unwrap();
+            if let FeedEventKind::Liquidation {
+                user: liquidated,
+                tx_hash,
+                ..
+            } = event.kind
+            {
+                if liquidated == user {
+                    assert_eq!(event.chain_id, 1);
+                    assert_eq!(tx_hash, "0xabc");
+                    break;
+                }
+            }
+            assert!(
+                !matches!(event.kind, FeedEventKind::Opportunity { user: seen, .. } if seen == user),
+                "received an event published before subscribing"
+            );
+        }
+    }
+}
@@ -0,0 +1,305 @@
+use alloy_primitives::U256;
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockNumberOrTag, FeeHistory};
+use eyre::Result;
+use parking_lot::RwLock;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use crate::models::FeeProfile;
+
+/// Reward percentiles requested from `eth_feeHistory`: conservative, normal, aggressive
+const REWARD_PERCENTILES: [f64; 3] = [25.0, 50.0, 90.0];
+
+/// Base fees kept for the circuit breaker baseline (about an hour of Base blocks)
+const BASE_FEE_HISTORY_CAP: usize = 1_800;
+
+/// Fee data older than this is refreshed before pricing a transaction
+const MAX_SNAPSHOT_AGE: Duration = Duration::from_secs(4);
+
+/// How often the background updater samples `eth_feeHistory`
+const REFRESH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Tip floor so empty blocks (zero rewards) never price a liquidation at no tip at all
+const MIN_PRIORITY_FEE_WEI: u128 = 1_000_000;
+
+/// EIP-1559 fee fields for one transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eip1559Fees {
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+/// Fee market state derived from one `eth_feeHistory` response
+#[derive(Debug, Clone)]
+pub struct FeeSnapshot {
+    /// Base fee of the block after the latest one
+    pub next_base_fee: u128,
+    /// Median over the sampled (non-empty) blocks of each reward percentile
+    pub priority_fees: [u128; 3],
+    pub latest_block: u64,
+    pub fetched_at: Instant,
+}
+
+impl FeeSnapshot {
+    pub fn from_fee_history(history: &FeeHistory) -> Option<Self> {
+        let next_base_fee = *history.base_fee_per_gas.last()?;
+        let block_count = history.base_fee_per_gas.len().saturating_sub(1) as u64;
+
+        let mut priority_fees = [0u128; 3];
+        if let Some(rewards) = &history.reward {
+            for (i, fee) in priority_fees.iter_mut().enumerate() {
+                let samples: Vec<u128> = rewards
+                    .iter()
+                    .zip(&history.gas_used_ratio)
+                    .filter(|(_, used)| **used > 0.0)
+                    .filter_map(|(block, _)| block.get(i).copied())
+                    .collect();
+                *fee = median(samples).unwrap_or(0);
+            }
+        }
+
+        Some(Self {
+            next_base_fee,
+            priority_fees: priority_fees.map(|fee| fee.max(MIN_PRIORITY_FEE_WEI)),
+            latest_block: (history.oldest_block + block_count).saturating_sub(1),
+            fetched_at: Instant::now(),
+        })
+    }
+
+    /// Fees for `profile`: its percentile tip plus headroom for base fee growth
+    pub fn fees(&self, profile: FeeProfile) -> Eip1559Fees {
+        let (reward_index, base_fee_headroom_pct) = match profile {
+            FeeProfile::Conservative => (0, 125u128),
+            FeeProfile::Normal => (1, 200),
+            FeeProfile::Aggressive => (2, 300),
+        };
+        let max_priority_fee_per_gas = self.priority_fees[reward_index];
+        let max_fee_per_gas = (self.next_base_fee.saturating_mul(base_fee_headroom_pct) / 100)
+            .saturating_add(max_priority_fee_per_gas);
+        Eip1559Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+        }
+    }
+}
+
+fn median(mut values: Vec<u128>) -> Option<u128> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    Some(values[values.len() / 2])
+}
+
+/// Profile for a liquidation expected to make `profit` when at least `required_profit` is
+/// needed: margins under `conservative_multiple`x the requirement bid low, margins of
+/// `aggressive_multiple`x or more bid high
+pub fn select_fee_profile(
+    profit: U256,
+    required_profit: U256,
+    conservative_multiple: u64,
+    aggressive_multiple: u64,
+) -> FeeProfile {
+    if required_profit.is_zero() {
+        return FeeProfile::Normal;
+    }
+    if profit >= required_profit.saturating_mul(U256::from(aggressive_multiple)) {
+        FeeProfile::Aggressive
+    } else if profit < required_profit.saturating_mul(U256::from(conservative_multiple)) {
+        FeeProfile::Conservative
+    } else {
+        FeeProfile::Normal
+    }
+}
+
+#[derive(Debug, Default)]
+struct OracleState {
+    snapshot: Option<FeeSnapshot>,
+    /// Base fee by block number, capped at `BASE_FEE_HISTORY_CAP` most recent blocks
+    base_fees: BTreeMap<u64, u128>,
+}
+
+/// Tracks base fee history and percentile priority fees from `eth_feeHistory`, prices
+/// liquidations per fee profile and supplies the circuit breaker's gas baseline
+pub struct GasOracle {
+    history_blocks: u64,
+    /// Fixed profile for every liquidation (None = chosen by profit size)
+    profile_override: Option<FeeProfile>,
+    conservative_profit_multiple: u64,
+    aggressive_profit_multiple: u64,
+    state: RwLock<OracleState>,
+}
+
+impl GasOracle {
+    pub fn new(
+        history_blocks: u64,
+        profile_override: Option<FeeProfile>,
+        conservative_profit_multiple: u64,
+        aggressive_profit_multiple: u64,
+    ) -> Self {
+        Self {
+            history_blocks,
+            profile_override,
+            conservative_profit_multiple,
+            aggressive_profit_multiple,
+            state: RwLock::new(OracleState::default()),
+        }
+    }
+
+    /// Fee profile for a liquidation with the given expected and required profit
+    pub fn profile_for_profit(&self, profit: U256, required_profit: U256) -> FeeProfile {
+        self.profile_override.unwrap_or_else(|| {
+            select_fee_profile(
+                profit,
+                required_profit,
+                self.conservative_profit_multiple,
+                self.aggressive_profit_multiple,
+            )
+        })
+    }
+
+    pub fn snapshot(&self) -> Option<FeeSnapshot> {
+        self.state.read().snapshot.clone()
+    }
+
+    /// Sample the latest `history_blocks` blocks
+    pub async fn refresh<P>(&self, provider: &P) -> Result<FeeSnapshot>
+    where
+        P: Provider,
+    {
+        let history = provider
+            .get_fee_history(self.history_blocks, BlockNumberOrTag::Latest, &REWARD_PERCENTILES)
+            .await?;
+        let snapshot = FeeSnapshot::from_fee_history(&history)
+            .ok_or_else(|| eyre::eyre!("eth_feeHistory returned no base fees"))?;
+
+        let mut state = self.state.write();
+        record_base_fees(&mut state.base_fees, &history);
+        state.snapshot = Some(snapshot.clone());
+        debug!(
+            "⛽ Fee snapshot at block {}: next base fee {} wei, tips {:?} wei",
+            snapshot.latest_block, snapshot.next_base_fee, snapshot.priority_fees
+        );
+        Ok(snapshot)
+    }
+
+    /// Fees for `profile`, refreshing the snapshot first if it is stale
+    pub async fn fees<P>(&self, provider: &P, profile: FeeProfile) -> Result<Eip1559Fees>
+    where
+        P: Provider,
+    {
+        let snapshot = match self.snapshot() {
+            Some(snapshot) if snapshot.fetched_at.elapsed() <= MAX_SNAPSHOT_AGE => snapshot,
+            _ => self.refresh(provider).await?,
+        };
+        Ok(snapshot.fees(profile))
+    }
+
+    /// Typical gas price over the tracked history: median base fee plus the median tip.
+    /// None until the first refresh.
+    pub fn baseline_gas_price(&self) -> Option<U256> {
+        let state = self.state.read();
+        let base_fee = median(state.base_fees.values().copied().collect())?;
+        let tip = state
+            .snapshot
+            .as_ref()
+            .map_or(0, |snapshot| snapshot.priority_fees[1]);
+        Some(U256::from(base_fee.saturating_add(tip)))
+    }
+
+    /// Keep the snapshot and base fee history current
+    pub async fn run_updater<P>(&self, provider: Arc<P>) -> Result<()>
+    where
+        P: Provider,
+    {
+        let mut interval = tokio::time::interval(REFRESH_INTERVAL);
+        loop {
+            interval.tick().await;
+            if let Err(e) = self.refresh(provider.as_ref()).await {
+                warn!("Failed to refresh gas oracle: {}", e);
+            }
+        }
+    }
+}
+
+fn record_base_fees(base_fees: &mut BTreeMap<u64, u128>, history: &FeeHistory) {
+    // The last entry is the next block's base fee, not a mined block's
+    let mined = history.base_fee_per_gas.len().saturating_sub(1);
+    for (offset, base_fee) in history.base_fee_per_gas[..mined].iter().enumerate() {
+        base_fees.insert(history.oldest_block + offset as u64, *base_fee);
+    }
+    while base_fees.len() > BASE_FEE_HISTORY_CAP {
+        base_fees.pop_first();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fee_history() -> FeeHistory {
+        FeeHistory {
+            oldest_block: 100,
+            base_fee_per_gas: vec![10_000_000, 12_000_000, 11_000_000, 20_000_000],
+            gas_used_ratio: vec![0.5, 0.0, 0.9],
+            reward: Some(vec![
+                vec![2_000_000, 5_000_000, 40_000_000],
+                vec![0, 0, 0],
+                vec![4_000_000, 7_000_000, 60_000_000],
+            ]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_snapshot_uses_next_base_fee_and_skips_empty_blocks() {
+        let snapshot = FeeSnapshot::from_fee_history(&fee_history()).unwrap();
+        assert_eq!(snapshot.next_base_fee, 20_000_000);
+        assert_eq!(snapshot.latest_block, 102);
+        assert_eq!(snapshot.priority_fees, [4_000_000, 7_000_000, 60_000_000]);
+
+        let conservative = snapshot.fees(FeeProfile::Conservative);
+        assert_eq!(conservative.max_priority_fee_per_gas, 4_000_000);
+        assert_eq!(conservative.max_fee_per_gas, 25_000_000 + 4_000_000);
+
+        let aggressive = snapshot.fees(FeeProfile::Aggressive);
+        assert_eq!(aggressive.max_priority_fee_per_gas, 60_000_000);
+        assert_eq!(aggressive.max_fee_per_gas, 60_000_000 + 60_000_000);
+    }
+
+    #[test]
+    fn test_profile_follows_profit_size() {
+        let required = U256::from(1_000u64);
+        let select = |profit: u64| select_fee_profile(U256::from(profit), required, 2, 10);
+
+        assert_eq!(select(1_500), FeeProfile::Conservative);
+        assert_eq!(select(2_000), FeeProfile::Normal);
+        assert_eq!(select(9_999), FeeProfile::Normal);
+        assert_eq!(select(10_000), FeeProfile::Aggressive);
+
+        let oracle = GasOracle::new(20, Some(FeeProfile::Normal), 2, 10);
+        assert_eq!(
+            oracle.profile_for_profit(U256::from(50_000u64), required),
+            FeeProfile::Normal
+        );
+    }
+
+    #[test]
+    fn test_baseline_tracks_recorded_base_fees() {
+        let oracle = GasOracle::new(20, None, 2, 10);
+        assert_eq!(oracle.baseline_gas_price(), None);
+
+        let history = fee_history();
+        {
+            let mut state = oracle.state.write();
+            record_base_fees(&mut state.base_fees, &history);
+            state.snapshot = FeeSnapshot::from_fee_history(&history);
+        }
+        // Median of the mined blocks' base fees plus the median tip
+        assert_eq!(oracle.baseline_gas_price(), Some(U256::from(11_000_000u64 + 7_000_000)));
+        assert_eq!(oracle.state.read().base_fees.len(), 3);
+    }
+}
@@ -0,0 +1,195 @@
+use eyre::Result;
+use futures::Stream;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tonic::{Request, Response, Status};
+use tracing::{info, warn};
+
+use crate::circuit_breaker::CircuitBreakerState;
+use crate::feed::{self, FeedEvent, FeedEventKind};
+
+pub mod proto {
+    tonic::include_proto!("liquidation_bot.v1");
+}
+
+use proto::liquidation_feed_server::{LiquidationFeed, LiquidationFeedServer};
+
+fn event_kind(kind: &FeedEventKind) -> proto::EventKind {
+    match kind {
+        FeedEventKind::Opportunity { .. } => proto::EventKind::Opportunity,
+        FeedEventKind::Liquidation { .. } => proto::EventKind::Liquidation,
+        FeedEventKind::CircuitBreaker { .. } => proto::EventKind::CircuitBreaker,
+    }
+}
+
+fn circuit_breaker_state(state: &CircuitBreakerState) -> proto::CircuitBreakerState {
+    match state {
+        CircuitBreakerState::Closed => proto::CircuitBreakerState::Closed,
+        CircuitBreakerState::Open => proto::CircuitBreakerState::Open,
+        CircuitBreakerState::HalfOpen => proto::CircuitBreakerState::HalfOpen,
+        CircuitBreakerState::Disabled => proto::CircuitBreakerState::Disabled,
+    }
+}
+
+/// Wire form of a feed event
+pub fn to_proto(event: FeedEvent) -> proto::FeedEvent {
+    let payload = match event.kind {
+        FeedEventKind::Opportunity {
+            user,
+            health_factor,
+        } => proto::feed_event::Event::Opportunity(proto::Opportunity {
+            user: user.to_string(),
+            health_factor: health_factor.map(|hf| hf.to_string()).unwrap_or_default(),
+        }),
+        FeedEventKind::Liquidation {
+            user,
+            tx_hash,
+            profit,
+        } => proto::feed_event::Event::Liquidation(proto::Liquidation {
+            user: user.to_string(),
+            tx_hash,
+            profit_wei: profit.to_string(),
+        }),
+        FeedEventKind::CircuitBreaker { state, message } => {
+            proto::feed_event::Event::CircuitBreaker(proto::CircuitBreakerChange {
+                state: circuit_breaker_state(&state) as i32,
+                message,
+            })
+        }
+    };
+    proto::FeedEvent {
+        chain_id: event.chain_id,
+        timestamp_ms: event.timestamp_ms,
+        event: Some(payload),
+    }
+}
+
+/// Streams one chain's feed to each subscriber
+pub struct FeedService {
+    chain_id: u64,
+}
+
+#[tonic::async_trait]
+impl LiquidationFeed for FeedService {
+    type SubscribeStream = Pin<Box<dyn Stream<Item = Result<proto::FeedEvent, Status>> + Send>>;
+
+    async fn subscribe(
+        &self,
+        request: Request<proto::SubscribeRequest>,
+    ) -> Result<Response<Self::SubscribeStream>, Status> {
+        let kinds = request.into_inner().kinds;
+        let chain_id = self.chain_id;
+        info!("📡 gRPC feed subscriber connected");
+
+        let stream = futures::stream::unfold(feed::subscribe(), move |mut receiver| {
+            let kinds = kinds.clone();
+            async move {
+                loop {
+                    match receiver.recv().await {
+                        Ok(event) => {
+                            let wanted = kinds.is_empty()
+                                || kinds.contains(&(event_kind(&event.kind) as i32));
+                            if event.chain_id == chain_id && wanted {
+                                return Some((Ok(to_proto(event)), receiver));
+                            }
+                        }
+                        Err(RecvError::Lagged(missed)) => {
+                            warn!(
+                                "📡 gRPC feed subscriber fell behind and missed {} events",
+                                missed
+                            );
+                        }
+                        Err(RecvError::Closed) => return None,
+                    }
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+/// Serve the gRPC feed of `chain_id`. With an admin token configured, callers must send it as
+/// `authorization: Bearer <token>` metadata.
+pub async fn start_grpc_server(
+    listen_addr: &str,
+    chain_id: u64,
+    admin_token: Option<String>,
+) -> Result<()> {
+    let addr: SocketAddr = listen_addr.parse()?;
+    if admin_token.is_none() {
+        warn!("⚠️ ADMIN_TOKEN not configured - the gRPC feed accepts unauthenticated subscribers");
+    }
+    let expected = admin_token.map(|token| Arc::new(format!("Bearer {}", token)));
+    let authorize = move |request: Request<()>| -> Result<Request<()>, Status> {
+        let Some(expected) = &expected else {
+            return Ok(request);
+        };
+        let provided = request
+            .metadata()
+            .get("authorization")
+            .and_then(|value| value.to_str().ok());
+        match provided {
+            Some(provided)
+                if crate::admin::constant_time_eq(provided.as_bytes(), expected.as_bytes()) =>
+            {
+                Ok(request)
+            }
+            _ => Err(Status::unauthenticated("unauthorized")),
+        }
+    };
+
+    info!("📡 gRPC feed listening on {}", addr);
+    tonic::transport::Server::builder()
+        .add_service(LiquidationFeedServer::with_interceptor(
+            FeedService { chain_id },
+            authorize,
+        ))
+        .serve(addr)
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+
+    #[test]
+    fn test_feed_events_convert_to_wire_form() {
+        let user = Address::repeat_byte(0x01);
+        let event = to_proto(FeedEvent {
+            chain_id: 8453,
+            timestamp_ms: 1_000,
+            kind: FeedEventKind::Liquidation {
+                user,
+                tx_hash: "0xabc".to_string(),
+                profit: U256::from(5_000u64),
+            },
+        });
+        assert_eq!(event.chain_id, 8453);
+        assert_eq!(
+            event.event,
+            Some(proto::feed_event::Event::Liquidation(proto::Liquidation {
+                user: user.to_string(),
+                tx_hash: "0xabc".to_string(),
+                profit_wei: "5000".to_string(),
+            }))
+        );
+
+        let breaker = to_proto(FeedEvent {
+            chain_id: 8453,
+            timestamp_ms: 2_000,
+            kind: FeedEventKind::CircuitBreaker {
+                state: CircuitBreakerState::HalfOpen,
+                message: "testing".to_string(),
+            },
+        });
+        assert!(matches!(
+            breaker.event,
+            Some(proto::feed_event::Event::CircuitBreaker(change))
+                if change.state == proto::CircuitBreakerState::HalfOpen as i32
+        ));
+    }
+}
@@ -0,0 +1,305 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use eyre::Result;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use tracing::{info, warn};
+
+use crate::liquidation::profit_recheck::fetch_token_balance;
+use crate::models::LiquidationAssetConfig;
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::networks::NetworkAddresses;
+
+/// One executor wallet's balance of one asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WalletHolding {
+    pub wallet: Address,
+    pub symbol: String,
+    pub token: Address,
+    pub balance: U256,
+    /// Oracle base currency units
+    pub value_base: U256,
+}
+
+/// Holdings of one asset across all executor wallets, valued at the Aave oracle price
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AssetExposure {
+    pub symbol: String,
+    pub token: Address,
+    pub balance: U256,
+    /// Oracle base currency units (USD with 8 decimals on Aave v3)
+    pub value_base: U256,
+    /// Share of the total inventory value
+    pub share_bps: u64,
+}
+
+/// What the executor wallets hold and how far that is from holding only the target asset
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryReport {
+    pub target_symbol: String,
+    pub total_value_base: U256,
+    /// Largest value first
+    pub assets: Vec<AssetExposure>,
+    /// Share of the inventory held in anything but the target asset
+    pub off_target_bps: u64,
+    pub updated_at_ms: i64,
+}
+
+/// A swap of one wallet's balance of an over-exposed asset into the target asset
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RebalanceSwap {
+    pub wallet: Address,
+    pub symbol: String,
+    pub token: Address,
+    pub amount: U256,
+}
+
+/// Value of `balance` token units at `price`, in the oracle's base currency
+pub fn holding_value(balance: U256, price: U256, decimals: u8) -> U256 {
+    let unit = U256::from(10u64).pow(U256::from(decimals));
+    balance.saturating_mul(price) / unit
+}
+
+fn share_bps(value: U256, total: U256) -> u64 {
+    if total.is_zero() {
+        return 0;
+    }
+    (value.saturating_mul(U256::from(10_000u64)) / total).to::<u64>()
+}
+
+impl InventoryReport {
+    /// Aggregate per-wallet holdings by asset
+    pub fn new(target_symbol: &str, holdings: &[WalletHolding], updated_at_ms: i64) -> Self {
+        let mut by_symbol: BTreeMap<&str, (Address, U256, U256)> = BTreeMap::new();
+        for holding in holdings {
+            let entry = by_symbol.entry(holding.symbol.as_str()).or_insert((
+                holding.token,
+                U256::ZERO,
+                U256::ZERO,
+            ));
+            entry.1 = entry.1.saturating_add(holding.balance);
+            entry.2 = entry.2.saturating_add(holding.value_base);
+        }
+
+        let total_value_base = by_symbol.values().fold(U256::ZERO, |total, (_, _, value)| {
+            total.saturating_add(*value)
+        });
+        let mut assets: Vec<AssetExposure> = by_symbol
+            .into_iter()
+            .filter(|(_, (_, balance, _))| !balance.is_zero())
+            .map(|(symbol, (token, balance, value_base))| AssetExposure {
+                symbol: symbol.to_string(),
+                token,
+                balance,
+                value_base,
+                share_bps: share_bps(value_base, total_value_base),
+            })
+            .collect();
+        assets.sort_by(|a, b| b.value_base.cmp(&a.value_base));
+
+        let off_target_value = assets
+            .iter()
+            .filter(|asset| asset.symbol != target_symbol)
+            .fold(U256::ZERO, |total, asset| {
+                total.saturating_add(asset.value_base)
+            });
+
+        Self {
+            target_symbol: target_symbol.to_string(),
+            total_value_base,
+            assets,
+            off_target_bps: share_bps(off_target_value, total_value_base),
+            updated_at_ms,
+        }
+    }
+
+    /// Non-target assets whose share of the inventory exceeds `max_deviation_bps`
+    pub fn over_exposed(&self, max_deviation_bps: u64) -> impl Iterator<Item = &AssetExposure> {
+        self.assets.iter().filter(move |asset| {
+            asset.symbol != self.target_symbol && asset.share_bps > max_deviation_bps
+        })
+    }
+}
+
+/// Values the collateral that piles up in executor wallets after liquidations and measures it
+/// against a target asset (e.g. holding everything as USDC). Assets whose share of the
+/// inventory deviates from the target by more than the threshold are planned for swapping
+/// into it; working capital (MIN_TOKEN_BALANCES) is left in place.
+pub struct InventoryTracker {
+    target_symbol: String, // Upper-case
+    max_deviation_bps: u64,
+    keep: BTreeMap<String, U256>, // By upper-case symbol
+    network: NetworkAddresses,
+    latest: RwLock<Option<InventoryReport>>,
+}
+
+impl InventoryTracker {
+    pub fn new(
+        target_symbol: String,
+        max_deviation_bps: u64,
+        keep: BTreeMap<String, U256>,
+        network: NetworkAddresses,
+    ) -> Self {
+        Self {
+            target_symbol: target_symbol.to_uppercase(),
+            max_deviation_bps,
+            keep,
+            network,
+            latest: RwLock::new(None),
+        }
+    }
+
+    pub fn target_symbol(&self) -> &str {
+        &self.target_symbol
+    }
+
+    /// Most recent report, for status output (None before the first refresh)
+    pub fn latest(&self) -> Option<InventoryReport> {
+        self.latest.read().clone()
+    }
+
+    /// Read every wallet's balance of `assets`, value them at oracle prices and store the
+    /// report. Balances that fail to read are left out until the next refresh.
+    pub async fn refresh<P>(
+        &self,
+        provider: &P,
+        wallets: &[Address],
+        assets: &[LiquidationAssetConfig],
+    ) -> Result<(InventoryReport, Vec<WalletHolding>)>
+    where
+        P: Provider,
+    {
+        let tokens: Vec<Address> = assets.iter().map(|asset| asset.address).collect();
+        let prices = fetch_oracle_prices(provider, &self.network, &tokens).await?;
+
+        let mut holdings = Vec::new();
+        for wallet in wallets {
+            for (asset, price) in assets.iter().zip(&prices) {
+                let balance = match fetch_token_balance(provider, asset.address, *wallet).await {
+                    Ok(balance) => balance,
+                    Err(e) => {
+                        warn!(
+                            "Failed to read {} balance of wallet {} for inventory: {}",
+                            asset.symbol, wallet, e
+                        );
+                        continue;
+                    }
+                };
+                if balance.is_zero() {
+                    continue;
+                }
+                holdings.push(WalletHolding {
+                    wallet: *wallet,
+                    symbol: asset.symbol.to_uppercase(),
+                    token: asset.address,
+                    balance,
+                    value_base: holding_value(balance, *price, asset.decimals),
+                });
+            }
+        }
+
+        let report = InventoryReport::new(
+            &self.target_symbol,
+            &holdings,
+            chrono::Utc::now().timestamp_millis(),
+        );
+        info!(
+            "📦 Inventory worth {} (base units), {}bps off target {}",
+            report.total_value_base, report.off_target_bps, self.target_symbol
+        );
+        *self.latest.write() = Some(report.clone());
+        Ok((report, holdings))
+    }
+
+    /// Swaps bringing `report`'s over-exposed assets back into the target asset: each wallet's
+    /// balance of them, less the minimum it keeps as working capital
+    pub fn plan_rebalance(
+        &self,
+        report: &InventoryReport,
+        holdings: &[WalletHolding],
+    ) -> Vec<RebalanceSwap> {
+        let over_exposed: Vec<&str> = report
+            .over_exposed(self.max_deviation_bps)
+            .map(|asset| asset.symbol.as_str())
+            .collect();
+
+        holdings
+            .iter()
+            .filter(|holding| over_exposed.contains(&holding.symbol.as_str()))
+            .filter_map(|holding| {
+                let keep = self.keep.get(&holding.symbol).copied().unwrap_or_default();
+                let amount = holding.balance.saturating_sub(keep);
+                (!amount.is_zero()).then(|| RebalanceSwap {
+                    wallet: holding.wallet,
+                    symbol: holding.symbol.clone(),
+                    token: holding.token,
+                    amount,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_over_exposed_assets_are_rebalanced_above_working_capital() {
+        let wallet_a = Address::repeat_byte(0x01);
+        let wallet_b = Address::repeat_byte(0x02);
+        let usdc = Address::repeat_byte(0x10);
+        let weth = Address::repeat_byte(0x20);
+        let cbeth = Address::repeat_byte(0x30);
+        let holding = |wallet, symbol: &str, token, balance: u64, value: u64| WalletHolding {
+            wallet,
+            symbol: symbol.to_string(),
+            token,
+            balance: U256::from(balance),
+            value_base: U256::from(value),
+        };
+        // Values: USDC 6000, WETH 3000, CBETH 1000
+        let holdings = vec![
+            holding(wallet_a, "USDC", usdc, 6_000, 6_000),
+            holding(wallet_a, "WETH", weth, 20, 2_000),
+            holding(wallet_b, "WETH", weth, 10, 1_000),
+            holding(wallet_b, "CBETH", cbeth, 5, 1_000),
+        ];
+
+        let report = InventoryReport::new("USDC", &holdings, 0);
+        assert_eq!(report.total_value_base, U256::from(10_000u64));
+        assert_eq!(report.off_target_bps, 4_000);
+        assert_eq!(report.assets[0].symbol, "USDC");
+        assert_eq!(report.assets[1].balance, U256::from(30u64));
+        assert_eq!(report.assets[1].share_bps, 3_000);
+
+        let tracker = InventoryTracker::new(
+            "usdc".to_string(),
+            2_000,
+            BTreeMap::from([("WETH".to_string(), U256::from(15u64))]),
+            crate::networks::base(),
+        );
+        // Only WETH is over 20%; wallet B's 10 WETH is all working capital
+        assert_eq!(
+            tracker.plan_rebalance(&report, &holdings),
+            vec![RebalanceSwap {
+                wallet: wallet_a,
+                symbol: "WETH".to_string(),
+                token: weth,
+                amount: U256::from(5u64),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_holding_value_uses_token_decimals() {
+        // 2.5 WETH at $3,000.00000000
+        let value = holding_value(
+            U256::from(2_500_000_000_000_000_000u128),
+            U256::from(300_000_000_000u64),
+            18,
+        );
+        assert_eq!(value, U256::from(750_000_000_000u64));
+    }
+}
@@ -1,10 +1,34 @@
+pub mod accounting;
+pub mod admin;
 pub mod bot;
 pub mod config;
+pub mod dashboard;
 pub mod database;
+pub mod event_bus;
 pub mod events;
+pub mod feed;
+pub mod gas;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod inventory;
 pub mod liquidation;
+pub mod logging;
+pub mod metrics;
+#[cfg(test)]
+mod mock_server;
 pub mod models;
+pub mod notifications;
 pub mod monitoring;
+pub mod networks;
+pub mod protocols;
+pub mod providers;
+pub mod replay;
+pub mod shutdown;
+pub mod signer;
+pub mod sim;
+pub mod treasury;
+pub mod web_dashboard;
+pub mod webhook_sink;
 pub mod circuit_breaker;
 
 pub use bot::LiquidationBot;
@@ -0,0 +1,131 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use eyre::Result;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::flash_loan::IAaveProtocolDataProvider;
+use super::profit_recheck::fetch_token_balance;
+use super::tx_manager::TxManager;
+use crate::models::ATokenLot;
+use crate::protocols::call_contract;
+use crate::signer::BotSigner;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IAavePoolWithdraw {
+        function withdraw(address asset, uint256 amount, address to) external returns (uint256);
+    }
+}
+
+/// How long to wait for a withdrawal's receipt
+const WITHDRAW_RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A lot withdrawn from the pool back into its underlying
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unwind {
+    pub amount: U256,
+    pub tx_hash: String,
+}
+
+/// Underlying to withdraw for a lot: the lot's amount, capped at the aTokens the wallet still
+/// holds (a lot spent elsewhere, e.g. by a manual withdrawal, can't be withdrawn twice)
+pub fn unwind_amount(lot_amount: U256, a_token_balance: U256) -> Option<U256> {
+    let amount = lot_amount.min(a_token_balance);
+    (!amount.is_zero()).then_some(amount)
+}
+
+/// Withdraws collateral that liquidations received as aTokens back into the underlying asset,
+/// once the lot has been held long enough. Interest accrued meanwhile stays as aTokens.
+pub struct ATokenUnwinder {
+    pool: Address,
+    data_provider: Address,
+    broadcast: bool, // BotConfig::broadcasts_transactions
+}
+
+impl ATokenUnwinder {
+    pub fn new(pool: Address, data_provider: Address, broadcast: bool) -> Self {
+        Self {
+            pool,
+            data_provider,
+            broadcast,
+        }
+    }
+
+    /// Withdraw `lot` to its wallet with a nonce from `tx_manager` (mocked unless
+    /// broadcasting). None when the wallet no longer holds any of the lot's aTokens, so the lot
+    /// can be closed as is.
+    pub async fn unwind<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+        lot: &ATokenLot,
+    ) -> Result<Option<Unwind>>
+    where
+        P: Provider,
+    {
+        let a_token = call_contract(
+            provider,
+            self.data_provider,
+            &IAaveProtocolDataProvider::getReserveTokensAddressesCall { asset: lot.asset },
+        )
+        .await?
+        .aTokenAddress;
+        let balance = fetch_token_balance(provider, a_token, lot.wallet).await?;
+        let Some(amount) = unwind_amount(lot.amount, balance) else {
+            warn!(
+                "Wallet {} no longer holds the aTokens of lot {} ({} of {})",
+                lot.wallet, lot.id, lot.amount, lot.asset
+            );
+            return Ok(None);
+        };
+
+        let tx_hash = if self.broadcast {
+            let withdraw = IAavePoolWithdraw::withdrawCall {
+                asset: lot.asset,
+                amount,
+                to: signer.address(),
+            };
+            let tx_req = TransactionRequest::default()
+                .from(signer.address())
+                .to(self.pool)
+                .input(withdraw.abi_encode().into());
+            let tx_hash = tx_manager
+                .send_and_confirm(provider, signer, tx_req, WITHDRAW_RECEIPT_TIMEOUT)
+                .await?;
+            format!("0x{:x}", tx_hash)
+        } else {
+            warn!(
+                "🎭 MOCK withdrawal of {} {} aTokens for {} - no on-chain execution",
+                amount, lot.asset, lot.wallet
+            );
+            "mock".to_string()
+        };
+
+        info!(
+            "🪙 Unwound {} of {} held since liquidation {}: {}",
+            amount, lot.asset, lot.tx_hash, tx_hash
+        );
+        Ok(Some(Unwind { amount, tx_hash }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unwind_is_capped_at_the_held_a_tokens() {
+        let lot = U256::from(1_000u64);
+
+        assert_eq!(unwind_amount(lot, U256::from(1_050u64)), Some(lot));
+        assert_eq!(
+            unwind_amount(lot, U256::from(400u64)),
+            Some(U256::from(400u64))
+        );
+        assert_eq!(unwind_amount(lot, U256::ZERO), None);
+    }
+}
@@ -0,0 +1,375 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::Provider;
+use eyre::Result;
+use parking_lot::Mutex;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+use super::swap::{min_amount_out, uniswap_plan, SwapPlan};
+use crate::models::{AggregatorKind, SwapRoute};
+use crate::networks::NetworkAddresses;
+
+/// Back off this long after a 429 that carries no Retry-After header
+const DEFAULT_RATE_LIMIT_BACKOFF_SECS: u64 = 10;
+
+/// One quote request; swaps of the same pair and amount share a cached plan
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct QuoteKey {
+    sell_token: Address,
+    buy_token: Address,
+    amount_in: U256,
+    taker: Address,
+}
+
+/// Client for the 1inch or 0x swap APIs building collateral -> debt swap calldata. Plans are
+/// cached briefly, 429s pause requests for the advertised Retry-After, and any failure falls
+/// back to a direct Uniswap V3 swap.
+pub struct AggregatorClient {
+    kind: AggregatorKind,
+    chain_id: u64,
+    api_url: String,
+    api_key: Option<String>,
+    slippage_bps: u64,
+    cache_ttl: Duration,
+    client: reqwest::Client,
+    cache: Mutex<HashMap<QuoteKey, (Instant, SwapPlan)>>,
+    rate_limited_until: Mutex<Option<Instant>>,
+}
+
+impl AggregatorClient {
+    pub fn new(
+        kind: AggregatorKind,
+        chain_id: u64,
+        api_url: Option<String>,
+        api_key: Option<String>,
+        slippage_bps: u64,
+        cache_ttl_secs: u64,
+    ) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(5))
+            .build()?;
+        Ok(Self {
+            kind,
+            chain_id,
+            api_url: api_url
+                .unwrap_or_else(|| kind.default_api_url().to_string())
+                .trim_end_matches('/')
+                .to_string(),
+            api_key,
+            slippage_bps,
+            cache_ttl: Duration::from_secs(cache_ttl_secs),
+            client,
+            cache: Mutex::new(HashMap::new()),
+            rate_limited_until: Mutex::new(None),
+        })
+    }
+
+    pub fn kind(&self) -> AggregatorKind {
+        self.kind
+    }
+
+    pub fn slippage_bps(&self) -> u64 {
+        self.slippage_bps
+    }
+
+    /// Whether requests are paused after a 429
+    pub fn is_rate_limited(&self) -> bool {
+        self.rate_limited_until
+            .lock()
+            .is_some_and(|until| Instant::now() < until)
+    }
+
+    fn cached(&self, key: &QuoteKey) -> Option<SwapPlan> {
+        let mut cache = self.cache.lock();
+        cache.retain(|_, (fetched_at, _)| fetched_at.elapsed() < self.cache_ttl);
+        cache.get(key).map(|(_, plan)| plan.clone())
+    }
+
+    /// Swap plan from the aggregator API (cached for the configured TTL)
+    pub async fn aggregator_plan(
+        &self,
+        sell_token: Address,
+        buy_token: Address,
+        amount_in: U256,
+        taker: Address,
+    ) -> Result<SwapPlan> {
+        let key = QuoteKey {
+            sell_token,
+            buy_token,
+            amount_in,
+            taker,
+        };
+        if let Some(plan) = self.cached(&key) {
+            debug!("Using cached {} swap plan for {} -> {}", plan.source, sell_token, buy_token);
+            return Ok(plan);
+        }
+        if self.is_rate_limited() {
+            return Err(eyre::eyre!("{} API rate limited", self.kind.as_str()));
+        }
+
+        let mut request = self.client.get(self.quote_url(&key));
+        request = match self.kind {
+            AggregatorKind::OneInch => match &self.api_key {
+                Some(key) => request.bearer_auth(key),
+                None => request,
+            },
+            AggregatorKind::ZeroX => {
+                let request = request.header("0x-version", "v2");
+                match &self.api_key {
+                    Some(key) => request.header("0x-api-key", key),
+                    None => request,
+                }
+            }
+        };
+
+        let response = request.send().await?;
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.trim().parse::<u64>().ok())
+                .unwrap_or(DEFAULT_RATE_LIMIT_BACKOFF_SECS);
+            *self.rate_limited_until.lock() = Some(Instant::now() + Duration::from_secs(retry_after));
+            return Err(eyre::eyre!(
+                "{} API rate limited for {}s",
+                self.kind.as_str(),
+                retry_after
+            ));
+        }
+        if !response.status().is_success() {
+            return Err(eyre::eyre!(
+                "{} API returned status {}",
+                self.kind.as_str(),
+                response.status()
+            ));
+        }
+
+        let body: Value = response.json().await?;
+        let plan = match self.kind {
+            AggregatorKind::OneInch => parse_one_inch_swap(&body, amount_in, self.slippage_bps)?,
+            AggregatorKind::ZeroX => parse_zero_x_quote(&body, amount_in)?,
+        };
+        self.cache.lock().insert(key, (Instant::now(), plan.clone()));
+        Ok(plan)
+    }
+
+    fn quote_url(&self, key: &QuoteKey) -> String {
+        match self.kind {
+            AggregatorKind::OneInch => format!(
+                "{}/swap/v6.0/{}/swap?src={}&dst={}&amount={}&from={}&origin={}&slippage={}&disableEstimate=true",
+                self.api_url,
+                self.chain_id,
+                key.sell_token,
+                key.buy_token,
+                key.amount_in,
+                key.taker,
+                key.taker,
+                self.slippage_bps as f64 / 100.0
+            ),
+            AggregatorKind::ZeroX => format!(
+                "{}/swap/allowance-holder/quote?chainId={}&sellToken={}&buyToken={}&sellAmount={}&taker={}&slippageBps={}",
+                self.api_url,
+                self.chain_id,
+                key.sell_token,
+                key.buy_token,
+                key.amount_in,
+                key.taker,
+                self.slippage_bps
+            ),
+        }
+    }
+
+    /// Best available plan from `route`'s input to its output token: the aggregator's, or a
+    /// direct Uniswap V3 swap along `route` when the API is unavailable
+    pub async fn best_plan<P>(
+        &self,
+        provider: &P,
+        network: &NetworkAddresses,
+        route: &SwapRoute,
+        amount_in: U256,
+        taker: Address,
+    ) -> Result<SwapPlan>
+    where
+        P: Provider,
+    {
+        match self
+            .aggregator_plan(route.token_in(), route.token_out(), amount_in, taker)
+            .await
+        {
+            Ok(plan) => Ok(plan),
+            Err(e) => {
+                warn!(
+                    "{} swap plan unavailable, falling back to Uniswap V3: {}",
+                    self.kind.as_str(),
+                    e
+                );
+                uniswap_plan(provider, network, route, amount_in, taker, self.slippage_bps).await
+            }
+        }
+    }
+}
+
+fn json_u256(value: &Value, field: &str) -> Result<U256> {
+    value
+        .as_str()
+        .and_then(|amount| amount.parse::<U256>().ok())
+        .ok_or_else(|| eyre::eyre!("Missing or invalid {} in aggregator response", field))
+}
+
+fn json_address(value: &Value, field: &str) -> Result<Address> {
+    value
+        .as_str()
+        .and_then(|address| address.parse::<Address>().ok())
+        .ok_or_else(|| eyre::eyre!("Missing or invalid {} in aggregator response", field))
+}
+
+fn json_bytes(value: &Value, field: &str) -> Result<Bytes> {
+    value
+        .as_str()
+        .and_then(|data| data.parse::<Bytes>().ok())
+        .ok_or_else(|| eyre::eyre!("Missing or invalid {} in aggregator response", field))
+}
+
+/// Plan from a 1inch v6 `/swap` response. The router is both the target and the spender.
+/// The response carries no minimum output, so it is derived from the quoted amount and the
+/// same `slippage_bps` the request asked 1inch to enforce.
+pub fn parse_one_inch_swap(body: &Value, amount_in: U256, slippage_bps: u64) -> Result<SwapPlan> {
+    let target = json_address(&body["tx"]["to"], "tx.to")?;
+    let amount_out = json_u256(&body["dstAmount"], "dstAmount")?;
+    Ok(SwapPlan {
+        source: "1inch",
+        target,
+        spender: target,
+        calldata: json_bytes(&body["tx"]["data"], "tx.data")?,
+        amount_in,
+        amount_out,
+        min_amount_out: min_amount_out(amount_out, slippage_bps),
+    })
+}
+
+/// Plan from a 0x v2 allowance-holder `/quote` response
+pub fn parse_zero_x_quote(body: &Value, amount_in: U256) -> Result<SwapPlan> {
+    let target = json_address(&body["transaction"]["to"], "transaction.to")?;
+    let spender = match body["issues"]["allowance"]["spender"].as_str() {
+        Some(spender) => spender
+            .parse::<Address>()
+            .map_err(|e| eyre::eyre!("Invalid allowance spender in 0x response: {}", e))?,
+        None => target,
+    };
+    Ok(SwapPlan {
+        source: "0x",
+        target,
+        spender,
+        calldata: json_bytes(&body["transaction"]["data"], "transaction.data")?,
+        amount_in,
+        amount_out: json_u256(&body["buyAmount"], "buyAmount")?,
+        min_amount_out: json_u256(&body["minBuyAmount"], "minBuyAmount")?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+
+    fn zero_x_body() -> Value {
+        serde_json::json!({
+            "buyAmount": "1000",
+            "minBuyAmount": "995",
+            "transaction": {"to": format!("{}", Address::repeat_byte(0xaa)), "data": "0x12345678"},
+            "issues": {"allowance": {"spender": format!("{}", Address::repeat_byte(0xbb))}},
+        })
+    }
+
+    #[test]
+    fn test_parse_aggregator_responses() {
+        let plan = parse_zero_x_quote(&zero_x_body(), U256::from(10u64)).unwrap();
+        assert_eq!(plan.target, Address::repeat_byte(0xaa));
+        assert_eq!(plan.spender, Address::repeat_byte(0xbb));
+        assert_eq!(plan.min_amount_out, U256::from(995u64));
+        assert_eq!(plan.calldata, Bytes::from(vec![0x12, 0x34, 0x56, 0x78]));
+
+        let one_inch = serde_json::json!({
+            "dstAmount": "2000",
+            "tx": {"to": format!("{}", Address::repeat_byte(0xcc)), "data": "0xabcd"},
+        });
+        let plan = parse_one_inch_swap(&one_inch, U256::from(10u64), 50).unwrap();
+        assert_eq!(plan.source, "1inch");
+        assert_eq!(plan.spender, Address::repeat_byte(0xcc));
+        assert_eq!(plan.amount_out, U256::from(2000u64));
+        // 0.5% below the quote rather than the quote itself
+        assert_eq!(plan.min_amount_out, U256::from(1990u64));
+
+        assert!(parse_one_inch_swap(&serde_json::json!({}), U256::ZERO, 50).is_err());
+    }
+
+    /// 0x-style quote API; answers 429 with Retry-After when `rate_limited`
+    async fn spawn_api(rate_limited: bool) -> MockServer {
+        MockServer::spawn(move |request| {
+            if request.method != "GET" || !request.path.starts_with("/swap/allowance-holder/quote")
+            {
+                return MockResponse::status("404 Not Found");
+            }
+            if rate_limited {
+                return MockResponse::json(&Value::Null)
+                    .with_status("429 Too Many Requests")
+                    .with_header("Retry-After", "60");
+            }
+            MockResponse::json(&zero_x_body())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_plans_are_cached() {
+        let server = spawn_api(false).await;
+        let client = AggregatorClient::new(
+            AggregatorKind::ZeroX,
+            8453,
+            Some(server.url.clone()),
+            None,
+            50,
+            30,
+        )
+        .unwrap();
+        let (sell, buy, taker) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+
+        let first = client.aggregator_plan(sell, buy, U256::from(10u64), taker).await.unwrap();
+        let second = client.aggregator_plan(sell, buy, U256::from(10u64), taker).await.unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(server.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_pauses_requests() {
+        let server = spawn_api(true).await;
+        let client = AggregatorClient::new(
+            AggregatorKind::ZeroX,
+            8453,
+            Some(server.url.clone()),
+            None,
+            50,
+            30,
+        )
+        .unwrap();
+        let (sell, buy, taker) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+
+        assert!(client.aggregator_plan(sell, buy, U256::from(10u64), taker).await.is_err());
+        assert!(client.is_rate_limited());
+        // The second attempt doesn't reach the API while the back-off lasts
+        assert!(client.aggregator_plan(sell, buy, U256::from(10u64), taker).await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+}
@@ -0,0 +1,224 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use dashmap::DashMap;
+use eyre::Result;
+use std::time::Duration;
+use tracing::{debug, info};
+
+use super::tx_manager::TxManager;
+use crate::models::ApprovalMode;
+use crate::protocols::call_contract;
+use crate::signer::BotSigner;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IERC20Allowance {
+        function allowance(address owner, address spender) external view returns (uint256);
+        function approve(address spender, uint256 amount) external returns (bool);
+    }
+}
+
+/// (owner, token, spender)
+type AllowanceKey = (Address, Address, Address);
+
+/// How long to wait for an approval to be mined
+const APPROVAL_RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Sets the ERC-20 allowances executor wallets need before a router or aggregator can pull
+/// their tokens. Known allowances are cached, so a wallet that already approved enough isn't
+/// asked again on every swap; approvals are only sent when the allowance falls short.
+#[derive(Debug)]
+pub struct ApprovalManager {
+    mode: ApprovalMode,
+    allowances: DashMap<AllowanceKey, U256>,
+}
+
+impl ApprovalManager {
+    pub fn new(mode: ApprovalMode) -> Self {
+        Self {
+            mode,
+            allowances: DashMap::new(),
+        }
+    }
+
+    pub fn mode(&self) -> ApprovalMode {
+        self.mode
+    }
+
+    /// Allowance to grant so `spender` can move `amount`
+    pub fn approval_amount(&self, amount: U256) -> U256 {
+        match self.mode {
+            ApprovalMode::Exact => amount,
+            ApprovalMode::Infinite => U256::MAX,
+        }
+    }
+
+    /// Current allowance, from the cache when known and from the token contract otherwise
+    pub async fn allowance<P>(
+        &self,
+        provider: &P,
+        owner: Address,
+        token: Address,
+        spender: Address,
+    ) -> Result<U256>
+    where
+        P: Provider,
+    {
+        if let Some(allowance) = self.allowances.get(&(owner, token, spender)) {
+            return Ok(*allowance);
+        }
+        let allowance = call_contract(
+            provider,
+            token,
+            &IERC20Allowance::allowanceCall { owner, spender },
+        )
+        .await?
+        ._0;
+        self.allowances.insert((owner, token, spender), allowance);
+        Ok(allowance)
+    }
+
+    /// Make sure `spender` may move `amount` of `signer`'s `token`, approving with a nonce from
+    /// `tx_manager` when it can't. Returns whether an approval was sent.
+    pub async fn ensure_allowance<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+        token: Address,
+        spender: Address,
+        amount: U256,
+    ) -> Result<bool>
+    where
+        P: Provider,
+    {
+        let owner = signer.address();
+        if self.allowance(provider, owner, token, spender).await? >= amount {
+            return Ok(false);
+        }
+        // The cache can be behind the chain, so re-read it before paying for an approval
+        self.invalidate(owner, token, spender);
+        if self.allowance(provider, owner, token, spender).await? >= amount {
+            return Ok(false);
+        }
+
+        let approved = self.approval_amount(amount);
+        info!(
+            "🔓 Approving {} to spend {} of {} for {} ({})",
+            spender,
+            approved,
+            token,
+            owner,
+            self.mode.as_str()
+        );
+        let approve = IERC20Allowance::approveCall {
+            spender,
+            amount: approved,
+        };
+        let tx_req = TransactionRequest::default()
+            .from(owner)
+            .to(token)
+            .input(approve.abi_encode().into());
+        if let Err(e) = tx_manager
+            .send_and_confirm(provider, signer, tx_req, APPROVAL_RECEIPT_TIMEOUT)
+            .await
+        {
+            self.invalidate(owner, token, spender);
+            return Err(e);
+        }
+        self.allowances.insert((owner, token, spender), approved);
+        Ok(true)
+    }
+
+    /// Deduct what a confirmed swap pulled from the cached allowance
+    pub fn record_spent(&self, owner: Address, token: Address, spender: Address, amount: U256) {
+        if let Some(mut allowance) = self.allowances.get_mut(&(owner, token, spender)) {
+            // Most tokens leave a maximum allowance untouched
+            if *allowance != U256::MAX {
+                *allowance = allowance.saturating_sub(amount);
+            }
+        }
+    }
+
+    /// Forget a cached allowance, e.g. after a swap failed and the on-chain state is unknown
+    pub fn invalidate(&self, owner: Address, token: Address, spender: Address) {
+        if self.allowances.remove(&(owner, token, spender)).is_some() {
+            debug!(
+                "Dropped cached allowance of {} for {} on {}",
+                spender, owner, token
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquidation::tx_manager::EscalationPolicy;
+    use alloy_provider::ProviderBuilder;
+    use alloy_signer_local::PrivateKeySigner;
+
+    #[tokio::test]
+    async fn test_cached_allowances_skip_the_rpc() {
+        let signer = BotSigner::from(PrivateKeySigner::random());
+        let owner = signer.address();
+        let tx_manager = TxManager::new(
+            owner,
+            EscalationPolicy {
+                stuck_after: Duration::from_secs(30),
+                bump_pct: 10,
+                max_bumps: 0,
+            },
+        );
+        let token = Address::repeat_byte(0x02);
+        let spender = Address::repeat_byte(0x03);
+        // Nothing listens here, so any RPC call fails
+        let provider = ProviderBuilder::new().on_http("http://127.0.0.1:1".parse().unwrap());
+
+        let approvals = ApprovalManager::new(ApprovalMode::Exact);
+        approvals
+            .allowances
+            .insert((owner, token, spender), U256::from(1_000u64));
+        assert!(!approvals
+            .ensure_allowance(
+                &provider,
+                &signer,
+                &tx_manager,
+                token,
+                spender,
+                U256::from(600u64)
+            )
+            .await
+            .unwrap());
+
+        // Only 400 is left after the swap, so a second one needs the chain again
+        approvals.record_spent(owner, token, spender, U256::from(600u64));
+        assert_eq!(
+            approvals
+                .allowance(&provider, owner, token, spender)
+                .await
+                .unwrap(),
+            U256::from(400u64)
+        );
+        assert!(approvals
+            .ensure_allowance(
+                &provider,
+                &signer,
+                &tx_manager,
+                token,
+                spender,
+                U256::from(600u64)
+            )
+            .await
+            .is_err());
+
+        let infinite = ApprovalManager::new(ApprovalMode::Infinite);
+        assert_eq!(infinite.approval_amount(U256::from(5u64)), U256::MAX);
+        assert_eq!(
+            approvals.approval_amount(U256::from(5u64)),
+            U256::from(5u64)
+        );
+    }
+}
@@ -1,4 +1,5 @@
 use crate::models::LiquidationAssetConfig;
+use crate::networks::NetworkAddresses;
 use alloy_primitives::Address;
 use alloy_sol_types::{sol, SolCall};
 use alloy_rpc_types::TransactionRequest;
@@ -8,11 +9,11 @@ use std::path::Path;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn, error};
 
-// Aave UiPoolDataProvider interface for fetching reserve list
+// Aave Pool interface for fetching the ordered reserve list
 sol! {
     #[allow(missing_docs)]
-    interface IUiPoolDataProvider {
-        function getReservesList(address provider) external view returns (address[] memory);
+    interface IPoolReservesList {
+        function getReservesList() external view returns (address[] memory);
     }
 }
 
@@ -56,40 +57,30 @@ pub struct AssetConfigFile {
     pub assets: Vec<ExternalAssetConfig>,
 }
 
-// Base mainnet Aave contract addresses
-pub const BASE_POOL_ADDRESSES_PROVIDER: &str = "0xe20fCBdBfFC4Dd138cE8b2E6FBb6CB49777ad64D";
-pub const BASE_UI_POOL_DATA_PROVIDER: &str = "0x68100bD5345eA474D93577127C11F39FF8463e93";
-// Legacy, only used for fetching token symbols
-pub const BASE_AAVE_PROTOCOL_DATA_PROVIDER: &str = "0xC4Fcf9893072d61Cc2899C0054877Cb752587981";
-
 /// Dynamically fetch reserve indices from Aave protocol
 pub async fn fetch_reserve_indices(
     provider: &impl alloy_provider::Provider,
+    network: &NetworkAddresses,
 ) -> Result<HashMap<Address, u16>> {
     info!("🔍 Fetching dynamic reserve indices from Aave protocol...");
     
-    let ui_pool_data_provider: Address = BASE_UI_POOL_DATA_PROVIDER.parse()?;
-    let pool_addresses_provider: Address = BASE_POOL_ADDRESSES_PROVIDER.parse()?;
-    
     // Call getReservesList to get the ordered list of reserves
-    let call = IUiPoolDataProvider::getReservesListCall {
-        provider: pool_addresses_provider,
-    };
+    let call = IPoolReservesList::getReservesListCall {};
     
     let call_data = call.abi_encode();
     let call_request = TransactionRequest::default()
-        .to(ui_pool_data_provider)
+        .to(network.pool)
         .input(call_data.into());
     
     let result = provider.call(&call_request).await
         .map_err(|e| eyre::eyre!("Failed to fetch reserves list: {}", e))?;
     
     // Decode the response
-    let reserves_list = IUiPoolDataProvider::getReservesListCall::abi_decode_returns(&result, true)
+    let reserves_list = IPoolReservesList::getReservesListCall::abi_decode_returns(&result, true)
         .map_err(|e| eyre::eyre!("Failed to decode reserves list: {}", e))?;
 
     // Fetch token symbols via AaveProtocolDataProvider
-    let protocol_data_provider: Address = BASE_AAVE_PROTOCOL_DATA_PROVIDER.parse()?;
+    let protocol_data_provider = network.data_provider;
     let symbol_call = IAaveProtocolDataProvider::getAllReservesTokensCall {};
     let symbol_data = provider.call(
         &TransactionRequest::default()
@@ -123,9 +114,10 @@ pub async fn fetch_reserve_indices(
 /// Fetch asset configuration data (decimals, liquidation bonus, etc.) from Aave protocol
 pub async fn fetch_asset_config_data(
     provider: &impl alloy_provider::Provider,
+    network: &NetworkAddresses,
     asset_address: Address,
 ) -> Result<(u8, u16)> {
-    let protocol_data_provider: Address = BASE_AAVE_PROTOCOL_DATA_PROVIDER.parse()?;
+    let protocol_data_provider = network.data_provider;
     
     let call = IAaveProtocolDataProvider::getReserveConfigurationDataCall {
         asset: asset_address,
@@ -178,14 +170,15 @@ pub fn load_asset_configs_from_file(file_path: impl AsRef<Path>) -> Result<Vec<E
 /// Initialize asset configurations with full dynamic loading from Aave protocol
 pub async fn init_assets_from_protocol(
     provider: &impl alloy_provider::Provider,
+    network: &NetworkAddresses,
 ) -> Result<HashMap<Address, LiquidationAssetConfig>> {
     info!("🔄 Initializing asset configurations dynamically from Aave protocol...");
     
-    let reserve_indices = fetch_reserve_indices(provider).await?;
+    let reserve_indices = fetch_reserve_indices(provider, network).await?;
     let mut assets = HashMap::new();
 
     // Fetch token symbols
-    let protocol_data_provider: Address = BASE_AAVE_PROTOCOL_DATA_PROVIDER.parse()?;
+    let protocol_data_provider = network.data_provider;
     let symbol_call = IAaveProtocolDataProvider::getAllReservesTokensCall {};
     let symbol_data = provider.call(
         &TransactionRequest::default()
@@ -211,7 +204,7 @@ pub async fn init_assets_from_protocol(
         };
 
         // Fetch configuration data from protocol
-        match fetch_asset_config_data(provider, asset_address).await {
+        match fetch_asset_config_data(provider, network, asset_address).await {
             Ok((decimals, liquidation_bonus)) => {
                 // Get reserve configuration to check if asset can be used as collateral/borrowable
                 let call = IAaveProtocolDataProvider::getReserveConfigurationDataCall {
@@ -266,12 +259,13 @@ pub async fn init_assets_from_protocol(
 /// Initialize asset configurations from external config file
 pub async fn init_assets_from_file(
     provider: &impl alloy_provider::Provider,
+    network: &NetworkAddresses,
     file_path: impl AsRef<Path>,
 ) -> Result<HashMap<Address, LiquidationAssetConfig>> {
     info!("📁 Initializing asset configurations from file...");
     
     let external_configs = load_asset_configs_from_file(file_path)?;
-    let reserve_indices = fetch_reserve_indices(provider).await?;
+    let reserve_indices = fetch_reserve_indices(provider, network).await?;
     let mut assets = HashMap::new();
 
     for external_config in external_configs {
@@ -309,11 +303,41 @@ pub async fn init_assets_from_file(
 }
 
 
+/// Chain the hardcoded asset list describes
+pub const BASE_CHAIN_ID: u64 = 8453;
+
+/// Hardcoded assets of `chain_id`. Only Base mainnet has a built-in list; other chains must
+/// load theirs from the protocol or a config file, never Base's.
+pub fn hardcoded_assets(chain_id: u64) -> Result<HashMap<Address, LiquidationAssetConfig>> {
+    if chain_id != BASE_CHAIN_ID {
+        return Err(eyre::eyre!(
+            "No hardcoded asset configurations for chain {} (only Base mainnet has them) - use ASSET_LOADING_METHOD=fully_dynamic or file:<path>",
+            chain_id
+        ));
+    }
+    Ok(init_base_mainnet_assets())
+}
+
+/// Load the assets of `chain_id` dynamically. Base mainnet reads its known assets' indices and
+/// configuration from the protocol; other chains load every reserve.
+pub async fn init_assets_dynamic(
+    provider: &impl alloy_provider::Provider,
+    network: &NetworkAddresses,
+    chain_id: u64,
+) -> Result<HashMap<Address, LiquidationAssetConfig>> {
+    if chain_id == BASE_CHAIN_ID {
+        init_base_mainnet_assets_async(provider, network).await
+    } else {
+        init_assets_from_protocol(provider, network).await
+    }
+}
+
 /// Initialize asset configurations for Base mainnet with dynamic reserve indices
 pub async fn init_base_mainnet_assets_async(
     provider: &impl alloy_provider::Provider,
+    network: &NetworkAddresses,
 ) -> Result<HashMap<Address, LiquidationAssetConfig>> {
-    let reserve_indices = fetch_reserve_indices(provider).await?;
+    let reserve_indices = fetch_reserve_indices(provider, network).await?;
     let mut assets = HashMap::new();
 
     // WETH (Wrapped Ether) - Base mainnet
@@ -322,7 +346,7 @@ pub async fn init_base_mainnet_assets_async(
         .ok_or_else(|| eyre::eyre!("WETH not found in Aave reserves list"))?;
     
     // Fetch dynamic configuration data for WETH
-    let (weth_decimals, weth_liquidation_bonus) = fetch_asset_config_data(provider, weth_address).await
+    let (weth_decimals, weth_liquidation_bonus) = fetch_asset_config_data(provider, network, weth_address).await
         .unwrap_or_else(|e| {
             warn!("Failed to fetch WETH config data dynamically, using fallback: {}", e);
             (18, 500) // Fallback values
@@ -345,7 +369,7 @@ pub async fn init_base_mainnet_assets_async(
         .ok_or_else(|| eyre::eyre!("USDC not found in Aave reserves list"))?;
     
     // Fetch dynamic configuration data for USDC
-    let (usdc_decimals, usdc_liquidation_bonus) = fetch_asset_config_data(provider, usdc_address).await
+    let (usdc_decimals, usdc_liquidation_bonus) = fetch_asset_config_data(provider, network, usdc_address).await
         .unwrap_or_else(|e| {
             warn!("Failed to fetch USDC config data dynamically, using fallback: {}", e);
             (6, 450) // Fallback values
@@ -368,7 +392,7 @@ pub async fn init_base_mainnet_assets_async(
         .ok_or_else(|| eyre::eyre!("cbETH not found in Aave reserves list"))?;
     
     // Fetch dynamic configuration data for cbETH
-    let (cbeth_decimals, cbeth_liquidation_bonus) = fetch_asset_config_data(provider, cbeth_address).await
+    let (cbeth_decimals, cbeth_liquidation_bonus) = fetch_asset_config_data(provider, network, cbeth_address).await
         .unwrap_or_else(|e| {
             warn!("Failed to fetch cbETH config data dynamically, using fallback: {}", e);
             (18, 700) // Fallback values
@@ -585,12 +609,12 @@ fn calculate_liquidation_pair_score(
 }
 
 /// Helper function to identify stablecoin assets
-fn is_stablecoin(symbol: &str) -> bool {
+pub(crate) fn is_stablecoin(symbol: &str) -> bool {
     matches!(symbol, "USDC" | "USDT" | "DAI" | "BUSD" | "FRAX")
 }
 
 /// Helper function to identify major collateral assets
-fn is_major_collateral(symbol: &str) -> bool {
+pub(crate) fn is_major_collateral(symbol: &str) -> bool {
     matches!(symbol, "ETH" | "WETH" | "cbETH" | "stETH" | "rETH")
 }
 
@@ -654,6 +678,15 @@ mod tests {
         assets
     }
 
+    #[test]
+    fn test_hardcoded_assets_are_base_only() {
+        let assets = hardcoded_assets(BASE_CHAIN_ID).unwrap();
+        assert!(assets.values().any(|asset| asset.symbol == "WETH"));
+
+        let err = hardcoded_assets(42161).unwrap_err();
+        assert!(err.to_string().contains("chain 42161"));
+    }
+
     #[test]
     fn test_dynamic_best_pair_selection() {
         let assets = create_test_assets();
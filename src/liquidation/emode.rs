@@ -0,0 +1,212 @@
+use alloy_primitives::Address;
+use alloy_provider::Provider;
+use alloy_sol_types::sol;
+use dashmap::DashMap;
+use eyre::Result;
+use std::collections::HashMap;
+
+use super::reserve_config::ReserveConfigCache;
+use crate::models::LiquidationAssetConfig;
+use crate::protocols::call_contract;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IPoolEMode {
+        struct EModeCategoryData {
+            uint16 ltv;
+            uint16 liquidationThreshold;
+            uint16 liquidationBonus;
+            address priceSource;
+            string label;
+        }
+
+        function getUserEMode(address user) external view returns (uint256);
+        function getEModeCategoryData(uint8 id) external view returns (EModeCategoryData memory);
+    }
+}
+
+/// Liquidation bonuses are stored as 10000 plus the bonus (e.g. 10500 = 5%)
+const BONUS_BASE_BPS: u16 = 10_000;
+
+/// Parameters of an e-mode category. For a user in the category they replace the
+/// liquidation threshold and bonus of every reserve belonging to it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EModeCategory {
+    pub id: u8,
+    pub ltv_bps: u16,
+    pub liquidation_threshold_bps: u16,
+    /// Bonus on top of the repaid debt, in basis points (100 = 1%)
+    pub liquidation_bonus_bps: u16,
+    pub label: String,
+}
+
+/// A user's e-mode category (0 = not in e-mode)
+pub async fn fetch_user_emode<P>(provider: &P, pool: Address, user: Address) -> Result<u8>
+where
+    P: Provider,
+{
+    let category = call_contract(provider, pool, &IPoolEMode::getUserEModeCall { user })
+        .await?
+        ._0;
+    u8::try_from(category).map_err(|_| eyre::eyre!("Invalid e-mode category {}", category))
+}
+
+pub async fn fetch_emode_category<P>(provider: &P, pool: Address, id: u8) -> Result<EModeCategory>
+where
+    P: Provider,
+{
+    let data = call_contract(provider, pool, &IPoolEMode::getEModeCategoryDataCall { id })
+        .await?
+        ._0;
+    Ok(EModeCategory {
+        id,
+        ltv_bps: data.ltv,
+        liquidation_threshold_bps: data.liquidationThreshold,
+        liquidation_bonus_bps: data.liquidationBonus.saturating_sub(BONUS_BASE_BPS),
+        label: data.label,
+    })
+}
+
+/// E-mode category of each user seen at liquidation time, plus the parameters of every
+/// category those users are in
+#[derive(Debug, Default)]
+pub struct EModeTracker {
+    categories: DashMap<u8, EModeCategory>,
+    users: DashMap<Address, u8>,
+}
+
+impl EModeTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn user_category(&self, user: Address) -> u8 {
+        self.users.get(&user).map(|id| *id).unwrap_or(0)
+    }
+
+    pub fn category(&self, id: u8) -> Option<EModeCategory> {
+        self.categories.get(&id).map(|category| category.clone())
+    }
+
+    pub fn set_category(&self, category: EModeCategory) {
+        self.categories.insert(category.id, category);
+    }
+
+    pub fn set_user_category(&self, user: Address, id: u8) {
+        if id == 0 {
+            self.users.remove(&user);
+        } else {
+            self.users.insert(user, id);
+        }
+    }
+
+    /// Read a user's current category, fetching its parameters the first time it's seen
+    pub async fn refresh_user<P>(
+        &self,
+        provider: &P,
+        pool: Address,
+        user: Address,
+    ) -> Result<Option<EModeCategory>>
+    where
+        P: Provider,
+    {
+        let id = fetch_user_emode(provider, pool, user).await?;
+        self.set_user_category(user, id);
+        if id == 0 {
+            return Ok(None);
+        }
+        if let Some(category) = self.category(id) {
+            return Ok(Some(category));
+        }
+        let category = fetch_emode_category(provider, pool, id).await?;
+        self.set_category(category.clone());
+        Ok(Some(category))
+    }
+
+    /// Replace the liquidation bonus of every asset in the user's e-mode category with the
+    /// category's. Category membership comes from the cached reserve configurations, so
+    /// assets without one keep their own bonus.
+    pub fn apply(
+        &self,
+        user: Address,
+        assets: &mut HashMap<Address, LiquidationAssetConfig>,
+        reserve_configs: &ReserveConfigCache,
+    ) {
+        let Some(category) = self.category(self.user_category(user)) else {
+            return;
+        };
+        for (address, asset) in assets.iter_mut() {
+            let in_category = reserve_configs
+                .get(*address)
+                .is_some_and(|config| config.emode_category == category.id);
+            if in_category {
+                asset.liquidation_bonus = category.liquidation_bonus_bps;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::liquidation::reserve_config::ReserveConfig;
+
+    fn asset(address: Address, symbol: &str) -> LiquidationAssetConfig {
+        LiquidationAssetConfig {
+            address,
+            symbol: symbol.to_string(),
+            decimals: 18,
+            asset_id: 0,
+            liquidation_bonus: 500,
+            is_collateral: true,
+            is_borrowable: true,
+        }
+    }
+
+    #[test]
+    fn test_emode_bonus_applies_only_to_category_assets() {
+        let (weth, cbeth, usdc) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+        let reserve_configs = ReserveConfigCache::new();
+        let eth_correlated = ReserveConfig { emode_category: 1, ..ReserveConfig::default() };
+        reserve_configs.insert(weth, eth_correlated);
+        reserve_configs.insert(cbeth, eth_correlated);
+        reserve_configs.insert(usdc, ReserveConfig::default());
+
+        let tracker = EModeTracker::new();
+        tracker.set_category(EModeCategory {
+            id: 1,
+            ltv_bps: 9_000,
+            liquidation_threshold_bps: 9_300,
+            liquidation_bonus_bps: 100,
+            label: "ETH correlated".to_string(),
+        });
+        let assets: HashMap<Address, LiquidationAssetConfig> = [
+            (weth, asset(weth, "WETH")),
+            (cbeth, asset(cbeth, "cbETH")),
+            (usdc, asset(usdc, "USDC")),
+        ]
+        .into_iter()
+        .collect();
+
+        // Not in e-mode: reserve bonuses stand
+        let user = Address::repeat_byte(0xaa);
+        let mut applied = assets.clone();
+        tracker.apply(user, &mut applied, &reserve_configs);
+        assert_eq!(applied[&cbeth].liquidation_bonus, 500);
+
+        tracker.set_user_category(user, 1);
+        let mut applied = assets.clone();
+        tracker.apply(user, &mut applied, &reserve_configs);
+        assert_eq!(applied[&weth].liquidation_bonus, 100);
+        assert_eq!(applied[&cbeth].liquidation_bonus, 100);
+        assert_eq!(applied[&usdc].liquidation_bonus, 500);
+
+        // Leaving e-mode restores the reserve bonuses
+        tracker.set_user_category(user, 0);
+        assert_eq!(tracker.user_category(user), 0);
+    }
+}
@@ -1,38 +1,124 @@
 use alloy_contract::{ContractInstance, Interface};
-use alloy_network::EthereumWallet;
-use alloy_primitives::{Address, U256};
-use alloy_provider::{Provider, ProviderBuilder};
-use alloy_signer_local::PrivateKeySigner;
+use alloy_network::eip2718::Encodable2718;
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_primitives::{b256, Address, Bytes, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{BlockId, Log, TransactionRequest};
 use eyre::Result;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::Hasher;
 use std::sync::Arc;
 use tracing::{error, info, warn};
 
-use crate::models::{LiquidationAssetConfig, LiquidationOpportunity, LiquidationParams};
+use crate::database::{self, DatabasePool};
+use crate::gas::GasOracle;
+use crate::liquidation::aggregator::AggregatorClient;
+use crate::liquidation::fill;
+use crate::liquidation::flash_loan::base_to_token_amount;
+use crate::liquidation::funding::{FundsReservation, WalletFunding};
+use crate::liquidation::profit_recheck::{self, ProfitRecheckMonitor};
+use crate::liquidation::racing::{self, RaceBudget, RacingStrategy};
+use crate::liquidation::submission::PrivateRelaySubmitter;
+use crate::liquidation::swap::{self, CollateralSwapper};
+use crate::liquidation::tx_manager::TxManager;
+use crate::liquidation::weth::WethBalancer;
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::monitoring::latency::{self, LatencyStage};
+use crate::models::{
+    AggregatorSwap, FeeProfile, FlashLoanSource, LiquidationAssetConfig, LiquidationFill,
+    LiquidationIntent, LiquidationOpportunity, LiquidationParams, LiquidationReceipt,
+    LiquidationSettlement,
+};
+use crate::networks::NetworkAddresses;
+use crate::providers;
+use crate::sim::{SimulationOutcome, Simulator};
+use crate::signer::BotSigner;
+
+/// Gas limit used when `eth_estimateGas` is unavailable (and for simulated transactions)
+pub(crate) const LIQUIDATION_GAS_LIMIT: u64 = 500_000;
+
+/// Headroom added on top of `eth_estimateGas`, in percent
+const GAS_ESTIMATE_BUFFER_PCT: u64 = 20;
+
+/// Priority fee multiplier used when the gas oracle is unavailable
+const PRIORITY_FEE_MULTIPLIER: u128 = 2;
+
+/// Wei in one ETH, for pricing gas costs at the oracle's (W)ETH price
+const WEI_PER_ETH: U256 = U256::from_limbs([1_000_000_000_000_000_000, 0, 0, 0]);
+
+/// Details of a submitted liquidation transaction
+struct SubmittedLiquidation {
+    tx_hash: String,
+    nonce: u64,
+    /// Max fee per gas (EIP-1559) the transaction was sent with
+    gas_price: u128,
+    gas_limit: u64,
+    /// The transaction as sent, replayed with `eth_call` to recover a revert reason
+    /// (None for simulated transactions)
+    request: Option<TransactionRequest>,
+}
+
+/// A mined liquidation and what it actually filled. Reverted transactions are returned too,
+/// so their receipt is stored before the revert is reported as a failure.
+pub struct ExecutedLiquidation {
+    pub tx_hash: String,
+    /// Amounts decoded from the receipt's LiquidationCall log (None if it couldn't be found)
+    pub fill: Option<LiquidationFill>,
+    pub receipt: LiquidationReceipt,
+    /// Why the transaction reverted, when the receipt's status is failed
+    pub revert_reason: Option<String>,
+    /// The collateral was received as aTokens, to be unwound later
+    pub received_a_tokens: bool,
+}
 
 /// Liquidation executor that interfaces with the deployed smart contract
 pub struct LiquidationExecutor<P> {
     provider: Arc<P>,
-    signer: PrivateKeySigner,
+    signer: BotSigner,
     liquidator_contract: ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
     contract_address: Address,
     asset_configs: std::collections::HashMap<Address, LiquidationAssetConfig>,
     contract_interface: Interface,
-    rpc_url: String,
+    chain_id: u64,
+    audit_db: Option<DatabasePool>,
+    profit_recheck: Option<Arc<ProfitRecheckMonitor>>,
+    private_relay: Option<Arc<PrivateRelaySubmitter>>,
+    collateral_swapper: Option<Arc<CollateralSwapper>>,
+    weth_balancer: Option<Arc<WethBalancer>>,
+    simulator: Option<Arc<Simulator>>,
+    tx_manager: Option<Arc<TxManager>>,
+    /// Fee source and the profile this liquidation bids with
+    gas_oracle: Option<(Arc<GasOracle>, FeeProfile)>,
+    /// Profit-scaled priority fee bidding and what this liquidation can spend on it
+    racing: Option<(Arc<RacingStrategy>, RaceBudget)>,
+    /// Tip of a competing liquidation already pending for the same user
+    rival_priority_fee: Option<u128>,
+    /// Share of the estimated net profit, in basis points, the market may take before the
+    /// contract reverts, and the network whose oracle prices the floor (None = no on-chain
+    /// minimum profit)
+    min_profit_guard: Option<(u64, NetworkAddresses)>,
+    /// Aggregator quoting the contract's collateral -> debt swap, and the network whose oracle
+    /// sizes it (None = the contract swaps through Uniswap V3)
+    swap_aggregator: Option<(Arc<AggregatorClient>, NetworkAddresses)>,
+    /// Repay the debt from the signer's own balance through the pool instead of a flash loan
+    wallet_funding: Option<(Arc<WalletFunding>, FundsReservation)>,
+    /// Keep wallet-funded collateral as aTokens rather than the underlying
+    receive_a_token: bool,
+    /// Sign and send the liquidation (BotConfig::broadcasts_transactions); mocked otherwise
+    broadcast: bool,
 }
 
 impl<P> LiquidationExecutor<P>
 where
-    P: Provider,
+    P: Provider + 'static,
 {
     /// Create a new liquidation executor
     pub fn new(
         provider: Arc<P>,
-        signer: PrivateKeySigner,
+        signer: BotSigner,
         contract_address: Address,
         asset_configs: std::collections::HashMap<Address, LiquidationAssetConfig>,
-        rpc_url: String,
+        chain_id: u64,
     ) -> Result<Self> {
         // Load the ABI from deployment info or hardcoded
         let liquidator_abi = get_liquidator_abi()?;
@@ -48,194 +134,804 @@ where
             contract_address,
             asset_configs,
             contract_interface: interface,
-            rpc_url,
+            chain_id,
+            audit_db: None,
+            profit_recheck: None,
+            private_relay: None,
+            collateral_swapper: None,
+            weth_balancer: None,
+            simulator: None,
+            tx_manager: None,
+            gas_oracle: None,
+            racing: None,
+            rival_priority_fee: None,
+            min_profit_guard: None,
+            swap_aggregator: None,
+            wallet_funding: None,
+            receive_a_token: false,
+            broadcast: false,
         })
     }
 
-    /// Execute a liquidation transaction
-    pub async fn execute_liquidation(
+    /// Record two-phase (intent + settlement) audit entries for every liquidation
+    pub fn with_audit_log(mut self, db_pool: DatabasePool) -> Self {
+        self.audit_db = Some(db_pool);
+        self
+    }
+
+    /// Compare realized profit against the estimate once each liquidation confirms
+    pub fn with_profit_recheck(mut self, monitor: Arc<ProfitRecheckMonitor>) -> Self {
+        self.profit_recheck = Some(monitor);
+        self
+    }
+
+    /// Submit real transactions through a private relay instead of (or alongside) the public mempool
+    pub fn with_private_relay(mut self, relay: Arc<PrivateRelaySubmitter>) -> Self {
+        self.private_relay = Some(relay);
+        self
+    }
+
+    /// Convert collateral left with the signer after each liquidation along the configured routes
+    pub fn with_collateral_swap(mut self, swapper: Arc<CollateralSwapper>) -> Self {
+        self.collateral_swapper = Some(swapper);
+        self
+    }
+
+    /// Unwrap WETH for gas (or wrap surplus ETH) after each liquidation
+    pub fn with_weth_balancer(mut self, balancer: Arc<WethBalancer>) -> Self {
+        self.weth_balancer = Some(balancer);
+        self
+    }
+
+    /// Simulate every liquidation before it is broadcast
+    pub fn with_simulator(mut self, simulator: Arc<Simulator>) -> Self {
+        self.simulator = Some(simulator);
+        self
+    }
+
+    /// Allocate nonces from (and track submissions in) a manager shared across executors
+    pub fn with_tx_manager(mut self, tx_manager: Arc<TxManager>) -> Self {
+        self.tx_manager = Some(tx_manager);
+        self
+    }
+
+    /// Price transactions from fee history percentiles using `profile`
+    pub fn with_gas_oracle(mut self, gas_oracle: Arc<GasOracle>, profile: FeeProfile) -> Self {
+        self.gas_oracle = Some((gas_oracle, profile));
+        self
+    }
+
+    /// Raise the priority fee with the liquidation's expected profit and recent win rate
+    pub fn with_racing(mut self, racing: Arc<RacingStrategy>, budget: RaceBudget) -> Self {
+        self.racing = Some((racing, budget));
+        self
+    }
+
+    /// Outbid a competing liquidation seen pending with `priority_fee`, up to the racing cap
+    pub fn with_rival_priority_fee(mut self, priority_fee: u128) -> Self {
+        self.rival_priority_fee = Some(priority_fee);
+        self
+    }
+
+    /// Have the contract revert unless the liquidation still clears its minimum profit at
+    /// inclusion, allowing the market to take up to `margin_bps` of the estimated net profit.
+    /// The floor is converted into debt asset units at `network`'s oracle prices.
+    pub fn with_min_profit_guard(mut self, margin_bps: u64, network: NetworkAddresses) -> Self {
+        self.min_profit_guard = Some((margin_bps, network));
+        self
+    }
+
+    /// Have the contract swap flash-loan seized collateral with 1inch / 0x calldata quoted for
+    /// the expected seizure, priced at `network`'s oracle. Without a quote the contract falls
+    /// back to its own Uniswap V3 swap.
+    pub fn with_swap_aggregator(
+        mut self,
+        aggregator: Arc<AggregatorClient>,
+        network: NetworkAddresses,
+    ) -> Self {
+        self.swap_aggregator = Some((aggregator, network));
+        self
+    }
+
+    /// Liquidate through Pool.liquidationCall, repaying the reserved funds from the signer's
+    /// balance; the seized collateral stays with the signer for the collateral swap
+    pub fn with_wallet_funding(
+        mut self,
+        funding: Arc<WalletFunding>,
+        reservation: FundsReservation,
+    ) -> Self {
+        self.wallet_funding = Some((funding, reservation));
+        self
+    }
+
+    /// Take wallet-funded collateral as aTokens, skipping the swap and earning supply yield
+    /// until it is unwound. Flash loans must be repaid in the underlying, so liquidations
+    /// through the contract always receive it.
+    pub fn with_receive_a_token(mut self) -> Self {
+        self.receive_a_token = true;
+        self
+    }
+
+    /// Send liquidations on-chain instead of mocking them
+    pub fn with_broadcast(mut self) -> Self {
+        self.broadcast = true;
+        self
+    }
+
+    /// Simulate the liquidation transaction for `opportunity` without broadcasting it.
+    /// None when no simulator is configured; reverts are returned as errors.
+    pub async fn simulate_liquidation(
         &self,
         opportunity: &LiquidationOpportunity,
-    ) -> Result<String> {
+    ) -> Result<Option<SimulationOutcome>> {
+        let Some(simulator) = &self.simulator else {
+            return Ok(None);
+        };
+
+        let params = self.liquidation_params(opportunity).await?;
+        let (function, args) = liquidate_call_args(&params);
+        let mut tx_req = self
+            .liquidator_contract
+            .function(function, &args)?
+            .into_transaction_request();
+        tx_req.from = Some(self.signer.address());
+
         info!(
-            "🚀 Executing liquidation for user: {} (profit: {} wei)",
-            opportunity.user, opportunity.estimated_profit
+            "🧪 Simulating liquidation of {} via {} ({})",
+            opportunity.user,
+            function,
+            simulator.mode().as_str()
         );
+        simulator
+            .simulate(self.provider.as_ref(), &tx_req, opportunity)
+            .await
+            .map(Some)
+    }
 
+    async fn liquidation_params(
+        &self,
+        opportunity: &LiquidationOpportunity,
+    ) -> Result<LiquidationParams> {
         // Get asset IDs - in a real implementation, you'd look these up from your asset configs
         let collateral_asset_id = self.get_asset_id(opportunity.collateral_asset)?;
         let debt_asset_id = self.get_asset_id(opportunity.debt_asset)?;
 
-        let params = LiquidationParams {
+        Ok(LiquidationParams {
             user: opportunity.user,
             collateral_asset: opportunity.collateral_asset,
             debt_asset: opportunity.debt_asset,
             debt_to_cover: opportunity.debt_to_cover,
             collateral_asset_id,
             debt_asset_id,
-            receive_a_token: false, // Receive underlying assets, not aTokens
+            receive_a_token: self.receive_a_token && self.wallet_funding.is_some(),
+            flash_loan_source: opportunity.flash_loan_source,
+            min_profit: self.min_profit_floor(opportunity).await,
+            aggregator_swap: self.aggregator_swap(opportunity).await,
+        })
+    }
+
+    /// Aggregator swap for the collateral a flash-loan liquidation of `opportunity` seizes,
+    /// with the contract as taker. None without an aggregator, for wallet-funded or same-asset
+    /// liquidations, or when no quote is available.
+    async fn aggregator_swap(
+        &self,
+        opportunity: &LiquidationOpportunity,
+    ) -> Option<AggregatorSwap> {
+        let (aggregator, network) = self.swap_aggregator.as_ref()?;
+        if self.wallet_funding.is_some() || opportunity.collateral_asset == opportunity.debt_asset
+        {
+            return None;
+        }
+        let (Some(collateral_asset), Some(debt_asset)) = (
+            self.asset_configs.get(&opportunity.collateral_asset),
+            self.asset_configs.get(&opportunity.debt_asset),
+        ) else {
+            return None;
+        };
+        let prices = match fetch_oracle_prices(
+            self.provider.as_ref(),
+            network,
+            &[opportunity.collateral_asset, opportunity.debt_asset],
+        )
+        .await
+        {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!(
+                    "Failed to size the {} swap of {}, using the contract's Uniswap V3 swap: {}",
+                    aggregator.kind().as_str(),
+                    collateral_asset.symbol,
+                    e
+                );
+                return None;
+            }
+        };
+        let amount_in = seized_collateral_amount(
+            opportunity.expected_collateral_received,
+            prices[1],
+            debt_asset.decimals,
+            prices[0],
+            collateral_asset.decimals,
+            aggregator.slippage_bps(),
+        )?;
+
+        match aggregator
+            .aggregator_plan(
+                opportunity.collateral_asset,
+                opportunity.debt_asset,
+                amount_in,
+                self.contract_address,
+            )
+            .await
+        {
+            Ok(plan) => {
+                info!(
+                    "🔁 Swapping {} {} in the liquidation via {} (min out {})",
+                    amount_in, collateral_asset.symbol, plan.source, plan.min_amount_out
+                );
+                Some(AggregatorSwap {
+                    target: plan.target,
+                    spender: plan.spender,
+                    calldata: plan.calldata,
+                    min_amount_out: plan.min_amount_out,
+                })
+            }
+            Err(e) => {
+                warn!(
+                    "{} swap plan unavailable, using the contract's Uniswap V3 swap: {}",
+                    aggregator.kind().as_str(),
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// On-chain minimum profit for `opportunity` in debt asset units. None without a guard, or
+    /// when the debt asset can't be priced; the off-chain profit check still applies then.
+    async fn min_profit_floor(&self, opportunity: &LiquidationOpportunity) -> Option<U256> {
+        let (margin_bps, network) = self.min_profit_guard?;
+        let Some(debt_asset) = self.asset_configs.get(&opportunity.debt_asset) else {
+            warn!(
+                "No asset config for {}, sending without an on-chain minimum profit",
+                opportunity.debt_asset
+            );
+            return None;
+        };
+        let prices = match fetch_oracle_prices(
+            self.provider.as_ref(),
+            &network,
+            &[opportunity.debt_asset, network.weth],
+        )
+        .await
+        {
+            Ok(prices) => prices,
+            Err(e) => {
+                warn!(
+                    "Failed to price the on-chain minimum profit in {}, sending without it: {}",
+                    debt_asset.symbol, e
+                );
+                return None;
+            }
         };
+        let floor = onchain_min_profit(
+            opportunity,
+            margin_bps,
+            prices[0],
+            debt_asset.decimals,
+            prices[1],
+        );
+        if floor.is_none() {
+            warn!(
+                "No oracle price for {}, sending without an on-chain minimum profit",
+                debt_asset.symbol
+            );
+        }
+        floor
+    }
+
+    /// Execute a liquidation transaction
+    pub async fn execute_liquidation(
+        &self,
+        opportunity: &LiquidationOpportunity,
+    ) -> Result<ExecutedLiquidation> {
+        info!(
+            "🚀 Executing liquidation for user: {} (profit: {} wei)",
+            opportunity.user, opportunity.estimated_profit
+        );
+
+        let params = self.liquidation_params(opportunity).await?;
+
+        // Snapshot the collateral balance so only what this liquidation seizes is swapped
+        let collateral_balance_before = self.collateral_balance_before(opportunity, &params).await;
 
         // Call the liquidate function on the smart contract
-        let tx_hash = self.call_liquidate_function(&params).await?;
+        let submission = self.call_liquidate_function(&params).await?;
+        let tx_hash = submission.tx_hash.clone();
 
         info!("✅ Liquidation transaction submitted: {}", tx_hash);
 
+        if let Some(db_pool) = &self.audit_db {
+            let intent = LiquidationIntent {
+                tx_hash: tx_hash.clone(),
+                user: opportunity.user,
+                nonce: submission.nonce,
+                gas_price: U256::from(submission.gas_price),
+                gas_limit: U256::from(submission.gas_limit),
+                estimated_profit: opportunity.estimated_profit,
+            };
+            if let Err(e) = database::record_liquidation_intent(db_pool, &intent).await {
+                error!("Failed to record liquidation intent for {}: {}", tx_hash, e);
+            }
+        }
+
         // Wait for transaction confirmation
-        self.wait_for_confirmation(&tx_hash).await?;
+        let receipt = self.wait_for_confirmation(&submission).await;
+
+        // A real transaction that reverted or never landed lost the race
+        if let (Some((racing, _)), Some(_)) = (&self.racing, &submission.request) {
+            racing.record_outcome(receipt.as_ref().is_ok_and(|receipt| receipt.status()));
+        }
+        let receipt = receipt?;
+
+        // Settle the audit trail from the receipt whatever its status; a reverted transaction
+        // filled nothing and leaves no LiquidationCall log
+        let fill = fill::fill_from_logs(receipt.inner.logs(), opportunity);
+        if let Some(db_pool) = &self.audit_db {
+            let settlement = build_settlement(&tx_hash, opportunity, fill.as_ref(), &receipt);
+            if let Err(e) = database::record_liquidation_settlement(db_pool, &settlement).await {
+                error!(
+                    "Failed to record liquidation settlement for {}: {}",
+                    tx_hash, e
+                );
+            }
+        }
+
+        if !receipt.status() {
+            let reason = match &submission.request {
+                Some(request) => self.replay_revert_reason(request, receipt.block_number).await,
+                None => None,
+            }
+            .unwrap_or_else(|| "unknown reason".to_string());
+            error!("❌ Transaction {} reverted: {}", tx_hash, reason);
+
+            return Ok(ExecutedLiquidation {
+                receipt: liquidation_receipt(&tx_hash, &receipt),
+                tx_hash,
+                fill: None,
+                revert_reason: Some(reason),
+                received_a_tokens: false,
+            });
+        }
 
         info!("🎉 Liquidation confirmed: {}", tx_hash);
 
-        Ok(tx_hash)
+        match &fill {
+            Some(fill) if fill.is_partial() => warn!(
+                "⚠️ Partial fill for {}: covered {} of {} requested debt ({} bps), received {} collateral (expected {})",
+                tx_hash,
+                fill.debt_covered,
+                fill.requested_debt,
+                fill.fill_bps(),
+                fill.collateral_received,
+                fill.expected_collateral
+            ),
+            Some(_) => info!("✅ Liquidation {} filled in full", tx_hash),
+            None => warn!(
+                "⚠️ No LiquidationCall log found in receipt for {} - assuming requested amounts",
+                tx_hash
+            ),
+        }
+
+        if let Some(monitor) = &self.profit_recheck {
+            self.recheck_realized_profit(monitor, opportunity, fill.as_ref(), receipt.inner.logs());
+        }
+
+        // The swap and WETH rebalance wait for receipts of their own, so they run in the
+        // background instead of holding up the next liquidation
+        self.spawn_post_settlement(opportunity, fill.as_ref(), collateral_balance_before);
+
+        let receipt = liquidation_receipt(&tx_hash, &receipt);
+        Ok(ExecutedLiquidation {
+            tx_hash,
+            fill,
+            receipt,
+            revert_reason: None,
+            received_a_tokens: params.receive_a_token,
+        })
+    }
+
+    /// The signer's balance of the collateral a swap would convert after this liquidation.
+    /// None when nothing will be swapped: no route, the collateral is the debt asset, or it
+    /// arrives as aTokens, which are held until the unwind job withdraws and swaps them.
+    async fn collateral_balance_before(
+        &self,
+        opportunity: &LiquidationOpportunity,
+        params: &LiquidationParams,
+    ) -> Option<U256> {
+        let swapper = self.collateral_swapper.as_ref()?;
+        let collateral = opportunity.collateral_asset;
+        if params.receive_a_token
+            || collateral == opportunity.debt_asset
+            || swapper.route(collateral).is_none()
+        {
+            return None;
+        }
+        match profit_recheck::fetch_token_balance(
+            self.provider.as_ref(),
+            collateral,
+            self.signer.address(),
+        )
+        .await
+        {
+            Ok(balance) => Some(balance),
+            Err(e) => {
+                warn!(
+                    "Failed to snapshot collateral balance, skipping the collateral swap: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+
+    /// Convert the collateral this liquidation left with the signer, then rebalance WETH
+    /// (which the swap may have left with it) on a background task
+    fn spawn_post_settlement(
+        &self,
+        opportunity: &LiquidationOpportunity,
+        fill: Option<&LiquidationFill>,
+        collateral_balance_before: Option<U256>,
+    ) {
+        // Follow-up transactions share the liquidation's nonce sequence
+        let Some(tx_manager) = self.tx_manager.clone() else {
+            return;
+        };
+        let swap = self.collateral_swapper.clone().zip(collateral_balance_before);
+        let balancer = self.weth_balancer.clone();
+        if swap.is_none() && balancer.is_none() {
+            return;
+        }
+
+        let provider = self.provider.clone();
+        let signer = self.signer.clone();
+        let collateral = opportunity.collateral_asset;
+        let seized = fill.map(|fill| fill.collateral_received);
+        tokio::spawn(async move {
+            if let Some((swapper, balance_before)) = swap {
+                swap_received_collateral(
+                    provider.as_ref(),
+                    &signer,
+                    &tx_manager,
+                    &swapper,
+                    collateral,
+                    balance_before,
+                    seized,
+                )
+                .await;
+            }
+            if let Some(balancer) = balancer {
+                let rebalance = balancer.rebalance(provider.as_ref(), &signer, &tx_manager);
+                if let Err(e) = rebalance.await {
+                    warn!("Failed to rebalance WETH for {}: {}", signer.address(), e);
+                }
+            }
+        });
+    }
+
+    /// Compare realized profit against the estimate, both before gas (gas is paid in the native
+    /// token). Realized profit is what our contract's LiquidationExecuted event reports it kept;
+    /// a wallet-funded liquidation bypasses the contract, so its fill stands in.
+    fn recheck_realized_profit(
+        &self,
+        monitor: &ProfitRecheckMonitor,
+        opportunity: &LiquidationOpportunity,
+        fill: Option<&LiquidationFill>,
+        logs: &[Log],
+    ) {
+        let realized = fill::decode_contract_profit(logs, self.contract_address, opportunity)
+            .or_else(|| fill.map(|fill| fill.realized_profit.saturating_add(opportunity.gas_cost)));
+        match realized {
+            Some(realized) => {
+                monitor.record(
+                    opportunity.estimated_profit.saturating_add(opportunity.gas_cost),
+                    realized,
+                );
+            }
+            None => warn!(
+                "No LiquidationExecuted or LiquidationCall log for {}, skipping profit recheck",
+                opportunity.user
+            ),
+        }
     }
 
     /// Call the liquidate function on the smart contract
-    async fn call_liquidate_function(&self, params: &LiquidationParams) -> Result<String> {
+    async fn call_liquidate_function(
+        &self,
+        params: &LiquidationParams,
+    ) -> Result<SubmittedLiquidation> {
         info!(
-            "Calling liquidate function with params: user={}, collateral={}, debt={}, amount={}",
-            params.user, params.collateral_asset, params.debt_asset, params.debt_to_cover
+            "Calling liquidate function with params: user={}, collateral={}, debt={}, amount={}, funding={}",
+            params.user,
+            params.collateral_asset,
+            params.debt_asset,
+            params.debt_to_cover,
+            self.funding_label(params)
         );
 
-        // Prepare function call arguments
-        let args = vec![
-            alloy_dyn_abi::DynSolValue::Address(params.user),
-            alloy_dyn_abi::DynSolValue::Address(params.collateral_asset),
-            alloy_dyn_abi::DynSolValue::Address(params.debt_asset),
-            alloy_dyn_abi::DynSolValue::Uint(params.debt_to_cover, 256),
-            alloy_dyn_abi::DynSolValue::Bool(params.receive_a_token),
-            alloy_dyn_abi::DynSolValue::Uint(U256::from(params.collateral_asset_id), 16),
-            alloy_dyn_abi::DynSolValue::Uint(U256::from(params.debt_asset_id), 16),
-        ];
-
-        // Check if we should use real execution or mock (based on environment variable)
-        let use_real_execution = std::env::var("LIQUIDATION_REAL_EXECUTION")
-            .map(|v| v.to_lowercase() == "true")
-            .unwrap_or(false);
+        // Prepare the transaction: the pool directly for wallet funding, the contract otherwise
+        let (function, tx_req) = match &self.wallet_funding {
+            Some((funding, reservation)) => (
+                "liquidationCall",
+                funding.liquidation_call(self.signer.address(), params, reservation.amount()),
+            ),
+            None => {
+                let (function, args) = liquidate_call_args(params);
+                let tx_req = self
+                    .liquidator_contract
+                    .function(function, &args)?
+                    .into_transaction_request();
+                (function, tx_req)
+            }
+        };
 
-        if use_real_execution {
-            self.execute_real_transaction(params, &args).await
+        if self.broadcast {
+            if let Some((funding, reservation)) = &self.wallet_funding {
+                self.approve_wallet_funding(funding, reservation).await?;
+            }
+            self.execute_real_transaction(params, function, tx_req).await
         } else {
-            self.execute_mock_transaction(params, &args).await
+            self.execute_mock_transaction(params).await
+        }
+    }
+
+    /// Where the repaid debt comes from: the signer's wallet or the flash loan lender
+    fn funding_label(&self, params: &LiquidationParams) -> &'static str {
+        match &self.wallet_funding {
+            Some(_) => "wallet",
+            None => params.flash_loan_source.as_str(),
         }
     }
 
+    /// Let the pool pull the reserved debt asset from the signer. The approval takes a nonce
+    /// of its own, so the nonce manager re-reads the chain afterwards.
+    async fn approve_wallet_funding(
+        &self,
+        funding: &WalletFunding,
+        reservation: &FundsReservation,
+    ) -> Result<()> {
+        let tx_manager = self
+            .tx_manager
+            .as_ref()
+            .ok_or_else(|| eyre::eyre!("Wallet-funded liquidations need a transaction manager"))?;
+        funding
+            .approvals()
+            .ensure_allowance(
+                self.provider.as_ref(),
+                &self.signer,
+                tx_manager,
+                reservation.token(),
+                funding.pool(),
+                reservation.amount(),
+            )
+            .await?;
+        Ok(())
+    }
+
     /// Execute real blockchain transaction with signing
     async fn execute_real_transaction(
         &self,
         params: &LiquidationParams,
-        args: &[alloy_dyn_abi::DynSolValue],
-    ) -> Result<String> {
+        function: &str,
+        mut tx_req: TransactionRequest,
+    ) -> Result<SubmittedLiquidation> {
         info!("🔗 EXECUTING REAL BLOCKCHAIN TRANSACTION");
 
-        // Create the transaction request from the contract call
-        let call = self.liquidator_contract.function("liquidate", args)?;
-        let mut tx_req = call.into_transaction_request();
+        tx_req.from = Some(self.signer.address());
+        tx_req.chain_id = Some(self.chain_id);
 
-        // Get current gas price and add multiplier for competitive execution
-        let base_gas_price = self.provider.get_gas_price().await?;
-        let gas_price_multiplier = 2; // Use 2x gas price for faster execution
-        let adjusted_gas_price = base_gas_price * gas_price_multiplier;
+        let (mut max_fee_per_gas, mut max_priority_fee_per_gas) = self.eip1559_fees().await?;
+        tx_req.max_fee_per_gas = Some(max_fee_per_gas);
+        tx_req.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
 
-        // Set transaction parameters directly
-        tx_req.gas_price = Some(adjusted_gas_price);
-        tx_req.gas = Some(500_000); // Conservative gas limit for liquidations
-        tx_req.from = Some(self.signer.address());
-        tx_req.chain_id = Some(8453); // Base mainnet
+        // A liquidation that would revert is not worth the gas: surface the reason instead
+        let gas_limit = match self.provider.estimate_gas(&tx_req).await {
+            Ok(estimate) => with_gas_buffer(u64::try_from(estimate).unwrap_or(u64::MAX)),
+            Err(e) => match revert_reason_from_error(&e) {
+                Some(reason) => {
+                    return Err(eyre::eyre!("Liquidation would revert: {}", reason));
+                }
+                None => {
+                    warn!(
+                        "⚠️ eth_estimateGas failed ({}), using default gas limit {}",
+                        e, LIQUIDATION_GAS_LIMIT
+                    );
+                    LIQUIDATION_GAS_LIMIT
+                }
+            },
+        };
+        tx_req.gas = Some(gas_limit.into());
 
-        info!(
-            "🔗 Preparing liquidation transaction: gas_price={}, gas_limit={}, from={:?}",
-            adjusted_gas_price,
-            500_000,
-            self.signer.address()
-        );
+        // Bid up the tip for the profit at stake; the base fee headroom stays as priced
+        let mut priority_fee_cap = None;
+        if let Some((racing, budget)) = &self.racing {
+            let base_priority_fee = max_priority_fee_per_gas;
+            let bid = racing.priority_fee(base_priority_fee, budget, gas_limit);
+            max_fee_per_gas = max_fee_per_gas.saturating_sub(base_priority_fee).saturating_add(bid);
+            max_priority_fee_per_gas = bid;
+            tx_req.max_fee_per_gas = Some(max_fee_per_gas);
+            tx_req.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+            priority_fee_cap = Some(racing.max_priority_fee(base_priority_fee, budget, gas_limit));
+        }
+        if let Some(rival_priority_fee) = self.rival_priority_fee {
+            let outbid = racing::outbid_priority_fee(rival_priority_fee)
+                .min(priority_fee_cap.unwrap_or(u128::MAX));
+            if outbid > max_priority_fee_per_gas {
+                info!(
+                    "🏎️ Outbidding competing liquidation: priority fee {} -> {} wei (rival {} wei)",
+                    max_priority_fee_per_gas, outbid, rival_priority_fee
+                );
+                max_fee_per_gas = max_fee_per_gas
+                    .saturating_sub(max_priority_fee_per_gas)
+                    .saturating_add(outbid);
+                max_priority_fee_per_gas = outbid;
+                tx_req.max_fee_per_gas = Some(max_fee_per_gas);
+                tx_req.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+            }
+        }
 
         // Log the transaction details
         info!("📋 Transaction parameters:");
-        info!("  - Function: liquidate");
+        info!("  - Function: {}", function);
+        info!("  - Funding: {}", self.funding_label(params));
         info!("  - User: {:?}", params.user);
         info!("  - Collateral Asset: {:?}", params.collateral_asset);
         info!("  - Debt Asset: {:?}", params.debt_asset);
         info!("  - Debt to Cover: {} wei", params.debt_to_cover);
-        info!("  - Gas price: {} wei", adjusted_gas_price);
-        info!("  - Gas limit: 500,000");
+        info!("  - Max fee per gas: {} wei", max_fee_per_gas);
+        info!("  - Max priority fee per gas: {} wei", max_priority_fee_per_gas);
+        info!("  - Gas limit: {}", gas_limit);
         info!("  - From: {:?}", self.signer.address());
-        info!("  - Chain ID: 8453 (Base mainnet)");
-
-        // Get RPC URL from environment or use default Base mainnet
-        let rpc_url =
-            std::env::var("RPC_URL").unwrap_or_else(|_| "https://mainnet.base.org".to_string());
+        info!("  - Chain ID: {}", self.chain_id);
 
         info!("🔗 Setting up provider with signer for real transaction execution...");
 
         // Create wallet from the signer
         let wallet = EthereumWallet::from(self.signer.clone());
 
-        // Create provider with signer using ProviderBuilder
-        let signer_provider = ProviderBuilder::new()
-            .with_recommended_fillers()
-            .wallet(wallet)
-            .on_http(rpc_url.parse()?);
+        // Create provider with signer, sending through the same transport (and provider
+        // pool failover) as the reads
+        let signer_provider = providers::signer_provider(self.provider.as_ref(), &self.signer);
 
         info!("✅ Signer provider created, submitting transaction...");
 
+        // Reserve the nonce through the shared manager so concurrent liquidations don't collide;
+        // without one, capture the nonce the fillers will assign for the audit trail
+        let nonce = match &self.tx_manager {
+            Some(tx_manager) => {
+                let nonce = tx_manager.allocate_nonce(self.provider.as_ref()).await?;
+                tx_req.nonce = Some(nonce);
+                nonce
+            }
+            None => {
+                self.provider
+                    .get_transaction_count(self.signer.address())
+                    .await?
+            }
+        };
+
+        if let Some(relay) = &self.private_relay {
+            // The relay only returns once the bundle is included, so stamp the hand-off
+            latency::mark(params.user, LatencyStage::TxSent);
+            return self
+                .submit_via_private_relay(relay, tx_req, &wallet, nonce, max_fee_per_gas, gas_limit)
+                .await;
+        }
+
         // Submit the transaction using the signer provider
-        let pending_tx = signer_provider.send_transaction(tx_req).await?;
-        let tx_hash = *pending_tx.tx_hash();
-        let tx_hash_string = format!("0x{:x}", tx_hash);
+        let pending_tx = match signer_provider.send_transaction(tx_req.clone()).await {
+            Ok(pending_tx) => pending_tx,
+            Err(e) => {
+                // The reserved nonce was never used; re-read it from chain next time
+                if let Some(tx_manager) = &self.tx_manager {
+                    tx_manager.resync();
+                }
+                return Err(e.into());
+            }
+        };
+        latency::mark(params.user, LatencyStage::TxSent);
+        let tx_hash_string = format!("0x{:x}", pending_tx.tx_hash());
+
+        if let Some(tx_manager) = &self.tx_manager {
+            tx_manager.track(
+                nonce,
+                *pending_tx.tx_hash(),
+                tx_req.clone(),
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            );
+            if let Some(cap) = priority_fee_cap {
+                tx_manager.cap_priority_fee(nonce, cap);
+            }
+            tx_manager.tag_borrower(nonce, params.user);
+        }
 
         info!(
             "🚀 REAL liquidation transaction submitted successfully: {}",
             tx_hash_string
         );
+
+        Ok(SubmittedLiquidation {
+            tx_hash: tx_hash_string,
+            nonce,
+            gas_price: max_fee_per_gas,
+            gas_limit,
+            request: Some(tx_req),
+        })
+    }
+
+    /// Sign the transaction locally and hand it to the private relay, which simulates it,
+    /// resubmits it on missed blocks and returns once it is included
+    async fn submit_via_private_relay(
+        &self,
+        relay: &PrivateRelaySubmitter,
+        mut tx_req: TransactionRequest,
+        wallet: &EthereumWallet,
+        nonce: u64,
+        gas_price: u128,
+        gas_limit: u64,
+    ) -> Result<SubmittedLiquidation> {
+        tx_req.nonce = Some(nonce);
+        let envelope = tx_req
+            .clone()
+            .build(wallet)
+            .await
+            .map_err(|e| eyre::eyre!("Failed to sign liquidation transaction: {}", e))?;
+        let tx_hash = *envelope.tx_hash();
+        let raw_tx = Bytes::from(envelope.encoded_2718());
+
         info!(
-            "📊 Transaction hash: {}, waiting for confirmation...",
-            tx_hash_string
+            "🔒 Submitting liquidation 0x{:x} via private relay {} (strategy: {})",
+            tx_hash,
+            relay.relay_url(),
+            relay.strategy().as_str()
         );
 
-        // Wait for confirmation with timeout
-        match tokio::time::timeout(
-            std::time::Duration::from_secs(120),
-            pending_tx.get_receipt(),
-        )
-        .await
+        let inclusion = match relay
+            .submit(self.provider.as_ref(), &self.signer, &raw_tx, tx_hash)
+            .await
         {
-            Ok(Ok(receipt)) => {
-                if receipt.status() {
-                    info!(
-                        "✅ Transaction confirmed successfully in block: {:?}",
-                        receipt.block_number
-                    );
-                    info!("🎉 Real liquidation executed on-chain!");
-                } else {
-                    error!("❌ Transaction failed on-chain");
-                    return Err(eyre::eyre!("Transaction failed on blockchain"));
+            Ok(inclusion) => inclusion,
+            Err(e) => {
+                if let Some(tx_manager) = &self.tx_manager {
+                    tx_manager.resync();
                 }
+                return Err(e);
             }
-            Ok(Err(e)) => {
-                warn!("⚠️ Could not get receipt: {}", e);
-                info!("Transaction submitted, hash: {}", tx_hash_string);
-            }
-            Err(_) => {
-                warn!("⏰ Receipt timeout - transaction may still be pending");
-                info!("Transaction submitted, hash: {}", tx_hash_string);
-            }
-        }
+        };
+        info!(
+            "🚀 REAL liquidation transaction included in block {} after {} bundle submission(s)",
+            inclusion.block_number, inclusion.submissions
+        );
 
-        Ok(tx_hash_string)
+        Ok(SubmittedLiquidation {
+            tx_hash: format!("0x{:x}", tx_hash),
+            nonce,
+            gas_price,
+            gas_limit,
+            request: Some(tx_req),
+        })
     }
 
     /// Execute mock transaction for testing/simulation
     async fn execute_mock_transaction(
         &self,
         params: &LiquidationParams,
-        _args: &[alloy_dyn_abi::DynSolValue],
-    ) -> Result<String> {
+    ) -> Result<SubmittedLiquidation> {
         info!("🎭 EXECUTING MOCK TRANSACTION (simulation mode)");
 
-        // Get current gas price and add multiplier for realistic simulation
-        let base_gas_price = self.provider.get_gas_price().await?;
-        let gas_price_multiplier = 2; // Use 2x gas price for faster execution
-        let adjusted_gas_price = base_gas_price * gas_price_multiplier;
+        // Price the simulated transaction the same way a real one would be
+        let (adjusted_gas_price, priority_fee) = self.eip1559_fees().await?;
 
         // Get nonce for the signer for realistic simulation
         let nonce = self
@@ -244,8 +940,8 @@ where
             .await?;
 
         info!(
-            "🔗 Simulating liquidation transaction with gas price: {} ({}x multiplier), gas limit: {}, nonce: {}",
-            adjusted_gas_price, gas_price_multiplier, 500_000, nonce
+            "🔗 Simulating liquidation transaction with max fee: {} (priority fee {}), gas limit: {}, nonce: {}",
+            adjusted_gas_price, priority_fee, 500_000, nonce
         );
 
         // Log detailed transaction parameters that would be used
@@ -254,11 +950,12 @@ where
         info!("  - Collateral Asset: {:?}", params.collateral_asset);
         info!("  - Debt Asset: {:?}", params.debt_asset);
         info!("  - Debt to Cover: {} wei", params.debt_to_cover);
-        info!("  - Gas price: {} wei", adjusted_gas_price);
+        info!("  - Funding: {}", self.funding_label(params));
+        info!("  - Max fee per gas: {} wei", adjusted_gas_price);
         info!("  - Gas limit: 500,000");
         info!("  - From: {:?}", self.signer.address());
         info!("  - Nonce: {}", nonce);
-        info!("  - Chain ID: 8453 (Base mainnet)");
+        info!("  - Chain ID: {}", self.chain_id);
 
         // Create deterministic mock transaction hash for testing
         let mut hasher = DefaultHasher::new();
@@ -272,14 +969,51 @@ where
         info!("🎭 Mock transaction hash generated: {}", mock_tx_hash);
         warn!("⚠️  This is a MOCK transaction - no real on-chain execution occurred");
         warn!(
-            "⚠️  Set LIQUIDATION_REAL_EXECUTION=true environment variable to enable real execution"
+            "⚠️  Set LIQUIDATION_REAL_EXECUTION=true with EXECUTION_MODE=live to enable real execution"
         );
 
-        Ok(mock_tx_hash)
+        Ok(SubmittedLiquidation {
+            tx_hash: mock_tx_hash,
+            nonce,
+            gas_price: adjusted_gas_price,
+            gas_limit: LIQUIDATION_GAS_LIMIT,
+            request: None,
+        })
     }
 
-    /// Wait for transaction confirmation
-    async fn wait_for_confirmation(&self, tx_hash: &str) -> Result<()> {
+    /// EIP-1559 fees for this liquidation: the gas oracle's profile when available, otherwise
+    /// the node's estimate with a boosted priority fee
+    async fn eip1559_fees(&self) -> Result<(u128, u128)> {
+        if let Some((gas_oracle, profile)) = &self.gas_oracle {
+            match gas_oracle.fees(self.provider.as_ref(), *profile).await {
+                Ok(fees) => {
+                    info!(
+                        "⛽ {} fee profile: max fee {} wei, priority fee {} wei",
+                        profile.as_str(),
+                        fees.max_fee_per_gas,
+                        fees.max_priority_fee_per_gas
+                    );
+                    return Ok((fees.max_fee_per_gas, fees.max_priority_fee_per_gas));
+                }
+                Err(e) => warn!("Gas oracle unavailable, using node fee estimate: {}", e),
+            }
+        }
+
+        let fees = self.provider.estimate_eip1559_fees(None).await?;
+        Ok(competitive_eip1559_fees(
+            fees.max_fee_per_gas,
+            fees.max_priority_fee_per_gas,
+            PRIORITY_FEE_MULTIPLIER,
+        ))
+    }
+
+    /// Wait for the transaction (or a replacement of it) to be mined and return its receipt,
+    /// whether it succeeded or reverted
+    async fn wait_for_confirmation(
+        &self,
+        submission: &SubmittedLiquidation,
+    ) -> Result<alloy_rpc_types::TransactionReceipt> {
+        let tx_hash = &submission.tx_hash;
         info!("⏳ Waiting for transaction confirmation: {}", tx_hash);
 
         // Parse tx hash
@@ -289,25 +1023,59 @@ where
         let mut attempts = 0;
         const MAX_ATTEMPTS: u32 = 60; // 2 minutes with 2-second intervals
 
+        // Transactions tracked by the nonce manager may have been replaced with higher fees;
+        // whichever version is mined settles the nonce
+        let tracked = self
+            .tx_manager
+            .as_ref()
+            .filter(|tx_manager| tx_manager.pending(submission.nonce).is_some());
+        let wallet = EthereumWallet::from(self.signer.clone());
+
         while attempts < MAX_ATTEMPTS {
             tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
-            match self.provider.get_transaction_receipt(hash).await {
+            let lookup = match tracked {
+                Some(tx_manager) => {
+                    tx_manager
+                        .find_receipt(self.provider.as_ref(), submission.nonce)
+                        .await
+                }
+                None => self
+                    .provider
+                    .get_transaction_receipt(hash)
+                    .await
+                    .map_err(Into::into),
+            };
+
+            match lookup {
                 Ok(Some(receipt)) => {
-                    if receipt.status() {
-                        info!(
-                            "✅ Transaction confirmed successfully in block: {:?}",
-                            receipt.block_number
-                        );
-                        return Ok(());
-                    } else {
-                        error!("❌ Transaction failed!");
-                        return Err(eyre::eyre!("Transaction failed"));
+                    if let Some(tx_manager) = tracked {
+                        tx_manager.confirm(submission.nonce);
+                        if receipt.transaction_hash != hash {
+                            info!(
+                                "⛽ Replacement 0x{:x} was mined for {}",
+                                receipt.transaction_hash, tx_hash
+                            );
+                        }
                     }
+                    info!(
+                        "✅ Transaction mined in block {:?} (status: {})",
+                        receipt.block_number,
+                        if receipt.status() { "success" } else { "reverted" }
+                    );
+                    return Ok(receipt);
                 }
                 Ok(None) => {
                     // Transaction still pending
                     attempts += 1;
+                    if let Some(tx_manager) = tracked {
+                        if let Err(e) = tx_manager
+                            .bump_if_stuck(self.provider.as_ref(), &wallet, submission.nonce)
+                            .await
+                        {
+                            warn!("Failed to replace stuck transaction {}: {}", tx_hash, e);
+                        }
+                    }
                     if attempts % 15 == 0 {
                         info!(
                             "⏳ Still waiting for confirmation... ({}/{})",
@@ -322,9 +1090,29 @@ where
             }
         }
 
+        // The transaction may still be mined, so its nonce stays tracked: a retry replaces it
+        // with higher fees instead of racing it with a second liquidation on a fresh nonce
         Err(eyre::eyre!("Transaction confirmation timeout"))
     }
 
+    /// Re-run a reverted transaction with `eth_call` against the state it was mined on top of
+    /// and decode why it reverted
+    async fn replay_revert_reason(
+        &self,
+        request: &TransactionRequest,
+        block_number: Option<u64>,
+    ) -> Option<String> {
+        let mut call = self.provider.call(request);
+        if let Some(block) = block_number {
+            call = call.block(BlockId::number(block.saturating_sub(1)));
+        }
+        match call.await {
+            // The replay succeeding means the revert depended on transactions earlier in the block
+            Ok(_) => None,
+            Err(e) => revert_reason_from_error(&e),
+        }
+    }
+
     /// Get asset ID for L2Pool encoding - uses asset configuration lookup
     fn get_asset_id(&self, asset_address: Address) -> Result<u16> {
         // Look up asset configuration to get dynamically fetched asset ID
@@ -340,8 +1128,8 @@ where
         }
     }
 
-    /// Check if the contract is properly configured
-    pub async fn verify_contract_setup(&self) -> Result<()> {
+    /// Check if the contract is properly configured for `expected_pool`
+    pub async fn verify_contract_setup(&self, expected_pool: Address) -> Result<()> {
         info!("🔍 Verifying liquidator contract setup...");
 
         // Call getPool() to verify contract is configured
@@ -353,9 +1141,7 @@ where
             if let Some(addr) = pool_address.as_address() {
                 info!("✅ Contract pool address: {:?}", addr);
 
-                // Verify it matches expected Base mainnet pool
-                let expected_pool: Address =
-                    "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5".parse()?;
+                // Verify it matches the pool of the configured chain
                 if addr == expected_pool {
                     info!("✅ Pool address verification successful");
                 } else {
@@ -371,6 +1157,325 @@ where
     }
 }
 
+/// Max fee and priority fee with the priority fee multiplied by `multiplier`; the extra tip is
+/// added to the max fee so the base fee headroom is kept
+fn competitive_eip1559_fees(
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+    multiplier: u128,
+) -> (u128, u128) {
+    let priority_fee = max_priority_fee_per_gas.saturating_mul(multiplier);
+    let extra_tip = priority_fee.saturating_sub(max_priority_fee_per_gas);
+    (max_fee_per_gas.saturating_add(extra_tip), priority_fee)
+}
+
+/// `eth_estimateGas` result plus GAS_ESTIMATE_BUFFER_PCT headroom
+fn with_gas_buffer(estimate: u64) -> u64 {
+    estimate.saturating_add(estimate.saturating_mul(GAS_ESTIMATE_BUFFER_PCT) / 100)
+}
+
+/// Human-readable revert reason: `Error(string)` messages and `Panic(uint256)` codes are
+/// decoded, custom errors are reported by selector
+pub fn decode_revert_data(data: &[u8]) -> String {
+    if data.is_empty() {
+        return "empty revert data".to_string();
+    }
+    if let Some(reason) = alloy_sol_types::decode_revert_reason(data) {
+        return reason;
+    }
+    format!(
+        "custom error 0x{}",
+        alloy_primitives::hex::encode(&data[..data.len().min(4)])
+    )
+}
+
+/// Revert reason carried by an RPC error, if the node reported an execution revert
+pub(crate) fn revert_reason_from_error(error: &alloy_transport::TransportError) -> Option<String> {
+    let payload = error.as_error_resp()?;
+    match payload.as_revert_data() {
+        Some(data) => Some(decode_revert_data(&data)),
+        None if payload.message.contains("revert") => Some(payload.message.to_string()),
+        None => None,
+    }
+}
+
+/// Receipt fields stored with the liquidation event
+fn liquidation_receipt(
+    tx_hash: &str,
+    receipt: &alloy_rpc_types::TransactionReceipt,
+) -> LiquidationReceipt {
+    LiquidationReceipt {
+        tx_hash: tx_hash.to_string(),
+        block_number: receipt.block_number,
+        tx_index: receipt.transaction_index,
+        gas_used: U256::from(receipt.gas_used),
+        effective_gas_price: U256::from(receipt.effective_gas_price),
+        succeeded: receipt.status(),
+    }
+}
+
+/// Collateral to convert after a liquidation: what the signer's balance grew by, capped at the
+/// amount the pool reports seizing so collateral held from before (or arriving from another
+/// liquidation meanwhile) is never sold. None when nothing arrived.
+pub fn seized_collateral_to_swap(
+    balance_before: U256,
+    balance_after: U256,
+    seized: Option<U256>,
+) -> Option<U256> {
+    let delta = balance_after.saturating_sub(balance_before);
+    let amount = seized.map_or(delta, |seized| delta.min(seized));
+    (!amount.is_zero()).then_some(amount)
+}
+
+/// Post-hoc conversion of seized collateral held by the signer. Collateral the contract
+/// already swapped inside the flash loan leaves no balance delta, so nothing is sent then.
+async fn swap_received_collateral<P>(
+    provider: &P,
+    signer: &BotSigner,
+    tx_manager: &TxManager,
+    swapper: &CollateralSwapper,
+    collateral: Address,
+    balance_before: U256,
+    seized: Option<U256>,
+) where
+    P: Provider,
+{
+    let balance_after =
+        match profit_recheck::fetch_token_balance(provider, collateral, signer.address()).await {
+            Ok(balance) => balance,
+            Err(e) => {
+                warn!("Failed to fetch collateral balance for swap: {}", e);
+                return;
+            }
+        };
+    let Some(amount) = seized_collateral_to_swap(balance_before, balance_after, seized) else {
+        return;
+    };
+
+    match swapper
+        .swap_collateral(provider, signer, tx_manager, collateral, amount)
+        .await
+    {
+        Ok(Some(outcome)) => info!(
+            "🔁 Converted {} of collateral {} to {} via {} (quoted {}, min {}): {}",
+            outcome.amount_in,
+            outcome.token_in,
+            outcome.token_out,
+            outcome.source,
+            outcome.quoted_amount_out,
+            outcome.min_amount_out,
+            outcome.tx_hash
+        ),
+        Ok(None) => {}
+        Err(e) => error!("Failed to swap received collateral {}: {}", collateral, e),
+    }
+}
+
+/// Minimum profit the contract must see after repaying the flash loan, in debt asset units as
+/// the contract measures it. The contract's profit is measured before gas, so the floor is the
+/// gas cost plus what's left of the estimated net profit once the market takes `margin_bps` of
+/// it; at 10000 bps it only has to break even. The profit is in the oracle's base currency and
+/// the gas cost in wei, so both are priced into the debt asset: `debt_price` and
+/// `native_price` are oracle prices of the debt asset and of (W)ETH. None when the debt asset
+/// has no price.
+pub fn onchain_min_profit(
+    opportunity: &LiquidationOpportunity,
+    margin_bps: u64,
+    debt_price: U256,
+    debt_decimals: u8,
+    native_price: U256,
+) -> Option<U256> {
+    let kept_bps = U256::from(10_000u64.saturating_sub(margin_bps));
+    let kept_profit = opportunity.estimated_profit.saturating_mul(kept_bps) / U256::from(10_000u64);
+    let gas_cost_base = opportunity.gas_cost.saturating_mul(native_price) / WEI_PER_ETH;
+    base_to_token_amount(gas_cost_base.saturating_add(kept_profit), debt_price, debt_decimals)
+}
+
+/// Collateral, in its own units, to quote the in-contract aggregator swap for. The expected
+/// seizure is valued in the debt asset, so it is converted at the oracle's `debt_price` and
+/// `collateral_price`, then cut by `slippage_bps`: the aggregator pulls exactly the quoted
+/// input, and a seizure that comes in short (the protocol's share of the bonus, price moves)
+/// would revert the whole liquidation. Whatever isn't swapped stays in the contract. None
+/// when either asset has no price.
+pub fn seized_collateral_amount(
+    expected_collateral: U256,
+    debt_price: U256,
+    debt_decimals: u8,
+    collateral_price: U256,
+    collateral_decimals: u8,
+    slippage_bps: u64,
+) -> Option<U256> {
+    if debt_price.is_zero() {
+        return None;
+    }
+    let debt_unit = U256::from(10u64).pow(U256::from(debt_decimals));
+    let value_base = expected_collateral.saturating_mul(debt_price) / debt_unit;
+    let amount = base_to_token_amount(value_base, collateral_price, collateral_decimals)?;
+    Some(swap::min_amount_out(amount, slippage_bps)).filter(|amount| !amount.is_zero())
+}
+
+/// Contract function and arguments for `params`. Aave V3 loans keep using `liquidate` so
+/// deployments predating the other lenders still work; other lenders go through
+/// `liquidateWithFlashLoan`, which takes the lender id first. With a minimum profit the call
+/// goes through `liquidateWithMinProfit`, which also takes the lender id first and the
+/// minimum last. An aggregator swap goes through `liquidateWithSwap`, which takes the lender
+/// id and the whole LiquidationParams struct.
+fn liquidate_call_args(
+    params: &LiquidationParams,
+) -> (&'static str, Vec<alloy_dyn_abi::DynSolValue>) {
+    if let Some(swap) = &params.aggregator_swap {
+        let source = U256::from(params.flash_loan_source.contract_id());
+        let tuple = alloy_dyn_abi::DynSolValue::Tuple(vec![
+            alloy_dyn_abi::DynSolValue::Address(params.user),
+            alloy_dyn_abi::DynSolValue::Address(params.collateral_asset),
+            alloy_dyn_abi::DynSolValue::Address(params.debt_asset),
+            alloy_dyn_abi::DynSolValue::Uint(params.debt_to_cover, 256),
+            alloy_dyn_abi::DynSolValue::Bool(params.receive_a_token),
+            alloy_dyn_abi::DynSolValue::Uint(U256::from(params.collateral_asset_id), 256),
+            alloy_dyn_abi::DynSolValue::Uint(U256::from(params.debt_asset_id), 256),
+            alloy_dyn_abi::DynSolValue::Uint(params.min_profit.unwrap_or_default(), 256),
+            alloy_dyn_abi::DynSolValue::Address(swap.target),
+            alloy_dyn_abi::DynSolValue::Address(swap.spender),
+            alloy_dyn_abi::DynSolValue::Bytes(swap.calldata.to_vec()),
+            alloy_dyn_abi::DynSolValue::Uint(swap.min_amount_out, 256),
+        ]);
+        return (
+            "liquidateWithSwap",
+            vec![alloy_dyn_abi::DynSolValue::Uint(source, 8), tuple],
+        );
+    }
+
+    let mut args = vec![
+        alloy_dyn_abi::DynSolValue::Address(params.user),
+        alloy_dyn_abi::DynSolValue::Address(params.collateral_asset),
+        alloy_dyn_abi::DynSolValue::Address(params.debt_asset),
+        alloy_dyn_abi::DynSolValue::Uint(params.debt_to_cover, 256),
+        alloy_dyn_abi::DynSolValue::Bool(params.receive_a_token),
+        alloy_dyn_abi::DynSolValue::Uint(U256::from(params.collateral_asset_id), 16),
+        alloy_dyn_abi::DynSolValue::Uint(U256::from(params.debt_asset_id), 16),
+    ];
+
+    match (params.flash_loan_source, params.min_profit) {
+        (source, Some(min_profit)) => {
+            args.insert(
+                0,
+                alloy_dyn_abi::DynSolValue::Uint(U256::from(source.contract_id()), 8),
+            );
+            args.push(alloy_dyn_abi::DynSolValue::Uint(min_profit, 256));
+            ("liquidateWithMinProfit", args)
+        }
+        (FlashLoanSource::AaveV3, None) => ("liquidate", args),
+        (source, None) => {
+            args.insert(
+                0,
+                alloy_dyn_abi::DynSolValue::Uint(U256::from(source.contract_id()), 8),
+            );
+            ("liquidateWithFlashLoan", args)
+        }
+    }
+}
+
+/// Opcode Solidity dispatchers use to push each function selector before comparing it
+const PUSH4_OPCODE: u8 = 0x63;
+
+/// EIP-1967 storage slot holding a proxy's implementation address
+const EIP1967_IMPLEMENTATION_SLOT: B256 =
+    b256!("360894a13ba1a3210667c828492db98dca3e2076cc3735a920a3ca505d382bbc");
+
+/// Selector of the liquidator contract's `liquidate` entrypoint
+fn liquidate_selector() -> Result<[u8; 4]> {
+    let abi = get_liquidator_abi()?;
+    abi.function("liquidate")
+        .and_then(|functions| functions.first())
+        .map(|function| function.selector().0)
+        .ok_or_else(|| eyre::eyre!("Liquidator ABI has no liquidate function"))
+}
+
+/// Whether deployed bytecode dispatches `selector`
+pub fn bytecode_has_selector(code: &[u8], selector: [u8; 4]) -> bool {
+    code.windows(5)
+        .any(|window| window[0] == PUSH4_OPCODE && window[1..] == selector)
+}
+
+/// Check that the configured liquidator contract is deployed and exposes the `liquidate`
+/// entrypoint, so a typo'd or undeployed address fails at startup instead of reverting
+/// every liquidation
+pub async fn verify_liquidator_contract<P>(provider: &Arc<P>, contract_address: Address) -> Result<()>
+where
+    P: Provider,
+{
+    let code = provider.get_code_at(contract_address).await?;
+
+    if code.is_empty() {
+        return Err(eyre::eyre!(
+            "No contract code at liquidator contract address {} - check LIQUIDATOR_CONTRACT and the configured network",
+            contract_address
+        ));
+    }
+
+    let selector = liquidate_selector()?;
+    if bytecode_has_selector(&code, selector) {
+        info!("✅ Liquidator contract verified at {}", contract_address);
+        return Ok(());
+    }
+
+    // Proxies don't contain the implementation's selectors: look for liquidate in the EIP-1967
+    // implementation rather than trusting any contract that answers a view call
+    let slot = provider
+        .get_storage_at(contract_address, U256::from_be_bytes(EIP1967_IMPLEMENTATION_SLOT.0))
+        .await?;
+    let implementation = Address::from_word(B256::from(slot));
+    if !implementation.is_zero() {
+        let implementation_code = provider.get_code_at(implementation).await?;
+        if bytecode_has_selector(&implementation_code, selector) {
+            info!(
+                "✅ Liquidator proxy verified at {} (implementation {})",
+                contract_address, implementation
+            );
+            return Ok(());
+        }
+    }
+
+    Err(eyre::eyre!(
+        "Contract at liquidator address {} does not expose liquidate (selector 0x{}), directly or through an EIP-1967 implementation",
+        contract_address,
+        alloy_primitives::hex::encode(selector)
+    ))
+}
+
+/// Build the settlement audit record from a confirmed receipt.
+///
+/// Realized profit starts from the fill-adjusted profit (when the fill is known) and
+/// replaces the estimated gas cost with the gas actually paid.
+fn build_settlement(
+    tx_hash: &str,
+    opportunity: &LiquidationOpportunity,
+    fill: Option<&LiquidationFill>,
+    receipt: &alloy_rpc_types::TransactionReceipt,
+) -> LiquidationSettlement {
+    let gas_used = U256::from(receipt.gas_used);
+    let actual_gas_cost = gas_used.saturating_mul(U256::from(receipt.effective_gas_price));
+    // A reverted liquidation earned nothing; its gas is the whole (unsigned, so floored) loss
+    let realized_profit = if receipt.status() {
+        let expected_profit =
+            fill.map_or(opportunity.estimated_profit, |fill| fill.realized_profit);
+        expected_profit
+            .saturating_add(opportunity.gas_cost)
+            .saturating_sub(actual_gas_cost)
+    } else {
+        U256::ZERO
+    };
+
+    LiquidationSettlement {
+        tx_hash: tx_hash.to_string(),
+        block_number: receipt.block_number,
+        tx_index: receipt.transaction_index,
+        gas_used,
+        realized_profit,
+        succeeded: receipt.status(),
+    }
+}
+
 /// Get the liquidator contract ABI
 fn get_liquidator_abi() -> Result<alloy_json_abi::JsonAbi> {
     // For now, create a minimal ABI with the liquidate function
@@ -391,6 +1496,67 @@ fn get_liquidator_abi() -> Result<alloy_json_abi::JsonAbi> {
             "stateMutability": "nonpayable",
             "type": "function"
         },
+        {
+            "inputs": [
+                {"internalType": "uint8", "name": "flashLoanSource", "type": "uint8"},
+                {"internalType": "address", "name": "user", "type": "address"},
+                {"internalType": "address", "name": "collateralAsset", "type": "address"},
+                {"internalType": "address", "name": "debtAsset", "type": "address"},
+                {"internalType": "uint256", "name": "debtToCover", "type": "uint256"},
+                {"internalType": "bool", "name": "receiveAToken", "type": "bool"},
+                {"internalType": "uint16", "name": "collateralAssetId", "type": "uint16"},
+                {"internalType": "uint16", "name": "debtAssetId", "type": "uint16"}
+            ],
+            "name": "liquidateWithFlashLoan",
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
+        {
+            "inputs": [
+                {"internalType": "uint8", "name": "flashLoanSource", "type": "uint8"},
+                {"internalType": "address", "name": "user", "type": "address"},
+                {"internalType": "address", "name": "collateralAsset", "type": "address"},
+                {"internalType": "address", "name": "debtAsset", "type": "address"},
+                {"internalType": "uint256", "name": "debtToCover", "type": "uint256"},
+                {"internalType": "bool", "name": "receiveAToken", "type": "bool"},
+                {"internalType": "uint16", "name": "collateralAssetId", "type": "uint16"},
+                {"internalType": "uint16", "name": "debtAssetId", "type": "uint16"},
+                {"internalType": "uint256", "name": "minProfit", "type": "uint256"}
+            ],
+            "name": "liquidateWithMinProfit",
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
+        {
+            "inputs": [
+                {"internalType": "uint8", "name": "flashLoanSource", "type": "uint8"},
+                {
+                    "components": [
+                        {"internalType": "address", "name": "user", "type": "address"},
+                        {"internalType": "address", "name": "collateralAsset", "type": "address"},
+                        {"internalType": "address", "name": "debtAsset", "type": "address"},
+                        {"internalType": "uint256", "name": "debtToCover", "type": "uint256"},
+                        {"internalType": "bool", "name": "receiveAToken", "type": "bool"},
+                        {"internalType": "uint256", "name": "collateralAssetId", "type": "uint256"},
+                        {"internalType": "uint256", "name": "debtAssetId", "type": "uint256"},
+                        {"internalType": "uint256", "name": "minProfit", "type": "uint256"},
+                        {"internalType": "address", "name": "swapTarget", "type": "address"},
+                        {"internalType": "address", "name": "swapSpender", "type": "address"},
+                        {"internalType": "bytes", "name": "swapData", "type": "bytes"},
+                        {"internalType": "uint256", "name": "swapMinAmountOut", "type": "uint256"}
+                    ],
+                    "internalType": "struct AaveLiquidator.LiquidationParams",
+                    "name": "params",
+                    "type": "tuple"
+                }
+            ],
+            "name": "liquidateWithSwap",
+            "outputs": [],
+            "stateMutability": "nonpayable",
+            "type": "function"
+        },
         {
             "inputs": [],
             "name": "getPool",
@@ -403,3 +1569,250 @@ fn get_liquidator_abi() -> Result<alloy_json_abi::JsonAbi> {
     let abi: alloy_json_abi::JsonAbi = serde_json::from_str(abi_json)?;
     Ok(abi)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+    use alloy_dyn_abi::JsonAbiExt;
+    use alloy_provider::ProviderBuilder;
+
+    #[tokio::test]
+    async fn test_undeployed_liquidator_contract_fails_startup_check() {
+        let server = MockServer::json_rpc(|_| serde_json::json!("0x")).await;
+        let provider =
+            Arc::new(ProviderBuilder::new().on_http(server.url.parse().unwrap()).boxed());
+        let address = Address::repeat_byte(0x42);
+
+        let err = verify_liquidator_contract(&provider, address).await.unwrap_err();
+
+        assert!(err.to_string().contains("No contract code at liquidator contract address"));
+        assert!(err.to_string().contains(&address.to_string()));
+    }
+
+    /// Node serving a proxy at 0x42..42 whose EIP-1967 slot points at `implementation`; the
+    /// proxy answers every `eth_call` (e.g. getPool()) with an address
+    async fn spawn_proxy_chain(
+        implementation: Address,
+        implementation_code: Vec<u8>,
+    ) -> MockServer {
+        MockServer::json_rpc(move |request| {
+            let param = request["params"][0].as_str().unwrap_or("").to_string();
+            match request["method"].as_str().unwrap_or("") {
+                "eth_getCode" if param.eq_ignore_ascii_case(&implementation.to_string()) => {
+                    serde_json::json!(Bytes::from(implementation_code.clone()).to_string())
+                }
+                // Delegating proxy stub without any function selectors
+                "eth_getCode" => serde_json::json!("0x363d3d373d3d3d363d73"),
+                "eth_getStorageAt" => serde_json::json!(format!(
+                    "0x{:x}",
+                    U256::from_be_bytes(implementation.into_word().0)
+                )),
+                "eth_call" => serde_json::json!(B256::left_padding_from(&[0x11; 20]).to_string()),
+                _ => serde_json::Value::Null,
+            }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_proxy_is_verified_through_its_implementation() {
+        let selector = liquidate_selector().unwrap();
+        let mut liquidator_code = vec![0x80, PUSH4_OPCODE];
+        liquidator_code.extend_from_slice(&selector);
+        liquidator_code.push(0x14);
+        let proxy = Address::repeat_byte(0x42);
+
+        let server = spawn_proxy_chain(Address::repeat_byte(0x43), liquidator_code).await;
+        let provider =
+            Arc::new(ProviderBuilder::new().on_http(server.url.parse().unwrap()).boxed());
+        verify_liquidator_contract(&provider, proxy).await.unwrap();
+
+        // Answering getPool() isn't enough when the implementation has no liquidate
+        let server = spawn_proxy_chain(Address::repeat_byte(0x43), vec![0x60, 0x80]).await;
+        let provider =
+            Arc::new(ProviderBuilder::new().on_http(server.url.parse().unwrap()).boxed());
+        let err = verify_liquidator_contract(&provider, proxy).await.unwrap_err();
+        assert!(err.to_string().contains("does not expose liquidate"));
+
+        // Neither is a contract that isn't an EIP-1967 proxy at all
+        let server = spawn_proxy_chain(Address::ZERO, Vec::new()).await;
+        let provider =
+            Arc::new(ProviderBuilder::new().on_http(server.url.parse().unwrap()).boxed());
+        assert!(verify_liquidator_contract(&provider, proxy).await.is_err());
+    }
+
+    #[test]
+    fn test_bytecode_selector_detection() {
+        let selector = liquidate_selector().unwrap();
+
+        // Dispatcher fragment: DUP1 PUSH4 <selector> EQ
+        let mut code = vec![0x80, PUSH4_OPCODE];
+        code.extend_from_slice(&selector);
+        code.push(0x14);
+        assert!(bytecode_has_selector(&code, selector));
+
+        // The selector bytes without the PUSH4 prefix don't count
+        let mut data_only = vec![0x00];
+        data_only.extend_from_slice(&selector);
+        assert!(!bytecode_has_selector(&data_only, selector));
+    }
+
+    #[test]
+    fn test_only_the_seized_collateral_is_swapped() {
+        let before = U256::from(500u64);
+
+        // Collateral held from before stays put
+        assert_eq!(
+            seized_collateral_to_swap(before, U256::from(1_500u64), Some(U256::from(1_000u64))),
+            Some(U256::from(1_000u64))
+        );
+        // Another liquidation's collateral arriving meanwhile isn't sold with this one
+        assert_eq!(
+            seized_collateral_to_swap(before, U256::from(2_500u64), Some(U256::from(1_000u64))),
+            Some(U256::from(1_000u64))
+        );
+        // Without a fill the balance delta is all that's known
+        assert_eq!(
+            seized_collateral_to_swap(before, U256::from(800u64), None),
+            Some(U256::from(300u64))
+        );
+        // Swapped inside the flash loan: nothing arrived
+        assert_eq!(seized_collateral_to_swap(before, before, Some(U256::from(1_000u64))), None);
+    }
+
+    #[test]
+    fn test_fee_and_gas_limit_headroom() {
+        // 2x a 1 gwei tip adds 1 gwei on top of the estimated max fee
+        assert_eq!(
+            competitive_eip1559_fees(3_000_000_000, 1_000_000_000, 2),
+            (4_000_000_000, 2_000_000_000)
+        );
+        assert_eq!(competitive_eip1559_fees(3_000, 0, 2), (3_000, 0));
+        assert_eq!(with_gas_buffer(400_000), 480_000);
+    }
+
+    #[test]
+    fn test_revert_data_is_decoded() {
+        use alloy_sol_types::SolError;
+
+        let message = alloy_sol_types::Revert::from("Insufficient funds").abi_encode();
+        assert_eq!(decode_revert_data(&message), "Insufficient funds");
+
+        let panic = alloy_sol_types::Panic {
+            code: U256::from(0x11u64),
+        }
+        .abi_encode();
+        assert!(decode_revert_data(&panic).contains("overflow"));
+
+        assert_eq!(
+            decode_revert_data(&[0xde, 0xad, 0xbe, 0xef, 0x00]),
+            "custom error 0xdeadbeef"
+        );
+        assert_eq!(decode_revert_data(&[]), "empty revert data");
+    }
+
+    #[test]
+    fn test_onchain_min_profit_is_in_debt_asset_units() {
+        let opportunity = LiquidationOpportunity {
+            user: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000_000_000_000u64),
+            expected_collateral_received: U256::ZERO,
+            liquidation_bonus: U256::ZERO,
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            // 0.001 ETH of gas, $50 estimated net profit
+            gas_cost: U256::from(1_000_000_000_000_000u64),
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::from(5_000_000_000u64),
+            profit_threshold_met: true,
+        };
+        let eth_price = U256::from(300_000_000_000u64); // $3,000.00000000
+
+        // Half the profit kept ($25) plus $3 of gas is 28 USDC (6 decimals)
+        let usdc_price = U256::from(100_000_000u64);
+        assert_eq!(
+            onchain_min_profit(&opportunity, 5_000, usdc_price, 6, eth_price),
+            Some(U256::from(28_000_000u64))
+        );
+        // The same $28 repaid in WETH
+        assert_eq!(
+            onchain_min_profit(&opportunity, 5_000, eth_price, 18, eth_price),
+            Some(U256::from(9_333_333_333_333_333u64))
+        );
+        assert_eq!(onchain_min_profit(&opportunity, 5_000, U256::ZERO, 6, eth_price), None);
+    }
+
+    #[test]
+    fn test_liquidate_call_follows_flash_loan_source() {
+        let mut params = LiquidationParams {
+            user: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000u64),
+            collateral_asset_id: 1,
+            debt_asset_id: 2,
+            receive_a_token: false,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            min_profit: None,
+            aggregator_swap: None,
+        };
+        let abi = get_liquidator_abi().unwrap();
+
+        let (function, args) = liquidate_call_args(&params);
+        assert_eq!(function, "liquidate");
+        assert_eq!(args.len(), 7);
+        assert!(abi.function(function).unwrap()[0].abi_encode_input(&args).is_ok());
+
+        params.flash_loan_source = FlashLoanSource::Morpho;
+        let (function, args) = liquidate_call_args(&params);
+        assert_eq!(function, "liquidateWithFlashLoan");
+        assert_eq!(args[0], alloy_dyn_abi::DynSolValue::Uint(U256::from(2u64), 8));
+        assert!(abi.function(function).unwrap()[0].abi_encode_input(&args).is_ok());
+
+        // The minimum profit is encoded into the calldata whatever the lender
+        params.flash_loan_source = FlashLoanSource::AaveV3;
+        params.min_profit = Some(U256::from(700u64));
+        let (function, args) = liquidate_call_args(&params);
+        assert_eq!(function, "liquidateWithMinProfit");
+        assert_eq!(args.len(), 9);
+        assert_eq!(args[0], alloy_dyn_abi::DynSolValue::Uint(U256::ZERO, 8));
+        assert_eq!(args[8], alloy_dyn_abi::DynSolValue::Uint(U256::from(700u64), 256));
+        assert!(abi.function(function).unwrap()[0].abi_encode_input(&args).is_ok());
+
+        // Aggregator calldata rides along in the params struct
+        params.flash_loan_source = FlashLoanSource::Balancer;
+        params.aggregator_swap = Some(AggregatorSwap {
+            target: Address::repeat_byte(0xaa),
+            spender: Address::repeat_byte(0xbb),
+            calldata: Bytes::from(vec![0x12, 0x34]),
+            min_amount_out: U256::from(990u64),
+        });
+        let (function, args) = liquidate_call_args(&params);
+        assert_eq!(function, "liquidateWithSwap");
+        assert_eq!(args.len(), 2);
+        assert_eq!(args[0], alloy_dyn_abi::DynSolValue::Uint(U256::from(1u64), 8));
+        let alloy_dyn_abi::DynSolValue::Tuple(fields) = &args[1] else {
+            panic!("params not encoded as a tuple");
+        };
+        assert_eq!(fields[7], alloy_dyn_abi::DynSolValue::Uint(U256::from(700u64), 256));
+        assert_eq!(fields[10], alloy_dyn_abi::DynSolValue::Bytes(vec![0x12, 0x34]));
+        assert!(abi.function(function).unwrap()[0].abi_encode_input(&args).is_ok());
+    }
+
+    #[test]
+    fn test_seized_collateral_sized_in_collateral_units() {
+        // 2,100 USDC of collateral value at $1, seized as WETH at $3,000, less 1%
+        let expected = U256::from(2_100_000_000u64);
+        let usdc_price = U256::from(100_000_000u64);
+        let eth_price = U256::from(300_000_000_000u64);
+        assert_eq!(
+            seized_collateral_amount(expected, usdc_price, 6, eth_price, 18, 100),
+            Some(U256::from(693_000_000_000_000_000u64))
+        );
+        assert_eq!(seized_collateral_amount(expected, U256::ZERO, 6, eth_price, 18, 100), None);
+        assert_eq!(seized_collateral_amount(expected, usdc_price, 6, U256::ZERO, 18, 100), None);
+    }
+}
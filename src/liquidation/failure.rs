@@ -0,0 +1,269 @@
+use alloy_primitives::{hex, keccak256};
+
+/// Why a liquidation attempt failed, decoded from the revert reason or RPC error
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FailureCategory {
+    /// HEALTH_FACTOR_NOT_BELOW_THRESHOLD: repaid, topped up or liquidated by someone else first
+    PositionHealthy,
+    /// COLLATERAL_CANNOT_BE_LIQUIDATED: the asset isn't used as collateral by the user
+    CollateralNotLiquidatable,
+    /// SPECIFIED_CURRENCY_NOT_BORROWED_BY_USER: the debt was already repaid or liquidated
+    DebtNotBorrowed,
+    /// The reserve is inactive or paused, or the price oracle sentinel blocks liquidations
+    ReserveUnavailable,
+    /// The swap returned too little to repay the flash loan or clear the on-chain minimum profit
+    Slippage,
+    /// The executor wallet can't pay for gas
+    InsufficientGasFunds,
+    /// The nonce was already used or is held by a pending transaction
+    NonceConflict,
+    /// The fee was below the base fee or the replacement threshold
+    Underpriced,
+    /// Transport failure, rate limit or other RPC error
+    Rpc,
+    /// The transaction wasn't mined before the confirmation deadline
+    Timeout,
+    /// Any other revert
+    Reverted,
+    Other,
+}
+
+/// Aave V3 errors a liquidation can hit: the numeric `Error(string)` code, its name and the
+/// custom error newer pool versions revert with instead
+const AAVE_ERRORS: [(&str, &str, &str, FailureCategory); 6] = [
+    (
+        "45",
+        "HEALTH_FACTOR_NOT_BELOW_THRESHOLD",
+        "HealthFactorNotBelowThreshold()",
+        FailureCategory::PositionHealthy,
+    ),
+    (
+        "46",
+        "COLLATERAL_CANNOT_BE_LIQUIDATED",
+        "CollateralCannotBeLiquidated()",
+        FailureCategory::CollateralNotLiquidatable,
+    ),
+    (
+        "47",
+        "SPECIFIED_CURRENCY_NOT_BORROWED_BY_USER",
+        "SpecifiedCurrencyNotBorrowedByUser()",
+        FailureCategory::DebtNotBorrowed,
+    ),
+    (
+        "27",
+        "RESERVE_INACTIVE",
+        "ReserveInactive()",
+        FailureCategory::ReserveUnavailable,
+    ),
+    (
+        "29",
+        "RESERVE_PAUSED",
+        "ReservePaused()",
+        FailureCategory::ReserveUnavailable,
+    ),
+    (
+        "59",
+        "PRICE_ORACLE_SENTINEL_CHECK_FAILED",
+        "PriceOracleSentinelCheckFailed()",
+        FailureCategory::ReserveUnavailable,
+    ),
+];
+
+/// Substrings of node and liquidator contract errors, checked in order on the lowercased
+/// message once no Aave error matched
+const ERROR_PATTERNS: [(&str, FailureCategory); 18] = [
+    (
+        "insufficient funds for gas",
+        FailureCategory::InsufficientGasFunds,
+    ),
+    (
+        "replacement transaction underpriced",
+        FailureCategory::NonceConflict,
+    ),
+    ("nonce too low", FailureCategory::NonceConflict),
+    ("nonce too high", FailureCategory::NonceConflict),
+    ("already known", FailureCategory::NonceConflict),
+    ("underpriced", FailureCategory::Underpriced),
+    ("less than block base fee", FailureCategory::Underpriced),
+    ("slippage", FailureCategory::Slippage),
+    ("too little received", FailureCategory::Slippage),
+    ("profit below minimum", FailureCategory::Slippage),
+    // The liquidator contract's flash loan repayment check
+    ("insufficient funds", FailureCategory::Slippage),
+    ("confirmation timeout", FailureCategory::Timeout),
+    ("revert", FailureCategory::Reverted),
+    ("too many requests", FailureCategory::Rpc),
+    ("rate limit", FailureCategory::Rpc),
+    ("timed out", FailureCategory::Rpc),
+    ("error sending request", FailureCategory::Rpc),
+    ("connection", FailureCategory::Rpc),
+];
+
+impl FailureCategory {
+    pub const ALL: [FailureCategory; 12] = [
+        FailureCategory::PositionHealthy,
+        FailureCategory::CollateralNotLiquidatable,
+        FailureCategory::DebtNotBorrowed,
+        FailureCategory::ReserveUnavailable,
+        FailureCategory::Slippage,
+        FailureCategory::InsufficientGasFunds,
+        FailureCategory::NonceConflict,
+        FailureCategory::Underpriced,
+        FailureCategory::Rpc,
+        FailureCategory::Timeout,
+        FailureCategory::Reverted,
+        FailureCategory::Other,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FailureCategory::PositionHealthy => "position_healthy",
+            FailureCategory::CollateralNotLiquidatable => "collateral_not_liquidatable",
+            FailureCategory::DebtNotBorrowed => "debt_not_borrowed",
+            FailureCategory::ReserveUnavailable => "reserve_unavailable",
+            FailureCategory::Slippage => "slippage",
+            FailureCategory::InsufficientGasFunds => "insufficient_gas_funds",
+            FailureCategory::NonceConflict => "nonce_conflict",
+            FailureCategory::Underpriced => "underpriced",
+            FailureCategory::Rpc => "rpc",
+            FailureCategory::Timeout => "timeout",
+            FailureCategory::Reverted => "reverted",
+            FailureCategory::Other => "other",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|category| category.as_str() == name.trim())
+    }
+
+    /// Categorize a failed liquidation from its error message
+    pub fn classify(message: &str) -> Self {
+        if let Some(category) = aave_error(message) {
+            return category;
+        }
+        let lower = message.to_ascii_lowercase();
+        ERROR_PATTERNS
+            .iter()
+            .find(|(pattern, _)| lower.contains(pattern))
+            .map_or(FailureCategory::Other, |(_, category)| *category)
+    }
+
+    /// Someone else got there first. Losing a race says nothing about the bot's health, so
+    /// the circuit breaker doesn't count it as a failure.
+    pub fn is_lost_race(&self) -> bool {
+        matches!(
+            self,
+            FailureCategory::PositionHealthy | FailureCategory::DebtNotBorrowed
+        )
+    }
+
+    /// Transient submission problems a fresh attempt with new gas quotes can get past
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            FailureCategory::NonceConflict
+                | FailureCategory::Underpriced
+                | FailureCategory::Timeout
+                | FailureCategory::Rpc
+        )
+    }
+}
+
+/// The Aave error a revert message carries: a numeric code as the reason (`... reverted: 45`),
+/// the error's name, or a custom error selector (`custom error 0x...`)
+fn aave_error(message: &str) -> Option<FailureCategory> {
+    let lower = message.to_ascii_lowercase();
+    if !lower.contains("revert") && !lower.contains("custom error") {
+        return None;
+    }
+    let reason = message
+        .rsplit(": ")
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .trim_matches('"');
+
+    AAVE_ERRORS
+        .iter()
+        .find(|(code, name, custom_error, _)| {
+            reason == *code
+                || message.contains(name)
+                || lower.contains(&format!(
+                    "custom error 0x{}",
+                    hex::encode(&keccak256(custom_error.as_bytes())[..4])
+                ))
+        })
+        .map(|(_, _, _, category)| *category)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aave_errors_are_decoded() {
+        assert_eq!(
+            FailureCategory::classify("Liquidation would revert: revert: 45"),
+            FailureCategory::PositionHealthy
+        );
+        assert_eq!(
+            FailureCategory::classify("Transaction reverted: 47"),
+            FailureCategory::DebtNotBorrowed
+        );
+        assert_eq!(
+            FailureCategory::classify("execution reverted: COLLATERAL_CANNOT_BE_LIQUIDATED"),
+            FailureCategory::CollateralNotLiquidatable
+        );
+        let selector = hex::encode(&keccak256("ReservePaused()".as_bytes())[..4]);
+        assert_eq!(
+            FailureCategory::classify(&format!(
+                "Liquidation would revert: custom error 0x{}",
+                selector
+            )),
+            FailureCategory::ReserveUnavailable
+        );
+        // Other reverts are still told apart from RPC failures
+        assert_eq!(
+            FailureCategory::classify("Transaction reverted: unknown reason"),
+            FailureCategory::Reverted
+        );
+    }
+
+    #[test]
+    fn test_rpc_and_contract_errors_are_categorized() {
+        assert_eq!(
+            FailureCategory::classify("insufficient funds for gas * price + value"),
+            FailureCategory::InsufficientGasFunds
+        );
+        assert_eq!(
+            FailureCategory::classify("Transaction reverted: revert: Insufficient funds"),
+            FailureCategory::Slippage
+        );
+        assert_eq!(
+            FailureCategory::classify("replacement transaction underpriced"),
+            FailureCategory::NonceConflict
+        );
+        assert_eq!(
+            FailureCategory::classify("Transaction confirmation timeout"),
+            FailureCategory::Timeout
+        );
+        assert_eq!(
+            FailureCategory::classify("error sending request for url (https://rpc.example)"),
+            FailureCategory::Rpc
+        );
+        assert_eq!(FailureCategory::classify("boom"), FailureCategory::Other);
+
+        assert!(FailureCategory::PositionHealthy.is_lost_race());
+        assert!(FailureCategory::Rpc.is_retryable());
+        assert!(FailureCategory::Timeout.is_retryable());
+        assert!(!FailureCategory::Reverted.is_retryable());
+        for category in FailureCategory::ALL {
+            assert_eq!(
+                FailureCategory::from_name(category.as_str()),
+                Some(category)
+            );
+        }
+    }
+}
@@ -0,0 +1,205 @@
+use alloy_primitives::{Address, U256};
+use alloy_rpc_types::Log;
+use alloy_sol_types::SolEvent;
+
+use crate::models::{
+    LiquidationCall, LiquidationExecuted, LiquidationFill, LiquidationOpportunity,
+};
+
+/// Find the pool's LiquidationCall log for `opportunity` among a receipt's logs and return
+/// the debt actually covered and collateral actually seized
+pub fn decode_liquidation_call(
+    logs: &[Log],
+    opportunity: &LiquidationOpportunity,
+) -> Option<(U256, U256)> {
+    logs.iter()
+        .filter(|log| log.topics().first() == Some(&LiquidationCall::SIGNATURE_HASH))
+        .filter_map(|log| LiquidationCall::decode_log(&log.inner, true).ok())
+        .find(|event| {
+            event.user == opportunity.user
+                && event.collateralAsset == opportunity.collateral_asset
+                && event.debtAsset == opportunity.debt_asset
+        })
+        .map(|event| (event.debtToCover, event.liquidatedCollateralAmount))
+}
+
+/// Find the liquidator contract's LiquidationExecuted log for `opportunity` among a receipt's
+/// logs and return the profit it kept, in debt asset units before gas
+pub fn decode_contract_profit(
+    logs: &[Log],
+    contract: Address,
+    opportunity: &LiquidationOpportunity,
+) -> Option<U256> {
+    logs.iter()
+        .filter(|log| log.address() == contract)
+        .filter(|log| log.topics().first() == Some(&LiquidationExecuted::SIGNATURE_HASH))
+        .filter_map(|log| LiquidationExecuted::decode_log(&log.inner, true).ok())
+        .find(|event| {
+            event.user == opportunity.user
+                && event.collateralAsset == opportunity.collateral_asset
+                && event.debtAsset == opportunity.debt_asset
+        })
+        .map(|event| event.profit)
+}
+
+/// Reconcile the requested liquidation against the amounts the pool actually filled.
+///
+/// Profit before gas scales with the share of debt covered; the estimated gas cost is paid
+/// in full regardless of the fill.
+pub fn reconcile_fill(
+    opportunity: &LiquidationOpportunity,
+    debt_covered: U256,
+    collateral_received: U256,
+) -> LiquidationFill {
+    let gross_profit = opportunity
+        .estimated_profit
+        .saturating_add(opportunity.gas_cost);
+    let realized_gross = if opportunity.debt_to_cover.is_zero() {
+        gross_profit
+    } else {
+        gross_profit.saturating_mul(debt_covered) / opportunity.debt_to_cover
+    };
+
+    LiquidationFill {
+        requested_debt: opportunity.debt_to_cover,
+        debt_covered,
+        expected_collateral: opportunity.expected_collateral_received,
+        collateral_received,
+        realized_profit: realized_gross.saturating_sub(opportunity.gas_cost),
+    }
+}
+
+/// Decode and reconcile the fill from a receipt's logs
+pub fn fill_from_logs(logs: &[Log], opportunity: &LiquidationOpportunity) -> Option<LiquidationFill> {
+    decode_liquidation_call(logs, opportunity).map(|(debt_covered, collateral_received)| {
+        reconcile_fill(opportunity, debt_covered, collateral_received)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FlashLoanSource;
+
+    fn opportunity() -> LiquidationOpportunity {
+        LiquidationOpportunity {
+            user: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000u64),
+            expected_collateral_received: U256::from(1_050u64),
+            liquidation_bonus: U256::from(50u64),
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            gas_cost: U256::from(10u64),
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::from(40u64),
+            profit_threshold_met: true,
+        }
+    }
+
+    fn liquidation_call_log(
+        opportunity: &LiquidationOpportunity,
+        debt_covered: U256,
+        collateral: U256,
+    ) -> Log {
+        let event = LiquidationCall {
+            collateralAsset: opportunity.collateral_asset,
+            debtAsset: opportunity.debt_asset,
+            user: opportunity.user,
+            debtToCover: debt_covered,
+            liquidatedCollateralAmount: collateral,
+            liquidator: Address::repeat_byte(0x04),
+            receiveAToken: false,
+        };
+        Log {
+            inner: alloy_primitives::Log {
+                address: Address::repeat_byte(0xaa),
+                data: event.encode_log_data(),
+            },
+            ..Default::default()
+        }
+    }
+
+    fn liquidation_executed_log(
+        contract: Address,
+        opportunity: &LiquidationOpportunity,
+        profit: U256,
+    ) -> Log {
+        let event = LiquidationExecuted {
+            user: opportunity.user,
+            collateralAsset: opportunity.collateral_asset,
+            debtAsset: opportunity.debt_asset,
+            debtCovered: opportunity.debt_to_cover,
+            collateralReceived: opportunity.expected_collateral_received,
+            profit,
+        };
+        Log {
+            inner: alloy_primitives::Log {
+                address: contract,
+                data: event.encode_log_data(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_partial_fill_reconciled_from_receipt_logs() {
+        let opportunity = opportunity();
+        // Pool only covered 60% of the requested debt
+        let logs = vec![liquidation_call_log(
+            &opportunity,
+            U256::from(600u64),
+            U256::from(630u64),
+        )];
+
+        let fill = fill_from_logs(&logs, &opportunity).unwrap();
+
+        assert!(fill.is_partial());
+        assert_eq!(fill.requested_debt, U256::from(1_000u64));
+        assert_eq!(fill.debt_covered, U256::from(600u64));
+        assert_eq!(fill.collateral_received, U256::from(630u64));
+        assert_eq!(fill.fill_bps(), U256::from(6_000u64));
+        // (40 + 10) * 60% - 10 gas
+        assert_eq!(fill.realized_profit, U256::from(20u64));
+        assert!(fill.realized_profit < opportunity.estimated_profit);
+    }
+
+    #[test]
+    fn test_full_fill_keeps_estimated_profit() {
+        let opportunity = opportunity();
+        let fill = reconcile_fill(&opportunity, U256::from(1_000u64), U256::from(1_050u64));
+
+        assert!(!fill.is_partial());
+        assert_eq!(fill.realized_profit, opportunity.estimated_profit);
+    }
+
+    #[test]
+    fn test_other_liquidations_in_receipt_are_ignored() {
+        let opportunity = opportunity();
+        let mut other = opportunity.clone();
+        other.user = Address::repeat_byte(0x09);
+        let logs = vec![liquidation_call_log(&other, U256::from(1u64), U256::from(1u64))];
+
+        assert!(fill_from_logs(&logs, &opportunity).is_none());
+    }
+
+    #[test]
+    fn test_contract_profit_decoded_from_our_contract_only() {
+        let opportunity = opportunity();
+        let contract = Address::repeat_byte(0xcc);
+        let mut other = opportunity.clone();
+        other.user = Address::repeat_byte(0x09);
+        let logs = vec![
+            liquidation_executed_log(Address::repeat_byte(0xdd), &opportunity, U256::from(99u64)),
+            liquidation_executed_log(contract, &other, U256::from(77u64)),
+            liquidation_executed_log(contract, &opportunity, U256::from(45u64)),
+        ];
+
+        assert_eq!(
+            decode_contract_profit(&logs, contract, &opportunity),
+            Some(U256::from(45u64))
+        );
+        assert_eq!(decode_contract_profit(&logs[..2], contract, &opportunity), None);
+    }
+}
@@ -0,0 +1,362 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::sol;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use eyre::Result;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use super::profit_recheck::fetch_token_balance;
+use crate::models::{FlashLoanSource, LiquidationAssetConfig};
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::networks::NetworkAddresses;
+use crate::protocols::call_contract;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IAaveProtocolDataProvider {
+        function getReserveTokensAddresses(address asset) external view returns (
+            address aTokenAddress,
+            address stableDebtTokenAddress,
+            address variableDebtTokenAddress
+        );
+    }
+}
+
+/// Aave V3 flash loan premium (0.05%)
+pub const AAVE_V3_FLASH_LOAN_FEE_BPS: u64 = 5;
+
+/// Balancer V2 Vault (same address on every chain)
+pub const BALANCER_VAULT: &str = "0xBA12222222228d8Ba445958a75a0704d566BF2C8";
+
+/// Fee charged on a flash loan of `amount` at `fee_bps`
+pub fn flash_loan_fee(amount: U256, fee_bps: u64) -> U256 {
+    amount.saturating_mul(U256::from(fee_bps)) / U256::from(10_000u64)
+}
+
+/// Convert an amount in the oracle's base currency into `decimals` token units at `price`
+pub fn base_to_token_amount(amount_base: U256, price: U256, decimals: u8) -> Option<U256> {
+    if price.is_zero() {
+        return None;
+    }
+    let unit = U256::from(10u64).pow(U256::from(decimals));
+    Some(amount_base.saturating_mul(unit) / price)
+}
+
+/// A lender the liquidator contract can borrow the debt asset from
+#[async_trait]
+pub trait FlashLoanProvider: Send + Sync {
+    fn source(&self) -> FlashLoanSource;
+
+    /// Contract the loan is taken from
+    fn lender(&self) -> Address;
+
+    fn fee_bps(&self) -> u64;
+
+    /// Amount of `asset` the lender can lend right now
+    async fn available_liquidity(&self, asset: Address) -> Result<U256>;
+}
+
+/// Aave V3 Pool flash loans; liquidity is the underlying held by the reserve's aToken
+pub struct AaveV3FlashLoan<P> {
+    provider: Arc<P>,
+    pool: Address,
+    data_provider: Address,
+    a_tokens: DashMap<Address, Address>,
+}
+
+impl<P> AaveV3FlashLoan<P>
+where
+    P: Provider,
+{
+    pub fn new(provider: Arc<P>, pool: Address, data_provider: Address) -> Self {
+        Self {
+            provider,
+            pool,
+            data_provider,
+            a_tokens: DashMap::new(),
+        }
+    }
+
+    async fn a_token(&self, asset: Address) -> Result<Address> {
+        if let Some(a_token) = self.a_tokens.get(&asset) {
+            return Ok(*a_token);
+        }
+        let a_token = call_contract(
+            self.provider.as_ref(),
+            self.data_provider,
+            &IAaveProtocolDataProvider::getReserveTokensAddressesCall { asset },
+        )
+        .await?
+        .aTokenAddress;
+        self.a_tokens.insert(asset, a_token);
+        Ok(a_token)
+    }
+}
+
+#[async_trait]
+impl<P> FlashLoanProvider for AaveV3FlashLoan<P>
+where
+    P: Provider,
+{
+    fn source(&self) -> FlashLoanSource {
+        FlashLoanSource::AaveV3
+    }
+
+    fn lender(&self) -> Address {
+        self.pool
+    }
+
+    fn fee_bps(&self) -> u64 {
+        AAVE_V3_FLASH_LOAN_FEE_BPS
+    }
+
+    async fn available_liquidity(&self, asset: Address) -> Result<U256> {
+        let a_token = self.a_token(asset).await?;
+        fetch_token_balance(self.provider.as_ref(), asset, a_token).await
+    }
+}
+
+/// Fee-free lender whose liquidity is simply its own token balance (Balancer Vault, Morpho Blue)
+pub struct VaultFlashLoan<P> {
+    provider: Arc<P>,
+    source: FlashLoanSource,
+    vault: Address,
+}
+
+impl<P> VaultFlashLoan<P>
+where
+    P: Provider,
+{
+    pub fn balancer(provider: Arc<P>, vault: Address) -> Self {
+        Self {
+            provider,
+            source: FlashLoanSource::Balancer,
+            vault,
+        }
+    }
+
+    pub fn morpho(provider: Arc<P>, morpho: Address) -> Self {
+        Self {
+            provider,
+            source: FlashLoanSource::Morpho,
+            vault: morpho,
+        }
+    }
+}
+
+#[async_trait]
+impl<P> FlashLoanProvider for VaultFlashLoan<P>
+where
+    P: Provider,
+{
+    fn source(&self) -> FlashLoanSource {
+        self.source
+    }
+
+    fn lender(&self) -> Address {
+        self.vault
+    }
+
+    fn fee_bps(&self) -> u64 {
+        0
+    }
+
+    async fn available_liquidity(&self, asset: Address) -> Result<U256> {
+        fetch_token_balance(self.provider.as_ref(), asset, self.vault).await
+    }
+}
+
+/// Build the configured lenders, in configuration order
+pub fn build_flash_loan_providers<P>(
+    provider: Arc<P>,
+    network: &NetworkAddresses,
+    sources: &[FlashLoanSource],
+) -> Result<Vec<Arc<dyn FlashLoanProvider>>>
+where
+    P: Provider + 'static,
+{
+    let mut providers: Vec<Arc<dyn FlashLoanProvider>> = Vec::new();
+    for source in sources {
+        let lender: Arc<dyn FlashLoanProvider> = match source {
+            FlashLoanSource::AaveV3 => Arc::new(AaveV3FlashLoan::new(
+                provider.clone(),
+                network.pool,
+                network.data_provider,
+            )),
+            FlashLoanSource::Balancer => {
+                Arc::new(VaultFlashLoan::balancer(provider.clone(), BALANCER_VAULT.parse()?))
+            }
+            FlashLoanSource::Morpho => {
+                let morpho = network.morpho_blue.ok_or_else(|| {
+                    eyre::eyre!(
+                        "Morpho flash loans are configured but Morpho Blue has no known address on this chain - set MORPHO_BLUE_ADDRESS"
+                    )
+                })?;
+                Arc::new(VaultFlashLoan::morpho(provider.clone(), morpho))
+            }
+        };
+        providers.push(lender);
+    }
+    Ok(providers)
+}
+
+/// Lender chosen for one liquidation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FlashLoanChoice {
+    pub source: FlashLoanSource,
+    pub fee_bps: u64,
+}
+
+impl FlashLoanChoice {
+    /// Aave V3, the lender every liquidator contract deployment supports
+    pub fn aave_v3() -> Self {
+        Self {
+            source: FlashLoanSource::AaveV3,
+            fee_bps: AAVE_V3_FLASH_LOAN_FEE_BPS,
+        }
+    }
+}
+
+/// Cheapest of `candidates` (source, fee bps, available liquidity) able to lend `amount`;
+/// ties keep configuration order
+pub fn cheapest_with_liquidity(
+    candidates: &[(FlashLoanSource, u64, U256)],
+    amount: U256,
+) -> Option<FlashLoanChoice> {
+    candidates
+        .iter()
+        .filter(|(_, _, liquidity)| *liquidity >= amount)
+        .min_by_key(|(_, fee_bps, _)| *fee_bps)
+        .map(|(source, fee_bps, _)| FlashLoanChoice {
+            source: *source,
+            fee_bps: *fee_bps,
+        })
+}
+
+/// Pick the cheapest configured lender with enough `debt_asset` liquidity to lend
+/// `debt_to_cover_base` (oracle base currency). Falls back to Aave V3 when no lender is
+/// configured, the amount can't be priced, or none reports enough liquidity.
+pub async fn select_flash_loan_provider<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    providers: &[Arc<dyn FlashLoanProvider>],
+    debt_asset: &LiquidationAssetConfig,
+    debt_to_cover_base: U256,
+) -> FlashLoanChoice
+where
+    P: Provider,
+{
+    // A single lender needs no liquidity comparison
+    match providers {
+        [] => return FlashLoanChoice::aave_v3(),
+        [only] => {
+            return FlashLoanChoice {
+                source: only.source(),
+                fee_bps: only.fee_bps(),
+            }
+        }
+        _ => {}
+    }
+
+    let amount = match fetch_oracle_prices(provider, network, &[debt_asset.address]).await {
+        Ok(prices) => base_to_token_amount(debt_to_cover_base, prices[0], debt_asset.decimals),
+        Err(e) => {
+            warn!("Failed to price {} flash loan amount: {}", debt_asset.symbol, e);
+            None
+        }
+    };
+    let Some(amount) = amount else {
+        return FlashLoanChoice::aave_v3();
+    };
+
+    let mut candidates = Vec::with_capacity(providers.len());
+    for lender in providers {
+        match lender.available_liquidity(debt_asset.address).await {
+            Ok(liquidity) => {
+                candidates.push((lender.source(), lender.fee_bps(), liquidity));
+            }
+            Err(e) => warn!(
+                "Failed to read {} flash loan liquidity for {}: {}",
+                lender.source().as_str(),
+                debt_asset.symbol,
+                e
+            ),
+        }
+    }
+
+    match cheapest_with_liquidity(&candidates, amount) {
+        Some(choice) => {
+            debug!(
+                "Flash loan for {} {}: {} ({} bps)",
+                amount,
+                debt_asset.symbol,
+                choice.source.as_str(),
+                choice.fee_bps
+            );
+            choice
+        }
+        None => {
+            warn!(
+                "No configured flash loan lender has {} {} available, assuming Aave V3",
+                amount, debt_asset.symbol
+            );
+            FlashLoanChoice::aave_v3()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cheapest_lender_with_liquidity_wins() {
+        let amount = U256::from(1_000u64);
+        let candidates = [
+            (FlashLoanSource::AaveV3, 5, U256::from(10_000u64)),
+            (FlashLoanSource::Balancer, 0, U256::from(500u64)),
+            (FlashLoanSource::Morpho, 0, U256::from(2_000u64)),
+        ];
+
+        // Balancer is free but too shallow; Morpho is free and deep enough
+        let choice = cheapest_with_liquidity(&candidates, amount).unwrap();
+        assert_eq!(choice.source, FlashLoanSource::Morpho);
+        assert_eq!(choice.fee_bps, 0);
+
+        // Only Aave can lend this much
+        let choice = cheapest_with_liquidity(&candidates, U256::from(5_000u64)).unwrap();
+        assert_eq!(choice.source, FlashLoanSource::AaveV3);
+
+        assert!(cheapest_with_liquidity(&candidates, U256::from(20_000u64)).is_none());
+    }
+
+    #[test]
+    fn test_equal_fees_keep_configuration_order() {
+        let candidates = [
+            (FlashLoanSource::Balancer, 0, U256::from(2_000u64)),
+            (FlashLoanSource::Morpho, 0, U256::from(2_000u64)),
+        ];
+        let choice = cheapest_with_liquidity(&candidates, U256::from(1_000u64)).unwrap();
+        assert_eq!(choice.source, FlashLoanSource::Balancer);
+    }
+
+    #[test]
+    fn test_fee_and_amount_conversion() {
+        assert_eq!(
+            flash_loan_fee(U256::from(1_000_000u64), AAVE_V3_FLASH_LOAN_FEE_BPS),
+            U256::from(500u64)
+        );
+        assert_eq!(flash_loan_fee(U256::from(1_000_000u64), 0), U256::ZERO);
+
+        // $2,000 (8 decimal base) of USDC priced at $1
+        let usdc = base_to_token_amount(
+            U256::from(200_000_000_000u64),
+            U256::from(100_000_000u64),
+            6,
+        );
+        assert_eq!(usdc, Some(U256::from(2_000_000_000u64)));
+        assert_eq!(base_to_token_amount(U256::from(1u64), U256::ZERO, 6), None);
+    }
+}
@@ -0,0 +1,327 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use dashmap::DashMap;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use super::approvals::ApprovalManager;
+use super::flash_loan::base_to_token_amount;
+use super::profit_recheck::fetch_token_balance;
+use super::profitability::BASE_GAS_LIMIT;
+use crate::models::{
+    ApprovalMode, LiquidationAssetConfig, LiquidationOpportunity, LiquidationParams,
+};
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::networks::NetworkAddresses;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IAavePoolLiquidation {
+        function liquidationCall(
+            address collateralAsset,
+            address debtAsset,
+            address user,
+            uint256 debtToCover,
+            bool receiveAToken
+        ) external;
+    }
+}
+
+/// Gas of calling Pool.liquidationCall straight from the wallet
+const WALLET_LIQUIDATION_GAS: u64 = 350_000;
+
+/// Gas of converting the seized collateral afterwards; the liquidator contract swaps inside
+/// the flash loan, a wallet-funded liquidation needs a transaction of its own
+const COLLATERAL_SWAP_GAS: u64 = 180_000;
+
+/// Estimated cost of executing one opportunity each way
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RouteCosts {
+    /// Flash loan fee plus the liquidator contract's gas
+    pub flash_loan: U256,
+    /// Gas of a direct liquidation, plus the collateral swap when one is needed
+    pub wallet: U256,
+}
+
+impl RouteCosts {
+    pub fn wallet_is_cheaper(&self) -> bool {
+        self.wallet < self.flash_loan
+    }
+}
+
+/// Costs of `opportunity` through the liquidator contract and straight from the wallet. The
+/// opportunity's gas cost was priced for the contract route, so the wallet route's gas is
+/// scaled from it at the same gas price.
+pub fn route_costs(opportunity: &LiquidationOpportunity, needs_swap: bool) -> RouteCosts {
+    let wallet_gas = WALLET_LIQUIDATION_GAS + if needs_swap { COLLATERAL_SWAP_GAS } else { 0 };
+    RouteCosts {
+        flash_loan: opportunity
+            .flash_loan_fee
+            .saturating_add(opportunity.gas_cost),
+        wallet: opportunity.gas_cost.saturating_mul(U256::from(wallet_gas))
+            / U256::from(BASE_GAS_LIMIT),
+    }
+}
+
+/// (wallet, token)
+type ReservationKey = (Address, Address);
+
+/// Wallet balance set aside for one liquidation; released when dropped
+#[derive(Debug)]
+pub struct FundsReservation {
+    reserved: Arc<DashMap<ReservationKey, U256>>,
+    key: ReservationKey,
+    amount: U256,
+}
+
+impl FundsReservation {
+    pub fn token(&self) -> Address {
+        self.key.1
+    }
+
+    /// Debt asset units the liquidation repays
+    pub fn amount(&self) -> U256 {
+        self.amount
+    }
+}
+
+impl Drop for FundsReservation {
+    fn drop(&mut self) {
+        if let Some(mut reserved) = self.reserved.get_mut(&self.key) {
+            *reserved = reserved.saturating_sub(self.amount);
+        }
+        self.reserved
+            .remove_if(&self.key, |_, reserved| reserved.is_zero());
+    }
+}
+
+/// Pays for small liquidations out of the executor wallet's own debt asset balance when that
+/// is cheaper than a flash loan round trip. Balances committed to liquidations still in flight
+/// are reserved, so concurrent liquidations from one wallet never count the same funds twice.
+pub struct WalletFunding {
+    pool: Address,
+    /// Per-symbol balances always left in the wallet (MIN_TOKEN_BALANCES)
+    keep: BTreeMap<String, U256>,
+    reserved: Arc<DashMap<ReservationKey, U256>>,
+    approvals: ApprovalManager,
+}
+
+impl WalletFunding {
+    pub fn new(pool: Address, keep: BTreeMap<String, U256>, approval_mode: ApprovalMode) -> Self {
+        Self {
+            pool,
+            keep,
+            reserved: Arc::new(DashMap::new()),
+            approvals: ApprovalManager::new(approval_mode),
+        }
+    }
+
+    /// Pool the wallet repays through, and has to approve for the debt asset
+    pub fn pool(&self) -> Address {
+        self.pool
+    }
+
+    pub fn approvals(&self) -> &ApprovalManager {
+        &self.approvals
+    }
+
+    /// Balance of `token` held back for liquidations of `wallet` in flight
+    pub fn reserved(&self, wallet: Address, token: Address) -> U256 {
+        self.reserved
+            .get(&(wallet, token))
+            .map_or(U256::ZERO, |reserved| *reserved)
+    }
+
+    /// Reserve `amount` of `wallet`'s `token` if its `balance`, less what must stay in the
+    /// wallet and what is already reserved, covers it
+    pub fn try_reserve(
+        &self,
+        wallet: Address,
+        token: Address,
+        balance: U256,
+        keep: U256,
+        amount: U256,
+    ) -> Option<FundsReservation> {
+        if amount.is_zero() {
+            return None;
+        }
+        let key = (wallet, token);
+        let covered = {
+            let mut reserved = self.reserved.entry(key).or_default();
+            let available = balance.saturating_sub(keep).saturating_sub(*reserved);
+            if available >= amount {
+                *reserved = reserved.saturating_add(amount);
+            }
+            available >= amount
+        };
+        if !covered {
+            self.reserved
+                .remove_if(&key, |_, reserved| reserved.is_zero());
+            return None;
+        }
+        Some(FundsReservation {
+            reserved: self.reserved.clone(),
+            key,
+            amount,
+        })
+    }
+
+    /// Reserve the funds to liquidate `opportunity` from `wallet` when that is the cheaper
+    /// route and the wallet holds enough unreserved debt asset. None keeps the flash loan.
+    /// Collateral taken as aTokens (`receive_a_token`) needs no swap afterwards.
+    pub async fn select_route<P>(
+        &self,
+        provider: &P,
+        network: &NetworkAddresses,
+        opportunity: &LiquidationOpportunity,
+        debt_asset: &LiquidationAssetConfig,
+        wallet: Address,
+        receive_a_token: bool,
+    ) -> Option<FundsReservation>
+    where
+        P: Provider,
+    {
+        let needs_swap = !receive_a_token && opportunity.collateral_asset != opportunity.debt_asset;
+        let costs = route_costs(opportunity, needs_swap);
+        if !costs.wallet_is_cheaper() {
+            debug!(
+                "Flash loan is cheaper for {:?} ({} vs {} from the wallet)",
+                opportunity.user, costs.flash_loan, costs.wallet
+            );
+            return None;
+        }
+
+        let amount = match fetch_oracle_prices(provider, network, &[debt_asset.address]).await {
+            Ok(prices) => {
+                base_to_token_amount(opportunity.debt_to_cover, prices[0], debt_asset.decimals)?
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to price {} for wallet funding: {}",
+                    debt_asset.symbol, e
+                );
+                return None;
+            }
+        };
+        let balance = match fetch_token_balance(provider, debt_asset.address, wallet).await {
+            Ok(balance) => balance,
+            Err(e) => {
+                warn!(
+                    "Failed to read {} balance of {}: {}",
+                    debt_asset.symbol, wallet, e
+                );
+                return None;
+            }
+        };
+        let keep = self
+            .keep
+            .get(&debt_asset.symbol.to_uppercase())
+            .copied()
+            .unwrap_or_default();
+
+        let Some(reservation) = self.try_reserve(wallet, debt_asset.address, balance, keep, amount)
+        else {
+            debug!(
+                "Wallet {} can't fund {} {} (balance {}, {} reserved) - using a flash loan",
+                wallet,
+                amount,
+                debt_asset.symbol,
+                balance,
+                self.reserved(wallet, debt_asset.address)
+            );
+            return None;
+        };
+        info!(
+            "💳 Paying {} {} for {:?} from wallet {} instead of a flash loan (cost {} vs {})",
+            amount, debt_asset.symbol, opportunity.user, wallet, costs.wallet, costs.flash_loan
+        );
+        Some(reservation)
+    }
+
+    /// Pool.liquidationCall for `params`, repaying `amount` of the debt from `wallet`
+    pub fn liquidation_call(
+        &self,
+        wallet: Address,
+        params: &LiquidationParams,
+        amount: U256,
+    ) -> TransactionRequest {
+        let call = IAavePoolLiquidation::liquidationCallCall {
+            collateralAsset: params.collateral_asset,
+            debtAsset: params.debt_asset,
+            user: params.user,
+            debtToCover: amount,
+            receiveAToken: params.receive_a_token,
+        };
+        TransactionRequest::default()
+            .from(wallet)
+            .to(self.pool)
+            .input(call.abi_encode().into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FlashLoanSource;
+
+    #[test]
+    fn test_small_liquidations_are_cheaper_from_the_wallet() {
+        let opportunity = |flash_loan_fee: u64| LiquidationOpportunity {
+            user: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000_000u64),
+            expected_collateral_received: U256::ZERO,
+            liquidation_bonus: U256::ZERO,
+            flash_loan_fee: U256::from(flash_loan_fee),
+            flash_loan_source: FlashLoanSource::AaveV3,
+            gas_cost: U256::from(800_000u64),
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::ZERO,
+            profit_threshold_met: true,
+        };
+
+        // 350k gas plus a 180k gas swap beats 800k gas through the contract
+        let costs = route_costs(&opportunity(0), true);
+        assert_eq!(costs.wallet, U256::from(530_000u64));
+        assert!(costs.wallet_is_cheaper());
+        assert_eq!(
+            route_costs(&opportunity(500), false).flash_loan,
+            U256::from(800_500u64)
+        );
+    }
+
+    #[test]
+    fn test_reserved_funds_are_not_committed_twice() {
+        let funding = WalletFunding::new(
+            Address::repeat_byte(0xaa),
+            BTreeMap::new(),
+            ApprovalMode::Exact,
+        );
+        let wallet = Address::repeat_byte(0x01);
+        let usdc = Address::repeat_byte(0x02);
+        let balance = U256::from(1_000u64);
+        let keep = U256::from(100u64);
+
+        let first = funding
+            .try_reserve(wallet, usdc, balance, keep, U256::from(600u64))
+            .unwrap();
+        // 900 spendable, 600 of it already committed
+        assert!(funding
+            .try_reserve(wallet, usdc, balance, keep, U256::from(400u64))
+            .is_none());
+        let second = funding
+            .try_reserve(wallet, usdc, balance, keep, U256::from(300u64))
+            .unwrap();
+        assert_eq!(funding.reserved(wallet, usdc), U256::from(900u64));
+
+        drop(first);
+        assert_eq!(funding.reserved(wallet, usdc), U256::from(300u64));
+        drop(second);
+        assert_eq!(funding.reserved(wallet, usdc), U256::ZERO);
+        assert!(funding.reserved.is_empty());
+    }
+}
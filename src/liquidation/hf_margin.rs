@@ -0,0 +1,89 @@
+use alloy_primitives::U256;
+
+/// Health factor at which a position becomes liquidatable on Aave (1.0 in 18 decimals)
+const LIQUIDATION_THRESHOLD: u64 = 1_000_000_000_000_000_000;
+
+/// Where a position's health factor sits relative to the liquidation threshold and the
+/// configured execution margin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HealthFactorBand {
+    /// Health factor is at or above 1.0 - nothing to do
+    Healthy,
+    /// Health factor is below 1.0 but within the margin - watch closely, don't act yet
+    Watch,
+    /// Health factor is below `1.0 - margin` - execute the liquidation
+    Execute,
+}
+
+/// Health factor below which liquidations are executed (`1.0 - margin`, saturating at zero)
+pub fn execution_threshold(margin: U256) -> U256 {
+    U256::from(LIQUIDATION_THRESHOLD).saturating_sub(margin)
+}
+
+/// Classify a health factor into the healthy, watch or execute band.
+///
+/// Positions only barely below 1.0 are likely to revert once interest accrues between
+/// detection and inclusion, so they are watched rather than executed. A margin of 0
+/// executes everything below 1.0.
+pub fn classify(health_factor: U256, margin: U256) -> HealthFactorBand {
+    if health_factor >= U256::from(LIQUIDATION_THRESHOLD) {
+        HealthFactorBand::Healthy
+    } else if health_factor < execution_threshold(margin) {
+        HealthFactorBand::Execute
+    } else {
+        HealthFactorBand::Watch
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0.001 (10bp) margin in 18 decimals
+    const MARGIN: u64 = 1_000_000_000_000_000;
+
+    #[test]
+    fn test_zero_margin_executes_everything_below_one() {
+        let hf = U256::from(999_999_999_999_999_999u64);
+
+        assert_eq!(classify(hf, U256::ZERO), HealthFactorBand::Execute);
+        assert_eq!(
+            classify(U256::from(LIQUIDATION_THRESHOLD), U256::ZERO),
+            HealthFactorBand::Healthy
+        );
+    }
+
+    #[test]
+    fn test_position_in_margin_band_is_watched_not_executed() {
+        let margin = U256::from(MARGIN);
+        // 0.9995 - below 1.0 but above the 0.999 execution threshold
+        let hf = U256::from(999_500_000_000_000_000u64);
+
+        assert_eq!(classify(hf, margin), HealthFactorBand::Watch);
+        // Exactly at the execution threshold is still watched
+        assert_eq!(
+            classify(execution_threshold(margin), margin),
+            HealthFactorBand::Watch
+        );
+    }
+
+    #[test]
+    fn test_position_below_margin_is_executed() {
+        let margin = U256::from(MARGIN);
+        let hf = U256::from(998_000_000_000_000_000u64);
+
+        assert_eq!(
+            execution_threshold(margin),
+            U256::from(999_000_000_000_000_000u64)
+        );
+        assert_eq!(classify(hf, margin), HealthFactorBand::Execute);
+    }
+
+    #[test]
+    fn test_oversized_margin_saturates() {
+        let margin = U256::from(2 * LIQUIDATION_THRESHOLD);
+
+        assert_eq!(execution_threshold(margin), U256::ZERO);
+        assert_eq!(classify(U256::from(1u64), margin), HealthFactorBand::Watch);
+    }
+}
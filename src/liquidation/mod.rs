@@ -1,13 +1,61 @@
+pub mod a_token;
+pub mod aggregator;
+pub mod approvals;
 pub mod assets;
+pub mod emode;
 pub mod executor;
+pub mod failure;
+pub mod fill;
+pub mod flash_loan;
+pub mod funding;
+pub mod hf_margin;
 pub mod opportunity;
+pub mod pool_version;
+pub mod position_age;
+pub mod profit_recheck;
 pub mod profitability;
+pub mod queue;
+pub mod racing;
+pub mod reserve_config;
+pub mod retry;
+pub mod submission;
+pub mod swap;
+pub mod tx_manager;
+pub mod user_locks;
+pub mod wallets;
+pub mod webhook;
+pub mod weth;
 
+pub use a_token::ATokenUnwinder;
+pub use aggregator::AggregatorClient;
+pub use approvals::ApprovalManager;
 pub use assets::{
     find_best_liquidation_pair, get_asset_config, init_base_mainnet_assets,
     init_assets_from_protocol, init_assets_from_file, load_asset_configs_from_file,
     fetch_asset_config_data, ExternalAssetConfig, AssetConfigFile
 };
+pub use emode::{EModeCategory, EModeTracker};
 pub use executor::LiquidationExecutor;
-pub use opportunity::{handle_liquidation_opportunity, handle_liquidation_opportunity_legacy};
+pub use failure::FailureCategory;
+pub use flash_loan::{build_flash_loan_providers, FlashLoanProvider};
+pub use funding::WalletFunding;
+pub use hf_margin::HealthFactorBand;
+pub use opportunity::{
+    handle_liquidation_opportunity, handle_liquidation_opportunity_legacy, LiquidationContext,
+    LiquidationThresholds,
+};
+pub use pool_version::PoolVersion;
+pub use position_age::PositionAgeTracker;
+pub use profit_recheck::ProfitRecheckMonitor;
 pub use profitability::{calculate_liquidation_profitability, validate_liquidation_opportunity};
+pub use queue::restore_persisted_queue;
+pub use racing::RacingStrategy;
+pub use reserve_config::{ReserveConfig, ReserveConfigCache};
+pub use retry::{RetryDecision, RetryPolicy};
+pub use submission::PrivateRelaySubmitter;
+pub use swap::CollateralSwapper;
+pub use tx_manager::{EscalationPolicy, TxManager};
+pub use user_locks::UserLocks;
+pub use wallets::{WalletLease, WalletPool, WalletStatus};
+pub use webhook::OpportunityWebhook;
+pub use weth::{WethBalancer, WethPolicy};
@@ -4,26 +4,183 @@ use alloy_primitives::{Address, U256};
 use alloy_provider::Provider;
 use dashmap::DashMap;
 use eyre::Result;
+use std::collections::HashMap;
 use std::str::FromStr;
 use std::sync::Arc;
 use tracing::{debug, error, info, warn};
 
+use super::aggregator::AggregatorClient;
+use super::flash_loan::FlashLoanProvider;
+use super::funding::WalletFunding;
+use super::hf_margin::{self, HealthFactorBand};
+use super::profit_recheck::ProfitRecheckMonitor;
+use super::pool_version::PoolVersion;
+use super::profitability::PairBalances;
+use super::racing::{self, RaceBudget, RacingStrategy};
+use super::reserve_config::{self, ReserveConfig};
+use super::submission::PrivateRelaySubmitter;
+use super::swap::CollateralSwapper;
+use super::tx_manager::TxManager;
+use super::webhook::OpportunityWebhook;
+use super::weth::WethBalancer;
 use super::{assets, executor, profitability};
+use crate::accounting;
+use crate::circuit_breaker::CircuitBreaker;
 use crate::database;
+use crate::feed::{self, FeedEventKind};
+use crate::gas::GasOracle;
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::monitoring::alerts::{AlertEvent, AlertNotifier};
+use crate::monitoring::health_engine;
+use crate::monitoring::latency::{self, LatencyStage};
+use crate::monitoring::mempool::CompetitorTracker;
+use crate::monitoring::metrics;
+use crate::networks::NetworkAddresses;
+use crate::sim::{SimulationOutcome, Simulator};
 use crate::models::{
-    LiquidationAssetConfig, LiquidationOpportunity, LiquidationResult, NotNeededReason,
-    UserPosition,
+    ExecutionMode, LiquidationAssetConfig, LiquidationOpportunity, LiquidationResult,
+    NotNeededReason, ProfitabilityModel, UserPosition, UserReserve,
 };
 
-/// Find the most profitable liquidation pair by simulating all viable combinations
-async fn find_most_profitable_liquidation_pair<P>(
+/// Base-currency value of a user's collateral and debt in each of their reserves
+#[derive(Debug, Default)]
+struct ReserveValues {
+    collateral: HashMap<Address, U256>,
+    debt: HashMap<Address, U256>,
+}
+
+impl ReserveValues {
+    /// Value each balance at the oracle price; reserves without a price or asset config are
+    /// left out
+    fn new(
+        user_reserves: &[UserReserve],
+        prices: &HashMap<Address, U256>,
+        assets: &HashMap<Address, LiquidationAssetConfig>,
+    ) -> Self {
+        let mut values = Self::default();
+        for user_reserve in user_reserves {
+            let (Some(price), Some(asset)) =
+                (prices.get(&user_reserve.reserve), assets.get(&user_reserve.reserve))
+            else {
+                continue;
+            };
+            let unit = U256::from(10u64).pow(U256::from(asset.decimals));
+            let value = |balance: U256| balance.saturating_mul(*price) / unit;
+            if user_reserve.usage_as_collateral_enabled {
+                values
+                    .collateral
+                    .insert(user_reserve.reserve, value(user_reserve.collateral_balance));
+            }
+            values.debt.insert(user_reserve.reserve, value(user_reserve.debt_balance));
+        }
+        values
+    }
+
+    fn pair(&self, collateral: Address, debt: Address) -> Option<PairBalances> {
+        Some(PairBalances {
+            collateral_base: *self.collateral.get(&collateral)?,
+            debt_base: *self.debt.get(&debt)?,
+        })
+    }
+}
+
+/// The user's balances in `reserves` together with their oracle prices. Balances are read
+/// on-chain, falling back to the last stored ones; prices missing leave pairs unvalued.
+async fn load_reserve_values<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    db_pool: &DatabasePool,
+    assets: &HashMap<Address, LiquidationAssetConfig>,
+    user: Address,
+    reserves: &[Address],
+) -> ReserveValues
+where
+    P: Provider,
+{
+    let user_reserves = match health_engine::fetch_user_reserves(provider, network, user, reserves)
+        .await
+    {
+        Ok(user_reserves) => user_reserves,
+        Err(e) => {
+            warn!("Failed to read reserve balances of {:?}, using stored ones: {}", user, e);
+            database::get_user_reserves(db_pool, user).await.unwrap_or_else(|e| {
+                warn!("Failed to load stored reserve balances of {:?}: {}", user, e);
+                Vec::new()
+            })
+        }
+    };
+    let prices: HashMap<Address, U256> =
+        match fetch_oracle_prices(provider, network, reserves).await {
+            Ok(prices) => reserves.iter().copied().zip(prices).collect(),
+            Err(e) => {
+                warn!("Failed to read oracle prices for {:?}: {}", user, e);
+                HashMap::new()
+            }
+        };
+    ReserveValues::new(&user_reserves, &prices, assets)
+}
+
+/// Pairs whose profit is within this share of the best pair's count as equally profitable
+const COMPARABLE_PROFIT_BPS: u64 = 100;
+
+/// Most profitable first. Among pairs within COMPARABLE_PROFIT_BPS of the best profit, the
+/// debt with the largest outstanding value in `debt_values` goes first: repaying it seizes the
+/// most collateral in one go and leaves fewer liquidations to resolve the position. Remaining
+/// ties prefer the larger repayment.
+fn rank_opportunities(
+    mut opportunities: Vec<LiquidationOpportunity>,
+    debt_values: &HashMap<Address, U256>,
+) -> Vec<LiquidationOpportunity> {
+    let debt_value = |opportunity: &LiquidationOpportunity| {
+        debt_values
+            .get(&opportunity.debt_asset)
+            .copied()
+            .unwrap_or_default()
+    };
+    opportunities.sort_by(|a, b| {
+        b.estimated_profit
+            .cmp(&a.estimated_profit)
+            .then(debt_value(b).cmp(&debt_value(a)))
+            .then(b.debt_to_cover.cmp(&a.debt_to_cover))
+    });
+
+    let Some(best_profit) = opportunities.first().map(|best| best.estimated_profit) else {
+        return opportunities;
+    };
+    let comparable_floor = best_profit
+        - best_profit.saturating_mul(U256::from(COMPARABLE_PROFIT_BPS)) / U256::from(10_000u64);
+    let comparable = opportunities
+        .iter()
+        .take_while(|opportunity| opportunity.estimated_profit >= comparable_floor)
+        .count();
+    opportunities[..comparable].sort_by(|a, b| {
+        debt_value(b)
+            .cmp(&debt_value(a))
+            .then(b.estimated_profit.cmp(&a.estimated_profit))
+            .then(b.debt_to_cover.cmp(&a.debt_to_cover))
+    });
+    opportunities
+}
+
+/// Price every viable collateral/debt pair against the user's balances in its reserves and
+/// rank them, so a pair rejected later (e.g. by simulation) can fall back to the next one
+#[allow(clippy::too_many_arguments)]
+async fn rank_liquidation_pairs<P>(
     provider: Arc<P>,
-    assets: &std::collections::HashMap<Address, LiquidationAssetConfig>,
+    network: &NetworkAddresses,
+    db_pool: &DatabasePool,
+    assets: &HashMap<Address, LiquidationAssetConfig>,
     user_collateral_assets: &[Address],
     user_debt_assets: &[Address],
+    reserve_values: &ReserveValues,
+    pool_version: PoolVersion,
     user_position: &UserPosition,
     min_profit_threshold: U256,
-) -> Result<Option<LiquidationOpportunity>>
+    profitability_model: ProfitabilityModel,
+    shadow_profitability: Option<ProfitabilityModel>,
+    shadow_divergence_threshold_bps: u64,
+    flash_loan_providers: &[Arc<dyn FlashLoanProvider>],
+) -> Result<Vec<LiquidationOpportunity>>
 where
     P: Provider,
 {
@@ -78,7 +235,7 @@ where
             }
         }
 
-        return Ok(None);
+        return Ok(Vec::new());
     }
 
     info!(
@@ -86,8 +243,7 @@ where
         viable_pairs.len()
     );
 
-    let mut best_opportunity: Option<LiquidationOpportunity> = None;
-    let mut highest_profit = U256::ZERO;
+    let mut opportunities = Vec::with_capacity(viable_pairs.len());
 
     // Simulate profitability for each viable pair
     for (collateral_addr, debt_addr) in viable_pairs {
@@ -118,10 +274,15 @@ where
         // Calculate actual profitability for this pair
         let opportunity = match profitability::calculate_liquidation_profitability(
             provider.clone(),
+            network,
             user_position,
             collateral_asset,
             debt_asset,
+            reserve_values.pair(collateral_addr, debt_addr),
+            pool_version,
             min_profit_threshold,
+            profitability_model,
+            flash_loan_providers,
         )
         .await
         {
@@ -140,20 +301,55 @@ where
             opportunity.estimated_profit, min_profit_threshold, opportunity.profit_threshold_met
         );
 
-        // Track the most profitable opportunity
-        if opportunity.estimated_profit > highest_profit {
-            highest_profit = opportunity.estimated_profit;
-            best_opportunity = Some(opportunity);
-            info!(
-                "🎯 New best pair: {} -> {} (profit: {} wei)",
-                collateral_asset.symbol, debt_asset.symbol, highest_profit
+        // Price the same pair with the shadow model; only the live model drives decisions
+        if let Some(shadow_model) = shadow_profitability {
+            let shadow_slippage = profitability::model_swap_slippage(
+                provider.as_ref(),
+                network,
+                opportunity.expected_collateral_received,
+                collateral_asset,
+                debt_asset,
+                shadow_model,
+            )
+            .await;
+            let shadow_opportunity = profitability::reprice_with_slippage(
+                &opportunity,
+                shadow_slippage,
+                min_profit_threshold,
             );
+
+            if let Some(divergence) = profitability::detect_profit_divergence(
+                &opportunity,
+                &shadow_opportunity,
+                profitability_model,
+                shadow_model,
+                shadow_divergence_threshold_bps,
+            ) {
+                warn!(
+                    "🔀 Shadow profitability divergence for {} -> {}: {} model {} wei vs {} model {} wei ({}bp)",
+                    collateral_asset.symbol,
+                    debt_asset.symbol,
+                    profitability_model.as_str(),
+                    divergence.live_profit,
+                    shadow_model.as_str(),
+                    divergence.shadow_profit,
+                    divergence.divergence_bps
+                );
+
+                if let Err(e) = database::record_profit_divergence(db_pool, &divergence).await {
+                    warn!("Failed to record shadow profitability divergence: {}", e);
+                }
+            }
         }
+
+        opportunities.push(opportunity);
     }
 
-    if let Some(ref opportunity) = best_opportunity {
+    let opportunities = rank_opportunities(opportunities, &reserve_values.debt);
+    if let Some(opportunity) = opportunities.first() {
         info!(
-            "✅ Most profitable pair selected: {} -> {} (profit: {} wei)",
+            "✅ Most profitable of {} pairs: {} -> {} (profit: {} wei)",
+            opportunities.len(),
             assets
                 .get(&opportunity.collateral_asset)
                 .map(|a| a.symbol.as_str())
@@ -165,10 +361,10 @@ where
             opportunity.estimated_profit
         );
     } else {
-        info!("❌ No profitable liquidation pairs found above threshold");
+        info!("❌ No liquidation pair could be priced");
     }
 
-    Ok(best_opportunity)
+    Ok(opportunities)
 }
 
 /// Fetch user's actual collateral and debt assets from the blockchain
@@ -265,21 +461,361 @@ where
     Ok((user_collateral_assets, user_debt_assets))
 }
 
-/// Handle a detected liquidation opportunity with real profitability calculation and execution
-pub async fn handle_liquidation_opportunity<P>(
+/// Decoded configurations of the given reserves. Reserves whose configuration can't be read
+/// are left out, which leaves them unconstrained.
+async fn fetch_user_reserve_configs<P>(
+    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    reserves: impl IntoIterator<Item = Address>,
+) -> HashMap<Address, ReserveConfig>
+where
+    P: Provider,
+{
+    let mut configs = HashMap::new();
+    for reserve in reserves {
+        if configs.contains_key(&reserve) {
+            continue;
+        }
+        match reserve_config::fetch_reserve_configuration(pool_contract, reserve).await {
+            Ok(configuration) => {
+                configs.insert(reserve, ReserveConfig::decode(configuration));
+            }
+            Err(e) => warn!("Failed to read reserve configuration of {:?}: {}", reserve, e),
+        }
+    }
+    configs
+}
+
+/// Narrow a user's collateral and debt to what the pool's isolation mode and siloed
+/// borrowing rules allow the position to contain:
+/// - in isolation mode (the only collateral has a debt ceiling) just the assets borrowable
+///   in isolation can be owed
+/// - a siloed debt can't be held next to other debts, so it is the only one to repay
+///
+/// Anything else comes from a stale or inconsistent user configuration and would make the
+/// liquidation plan diverge from what the pool executes.
+fn apply_position_constraints(
+    user_collateral_assets: Vec<Address>,
+    user_debt_assets: Vec<Address>,
+    configs: &HashMap<Address, ReserveConfig>,
+) -> (Vec<Address>, Vec<Address>) {
+    let isolated_collateral = match user_collateral_assets.as_slice() {
+        [only] if configs.get(only).is_some_and(ReserveConfig::is_isolated) => Some(*only),
+        _ => None,
+    };
+
+    let user_debt_assets: Vec<Address> = match isolated_collateral {
+        Some(collateral) => user_debt_assets
+            .into_iter()
+            .filter(|debt| {
+                let borrowable = configs
+                    .get(debt)
+                    .map_or(true, |config| config.borrowable_in_isolation);
+                if !borrowable {
+                    debug!(
+                        "Skipping debt {:?} - not borrowable against isolated collateral {:?}",
+                        debt, collateral
+                    );
+                }
+                borrowable
+            })
+            .collect(),
+        None => user_debt_assets,
+    };
+
+    let siloed: Vec<Address> = user_debt_assets
+        .iter()
+        .copied()
+        .filter(|debt| configs.get(debt).is_some_and(|config| config.siloed_borrowing))
+        .collect();
+    let user_debt_assets = match siloed.as_slice() {
+        [] => user_debt_assets,
+        [siloed_debt] => vec![*siloed_debt],
+        // Two siloed debts can't coexist; the user configuration can't be trusted
+        _ => Vec::new(),
+    };
+
+    (user_collateral_assets, user_debt_assets)
+}
+
+/// Profit and health factor thresholds a liquidation must clear. They hot-reload, so the bot
+/// reads them afresh for every opportunity.
+#[derive(Debug, Clone, Copy)]
+pub struct LiquidationThresholds {
+    pub min_profit_threshold: U256,
+    pub min_profit_pct_of_debt_bps: u64,
+    pub liquidation_hf_margin: U256,
+}
+
+/// Services and policy a liquidation runs with, shared by every opportunity the bot handles.
+/// Built once; optional services are attached with the `with_*` builders.
+pub struct LiquidationContext<P> {
     provider: Arc<P>,
-    db_pool: &DatabasePool,
+    db_pool: DatabasePool,
+    pool_contract: ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    pool_version: PoolVersion,
+    network: NetworkAddresses,
+    execution_mode: ExecutionMode,
+    profitability_model: ProfitabilityModel,
+    shadow_profitability: Option<ProfitabilityModel>,
+    shadow_divergence_threshold_bps: u64,
+    liquidator_contract: Option<Address>,
+    broadcast: bool,
+    onchain_min_profit_margin_bps: Option<u64>,
+    audit_enabled: bool,
+    strategy_label: String,
+    receive_a_token: bool,
+    opportunity_webhook: Option<OpportunityWebhook>,
+    profit_recheck: Option<Arc<ProfitRecheckMonitor>>,
+    private_relay: Option<Arc<PrivateRelaySubmitter>>,
+    collateral_swapper: Option<Arc<CollateralSwapper>>,
+    swap_aggregator: Option<Arc<AggregatorClient>>,
+    weth_balancer: Option<Arc<WethBalancer>>,
+    flash_loan_providers: Vec<Arc<dyn FlashLoanProvider>>,
+    wallet_funding: Option<Arc<WalletFunding>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    simulator: Option<Arc<Simulator>>,
+    gas_oracle: Option<Arc<GasOracle>>,
+    racing: Option<Arc<RacingStrategy>>,
+    competitors: Option<Arc<CompetitorTracker>>,
+    alert_notifier: Option<AlertNotifier>,
+}
+
+impl<P> LiquidationContext<P> {
+    /// Create a context that prices opportunities with `profitability_model` and handles
+    /// them according to `execution_mode`
+    pub fn new(
+        provider: Arc<P>,
+        db_pool: DatabasePool,
+        pool_contract: ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+        pool_version: PoolVersion,
+        network: NetworkAddresses,
+        execution_mode: ExecutionMode,
+        profitability_model: ProfitabilityModel,
+    ) -> Self {
+        Self {
+            provider,
+            db_pool,
+            pool_contract,
+            pool_version,
+            network,
+            execution_mode,
+            profitability_model,
+            shadow_profitability: None,
+            shadow_divergence_threshold_bps: 0,
+            liquidator_contract: None,
+            broadcast: false,
+            onchain_min_profit_margin_bps: None,
+            audit_enabled: false,
+            strategy_label: String::new(),
+            receive_a_token: false,
+            opportunity_webhook: None,
+            profit_recheck: None,
+            private_relay: None,
+            collateral_swapper: None,
+            swap_aggregator: None,
+            weth_balancer: None,
+            flash_loan_providers: Vec::new(),
+            wallet_funding: None,
+            circuit_breaker: None,
+            simulator: None,
+            gas_oracle: None,
+            racing: None,
+            competitors: None,
+            alert_notifier: None,
+        }
+    }
+
+    /// Execute liquidations through the liquidator contract at `address`
+    pub fn with_liquidator_contract(mut self, address: Address) -> Self {
+        self.liquidator_contract = Some(address);
+        self
+    }
+
+    /// Broadcast live liquidations rather than stopping short of submission
+    pub fn with_broadcast(mut self) -> Self {
+        self.broadcast = true;
+        self
+    }
+
+    /// Also price every opportunity with `model`, logging estimates that diverge by more
+    /// than `divergence_threshold_bps`
+    pub fn with_shadow_profitability(
+        mut self,
+        model: ProfitabilityModel,
+        divergence_threshold_bps: u64,
+    ) -> Self {
+        self.shadow_profitability = Some(model);
+        self.shadow_divergence_threshold_bps = divergence_threshold_bps;
+        self
+    }
+
+    /// Have the contract enforce the minimum profit at inclusion; see
+    /// `LiquidationExecutor::with_min_profit_guard`
+    pub fn with_min_profit_guard(mut self, margin_bps: u64) -> Self {
+        self.onchain_min_profit_margin_bps = Some(margin_bps);
+        self
+    }
+
+    /// Record two-phase audit entries for every liquidation
+    pub fn with_audit_log(mut self) -> Self {
+        self.audit_enabled = true;
+        self
+    }
+
+    /// Tag recorded liquidations with the strategy that produced them
+    pub fn with_strategy_label(mut self, label: impl Into<String>) -> Self {
+        self.strategy_label = label.into();
+        self
+    }
+
+    /// Keep seized collateral as aTokens when the debt is repaid from the wallet
+    pub fn with_receive_a_token(mut self) -> Self {
+        self.receive_a_token = true;
+        self
+    }
+
+    /// Forward validated opportunities to an external executor
+    pub fn with_opportunity_webhook(mut self, webhook: OpportunityWebhook) -> Self {
+        self.opportunity_webhook = Some(webhook);
+        self
+    }
+
+    /// Compare realized profit against the estimate once each liquidation confirms
+    pub fn with_profit_recheck(mut self, monitor: Arc<ProfitRecheckMonitor>) -> Self {
+        self.profit_recheck = Some(monitor);
+        self
+    }
+
+    /// Submit liquidations through a private relay
+    pub fn with_private_relay(mut self, relay: Arc<PrivateRelaySubmitter>) -> Self {
+        self.private_relay = Some(relay);
+        self
+    }
+
+    /// Convert collateral left with the signer after each liquidation
+    pub fn with_collateral_swap(mut self, swapper: Arc<CollateralSwapper>) -> Self {
+        self.collateral_swapper = Some(swapper);
+        self
+    }
+
+    /// Swap seized collateral through a DEX aggregator inside the flash loan
+    pub fn with_swap_aggregator(mut self, aggregator: Arc<AggregatorClient>) -> Self {
+        self.swap_aggregator = Some(aggregator);
+        self
+    }
+
+    /// Unwrap WETH for gas (or wrap surplus ETH) after each liquidation
+    pub fn with_weth_balancer(mut self, balancer: Arc<WethBalancer>) -> Self {
+        self.weth_balancer = Some(balancer);
+        self
+    }
+
+    /// Flash loan sources to price each opportunity against, cheapest first
+    pub fn with_flash_loan_providers(
+        mut self,
+        providers: Vec<Arc<dyn FlashLoanProvider>>,
+    ) -> Self {
+        self.flash_loan_providers = providers;
+        self
+    }
+
+    /// Repay small liquidations from the operator wallet when that beats a flash loan
+    pub fn with_wallet_funding(mut self, funding: Arc<WalletFunding>) -> Self {
+        self.wallet_funding = Some(funding);
+        self
+    }
+
+    /// Skip assets a circuit breaker rule has paused
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+
+    /// Simulate every liquidation before it is broadcast
+    pub fn with_simulator(mut self, simulator: Arc<Simulator>) -> Self {
+        self.simulator = Some(simulator);
+        self
+    }
+
+    /// Price transactions from fee history, with a profile scaled to each opportunity's margin
+    pub fn with_gas_oracle(mut self, gas_oracle: Arc<GasOracle>) -> Self {
+        self.gas_oracle = Some(gas_oracle);
+        self
+    }
+
+    /// Raise the priority fee with expected profit and recent win rate
+    pub fn with_racing(mut self, racing: Arc<RacingStrategy>) -> Self {
+        self.racing = Some(racing);
+        self
+    }
+
+    /// Re-bid above (or stand down from) liquidations other bots already have pending
+    pub fn with_competitor_tracker(mut self, competitors: Arc<CompetitorTracker>) -> Self {
+        self.competitors = Some(competitors);
+        self
+    }
+
+    /// Alert on every executed liquidation
+    pub fn with_alert_notifier(mut self, notifier: AlertNotifier) -> Self {
+        self.alert_notifier = Some(notifier);
+        self
+    }
+
+    /// `candidate` priced for the route it will be liquidated by. With receive_a_token, a pair
+    /// the wallet pays for keeps its collateral as aTokens and swaps nothing.
+    fn route_priced(
+        &self,
+        candidate: &LiquidationOpportunity,
+        min_profit_threshold: U256,
+    ) -> LiquidationOpportunity {
+        let a_token_route = self.receive_a_token && self.wallet_funding.is_some();
+        a_token_route
+            .then(|| profitability::price_a_token_route(candidate, min_profit_threshold))
+            .flatten()
+            .unwrap_or_else(|| candidate.clone())
+    }
+
+    /// Keep the ranked pairs that clear the profit thresholds on the route each would be
+    /// liquidated by; the ones left are fallbacks, best first
+    fn validated_candidates(
+        &self,
+        ranked: Vec<LiquidationOpportunity>,
+        thresholds: LiquidationThresholds,
+    ) -> Vec<LiquidationOpportunity> {
+        ranked
+            .into_iter()
+            .filter(|candidate| {
+                profitability::validate_liquidation_opportunity(
+                    &self.route_priced(candidate, thresholds.min_profit_threshold),
+                    thresholds.min_profit_threshold,
+                    thresholds.min_profit_pct_of_debt_bps,
+                )
+            })
+            .collect()
+    }
+}
+
+/// Handle a detected liquidation opportunity with real profitability calculation and execution.
+/// `signer` and `tx_manager` belong to the executor wallet leased for this attempt.
+pub async fn handle_liquidation_opportunity<P>(
+    ctx: &LiquidationContext<P>,
     user: Address,
-    min_profit_threshold: U256,
-    liquidator_contract_address: Option<Address>,
-    signer: Option<alloy_signer_local::PrivateKeySigner>,
-    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
-    asset_configs: &std::collections::HashMap<Address, LiquidationAssetConfig>,
-    rpc_url: &str,
+    signer: Option<crate::signer::BotSigner>,
+    tx_manager: Option<&Arc<TxManager>>,
+    asset_configs: &HashMap<Address, LiquidationAssetConfig>,
+    thresholds: LiquidationThresholds,
 ) -> Result<LiquidationResult>
 where
     P: Provider + 'static,
 {
+    let LiquidationThresholds {
+        min_profit_threshold,
+        min_profit_pct_of_debt_bps,
+        liquidation_hf_margin,
+    } = thresholds;
+    let (provider, db_pool, network) = (&ctx.provider, &ctx.db_pool, &ctx.network);
+    let pool_contract = &ctx.pool_contract;
+
     info!("🎯 LIQUIDATION OPPORTUNITY DETECTED for user: {:?}", user);
 
     // Log the opportunity detection
@@ -318,6 +854,33 @@ where
         }
     };
 
+    // Positions only barely below 1.0 are watched rather than executed
+    if hf_margin::classify(user_position.health_factor, liquidation_hf_margin)
+        == HealthFactorBand::Watch
+    {
+        info!(
+            "👀 User {:?} is within the liquidation HF margin (HF: {}, executing below {}) - watching",
+            user,
+            user_position.health_factor,
+            hf_margin::execution_threshold(liquidation_hf_margin)
+        );
+
+        database::log_monitoring_event(
+            db_pool,
+            "liquidation_deferred_hf_margin",
+            Some(user),
+            Some(&format!(
+                "Liquidation deferred: health factor {} within margin {}",
+                user_position.health_factor, liquidation_hf_margin
+            )),
+        )
+        .await?;
+
+        return Ok(LiquidationResult::NotNeeded(
+            NotNeededReason::WithinHealthFactorMargin,
+        ));
+    }
+
     // Initialize asset configurations
     // Use the passed asset_configs (with dynamic reserve indices)
 
@@ -346,37 +909,99 @@ where
         return Ok(LiquidationResult::NotNeeded(NotNeededReason::NoDebt));
     }
 
-    // Find the most profitable liquidation pair by simulating all viable combinations
-    let opportunity = match find_most_profitable_liquidation_pair(
+    // Keep only the pairs isolation mode and siloed borrowing allow the position to hold
+    let reserve_configs = fetch_user_reserve_configs(
+        pool_contract,
+        user_collateral_assets.iter().chain(&user_debt_assets).copied(),
+    )
+    .await;
+    let (user_collateral_assets, user_debt_assets) =
+        apply_position_constraints(user_collateral_assets, user_debt_assets, &reserve_configs);
+    if user_debt_assets.is_empty() {
+        info!(
+            "🏝️ Skipping liquidation of {:?} - isolation mode or siloed borrowing leaves no debt to repay",
+            user
+        );
+        return Ok(LiquidationResult::NotNeeded(
+            NotNeededReason::ProtocolConstraints,
+        ));
+    }
+
+    // Leave out assets a circuit breaker rule has paused; pairs without them stay eligible
+    let (user_collateral_assets, user_debt_assets) = match &ctx.circuit_breaker {
+        Some(circuit_breaker) => {
+            let unpaused = |assets: Vec<Address>| -> Vec<Address> {
+                assets
+                    .into_iter()
+                    .filter(|asset| !circuit_breaker.is_asset_paused(*asset))
+                    .collect()
+            };
+            (unpaused(user_collateral_assets), unpaused(user_debt_assets))
+        }
+        None => (user_collateral_assets, user_debt_assets),
+    };
+    if user_collateral_assets.is_empty() || user_debt_assets.is_empty() {
+        info!(
+            "⏸️ Skipping liquidation of {:?} - every pair touches an asset paused by the circuit breaker",
+            user
+        );
+        return Ok(LiquidationResult::NotNeeded(NotNeededReason::AssetPaused));
+    }
+
+    // Rank every viable pair by the profit it yields on the user's actual reserve balances
+    let user_reserves: Vec<Address> =
+        user_collateral_assets.iter().chain(&user_debt_assets).copied().collect();
+    let reserve_values = load_reserve_values(
+        provider.as_ref(),
+        network,
+        db_pool,
+        asset_configs,
+        user,
+        &user_reserves,
+    )
+    .await;
+    let ranked = rank_liquidation_pairs(
         provider.clone(),
+        network,
+        db_pool,
         asset_configs,
         &user_collateral_assets,
         &user_debt_assets,
+        &reserve_values,
+        ctx.pool_version,
         &user_position,
         min_profit_threshold,
+        ctx.profitability_model,
+        ctx.shadow_profitability,
+        ctx.shadow_divergence_threshold_bps,
+        &ctx.flash_loan_providers,
     )
-    .await?
-    {
-        Some(opp) => opp,
-        None => {
-            warn!("No profitable liquidation pair found for user: {:?}", user);
-            return Ok(LiquidationResult::NotNeeded(
-                NotNeededReason::NoProfitablePairs,
-            ));
-        }
+    .await?;
+    latency::mark(user, LatencyStage::ProfitabilityDone);
+    let Some(best) = ranked.first().cloned() else {
+        warn!("No profitable liquidation pair found for user: {:?}", user);
+        return Ok(LiquidationResult::NotNeeded(
+            NotNeededReason::NoProfitablePairs,
+        ));
     };
 
-    // Validate the opportunity
-    if !profitability::validate_liquidation_opportunity(&opportunity, min_profit_threshold) {
+    let candidates = ctx.validated_candidates(ranked, thresholds);
+    let Some(opportunity) = candidates.first().cloned() else {
         info!("❌ Liquidation opportunity rejected - not profitable enough");
 
+        let best = ctx.route_priced(&best, min_profit_threshold);
+        let required_profit = profitability::effective_min_profit_threshold(
+            best.debt_to_cover,
+            min_profit_threshold,
+            min_profit_pct_of_debt_bps,
+        );
         database::log_monitoring_event(
             db_pool,
             "liquidation_rejected",
             Some(user),
             Some(&format!(
                 "Liquidation rejected: profit {} < threshold {} wei",
-                opportunity.estimated_profit, min_profit_threshold
+                best.estimated_profit, required_profit
             )),
         )
         .await?;
@@ -384,33 +1009,345 @@ where
         return Ok(LiquidationResult::NotNeeded(
             NotNeededReason::InsufficientProfit,
         ));
+    };
+
+    info!(
+        "✅ Liquidation opportunity validated ({} fallback pairs) - proceeding with execution",
+        candidates.len() - 1
+    );
+
+    // Hand the opportunity to the external executor, if one is configured
+    if let Some(webhook) = &ctx.opportunity_webhook {
+        match webhook.post(&opportunity, user_position.health_factor).await {
+            Ok(()) => {
+                info!(
+                    "📤 Forwarded liquidation opportunity for {:?} to {}",
+                    user,
+                    webhook.url()
+                );
+                database::log_monitoring_event(
+                    db_pool,
+                    "liquidation_forwarded",
+                    Some(user),
+                    Some(&format!(
+                        "Opportunity forwarded to webhook. Estimated profit: {} wei",
+                        opportunity.estimated_profit
+                    )),
+                )
+                .await?;
+            }
+            Err(e) => warn!(
+                "Failed to forward liquidation opportunity for {:?} to {}: {}",
+                user,
+                webhook.url(),
+                e
+            ),
+        }
+
+        if !webhook.executes_locally() {
+            return Ok(LiquidationResult::NotNeeded(
+                NotNeededReason::ForwardedToWebhook,
+            ));
+        }
     }
 
-    info!("✅ Liquidation opportunity validated - proceeding with execution");
+    execute_opportunity(ctx, user, signer, tx_manager, asset_configs, thresholds, &candidates).await
+}
 
-    // Execute liquidation if we have the necessary components
-    match (liquidator_contract_address, signer) {
-        (Some(contract_addr), Some(signer)) => {
+/// Simulate and execute (or, in dry run mode, record) the best of the validated `candidates`,
+/// falling back to the next one when simulation rejects it
+async fn execute_opportunity<P>(
+    ctx: &LiquidationContext<P>,
+    user: Address,
+    signer: Option<crate::signer::BotSigner>,
+    tx_manager: Option<&Arc<TxManager>>,
+    asset_configs: &HashMap<Address, LiquidationAssetConfig>,
+    thresholds: LiquidationThresholds,
+    candidates: &[LiquidationOpportunity],
+) -> Result<LiquidationResult>
+where
+    P: Provider + 'static,
+{
+    let LiquidationThresholds {
+        min_profit_threshold,
+        min_profit_pct_of_debt_bps,
+        ..
+    } = thresholds;
+    let (provider, db_pool, network) = (&ctx.provider, &ctx.db_pool, &ctx.network);
+    let strategy_label = ctx.strategy_label.as_str();
+    let opportunity = &ctx.route_priced(&candidates[0], min_profit_threshold);
+
+    // Execute liquidation if we have the necessary components (and aren't only simulating)
+    match (ctx.liquidator_contract, signer) {
+        (Some(contract_addr), Some(signer)) if ctx.execution_mode != ExecutionMode::Simulate => {
+            let operator = signer.address();
             // Create liquidation executor
-            let executor = executor::LiquidationExecutor::new(
+            let mut executor = executor::LiquidationExecutor::new(
                 provider.clone(),
                 signer,
                 contract_addr,
                 asset_configs.clone(),
-                rpc_url.to_string(),
+                db_pool.chain_id,
             )?;
+            if ctx.audit_enabled {
+                executor = executor.with_audit_log(db_pool.clone());
+            }
+            if let Some(monitor) = &ctx.profit_recheck {
+                executor = executor.with_profit_recheck(monitor.clone());
+            }
+            if let Some(relay) = &ctx.private_relay {
+                executor = executor.with_private_relay(relay.clone());
+            }
+            if let Some(swapper) = &ctx.collateral_swapper {
+                executor = executor.with_collateral_swap(swapper.clone());
+            }
+            if let Some(aggregator) = &ctx.swap_aggregator {
+                executor = executor.with_swap_aggregator(aggregator.clone(), *network);
+            }
+            if let Some(balancer) = &ctx.weth_balancer {
+                executor = executor.with_weth_balancer(balancer.clone());
+            }
+            if let Some(simulator) = &ctx.simulator {
+                executor = executor.with_simulator(simulator.clone());
+            }
+            if let Some(tx_manager) = tx_manager {
+                executor = executor.with_tx_manager(tx_manager.clone());
+            }
+            if ctx.broadcast {
+                executor = executor.with_broadcast();
+            }
+            if let Some(margin_bps) = ctx.onchain_min_profit_margin_bps {
+                executor = executor.with_min_profit_guard(margin_bps, *network);
+            }
+            // Fee profile and racing bid both scale with the margin over the required profit
+            let required_profit = profitability::effective_min_profit_threshold(
+                opportunity.debt_to_cover,
+                min_profit_threshold,
+                min_profit_pct_of_debt_bps,
+            );
+            if let Some(gas_oracle) = &ctx.gas_oracle {
+                let profile =
+                    gas_oracle.profile_for_profit(opportunity.estimated_profit, required_profit);
+                executor = executor.with_gas_oracle(gas_oracle.clone(), profile);
+            }
+            let budget = RaceBudget {
+                expected_profit: opportunity.estimated_profit,
+                required_profit,
+            };
+            if let Some(racing) = &ctx.racing {
+                executor = executor.with_racing(racing.clone(), budget);
+            }
+
+            // Another bot already has a liquidation of this user pending: re-bid above it if the
+            // profit allows, otherwise stand down rather than pay gas for a losing race
+            let rival = ctx.competitors.as_ref().and_then(|tracker| tracker.racing_for(user));
+            if let Some(rival) = rival {
+                if let Err(e) = database::record_competitor_race(db_pool, &rival.liquidator).await {
+                    warn!("Failed to record race against {}: {}", rival.liquidator, e);
+                }
+                let outbid = racing::outbid_priority_fee(rival.priority_fee_per_gas);
+                let affordable = ctx.racing.as_ref().map_or(0, |racing| {
+                    racing.max_priority_fee(0, &budget, executor::LIQUIDATION_GAS_LIMIT)
+                });
+                if outbid > affordable {
+                    warn!(
+                        "🏎️ {} is already liquidating {:?} with a {} wei tip (we can afford {} wei), standing down: {}",
+                        rival.liquidator, user, rival.priority_fee_per_gas, affordable, rival.tx_hash
+                    );
+                    database::log_monitoring_event(
+                        db_pool,
+                        "liquidation_competitor_racing",
+                        Some(user),
+                        Some(&format!(
+                            "Competitor {} racing with a {} wei tip in {}",
+                            rival.liquidator, rival.priority_fee_per_gas, rival.tx_hash
+                        )),
+                    )
+                    .await?;
+                    return Ok(LiquidationResult::NotNeeded(
+                        NotNeededReason::CompetitorRacing,
+                    ));
+                }
+                info!(
+                    "🏎️ {} is already liquidating {:?} with a {} wei tip, re-bidding above it",
+                    rival.liquidator, user, rival.priority_fee_per_gas
+                );
+                executor = executor.with_rival_priority_fee(rival.priority_fee_per_gas);
+            }
 
             // Verify contract setup
-            if let Err(e) = executor.verify_contract_setup().await {
+            if let Err(e) = executor.verify_contract_setup(network.pool).await {
                 error!("Contract setup verification failed: {}", e);
                 return Err(e);
             }
 
-            // Execute the liquidation
-            match executor.execute_liquidation(&opportunity).await {
-                Ok(tx_hash) => {
+            // Pre-flight: the transaction must not revert and must still clear the threshold
+            // once priced with simulated gas (and, on a fork, the simulated fill). A rejected
+            // pair falls back to the next ranked one.
+            let mut accepted = None;
+            let mut rejection = String::new();
+            for candidate in candidates {
+                let priced = ctx.route_priced(candidate, min_profit_threshold);
+                let simulation = executor.simulate_liquidation(&priced).await;
+                let (reason, simulated) = match simulation {
+                    Ok(None) => (None, None),
+                    Ok(Some(outcome)) => {
+                        let required_profit = profitability::effective_min_profit_threshold(
+                            candidate.debt_to_cover,
+                            min_profit_threshold,
+                            min_profit_pct_of_debt_bps,
+                        );
+                        info!(
+                            "🧪 Simulation passed: gas used {}, profit after gas {} wei (required {} wei)",
+                            outcome.gas_used, outcome.profit_after_gas, required_profit
+                        );
+                        let reason = (outcome.profit_after_gas < required_profit).then(|| {
+                            format!(
+                                "simulated profit {} < threshold {} wei",
+                                outcome.profit_after_gas, required_profit
+                            )
+                        });
+                        (reason, Some(outcome))
+                    }
+                    Err(e) => (Some(e.to_string()), None),
+                };
+                match reason {
+                    None => {
+                        accepted = Some((candidate.clone(), priced, simulated));
+                        break;
+                    }
+                    Some(reason) => {
+                        warn!(
+                            "❌ Liquidation of {:?} via {:?} -> {:?} rejected by simulation: {}",
+                            user, candidate.collateral_asset, candidate.debt_asset, reason
+                        );
+                        rejection = reason;
+                    }
+                }
+            }
+            let Some((swapped, priced, simulated)) = accepted else {
+                database::log_monitoring_event(
+                    db_pool,
+                    "liquidation_simulation_rejected",
+                    Some(user),
+                    Some(&format!("Liquidation rejected by simulation: {}", rejection)),
+                )
+                .await?;
+                return Ok(LiquidationResult::NotNeeded(
+                    NotNeededReason::SimulationRejected,
+                ));
+            };
+
+            // Small liquidations may be cheaper paid from the wallet than via a flash loan
+            let mut keeps_a_tokens = false;
+            if let (Some(funding), Some(debt_asset)) =
+                (&ctx.wallet_funding, asset_configs.get(&priced.debt_asset))
+            {
+                if let Some(reservation) = funding
+                    .select_route(
+                        provider.as_ref(),
+                        network,
+                        &priced,
+                        debt_asset,
+                        operator,
+                        ctx.receive_a_token,
+                    )
+                    .await
+                {
+                    executor = executor.with_wallet_funding(funding.clone(), reservation);
+                    if ctx.receive_a_token {
+                        executor = executor.with_receive_a_token();
+                        keeps_a_tokens = true;
+                    }
+                }
+            }
+
+            // A pair priced for aTokens the wallet then can't fund is flash-loaned and swapped
+            // instead, and has to clear the thresholds at that price too
+            let opportunity = if keeps_a_tokens { priced } else { swapped };
+            let simulated = simulated.map(|outcome| SimulationOutcome {
+                profit_after_gas: crate::sim::simulated_profit(
+                    &opportunity,
+                    outcome.fill.as_ref(),
+                    outcome.gas_cost,
+                ),
+                ..outcome
+            });
+            let required_profit = profitability::effective_min_profit_threshold(
+                opportunity.debt_to_cover,
+                min_profit_threshold,
+                min_profit_pct_of_debt_bps,
+            );
+            let profit = simulated
+                .as_ref()
+                .map_or(opportunity.estimated_profit, |outcome| outcome.profit_after_gas);
+            if profit < required_profit {
+                info!(
+                    "❌ Liquidation of {:?} rejected - the swap route earns {} wei, {} required",
+                    user, profit, required_profit
+                );
+                database::log_monitoring_event(
+                    db_pool,
+                    "liquidation_rejected",
+                    Some(user),
+                    Some(&format!(
+                        "Liquidation rejected: profit {} < threshold {} wei without aToken funding",
+                        profit, required_profit
+                    )),
+                )
+                .await?;
+                return Ok(LiquidationResult::NotNeeded(
+                    NotNeededReason::InsufficientProfit,
+                ));
+            }
+
+            // Dry runs stop here, with the liquidation priced exactly as it would be submitted
+            if ctx.execution_mode == ExecutionMode::DryRun {
+                return record_dry_run(
+                    db_pool,
+                    user,
+                    &opportunity,
+                    simulated.as_ref(),
+                    strategy_label,
+                )
+                .await;
+            }
+
+            // Execute the liquidation. A reverted transaction's receipt is stored before the
+            // revert is reported, since it spent gas all the same.
+            let executed = match executor.execute_liquidation(&opportunity).await {
+                Ok(executed) if !executed.receipt.succeeded => {
+                    if let Err(e) = save_reverted_liquidation_record(
+                        db_pool,
+                        &opportunity,
+                        &executed.receipt,
+                        strategy_label,
+                    )
+                    .await
+                    {
+                        warn!(
+                            "Failed to record reverted liquidation {}: {}",
+                            executed.tx_hash, e
+                        );
+                    }
+                    Err(eyre::eyre!(
+                        "Transaction reverted: {}",
+                        executed.revert_reason.as_deref().unwrap_or("unknown reason")
+                    ))
+                }
+                executed => executed,
+            };
+            match executed {
+                Ok(executed) => {
+                    latency::mark(user, LatencyStage::TxMined);
+                    let tx_hash = executed.tx_hash;
                     info!("🎉 Liquidation executed successfully! TX: {}", tx_hash);
 
+                    let profit = executed
+                        .fill
+                        .as_ref()
+                        .map_or(opportunity.estimated_profit, |fill| fill.realized_profit);
+
                     // Log successful execution
                     database::log_monitoring_event(
                         db_pool,
@@ -418,13 +1355,82 @@ where
                         Some(user),
                         Some(&format!(
                             "Liquidation executed successfully. TX: {}, Profit: {} wei",
-                            tx_hash, opportunity.estimated_profit
+                            tx_hash, profit
                         )),
                     )
                     .await?;
 
-                    // Save liquidation record
-                    save_liquidation_record(db_pool, &opportunity, &tx_hash).await?;
+                    // Save liquidation record with the amounts actually filled
+                    save_liquidation_record(
+                        db_pool,
+                        &opportunity,
+                        executed.fill.as_ref(),
+                        &executed.receipt,
+                        strategy_label,
+                    )
+                    .await?;
+
+                    // Track the aTokens held until the unwind job withdraws them
+                    if executed.received_a_tokens {
+                        let amount = executed
+                            .fill
+                            .as_ref()
+                            .map_or(opportunity.expected_collateral_received, |fill| {
+                                fill.collateral_received
+                            });
+                        if let Err(e) = database::record_a_token_lot(
+                            db_pool,
+                            operator,
+                            opportunity.collateral_asset,
+                            amount,
+                            &tx_hash,
+                            chrono::Utc::now().timestamp_millis(),
+                        )
+                        .await
+                        {
+                            warn!("Failed to record aToken inventory from {}: {}", tx_hash, e);
+                        }
+                    }
+
+                    // Account for the P&L in the background; it needs several RPC reads
+                    let mined = accounting::MinedLiquidation::new(
+                        &opportunity,
+                        executed.fill.as_ref(),
+                        &executed.receipt,
+                        asset_configs,
+                        operator,
+                    );
+                    let (accounting_provider, accounting_db) = (provider.clone(), db_pool.clone());
+                    let accounting_network = *network;
+                    tokio::spawn(async move {
+                        if let Err(e) = accounting::record_liquidation_pnl(
+                            accounting_provider,
+                            &accounting_network,
+                            &accounting_db,
+                            &mined,
+                            contract_addr,
+                        )
+                        .await
+                        {
+                            warn!("Failed to record P&L for {}: {}", mined.tx_hash, e);
+                        }
+                    });
+
+                    feed::publish(
+                        db_pool.chain_id,
+                        FeedEventKind::Liquidation {
+                            user,
+                            tx_hash: tx_hash.clone(),
+                            profit,
+                        },
+                    );
+                    if let Some(notifier) = &ctx.alert_notifier {
+                        notifier.spawn_notify(AlertEvent::LiquidationSucceeded {
+                            user,
+                            tx_hash: tx_hash.clone(),
+                            profit,
+                        });
+                    }
 
                     return Ok(LiquidationResult::Executed(tx_hash));
                 }
@@ -443,17 +1449,20 @@ where
                     // This handles cases like "user already repaid" where the position may no longer be at risk
                     if let Err(update_err) = crate::monitoring::scanner::update_user_position(
                         provider.clone(),
-                        pool_contract,
+                        &ctx.pool_contract,
                         db_pool,
                         // We need empty DashMap and HashSet for the function signature but they won't be used meaningfully here
                         Arc::new(DashMap::new()),
+                        &crate::monitoring::scanner::PersistedPositions::default(),
                         Arc::new(parking_lot::RwLock::new(std::collections::HashSet::new())),
                         // Create a dummy event sender since we don't need events here
-                        tokio::sync::mpsc::unbounded_channel().0,
+                        crate::events::channel(1, 1).0,
                         U256::from_str("1000000000000000000").unwrap(), // 1.0 health factor threshold
+                        0, // No previous position here, so the fresh check is always persisted
                         user,
                         None,
                         None,
+                        None,
                         None, // No priority liquidation channel needed here
                     )
                     .await
@@ -474,8 +1483,21 @@ where
             }
         }
         _ => {
-            // Missing liquidator contract or signer - just simulate
-            warn!("⏳ Liquidation execution not available - missing contract address or signer");
+            // Simulate mode, or missing liquidator contract or signer - just simulate
+            if ctx.execution_mode == ExecutionMode::Simulate {
+                info!("⏳ Execution mode is simulate - not executing liquidation");
+            } else if ctx.execution_mode == ExecutionMode::DryRun {
+                // Without a contract or signer there is nothing to simulate or reprice, so the
+                // dry run records the profitability estimate
+                warn!(
+                    "📝 Dry run without a liquidator contract or signer - recording the estimate"
+                );
+                return record_dry_run(db_pool, user, opportunity, None, strategy_label).await;
+            } else {
+                warn!(
+                    "⏳ Liquidation execution not available - missing contract address or signer"
+                );
+            }
             warn!(
                 "This would be a profitable liquidation worth {} wei",
                 opportunity.estimated_profit
@@ -499,6 +1521,42 @@ where
     }
 }
 
+/// Record the liquidation a dry run would have broadcast, at the simulated profit when a
+/// pre-flight simulation ran
+async fn record_dry_run(
+    db_pool: &DatabasePool,
+    user: Address,
+    opportunity: &LiquidationOpportunity,
+    simulated: Option<&SimulationOutcome>,
+    strategy_label: &str,
+) -> Result<LiquidationResult> {
+    let simulated_profit =
+        simulated.map_or(opportunity.estimated_profit, |outcome| outcome.profit_after_gas);
+    info!(
+        "📝 Dry run: would have liquidated {:?} for {} wei (simulated profit {} wei)",
+        user, opportunity.estimated_profit, simulated_profit
+    );
+    database::record_dry_run_liquidation(
+        db_pool,
+        opportunity,
+        simulated_profit,
+        simulated.map(|outcome| outcome.gas_used),
+        strategy_label,
+    )
+    .await?;
+    database::log_monitoring_event(
+        db_pool,
+        "liquidation_dry_run",
+        Some(user),
+        Some(&format!(
+            "Dry run: liquidation not broadcast. Simulated profit: {} wei",
+            simulated_profit
+        )),
+    )
+    .await?;
+    Ok(LiquidationResult::NotNeeded(NotNeededReason::DryRun))
+}
+
 /// Get user position from database
 async fn get_user_position_from_db(
     db_pool: &DatabasePool,
@@ -528,15 +1586,28 @@ async fn get_user_position_from_db(
 async fn save_liquidation_record(
     db_pool: &DatabasePool,
     opportunity: &crate::models::LiquidationOpportunity,
-    tx_hash: &str,
+    fill: Option<&crate::models::LiquidationFill>,
+    receipt: &crate::models::LiquidationReceipt,
+    strategy_label: &str,
 ) -> Result<()> {
     // Use checksummed hex representation for consistent address storage (matches database storage format)
     let _user_str = opportunity.user.to_string();
     let collateral_str = opportunity.collateral_asset.to_string();
     let debt_str = opportunity.debt_asset.to_string();
-    let debt_covered_str = opportunity.debt_to_cover.to_string();
-    let collateral_received_str = opportunity.expected_collateral_received.to_string();
-    let profit_str = opportunity.estimated_profit.to_string();
+    let requested_debt_str = opportunity.debt_to_cover.to_string();
+
+    // Prefer the amounts the pool actually filled over the requested ones
+    let (debt_covered, collateral_received, profit) = match fill {
+        Some(fill) => (fill.debt_covered, fill.collateral_received, fill.realized_profit),
+        None => (
+            opportunity.debt_to_cover,
+            opportunity.expected_collateral_received,
+            opportunity.estimated_profit,
+        ),
+    };
+    let debt_covered_str = debt_covered.to_string();
+    let collateral_received_str = collateral_received.to_string();
+    let profit_str = profit.to_string();
 
     crate::database::record_liquidation_event(
         db_pool,
@@ -544,16 +1615,47 @@ async fn save_liquidation_record(
         &collateral_str,
         &debt_str,
         &debt_covered_str,
+        Some(&requested_debt_str),
         &collateral_received_str,
         &profit_str,
-        Some(tx_hash),
-        None,
+        Some(&receipt.tx_hash),
+        receipt.block_number.map(|block| block as i64),
+        strategy_label,
     )
     .await?;
+    // Gas, position in block and status of the mined transaction
+    crate::database::record_liquidation_receipt(db_pool, receipt).await?;
+    metrics::record_liquidation_profit(strategy_label, profit);
 
     Ok(())
 }
 
+/// Save a liquidation whose transaction was mined but reverted: nothing was covered or
+/// received, but its receipt (gas used, failed status) is kept
+async fn save_reverted_liquidation_record(
+    db_pool: &DatabasePool,
+    opportunity: &crate::models::LiquidationOpportunity,
+    receipt: &crate::models::LiquidationReceipt,
+    strategy_label: &str,
+) -> Result<()> {
+    let requested_debt_str = opportunity.debt_to_cover.to_string();
+    crate::database::record_liquidation_event(
+        db_pool,
+        &opportunity.user,
+        &opportunity.collateral_asset.to_string(),
+        &opportunity.debt_asset.to_string(),
+        "0",
+        Some(&requested_debt_str),
+        "0",
+        "0",
+        Some(&receipt.tx_hash),
+        receipt.block_number.map(|block| block as i64),
+        strategy_label,
+    )
+    .await?;
+    crate::database::record_liquidation_receipt(db_pool, receipt).await
+}
+
 /// Legacy function for backward compatibility - now with enhanced functionality
 pub async fn handle_liquidation_opportunity_legacy(
     _db_pool: &DatabasePool,
@@ -576,3 +1678,283 @@ pub async fn handle_liquidation_opportunity_legacy(
         NotNeededReason::SimulationMode,
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ISOLATED: Address = Address::repeat_byte(0x01);
+    const WETH: Address = Address::repeat_byte(0x02);
+    const USDC: Address = Address::repeat_byte(0x03);
+    const GHO: Address = Address::repeat_byte(0x04);
+
+    fn configs() -> HashMap<Address, ReserveConfig> {
+        HashMap::from([
+            (ISOLATED, ReserveConfig { debt_ceiling: 100_000_000, ..ReserveConfig::default() }),
+            (WETH, ReserveConfig::default()),
+            (USDC, ReserveConfig { borrowable_in_isolation: true, ..ReserveConfig::default() }),
+            (GHO, ReserveConfig { siloed_borrowing: true, ..ReserveConfig::default() }),
+        ])
+    }
+
+    fn opportunity(
+        collateral: Address,
+        debt: Address,
+        profit: u64,
+        debt_to_cover: u64,
+    ) -> LiquidationOpportunity {
+        LiquidationOpportunity {
+            user: Address::repeat_byte(0xaa),
+            collateral_asset: collateral,
+            debt_asset: debt,
+            debt_to_cover: U256::from(debt_to_cover),
+            expected_collateral_received: U256::ZERO,
+            liquidation_bonus: U256::ZERO,
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: crate::models::FlashLoanSource::AaveV3,
+            gas_cost: U256::ZERO,
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::from(profit),
+            profit_threshold_met: true,
+        }
+    }
+
+    #[test]
+    fn test_pairs_are_valued_from_reserve_balances_and_ranked() {
+        let asset = |address: Address, symbol: &str, decimals| LiquidationAssetConfig {
+            address,
+            symbol: symbol.to_string(),
+            decimals,
+            asset_id: 0,
+            liquidation_bonus: 500,
+            is_collateral: true,
+            is_borrowable: true,
+        };
+        let assets =
+            HashMap::from([(WETH, asset(WETH, "WETH", 18)), (USDC, asset(USDC, "USDC", 6))]);
+        let prices = HashMap::from([
+            (WETH, U256::from(2_000u64) * U256::from(100_000_000u64)),
+            (USDC, U256::from(100_000_000u64)),
+        ]);
+        let user_reserves = [
+            UserReserve {
+                reserve: WETH,
+                collateral_balance: U256::from(10u64).pow(U256::from(18u64)),
+                debt_balance: U256::ZERO,
+                usage_as_collateral_enabled: true,
+            },
+            UserReserve {
+                reserve: USDC,
+                collateral_balance: U256::from(500_000_000u64),
+                debt_balance: U256::from(1_500_000_000u64),
+                usage_as_collateral_enabled: false,
+            },
+        ];
+
+        let values = ReserveValues::new(&user_reserves, &prices, &assets);
+        assert_eq!(
+            values.pair(WETH, USDC),
+            Some(PairBalances {
+                collateral_base: U256::from(200_000_000_000u64),
+                debt_base: U256::from(150_000_000_000u64),
+            })
+        );
+        // USDC isn't enabled as collateral and GHO has no balance
+        assert_eq!(values.pair(USDC, USDC), None);
+        assert_eq!(values.pair(WETH, GHO), None);
+
+        let ranked = rank_opportunities(
+            vec![
+                opportunity(WETH, USDC, 100, 1_000),
+                opportunity(WETH, GHO, 300, 1_000),
+                opportunity(USDC, USDC, 100, 5_000),
+            ],
+            &HashMap::new(),
+        );
+        let order: Vec<(Address, Address)> = ranked
+            .iter()
+            .map(|candidate| (candidate.collateral_asset, candidate.debt_asset))
+            .collect();
+        assert_eq!(order, vec![(WETH, GHO), (USDC, USDC), (WETH, USDC)]);
+    }
+
+    #[test]
+    fn test_multi_debt_user_repays_largest_debt_at_comparable_profit() {
+        let debt_values = HashMap::from([
+            (USDC, U256::from(100u64)),
+            (GHO, U256::from(500u64)),
+            (WETH, U256::from(900u64)),
+        ]);
+
+        let ranked = rank_opportunities(
+            vec![
+                opportunity(WETH, USDC, 1_000, 1_000),
+                opportunity(WETH, GHO, 995, 1_000),
+                opportunity(ISOLATED, WETH, 900, 1_000),
+            ],
+            &debt_values,
+        );
+        let debts: Vec<Address> = ranked.iter().map(|candidate| candidate.debt_asset).collect();
+        // GHO is within 1% of the best profit and the larger debt; WETH's debt is the
+        // largest, but its pair earns clearly less
+        assert_eq!(debts, vec![GHO, USDC, WETH]);
+    }
+
+    #[test]
+    fn test_isolation_mode_and_siloed_debt_narrow_pairs() {
+        let configs = configs();
+
+        // Isolation mode: only debts borrowable in isolation are repaid
+        let (collateral, debt) =
+            apply_position_constraints(vec![ISOLATED], vec![WETH, USDC], &configs);
+        assert_eq!(collateral, vec![ISOLATED]);
+        assert_eq!(debt, vec![USDC]);
+
+        // The isolated asset next to other collateral doesn't put the user in isolation
+        let (_, debt) =
+            apply_position_constraints(vec![ISOLATED, WETH], vec![WETH, USDC], &configs);
+        assert_eq!(debt, vec![WETH, USDC]);
+
+        // A siloed debt is the only one the position can hold
+        let (_, debt) = apply_position_constraints(vec![WETH], vec![USDC, GHO], &configs);
+        assert_eq!(debt, vec![GHO]);
+
+        // Unknown reserves stay unconstrained
+        let unknown = Address::repeat_byte(0x05);
+        let (_, debt) = apply_position_constraints(vec![ISOLATED], vec![unknown], &configs);
+        assert_eq!(debt, vec![unknown]);
+    }
+
+    #[tokio::test]
+    async fn test_reverted_liquidation_keeps_its_receipt() {
+        let db_pool = crate::database::tests::create_test_pool().await;
+        let receipt = crate::models::LiquidationReceipt {
+            tx_hash: "0xdead".to_string(),
+            block_number: Some(9),
+            tx_index: Some(3),
+            gas_used: U256::from(210_000u64),
+            effective_gas_price: U256::from(1_000_000_000u64),
+            succeeded: false,
+        };
+
+        let opportunity = opportunity(WETH, USDC, 50, 1_000);
+        save_reverted_liquidation_record(&db_pool, &opportunity, &receipt, "default")
+            .await
+            .unwrap();
+
+        let records = database::get_recent_liquidations(&db_pool, 10).await.unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].tx_hash.as_deref(), Some("0xdead"));
+        assert_eq!(records[0].succeeded, Some(false));
+        assert_eq!(records[0].debt_covered, U256::ZERO);
+        assert_eq!(records[0].profit, U256::ZERO);
+    }
+
+    /// Dry-run context on an unreachable node; nothing is sent to it without a signer
+    fn context(
+        db_pool: DatabasePool,
+    ) -> LiquidationContext<alloy_provider::RootProvider<alloy_transport::BoxTransport>> {
+        let provider = Arc::new(
+            alloy_provider::ProviderBuilder::new()
+                .on_http("http://127.0.0.1:1".parse().unwrap())
+                .boxed(),
+        );
+        let pool_contract = ContractInstance::new(
+            Address::ZERO,
+            provider.clone(),
+            alloy_contract::Interface::new(alloy_json_abi::JsonAbi::default()),
+        );
+        LiquidationContext::new(
+            provider,
+            db_pool,
+            pool_contract,
+            PoolVersion::default(),
+            crate::networks::base(),
+            ExecutionMode::DryRun,
+            ProfitabilityModel::FlatSlippage,
+        )
+        .with_liquidator_contract(Address::repeat_byte(0x1c))
+        .with_strategy_label("paper")
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_without_signer_is_recorded() {
+        let db_pool = crate::database::tests::create_test_pool().await;
+        let ctx = context(db_pool.clone());
+        let thresholds = LiquidationThresholds {
+            min_profit_threshold: U256::ZERO,
+            min_profit_pct_of_debt_bps: 0,
+            liquidation_hf_margin: U256::ZERO,
+        };
+        let candidate = opportunity(WETH, USDC, 50, 1_000);
+
+        let result = execute_opportunity(
+            &ctx,
+            candidate.user,
+            None,
+            None,
+            &HashMap::new(),
+            thresholds,
+            &[candidate.clone()],
+        )
+        .await
+        .unwrap();
+
+        assert!(matches!(result, LiquidationResult::NotNeeded(NotNeededReason::DryRun)));
+        if let database::DatabaseBackend::Sqlite(pool) = &db_pool.backend {
+            let (estimated, simulated, gas_used, strategy): (String, String, Option<i64>, String) =
+                sqlx::query_as(
+                    "SELECT estimated_profit, simulated_profit, gas_used, strategy_label \
+                     FROM dry_run_liquidations",
+                )
+                .fetch_one(pool)
+                .await
+                .unwrap();
+            assert_eq!((estimated.as_str(), simulated.as_str()), ("50", "50"));
+            assert_eq!((gas_used, strategy.as_str()), (None, "paper"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_a_token_route_is_priced_without_swap_cost() {
+        let finney = |amount: u64| U256::from(amount) * U256::from(1_000_000_000_000_000u64);
+        let thresholds = LiquidationThresholds {
+            min_profit_threshold: finney(10),
+            min_profit_pct_of_debt_bps: 300,
+            liquidation_hf_margin: U256::ZERO,
+        };
+        let base = LiquidationOpportunity {
+            debt_to_cover: finney(1_000),
+            liquidation_bonus: finney(50),
+            flash_loan_fee: finney(1),
+            gas_cost: finney(1),
+            ..opportunity(WETH, USDC, 0, 0)
+        };
+        // Swapping the collateral costs 30 of the 50 bonus, short of 3% of the debt
+        let swapped = profitability::reprice_with_slippage(
+            &base,
+            finney(30),
+            thresholds.min_profit_threshold,
+        );
+        assert_eq!(swapped.estimated_profit, finney(18));
+
+        let db_pool = crate::database::tests::create_test_pool().await;
+        let flash_loaned = context(db_pool.clone());
+        assert!(flash_loaned.validated_candidates(vec![swapped.clone()], thresholds).is_empty());
+
+        let funding = WalletFunding::new(
+            Address::ZERO,
+            std::collections::BTreeMap::new(),
+            crate::models::ApprovalMode::Exact,
+        );
+        let a_tokens = context(db_pool)
+            .with_wallet_funding(Arc::new(funding))
+            .with_receive_a_token();
+        let candidates = a_tokens.validated_candidates(vec![swapped], thresholds);
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(
+            a_tokens.route_priced(&candidates[0], thresholds.min_profit_threshold).estimated_profit,
+            finney(48)
+        );
+    }
+}
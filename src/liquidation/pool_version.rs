@@ -0,0 +1,77 @@
+use alloy_primitives::Address;
+use alloy_provider::Provider;
+use alloy_sol_types::sol;
+use tracing::{info, warn};
+
+use crate::protocols::call_contract;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IPoolVersionProbe {
+        function getReserveDeficit(address asset) external view returns (uint256);
+        function getVirtualUnderlyingBalance(address asset) external view returns (uint128);
+    }
+}
+
+/// Aave V3 release a pool runs, as far as liquidation rules are concerned
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PoolVersion {
+    /// 3.0: 50% close factor until the health factor drops under 0.95
+    #[default]
+    V3_0,
+    /// 3.1 and 3.2: virtual accounting, liquidation rules unchanged from 3.0
+    V3_1,
+    /// 3.3 and later: full close factor for small positions, no dust left by partial
+    /// liquidations, and debt left without collateral written off as reserve deficit
+    V3_3,
+}
+
+impl PoolVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PoolVersion::V3_0 => "3.0",
+            PoolVersion::V3_1 => "3.1",
+            PoolVersion::V3_3 => "3.3",
+        }
+    }
+
+    /// Whether reserves under $2,000 are liquidatable in full and partial liquidations must
+    /// leave at least $1,000 of debt and collateral
+    pub fn has_dust_rules(&self) -> bool {
+        *self == PoolVersion::V3_3
+    }
+}
+
+/// Detect the pool's release by probing getters each release added: `getReserveDeficit`
+/// (3.3) and `getVirtualUnderlyingBalance` (3.1). Both are plain storage reads that succeed
+/// for any address, so a revert means the getter doesn't exist.
+pub async fn detect_pool_version<P>(provider: &P, pool: Address) -> PoolVersion
+where
+    P: Provider,
+{
+    let probe = Address::ZERO;
+    let version = if call_contract(
+        provider,
+        pool,
+        &IPoolVersionProbe::getReserveDeficitCall { asset: probe },
+    )
+    .await
+    .is_ok()
+    {
+        PoolVersion::V3_3
+    } else if call_contract(
+        provider,
+        pool,
+        &IPoolVersionProbe::getVirtualUnderlyingBalanceCall { asset: probe },
+    )
+    .await
+    .is_ok()
+    {
+        PoolVersion::V3_1
+    } else {
+        warn!("Pool {} has neither 3.1 nor 3.3 getters, assuming Aave 3.0 rules", pool);
+        PoolVersion::V3_0
+    };
+    info!("🏛️ Aave pool {} follows {} liquidation rules", pool, version.as_str());
+    version
+}
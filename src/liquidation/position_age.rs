@@ -0,0 +1,117 @@
+use alloy_primitives::Address;
+use dashmap::DashMap;
+use tracing::debug;
+
+/// Tracks the block at which each user was first observed so that liquidations are only
+/// attempted once a position has existed for `min_age_blocks` blocks.
+///
+/// Brand-new positions can briefly report inconsistent account data while indexers catch up,
+/// so acting on them in the block they were created is avoided.
+#[derive(Debug, Default)]
+pub struct PositionAgeTracker {
+    min_age_blocks: u64,
+    first_seen_block: DashMap<Address, u64>,
+}
+
+impl PositionAgeTracker {
+    pub fn new(min_age_blocks: u64) -> Self {
+        Self {
+            min_age_blocks,
+            first_seen_block: DashMap::new(),
+        }
+    }
+
+    /// Whether age gating is active (a minimum age of 0 disables it)
+    pub fn is_enabled(&self) -> bool {
+        self.min_age_blocks > 0
+    }
+
+    /// Record that a user was observed at `block`, keeping the earliest observation
+    pub fn record_observation(&self, user: Address, block: u64) {
+        self.first_seen_block
+            .entry(user)
+            .and_modify(|first_seen| *first_seen = (*first_seen).min(block))
+            .or_insert(block);
+    }
+
+    /// Block at which the user was first observed, if ever
+    pub fn first_seen(&self, user: Address) -> Option<u64> {
+        self.first_seen_block.get(&user).map(|block| *block)
+    }
+
+    /// Check whether the user's position is old enough to be liquidated at `current_block`.
+    ///
+    /// Users that have never been observed are recorded as first seen at `current_block`.
+    pub fn is_eligible(&self, user: Address, current_block: u64) -> bool {
+        if !self.is_enabled() {
+            return true;
+        }
+
+        self.record_observation(user, current_block);
+        let first_seen = self.first_seen(user).unwrap_or(current_block);
+        let age = current_block.saturating_sub(first_seen);
+
+        if age < self.min_age_blocks {
+            debug!(
+                "Position for {:?} is {} block(s) old, needs {} before liquidation",
+                user, age, self.min_age_blocks
+            );
+            return false;
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_disabled_tracker_allows_everything() {
+        let tracker = PositionAgeTracker::new(0);
+        let user = Address::from([1u8; 20]);
+
+        assert!(!tracker.is_enabled());
+        assert!(tracker.is_eligible(user, 100));
+    }
+
+    #[test]
+    fn test_too_new_position_is_not_liquidated() {
+        let tracker = PositionAgeTracker::new(2);
+        let user = Address::from([1u8; 20]);
+
+        tracker.record_observation(user, 100);
+
+        // Same block and one block later are both too new
+        assert!(!tracker.is_eligible(user, 100));
+        assert!(!tracker.is_eligible(user, 101));
+
+        // Once the minimum age is reached the position becomes eligible
+        assert!(tracker.is_eligible(user, 102));
+        assert!(tracker.is_eligible(user, 150));
+    }
+
+    #[test]
+    fn test_unobserved_user_is_recorded_at_first_check() {
+        let tracker = PositionAgeTracker::new(3);
+        let user = Address::from([2u8; 20]);
+
+        assert!(!tracker.is_eligible(user, 500));
+        assert_eq!(tracker.first_seen(user), Some(500));
+        assert!(tracker.is_eligible(user, 503));
+    }
+
+    #[test]
+    fn test_earliest_observation_is_kept() {
+        let tracker = PositionAgeTracker::new(5);
+        let user = Address::from([3u8; 20]);
+
+        tracker.record_observation(user, 200);
+        tracker.record_observation(user, 210);
+        tracker.record_observation(user, 195);
+
+        assert_eq!(tracker.first_seen(user), Some(195));
+        assert!(tracker.is_eligible(user, 200));
+    }
+}
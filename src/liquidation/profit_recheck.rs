@@ -0,0 +1,170 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use eyre::Result;
+use parking_lot::Mutex;
+use tracing::{error, info, warn};
+
+use crate::monitoring::alerts::{AlertEvent, AlertNotifier};
+
+// ERC20 balance lookup for measuring realized profit
+sol! {
+    #[allow(missing_docs)]
+    interface IERC20Balance {
+        function balanceOf(address account) external view returns (uint256);
+    }
+}
+
+/// Consecutive below-threshold liquidations that indicate estimates are systematically optimistic
+pub const SYSTEMATIC_OVERSHOOT_STREAK: u64 = 3;
+
+/// Fetch `account`'s balance of `token`
+pub async fn fetch_token_balance<P>(provider: &P, token: Address, account: Address) -> Result<U256>
+where
+    P: Provider,
+{
+    let call = IERC20Balance::balanceOfCall { account };
+    let result = provider
+        .call(
+            &TransactionRequest::default()
+                .to(token)
+                .input(call.abi_encode().into()),
+        )
+        .await
+        .map_err(|e| eyre::eyre!("Failed to fetch balance of {} for {}: {}", token, account, e))?;
+
+    Ok(IERC20Balance::balanceOfCall::abi_decode_returns(&result, true)?._0)
+}
+
+/// Realized profit as basis points of the estimate (10000 = exactly as estimated)
+pub fn realized_profit_bps(estimated: U256, realized: U256) -> u64 {
+    if estimated.is_zero() {
+        return 10_000;
+    }
+    let bps = realized.saturating_mul(U256::from(10_000u64)) / estimated;
+    bps.try_into().unwrap_or(u64::MAX)
+}
+
+/// Compares each confirmed liquidation's realized profit against its pre-submission estimate
+/// and alerts when realized profit keeps falling below `alert_bps` of the estimate
+pub struct ProfitRecheckMonitor {
+    alert_bps: u64,
+    shortfall_streak: Mutex<u64>,
+    alert_notifier: Option<AlertNotifier>,
+}
+
+impl ProfitRecheckMonitor {
+    pub fn new(alert_bps: u64) -> Self {
+        Self {
+            alert_bps,
+            shortfall_streak: Mutex::new(0),
+            alert_notifier: None,
+        }
+    }
+
+    pub fn with_alert_notifier(mut self, notifier: AlertNotifier) -> Self {
+        self.alert_notifier = Some(notifier);
+        self
+    }
+
+    /// A threshold of zero disables the recheck
+    pub fn is_enabled(&self) -> bool {
+        self.alert_bps > 0
+    }
+
+    /// Record one confirmed liquidation. Returns true when this result completes a streak of
+    /// shortfalls long enough to flag the estimates as systematically optimistic (alerted once
+    /// per streak; any liquidation meeting the threshold resets it).
+    pub fn record(&self, estimated: U256, realized: U256) -> bool {
+        if !self.is_enabled() {
+            return false;
+        }
+
+        let realized_bps = realized_profit_bps(estimated, realized);
+        if realized_bps >= self.alert_bps {
+            info!(
+                "✅ Realized profit {} wei is {} bps of the {} wei estimate",
+                realized, realized_bps, estimated
+            );
+            *self.shortfall_streak.lock() = 0;
+            return false;
+        }
+
+        let streak = {
+            let mut streak = self.shortfall_streak.lock();
+            *streak += 1;
+            *streak
+        };
+        warn!(
+            "📉 Realized profit {} wei is only {} bps of the {} wei estimate ({} in a row below {} bps)",
+            realized, realized_bps, estimated, streak, self.alert_bps
+        );
+
+        if streak != SYSTEMATIC_OVERSHOOT_STREAK {
+            return false;
+        }
+
+        error!(
+            "🚨 ALERT: {} consecutive liquidations realized less than {} bps of estimated profit - estimates are systematically optimistic",
+            streak, self.alert_bps
+        );
+        if let Some(notifier) = &self.alert_notifier {
+            notifier.spawn_notify(AlertEvent::OptimisticProfitEstimates {
+                consecutive_shortfalls: streak,
+                realized_bps,
+            });
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_realized_profit_bps() {
+        assert_eq!(realized_profit_bps(U256::from(100u64), U256::from(80u64)), 8_000);
+        assert_eq!(realized_profit_bps(U256::from(100u64), U256::from(150u64)), 15_000);
+        assert_eq!(realized_profit_bps(U256::ZERO, U256::ZERO), 10_000);
+    }
+
+    #[test]
+    fn test_repeated_estimate_overshoot_is_detected() {
+        let monitor = ProfitRecheckMonitor::new(9_000);
+        let estimated = U256::from(1_000u64);
+
+        // Realized 70% of the estimate every time: the third shortfall in a row alerts
+        let alerts: Vec<bool> = (0..4)
+            .map(|_| monitor.record(estimated, U256::from(700u64)))
+            .collect();
+        assert_eq!(alerts, vec![false, false, true, false]);
+
+        // A liquidation meeting the estimate resets the streak
+        assert!(!monitor.record(estimated, U256::from(950u64)));
+        assert!(!monitor.record(estimated, U256::from(700u64)));
+        assert!(!monitor.record(estimated, U256::from(700u64)));
+        assert!(monitor.record(estimated, U256::from(700u64)));
+    }
+
+    #[test]
+    fn test_isolated_shortfalls_do_not_alert() {
+        let monitor = ProfitRecheckMonitor::new(9_000);
+        let estimated = U256::from(1_000u64);
+
+        for realized in [700u64, 1_000, 700, 1_000, 700] {
+            assert!(!monitor.record(estimated, U256::from(realized)));
+        }
+    }
+
+    #[test]
+    fn test_zero_threshold_disables_recheck() {
+        let monitor = ProfitRecheckMonitor::new(0);
+
+        assert!(!monitor.is_enabled());
+        for _ in 0..5 {
+            assert!(!monitor.record(U256::from(1_000u64), U256::ZERO));
+        }
+    }
+}
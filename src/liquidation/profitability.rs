@@ -2,9 +2,19 @@ use alloy_primitives::U256;
 use alloy_provider::Provider;
 use eyre::Result;
 use std::sync::Arc;
-use tracing::{debug, info};
-
-use crate::models::{GasEstimate, LiquidationAssetConfig, LiquidationOpportunity, UserPosition};
+use tracing::{debug, info, warn};
+
+use super::assets::{is_major_collateral, is_stablecoin};
+use super::flash_loan::{self, FlashLoanProvider};
+use super::funding;
+use super::pool_version::PoolVersion;
+use super::swap::quote_exact_input;
+use crate::models::{
+    GasEstimate, LiquidationAssetConfig, LiquidationOpportunity, ProfitDivergence,
+    ProfitabilityModel, SwapRoute, UserPosition,
+};
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::networks::NetworkAddresses;
 
 /// Helper function to convert wei to ETH as f64 for display
 fn wei_to_eth_f64(wei: U256) -> f64 {
@@ -15,18 +25,37 @@ fn wei_to_eth_f64(wei: U256) -> f64 {
 }
 
 // Constants for calculations
-const FLASH_LOAN_FEE_BPS: u16 = 5; // 0.05% Aave flash loan fee
 const MAX_LIQUIDATION_CLOSE_FACTOR: u16 = 5000; // 50% max liquidation
+const FULL_LIQUIDATION_CLOSE_FACTOR: u16 = 10000; // Whole reserve debt
+const CLOSE_FACTOR_HF_THRESHOLD: u64 = 950_000_000_000_000_000; // Full close factor below 0.95
+const MIN_BASE_MAX_CLOSE_FACTOR_THRESHOLD: u64 = 2_000 * 100_000_000; // $2,000 (3.3+)
+const MIN_LEFTOVER_BASE: u64 = 1_000 * 100_000_000; // $1,000 left by partial liquidations (3.3+)
 const SLIPPAGE_TOLERANCE_BPS: u16 = 100; // 1% slippage tolerance
-const BASE_GAS_LIMIT: u64 = 800_000; // Base gas limit for liquidation
+const STABLE_PAIR_SLIPPAGE_BPS: u16 = 10; // 0.1% for stablecoin <-> stablecoin swaps
+const MAJOR_PAIR_SLIPPAGE_BPS: u16 = 50; // 0.5% for major collateral -> stablecoin swaps
+pub(crate) const BASE_GAS_LIMIT: u64 = 800_000; // Base gas limit for liquidation
+const CONTRACT_SWAP_FEE_TIER: u32 = 3000; // Pool the liquidator contract swaps through (its defaultSwapFee)
+
+/// Value (base currency) of a user's balances in the two reserves of a liquidation pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PairBalances {
+    pub collateral_base: U256,
+    pub debt_base: U256,
+}
 
-/// Calculate the profitability of a liquidation opportunity
+/// Calculate the profitability of a liquidation opportunity. With the user's balances in the
+/// pair's reserves the debt to cover follows them; without, it falls back to the aggregate debt.
 pub async fn calculate_liquidation_profitability<P>(
     provider: Arc<P>,
+    network: &NetworkAddresses,
     user_position: &UserPosition,
     collateral_asset: &LiquidationAssetConfig,
     debt_asset: &LiquidationAssetConfig,
+    pair_balances: Option<PairBalances>,
+    pool_version: PoolVersion,
     min_profit_threshold: U256,
+    model: ProfitabilityModel,
+    flash_loan_providers: &[Arc<dyn FlashLoanProvider>],
 ) -> Result<LiquidationOpportunity>
 where
     P: Provider,
@@ -37,24 +66,44 @@ where
     );
 
     // Step 1: Calculate maximum liquidation amount (50% of debt)
-    let max_debt_to_cover = calculate_max_debt_to_cover(user_position.total_debt_base);
+    let max_debt_to_cover = match pair_balances {
+        Some(balances) => calculate_pair_debt_to_cover(
+            balances,
+            collateral_asset.liquidation_bonus,
+            user_position.health_factor,
+            pool_version,
+        ),
+        None => calculate_max_debt_to_cover(user_position.total_debt_base),
+    };
 
     // Step 2: Calculate expected collateral received with liquidation bonus
     let (expected_collateral, liquidation_bonus) =
         calculate_collateral_received(max_debt_to_cover, collateral_asset.liquidation_bonus);
 
-    // Step 3: Calculate flash loan fee
-    let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover);
+    // Step 3: Pick the cheapest lender able to fund the debt and calculate its fee
+    let flash_loan = flash_loan::select_flash_loan_provider(
+        provider.as_ref(),
+        network,
+        flash_loan_providers,
+        debt_asset,
+        max_debt_to_cover,
+    )
+    .await;
+    let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover, flash_loan.fee_bps);
 
     // Step 4: Estimate gas costs
     let gas_estimate = estimate_gas_cost(provider.clone()).await?;
 
-    // Step 5: Estimate swap slippage (if assets are different)
-    let swap_slippage = if collateral_asset.address != debt_asset.address {
-        estimate_swap_slippage(expected_collateral, collateral_asset, debt_asset)
-    } else {
-        U256::ZERO
-    };
+    // Step 5: Estimate swap slippage (zero if assets are the same)
+    let swap_slippage = model_swap_slippage(
+        provider.as_ref(),
+        network,
+        expected_collateral,
+        collateral_asset,
+        debt_asset,
+        model,
+    )
+    .await;
 
     // Step 6: Calculate net profit
     let estimated_profit = calculate_net_profit(
@@ -76,6 +125,7 @@ where
         expected_collateral_received: expected_collateral,
         liquidation_bonus,
         flash_loan_fee,
+        flash_loan_source: flash_loan.source,
         gas_cost: gas_estimate.total_cost,
         swap_slippage,
         estimated_profit,
@@ -92,7 +142,7 @@ where
         💵 Revenue:
           - Liquidation bonus: {} wei ({:.6} ETH)
         💸 Costs:
-          - Flash loan fee: {} wei ({:.6} ETH)
+          - Flash loan fee: {} wei ({:.6} ETH) via {}
           - Gas cost: {} wei ({:.6} ETH)
           - Swap slippage: {} wei ({:.6} ETH)
           - Total costs: {} wei ({:.6} ETH)
@@ -108,6 +158,7 @@ where
         wei_to_eth_f64(liquidation_bonus),
         flash_loan_fee,
         wei_to_eth_f64(flash_loan_fee),
+        flash_loan.source.as_str(),
         gas_estimate.total_cost,
         wei_to_eth_f64(gas_estimate.total_cost),
         swap_slippage,
@@ -125,7 +176,7 @@ where
 }
 
 /// Calculate maximum debt that can be covered (50% of total debt)
-fn calculate_max_debt_to_cover(total_debt_base: U256) -> U256 {
+pub(crate) fn calculate_max_debt_to_cover(total_debt_base: U256) -> U256 {
     // Aave allows up to 50% of debt to be liquidated in a single transaction
     // Use saturating arithmetic to prevent overflow and ensure safe division
     if total_debt_base.is_zero() {
@@ -134,6 +185,57 @@ fn calculate_max_debt_to_cover(total_debt_base: U256) -> U256 {
     total_debt_base.saturating_mul(U256::from(MAX_LIQUIDATION_CLOSE_FACTOR)) / U256::from(10000)
 }
 
+/// Share of the reserve debt one liquidation may repay (bps). Half until the health factor
+/// drops under 0.95; from 3.3 also all of it when either reserve is worth under $2,000.
+fn close_factor_bps(
+    balances: PairBalances,
+    health_factor: U256,
+    pool_version: PoolVersion,
+) -> u16 {
+    let small_position = pool_version.has_dust_rules()
+        && (balances.collateral_base < U256::from(MIN_BASE_MAX_CLOSE_FACTOR_THRESHOLD)
+            || balances.debt_base < U256::from(MIN_BASE_MAX_CLOSE_FACTOR_THRESHOLD));
+    if health_factor < U256::from(CLOSE_FACTOR_HF_THRESHOLD) || small_position {
+        FULL_LIQUIDATION_CLOSE_FACTOR
+    } else {
+        MAX_LIQUIDATION_CLOSE_FACTOR
+    }
+}
+
+/// Debt to cover in one pair: the close factor applies to the user's debt in that reserve, and
+/// the collateral seized (debt plus bonus) can't exceed what they hold of the collateral. Once
+/// the collateral runs out the rest of the debt stays behind (from 3.3 as reserve deficit). From
+/// 3.3 a partial liquidation leaving under $1,000 of either reserve reverts, so it covers the
+/// whole debt instead.
+pub(crate) fn calculate_pair_debt_to_cover(
+    balances: PairBalances,
+    liquidation_bonus_bps: u16,
+    health_factor: U256,
+    pool_version: PoolVersion,
+) -> U256 {
+    let bonus_multiplier = U256::from(10000_u16.saturating_add(liquidation_bonus_bps));
+    let collateral_limit =
+        balances.collateral_base.saturating_mul(U256::from(10000)) / bonus_multiplier;
+    let close_factor = close_factor_bps(balances, health_factor, pool_version);
+    let debt_to_cover =
+        balances.debt_base.saturating_mul(U256::from(close_factor)) / U256::from(10000);
+
+    if debt_to_cover >= collateral_limit {
+        return collateral_limit;
+    }
+    if pool_version.has_dust_rules() && debt_to_cover < balances.debt_base {
+        let leftover_debt = balances.debt_base - debt_to_cover;
+        let leftover_collateral = balances
+            .collateral_base
+            .saturating_sub(debt_to_cover.saturating_mul(bonus_multiplier) / U256::from(10000));
+        let min_leftover = U256::from(MIN_LEFTOVER_BASE);
+        if leftover_debt < min_leftover || leftover_collateral < min_leftover {
+            return balances.debt_base.min(collateral_limit);
+        }
+    }
+    debt_to_cover
+}
+
 /// Calculate expected collateral received including liquidation bonus
 fn calculate_collateral_received(debt_to_cover: U256, liquidation_bonus_bps: u16) -> (U256, U256) {
     // Collateral received = debt_to_cover * (1 + liquidation_bonus)
@@ -149,13 +251,12 @@ fn calculate_collateral_received(debt_to_cover: U256, liquidation_bonus_bps: u16
     (collateral_received, bonus_amount)
 }
 
-/// Calculate Aave flash loan fee (0.05%)
-fn calculate_flash_loan_fee(amount: U256) -> U256 {
-    // Use saturating arithmetic to prevent overflow
+/// Calculate the chosen lender's flash loan fee
+fn calculate_flash_loan_fee(amount: U256, fee_bps: u64) -> U256 {
     if amount.is_zero() {
         return U256::ZERO;
     }
-    amount.saturating_mul(U256::from(FLASH_LOAN_FEE_BPS)) / U256::from(10000)
+    flash_loan::flash_loan_fee(amount, fee_bps)
 }
 
 /// Estimate gas cost for liquidation transaction
@@ -196,12 +297,213 @@ where
 /// Estimate slippage for DEX swap
 fn estimate_swap_slippage(
     amount_in: U256,
-    _collateral_asset: &LiquidationAssetConfig,
-    _debt_asset: &LiquidationAssetConfig,
+    collateral_asset: &LiquidationAssetConfig,
+    debt_asset: &LiquidationAssetConfig,
+    model: ProfitabilityModel,
 ) -> U256 {
-    // Simple slippage estimation: 1% of swap amount
-    // In production, you'd query DEX pools for more accurate estimates
-    amount_in * U256::from(SLIPPAGE_TOLERANCE_BPS) / U256::from(10000)
+    // On-chain quotes are async; this static estimate is their fallback
+    let slippage_bps = match model {
+        ProfitabilityModel::FlatSlippage => SLIPPAGE_TOLERANCE_BPS,
+        ProfitabilityModel::TieredSlippage | ProfitabilityModel::OnChainQuote => {
+            if is_stablecoin(&collateral_asset.symbol) && is_stablecoin(&debt_asset.symbol) {
+                STABLE_PAIR_SLIPPAGE_BPS
+            } else if is_major_collateral(&collateral_asset.symbol)
+                && is_stablecoin(&debt_asset.symbol)
+            {
+                MAJOR_PAIR_SLIPPAGE_BPS
+            } else {
+                SLIPPAGE_TOLERANCE_BPS
+            }
+        }
+    };
+    amount_in * U256::from(slippage_bps) / U256::from(10000)
+}
+
+/// Slippage (bps) of swapping `amount_in` (base currency) of collateral into the debt asset
+/// through the pool the liquidator contract uses: the quoted output's shortfall against the
+/// oracle-implied output, i.e. pool fee plus price impact plus any pool/oracle deviation
+pub async fn quote_swap_slippage_bps<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    amount_in: U256,
+    collateral_asset: &LiquidationAssetConfig,
+    debt_asset: &LiquidationAssetConfig,
+) -> Result<u64>
+where
+    P: Provider,
+{
+    let prices = fetch_oracle_prices(
+        provider,
+        network,
+        &[collateral_asset.address, debt_asset.address],
+    )
+    .await?;
+    let (collateral_price, debt_price) = (prices[0], prices[1]);
+    if collateral_price.is_zero() || debt_price.is_zero() {
+        return Err(eyre::eyre!(
+            "No oracle price for {} or {}",
+            collateral_asset.symbol,
+            debt_asset.symbol
+        ));
+    }
+
+    let collateral_unit = U256::from(10u64).pow(U256::from(collateral_asset.decimals));
+    let debt_unit = U256::from(10u64).pow(U256::from(debt_asset.decimals));
+    let amount_in_tokens = amount_in.saturating_mul(collateral_unit) / collateral_price;
+    let oracle_out = amount_in_tokens
+        .saturating_mul(collateral_price)
+        .saturating_mul(debt_unit)
+        / debt_price.saturating_mul(collateral_unit);
+    if oracle_out.is_zero() {
+        return Err(eyre::eyre!("Swap amount too small to quote"));
+    }
+
+    let route = SwapRoute {
+        tokens: vec![collateral_asset.address, debt_asset.address],
+        fees: vec![CONTRACT_SWAP_FEE_TIER],
+    };
+    let quoted_out =
+        quote_exact_input(provider, network.swap_quoter, &route, amount_in_tokens).await?;
+
+    debug!(
+        "Quoted {} {} -> {} {} (oracle-implied {})",
+        amount_in_tokens, collateral_asset.symbol, quoted_out, debt_asset.symbol, oracle_out
+    );
+    Ok(slippage_bps_from_quote(quoted_out, oracle_out))
+}
+
+/// Shortfall of `quoted_out` against `oracle_out` in basis points (0 when the pool pays more)
+pub fn slippage_bps_from_quote(quoted_out: U256, oracle_out: U256) -> u64 {
+    if oracle_out.is_zero() {
+        return 0;
+    }
+    let shortfall = oracle_out.saturating_sub(quoted_out);
+    (shortfall.saturating_mul(U256::from(10000)) / oracle_out)
+        .min(U256::from(10000))
+        .to::<u64>()
+}
+
+/// Swap slippage of converting `amount_in` of collateral into the debt asset under `model`;
+/// zero for same-asset liquidations. Failed on-chain quotes fall back to the tiered estimate.
+pub async fn model_swap_slippage<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    amount_in: U256,
+    collateral_asset: &LiquidationAssetConfig,
+    debt_asset: &LiquidationAssetConfig,
+    model: ProfitabilityModel,
+) -> U256
+where
+    P: Provider,
+{
+    if collateral_asset.address == debt_asset.address {
+        return U256::ZERO;
+    }
+    if model == ProfitabilityModel::OnChainQuote {
+        match quote_swap_slippage_bps(provider, network, amount_in, collateral_asset, debt_asset)
+            .await
+        {
+            Ok(slippage_bps) => {
+                return amount_in.saturating_mul(U256::from(slippage_bps)) / U256::from(10000)
+            }
+            Err(e) => warn!(
+                "Failed to quote {} -> {} swap, using tiered slippage: {}",
+                collateral_asset.symbol, debt_asset.symbol, e
+            ),
+        }
+    }
+    estimate_swap_slippage(amount_in, collateral_asset, debt_asset, model)
+}
+
+/// Re-price `live` with another slippage model, reusing its debt, bonus, fee and gas figures
+pub fn reprice_with_model(
+    live: &LiquidationOpportunity,
+    collateral_asset: &LiquidationAssetConfig,
+    debt_asset: &LiquidationAssetConfig,
+    min_profit_threshold: U256,
+    model: ProfitabilityModel,
+) -> LiquidationOpportunity {
+    let swap_slippage = if collateral_asset.address != debt_asset.address {
+        estimate_swap_slippage(
+            live.expected_collateral_received,
+            collateral_asset,
+            debt_asset,
+            model,
+        )
+    } else {
+        U256::ZERO
+    };
+    reprice_with_slippage(live, swap_slippage, min_profit_threshold)
+}
+
+/// Re-price `live` with a different swap slippage, reusing every other figure
+pub fn reprice_with_slippage(
+    live: &LiquidationOpportunity,
+    swap_slippage: U256,
+    min_profit_threshold: U256,
+) -> LiquidationOpportunity {
+    let estimated_profit = calculate_net_profit(
+        live.expected_collateral_received,
+        live.debt_to_cover,
+        live.liquidation_bonus,
+        live.flash_loan_fee,
+        live.gas_cost,
+        swap_slippage,
+    );
+
+    LiquidationOpportunity {
+        swap_slippage,
+        estimated_profit,
+        profit_threshold_met: estimated_profit >= min_profit_threshold,
+        ..live.clone()
+    }
+}
+
+/// Price `opportunity` for a wallet-funded liquidation that keeps the seized collateral as
+/// aTokens. Nothing is swapped, so it costs no slippage. None when the flash loan is the cheaper
+/// route, since only liquidations paid from the wallet can keep aTokens.
+pub fn price_a_token_route(
+    opportunity: &LiquidationOpportunity,
+    min_profit_threshold: U256,
+) -> Option<LiquidationOpportunity> {
+    funding::route_costs(opportunity, false)
+        .wallet_is_cheaper()
+        .then(|| reprice_with_slippage(opportunity, U256::ZERO, min_profit_threshold))
+}
+
+/// Compare live and shadow pricing of the same opportunity.
+///
+/// Returns the divergence when the profits differ by more than `threshold_bps` of the larger
+/// of the two; the live opportunity is never modified.
+pub fn detect_profit_divergence(
+    live: &LiquidationOpportunity,
+    shadow: &LiquidationOpportunity,
+    live_model: ProfitabilityModel,
+    shadow_model: ProfitabilityModel,
+    threshold_bps: u64,
+) -> Option<ProfitDivergence> {
+    let larger = live.estimated_profit.max(shadow.estimated_profit);
+    if larger.is_zero() {
+        return None;
+    }
+
+    let difference = live.estimated_profit.abs_diff(shadow.estimated_profit);
+    let divergence_bps = (difference.saturating_mul(U256::from(10000)) / larger).to::<u64>();
+
+    if divergence_bps <= threshold_bps {
+        return None;
+    }
+
+    Some(ProfitDivergence {
+        user: live.user,
+        collateral_asset: live.collateral_asset,
+        debt_asset: live.debt_asset,
+        live_model,
+        shadow_model,
+        live_profit: live.estimated_profit,
+        shadow_profit: shadow.estimated_profit,
+        divergence_bps,
+    })
 }
 
 /// Calculate net profit after all costs
@@ -225,16 +527,36 @@ fn calculate_net_profit(
     }
 }
 
+/// Minimum profit required for a liquidation covering `debt_to_cover`.
+///
+/// The absolute threshold acts as a floor; the percentage threshold scales the requirement
+/// with position size so large liquidations must earn proportionally more.
+pub fn effective_min_profit_threshold(
+    debt_to_cover: U256,
+    min_profit_threshold: U256,
+    min_profit_pct_of_debt_bps: u64,
+) -> U256 {
+    let pct_threshold =
+        debt_to_cover.saturating_mul(U256::from(min_profit_pct_of_debt_bps)) / U256::from(10000);
+    min_profit_threshold.max(pct_threshold)
+}
+
 /// Validate if liquidation opportunity meets minimum requirements
 pub fn validate_liquidation_opportunity(
     opportunity: &LiquidationOpportunity,
     min_profit_threshold: U256,
+    min_profit_pct_of_debt_bps: u64,
 ) -> bool {
-    // Check profitability
-    if opportunity.estimated_profit < min_profit_threshold {
+    // Check profitability against both the absolute and percentage-of-debt thresholds
+    let required_profit = effective_min_profit_threshold(
+        opportunity.debt_to_cover,
+        min_profit_threshold,
+        min_profit_pct_of_debt_bps,
+    );
+    if opportunity.estimated_profit < required_profit {
         debug!(
             "Liquidation rejected: profit {} < threshold {}",
-            opportunity.estimated_profit, min_profit_threshold
+            opportunity.estimated_profit, required_profit
         );
         return false;
     }
@@ -255,6 +577,8 @@ pub fn validate_liquidation_opportunity(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::liquidation::flash_loan::AAVE_V3_FLASH_LOAN_FEE_BPS;
+    use crate::models::FlashLoanSource;
     use alloy_primitives::Address;
     use chrono::Utc;
     use std::str::FromStr;
@@ -342,9 +666,13 @@ mod tests {
         let max_debt_to_cover = calculate_max_debt_to_cover(user_position.total_debt_base);
         let (expected_collateral, liquidation_bonus) =
             calculate_collateral_received(max_debt_to_cover, collateral_asset.liquidation_bonus);
-        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover);
-        let swap_slippage =
-            estimate_swap_slippage(expected_collateral, &collateral_asset, &debt_asset);
+        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover, AAVE_V3_FLASH_LOAN_FEE_BPS);
+        let swap_slippage = estimate_swap_slippage(
+            expected_collateral,
+            &collateral_asset,
+            &debt_asset,
+            ProfitabilityModel::FlatSlippage,
+        );
         let estimated_profit = calculate_net_profit(
             expected_collateral,
             max_debt_to_cover,
@@ -362,6 +690,7 @@ mod tests {
             expected_collateral_received: expected_collateral,
             liquidation_bonus,
             flash_loan_fee,
+            flash_loan_source: FlashLoanSource::AaveV3,
             gas_cost: gas_estimate.total_cost,
             swap_slippage,
             estimated_profit,
@@ -412,9 +741,13 @@ mod tests {
         let max_debt_to_cover = calculate_max_debt_to_cover(user_position.total_debt_base);
         let (expected_collateral, liquidation_bonus) =
             calculate_collateral_received(max_debt_to_cover, collateral_asset.liquidation_bonus);
-        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover);
-        let swap_slippage =
-            estimate_swap_slippage(expected_collateral, &collateral_asset, &debt_asset);
+        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover, AAVE_V3_FLASH_LOAN_FEE_BPS);
+        let swap_slippage = estimate_swap_slippage(
+            expected_collateral,
+            &collateral_asset,
+            &debt_asset,
+            ProfitabilityModel::FlatSlippage,
+        );
         let estimated_profit = calculate_net_profit(
             expected_collateral,
             max_debt_to_cover,
@@ -432,6 +765,7 @@ mod tests {
             expected_collateral_received: expected_collateral,
             liquidation_bonus,
             flash_loan_fee,
+            flash_loan_source: FlashLoanSource::AaveV3,
             gas_cost: gas_estimate.total_cost,
             swap_slippage,
             estimated_profit,
@@ -466,9 +800,13 @@ mod tests {
         let max_debt_to_cover = calculate_max_debt_to_cover(user_position.total_debt_base);
         let (expected_collateral, liquidation_bonus) =
             calculate_collateral_received(max_debt_to_cover, collateral_asset.liquidation_bonus);
-        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover);
-        let swap_slippage =
-            estimate_swap_slippage(expected_collateral, &collateral_asset, &debt_asset);
+        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover, AAVE_V3_FLASH_LOAN_FEE_BPS);
+        let swap_slippage = estimate_swap_slippage(
+            expected_collateral,
+            &collateral_asset,
+            &debt_asset,
+            ProfitabilityModel::FlatSlippage,
+        );
         let estimated_profit = calculate_net_profit(
             expected_collateral,
             max_debt_to_cover,
@@ -486,6 +824,7 @@ mod tests {
             expected_collateral_received: expected_collateral,
             liquidation_bonus,
             flash_loan_fee,
+            flash_loan_source: FlashLoanSource::AaveV3,
             gas_cost: gas_estimate.total_cost,
             swap_slippage,
             estimated_profit,
@@ -497,7 +836,7 @@ mod tests {
         println!("   NET PROFIT: {} wei", opportunity.estimated_profit);
 
         // Validate that small liquidations are rejected
-        let is_valid = validate_liquidation_opportunity(&opportunity, min_profit_threshold);
+        let is_valid = validate_liquidation_opportunity(&opportunity, min_profit_threshold, 0);
         assert!(!is_valid, "Small liquidations should be rejected");
     }
 
@@ -522,9 +861,10 @@ mod tests {
         );
 
         // Test flash loan fee calculation
-        let flash_fee = calculate_flash_loan_fee(debt_amount);
+        let flash_fee = calculate_flash_loan_fee(debt_amount, AAVE_V3_FLASH_LOAN_FEE_BPS);
         let expected_fee = debt_amount * U256::from(5) / U256::from(10000); // 0.05%
         assert_eq!(flash_fee, expected_fee);
+        assert_eq!(calculate_flash_loan_fee(debt_amount, 0), U256::ZERO); // Balancer / Morpho
 
         println!("🧪 EDGE CASE CALCULATIONS:");
         println!(
@@ -536,6 +876,50 @@ mod tests {
         println!("   Flash loan fee: {} wei", flash_fee);
     }
 
+    #[test]
+    fn test_pair_debt_to_cover_follows_reserve_balances() {
+        let usd = |value: u64| U256::from(value) * U256::from(100_000_000u64);
+
+        let hf = |value: u64| U256::from(value) * U256::from(10_000_000_000_000_000u64);
+        let pair = |collateral: u64, debt: u64| PairBalances {
+            collateral_base: usd(collateral),
+            debt_base: usd(debt),
+        };
+
+        // Close factor applies to the debt in the pair's reserve, not the aggregate debt
+        let cover = |balances, health_factor| {
+            calculate_pair_debt_to_cover(balances, 500, health_factor, PoolVersion::V3_0)
+        };
+        assert_eq!(cover(pair(10_000, 1_000), hf(98)), usd(500));
+        // Whole reserve debt under HF 0.95
+        assert_eq!(cover(pair(10_000, 1_000), hf(90)), usd(1_000));
+        // Small collateral reserve: seized collateral (debt + 5%) is capped at its balance
+        assert_eq!(cover(pair(210, 1_000), hf(98)), usd(200));
+    }
+
+    #[test]
+    fn test_aave_3_3_close_factor_and_dust_rules() {
+        let usd = |value: u64| U256::from(value) * U256::from(100_000_000u64);
+        let hf = U256::from(980_000_000_000_000_000u64);
+        let pair = |collateral: u64, debt: u64| PairBalances {
+            collateral_base: usd(collateral),
+            debt_base: usd(debt),
+        };
+        let cover = |balances, version| calculate_pair_debt_to_cover(balances, 500, hf, version);
+
+        // Debt under $2,000: 3.0 halves it, 3.3 liquidates all of it
+        assert_eq!(cover(pair(10_000, 1_500), PoolVersion::V3_0), usd(750));
+        assert_eq!(cover(pair(10_000, 1_500), PoolVersion::V3_3), usd(1_500));
+
+        // Large position: half the debt, leaving more than $1,000 of both reserves
+        assert_eq!(cover(pair(10_000, 5_000), PoolVersion::V3_3), usd(2_500));
+
+        // Half would leave $3,570 - $2,625 = $945 of collateral behind, so 3.3 requires taking
+        // all of it (as much debt as the collateral covers)
+        assert_eq!(cover(pair(3_570, 5_000), PoolVersion::V3_3), usd(3_400));
+        assert_eq!(cover(pair(3_570, 5_000), PoolVersion::V3_1), usd(2_500));
+    }
+
     #[tokio::test]
     async fn test_same_asset_liquidation() {
         // Test liquidation where collateral and debt are the same asset (no swap needed)
@@ -548,13 +932,18 @@ mod tests {
         let max_debt_to_cover = calculate_max_debt_to_cover(user_position.total_debt_base);
         let (expected_collateral, liquidation_bonus) =
             calculate_collateral_received(max_debt_to_cover, weth_config.liquidation_bonus);
-        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover);
+        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover, AAVE_V3_FLASH_LOAN_FEE_BPS);
 
         // Same asset liquidation - should have zero slippage
         let swap_slippage = if weth_config.address == weth_config.address {
             U256::ZERO
         } else {
-            estimate_swap_slippage(expected_collateral, &weth_config, &weth_config)
+            estimate_swap_slippage(
+                expected_collateral,
+                &weth_config,
+                &weth_config,
+                ProfitabilityModel::FlatSlippage,
+            )
         };
 
         let estimated_profit = calculate_net_profit(
@@ -574,6 +963,7 @@ mod tests {
             expected_collateral_received: expected_collateral,
             liquidation_bonus,
             flash_loan_fee,
+            flash_loan_source: FlashLoanSource::AaveV3,
             gas_cost: gas_estimate.total_cost,
             swap_slippage,
             estimated_profit,
@@ -619,9 +1009,13 @@ mod tests {
         let max_debt_to_cover = calculate_max_debt_to_cover(user_position.total_debt_base);
         let (expected_collateral, liquidation_bonus) =
             calculate_collateral_received(max_debt_to_cover, collateral_asset.liquidation_bonus);
-        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover);
-        let swap_slippage =
-            estimate_swap_slippage(expected_collateral, &collateral_asset, &debt_asset);
+        let flash_loan_fee = calculate_flash_loan_fee(max_debt_to_cover, AAVE_V3_FLASH_LOAN_FEE_BPS);
+        let swap_slippage = estimate_swap_slippage(
+            expected_collateral,
+            &collateral_asset,
+            &debt_asset,
+            ProfitabilityModel::FlatSlippage,
+        );
         let estimated_profit = calculate_net_profit(
             expected_collateral,
             max_debt_to_cover,
@@ -639,6 +1033,7 @@ mod tests {
             expected_collateral_received: expected_collateral,
             liquidation_bonus,
             flash_loan_fee,
+            flash_loan_source: FlashLoanSource::AaveV3,
             gas_cost: gas_estimate.total_cost,
             swap_slippage,
             estimated_profit,
@@ -679,4 +1074,129 @@ mod tests {
             println!("   ❌ This liquidation is not profitable enough");
         }
     }
+
+    #[test]
+    fn test_percentage_of_debt_profit_threshold() {
+        let one_eth = U256::from(1_000_000_000_000_000_000u64);
+        let min_profit_threshold = one_eth / U256::from(100); // 0.01 ETH absolute floor
+        let min_profit_pct_of_debt_bps = 100; // 1% of debt covered
+
+        let opportunity = |debt_to_cover: U256, estimated_profit: U256| LiquidationOpportunity {
+            user: Address::ZERO,
+            collateral_asset: Address::ZERO,
+            debt_asset: Address::ZERO,
+            debt_to_cover,
+            expected_collateral_received: U256::ZERO,
+            liquidation_bonus: U256::ZERO,
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            gas_cost: U256::ZERO,
+            swap_slippage: U256::ZERO,
+            estimated_profit,
+            profit_threshold_met: false,
+        };
+
+        // Small liquidation: 1 ETH of debt, 0.02 ETH profit (2%) - the absolute floor applies
+        let small = opportunity(one_eth, one_eth / U256::from(50));
+        assert_eq!(
+            effective_min_profit_threshold(
+                small.debt_to_cover,
+                min_profit_threshold,
+                min_profit_pct_of_debt_bps
+            ),
+            min_profit_threshold
+        );
+        assert!(validate_liquidation_opportunity(
+            &small,
+            min_profit_threshold,
+            min_profit_pct_of_debt_bps
+        ));
+
+        // Large liquidation: 100 ETH of debt, 0.5 ETH profit (0.5%) - needs 1 ETH
+        let large = opportunity(one_eth * U256::from(100), one_eth / U256::from(2));
+        assert_eq!(
+            effective_min_profit_threshold(
+                large.debt_to_cover,
+                min_profit_threshold,
+                min_profit_pct_of_debt_bps
+            ),
+            one_eth
+        );
+        assert!(!validate_liquidation_opportunity(
+            &large,
+            min_profit_threshold,
+            min_profit_pct_of_debt_bps
+        ));
+
+        // With the percentage threshold disabled the large liquidation passes on absolute profit
+        assert!(validate_liquidation_opportunity(&large, min_profit_threshold, 0));
+    }
+
+    #[test]
+    fn test_shadow_model_divergence_is_detected() {
+        let weth = create_weth_config();
+        let usdc = create_usdc_config();
+        let one_eth = U256::from(1_000_000_000_000_000_000u64);
+
+        let (expected_collateral, liquidation_bonus) =
+            calculate_collateral_received(one_eth * U256::from(10), weth.liquidation_bonus);
+        let live = LiquidationOpportunity {
+            user: Address::from([1u8; 20]),
+            collateral_asset: weth.address,
+            debt_asset: usdc.address,
+            debt_to_cover: one_eth * U256::from(10),
+            expected_collateral_received: expected_collateral,
+            liquidation_bonus,
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            gas_cost: U256::ZERO,
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::ZERO,
+            profit_threshold_met: false,
+        };
+        let live =
+            reprice_with_model(&live, &weth, &usdc, U256::ZERO, ProfitabilityModel::FlatSlippage);
+        let shadow =
+            reprice_with_model(&live, &weth, &usdc, U256::ZERO, ProfitabilityModel::TieredSlippage);
+
+        // Tiered slippage for WETH -> USDC is half the flat 1%, so the shadow model is more optimistic
+        assert!(shadow.estimated_profit > live.estimated_profit);
+        // Re-pricing never touches the live opportunity's inputs
+        assert_eq!(shadow.debt_to_cover, live.debt_to_cover);
+        assert_eq!(shadow.liquidation_bonus, live.liquidation_bonus);
+
+        let divergence = detect_profit_divergence(
+            &live,
+            &shadow,
+            ProfitabilityModel::FlatSlippage,
+            ProfitabilityModel::TieredSlippage,
+            100,
+        )
+        .expect("models disagree by more than 1%");
+        assert_eq!(divergence.user, live.user);
+        assert_eq!(divergence.live_profit, live.estimated_profit);
+        assert_eq!(divergence.shadow_profit, shadow.estimated_profit);
+        assert!(divergence.divergence_bps > 100);
+
+        // A wide enough threshold tolerates the disagreement
+        assert!(detect_profit_divergence(
+            &live,
+            &shadow,
+            ProfitabilityModel::FlatSlippage,
+            ProfitabilityModel::TieredSlippage,
+            10_000,
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn test_slippage_from_quote() {
+        let oracle_out = U256::from(1_000_000u64);
+        // 0.3% fee plus 0.2% price impact
+        assert_eq!(slippage_bps_from_quote(U256::from(995_000u64), oracle_out), 50);
+        // Pool paying above the oracle isn't negative slippage
+        assert_eq!(slippage_bps_from_quote(U256::from(1_001_000u64), oracle_out), 0);
+        assert_eq!(slippage_bps_from_quote(U256::ZERO, oracle_out), 10_000);
+        assert_eq!(slippage_bps_from_quote(U256::ZERO, U256::ZERO), 0);
+    }
 }
@@ -0,0 +1,113 @@
+use alloy_primitives::Address;
+use eyre::Result;
+use std::future::Future;
+use tracing::{info, warn};
+
+use crate::database::{self, DatabasePool};
+
+/// Reload liquidations persisted before a restart and re-validate each one.
+///
+/// Positions that are no longer liquidatable are dropped from the persisted queue. The
+/// returned users should be re-queued for liquidation; they stay persisted until processed.
+/// Users whose validation fails (e.g. a transient RPC error) are kept, since the liquidation
+/// pipeline re-checks the position anyway.
+pub async fn restore_persisted_queue<F, Fut>(
+    db_pool: &DatabasePool,
+    mut is_still_liquidatable: F,
+) -> Result<Vec<Address>>
+where
+    F: FnMut(Address) -> Fut,
+    Fut: Future<Output = Result<bool>>,
+{
+    let pending = database::get_pending_liquidations(db_pool).await?;
+    if pending.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    info!(
+        "📥 Restoring {} persisted liquidation(s) from previous run",
+        pending.len()
+    );
+
+    let mut requeue = Vec::with_capacity(pending.len());
+    for user in pending {
+        match is_still_liquidatable(user).await {
+            Ok(true) => requeue.push(user),
+            Ok(false) => {
+                info!(
+                    "🧹 Dropping persisted liquidation for {:?} - no longer liquidatable",
+                    user
+                );
+                database::dequeue_pending_liquidation(db_pool, user).await?;
+            }
+            Err(e) => {
+                warn!(
+                    "Failed to re-validate persisted liquidation for {:?}: {} - re-queuing anyway",
+                    user, e
+                );
+                requeue.push(user);
+            }
+        }
+    }
+
+    Ok(requeue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tests::create_test_pool;
+
+    #[tokio::test]
+    async fn test_persisted_entry_is_revalidated_on_startup() {
+        let db_pool = create_test_pool().await;
+        let still_underwater = Address::from([1u8; 20]);
+        let recovered = Address::from([2u8; 20]);
+
+        // Entries left behind by a crash
+        database::enqueue_pending_liquidation(&db_pool, still_underwater)
+            .await
+            .unwrap();
+        database::enqueue_pending_liquidation(&db_pool, recovered)
+            .await
+            .unwrap();
+        // Re-enqueuing is idempotent
+        database::enqueue_pending_liquidation(&db_pool, still_underwater)
+            .await
+            .unwrap();
+
+        let requeue = restore_persisted_queue(&db_pool, |user| async move {
+            Ok(user == still_underwater)
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(requeue, vec![still_underwater]);
+        // The recovered position is dropped, the liquidatable one stays until processed
+        assert_eq!(
+            database::get_pending_liquidations(&db_pool).await.unwrap(),
+            vec![still_underwater]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_validation_error_keeps_entry() {
+        let db_pool = create_test_pool().await;
+        let user = Address::from([3u8; 20]);
+        database::enqueue_pending_liquidation(&db_pool, user)
+            .await
+            .unwrap();
+
+        let requeue = restore_persisted_queue(&db_pool, |_| async {
+            Err(eyre::eyre!("rpc unavailable"))
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(requeue, vec![user]);
+        assert_eq!(
+            database::get_pending_liquidations(&db_pool).await.unwrap(),
+            vec![user]
+        );
+    }
+}
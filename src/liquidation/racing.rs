@@ -0,0 +1,208 @@
+use alloy_primitives::U256;
+use parking_lot::Mutex;
+use std::collections::VecDeque;
+use tracing::info;
+
+/// Share of the bid budget spent before any race outcome has been observed
+const DEFAULT_PRESSURE_BPS: u64 = 5_000;
+
+/// Least share of the budget spent, however often recent races were won
+const MIN_PRESSURE_BPS: u64 = 2_500;
+
+/// What one liquidation can afford to spend competing for inclusion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RaceBudget {
+    pub expected_profit: U256,
+    /// Profit the liquidation must still clear after paying for its bid
+    pub required_profit: U256,
+}
+
+impl RaceBudget {
+    /// Most the bid may cost in total: `max_profit_share_bps` of the expected profit, never
+    /// eating into the required minimum
+    pub fn max_spend(&self, max_profit_share_bps: u64) -> U256 {
+        let share = self.expected_profit.saturating_mul(U256::from(max_profit_share_bps))
+            / U256::from(10_000u64);
+        share.min(self.expected_profit.saturating_sub(self.required_profit))
+    }
+}
+
+/// Portion of the budget to spend given the recent win rate: races we keep losing are bid
+/// closer to the cap, races we keep winning back off towards `MIN_PRESSURE_BPS`
+pub fn bid_pressure_bps(win_rate_bps: Option<u64>) -> u64 {
+    match win_rate_bps {
+        Some(win_rate) => 10_000u64.saturating_sub(win_rate).max(MIN_PRESSURE_BPS),
+        None => DEFAULT_PRESSURE_BPS,
+    }
+}
+
+/// Priority fee per gas for a liquidation using up to `gas_limit` gas: `base_priority_fee`
+/// (the gas oracle's profile tip) raised to spend `pressure_bps` of the budget
+pub fn racing_priority_fee(
+    base_priority_fee: u128,
+    budget_wei: U256,
+    gas_limit: u64,
+    pressure_bps: u64,
+) -> u128 {
+    if gas_limit == 0 {
+        return base_priority_fee;
+    }
+    let spend = budget_wei.saturating_mul(U256::from(pressure_bps)) / U256::from(10_000u64);
+    let per_gas: u128 = (spend / U256::from(gas_limit)).try_into().unwrap_or(u128::MAX);
+    per_gas.max(base_priority_fee)
+}
+
+/// Tip that beats a rival's pending liquidation: 10% above it plus one wei, so a rival's
+/// minimal fee bump doesn't immediately retake the lead
+pub fn outbid_priority_fee(rival_priority_fee: u128) -> u128 {
+    rival_priority_fee
+        .saturating_add(rival_priority_fee / 10)
+        .saturating_add(1)
+}
+
+/// Scales each liquidation's priority fee with its expected profit and the bot's recent
+/// win rate, so large liquidations are bid aggressively and marginal ones don't overpay
+pub struct RacingStrategy {
+    max_profit_share_bps: u64,
+    window: usize,
+    /// Most recent race outcomes (true = our transaction landed), oldest first
+    outcomes: Mutex<VecDeque<bool>>,
+}
+
+impl RacingStrategy {
+    pub fn new(max_profit_share_bps: u64, window: usize) -> Self {
+        Self {
+            max_profit_share_bps,
+            window: window.max(1),
+            outcomes: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// A zero profit share disables racing
+    pub fn is_enabled(&self) -> bool {
+        self.max_profit_share_bps > 0
+    }
+
+    pub fn record_outcome(&self, won: bool) {
+        let mut outcomes = self.outcomes.lock();
+        outcomes.push_back(won);
+        while outcomes.len() > self.window {
+            outcomes.pop_front();
+        }
+    }
+
+    /// Share of recent races won, in basis points (None before the first race)
+    pub fn win_rate_bps(&self) -> Option<u64> {
+        let outcomes = self.outcomes.lock();
+        if outcomes.is_empty() {
+            return None;
+        }
+        let won = outcomes.iter().filter(|won| **won).count() as u64;
+        Some(won * 10_000 / outcomes.len() as u64)
+    }
+
+    /// Priority fee to bid for a liquidation with `budget`
+    pub fn priority_fee(
+        &self,
+        base_priority_fee: u128,
+        budget: &RaceBudget,
+        gas_limit: u64,
+    ) -> u128 {
+        let win_rate_bps = self.win_rate_bps();
+        let priority_fee = racing_priority_fee(
+            base_priority_fee,
+            budget.max_spend(self.max_profit_share_bps),
+            gas_limit,
+            bid_pressure_bps(win_rate_bps),
+        );
+        if priority_fee > base_priority_fee {
+            info!(
+                "🏁 Racing bid: priority fee {} -> {} wei (expected profit {} wei, win rate {})",
+                base_priority_fee,
+                priority_fee,
+                budget.expected_profit,
+                win_rate_bps.map_or_else(|| "n/a".to_string(), |bps| format!("{} bps", bps))
+            );
+        }
+        priority_fee
+    }
+
+    /// Highest priority fee that fee bumps may escalate to: the whole budget over `gas_limit`
+    pub fn max_priority_fee(
+        &self,
+        base_priority_fee: u128,
+        budget: &RaceBudget,
+        gas_limit: u64,
+    ) -> u128 {
+        racing_priority_fee(
+            base_priority_fee,
+            budget.max_spend(self.max_profit_share_bps),
+            gas_limit,
+            10_000,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn budget(expected: u64, required: u64) -> RaceBudget {
+        RaceBudget {
+            expected_profit: U256::from(expected),
+            required_profit: U256::from(required),
+        }
+    }
+
+    #[test]
+    fn test_budget_never_eats_into_required_profit() {
+        // 20% of a large surplus
+        assert_eq!(budget(1_000_000, 100_000).max_spend(2_000), U256::from(200_000u64));
+        // Marginal opportunity: only the surplus over the minimum can be spent
+        assert_eq!(budget(105_000, 100_000).max_spend(2_000), U256::from(5_000u64));
+        assert_eq!(budget(90_000, 100_000).max_spend(2_000), U256::ZERO);
+    }
+
+    #[test]
+    fn test_big_liquidations_outbid_marginal_ones() {
+        let strategy = RacingStrategy::new(2_000, 10);
+        let base_tip = 1_000u128;
+
+        let big = budget(1_000_000_000_000, 100_000_000_000);
+        let marginal = budget(100_500_000_000, 100_000_000_000);
+
+        // 20% of the profit at 50% pressure spread over 500k gas
+        assert_eq!(strategy.priority_fee(base_tip, &big, 500_000), 200_000);
+        // Half the 0.5e9 surplus over 500k gas is below the profile tip, which is kept
+        assert_eq!(strategy.priority_fee(base_tip, &marginal, 500_000), base_tip);
+
+        // Fee bumps may escalate to the whole budget
+        assert_eq!(strategy.max_priority_fee(base_tip, &big, 500_000), 400_000);
+    }
+
+    #[test]
+    fn test_outbid_clears_rival_tip() {
+        assert_eq!(outbid_priority_fee(0), 1);
+        assert_eq!(outbid_priority_fee(1_000), 1_101);
+        assert_eq!(outbid_priority_fee(u128::MAX), u128::MAX);
+    }
+
+    #[test]
+    fn test_losing_streak_raises_pressure() {
+        let strategy = RacingStrategy::new(1_000, 4);
+        assert_eq!(bid_pressure_bps(strategy.win_rate_bps()), DEFAULT_PRESSURE_BPS);
+
+        for won in [true, true, true, true] {
+            strategy.record_outcome(won);
+        }
+        assert_eq!(strategy.win_rate_bps(), Some(10_000));
+        assert_eq!(bid_pressure_bps(strategy.win_rate_bps()), MIN_PRESSURE_BPS);
+
+        // Window of four: three losses push the oldest wins out
+        for _ in 0..3 {
+            strategy.record_outcome(false);
+        }
+        assert_eq!(strategy.win_rate_bps(), Some(2_500));
+        assert_eq!(bid_pressure_bps(strategy.win_rate_bps()), 7_500);
+    }
+}
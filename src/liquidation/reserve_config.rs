@@ -0,0 +1,430 @@
+use alloy_contract::ContractInstance;
+use alloy_dyn_abi::DynSolValue;
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use dashmap::DashMap;
+use eyre::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::models::LiquidationAssetConfig;
+
+// Bit layout of Aave V3's ReserveConfigurationMap
+const LTV_START_BIT: usize = 0;
+const LIQUIDATION_THRESHOLD_START_BIT: usize = 16;
+const LIQUIDATION_BONUS_START_BIT: usize = 32;
+const DECIMALS_START_BIT: usize = 48;
+const ACTIVE_BIT: usize = 56;
+const FROZEN_BIT: usize = 57;
+const BORROWING_ENABLED_BIT: usize = 58;
+const PAUSED_BIT: usize = 60;
+const BORROWABLE_IN_ISOLATION_BIT: usize = 61;
+const SILOED_BORROWING_BIT: usize = 62;
+const FLASH_LOAN_ENABLED_BIT: usize = 63;
+const RESERVE_FACTOR_START_BIT: usize = 64;
+const BORROW_CAP_START_BIT: usize = 80;
+const SUPPLY_CAP_START_BIT: usize = 116;
+const LIQUIDATION_PROTOCOL_FEE_START_BIT: usize = 152;
+const EMODE_CATEGORY_START_BIT: usize = 168;
+const DEBT_CEILING_START_BIT: usize = 212;
+const CAP_BITS: usize = 36;
+const DEBT_CEILING_BITS: usize = 40;
+
+/// Liquidation bonuses are stored as 10000 plus the bonus (e.g. 10500 = 5%)
+const BONUS_BASE_BPS: u16 = 10_000;
+
+/// `ReserveDataUpdated` is emitted on every supply, borrow and repay, so a reserve is re-read
+/// at most this often in response to it
+const EVENT_REFRESH_MIN_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Decoded reserve configuration bitmap
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReserveConfig {
+    pub ltv_bps: u16,
+    pub liquidation_threshold_bps: u16,
+    /// Bonus on top of the repaid debt, in basis points (500 = 5%)
+    pub liquidation_bonus_bps: u16,
+    pub decimals: u8,
+    pub active: bool,
+    pub frozen: bool,
+    pub borrowing_enabled: bool,
+    pub paused: bool,
+    pub borrowable_in_isolation: bool,
+    pub siloed_borrowing: bool,
+    pub flash_loan_enabled: bool,
+    pub reserve_factor_bps: u16,
+    /// Whole tokens, 0 = no cap
+    pub borrow_cap: u64,
+    /// Whole tokens, 0 = no cap
+    pub supply_cap: u64,
+    pub liquidation_protocol_fee_bps: u16,
+    pub emode_category: u8,
+    /// Isolation mode debt ceiling in USD with 2 decimals, 0 = not an isolated asset
+    pub debt_ceiling: u64,
+}
+
+fn bits(configuration: U256, start: usize, width: usize) -> u64 {
+    let mask = (U256::from(1u8) << width) - U256::from(1u8);
+    ((configuration >> start) & mask).saturating_to()
+}
+
+impl ReserveConfig {
+    pub fn decode(configuration: U256) -> Self {
+        let raw_bonus = bits(configuration, LIQUIDATION_BONUS_START_BIT, 16) as u16;
+        Self {
+            ltv_bps: bits(configuration, LTV_START_BIT, 16) as u16,
+            liquidation_threshold_bps: bits(configuration, LIQUIDATION_THRESHOLD_START_BIT, 16)
+                as u16,
+            liquidation_bonus_bps: raw_bonus.saturating_sub(BONUS_BASE_BPS),
+            decimals: bits(configuration, DECIMALS_START_BIT, 8) as u8,
+            active: configuration.bit(ACTIVE_BIT),
+            frozen: configuration.bit(FROZEN_BIT),
+            borrowing_enabled: configuration.bit(BORROWING_ENABLED_BIT),
+            paused: configuration.bit(PAUSED_BIT),
+            borrowable_in_isolation: configuration.bit(BORROWABLE_IN_ISOLATION_BIT),
+            siloed_borrowing: configuration.bit(SILOED_BORROWING_BIT),
+            flash_loan_enabled: configuration.bit(FLASH_LOAN_ENABLED_BIT),
+            reserve_factor_bps: bits(configuration, RESERVE_FACTOR_START_BIT, 16) as u16,
+            borrow_cap: bits(configuration, BORROW_CAP_START_BIT, CAP_BITS),
+            supply_cap: bits(configuration, SUPPLY_CAP_START_BIT, CAP_BITS),
+            liquidation_protocol_fee_bps: bits(
+                configuration,
+                LIQUIDATION_PROTOCOL_FEE_START_BIT,
+                16,
+            ) as u16,
+            emode_category: bits(configuration, EMODE_CATEGORY_START_BIT, 8) as u8,
+            debt_ceiling: bits(configuration, DEBT_CEILING_START_BIT, DEBT_CEILING_BITS),
+        }
+    }
+
+    /// Collateral with a debt ceiling puts its holder in isolation mode
+    pub fn is_isolated(&self) -> bool {
+        self.debt_ceiling > 0
+    }
+
+    /// The pool lets liquidators seize collateral from active, unpaused reserves with a
+    /// liquidation threshold; frozen reserves can still be liquidated
+    pub fn can_seize_as_collateral(&self) -> bool {
+        self.active && !self.paused && self.liquidation_threshold_bps > 0
+    }
+
+    /// Debt can be repaid in a liquidation while the reserve is active and unpaused, even
+    /// once new borrowing is disabled or the reserve is frozen
+    pub fn can_repay_as_debt(&self) -> bool {
+        self.active && !self.paused
+    }
+
+    /// Overwrite the on-chain parameters of a configured liquidation asset
+    pub fn apply_to(&self, asset: &mut LiquidationAssetConfig) {
+        asset.decimals = self.decimals;
+        asset.liquidation_bonus = self.liquidation_bonus_bps;
+        asset.is_collateral = self.can_seize_as_collateral();
+        asset.is_borrowable = self.can_repay_as_debt();
+    }
+}
+
+/// Raw configuration bitmap of every reserve of the pool, in reserve list order
+pub async fn fetch_reserve_configurations<P>(
+    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+) -> Result<Vec<(Address, U256)>>
+where
+    P: Provider,
+{
+    let reserves_call = pool_contract.function("getReservesList", &[])?;
+    let reserves_result = reserves_call.call().await?;
+
+    let reserves: Vec<Address> = match reserves_result.first() {
+        Some(DynSolValue::Array(array)) => array
+            .iter()
+            .filter_map(|value| value.as_address())
+            .collect(),
+        _ => return Err(eyre::eyre!("getReservesList result is not an array")),
+    };
+
+    let mut configurations = Vec::with_capacity(reserves.len());
+    for reserve in reserves {
+        let configuration = fetch_reserve_configuration(pool_contract, reserve).await?;
+        configurations.push((reserve, configuration));
+    }
+    Ok(configurations)
+}
+
+/// Raw configuration bitmap of one reserve
+pub async fn fetch_reserve_configuration<P>(
+    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    reserve: Address,
+) -> Result<U256>
+where
+    P: Provider,
+{
+    let args = [DynSolValue::Address(reserve)];
+    let result = pool_contract
+        .function("getConfiguration", &args)?
+        .call()
+        .await?;
+
+    match result.first() {
+        Some(DynSolValue::Tuple(tuple)) => tuple
+            .first()
+            .and_then(|value| value.as_uint())
+            .map(|(data, _)| data),
+        _ => None,
+    }
+    .ok_or_else(|| eyre::eyre!("Invalid getConfiguration result for {}", reserve))
+}
+
+#[derive(Debug, Clone, Copy)]
+struct CachedReserveConfig {
+    config: ReserveConfig,
+    refreshed_at: Instant,
+}
+
+/// On-chain reserve configurations, refreshed periodically and when the pool reports a
+/// reserve update. Overlaid on the loaded liquidation assets so profitability and pair
+/// selection use the live bonus and flags rather than the values known at startup.
+#[derive(Debug, Default)]
+pub struct ReserveConfigCache {
+    configs: DashMap<Address, CachedReserveConfig>,
+}
+
+impl ReserveConfigCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self, reserve: Address) -> Option<ReserveConfig> {
+        self.configs.get(&reserve).map(|cached| cached.config)
+    }
+
+    /// Every reserve with a cached configuration
+    pub fn reserves(&self) -> Vec<Address> {
+        self.configs.iter().map(|entry| *entry.key()).collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.configs.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.configs.is_empty()
+    }
+
+    /// Store a reserve's configuration, returning true if it differs from the cached one
+    pub fn insert(&self, reserve: Address, config: ReserveConfig) -> bool {
+        let cached = CachedReserveConfig {
+            config,
+            refreshed_at: Instant::now(),
+        };
+        match self.configs.insert(reserve, cached) {
+            Some(previous) if previous.config != config => {
+                info!(
+                    "🔧 Reserve {:?} configuration changed: bonus {} -> {}bps, LT {} -> {}bps, \
+                     frozen {} -> {}, paused {} -> {}",
+                    reserve,
+                    previous.config.liquidation_bonus_bps,
+                    config.liquidation_bonus_bps,
+                    previous.config.liquidation_threshold_bps,
+                    config.liquidation_threshold_bps,
+                    previous.config.frozen,
+                    config.frozen,
+                    previous.config.paused,
+                    config.paused
+                );
+                true
+            }
+            Some(_) => false,
+            None => true,
+        }
+    }
+
+    /// Liquidation assets with each cached reserve's live parameters applied; assets
+    /// without a cached configuration keep their loaded values
+    pub fn overlay(
+        &self,
+        assets: &HashMap<Address, LiquidationAssetConfig>,
+    ) -> HashMap<Address, LiquidationAssetConfig> {
+        let mut assets = assets.clone();
+        for (address, asset) in assets.iter_mut() {
+            if let Some(config) = self.get(*address) {
+                config.apply_to(asset);
+            }
+        }
+        assets
+    }
+
+    /// Re-read every reserve's configuration, returning how many changed
+    pub async fn refresh_all<P>(
+        &self,
+        pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    ) -> Result<usize>
+    where
+        P: Provider,
+    {
+        let configurations = fetch_reserve_configurations(pool_contract).await?;
+        let changed = configurations
+            .into_iter()
+            .filter(|(reserve, configuration)| {
+                self.insert(*reserve, ReserveConfig::decode(*configuration))
+            })
+            .count();
+        Ok(changed)
+    }
+
+    /// Re-read one reserve after the pool reported an update, unless it was read recently
+    pub async fn refresh_reserve<P>(
+        &self,
+        pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+        reserve: Address,
+    ) -> Result<()>
+    where
+        P: Provider,
+    {
+        let recently_refreshed = self
+            .configs
+            .get(&reserve)
+            .is_some_and(|cached| cached.refreshed_at.elapsed() < EVENT_REFRESH_MIN_INTERVAL);
+        if recently_refreshed {
+            return Ok(());
+        }
+
+        let configuration = fetch_reserve_configuration(pool_contract, reserve).await?;
+        self.insert(reserve, ReserveConfig::decode(configuration));
+        Ok(())
+    }
+}
+
+/// Load every reserve's configuration, then re-read it every `refresh_interval_secs`
+/// (0 = only at startup and on reserve updates)
+pub async fn run_reserve_config_refresher<P>(
+    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    cache: Arc<ReserveConfigCache>,
+    refresh_interval_secs: u64,
+) -> Result<()>
+where
+    P: Provider,
+{
+    info!(
+        "🔧 Starting reserve configuration cache (refresh every {} seconds)",
+        refresh_interval_secs
+    );
+
+    let mut interval = (refresh_interval_secs > 0)
+        .then(|| tokio::time::interval(Duration::from_secs(refresh_interval_secs)));
+
+    loop {
+        if let Some(interval) = interval.as_mut() {
+            interval.tick().await;
+        }
+
+        match cache.refresh_all(pool_contract).await {
+            Ok(changed) => debug!(
+                "Reserve configurations refreshed: {} reserves, {} changed",
+                cache.len(),
+                changed
+            ),
+            Err(e) => warn!("Failed to refresh reserve configurations: {}", e),
+        }
+
+        if interval.is_none() {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// WETH on Base: LTV 80%, LT 83%, bonus 5%, 18 decimals, active, borrowing (also in
+    /// isolation mode) and flash loans enabled, 15% reserve factor, caps of 1,000 borrowed /
+    /// 2,000 supplied
+    fn weth_configuration() -> U256 {
+        U256::from(8_000u64)
+            | U256::from(8_300u64) << LIQUIDATION_THRESHOLD_START_BIT
+            | U256::from(10_500u64) << LIQUIDATION_BONUS_START_BIT
+            | U256::from(18u64) << DECIMALS_START_BIT
+            | U256::from(1u8) << ACTIVE_BIT
+            | U256::from(1u8) << BORROWING_ENABLED_BIT
+            | U256::from(1u8) << FLASH_LOAN_ENABLED_BIT
+            | U256::from(1_500u64) << RESERVE_FACTOR_START_BIT
+            | U256::from(1_000u64) << BORROW_CAP_START_BIT
+            | U256::from(2_000u64) << SUPPLY_CAP_START_BIT
+            | U256::from(1_000u64) << LIQUIDATION_PROTOCOL_FEE_START_BIT
+            | U256::from(1u8) << BORROWABLE_IN_ISOLATION_BIT
+    }
+
+    #[test]
+    fn test_configuration_bitmap_is_decoded() {
+        let config = ReserveConfig::decode(weth_configuration());
+
+        assert_eq!(
+            config,
+            ReserveConfig {
+                ltv_bps: 8_000,
+                liquidation_threshold_bps: 8_300,
+                liquidation_bonus_bps: 500,
+                decimals: 18,
+                active: true,
+                frozen: false,
+                borrowing_enabled: true,
+                paused: false,
+                borrowable_in_isolation: true,
+                siloed_borrowing: false,
+                flash_loan_enabled: true,
+                reserve_factor_bps: 1_500,
+                borrow_cap: 1_000,
+                supply_cap: 2_000,
+                liquidation_protocol_fee_bps: 1_000,
+                emode_category: 0,
+                debt_ceiling: 0,
+            }
+        );
+        assert!(!config.is_isolated());
+        assert!(config.can_seize_as_collateral());
+        assert!(config.can_repay_as_debt());
+    }
+
+    #[test]
+    fn test_overlay_applies_live_bonus_and_flags() {
+        let weth = Address::repeat_byte(0x01);
+        let usdc = Address::repeat_byte(0x02);
+        let asset = |address, symbol: &str, decimals| LiquidationAssetConfig {
+            address,
+            symbol: symbol.to_string(),
+            decimals,
+            asset_id: 0,
+            liquidation_bonus: 500,
+            is_collateral: true,
+            is_borrowable: true,
+        };
+        let assets =
+            HashMap::from([(weth, asset(weth, "WETH", 18)), (usdc, asset(usdc, "USDC", 6))]);
+
+        let cache = ReserveConfigCache::new();
+        // Governance raised the bonus to 7.5%, then froze and later paused the reserve
+        let bonus_mask = U256::from(0xffffu64) << LIQUIDATION_BONUS_START_BIT;
+        let raised = (weth_configuration() & !bonus_mask)
+            | U256::from(10_750u64) << LIQUIDATION_BONUS_START_BIT;
+        assert!(cache.insert(weth, ReserveConfig::decode(raised)));
+        assert!(!cache.insert(weth, ReserveConfig::decode(raised)));
+
+        let overlaid = cache.overlay(&assets);
+        assert_eq!(overlaid[&weth].liquidation_bonus, 750);
+        assert!(overlaid[&weth].is_collateral);
+        // No cached configuration: loaded values are kept
+        assert_eq!(overlaid[&usdc].decimals, 6);
+        assert_eq!(overlaid[&usdc].liquidation_bonus, 500);
+
+        let frozen = raised | U256::from(1u8) << FROZEN_BIT;
+        cache.insert(weth, ReserveConfig::decode(frozen));
+        assert!(cache.overlay(&assets)[&weth].is_collateral);
+
+        let paused = frozen | U256::from(1u8) << PAUSED_BIT;
+        cache.insert(weth, ReserveConfig::decode(paused));
+        let overlaid = cache.overlay(&assets);
+        assert!(!overlaid[&weth].is_collateral);
+        assert!(!overlaid[&weth].is_borrowable);
+    }
+}
@@ -0,0 +1,108 @@
+use std::time::Duration;
+
+use super::FailureCategory;
+
+/// What to do after a failed liquidation attempt
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RetryDecision {
+    /// Try again after the delay, with freshly quoted gas
+    Retry(Duration),
+    /// The failure won't go away by trying again
+    Abandon,
+    /// Transient, but the attempt cap has been reached
+    Exhausted,
+}
+
+impl RetryDecision {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RetryDecision::Retry(_) => "retry",
+            RetryDecision::Abandon => "abandon",
+            RetryDecision::Exhausted => "exhausted",
+        }
+    }
+}
+
+/// Retries transient failures (underpriced, nonce conflicts, timeouts, RPC errors) with
+/// exponential backoff up to a total attempt cap. A retry whose liquidation is still pending
+/// replaces it on the same nonce. Everything else, including losing the position to another
+/// liquidator, is abandoned after the first attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Attempts per opportunity including the first; 1 disables retries
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_attempts: u32, base_delay: Duration, max_delay: Duration) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            base_delay,
+            max_delay: max_delay.max(base_delay),
+        }
+    }
+
+    /// Decide whether to retry after `attempt` (1-based) failed with `category`
+    pub fn decide(&self, category: FailureCategory, attempt: u32) -> RetryDecision {
+        if !category.is_retryable() {
+            return RetryDecision::Abandon;
+        }
+        if attempt >= self.max_attempts {
+            return RetryDecision::Exhausted;
+        }
+        RetryDecision::Retry(self.backoff(attempt))
+    }
+
+    /// Delay before the attempt after `attempt`: the base delay, doubled per earlier retry
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let doublings = attempt.saturating_sub(1).min(16);
+        self.base_delay
+            .saturating_mul(1 << doublings)
+            .min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transient_failures_back_off_until_the_cap() {
+        let policy = RetryPolicy::new(4, Duration::from_secs(1), Duration::from_secs(3));
+
+        assert_eq!(
+            policy.decide(FailureCategory::Underpriced, 1),
+            RetryDecision::Retry(Duration::from_secs(1))
+        );
+        assert_eq!(
+            policy.decide(FailureCategory::NonceConflict, 2),
+            RetryDecision::Retry(Duration::from_secs(2))
+        );
+        // Capped at the maximum delay
+        assert_eq!(
+            policy.decide(FailureCategory::Timeout, 3),
+            RetryDecision::Retry(Duration::from_secs(3))
+        );
+        assert_eq!(
+            policy.decide(FailureCategory::Rpc, 4),
+            RetryDecision::Exhausted
+        );
+
+        // Permanent failures are given up on straight away
+        assert_eq!(
+            policy.decide(FailureCategory::PositionHealthy, 1),
+            RetryDecision::Abandon
+        );
+        assert_eq!(
+            policy.decide(FailureCategory::DebtNotBorrowed, 1),
+            RetryDecision::Abandon
+        );
+        assert_eq!(
+            RetryPolicy::new(1, Duration::from_secs(1), Duration::from_secs(1))
+                .decide(FailureCategory::Underpriced, 1),
+            RetryDecision::Exhausted
+        );
+    }
+}
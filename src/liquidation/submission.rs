@@ -0,0 +1,442 @@
+use alloy_primitives::{hex, keccak256, Bytes, B256};
+use alloy_provider::Provider;
+use eyre::Result;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use crate::models::SubmissionStrategy;
+use crate::signer::BotSigner;
+
+/// How often the chain head is polled while waiting for a bundle's target block
+const BLOCK_POLL_INTERVAL_MS: u64 = 500;
+
+/// Stop waiting for a single target block after this long and check inclusion anyway
+const TARGET_BLOCK_TIMEOUT_SECS: u64 = 30;
+
+/// Where and when a privately submitted liquidation landed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleInclusion {
+    pub block_number: u64,
+    /// Number of target blocks the bundle was sent for (1 = included on the first try)
+    pub submissions: u64,
+}
+
+/// `X-Flashbots-Signature` header value authenticating a relay request: the signer address
+/// and its EIP-191 signature over the hex-encoded keccak256 of the request body
+pub async fn relay_signature_header(signer: &BotSigner, body: &str) -> Result<String> {
+    let body_hash = format!("0x{}", hex::encode(keccak256(body.as_bytes())));
+    let signature = signer.sign_message(body_hash.as_bytes()).await?;
+    Ok(format!(
+        "{}:0x{}",
+        signer.address(),
+        hex::encode(signature.as_bytes())
+    ))
+}
+
+/// `eth_sendBundle` parameters for a single-transaction bundle targeting one block
+pub fn send_bundle_params(raw_tx: &Bytes, target_block: u64) -> Value {
+    json!({
+        "txs": [raw_tx.to_string()],
+        "blockNumber": format!("0x{:x}", target_block),
+    })
+}
+
+/// `eth_callBundle` parameters simulating the bundle for `target_block` on top of the latest state
+pub fn call_bundle_params(raw_tx: &Bytes, target_block: u64) -> Value {
+    json!({
+        "txs": [raw_tx.to_string()],
+        "blockNumber": format!("0x{:x}", target_block),
+        "stateBlockNumber": "latest",
+    })
+}
+
+/// Reason a bundle simulation failed, if any transaction in it errored or reverted
+pub fn simulation_failure(result: &Value) -> Option<String> {
+    result["results"].as_array()?.iter().find_map(|tx| {
+        ["error", "revert"]
+            .iter()
+            .filter_map(|field| tx.get(*field))
+            .find(|reason| !reason.is_null())
+            .map(|reason| reason.as_str().map_or_else(|| reason.to_string(), str::to_string))
+    })
+}
+
+/// Sends signed liquidation transactions to a Flashbots Protect / MEV-Share style relay as
+/// single-transaction bundles, tracks inclusion and resubmits for the next block on a miss
+pub struct PrivateRelaySubmitter {
+    relay_url: String,
+    strategy: SubmissionStrategy,
+    max_blocks: u64,
+    client: reqwest::Client,
+}
+
+impl PrivateRelaySubmitter {
+    pub fn new(relay_url: String, strategy: SubmissionStrategy, max_blocks: u64) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            relay_url,
+            strategy,
+            max_blocks: max_blocks.max(1),
+            client,
+        })
+    }
+
+    pub fn relay_url(&self) -> &str {
+        &self.relay_url
+    }
+
+    pub fn strategy(&self) -> SubmissionStrategy {
+        self.strategy
+    }
+
+    /// Signed JSON-RPC call to the relay, returning the `result` field
+    async fn relay_request(
+        &self,
+        signer: &BotSigner,
+        method: &str,
+        params: Value,
+    ) -> Result<Value> {
+        let body = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": method,
+            "params": [params],
+        })
+        .to_string();
+
+        let response = self
+            .client
+            .post(&self.relay_url)
+            .header("Content-Type", "application/json")
+            .header("X-Flashbots-Signature", relay_signature_header(signer, &body).await?)
+            .body(body)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(eyre::eyre!(
+                "Private relay returned status {} for {}",
+                response.status(),
+                method
+            ));
+        }
+
+        let payload: Value = response.json().await?;
+        if let Some(error) = payload.get("error").filter(|error| !error.is_null()) {
+            return Err(eyre::eyre!("Private relay rejected {}: {}", method, error));
+        }
+        Ok(payload["result"].clone())
+    }
+
+    /// Simulate the bundle against the latest state. Returns the failure reason if the
+    /// liquidation would revert, or an error if the relay couldn't simulate at all.
+    pub async fn simulate_bundle(
+        &self,
+        signer: &BotSigner,
+        raw_tx: &Bytes,
+        target_block: u64,
+    ) -> Result<Option<String>> {
+        let result = self
+            .relay_request(signer, "eth_callBundle", call_bundle_params(raw_tx, target_block))
+            .await?;
+        Ok(simulation_failure(&result))
+    }
+
+    /// Send the bundle for inclusion in `target_block`, returning the relay's bundle hash
+    pub async fn send_bundle(
+        &self,
+        signer: &BotSigner,
+        raw_tx: &Bytes,
+        target_block: u64,
+    ) -> Result<Option<String>> {
+        let result = self
+            .relay_request(signer, "eth_sendBundle", send_bundle_params(raw_tx, target_block))
+            .await?;
+        Ok(result["bundleHash"].as_str().map(str::to_string))
+    }
+
+    /// Submit a signed transaction according to the strategy and resubmit it for each new
+    /// block until it is included or `max_blocks` blocks have been missed
+    pub async fn submit<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        raw_tx: &Bytes,
+        tx_hash: B256,
+    ) -> Result<BundleInclusion>
+    where
+        P: Provider,
+    {
+        let head = provider.get_block_number().await?;
+
+        // A reverting liquidation is never sent; a relay that can't simulate doesn't block submission
+        match self.simulate_bundle(signer, raw_tx, head + 1).await {
+            Ok(None) => debug!("Bundle simulation for {} succeeded", tx_hash),
+            Ok(Some(reason)) => {
+                return Err(eyre::eyre!(
+                    "Bundle simulation for {} failed: {}",
+                    tx_hash,
+                    reason
+                ));
+            }
+            Err(e) => warn!("⚠️ Bundle simulation unavailable, submitting anyway: {}", e),
+        }
+
+        if self.strategy == SubmissionStrategy::Both {
+            match provider.send_raw_transaction(raw_tx).await {
+                Ok(_) => info!("📡 Liquidation {} broadcast to the public mempool", tx_hash),
+                Err(e) => warn!("⚠️ Public broadcast of {} failed, relying on relay: {}", tx_hash, e),
+            }
+        }
+
+        for submission in 1..=self.max_blocks {
+            let target_block = provider.get_block_number().await? + 1;
+            match self.send_bundle(signer, raw_tx, target_block).await {
+                Ok(bundle_hash) => info!(
+                    "🔒 Liquidation {} sent to private relay for block {} (bundle {})",
+                    tx_hash,
+                    target_block,
+                    bundle_hash.as_deref().unwrap_or("unknown")
+                ),
+                Err(e) => warn!(
+                    "⚠️ Failed to send bundle for block {}: {}",
+                    target_block, e
+                ),
+            }
+
+            wait_for_block(provider, target_block).await?;
+
+            if let Some(receipt) = provider.get_transaction_receipt(tx_hash).await? {
+                let block_number = receipt.block_number.unwrap_or(target_block);
+                info!(
+                    "✅ Liquidation {} included in block {} after {} submission(s)",
+                    tx_hash, block_number, submission
+                );
+                return Ok(BundleInclusion {
+                    block_number,
+                    submissions: submission,
+                });
+            }
+
+            debug!(
+                "Liquidation {} missed block {}, resubmitting",
+                tx_hash, target_block
+            );
+        }
+
+        Err(eyre::eyre!(
+            "Liquidation {} not included after {} blocks",
+            tx_hash,
+            self.max_blocks
+        ))
+    }
+}
+
+/// Wait until the chain head reaches `target_block` (or the per-block timeout passes)
+async fn wait_for_block<P>(provider: &P, target_block: u64) -> Result<()>
+where
+    P: Provider,
+{
+    let reached = tokio::time::timeout(Duration::from_secs(TARGET_BLOCK_TIMEOUT_SECS), async {
+        loop {
+            if provider.get_block_number().await? >= target_block {
+                return Ok::<_, eyre::Report>(());
+            }
+            tokio::time::sleep(Duration::from_millis(BLOCK_POLL_INTERVAL_MS)).await;
+        }
+    })
+    .await;
+
+    match reached {
+        Ok(result) => result,
+        Err(_) => {
+            warn!("⏰ Timed out waiting for block {}", target_block);
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::MockServer;
+    use alloy_primitives::{Address, Signature};
+    use alloy_provider::ProviderBuilder;
+    use alloy_signer_local::PrivateKeySigner;
+
+    const TEST_KEY: &str = "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+    /// Mock node + relay: the chain advances one block per `eth_blockNumber` call and the
+    /// transaction is included once `included_after` bundles have been sent
+    async fn spawn_mock_chain(
+        included_after: usize,
+        simulation_revert: Option<&'static str>,
+    ) -> MockServer {
+        let mut block = 100u64;
+        let mut bundles_sent = 0usize;
+        MockServer::json_rpc(move |request| match request["method"].as_str().unwrap_or("") {
+            "eth_blockNumber" => {
+                block += 1;
+                json!(format!("0x{:x}", block))
+            }
+            "eth_callBundle" => match simulation_revert {
+                Some(reason) => json!({"results": [{"revert": reason}]}),
+                None => json!({"results": [{"gasUsed": 21000}]}),
+            },
+            "eth_sendBundle" => {
+                bundles_sent += 1;
+                json!({"bundleHash": format!("0x{:064x}", bundles_sent)})
+            }
+            "eth_sendRawTransaction" => json!(B256::ZERO.to_string()),
+            "eth_getTransactionReceipt" if bundles_sent >= included_after => json!({
+                "transactionHash": request["params"][0],
+                "transactionIndex": "0x0",
+                "blockHash": format!("0x{:064x}", block),
+                "blockNumber": format!("0x{:x}", block),
+                "from": Address::repeat_byte(0x01).to_string(),
+                "to": Address::repeat_byte(0x02).to_string(),
+                "cumulativeGasUsed": "0x5208",
+                "gasUsed": "0x5208",
+                "effectiveGasPrice": "0x1",
+                "contractAddress": null,
+                "logs": [],
+                "logsBloom": format!("0x{}", "0".repeat(512)),
+                "status": "0x1",
+                "type": "0x0",
+            }),
+            _ => Value::Null,
+        })
+        .await
+    }
+
+    /// JSON-RPC requests the mock chain has received
+    fn calls(server: &MockServer) -> Vec<Value> {
+        server.requests().iter().map(|request| request.json()).collect()
+    }
+
+    fn methods(requests: &[Value]) -> Vec<String> {
+        requests
+            .iter()
+            .map(|r| r["method"].as_str().unwrap_or("").to_string())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_missed_blocks_are_resubmitted_until_included() {
+        let server = spawn_mock_chain(3, None).await;
+        let provider = ProviderBuilder::new().on_http(server.url.parse().unwrap());
+        let signer = BotSigner::from(TEST_KEY.parse::<PrivateKeySigner>().unwrap());
+        let submitter =
+            PrivateRelaySubmitter::new(server.url.clone(), SubmissionStrategy::PrivateRelay, 10)
+                .unwrap();
+        let raw_tx = Bytes::from(vec![0x02, 0xf8, 0x70]);
+
+        let inclusion = submitter
+            .submit(&provider, &signer, &raw_tx, B256::repeat_byte(0xaa))
+            .await
+            .unwrap();
+
+        assert_eq!(inclusion.submissions, 3);
+        let requests = calls(&server);
+        let methods = methods(&requests);
+        assert_eq!(methods.iter().filter(|m| *m == "eth_callBundle").count(), 1);
+        assert!(!methods.contains(&"eth_sendRawTransaction".to_string()));
+
+        // Each resubmission targets a later block than the one before
+        let bundles: Vec<&Value> = requests
+            .iter()
+            .filter(|r| r["method"] == "eth_sendBundle")
+            .collect();
+        assert!(bundles.iter().all(|r| r["params"][0]["txs"][0] == raw_tx.to_string()));
+        let targets: Vec<u64> = bundles
+            .iter()
+            .map(|r| {
+                u64::from_str_radix(
+                    r["params"][0]["blockNumber"].as_str().unwrap().trim_start_matches("0x"),
+                    16,
+                )
+                .unwrap()
+            })
+            .collect();
+        assert_eq!(targets.len(), 3);
+        assert!(targets.windows(2).all(|pair| pair[1] > pair[0]));
+    }
+
+    #[tokio::test]
+    async fn test_reverting_simulation_is_not_submitted() {
+        let server = spawn_mock_chain(1, Some("execution reverted")).await;
+        let provider = ProviderBuilder::new().on_http(server.url.parse().unwrap());
+        let signer = BotSigner::from(TEST_KEY.parse::<PrivateKeySigner>().unwrap());
+        let submitter =
+            PrivateRelaySubmitter::new(server.url.clone(), SubmissionStrategy::Both, 10).unwrap();
+
+        let err = submitter
+            .submit(&provider, &signer, &Bytes::from(vec![0x01]), B256::ZERO)
+            .await
+            .unwrap_err();
+
+        assert!(err.to_string().contains("execution reverted"));
+        let methods = methods(&calls(&server));
+        assert!(!methods.contains(&"eth_sendBundle".to_string()));
+        assert!(!methods.contains(&"eth_sendRawTransaction".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_both_strategy_also_broadcasts_publicly() {
+        let server = spawn_mock_chain(1, None).await;
+        let provider = ProviderBuilder::new().on_http(server.url.parse().unwrap());
+        let signer = BotSigner::from(TEST_KEY.parse::<PrivateKeySigner>().unwrap());
+        let submitter =
+            PrivateRelaySubmitter::new(server.url.clone(), SubmissionStrategy::Both, 10).unwrap();
+
+        let inclusion = submitter
+            .submit(&provider, &signer, &Bytes::from(vec![0x01]), B256::ZERO)
+            .await
+            .unwrap();
+
+        assert_eq!(inclusion.submissions, 1);
+        let methods = methods(&calls(&server));
+        assert_eq!(
+            methods.iter().filter(|m| *m == "eth_sendRawTransaction").count(),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_relay_signature_header_recovers_signer() {
+        let signer = BotSigner::from(TEST_KEY.parse::<PrivateKeySigner>().unwrap());
+        let body = r#"{"jsonrpc":"2.0","id":1,"method":"eth_sendBundle","params":[]}"#;
+
+        let header = relay_signature_header(&signer, body).await.unwrap();
+        let (address, signature) = header.split_once(':').unwrap();
+
+        assert_eq!(address, signer.address().to_string());
+        let signature: Signature = signature.parse().unwrap();
+        let body_hash = format!("0x{}", hex::encode(keccak256(body.as_bytes())));
+        assert_eq!(
+            signature.recover_address_from_msg(body_hash.as_bytes()).unwrap(),
+            signer.address()
+        );
+    }
+
+    #[test]
+    fn test_simulation_failure_detection() {
+        assert_eq!(
+            simulation_failure(&json!({"results": [{"gasUsed": 1}, {"revert": "HF too high"}]})),
+            Some("HF too high".to_string())
+        );
+        assert_eq!(
+            simulation_failure(&json!({"results": [{"error": "nonce too low", "revert": null}]})),
+            Some("nonce too low".to_string())
+        );
+        assert_eq!(
+            simulation_failure(&json!({"results": [{"error": null, "revert": "paused"}]})),
+            Some("paused".to_string())
+        );
+        assert_eq!(simulation_failure(&json!({"results": [{"gasUsed": 1, "error": null}]})), None);
+        assert_eq!(simulation_failure(&Value::Null), None);
+    }
+}
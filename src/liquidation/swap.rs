@@ -0,0 +1,403 @@
+use alloy_primitives::{Address, Bytes, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use eyre::Result;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use super::aggregator::AggregatorClient;
+use super::approvals::ApprovalManager;
+use super::tx_manager::TxManager;
+use crate::models::{ApprovalMode, SwapRoute};
+use crate::networks::NetworkAddresses;
+use crate::protocols::call_contract;
+use crate::signer::BotSigner;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IQuoterV2 {
+        function quoteExactInput(bytes memory path, uint256 amountIn) external returns (
+            uint256 amountOut,
+            uint160[] memory sqrtPriceX96AfterList,
+            uint32[] memory initializedTicksCrossedList,
+            uint256 gasEstimate
+        );
+    }
+
+    #[allow(missing_docs)]
+    interface ISwapRouter02 {
+        struct ExactInputParams {
+            bytes path;
+            address recipient;
+            uint256 amountIn;
+            uint256 amountOutMinimum;
+        }
+        function exactInput(ExactInputParams calldata params) external payable returns (uint256 amountOut);
+    }
+}
+
+/// How long to wait for an approval or swap receipt
+const SWAP_RECEIPT_TIMEOUT_SECS: u64 = 120;
+
+/// A collateral conversion that was sent (or simulated)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapOutcome {
+    pub tx_hash: String,
+    pub source: &'static str,
+    pub token_in: Address,
+    pub token_out: Address,
+    pub amount_in: U256,
+    pub quoted_amount_out: U256,
+    pub min_amount_out: U256,
+}
+
+/// Uniswap V3 packed path: token (20 bytes), then fee (3 bytes) and token for every hop
+pub fn encode_path(route: &SwapRoute) -> Bytes {
+    let mut path = Vec::with_capacity(20 + route.fees.len() * 23);
+    path.extend_from_slice(route.tokens[0].as_slice());
+    for (fee, token) in route.fees.iter().zip(&route.tokens[1..]) {
+        path.extend_from_slice(&fee.to_be_bytes()[1..]);
+        path.extend_from_slice(token.as_slice());
+    }
+    path.into()
+}
+
+/// Quoted output less the slippage tolerance
+pub fn min_amount_out(quoted_amount_out: U256, slippage_bps: u64) -> U256 {
+    quoted_amount_out.saturating_mul(U256::from(10_000u64.saturating_sub(slippage_bps)))
+        / U256::from(10_000u64)
+}
+
+/// `exactInput` calldata swapping `amount_in` along `route` to `recipient`
+pub fn exact_input_calldata(
+    route: &SwapRoute,
+    amount_in: U256,
+    min_amount_out: U256,
+    recipient: Address,
+) -> Bytes {
+    ISwapRouter02::exactInputCall {
+        params: ISwapRouter02::ExactInputParams {
+            path: encode_path(route),
+            recipient,
+            amountIn: amount_in,
+            amountOutMinimum: min_amount_out,
+        },
+    }
+    .abi_encode()
+    .into()
+}
+
+/// Expected output of swapping `amount_in` along `route`, from the QuoterV2 at `quoter`
+pub async fn quote_exact_input<P>(
+    provider: &P,
+    quoter: Address,
+    route: &SwapRoute,
+    amount_in: U256,
+) -> Result<U256>
+where
+    P: Provider,
+{
+    let quote = call_contract(
+        provider,
+        quoter,
+        &IQuoterV2::quoteExactInputCall {
+            path: encode_path(route),
+            amountIn: amount_in,
+        },
+    )
+    .await?;
+    Ok(quote.amountOut)
+}
+
+/// Ready-to-send swap: approve `spender` for the input, then call `target` with `calldata`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapPlan {
+    /// "1inch", "0x" or "uniswap_v3"
+    pub source: &'static str,
+    pub target: Address,
+    pub spender: Address,
+    pub calldata: Bytes,
+    pub amount_in: U256,
+    pub amount_out: U256,
+    pub min_amount_out: U256,
+}
+
+/// Direct Uniswap V3 exact-input plan along `route` through `network`'s router, bounded by
+/// the quoter's output
+pub async fn uniswap_plan<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    route: &SwapRoute,
+    amount_in: U256,
+    recipient: Address,
+    slippage_bps: u64,
+) -> Result<SwapPlan>
+where
+    P: Provider,
+{
+    let router = network.swap_router;
+    let amount_out = quote_exact_input(provider, network.swap_quoter, route, amount_in).await?;
+    if amount_out.is_zero() {
+        return Err(eyre::eyre!(
+            "Quoter returned no output for {} of {}",
+            amount_in,
+            route.token_in()
+        ));
+    }
+    let min_amount_out = min_amount_out(amount_out, slippage_bps);
+    Ok(SwapPlan {
+        source: "uniswap_v3",
+        target: router,
+        spender: router,
+        calldata: exact_input_calldata(route, amount_in, min_amount_out, recipient),
+        amount_in,
+        amount_out,
+        min_amount_out,
+    })
+}
+
+/// Converts collateral the signer received from a liquidation back into the route's output
+/// token, through the swap aggregator when one is configured and with Uniswap V3 exact-input
+/// swaps otherwise. The liquidator contract already swaps inside the flash loan; this handles
+/// whatever collateral ends up with the signer afterwards.
+pub struct CollateralSwapper {
+    routes: HashMap<Address, SwapRoute>,
+    slippage_bps: u64,
+    network: NetworkAddresses,
+    /// Send swaps and approvals on-chain (BotConfig::broadcasts_transactions); mocked otherwise
+    broadcast: bool,
+    aggregator: Option<Arc<AggregatorClient>>,
+    approvals: ApprovalManager,
+}
+
+impl CollateralSwapper {
+    pub fn new(
+        routes: &[SwapRoute],
+        slippage_bps: u64,
+        network: NetworkAddresses,
+        broadcast: bool,
+    ) -> Self {
+        Self {
+            routes: routes
+                .iter()
+                .map(|route| (route.token_in(), route.clone()))
+                .collect(),
+            slippage_bps,
+            network,
+            broadcast,
+            aggregator: None,
+            approvals: ApprovalManager::new(ApprovalMode::Exact),
+        }
+    }
+
+    /// Approve exactly each swap's input (the default) or the maximum once per token
+    pub fn with_approval_mode(mut self, mode: ApprovalMode) -> Self {
+        self.approvals = ApprovalManager::new(mode);
+        self
+    }
+
+    /// Build swaps with a 1inch / 0x aggregator, keeping the configured route as fallback
+    pub fn with_aggregator(mut self, aggregator: Arc<AggregatorClient>) -> Self {
+        self.aggregator = Some(aggregator);
+        self
+    }
+
+    /// Configured route for converting `token`, if any
+    pub fn route(&self, token: Address) -> Option<&SwapRoute> {
+        self.routes.get(&token)
+    }
+
+    /// Swap `amount_in` of `token` along its configured route to the signer, with nonces from
+    /// `tx_manager` (mocked unless broadcasting). Returns None when no route is configured for
+    /// `token`.
+    pub async fn swap_collateral<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+        token: Address,
+        amount_in: U256,
+    ) -> Result<Option<SwapOutcome>>
+    where
+        P: Provider,
+    {
+        let Some(route) = self.route(token) else {
+            return Ok(None);
+        };
+
+        let plan = match &self.aggregator {
+            Some(aggregator) => {
+                aggregator
+                    .best_plan(provider, &self.network, route, amount_in, signer.address())
+                    .await?
+            }
+            None => {
+                uniswap_plan(
+                    provider,
+                    &self.network,
+                    route,
+                    amount_in,
+                    signer.address(),
+                    self.slippage_bps,
+                )
+                .await?
+            }
+        };
+
+        let tx_hash = if self.broadcast {
+            self.send_swap(provider, signer, tx_manager, token, &plan).await?
+        } else {
+            warn!(
+                "🎭 MOCK collateral swap of {} {} -> {} via {} (min {}) - no on-chain execution",
+                amount_in,
+                token,
+                route.token_out(),
+                plan.source,
+                plan.min_amount_out
+            );
+            "mock".to_string()
+        };
+
+        Ok(Some(SwapOutcome {
+            tx_hash,
+            source: plan.source,
+            token_in: token,
+            token_out: route.token_out(),
+            amount_in,
+            quoted_amount_out: plan.amount_out,
+            min_amount_out: plan.min_amount_out,
+        }))
+    }
+
+    /// With infinite approvals, let the Uniswap router spend every route's input token before
+    /// the first swap needs it (skipped unless broadcasting). Exact approvals
+    /// depend on the amount, so they're left to each swap.
+    pub async fn prepare_approvals<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+    ) -> Result<()>
+    where
+        P: Provider,
+    {
+        if self.approvals.mode() != ApprovalMode::Infinite || !self.broadcast {
+            return Ok(());
+        }
+
+        let router = self.network.swap_router;
+        // More than any balance, and below the maximum so tokens that decrement even an
+        // infinite allowance aren't approved again on every start
+        let ample = U256::from(u128::MAX);
+        for token in self.routes.keys() {
+            self.approvals
+                .ensure_allowance(provider, signer, tx_manager, *token, router, ample)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Approve the plan's spender if needed, then send the swap and wait for it, both with
+    /// nonces from `tx_manager`
+    async fn send_swap<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+        token: Address,
+        plan: &SwapPlan,
+    ) -> Result<String>
+    where
+        P: Provider,
+    {
+        self.approvals
+            .ensure_allowance(
+                provider,
+                signer,
+                tx_manager,
+                token,
+                plan.spender,
+                plan.amount_in,
+            )
+            .await?;
+
+        let tx_req = TransactionRequest::default()
+            .from(signer.address())
+            .to(plan.target)
+            .input(plan.calldata.clone().into());
+        let timeout = Duration::from_secs(SWAP_RECEIPT_TIMEOUT_SECS);
+        let tx_hash = match tx_manager
+            .send_and_confirm(provider, signer, tx_req, timeout)
+            .await
+        {
+            Ok(tx_hash) => format!("0x{:x}", tx_hash),
+            Err(e) => {
+                self.approvals.invalidate(signer.address(), token, plan.spender);
+                return Err(e);
+            }
+        };
+        self.approvals.record_spent(signer.address(), token, plan.spender, plan.amount_in);
+
+        info!(
+            "🔁 Swapped {} {} via {} (min out {}): {}",
+            plan.amount_in, token, plan.source, plan.min_amount_out, tx_hash
+        );
+        Ok(tx_hash)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn route() -> SwapRoute {
+        SwapRoute::parse(&format!(
+            "{}>500>{}>3000>{}",
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03)
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_path_encoding() {
+        let path = encode_path(&route());
+
+        assert_eq!(path.len(), 20 + 2 * 23);
+        assert_eq!(&path[..20], Address::repeat_byte(0x01).as_slice());
+        assert_eq!(&path[20..23], &[0x00, 0x01, 0xf4]); // 500
+        assert_eq!(&path[43..46], &[0x00, 0x0b, 0xb8]); // 3000
+        assert_eq!(&path[46..], Address::repeat_byte(0x03).as_slice());
+    }
+
+    #[test]
+    fn test_min_amount_out_applies_slippage() {
+        assert_eq!(min_amount_out(U256::from(10_000u64), 50), U256::from(9_950u64));
+        assert_eq!(min_amount_out(U256::from(10_000u64), 0), U256::from(10_000u64));
+    }
+
+    #[test]
+    fn test_exact_input_calldata_round_trips() {
+        let recipient = Address::repeat_byte(0x42);
+        let calldata = exact_input_calldata(&route(), U256::from(7u64), U256::from(5u64), recipient);
+
+        let decoded = ISwapRouter02::exactInputCall::abi_decode(&calldata, true).unwrap();
+        assert_eq!(decoded.params.path, encode_path(&route()));
+        assert_eq!(decoded.params.recipient, recipient);
+        assert_eq!(decoded.params.amountIn, U256::from(7u64));
+        assert_eq!(decoded.params.amountOutMinimum, U256::from(5u64));
+    }
+
+    #[test]
+    fn test_routes_are_keyed_by_input_token() {
+        let swapper =
+            CollateralSwapper::new(&[route()], 50, crate::networks::base(), false);
+
+        assert_eq!(swapper.route(Address::repeat_byte(0x01)), Some(&route()));
+        assert!(swapper.route(Address::repeat_byte(0x03)).is_none());
+    }
+}
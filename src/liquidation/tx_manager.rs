@@ -0,0 +1,500 @@
+use alloy_network::eip2718::Encodable2718;
+use alloy_network::{EthereumWallet, TransactionBuilder};
+use alloy_primitives::{Address, TxHash};
+use alloy_provider::Provider;
+use alloy_rpc_types::{TransactionReceipt, TransactionRequest};
+use eyre::Result;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+use crate::signer::BotSigner;
+
+/// Smallest fee bump nodes accept for a same-nonce replacement
+pub const MIN_REPLACEMENT_BUMP_PCT: u64 = 10;
+
+/// How often a sent transaction's receipt is looked for
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// When a pending transaction counts as stuck and how its fees are escalated
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EscalationPolicy {
+    /// Resubmit with higher fees once a transaction has been pending this long
+    pub stuck_after: Duration,
+    /// Percentage added to both fee fields on each replacement
+    pub bump_pct: u64,
+    /// Replacements per nonce before the transaction is left to its fate
+    pub max_bumps: u32,
+}
+
+/// Fees raised by `bump_pct` (at least the replacement minimum), rounded up
+pub fn bumped_fees(
+    max_fee_per_gas: u128,
+    max_priority_fee_per_gas: u128,
+    bump_pct: u64,
+) -> (u128, u128) {
+    let pct = u128::from(bump_pct.max(MIN_REPLACEMENT_BUMP_PCT));
+    let bump = |fee: u128| fee.saturating_add(fee.saturating_mul(pct).saturating_add(99) / 100);
+    (bump(max_fee_per_gas), bump(max_priority_fee_per_gas))
+}
+
+/// A submitted transaction awaiting inclusion
+#[derive(Debug, Clone)]
+pub struct PendingTx {
+    pub nonce: u64,
+    /// Every hash broadcast for this nonce, latest last; any of them may be the one mined
+    pub hashes: Vec<TxHash>,
+    pub request: TransactionRequest,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub submitted_at: Instant,
+    pub bumps: u32,
+    /// Priority fee replacements may not exceed (None = uncapped)
+    pub priority_fee_cap: Option<u128>,
+    /// Borrower the transaction liquidates, if it is a liquidation
+    pub borrower: Option<Address>,
+}
+
+impl PendingTx {
+    pub fn latest_hash(&self) -> TxHash {
+        *self.hashes.last().expect("pending transaction has at least one hash")
+    }
+}
+
+#[derive(Debug, Default)]
+struct TxState {
+    /// Next nonce to hand out (None until read from chain, or after a resync)
+    next_nonce: Option<u64>,
+    pending: BTreeMap<u64, PendingTx>,
+}
+
+impl TxState {
+    /// Hand out the next nonce, never going below the chain's pending count
+    fn allocate(&mut self, chain_pending: u64) -> u64 {
+        let nonce = self.next_nonce.map_or(chain_pending, |next| next.max(chain_pending));
+        self.next_nonce = Some(nonce + 1);
+        nonce
+    }
+
+    fn stuck(&self, policy: &EscalationPolicy, now: Instant) -> Vec<u64> {
+        self.pending
+            .values()
+            .filter(|tx| {
+                tx.bumps < policy.max_bumps
+                    && now.saturating_duration_since(tx.submitted_at) >= policy.stuck_after
+            })
+            .map(|tx| tx.nonce)
+            .collect()
+    }
+}
+
+/// Owns nonce allocation for the signer so concurrent liquidations never share a nonce,
+/// tracks their pending transactions and replaces stuck ones with higher fees
+pub struct TxManager {
+    signer: Address,
+    policy: EscalationPolicy,
+    state: Mutex<TxState>,
+}
+
+impl TxManager {
+    pub fn new(signer: Address, policy: EscalationPolicy) -> Self {
+        Self {
+            signer,
+            policy,
+            state: Mutex::new(TxState::default()),
+        }
+    }
+
+    pub fn policy(&self) -> &EscalationPolicy {
+        &self.policy
+    }
+
+    /// Address whose nonces this manager hands out
+    pub fn signer(&self) -> Address {
+        self.signer
+    }
+
+    /// Reserve the next nonce for the signer
+    pub async fn allocate_nonce<P>(&self, provider: &P) -> Result<u64>
+    where
+        P: Provider,
+    {
+        let chain_pending = provider
+            .get_transaction_count(self.signer)
+            .pending()
+            .await?;
+        Ok(self.state.lock().allocate(chain_pending))
+    }
+
+    /// Forget the local nonce so the next allocation re-reads it from chain, e.g. after a
+    /// reserved nonce was never broadcast or the node rejected it
+    pub fn resync(&self) {
+        self.state.lock().next_nonce = None;
+    }
+
+    /// Start tracking a broadcast transaction
+    pub fn track(
+        &self,
+        nonce: u64,
+        tx_hash: TxHash,
+        request: TransactionRequest,
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    ) {
+        self.state.lock().pending.insert(
+            nonce,
+            PendingTx {
+                nonce,
+                hashes: vec![tx_hash],
+                request,
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+                submitted_at: Instant::now(),
+                bumps: 0,
+                priority_fee_cap: None,
+                borrower: None,
+            },
+        );
+    }
+
+    /// Stop escalating `nonce` once its priority fee would exceed `cap`, e.g. the share of
+    /// expected profit a racing bid may spend
+    pub fn cap_priority_fee(&self, nonce: u64, cap: u128) {
+        if let Some(tracked) = self.state.lock().pending.get_mut(&nonce) {
+            tracked.priority_fee_cap = Some(cap);
+        }
+    }
+
+    /// Mark `nonce` as the liquidation of `borrower`, so a retry can find and replace it
+    pub fn tag_borrower(&self, nonce: u64, borrower: Address) {
+        if let Some(tracked) = self.state.lock().pending.get_mut(&nonce) {
+            tracked.borrower = Some(borrower);
+        }
+    }
+
+    /// Nonce of a liquidation of `borrower` that is still pending
+    pub fn pending_liquidation(&self, borrower: Address) -> Option<u64> {
+        self.state
+            .lock()
+            .pending
+            .values()
+            .find(|tx| tx.borrower == Some(borrower))
+            .map(|tx| tx.nonce)
+    }
+
+    pub fn pending(&self, nonce: u64) -> Option<PendingTx> {
+        self.state.lock().pending.get(&nonce).cloned()
+    }
+
+    pub fn pending_count(&self) -> usize {
+        self.state.lock().pending.len()
+    }
+
+    /// Stop tracking `nonce` once one of its transactions is mined (or the caller gives up on it)
+    pub fn confirm(&self, nonce: u64) {
+        self.state.lock().pending.remove(&nonce);
+    }
+
+    /// Nonces whose latest transaction has been pending past the policy and can still be bumped
+    pub fn stuck_nonces(&self, now: Instant) -> Vec<u64> {
+        self.state.lock().stuck(&self.policy, now)
+    }
+
+    /// Sign and broadcast `tx_req` from `signer` with the next nonce and track it, so it never
+    /// shares a nonce with a concurrent liquidation and gets fee-bumped while stuck. Gas, fees
+    /// and the chain id are filled in when the request doesn't set them.
+    pub async fn send<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        mut tx_req: TransactionRequest,
+    ) -> Result<(u64, TxHash)>
+    where
+        P: Provider,
+    {
+        tx_req.from = Some(self.signer);
+        if tx_req.chain_id.is_none() {
+            tx_req.chain_id = Some(provider.get_chain_id().await?);
+        }
+        if tx_req.max_fee_per_gas.is_none() || tx_req.max_priority_fee_per_gas.is_none() {
+            let fees = provider.estimate_eip1559_fees(None).await?;
+            tx_req.max_fee_per_gas = Some(fees.max_fee_per_gas);
+            tx_req.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+        }
+        if tx_req.gas.is_none() {
+            tx_req.gas = Some(provider.estimate_gas(&tx_req).await?);
+        }
+
+        let nonce = self.allocate_nonce(provider).await?;
+        tx_req.nonce = Some(nonce);
+        let envelope = match tx_req.clone().build(&EthereumWallet::from(signer.clone())).await {
+            Ok(envelope) => envelope,
+            Err(e) => {
+                self.resync();
+                return Err(eyre::eyre!("Failed to sign transaction: {}", e));
+            }
+        };
+        let tx_hash = *envelope.tx_hash();
+        if let Err(e) = provider.send_raw_transaction(&envelope.encoded_2718()).await {
+            // The reserved nonce was never used; re-read it from chain next time
+            self.resync();
+            return Err(e.into());
+        }
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = (
+            tx_req.max_fee_per_gas.unwrap_or_default(),
+            tx_req.max_priority_fee_per_gas.unwrap_or_default(),
+        );
+        self.track(nonce, tx_hash, tx_req, max_fee_per_gas, max_priority_fee_per_gas);
+        Ok((nonce, tx_hash))
+    }
+
+    /// Wait up to `timeout` for one of `nonce`'s transactions to be mined, replacing it with a
+    /// fee-bumped copy whenever it is stuck. The nonce stays tracked if none is mined in time,
+    /// so the transaction is still bumped and never mistaken for a free nonce.
+    pub async fn wait_for_receipt<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        nonce: u64,
+        timeout: Duration,
+    ) -> Result<TransactionReceipt>
+    where
+        P: Provider,
+    {
+        let wallet = EthereumWallet::from(signer.clone());
+        let deadline = Instant::now() + timeout;
+        loop {
+            if let Some(receipt) = self.find_receipt(provider, nonce).await? {
+                self.confirm(nonce);
+                return Ok(receipt);
+            }
+            if Instant::now() >= deadline {
+                return Err(eyre::eyre!(
+                    "Timed out after {:?} waiting for nonce {} to be mined",
+                    timeout,
+                    nonce
+                ));
+            }
+            if let Err(e) = self.bump_if_stuck(provider, &wallet, nonce).await {
+                warn!("Failed to replace stuck transaction with nonce {}: {}", nonce, e);
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+
+    /// [`send`](Self::send) `tx_req` and wait up to `timeout` for it to be mined, failing if it
+    /// reverted. Returns the hash of the transaction that was mined.
+    pub async fn send_and_confirm<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_req: TransactionRequest,
+        timeout: Duration,
+    ) -> Result<TxHash>
+    where
+        P: Provider,
+    {
+        let (nonce, tx_hash) = self.send(provider, signer, tx_req).await?;
+        let receipt = self.wait_for_receipt(provider, signer, nonce, timeout).await?;
+        if !receipt.status() {
+            return Err(eyre::eyre!(
+                "Transaction 0x{:x} reverted on-chain",
+                receipt.transaction_hash
+            ));
+        }
+        if receipt.transaction_hash != tx_hash {
+            debug!(
+                "Nonce {} mined as replacement 0x{:x}",
+                nonce, receipt.transaction_hash
+            );
+        }
+        Ok(receipt.transaction_hash)
+    }
+
+    /// Receipt of whichever transaction broadcast for `nonce` was mined, if any
+    pub async fn find_receipt<P>(
+        &self,
+        provider: &P,
+        nonce: u64,
+    ) -> Result<Option<TransactionReceipt>>
+    where
+        P: Provider,
+    {
+        let Some(pending) = self.pending(nonce) else {
+            return Ok(None);
+        };
+        for hash in pending.hashes.iter().rev() {
+            if let Some(receipt) = provider.get_transaction_receipt(*hash).await? {
+                return Ok(Some(receipt));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Replace `nonce`'s transaction with a fee-bumped copy if it is stuck. Returns the new
+    /// hash when a replacement was broadcast.
+    pub async fn bump_if_stuck<P>(
+        &self,
+        provider: &P,
+        wallet: &EthereumWallet,
+        nonce: u64,
+    ) -> Result<Option<TxHash>>
+    where
+        P: Provider,
+    {
+        if !self.stuck_nonces(Instant::now()).contains(&nonce) {
+            return Ok(None);
+        }
+        self.replace(provider, wallet, nonce).await
+    }
+
+    /// Replace `nonce`'s transaction with a fee-bumped copy now, whether or not it counts as
+    /// stuck yet. Returns the new hash, or None if the nonce isn't pending or the bump would
+    /// exceed its priority fee cap.
+    pub async fn replace<P>(
+        &self,
+        provider: &P,
+        wallet: &EthereumWallet,
+        nonce: u64,
+    ) -> Result<Option<TxHash>>
+    where
+        P: Provider,
+    {
+        let Some(pending) = self.pending(nonce) else {
+            return Ok(None);
+        };
+
+        let (max_fee_per_gas, max_priority_fee_per_gas) = bumped_fees(
+            pending.max_fee_per_gas,
+            pending.max_priority_fee_per_gas,
+            self.policy.bump_pct,
+        );
+        if pending
+            .priority_fee_cap
+            .is_some_and(|cap| max_priority_fee_per_gas > cap)
+        {
+            debug!(
+                "Nonce {} stuck but a {} wei priority fee exceeds its cap, not replacing",
+                nonce, max_priority_fee_per_gas
+            );
+            return Ok(None);
+        }
+        let mut replacement = pending.request.clone();
+        replacement.nonce = Some(nonce);
+        replacement.max_fee_per_gas = Some(max_fee_per_gas);
+        replacement.max_priority_fee_per_gas = Some(max_priority_fee_per_gas);
+
+        let envelope = replacement
+            .clone()
+            .build(wallet)
+            .await
+            .map_err(|e| eyre::eyre!("Failed to sign replacement transaction: {}", e))?;
+        let tx_hash = *envelope.tx_hash();
+        provider
+            .send_raw_transaction(&envelope.encoded_2718())
+            .await?;
+
+        warn!(
+            "⛽ Transaction with nonce {} stuck for {:?}, replaced 0x{:x} with 0x{:x} (max fee {} -> {} wei, bump {}/{})",
+            nonce,
+            pending.submitted_at.elapsed(),
+            pending.latest_hash(),
+            tx_hash,
+            pending.max_fee_per_gas,
+            max_fee_per_gas,
+            pending.bumps + 1,
+            self.policy.max_bumps
+        );
+
+        if let Some(tracked) = self.state.lock().pending.get_mut(&nonce) {
+            tracked.hashes.push(tx_hash);
+            tracked.request = replacement;
+            tracked.max_fee_per_gas = max_fee_per_gas;
+            tracked.max_priority_fee_per_gas = max_priority_fee_per_gas;
+            tracked.submitted_at = Instant::now();
+            tracked.bumps += 1;
+        }
+        if pending.bumps + 1 == self.policy.max_bumps {
+            info!(
+                "Nonce {} reached {} fee bumps, no further replacements",
+                nonce, self.policy.max_bumps
+            );
+        }
+        Ok(Some(tx_hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy() -> EscalationPolicy {
+        EscalationPolicy {
+            stuck_after: Duration::from_secs(30),
+            bump_pct: 12,
+            max_bumps: 2,
+        }
+    }
+
+    #[test]
+    fn test_concurrent_allocations_get_distinct_nonces() {
+        let mut state = TxState::default();
+
+        // Two liquidations reserve nonces before either is broadcast
+        assert_eq!(state.allocate(7), 7);
+        assert_eq!(state.allocate(7), 8);
+
+        // The chain moving ahead (e.g. a transaction sent elsewhere) wins over the local count
+        assert_eq!(state.allocate(12), 12);
+
+        state.next_nonce = None;
+        assert_eq!(state.allocate(13), 13);
+    }
+
+    #[test]
+    fn test_fee_bumps_meet_replacement_minimum() {
+        assert_eq!(bumped_fees(1_000, 100, 12), (1_120, 112));
+        // Bumps below the node minimum are raised to it
+        assert_eq!(bumped_fees(1_000, 100, 5), (1_100, 110));
+        // Rounded up so tiny fees still strictly increase
+        assert_eq!(bumped_fees(1, 1, 10), (2, 2));
+    }
+
+    #[test]
+    fn test_stuck_detection_honours_policy() {
+        let manager = TxManager::new(Address::repeat_byte(0x01), policy());
+        manager.track(3, TxHash::repeat_byte(0xaa), TransactionRequest::default(), 1_000, 100);
+        manager.track(4, TxHash::repeat_byte(0xbb), TransactionRequest::default(), 1_000, 100);
+        assert_eq!(manager.pending_count(), 2);
+
+        let now = Instant::now();
+        assert!(manager.stuck_nonces(now).is_empty());
+        assert_eq!(manager.stuck_nonces(now + Duration::from_secs(31)), vec![3, 4]);
+
+        // Exhausted nonces are no longer bumped
+        manager.state.lock().pending.get_mut(&3).unwrap().bumps = 2;
+        assert_eq!(manager.stuck_nonces(now + Duration::from_secs(31)), vec![4]);
+
+        manager.confirm(4);
+        assert!(manager.pending(4).is_none());
+        assert_eq!(manager.pending(3).unwrap().latest_hash(), TxHash::repeat_byte(0xaa));
+    }
+
+    #[test]
+    fn test_pending_liquidation_is_found_by_borrower() {
+        let borrower = Address::repeat_byte(0x42);
+        let manager = TxManager::new(Address::repeat_byte(0x01), policy());
+        manager.track(5, TxHash::repeat_byte(0xaa), TransactionRequest::default(), 1_000, 100);
+        manager.track(6, TxHash::repeat_byte(0xbb), TransactionRequest::default(), 1_000, 100);
+        assert_eq!(manager.pending_liquidation(borrower), None);
+
+        manager.tag_borrower(6, borrower);
+        assert_eq!(manager.pending_liquidation(borrower), Some(6));
+
+        // Mined: a retry has nothing left to replace
+        manager.confirm(6);
+        assert_eq!(manager.pending_liquidation(borrower), None);
+    }
+}
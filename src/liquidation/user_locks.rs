@@ -0,0 +1,69 @@
+use alloy_primitives::Address;
+use dashmap::DashSet;
+
+/// Users with a liquidation in progress. Liquidations of different users run concurrently,
+/// but a second attempt on a user whose liquidation is still in flight is turned away, so the
+/// same account is never liquidated twice by racing transactions.
+#[derive(Debug, Default)]
+pub struct UserLocks {
+    locked: DashSet<Address>,
+}
+
+impl UserLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lock `user` until the returned guard is dropped. None while another liquidation of
+    /// the user holds the lock.
+    pub fn try_lock(&self, user: Address) -> Option<UserLockGuard<'_>> {
+        self.locked
+            .insert(user)
+            .then_some(UserLockGuard { locks: self, user })
+    }
+
+    /// Number of users being liquidated right now
+    pub fn len(&self) -> usize {
+        self.locked.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locked.is_empty()
+    }
+}
+
+/// Releases a user's lock when dropped
+#[derive(Debug)]
+pub struct UserLockGuard<'a> {
+    locks: &'a UserLocks,
+    user: Address,
+}
+
+impl Drop for UserLockGuard<'_> {
+    fn drop(&mut self) {
+        self.locks.locked.remove(&self.user);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_a_user_is_locked_until_its_guard_drops() {
+        let locks = UserLocks::new();
+        let user = Address::repeat_byte(0x01);
+        let other = Address::repeat_byte(0x02);
+
+        let guard = locks.try_lock(user).unwrap();
+        assert!(locks.try_lock(user).is_none());
+        // Other users can be liquidated meanwhile
+        let other_guard = locks.try_lock(other).unwrap();
+        assert_eq!(locks.len(), 2);
+
+        drop(guard);
+        assert!(locks.try_lock(user).is_some());
+        drop(other_guard);
+        assert!(locks.is_empty());
+    }
+}
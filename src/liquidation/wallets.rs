@@ -0,0 +1,285 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use eyre::Result;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use super::tx_manager::{EscalationPolicy, TxManager};
+use crate::signer::BotSigner;
+
+/// One executor wallet: its signer, its own nonce sequence and its last known balance
+pub struct ExecutorWallet {
+    signer: BotSigner,
+    tx_manager: Arc<TxManager>,
+    balance: RwLock<Option<U256>>, // None until the first refresh
+    in_flight: AtomicUsize,
+    liquidations: AtomicU64,
+}
+
+impl ExecutorWallet {
+    fn new(signer: BotSigner, policy: EscalationPolicy) -> Self {
+        Self {
+            tx_manager: Arc::new(TxManager::new(signer.address(), policy)),
+            signer,
+            balance: RwLock::new(None),
+            in_flight: AtomicUsize::new(0),
+            liquidations: AtomicU64::new(0),
+        }
+    }
+
+    pub fn address(&self) -> Address {
+        self.signer.address()
+    }
+
+    /// A wallet whose balance hasn't been read yet is assumed funded
+    fn is_funded(&self, gas_reserve: U256) -> bool {
+        self.balance
+            .read()
+            .map_or(true, |balance| balance >= gas_reserve)
+    }
+}
+
+/// Health of one executor wallet, as shown in status reports
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletStatus {
+    pub address: Address,
+    pub balance_wei: Option<U256>,
+    /// At or above the gas reserve, so it can be picked for liquidations
+    pub funded: bool,
+    pub in_flight: usize,
+    pub pending_txs: usize,
+    pub liquidations: u64,
+}
+
+/// A wallet reserved for one liquidation; released when dropped
+pub struct WalletLease {
+    wallet: Arc<ExecutorWallet>,
+}
+
+impl WalletLease {
+    pub fn address(&self) -> Address {
+        self.wallet.address()
+    }
+
+    pub fn signer(&self) -> &BotSigner {
+        &self.wallet.signer
+    }
+
+    pub fn tx_manager(&self) -> &Arc<TxManager> {
+        &self.wallet.tx_manager
+    }
+
+    /// Count a liquidation this wallet got mined
+    pub fn record_executed(&self) {
+        self.wallet.liquidations.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for WalletLease {
+    fn drop(&mut self) {
+        self.wallet.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Executor wallets liquidations are spread across. Each wallet has its own nonce sequence,
+/// so concurrent liquidations sent from different wallets never wait on each other's nonces.
+pub struct WalletPool {
+    wallets: Vec<Arc<ExecutorWallet>>,
+    gas_reserve_wei: U256,
+    next: AtomicUsize,
+}
+
+impl WalletPool {
+    pub fn new(signers: Vec<BotSigner>, policy: EscalationPolicy, gas_reserve_wei: U256) -> Self {
+        assert!(!signers.is_empty(), "wallet pool needs at least one signer");
+        Self {
+            wallets: signers
+                .into_iter()
+                .map(|signer| Arc::new(ExecutorWallet::new(signer, policy)))
+                .collect(),
+            gas_reserve_wei,
+            next: AtomicUsize::new(0),
+        }
+    }
+
+    /// First configured wallet, used where the bot acts under a single identity
+    pub fn primary(&self) -> &BotSigner {
+        &self.wallets[0].signer
+    }
+
+    pub fn len(&self) -> usize {
+        self.wallets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wallets.is_empty()
+    }
+
+    pub fn addresses(&self) -> Vec<Address> {
+        self.wallets.iter().map(|wallet| wallet.address()).collect()
+    }
+
+    pub fn signers(&self) -> impl Iterator<Item = &BotSigner> {
+        self.wallets.iter().map(|wallet| &wallet.signer)
+    }
+
+    /// Each wallet's signer with the transaction manager owning its nonces, which every
+    /// transaction it sends has to go through
+    pub fn senders(&self) -> impl Iterator<Item = (&BotSigner, &Arc<TxManager>)> {
+        self.wallets
+            .iter()
+            .map(|wallet| (&wallet.signer, &wallet.tx_manager))
+    }
+
+    /// Transaction manager of the first configured wallet
+    pub fn primary_tx_manager(&self) -> &Arc<TxManager> {
+        &self.wallets[0].tx_manager
+    }
+
+    /// Transaction manager owning `address`'s nonces, if it is an executor wallet
+    pub fn tx_manager(&self, address: Address) -> Option<&Arc<TxManager>> {
+        self.wallets
+            .iter()
+            .find(|wallet| wallet.address() == address)
+            .map(|wallet| &wallet.tx_manager)
+    }
+
+    /// Wallet with a liquidation of `borrower` still pending, and that transaction's nonce
+    pub fn pending_liquidation(
+        &self,
+        borrower: Address,
+    ) -> Option<(&BotSigner, &Arc<TxManager>, u64)> {
+        self.wallets.iter().find_map(|wallet| {
+            let nonce = wallet.tx_manager.pending_liquidation(borrower)?;
+            Some((&wallet.signer, &wallet.tx_manager, nonce))
+        })
+    }
+
+    /// Reserve the funded wallet with the fewest liquidations in flight, rotating between
+    /// equally busy ones. None when every wallet is below the gas reserve.
+    pub fn acquire(&self) -> Option<WalletLease> {
+        let start = self.next.fetch_add(1, Ordering::Relaxed);
+        let count = self.wallets.len();
+        let wallet = (0..count)
+            .map(|offset| &self.wallets[(start + offset) % count])
+            .filter(|wallet| wallet.is_funded(self.gas_reserve_wei))
+            .min_by_key(|wallet| wallet.in_flight.load(Ordering::Relaxed))?;
+        wallet.in_flight.fetch_add(1, Ordering::Relaxed);
+        Some(WalletLease {
+            wallet: wallet.clone(),
+        })
+    }
+
+    /// Record a freshly fetched balance, logging when a wallet drops below or recovers to
+    /// the gas reserve
+    pub fn record_balance(&self, address: Address, balance: U256) {
+        let Some(wallet) = self
+            .wallets
+            .iter()
+            .find(|wallet| wallet.address() == address)
+        else {
+            return;
+        };
+        let was_funded = wallet.is_funded(self.gas_reserve_wei);
+        *wallet.balance.write() = Some(balance);
+        match (was_funded, wallet.is_funded(self.gas_reserve_wei)) {
+            (true, false) => warn!(
+                "⛽ Wallet {} balance {} wei below gas reserve {} wei - skipping it",
+                address, balance, self.gas_reserve_wei
+            ),
+            (false, true) => info!(
+                "✅ Wallet {} balance {} wei back above gas reserve - using it again",
+                address, balance
+            ),
+            _ => {}
+        }
+    }
+
+    /// Re-read every wallet's native balance, returning the highest
+    pub async fn refresh_balances<P>(&self, provider: &P) -> Result<U256>
+    where
+        P: Provider,
+    {
+        let mut highest = U256::ZERO;
+        for wallet in &self.wallets {
+            let balance = provider.get_balance(wallet.address()).await?;
+            self.record_balance(wallet.address(), balance);
+            highest = highest.max(balance);
+        }
+        Ok(highest)
+    }
+
+    pub fn status(&self) -> Vec<WalletStatus> {
+        self.wallets
+            .iter()
+            .map(|wallet| WalletStatus {
+                address: wallet.address(),
+                balance_wei: *wallet.balance.read(),
+                funded: wallet.is_funded(self.gas_reserve_wei),
+                in_flight: wallet.in_flight.load(Ordering::Relaxed),
+                pending_txs: wallet.tx_manager.pending_count(),
+                liquidations: wallet.liquidations.load(Ordering::Relaxed),
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_signer_local::PrivateKeySigner;
+    use std::time::Duration;
+
+    fn pool(wallets: usize, gas_reserve_wei: u64) -> WalletPool {
+        let signers = (0..wallets)
+            .map(|_| BotSigner::from(PrivateKeySigner::random()))
+            .collect();
+        let policy = EscalationPolicy {
+            stuck_after: Duration::from_secs(30),
+            bump_pct: 15,
+            max_bumps: 3,
+        };
+        WalletPool::new(signers, policy, U256::from(gas_reserve_wei))
+    }
+
+    #[test]
+    fn test_concurrent_liquidations_use_different_wallets() {
+        let pool = pool(3, 0);
+
+        let mut leases: Vec<WalletLease> = (0..3).map(|_| pool.acquire().unwrap()).collect();
+        let mut addresses: Vec<Address> = leases.iter().map(|lease| lease.address()).collect();
+        addresses.sort();
+        addresses.dedup();
+        assert_eq!(addresses.len(), 3);
+
+        // Releasing a lease makes its wallet the least busy one again
+        let released = leases.remove(1).address();
+        assert_eq!(pool.status().iter().map(|s| s.in_flight).sum::<usize>(), 2);
+        assert_eq!(pool.acquire().unwrap().address(), released);
+    }
+
+    #[test]
+    fn test_wallets_below_gas_reserve_are_skipped() {
+        let pool = pool(2, 1_000);
+        let addresses = pool.addresses();
+        pool.record_balance(addresses[0], U256::from(999u64));
+        pool.record_balance(addresses[1], U256::from(1_000u64));
+
+        for _ in 0..4 {
+            assert_eq!(pool.acquire().unwrap().address(), addresses[1]);
+        }
+
+        pool.record_balance(addresses[1], U256::ZERO);
+        assert!(pool.acquire().is_none());
+        let status = pool.status();
+        assert!(status.iter().all(|wallet| !wallet.funded));
+        assert_eq!(status[0].balance_wei, Some(U256::from(999u64)));
+
+        // A top-up brings the wallet back
+        pool.record_balance(addresses[0], U256::from(5_000u64));
+        assert_eq!(pool.acquire().unwrap().address(), addresses[0]);
+    }
+}
@@ -0,0 +1,132 @@
+use alloy_primitives::U256;
+use eyre::Result;
+use serde_json::{json, Value};
+use std::time::Duration;
+
+use crate::models::LiquidationOpportunity;
+
+/// Forwards validated liquidation opportunities to an external executor, decoupling
+/// detection (this bot) from execution (the operator's own infrastructure)
+pub struct OpportunityWebhook {
+    url: String,
+    execute_locally: bool,
+    client: reqwest::Client,
+}
+
+impl OpportunityWebhook {
+    pub fn new(url: String, execute_locally: bool) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            url,
+            execute_locally,
+            client,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Whether the bot still executes opportunities itself after forwarding them
+    pub fn executes_locally(&self) -> bool {
+        self.execute_locally
+    }
+
+    /// POST an opportunity to the webhook, failing on transport errors and non-2xx responses
+    pub async fn post(&self, opportunity: &LiquidationOpportunity, health_factor: U256) -> Result<()> {
+        let response = self
+            .client
+            .post(&self.url)
+            .json(&opportunity_payload(opportunity, health_factor))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(eyre::eyre!(
+                "Opportunity webhook returned status {}",
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// JSON body sent for a detected opportunity. Amounts are decimal strings since they don't
+/// fit in a JSON number; the health factor has 18 decimals.
+pub fn opportunity_payload(opportunity: &LiquidationOpportunity, health_factor: U256) -> Value {
+    json!({
+        "user": opportunity.user.to_string(),
+        "collateral_asset": opportunity.collateral_asset.to_string(),
+        "debt_asset": opportunity.debt_asset.to_string(),
+        "debt_to_cover": opportunity.debt_to_cover.to_string(),
+        "expected_collateral_received": opportunity.expected_collateral_received.to_string(),
+        "estimated_profit": opportunity.estimated_profit.to_string(),
+        "flash_loan_source": opportunity.flash_loan_source.as_str(),
+        "health_factor": health_factor.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use crate::models::FlashLoanSource;
+    use alloy_primitives::Address;
+
+    fn opportunity() -> LiquidationOpportunity {
+        LiquidationOpportunity {
+            user: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000u64),
+            expected_collateral_received: U256::from(1_050u64),
+            liquidation_bonus: U256::from(50u64),
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            gas_cost: U256::from(10u64),
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::from(40u64),
+            profit_threshold_met: true,
+        }
+    }
+
+    #[test]
+    fn test_opportunity_payload() {
+        let health_factor = U256::from(950_000_000_000_000_000u64);
+        let payload = opportunity_payload(&opportunity(), health_factor);
+
+        assert_eq!(payload["user"], Address::repeat_byte(0x01).to_string());
+        assert_eq!(payload["collateral_asset"], Address::repeat_byte(0x02).to_string());
+        assert_eq!(payload["debt_asset"], Address::repeat_byte(0x03).to_string());
+        assert_eq!(payload["debt_to_cover"], "1000");
+        assert_eq!(payload["expected_collateral_received"], "1050");
+        assert_eq!(payload["estimated_profit"], "40");
+        assert_eq!(payload["flash_loan_source"], "aave");
+        assert_eq!(payload["health_factor"], "950000000000000000");
+    }
+
+    #[tokio::test]
+    async fn test_detected_opportunity_is_posted_to_webhook() {
+        let server = MockServer::spawn(|_| MockResponse::ok()).await;
+        let webhook = OpportunityWebhook::new(server.url("/opportunities"), false).unwrap();
+        let health_factor = U256::from(950_000_000_000_000_000u64);
+
+        webhook.post(&opportunity(), health_factor).await.unwrap();
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), 1);
+        assert_eq!(requests[0].path, "/opportunities");
+        assert_eq!(requests[0].json(), opportunity_payload(&opportunity(), health_factor));
+    }
+
+    #[tokio::test]
+    async fn test_webhook_error_status_is_reported() {
+        let server =
+            MockServer::spawn(|_| MockResponse::status("500 Internal Server Error")).await;
+        let webhook = OpportunityWebhook::new(server.url("/opportunities"), false).unwrap();
+
+        assert!(webhook.post(&opportunity(), U256::ZERO).await.is_err());
+    }
+}
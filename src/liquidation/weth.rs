@@ -0,0 +1,189 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use eyre::Result;
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+use super::profit_recheck::fetch_token_balance;
+use super::tx_manager::TxManager;
+use crate::signer::BotSigner;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IWETH9 {
+        function deposit() external payable;
+        function withdraw(uint256 wad) external;
+    }
+}
+
+/// Gas a WETH9 deposit or withdrawal costs, with headroom
+const WETH_CALL_GAS: u64 = 50_000;
+
+/// A conversion has to move at least this multiple of its own gas cost to be worth sending
+const MIN_VALUE_TO_GAS_MULTIPLE: u64 = 10;
+
+/// How long to wait for a wrap or unwrap to be mined
+const WETH_RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Conversion between an executor wallet's native ETH and WETH
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WethAction {
+    /// Deposit this much native ETH into WETH
+    Wrap(U256),
+    /// Withdraw this much WETH to native ETH
+    Unwrap(U256),
+}
+
+/// Native balance band an executor wallet is kept in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WethPolicy {
+    /// Unwrap WETH until the native balance reaches this
+    pub gas_target_wei: U256,
+    /// Wrap native ETH above the gas target once the balance exceeds this (None = never wrap)
+    pub wrap_above_wei: Option<U256>,
+}
+
+/// What to convert given the wallet's balances and the current gas price. A low native
+/// balance is topped up from WETH; a native balance over the wrap ceiling is wrapped back down
+/// to the gas target, so profits stay in WETH. Conversions too small to be worth their own
+/// gas are skipped.
+pub fn weth_action(
+    native_balance: U256,
+    weth_balance: U256,
+    policy: &WethPolicy,
+    gas_price: u128,
+) -> Option<WethAction> {
+    let min_worthwhile =
+        U256::from(gas_price).saturating_mul(U256::from(WETH_CALL_GAS * MIN_VALUE_TO_GAS_MULTIPLE));
+
+    let action = if native_balance < policy.gas_target_wei {
+        WethAction::Unwrap((policy.gas_target_wei - native_balance).min(weth_balance))
+    } else {
+        match policy.wrap_above_wei {
+            Some(ceiling) if native_balance > ceiling => {
+                WethAction::Wrap(native_balance - policy.gas_target_wei)
+            }
+            _ => return None,
+        }
+    };
+    let (WethAction::Wrap(amount) | WethAction::Unwrap(amount)) = action;
+    (!amount.is_zero() && amount >= min_worthwhile).then_some(action)
+}
+
+/// Keeps executor wallets' gas topped up from the WETH liquidations leave with them, and
+/// optionally parks surplus native ETH in WETH
+pub struct WethBalancer {
+    weth: Address,
+    policy: WethPolicy,
+    broadcast: bool, // BotConfig::broadcasts_transactions
+}
+
+impl WethBalancer {
+    pub fn new(weth: Address, policy: WethPolicy, broadcast: bool) -> Self {
+        Self {
+            weth,
+            policy,
+            broadcast,
+        }
+    }
+
+    pub fn weth(&self) -> Address {
+        self.weth
+    }
+
+    /// Wrap or unwrap for `signer` if its balances call for it, with a nonce from `tx_manager`
+    /// (mocked unless broadcasting). Returns the conversion made, if any.
+    pub async fn rebalance<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+    ) -> Result<Option<WethAction>>
+    where
+        P: Provider,
+    {
+        let owner = signer.address();
+        let native_balance = provider.get_balance(owner).await?;
+        let weth_balance = fetch_token_balance(provider, self.weth, owner).await?;
+        let gas_price = provider.get_gas_price().await?;
+
+        let Some(action) = weth_action(native_balance, weth_balance, &self.policy, gas_price)
+        else {
+            debug!(
+                "⛽ No WETH conversion needed for {} (native {}, WETH {})",
+                owner, native_balance, weth_balance
+            );
+            return Ok(None);
+        };
+
+        if !self.broadcast {
+            warn!(
+                "🎭 MOCK WETH {:?} for {} (native {}, WETH {}) - no on-chain execution",
+                action, owner, native_balance, weth_balance
+            );
+            return Ok(Some(action));
+        }
+
+        let tx_req = match action {
+            WethAction::Wrap(amount) => TransactionRequest::default()
+                .from(owner)
+                .to(self.weth)
+                .value(amount)
+                .input(IWETH9::depositCall {}.abi_encode().into()),
+            WethAction::Unwrap(amount) => TransactionRequest::default()
+                .from(owner)
+                .to(self.weth)
+                .input(IWETH9::withdrawCall { wad: amount }.abi_encode().into()),
+        };
+        let tx_hash = tx_manager
+            .send_and_confirm(provider, signer, tx_req, WETH_RECEIPT_TIMEOUT)
+            .await?;
+
+        info!(
+            "⛽ WETH {:?} for {} (native was {}, WETH {}): 0x{:x}",
+            action, owner, native_balance, weth_balance, tx_hash
+        );
+        Ok(Some(action))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gas_is_topped_up_from_weth_and_surplus_wrapped() {
+        let eth = |milli: u64| U256::from(milli) * U256::from(1_000_000_000_000_000u64);
+        let policy = WethPolicy {
+            gas_target_wei: eth(50),
+            wrap_above_wei: Some(eth(200)),
+        };
+        let gas_price = 1_000_000_000; // 1 gwei: conversions under 0.0005 ETH are skipped
+
+        // Low on gas: unwrap up to the target, limited by the WETH held
+        assert_eq!(
+            weth_action(eth(10), eth(100), &policy, gas_price),
+            Some(WethAction::Unwrap(eth(40)))
+        );
+        assert_eq!(
+            weth_action(eth(10), eth(5), &policy, gas_price),
+            Some(WethAction::Unwrap(eth(5)))
+        );
+        assert_eq!(weth_action(eth(10), U256::ZERO, &policy, gas_price), None);
+
+        // Inside the band nothing moves; above it the surplus goes back to WETH
+        assert_eq!(weth_action(eth(150), eth(100), &policy, gas_price), None);
+        assert_eq!(
+            weth_action(eth(250), U256::ZERO, &policy, gas_price),
+            Some(WethAction::Wrap(eth(200)))
+        );
+
+        // Not worth the gas
+        assert_eq!(
+            weth_action(eth(50) - U256::from(1_000u64), eth(100), &policy, gas_price),
+            None
+        );
+    }
+}
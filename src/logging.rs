@@ -0,0 +1,100 @@
+/// Log a per-item detail line (one per asset, user, ...) inside a high-frequency loop.
+///
+/// Emitted at `info` level when `verbose` is set, otherwise at `debug` level so that
+/// production logs only carry the per-cycle summary line.
+///
+/// ```rust
+/// # let verbose = false;
+/// # let symbol = "WETH";
+/// liquidation_bot::per_item_log!(verbose, "✅ {} price fetched", symbol);
+/// ```
+#[macro_export]
+macro_rules! per_item_log {
+    ($verbose:expr, $($arg:tt)+) => {
+        if $verbose {
+            tracing::info!($($arg)+)
+        } else {
+            tracing::debug!($($arg)+)
+        }
+    };
+}
+
+// In-memory log capture for tests asserting what a code path logs
+#[cfg(test)]
+pub(crate) mod capture {
+    use parking_lot::Mutex;
+    use std::io::Write;
+    use std::sync::Arc;
+    use tracing_subscriber::fmt::MakeWriter;
+
+    /// Writer collecting formatted log output in memory
+    #[derive(Clone, Default)]
+    pub(crate) struct CapturedLogs(Arc<Mutex<Vec<u8>>>);
+
+    impl CapturedLogs {
+        pub(crate) fn contents(&self) -> String {
+            String::from_utf8_lossy(&self.0.lock()).into_owned()
+        }
+    }
+
+    impl Write for CapturedLogs {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for CapturedLogs {
+        type Writer = CapturedLogs;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    /// Run `f` under an info-level subscriber and return everything it logged
+    pub(crate) fn capture_info_logs(f: impl FnOnce()) -> String {
+        let logs = CapturedLogs::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::INFO)
+            .with_writer(logs.clone())
+            .finish();
+
+        tracing::subscriber::with_default(subscriber, f);
+        logs.contents()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::capture::capture_info_logs;
+    use tracing::info;
+
+    /// Run a simulated polling cycle under an info-level subscriber and return its output
+    fn run_cycle(verbose: bool) -> String {
+        capture_info_logs(|| {
+            for symbol in ["WETH", "USDC", "cbBTC"] {
+                crate::per_item_log!(verbose, "per-item: {} price fetched", symbol);
+            }
+            info!("summary: 3 prices fetched");
+        })
+    }
+
+    #[test]
+    fn test_per_item_info_logs_suppressed_when_not_verbose() {
+        let output = run_cycle(false);
+        assert!(output.contains("summary: 3 prices fetched"));
+        assert!(!output.contains("per-item"));
+    }
+
+    #[test]
+    fn test_per_item_info_logs_emitted_when_verbose() {
+        let output = run_cycle(true);
+        assert!(output.contains("summary: 3 prices fetched"));
+        assert_eq!(output.matches("per-item").count(), 3);
+    }
+}
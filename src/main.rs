@@ -1,54 +1,189 @@
-use alloy_provider::ProviderBuilder;
-use alloy_signer_local::PrivateKeySigner;
+use alloy_provider::RootProvider;
+use clap::{Parser, Subcommand};
 use eyre::Result;
+use std::path::PathBuf;
 use std::sync::Arc;
-use tracing::info;
+use tracing::{error, info, warn};
 
-use liquidation_bot::{BotConfig, LiquidationBot};
+use liquidation_bot::providers::ProviderPool;
+use liquidation_bot::{
+    config, dashboard, database, replay, shutdown, signer, BotConfig, LiquidationBot,
+};
+
+#[derive(Parser)]
+#[command(name = "liquidation-bot")]
+#[command(about = "Aave v3 liquidation bot", long_about = None)]
+struct Cli {
+    /// Write a Grafana dashboard JSON for the bot's metrics to this path and exit
+    #[arg(long, value_name = "PATH")]
+    emit_dashboard: Option<PathBuf>,
+
+    /// TOML configuration file; environment variables override its values
+    /// (defaults to config.toml when present)
+    #[arg(long, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// Validate the configuration, print the fully resolved values and exit
+    #[arg(long)]
+    check_config: bool,
+
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Reproduce the decisions of a recorded event stream (see EVENT_RECORD_PATH) from its
+    /// recorded inputs, against an in-memory database and without touching the chain
+    Replay {
+        /// Recording to replay
+        file: PathBuf,
+    },
+    /// Copy an existing SQLite database into PostgreSQL, applying schema migrations to both
+    MigrateData {
+        /// Source database URL (sqlite:...)
+        #[arg(long)]
+        from: String,
+        /// Target database URL (postgres://...)
+        #[arg(long)]
+        to: String,
+    },
+}
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = Cli::parse();
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
         .init();
 
-    info!("Starting Aave v3 Liquidation Bot on Base");
+    if let Some(path) = cli.emit_dashboard {
+        dashboard::write_dashboard(&path)?;
+        info!("📈 Grafana dashboard written to {}", path.display());
+        return Ok(());
+    }
+
+    if cli.check_config {
+        let (configs, problems) = BotConfig::load_chains_with_problems(cli.config.as_deref())?;
+        for config in &configs {
+            println!("{:#?}", config.redacted());
+        }
+        if problems.is_empty() {
+            println!("✅ Configuration is valid");
+            return Ok(());
+        }
+        for problem in &problems {
+            eprintln!("❌ {}", problem);
+        }
+        return Err(eyre::eyre!("{} configuration problem(s) found", problems.len()));
+    }
+
+    if let Some(Command::MigrateData { from, to }) = &cli.command {
+        // Rows carry their own chain_id, so the pools' chain scope is irrelevant to the copy
+        let source = database::init_database(from, config::DEFAULT_CHAIN_ID).await?;
+        let target = database::init_database(to, config::DEFAULT_CHAIN_ID).await?;
+        let copied = database::copy_sqlite_to_postgres(&source, &target).await?;
+        let total: u64 = copied.iter().map(|(_, rows)| rows).sum();
+        info!("✅ Copied {} rows across {} tables", total, copied.len());
+        return Ok(());
+    }
+
+    info!("Starting Aave v3 Liquidation Bot");
+
+    // Load configuration: one bot per configured chain
+    let configs = BotConfig::load_chains(cli.config.as_deref())?;
+    info!("Configuration loaded for {} chain(s)", configs.len());
 
-    // Load configuration
-    let config = BotConfig::from_env()?;
-    info!("Configuration loaded");
+    if let Some(Command::Replay { file }) = &cli.command {
+        let [config] = <[BotConfig; 1]>::try_from(configs)
+            .map_err(|_| eyre::eyre!("Replay needs a configuration with a single chain"))?;
+        info!("📼 Replay mode - recorded inputs only, nothing is read from or sent to the chain");
+        let decisions =
+            replay::replay_file(file, config.chain_id, config.min_hf_change_to_persist_bps).await?;
+        for decision in &decisions {
+            info!("📼 {:?}", decision);
+        }
+        info!("✅ Replay complete: {} decisions", decisions.len());
+        return Ok(());
+    }
+
+    let mut bots = Vec::with_capacity(configs.len());
+    for config in configs {
+        bots.push(start_bot(config).await?);
+    }
+
+    // SIGTERM / Ctrl-C stops every bot gracefully; a second signal exits immediately
+    let shutdown_handles: Vec<_> = bots.iter().map(|bot| bot.shutdown_handle()).collect();
+    tokio::spawn(async move {
+        match shutdown::wait_for_signal().await {
+            Ok(signal) => info!("🛑 Received {} - shutting down gracefully", signal),
+            Err(e) => {
+                error!("Unable to listen for shutdown signals: {}", e);
+                return;
+            }
+        }
+        for handle in &shutdown_handles {
+            handle.trigger();
+        }
+        if shutdown::wait_for_signal().await.is_ok() {
+            warn!("🛑 Received a second signal - exiting immediately");
+            std::process::exit(130);
+        }
+    });
+
+    // Run the bots; one failing stops the process
+    futures::future::try_join_all(bots.iter().map(|bot| bot.run())).await?;
+
+    Ok(())
+}
 
-    // Parse private key and create signer
-    let signer: PrivateKeySigner = config.private_key.parse()?;
-    info!("Signer created from private key");
+/// Build the signer, providers and bot for one chain's configuration
+async fn start_bot(
+    config: BotConfig,
+) -> Result<LiquidationBot<RootProvider<alloy_transport::BoxTransport>>> {
+    info!("⛓️ Setting up {} (chain {})", config.chain_name, config.chain_id);
+
+    // Open the executor wallets from the configured backend (private keys, keystores, KMS or Ledger)
+    let signers =
+        signer::build_signers(&config.signer_backend, &config.private_key, config.chain_id).await?;
+
+    // Build the HTTP provider over the primary RPC endpoint and any fallbacks
+    let rpc_urls: Vec<String> = std::iter::once(config.rpc_url.clone())
+        .chain(config.rpc_fallback_urls.iter().cloned())
+        .collect();
+    let provider_pool = ProviderPool::new(&rpc_urls)?
+        .with_rate_limits(config.rpc_rate_limit_rps, &config.rpc_endpoint_rate_limits);
 
-    // Build HTTP provider
-    let url = url::Url::parse(&config.rpc_url)?;
-    
     // Create a provider for reading blockchain state
     // The signer will be used separately for sending transactions
-    let provider = ProviderBuilder::new().on_http(url.clone()).boxed();
-    let provider = Arc::new(provider);
-    info!("HTTP Provider connected to: {}", config.rpc_url);
+    let provider = Arc::new(provider_pool.provider());
+    info!(
+        "HTTP Provider connected to: {} ({} fallback endpoints)",
+        config.rpc_url,
+        config.rpc_fallback_urls.len()
+    );
+
+    if provider_pool.len() > 1 && config.provider_health_check_interval_secs > 0 {
+        tokio::spawn(
+            provider_pool
+                .clone()
+                .run_health_checks(config.provider_health_check_interval_secs),
+        );
+    }
 
-    info!("✅ Signer ready for transaction signing");
     info!("WebSocket will connect to: {}", config.ws_url);
 
     // Create bot instance with signer for transaction signing
-    let bot = LiquidationBot::new(provider, config, signer).await?;
+    let chain_name = config.chain_name.clone();
+    let bot = LiquidationBot::new(provider, config, signers).await?;
 
-    let using_websocket =
-        bot.config.ws_url.starts_with("wss://") && !bot.config.ws_url.contains("sepolia.base.org");
-
-    if using_websocket {
-        info!("🤖 Liquidation bot initialized with real-time WebSocket monitoring");
+    if bot.is_real_time_enabled() {
+        info!("🤖 {} bot initialized with real-time WebSocket monitoring", chain_name);
     } else {
-        info!("🤖 Liquidation bot initialized with polling-based monitoring");
+        info!("🤖 {} bot initialized with polling-based monitoring", chain_name);
     }
 
-    // Run the bot
-    bot.run().await?;
-
-    Ok(())
+    Ok(bot)
 }
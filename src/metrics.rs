@@ -0,0 +1,278 @@
+// Metric names shared by the exporter registry and the Grafana dashboard generator, so a
+// renamed metric can't silently break an imported dashboard
+
+pub const POSITIONS_TRACKED: &str = "liquidation_bot_positions_tracked";
+pub const POSITIONS_AT_RISK: &str = "liquidation_bot_positions_at_risk";
+pub const POSITIONS_LIQUIDATABLE: &str = "liquidation_bot_positions_liquidatable";
+pub const VALUE_AT_RISK: &str = "liquidation_bot_value_at_risk";
+pub const CIRCUIT_BREAKER_STATE: &str = "liquidation_bot_circuit_breaker_state";
+pub const LIQUIDATIONS_TOTAL: &str = "liquidation_bot_liquidations_total";
+pub const LIQUIDATIONS_BLOCKED_TOTAL: &str = "liquidation_bot_liquidations_blocked_total";
+pub const PROFIT_WEI_TOTAL: &str = "liquidation_bot_profit_wei_total";
+pub const LIQUIDATION_QUEUE_DEPTH: &str = "liquidation_bot_liquidation_queue_depth";
+pub const RPC_ERRORS_TOTAL: &str = "liquidation_bot_rpc_errors_total";
+pub const RPC_REQUESTS_THROTTLED_TOTAL: &str = "liquidation_bot_rpc_requests_throttled_total";
+pub const EVENT_LOOP_LAG_SECONDS: &str = "liquidation_bot_event_loop_lag_seconds";
+pub const POSITION_WRITE_QUEUE_DEPTH: &str = "liquidation_bot_position_write_queue_depth";
+pub const WALLET_NATIVE_BALANCE_WEI: &str = "liquidation_bot_wallet_native_balance_wei";
+pub const WALLET_TOKEN_BALANCE: &str = "liquidation_bot_wallet_token_balance";
+pub const WALLETS_FUNDED: &str = "liquidation_bot_wallets_funded";
+pub const EVENT_QUEUE_DEPTH: &str = "liquidation_bot_event_queue_depth";
+pub const EVENTS_COALESCED_TOTAL: &str = "liquidation_bot_events_coalesced_total";
+pub const EVENTS_DROPPED_TOTAL: &str = "liquidation_bot_events_dropped_total";
+pub const POSITION_REFRESHES_THROTTLED_TOTAL: &str =
+    "liquidation_bot_position_refreshes_throttled_total";
+pub const BLOCK_SCANS_TOTAL: &str = "liquidation_bot_block_scans_total";
+pub const BLOCK_SCAN_BUDGET_EXHAUSTED_TOTAL: &str =
+    "liquidation_bot_block_scan_budget_exhausted_total";
+pub const SCAN_TIER_POSITIONS: &str = "liquidation_bot_scan_tier_positions";
+pub const EVENT_BUS_MESSAGES_TOTAL: &str = "liquidation_bot_event_bus_messages_total";
+pub const WEBHOOK_DELIVERIES_TOTAL: &str = "liquidation_bot_webhook_deliveries_total";
+pub const LIQUIDATION_FAILURES_TOTAL: &str = "liquidation_bot_liquidation_failures_total";
+pub const LIQUIDATION_RETRY_DECISIONS_TOTAL: &str =
+    "liquidation_bot_liquidation_retry_decisions_total";
+pub const TREASURY_SWEEPS_TOTAL: &str = "liquidation_bot_treasury_sweeps_total";
+pub const INVENTORY_VALUE_USD: &str = "liquidation_bot_inventory_value_usd";
+pub const INVENTORY_REBALANCES_TOTAL: &str = "liquidation_bot_inventory_rebalances_total";
+
+/// Prometheus metric type
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+impl MetricKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MetricKind::Gauge => "gauge",
+            MetricKind::Counter => "counter",
+        }
+    }
+}
+
+/// A metric the bot exports
+#[derive(Debug, Clone, Copy)]
+pub struct MetricDefinition {
+    pub name: &'static str,
+    pub help: &'static str,
+    pub kind: MetricKind,
+    /// Label names attached to each sample (empty for unlabelled metrics)
+    pub labels: &'static [&'static str],
+}
+
+/// Every metric the bot exports
+pub const REGISTRY: &[MetricDefinition] = &[
+    MetricDefinition {
+        name: POSITIONS_TRACKED,
+        help: "User positions currently tracked",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: POSITIONS_AT_RISK,
+        help: "Tracked positions below the at-risk health factor threshold",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: POSITIONS_LIQUIDATABLE,
+        help: "Tracked positions with a health factor below 1.0",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: VALUE_AT_RISK,
+        help: "Total value at risk across liquidatable positions (base currency, 8 decimals)",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: CIRCUIT_BREAKER_STATE,
+        help: "Circuit breaker state (0 = closed, 1 = open, 2 = half-open, 3 = disabled)",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: LIQUIDATIONS_TOTAL,
+        help: "Liquidations attempted, by outcome",
+        kind: MetricKind::Counter,
+        labels: &["outcome"],
+    },
+    MetricDefinition {
+        name: LIQUIDATIONS_BLOCKED_TOTAL,
+        help: "Liquidations blocked by the circuit breaker",
+        kind: MetricKind::Counter,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: PROFIT_WEI_TOTAL,
+        help: "Recorded liquidation profit in wei, by strategy label",
+        kind: MetricKind::Counter,
+        labels: &["strategy"],
+    },
+    MetricDefinition {
+        name: LIQUIDATION_QUEUE_DEPTH,
+        help: "Priority liquidations waiting to be processed",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: RPC_ERRORS_TOTAL,
+        help: "Failed RPC requests, by the subsystem that made them",
+        kind: MetricKind::Counter,
+        labels: &["source"],
+    },
+    MetricDefinition {
+        name: RPC_REQUESTS_THROTTLED_TOTAL,
+        help: "RPC requests delayed by an endpoint's rate limiter, by endpoint host",
+        kind: MetricKind::Counter,
+        labels: &["endpoint"],
+    },
+    MetricDefinition {
+        name: EVENT_LOOP_LAG_SECONDS,
+        help: "How late the async runtime woke a one-second timer",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: POSITION_WRITE_QUEUE_DEPTH,
+        help: "Position updates queued for a batched database write",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: WALLET_NATIVE_BALANCE_WEI,
+        help: "Native balance of each executor wallet in wei",
+        kind: MetricKind::Gauge,
+        labels: &["wallet"],
+    },
+    MetricDefinition {
+        name: WALLET_TOKEN_BALANCE,
+        help: "Debt asset balance of each executor wallet in whole tokens",
+        kind: MetricKind::Gauge,
+        labels: &["wallet", "token"],
+    },
+    MetricDefinition {
+        name: WALLETS_FUNDED,
+        help: "Executor wallets above the gas reserve (0 = liquidation execution paused)",
+        kind: MetricKind::Gauge,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: EVENT_QUEUE_DEPTH,
+        help: "Events waiting to be processed, by lane",
+        kind: MetricKind::Gauge,
+        labels: &["lane"],
+    },
+    MetricDefinition {
+        name: EVENTS_COALESCED_TOTAL,
+        help: "Events merged into an identical event already queued, by lane",
+        kind: MetricKind::Counter,
+        labels: &["lane"],
+    },
+    MetricDefinition {
+        name: EVENTS_DROPPED_TOTAL,
+        help: "Events dropped because their lane was full, by lane",
+        kind: MetricKind::Counter,
+        labels: &["lane"],
+    },
+    MetricDefinition {
+        name: POSITION_REFRESHES_THROTTLED_TOTAL,
+        help: "Position refresh requests deferred or merged because the user was refreshed recently",
+        kind: MetricKind::Counter,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: BLOCK_SCANS_TOTAL,
+        help: "New blocks on which the riskiest positions were re-checked",
+        kind: MetricKind::Counter,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: BLOCK_SCAN_BUDGET_EXHAUSTED_TOTAL,
+        help: "Block scans stopped by their per-block time budget before every user was checked",
+        kind: MetricKind::Counter,
+        labels: &[],
+    },
+    MetricDefinition {
+        name: SCAN_TIER_POSITIONS,
+        help: "Positions with debt per scan tier, by the health factor bound of the tier",
+        kind: MetricKind::Gauge,
+        labels: &["tier"],
+    },
+    MetricDefinition {
+        name: EVENT_BUS_MESSAGES_TOTAL,
+        help: "Messages mirrored to the message bus, by topic and outcome (published, dropped or failed)",
+        kind: MetricKind::Counter,
+        labels: &["topic", "outcome"],
+    },
+    MetricDefinition {
+        name: WEBHOOK_DELIVERIES_TOTAL,
+        help: "Event webhook deliveries by outcome (delivered, retried or failed)",
+        kind: MetricKind::Counter,
+        labels: &["outcome"],
+    },
+    MetricDefinition {
+        name: LIQUIDATION_FAILURES_TOTAL,
+        help: "Failed liquidation attempts by failure category",
+        kind: MetricKind::Counter,
+        labels: &["category"],
+    },
+    MetricDefinition {
+        name: LIQUIDATION_RETRY_DECISIONS_TOTAL,
+        help: "Retry policy decisions after failed liquidations (retry, abandon or exhausted)",
+        kind: MetricKind::Counter,
+        labels: &["decision"],
+    },
+    MetricDefinition {
+        name: TREASURY_SWEEPS_TOTAL,
+        help: "Transfers of executor wallet profits to the cold wallet by asset and outcome (swept or failed)",
+        kind: MetricKind::Counter,
+        labels: &["asset", "outcome"],
+    },
+    MetricDefinition {
+        name: INVENTORY_VALUE_USD,
+        help: "Oracle value of each asset held across the executor wallets in USD",
+        kind: MetricKind::Gauge,
+        labels: &["asset"],
+    },
+    MetricDefinition {
+        name: INVENTORY_REBALANCES_TOTAL,
+        help: "Swaps of over-exposed inventory into the target asset by asset and outcome (swapped, no_route or failed)",
+        kind: MetricKind::Counter,
+        labels: &["asset", "outcome"],
+    },
+];
+
+/// Look up a metric definition by name
+pub fn find(name: &str) -> Option<&'static MetricDefinition> {
+    REGISTRY.iter().find(|metric| metric.name == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_metric_names_are_unique_and_prefixed() {
+        let mut seen = HashSet::new();
+        for metric in REGISTRY {
+            assert!(seen.insert(metric.name), "duplicate metric {}", metric.name);
+            assert!(metric.name.starts_with("liquidation_bot_"));
+        }
+    }
+
+    #[test]
+    fn test_counters_follow_total_suffix_convention() {
+        for metric in REGISTRY {
+            assert_eq!(
+                metric.kind == MetricKind::Counter,
+                metric.name.ends_with("_total"),
+                "{}",
+                metric.name
+            );
+        }
+    }
+}
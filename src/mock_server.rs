@@ -0,0 +1,190 @@
+//! Minimal HTTP server for tests standing in for webhooks, relays, RPC nodes and aggregator
+//! APIs. Connections are served one at a time and closed after each response.
+
+use parking_lot::Mutex;
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+
+/// A request received by a [`MockServer`]
+#[derive(Debug, Clone)]
+pub(crate) struct MockRequest {
+    pub method: String,
+    /// Path including the query string
+    pub path: String,
+    /// Request line and headers as sent
+    pub head: String,
+    pub body: String,
+}
+
+impl MockRequest {
+    /// Body parsed as JSON (Null when it isn't)
+    pub fn json(&self) -> Value {
+        serde_json::from_str(&self.body).unwrap_or(Value::Null)
+    }
+
+    /// Value of header `name`, matched case-insensitively
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.head.lines().skip(1).find_map(|line| {
+            let (header, value) = line.split_once(':')?;
+            header.eq_ignore_ascii_case(name).then(|| value.trim())
+        })
+    }
+}
+
+/// Response a [`MockServer`] handler sends back
+#[derive(Debug, Clone)]
+pub(crate) struct MockResponse {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: String,
+}
+
+impl MockResponse {
+    /// Empty response with `status`, e.g. "503 Service Unavailable"
+    pub fn status(status: &'static str) -> Self {
+        Self {
+            status,
+            headers: Vec::new(),
+            body: String::new(),
+        }
+    }
+
+    pub fn ok() -> Self {
+        Self::status("200 OK")
+    }
+
+    /// 200 response with a JSON body
+    pub fn json(body: &Value) -> Self {
+        Self {
+            status: "200 OK",
+            headers: vec![("Content-Type", "application/json".to_string())],
+            body: body.to_string(),
+        }
+    }
+
+    pub fn with_status(mut self, status: &'static str) -> Self {
+        self.status = status;
+        self
+    }
+
+    pub fn with_header(mut self, name: &'static str, value: impl Into<String>) -> Self {
+        self.headers.push((name, value.into()));
+        self
+    }
+
+    fn to_http(&self) -> String {
+        let mut response = format!("HTTP/1.1 {}\r\n", self.status);
+        for (name, value) in &self.headers {
+            response.push_str(&format!("{}: {}\r\n", name, value));
+        }
+        response.push_str(&format!(
+            "Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.body.len(),
+            self.body
+        ));
+        response
+    }
+}
+
+/// Local HTTP server recording every request and answering it with a handler
+pub(crate) struct MockServer {
+    /// Base URL, without a trailing slash
+    pub url: String,
+    requests: Arc<Mutex<Vec<MockRequest>>>,
+}
+
+impl MockServer {
+    /// Serve every request with `handler`
+    pub async fn spawn<F>(mut handler: F) -> Self
+    where
+        F: FnMut(&MockRequest) -> MockResponse + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let recorded = requests.clone();
+
+        tokio::spawn(async move {
+            loop {
+                let (mut socket, _) = match listener.accept().await {
+                    Ok(conn) => conn,
+                    Err(_) => return,
+                };
+                let Some(request) = read_request(&mut socket).await else {
+                    continue;
+                };
+                recorded.lock().push(request.clone());
+                let response = handler(&request);
+                let _ = socket.write_all(response.to_http().as_bytes()).await;
+            }
+        });
+
+        Self {
+            url: format!("http://{}", addr),
+            requests,
+        }
+    }
+
+    /// JSON-RPC node answering every call with `handler(request)` as the result
+    pub async fn json_rpc<F>(mut handler: F) -> Self
+    where
+        F: FnMut(&Value) -> Value + Send + 'static,
+    {
+        Self::spawn(move |request| {
+            let request = request.json();
+            let result = handler(&request);
+            MockResponse::json(&json!({"jsonrpc": "2.0", "id": request["id"], "result": result}))
+        })
+        .await
+    }
+
+    /// `path` appended to the base URL
+    pub fn url(&self, path: &str) -> String {
+        format!("{}{}", self.url, path)
+    }
+
+    /// Requests received so far, oldest first
+    pub fn requests(&self) -> Vec<MockRequest> {
+        self.requests.lock().clone()
+    }
+}
+
+/// Read until the headers and the full Content-Length body have arrived
+async fn read_request(socket: &mut TcpStream) -> Option<MockRequest> {
+    let mut request = Vec::new();
+    let mut buf = [0u8; 1024];
+    loop {
+        let n = socket.read(&mut buf).await.unwrap_or(0);
+        if n == 0 {
+            return None;
+        }
+        request.extend_from_slice(&buf[..n]);
+
+        let text = String::from_utf8_lossy(&request);
+        let Some(header_end) = text.find("\r\n\r\n") else {
+            continue;
+        };
+        let head = &text[..header_end];
+        let content_length = head
+            .lines()
+            .find_map(|line| {
+                let (name, value) = line.split_once(':')?;
+                name.eq_ignore_ascii_case("content-length")
+                    .then(|| value.trim().parse::<usize>().ok())?
+            })
+            .unwrap_or(0);
+        if request.len() < header_end + 4 + content_length {
+            continue;
+        }
+
+        let mut request_line = head.split_whitespace();
+        return Some(MockRequest {
+            method: request_line.next().unwrap_or("").to_string(),
+            path: request_line.next().unwrap_or("").to_string(),
+            head: head.to_string(),
+            body: text[header_end + 4..].to_string(),
+        });
+    }
+}
@@ -1,8 +1,9 @@
 use alloy_json_abi::JsonAbi;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, Bytes, I256, U256};
 use alloy_sol_types::sol;
 use chrono::{DateTime, Utc};
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
 
 // Define Aave events using sol! macro for type safety
 sol! {
@@ -64,6 +65,17 @@ sol! {
         uint256 indexed roundId,
         uint256 updatedAt
     );
+
+    // Emitted by our liquidator contract; profit is in debt asset units, after repaying the
+    // flash loan
+    event LiquidationExecuted(
+        address indexed user,
+        address indexed collateralAsset,
+        address indexed debtAsset,
+        uint256 debtCovered,
+        uint256 collateralReceived,
+        uint256 profit
+    );
 }
 
 // Oracle price feed monitoring
@@ -83,6 +95,7 @@ pub struct AssetConfig {
     pub symbol: String,
     pub chainlink_feed: Address,
     pub price_change_threshold: f64, // e.g., 0.05 for 5% change
+    pub heartbeat_secs: u64, // Longest the feed goes without a new round under normal conditions
 }
 
 #[derive(Deserialize)]
@@ -91,7 +104,7 @@ pub struct HardhatArtifact {
 }
 
 // User position tracking
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct UserPosition {
     pub address: Address,
     pub total_collateral_base: U256,
@@ -124,6 +137,7 @@ pub struct LiquidationOpportunity {
     pub expected_collateral_received: U256,
     pub liquidation_bonus: U256,
     pub flash_loan_fee: U256,
+    pub flash_loan_source: FlashLoanSource,
     pub gas_cost: U256,
     pub swap_slippage: U256,
     pub estimated_profit: U256,
@@ -139,6 +153,21 @@ pub struct LiquidationParams {
     pub collateral_asset_id: u16,
     pub debt_asset_id: u16,
     pub receive_a_token: bool,
+    pub flash_loan_source: FlashLoanSource,
+    /// Least profit the contract must clear, in debt asset units (None = no on-chain check)
+    pub min_profit: Option<U256>,
+    /// Aggregator swap the contract runs on the seized collateral (None = its Uniswap V3 swap)
+    pub aggregator_swap: Option<AggregatorSwap>,
+}
+
+/// 1inch / 0x calldata converting seized collateral into the debt asset inside the liquidator
+/// contract, quoted with the contract as taker
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregatorSwap {
+    pub target: Address,
+    pub spender: Address,
+    pub calldata: Bytes,
+    pub min_amount_out: U256,
 }
 
 #[derive(Debug, Clone)]
@@ -149,6 +178,942 @@ pub struct GasEstimate {
     pub total_cost: U256,
 }
 
+/// Intent record written when a liquidation transaction is submitted
+#[derive(Debug, Clone)]
+pub struct LiquidationIntent {
+    pub tx_hash: String,
+    pub user: Address,
+    pub nonce: u64,
+    pub gas_price: U256,
+    pub gas_limit: U256,
+    pub estimated_profit: U256,
+}
+
+/// Settlement record written once a submitted liquidation is confirmed on chain
+#[derive(Debug, Clone)]
+pub struct LiquidationSettlement {
+    pub tx_hash: String,
+    pub block_number: Option<u64>,
+    pub tx_index: Option<u64>,
+    pub gas_used: U256,
+    pub realized_profit: U256,
+    pub succeeded: bool,
+}
+
+/// Receipt fields of a mined liquidation transaction, stored alongside its liquidation event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidationReceipt {
+    pub tx_hash: String,
+    pub block_number: Option<u64>,
+    pub tx_index: Option<u64>,
+    pub gas_used: U256,
+    pub effective_gas_price: U256,
+    pub succeeded: bool,
+}
+
+/// Collateral received as aTokens by one liquidation, held until it is unwound
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ATokenLot {
+    pub id: i64,
+    pub wallet: Address,
+    /// Underlying reserve the aTokens are for
+    pub asset: Address,
+    pub amount: U256,
+    pub tx_hash: String,
+    pub acquired_at_ms: i64,
+}
+
+/// Requested vs actually liquidated amounts for an executed liquidation. Aave may cover
+/// less debt than requested (e.g. when bounded by the user's collateral).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidationFill {
+    pub requested_debt: U256,
+    pub debt_covered: U256,
+    pub expected_collateral: U256,
+    pub collateral_received: U256,
+    /// Estimated profit scaled to the debt actually covered
+    pub realized_profit: U256,
+}
+
+impl LiquidationFill {
+    /// Whether the pool covered less debt than requested
+    pub fn is_partial(&self) -> bool {
+        self.debt_covered < self.requested_debt
+    }
+
+    /// Share of the requested debt that was covered, in basis points
+    pub fn fill_bps(&self) -> U256 {
+        if self.requested_debt.is_zero() {
+            return U256::from(10_000u64);
+        }
+        self.debt_covered.saturating_mul(U256::from(10_000u64)) / self.requested_debt
+    }
+}
+
+/// Model used to estimate swap slippage when pricing a liquidation opportunity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProfitabilityModel {
+    /// Flat 1% slippage on every cross-asset swap
+    FlatSlippage,
+    /// Slippage tiered by pair liquidity (stable/stable, major/stable, everything else)
+    TieredSlippage,
+    /// Fee and price impact quoted from live Uniswap V3 pool state against oracle prices
+    /// (tiered when the quote fails)
+    OnChainQuote,
+}
+
+impl ProfitabilityModel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProfitabilityModel::FlatSlippage => "flat",
+            ProfitabilityModel::TieredSlippage => "tiered",
+            ProfitabilityModel::OnChainQuote => "quoter",
+        }
+    }
+
+    /// Parse a model name as used in configuration ("flat", "tiered" or "quoter")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "flat" => Some(ProfitabilityModel::FlatSlippage),
+            "tiered" => Some(ProfitabilityModel::TieredSlippage),
+            "quoter" => Some(ProfitabilityModel::OnChainQuote),
+            _ => None,
+        }
+    }
+}
+
+/// Lending protocol a position belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProtocolKind {
+    AaveV3,
+    CompoundV3,
+}
+
+impl ProtocolKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProtocolKind::AaveV3 => "aave_v3",
+            ProtocolKind::CompoundV3 => "compound_v3",
+        }
+    }
+
+    /// Parse a protocol name as stored in the database ("aave_v3" or "compound_v3")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "aave_v3" => Some(ProtocolKind::AaveV3),
+            "compound_v3" => Some(ProtocolKind::CompoundV3),
+            _ => None,
+        }
+    }
+}
+
+/// Protocol-neutral health snapshot of one account on one protocol
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolPosition {
+    pub protocol: ProtocolKind,
+    pub address: Address,
+    /// Collateral value weighted by the protocol's liquidation threshold (protocol base units)
+    pub collateral_value: U256,
+    pub debt_value: U256,
+    /// Liquidation-weighted collateral over debt, 18 decimals (below 1.0 = liquidatable)
+    pub health_factor: U256,
+    pub is_liquidatable: bool,
+    pub last_updated: DateTime<Utc>,
+}
+
+/// Where and with what calldata a liquidation is sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProtocolLiquidationCall {
+    pub target: Address,
+    pub calldata: Vec<u8>,
+}
+
+/// Where signed liquidation transactions are sent for inclusion
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SubmissionStrategy {
+    /// Broadcast through the RPC node to the public mempool
+    Public,
+    /// Send only to a private relay (Flashbots Protect / MEV-Share style) as a bundle
+    PrivateRelay,
+    /// Broadcast publicly and send to the private relay
+    Both,
+}
+
+impl SubmissionStrategy {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SubmissionStrategy::Public => "public",
+            SubmissionStrategy::PrivateRelay => "private",
+            SubmissionStrategy::Both => "both",
+        }
+    }
+
+    /// Parse a strategy name as used in configuration ("public", "private" or "both")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "public" => Some(SubmissionStrategy::Public),
+            "private" | "private_relay" => Some(SubmissionStrategy::PrivateRelay),
+            "both" => Some(SubmissionStrategy::Both),
+            _ => None,
+        }
+    }
+}
+
+/// Uniswap V3 route converting seized collateral: `tokens[0]` is swapped through the pool
+/// with fee tier `fees[i]` between `tokens[i]` and `tokens[i + 1]`, ending in the last token
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapRoute {
+    pub tokens: Vec<Address>,
+    pub fees: Vec<u32>, // Hundredths of a basis point (500 = 0.05%)
+}
+
+impl SwapRoute {
+    pub fn token_in(&self) -> Address {
+        self.tokens[0]
+    }
+
+    pub fn token_out(&self) -> Address {
+        self.tokens[self.tokens.len() - 1]
+    }
+
+    /// Parse a route as used in configuration: `token>fee>token[>fee>token...]`
+    pub fn parse(route: &str) -> Option<Self> {
+        let parts: Vec<&str> = route.split('>').map(str::trim).collect();
+        if parts.len() < 3 || parts.len() % 2 == 0 {
+            return None;
+        }
+
+        let mut tokens = Vec::new();
+        let mut fees = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            if i % 2 == 0 {
+                tokens.push(part.parse::<Address>().ok()?);
+            } else {
+                // Fee tiers are uint24 on-chain
+                fees.push(part.parse::<u32>().ok().filter(|fee| *fee < 1 << 24)?);
+            }
+        }
+        Some(Self { tokens, fees })
+    }
+}
+
+/// Chat and paging channels alerts can be routed to
+pub const NOTIFICATION_CHANNELS: [&str; 4] = ["telegram", "discord", "slack", "pagerduty"];
+
+/// Overrides which channels receive one alert event kind (empty = none of them)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AlertRoute {
+    pub event: String,
+    pub channels: Vec<String>,
+}
+
+impl AlertRoute {
+    /// Parse a route as used in configuration: `event=channel[+channel...]` or `event=none`
+    pub fn parse(route: &str) -> Option<Self> {
+        let (event, channels) = route.split_once('=')?;
+        let event = event.trim().to_ascii_lowercase();
+        if event.is_empty() {
+            return None;
+        }
+        let channels = channels.trim().to_ascii_lowercase();
+        if channels == "none" {
+            return Some(Self {
+                event,
+                channels: Vec::new(),
+            });
+        }
+
+        let channels = channels
+            .split('+')
+            .map(|channel| channel.trim().to_string())
+            .map(|channel| NOTIFICATION_CHANNELS.contains(&channel.as_str()).then_some(channel))
+            .collect::<Option<Vec<_>>>()?;
+        (!channels.is_empty()).then_some(Self { event, channels })
+    }
+}
+
+/// Quantity a circuit breaker trigger rule compares against its threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerMetric {
+    /// Largest move (percent) of any asset's price against its first price in the window
+    Volatility,
+    /// Liquidation attempts (successful or failed) per minute over the window
+    LiquidationsPerMinute,
+    /// Latest gas price as a multiple of the baseline
+    GasMultiplier,
+    /// Liquidation attempts that failed in a row, counting back from the latest
+    ConsecutiveReverts,
+    /// Failed liquidation attempts in the window
+    FailedLiquidations,
+    /// Seconds since an asset's oracle price last changed
+    OracleStalenessSecs,
+}
+
+impl TriggerMetric {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriggerMetric::Volatility => "volatility",
+            TriggerMetric::LiquidationsPerMinute => "liquidations_per_minute",
+            TriggerMetric::GasMultiplier => "gas_multiplier",
+            TriggerMetric::ConsecutiveReverts => "consecutive_reverts",
+            TriggerMetric::FailedLiquidations => "failed_liquidations",
+            TriggerMetric::OracleStalenessSecs => "oracle_staleness_secs",
+        }
+    }
+
+    /// Parse a metric name as used in configuration (e.g. "consecutive_reverts")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "volatility" | "price_volatility" => Some(TriggerMetric::Volatility),
+            "liquidations_per_minute" => Some(TriggerMetric::LiquidationsPerMinute),
+            "gas_multiplier" => Some(TriggerMetric::GasMultiplier),
+            "consecutive_reverts" => Some(TriggerMetric::ConsecutiveReverts),
+            "failed_liquidations" => Some(TriggerMetric::FailedLiquidations),
+            "oracle_staleness_secs" | "oracle_staleness" => {
+                Some(TriggerMetric::OracleStalenessSecs)
+            }
+            _ => None,
+        }
+    }
+
+    /// Whether the metric is measured over a window of recent data
+    pub fn is_windowed(&self) -> bool {
+        matches!(
+            self,
+            TriggerMetric::Volatility
+                | TriggerMetric::LiquidationsPerMinute
+                | TriggerMetric::FailedLiquidations
+        )
+    }
+
+    /// Whether the metric is measured per asset, so a breach can pause just that asset
+    pub fn is_per_asset(&self) -> bool {
+        matches!(self, TriggerMetric::Volatility | TriggerMetric::OracleStalenessSecs)
+    }
+}
+
+/// What a circuit breaker trigger rule does when its metric exceeds the threshold
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TriggerAction {
+    /// Open the circuit breaker, suspending every liquidation
+    PauseAll,
+    /// Skip liquidations touching the offending asset until the cooldown passes
+    PauseAsset,
+    /// Notify operators without blocking anything
+    AlertOnly,
+}
+
+impl TriggerAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TriggerAction::PauseAll => "pause_all",
+            TriggerAction::PauseAsset => "pause_asset",
+            TriggerAction::AlertOnly => "alert_only",
+        }
+    }
+
+    /// Parse an action name as used in configuration ("pause-all", "pause_asset", ...)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+            "pause_all" => Some(TriggerAction::PauseAll),
+            "pause_asset" => Some(TriggerAction::PauseAsset),
+            "alert_only" | "alert" => Some(TriggerAction::AlertOnly),
+            _ => None,
+        }
+    }
+}
+
+/// Circuit breaker trigger: `action` fires when `metric` (over `window_secs`, or the
+/// monitoring window when None) exceeds `threshold`
+#[derive(Debug, Clone, PartialEq)]
+pub struct TriggerRule {
+    pub metric: TriggerMetric,
+    pub window_secs: Option<u64>,
+    pub threshold: f64,
+    pub action: TriggerAction,
+}
+
+impl TriggerRule {
+    /// Parse a rule as used in configuration: `metric[@window_secs]>threshold=action`,
+    /// e.g. `consecutive_reverts>5=pause_all` or `volatility@120>8=pause_asset`
+    pub fn parse(rule: &str) -> Option<Self> {
+        let (condition, action) = rule.split_once('=')?;
+        let action = TriggerAction::from_name(action)?;
+        let (metric, threshold) = condition.split_once('>')?;
+        let threshold = threshold.trim().parse::<f64>().ok()?;
+        if !threshold.is_finite() || threshold < 0.0 {
+            return None;
+        }
+
+        let (metric, window_secs) = match metric.split_once('@') {
+            Some((metric, window)) => {
+                let window = window.trim().trim_end_matches('s').parse::<u64>().ok();
+                (metric, Some(window.filter(|window| *window > 0)?))
+            }
+            None => (metric, None),
+        };
+        let metric = TriggerMetric::from_name(metric)?;
+        if window_secs.is_some() && !metric.is_windowed() {
+            return None;
+        }
+        if action == TriggerAction::PauseAsset && !metric.is_per_asset() {
+            return None;
+        }
+
+        Some(Self {
+            metric,
+            window_secs,
+            threshold,
+            action,
+        })
+    }
+
+    /// Canonical form of the rule, as accepted by `parse`
+    pub fn label(&self) -> String {
+        let window = self
+            .window_secs
+            .map(|window| format!("@{}", window))
+            .unwrap_or_default();
+        format!(
+            "{}{}>{}={}",
+            self.metric.as_str(),
+            window,
+            self.threshold,
+            self.action.as_str()
+        )
+    }
+}
+
+/// Second Chainlink-compatible feed (`latestRoundData`) an asset's price is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SecondaryPriceFeed {
+    pub asset: Address,
+    pub feed: Address,
+}
+
+impl SecondaryPriceFeed {
+    /// Parse a feed as used in configuration: `asset=feed`
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (asset, feed) = entry.split_once('=')?;
+        Some(Self {
+            asset: asset.trim().parse().ok()?,
+            feed: feed.trim().parse().ok()?,
+        })
+    }
+}
+
+/// How often the positions of a scan tier are re-checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TierInterval {
+    EveryBlock,
+    Every(Duration),
+}
+
+/// Positions with a health factor below `max_health_factor` that no lower tier covers are
+/// re-checked every `interval`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScanTier {
+    pub max_health_factor: U256, // 18 decimals
+    pub interval: TierInterval,
+}
+
+impl ScanTier {
+    /// Parse a tier as used in configuration: `health_factor:interval`, the interval in
+    /// seconds or `block`, e.g. `1.02:block` or `1.1:5`
+    pub fn parse(entry: &str) -> Option<Self> {
+        let (health_factor, interval) = entry.split_once(':')?;
+        let health_factor = health_factor.trim().parse::<f64>().ok()?;
+        if !health_factor.is_finite() || health_factor <= 0.0 {
+            return None;
+        }
+        let interval = match interval.trim() {
+            "block" => TierInterval::EveryBlock,
+            secs => TierInterval::Every(Duration::from_secs(secs.parse().ok()?)),
+        };
+        // Kept to 4 decimals, i.e. in basis points of 1.0
+        let bps = (health_factor * 10_000.0).round() as u64;
+        Some(Self {
+            max_health_factor: U256::from(bps) * U256::from(100_000_000_000_000u64),
+            interval,
+        })
+    }
+
+    /// Checked every block below 1.02, every 5s below 1.1 and every minute below 1.5
+    pub fn default_tiers() -> Vec<Self> {
+        ["1.02:block", "1.1:5", "1.5:60"]
+            .iter()
+            .filter_map(|entry| Self::parse(entry))
+            .collect()
+    }
+
+    /// Label for metrics, e.g. `hf<1.02`
+    pub fn label(&self) -> String {
+        let bps = self.max_health_factor / U256::from(100_000_000_000_000u64);
+        format!("hf<{}", bps.to::<u64>() as f64 / 10_000.0)
+    }
+}
+
+/// Swap aggregator API used to build collateral -> debt swaps
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AggregatorKind {
+    OneInch,
+    ZeroX,
+}
+
+impl AggregatorKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AggregatorKind::OneInch => "1inch",
+            AggregatorKind::ZeroX => "0x",
+        }
+    }
+
+    /// Parse an aggregator name as used in configuration ("1inch" or "0x")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "1inch" | "oneinch" => Some(AggregatorKind::OneInch),
+            "0x" | "zerox" => Some(AggregatorKind::ZeroX),
+            _ => None,
+        }
+    }
+
+    pub fn default_api_url(&self) -> &'static str {
+        match self {
+            AggregatorKind::OneInch => "https://api.1inch.dev",
+            AggregatorKind::ZeroX => "https://api.0x.org",
+        }
+    }
+}
+
+/// How much an executor wallet lets a swap router or aggregator spend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApprovalMode {
+    /// Approve exactly the amount about to be swapped
+    Exact,
+    /// Approve the maximum once per token and spender
+    Infinite,
+}
+
+impl ApprovalMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalMode::Exact => "exact",
+            ApprovalMode::Infinite => "infinite",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "exact" => Some(ApprovalMode::Exact),
+            "infinite" | "max" | "unlimited" => Some(ApprovalMode::Infinite),
+            _ => None,
+        }
+    }
+}
+
+/// Where liquidations are simulated before being broadcast
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SimulationMode {
+    Off,
+    /// `eth_call` + `eth_estimateGas` against the live node: catches reverts and prices gas
+    EthCall,
+    /// Execute on a local Anvil fork: also yields the exact collateral received
+    AnvilFork,
+}
+
+impl SimulationMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SimulationMode::Off => "off",
+            SimulationMode::EthCall => "eth_call",
+            SimulationMode::AnvilFork => "anvil",
+        }
+    }
+
+    /// Parse a mode name as used in configuration ("off", "eth_call" or "anvil")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(SimulationMode::Off),
+            "eth_call" | "call" => Some(SimulationMode::EthCall),
+            "anvil" => Some(SimulationMode::AnvilFork),
+            _ => None,
+        }
+    }
+}
+
+/// What the bot does with a liquidation that passed profitability and simulation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecutionMode {
+    /// Broadcast the liquidation transaction
+    Live,
+    /// Run the whole pipeline, including pre-flight simulation, but only record the
+    /// liquidation that would have been broadcast
+    DryRun,
+    /// Stop after profitability checks, as when no liquidator contract or signer is configured
+    Simulate,
+}
+
+impl ExecutionMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ExecutionMode::Live => "live",
+            ExecutionMode::DryRun => "dry_run",
+            ExecutionMode::Simulate => "simulate",
+        }
+    }
+
+    /// Parse a mode name as used in configuration ("live", "dry_run" or "simulate")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "live" => Some(ExecutionMode::Live),
+            "dry_run" | "dry-run" | "paper" => Some(ExecutionMode::DryRun),
+            "simulate" => Some(ExecutionMode::Simulate),
+            _ => None,
+        }
+    }
+}
+
+/// How the borrower set is bootstrapped on startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiscoveryMethod {
+    /// Scan recent Borrow/Supply/Repay/Withdraw logs of the pool
+    EventScan,
+    /// Page through every borrower, with reserve balances, from an Aave V3 subgraph
+    Subgraph,
+}
+
+impl DiscoveryMethod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiscoveryMethod::EventScan => "events",
+            DiscoveryMethod::Subgraph => "subgraph",
+        }
+    }
+
+    /// Parse a method name as used in configuration ("events" or "subgraph")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "events" | "event_scan" | "logs" => Some(DiscoveryMethod::EventScan),
+            "subgraph" => Some(DiscoveryMethod::Subgraph),
+            _ => None,
+        }
+    }
+}
+
+/// Pending transaction feed watched for competing liquidations
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MempoolSource {
+    Off,
+    /// Standard `newPendingTransactions` subscription with full transaction bodies
+    PendingTransactions,
+    /// Alchemy's `alchemy_pendingTransactions`, filtered to the pool by the node
+    Alchemy,
+}
+
+impl MempoolSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MempoolSource::Off => "off",
+            MempoolSource::PendingTransactions => "standard",
+            MempoolSource::Alchemy => "alchemy",
+        }
+    }
+
+    /// Parse a source name as used in configuration ("off", "standard" or "alchemy")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "off" | "none" => Some(MempoolSource::Off),
+            "standard" | "pending" => Some(MempoolSource::PendingTransactions),
+            "alchemy" => Some(MempoolSource::Alchemy),
+            _ => None,
+        }
+    }
+}
+
+/// Message bus processor events and execution results are mirrored to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BusKind {
+    Kafka,
+    Nats,
+    /// Redis Streams (XADD)
+    Redis,
+}
+
+impl BusKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BusKind::Kafka => "kafka",
+            BusKind::Nats => "nats",
+            BusKind::Redis => "redis",
+        }
+    }
+
+    /// The bus a URL points at, from its scheme (`kafka://`, `nats://`, `redis://` or `rediss://`)
+    pub fn from_url(url: &str) -> Option<Self> {
+        let (scheme, _) = url.trim().split_once("://")?;
+        match scheme.to_ascii_lowercase().as_str() {
+            "kafka" => Some(BusKind::Kafka),
+            "nats" | "tls" => Some(BusKind::Nats),
+            "redis" | "rediss" => Some(BusKind::Redis),
+            _ => None,
+        }
+    }
+}
+
+/// Another liquidator's pending `liquidationCall` seen in the mempool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompetingLiquidation {
+    pub tx_hash: String,
+    pub liquidator: Address,
+    pub user: Address,
+    pub collateral_asset: Address,
+    pub debt_asset: Address,
+    pub debt_to_cover: U256,
+    /// Tip the transaction bids (legacy gas price for pre-EIP-1559 transactions)
+    pub priority_fee_per_gas: u128,
+}
+
+/// Running tally for one competing liquidator, as stored in the competitors table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompetitorStats {
+    pub liquidator: Address,
+    /// Pending liquidations seen from this liquidator
+    pub sightings: u64,
+    /// Liquidations we were about to send when this liquidator was already racing
+    pub races: u64,
+    pub last_user: Address,
+    pub last_tx_hash: String,
+    pub last_priority_fee: u128,
+}
+
+/// How hard a liquidation competes on fees
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FeeProfile {
+    /// Low percentile tip: thin margins can't afford to overpay for inclusion
+    Conservative,
+    Normal,
+    /// High percentile tip and base fee headroom: large liquidations are worth winning
+    Aggressive,
+}
+
+impl FeeProfile {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FeeProfile::Conservative => "conservative",
+            FeeProfile::Normal => "normal",
+            FeeProfile::Aggressive => "aggressive",
+        }
+    }
+
+    /// Parse a profile name as used in configuration
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "conservative" => Some(FeeProfile::Conservative),
+            "normal" => Some(FeeProfile::Normal),
+            "aggressive" => Some(FeeProfile::Aggressive),
+            _ => None,
+        }
+    }
+}
+
+/// Lender the liquidator contract borrows the debt asset from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlashLoanSource {
+    AaveV3,
+    Balancer,
+    Morpho,
+}
+
+impl FlashLoanSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            FlashLoanSource::AaveV3 => "aave",
+            FlashLoanSource::Balancer => "balancer",
+            FlashLoanSource::Morpho => "morpho",
+        }
+    }
+
+    /// Parse a lender name as used in configuration ("aave", "balancer" or "morpho")
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "aave" | "aave_v3" | "aavev3" => Some(FlashLoanSource::AaveV3),
+            "balancer" => Some(FlashLoanSource::Balancer),
+            "morpho" => Some(FlashLoanSource::Morpho),
+            _ => None,
+        }
+    }
+
+    /// Lender id understood by the liquidator contract's `liquidateWithFlashLoan`
+    pub fn contract_id(&self) -> u8 {
+        match self {
+            FlashLoanSource::AaveV3 => 0,
+            FlashLoanSource::Balancer => 1,
+            FlashLoanSource::Morpho => 2,
+        }
+    }
+}
+
+/// Disagreement between the live and shadow profitability models for one liquidation pair
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfitDivergence {
+    pub user: Address,
+    pub collateral_asset: Address,
+    pub debt_asset: Address,
+    pub live_model: ProfitabilityModel,
+    pub shadow_model: ProfitabilityModel,
+    pub live_profit: U256,
+    pub shadow_profit: U256,
+    pub divergence_bps: u64,
+}
+
+/// Granularity of a stored oracle price row: every polled price, or one row per bucket
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum PriceResolution {
+    Raw,
+    Minute,
+    Hour,
+}
+
+impl PriceResolution {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            PriceResolution::Raw => "raw",
+            PriceResolution::Minute => "minute",
+            PriceResolution::Hour => "hour",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "raw" => Some(PriceResolution::Raw),
+            "minute" => Some(PriceResolution::Minute),
+            "hour" => Some(PriceResolution::Hour),
+            _ => None,
+        }
+    }
+
+    /// Bucket width in seconds (`None` for raw samples)
+    pub fn bucket_secs(&self) -> Option<u64> {
+        match self {
+            PriceResolution::Raw => None,
+            PriceResolution::Minute => Some(60),
+            PriceResolution::Hour => Some(3600),
+        }
+    }
+}
+
+/// One row of oracle price history. Raw samples have `min_price == max_price == price`;
+/// downsampled rows keep the bucket's closing price and its range.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PricePoint {
+    pub asset: Address,
+    pub timestamp: u64, // Unix seconds (bucket start for downsampled rows)
+    pub resolution: PriceResolution,
+    pub price: U256,
+    pub min_price: U256,
+    pub max_price: U256,
+    pub sample_count: u64,
+}
+
+/// One recorded health factor snapshot of a user's position
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PositionSnapshot {
+    pub address: Address,
+    pub timestamp: u64, // Unix seconds
+    pub health_factor: U256,
+    pub total_collateral_base: U256,
+    pub total_debt_base: U256,
+    pub is_at_risk: bool,
+}
+
+/// A tracked user's balances in one reserve, in the reserve's native units
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UserReserve {
+    pub reserve: Address,
+    pub collateral_balance: U256, // aToken balance
+    pub debt_balance: U256,       // Stable plus variable debt
+    pub usage_as_collateral_enabled: bool,
+}
+
+/// Aggregated liquidation PnL for a single strategy label
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrategyPnl {
+    pub strategy_label: String,
+    pub liquidation_count: u64,
+    pub total_profit: U256,
+}
+
+/// One recorded liquidation as shown in the liquidation history
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidationRecord {
+    pub user: Address,
+    pub collateral_asset: String,
+    pub debt_asset: String,
+    pub debt_covered: U256,
+    pub profit: U256, // Realized profit when the fill was decoded, estimated otherwise
+    pub tx_hash: Option<String>,
+    pub block_number: Option<u64>,
+    pub strategy_label: String,
+    pub succeeded: Option<bool>, // None until the receipt has been recorded
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Accounted P&L of one mined liquidation. Values are in the Aave base currency (USD,
+/// 8 decimals) at oracle prices.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PnlEntry {
+    pub tx_hash: String,
+    pub user: Address,
+    pub wallet: Address, // Executor wallet that sent the liquidation
+    pub collateral_asset: Address,
+    pub debt_asset: Address,
+    pub block_number: Option<u64>,
+    pub collateral_value: U256, // Seized collateral
+    pub debt_value: U256,       // Debt repaid on the user's behalf
+    pub flash_loan_fee: U256,
+    pub gas_cost: U256,
+    /// Change in the debt asset and operator ETH balances (profit and costs actually settled)
+    pub realized_pnl: I256,
+    /// Seized collateral still held unswapped, marked at the oracle price
+    pub unrealized_pnl: I256,
+    pub timestamp: u64, // Unix seconds
+}
+
+impl PnlEntry {
+    pub fn total_pnl(&self) -> I256 {
+        self.realized_pnl.saturating_add(self.unrealized_pnl)
+    }
+}
+
+/// P&L over a period (base currency, 8 decimals)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PnlSummary {
+    pub liquidations: u64,
+    pub realized_pnl: I256,
+    pub unrealized_pnl: I256,
+    pub flash_loan_fees: U256,
+    pub gas_costs: U256,
+    /// Running total over all liquidations up to the end of the period
+    pub cumulative_pnl: I256,
+}
+
+impl PnlSummary {
+    pub fn total_pnl(&self) -> I256 {
+        self.realized_pnl.saturating_add(self.unrealized_pnl)
+    }
+}
+
+/// When one liquidation opportunity passed each stage of the pipeline (unix milliseconds);
+/// stages it never reached are None
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LatencyTrace {
+    pub log_received_ms: Option<i64>, // None when found by a scan rather than a log
+    pub hf_confirmed_ms: Option<i64>,
+    pub profitability_done_ms: Option<i64>,
+    pub tx_sent_ms: Option<i64>,
+    pub tx_mined_ms: Option<i64>,
+}
+
 /// Result of a liquidation attempt to distinguish between executed vs not-needed liquidations
 #[derive(Debug, Clone)]
 pub enum LiquidationResult {
@@ -173,6 +1138,20 @@ pub enum NotNeededReason {
     NoProfitablePairs,
     /// Liquidation opportunity exists but doesn't meet profit threshold
     InsufficientProfit,
-    /// Liquidator contract or signer not configured (simulation mode)
+    /// Health factor is below 1.0 but still within the configured execution margin
+    WithinHealthFactorMargin,
+    /// Liquidator contract or signer not configured, or execution mode is simulate
     SimulationMode,
+    /// Opportunity forwarded to the external executor webhook instead of executed locally
+    ForwardedToWebhook,
+    /// Pre-flight simulation reverted or left less profit than the threshold after gas
+    SimulationRejected,
+    /// Another liquidator's pending transaction for the user can't be outbid profitably
+    CompetitorRacing,
+    /// Execution mode is dry_run: the liquidation was recorded instead of broadcast
+    DryRun,
+    /// Every pair touches an asset a circuit breaker rule has paused
+    AssetPaused,
+    /// Isolation mode or siloed borrowing leaves no collateral/debt pair to liquidate
+    ProtocolConstraints,
 }
@@ -0,0 +1,99 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::sol;
+use eyre::Result;
+use std::collections::HashMap;
+
+use crate::networks::NetworkAddresses;
+use crate::protocols::call_contract;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IAaveOracle {
+        function getAssetPrice(address asset) external view returns (uint256);
+        function getAssetsPrices(address[] calldata assets) external view returns (uint256[] memory);
+    }
+}
+
+/// Client for the Aave oracle, the price source the pool computes health factors and
+/// liquidation amounts with (base currency, 8 decimals). Chainlink feeds only signal when
+/// to re-read it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AaveOracle {
+    address: Address,
+}
+
+impl AaveOracle {
+    pub fn new(address: Address) -> Self {
+        Self { address }
+    }
+
+    pub fn address(&self) -> Address {
+        self.address
+    }
+
+    pub async fn get_asset_price<P>(&self, provider: &P, asset: Address) -> Result<U256>
+    where
+        P: Provider,
+    {
+        Ok(call_contract(provider, self.address, &IAaveOracle::getAssetPriceCall { asset })
+            .await?
+            ._0)
+    }
+
+    /// Prices of `assets` in order, read in a single call
+    pub async fn get_assets_prices<P>(&self, provider: &P, assets: &[Address]) -> Result<Vec<U256>>
+    where
+        P: Provider,
+    {
+        let prices = call_contract(
+            provider,
+            self.address,
+            &IAaveOracle::getAssetsPricesCall {
+                assets: assets.to_vec(),
+            },
+        )
+        .await?
+        ._0;
+        if prices.len() != assets.len() {
+            return Err(eyre::eyre!(
+                "Oracle returned {} prices for {} assets",
+                prices.len(),
+                assets.len()
+            ));
+        }
+        Ok(prices)
+    }
+
+    /// Prices of `assets` keyed by asset, leaving out assets the oracle prices at zero
+    pub async fn get_price_map<P>(
+        &self,
+        provider: &P,
+        assets: &[Address],
+    ) -> Result<HashMap<Address, U256>>
+    where
+        P: Provider,
+    {
+        let prices = self.get_assets_prices(provider, assets).await?;
+        Ok(assets
+            .iter()
+            .copied()
+            .zip(prices)
+            .filter(|(_, price)| !price.is_zero())
+            .collect())
+    }
+}
+
+/// Current Aave oracle prices (base currency, 8 decimals) of `assets`, in order
+pub async fn fetch_oracle_prices<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    assets: &[Address],
+) -> Result<Vec<U256>>
+where
+    P: Provider,
+{
+    AaveOracle::new(network.oracle)
+        .get_assets_prices(provider, assets)
+        .await
+}
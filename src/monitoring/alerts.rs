@@ -0,0 +1,389 @@
+use alloy_primitives::{Address, U256};
+use eyre::Result;
+use serde_json::{json, Value};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::config::BotConfig;
+use crate::monitoring::operator_balance::BalanceAlertLevel;
+use crate::notifications::Notifications;
+
+/// Consecutive failed liquidations that raise a repeated-failure alert
+pub const REPEATED_FAILURE_ALERT_THRESHOLD: u64 = 3;
+
+/// How urgently an alert needs a human
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl AlertSeverity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AlertSeverity::Info => "info",
+            AlertSeverity::Warning => "warning",
+            AlertSeverity::Critical => "critical",
+        }
+    }
+}
+
+/// Operational events worth notifying an operator about
+#[derive(Debug, Clone, PartialEq)]
+pub enum AlertEvent {
+    LiquidatableUserDetected { user: Address, health_factor: Option<U256> },
+    LiquidationSucceeded { user: Address, tx_hash: String, profit: U256 },
+    RepeatedLiquidationFailures { consecutive_failures: u64, last_error: String },
+    CircuitBreakerOpened { message: String },
+    CircuitBreakerRecovered { message: String },
+    CircuitBreakerHalfOpen { message: String },
+    CircuitBreakerDisabled { message: String },
+    CircuitBreakerRuleTriggered { message: String },
+    ConnectionLost { endpoint: String, error: String },
+    ConnectionRestored { endpoint: String },
+    DatabaseUnreachable { error: String },
+    DatabaseRecovered,
+    OracleUnhealthy { symbol: String, reason: String },
+    OracleRecovered,
+    OperatorBalanceChanged { level: BalanceAlertLevel, min_balance: U256 },
+    TokenBalanceLow { wallet: Address, symbol: String, balance: U256, min_balance: U256 },
+    TokenBalanceRecovered { wallet: Address, symbol: String },
+    OptimisticProfitEstimates { consecutive_shortfalls: u64, realized_bps: u64 },
+    DailyPnlReport { report: String },
+    TreasurySweep { wallet: Address, symbol: String, amount: U256, cold_wallet: Address, tx_hash: String },
+}
+
+impl AlertEvent {
+    /// Stable event name used in webhook payloads
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AlertEvent::LiquidatableUserDetected { .. } => "liquidatable_user_detected",
+            AlertEvent::LiquidationSucceeded { .. } => "liquidation_succeeded",
+            AlertEvent::RepeatedLiquidationFailures { .. } => "repeated_liquidation_failures",
+            AlertEvent::CircuitBreakerOpened { .. } => "circuit_breaker_opened",
+            AlertEvent::CircuitBreakerRecovered { .. } => "circuit_breaker_recovered",
+            AlertEvent::CircuitBreakerHalfOpen { .. } => "circuit_breaker_half_open",
+            AlertEvent::CircuitBreakerDisabled { .. } => "circuit_breaker_disabled",
+            AlertEvent::CircuitBreakerRuleTriggered { .. } => "circuit_breaker_rule_triggered",
+            AlertEvent::ConnectionLost { .. } => "connection_lost",
+            AlertEvent::ConnectionRestored { .. } => "connection_restored",
+            AlertEvent::DatabaseUnreachable { .. } => "database_unreachable",
+            AlertEvent::DatabaseRecovered => "database_recovered",
+            AlertEvent::OracleUnhealthy { .. } => "oracle_unhealthy",
+            AlertEvent::OracleRecovered => "oracle_recovered",
+            AlertEvent::OperatorBalanceChanged { .. } => "operator_balance_changed",
+            AlertEvent::TokenBalanceLow { .. } => "token_balance_low",
+            AlertEvent::TokenBalanceRecovered { .. } => "token_balance_recovered",
+            AlertEvent::OptimisticProfitEstimates { .. } => "optimistic_profit_estimates",
+            AlertEvent::DailyPnlReport { .. } => "daily_pnl_report",
+            AlertEvent::TreasurySweep { .. } => "treasury_sweep",
+        }
+    }
+
+    pub fn severity(&self) -> AlertSeverity {
+        match self {
+            AlertEvent::LiquidatableUserDetected { .. }
+            | AlertEvent::LiquidationSucceeded { .. }
+            | AlertEvent::CircuitBreakerRecovered { .. }
+            | AlertEvent::CircuitBreakerHalfOpen { .. }
+            | AlertEvent::ConnectionRestored { .. }
+            | AlertEvent::DatabaseRecovered
+            | AlertEvent::OracleRecovered
+            | AlertEvent::TokenBalanceRecovered { .. }
+            | AlertEvent::DailyPnlReport { .. }
+            | AlertEvent::TreasurySweep { .. } => AlertSeverity::Info,
+            AlertEvent::RepeatedLiquidationFailures { .. }
+            | AlertEvent::CircuitBreakerOpened { .. }
+            | AlertEvent::ConnectionLost { .. }
+            | AlertEvent::DatabaseUnreachable { .. }
+            | AlertEvent::OracleUnhealthy { .. } => AlertSeverity::Critical,
+            AlertEvent::OptimisticProfitEstimates { .. }
+            | AlertEvent::CircuitBreakerDisabled { .. }
+            | AlertEvent::CircuitBreakerRuleTriggered { .. }
+            | AlertEvent::TokenBalanceLow { .. } => AlertSeverity::Warning,
+            AlertEvent::OperatorBalanceChanged { level, .. } => match level {
+                BalanceAlertLevel::Healthy => AlertSeverity::Info,
+                BalanceAlertLevel::Low => AlertSeverity::Warning,
+                BalanceAlertLevel::Critical | BalanceAlertLevel::BelowMinimum => {
+                    AlertSeverity::Critical
+                }
+            },
+        }
+    }
+
+    pub fn message(&self) -> String {
+        match self {
+            AlertEvent::LiquidatableUserDetected {
+                user,
+                health_factor,
+            } => match health_factor {
+                Some(hf) => format!(
+                    "{} is liquidatable (health factor {:.4})",
+                    user,
+                    f64::from(*hf) / 1e18
+                ),
+                None => format!("{} is liquidatable", user),
+            },
+            AlertEvent::LiquidationSucceeded {
+                user,
+                tx_hash,
+                profit,
+            } => format!("Liquidated {} in {} for {} wei profit", user, tx_hash, profit),
+            AlertEvent::RepeatedLiquidationFailures {
+                consecutive_failures,
+                last_error,
+            } => format!(
+                "{} consecutive liquidations failed, last error: {}",
+                consecutive_failures, last_error
+            ),
+            AlertEvent::CircuitBreakerOpened { message }
+            | AlertEvent::CircuitBreakerRecovered { message }
+            | AlertEvent::CircuitBreakerHalfOpen { message }
+            | AlertEvent::CircuitBreakerDisabled { message }
+            | AlertEvent::CircuitBreakerRuleTriggered { message } => message.clone(),
+            AlertEvent::ConnectionLost { endpoint, error } => {
+                format!("{} connection lost and not yet restored: {}", endpoint, error)
+            }
+            AlertEvent::ConnectionRestored { endpoint } => {
+                format!("{} connection restored", endpoint)
+            }
+            AlertEvent::DatabaseUnreachable { error } => format!("Database unreachable: {}", error),
+            AlertEvent::DatabaseRecovered => "Database reachable again".to_string(),
+            AlertEvent::OracleUnhealthy { symbol, reason } => {
+                format!("{} price feed unsafe, liquidations suspended: {}", symbol, reason)
+            }
+            AlertEvent::OracleRecovered => {
+                "Price feeds healthy again, liquidations resumed".to_string()
+            }
+            AlertEvent::OperatorBalanceChanged { level, min_balance } => {
+                format!("Operator balance now {:?} (minimum {} wei)", level, min_balance)
+            }
+            AlertEvent::TokenBalanceLow {
+                wallet,
+                symbol,
+                balance,
+                min_balance,
+            } => format!(
+                "Wallet {} holds {} {} base units, below the {} minimum - top up",
+                wallet, balance, symbol, min_balance
+            ),
+            AlertEvent::TokenBalanceRecovered { wallet, symbol } => {
+                format!("Wallet {} {} balance back above its minimum", wallet, symbol)
+            }
+            AlertEvent::OptimisticProfitEstimates {
+                consecutive_shortfalls,
+                realized_bps,
+            } => format!(
+                "{} consecutive liquidations realized below the profit estimate (latest {} bps)",
+                consecutive_shortfalls, realized_bps
+            ),
+            AlertEvent::DailyPnlReport { report } => report.clone(),
+            AlertEvent::TreasurySweep {
+                wallet,
+                symbol,
+                amount,
+                cold_wallet,
+                tx_hash,
+            } => format!(
+                "Swept {} {} base units from {} to cold wallet {} in {}",
+                amount, symbol, wallet, cold_wallet, tx_hash
+            ),
+        }
+    }
+}
+
+/// JSON body posted for an alert
+pub fn alert_payload(event: &AlertEvent) -> Value {
+    json!({
+        "event": event.kind(),
+        "severity": event.severity().as_str(),
+        "message": event.message(),
+    })
+}
+
+/// Posts alerts to a webhook chosen by severity: info-level events to the info webhook,
+/// warnings and critical events to the critical webhook (e.g. a pager). Every alert is also
+/// pushed to the configured chat channels (Telegram, Discord).
+#[derive(Clone)]
+pub struct AlertNotifier {
+    info_webhook_url: Option<String>,
+    critical_webhook_url: Option<String>,
+    notifications: Notifications,
+    client: reqwest::Client,
+}
+
+impl AlertNotifier {
+    pub fn new(info_webhook_url: Option<String>, critical_webhook_url: Option<String>) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            info_webhook_url,
+            critical_webhook_url,
+            notifications: Notifications::new(),
+            client,
+        })
+    }
+
+    pub fn from_config(config: &BotConfig) -> Result<Self> {
+        let notifier = Self::new(
+            config.info_webhook_url.clone(),
+            config.critical_webhook_url.clone(),
+        )?;
+        let notifications = Notifications::from_config(config, &notifier.client);
+        Ok(notifier.with_notifications(notifications))
+    }
+
+    /// Also push every alert to these chat channels
+    pub fn with_notifications(mut self, notifications: Notifications) -> Self {
+        self.notifications = notifications;
+        self
+    }
+
+    /// Webhook an alert of `severity` is routed to, if one is configured
+    pub fn webhook_for(&self, severity: AlertSeverity) -> Option<&str> {
+        match severity {
+            AlertSeverity::Info => self.info_webhook_url.as_deref(),
+            AlertSeverity::Warning | AlertSeverity::Critical => self.critical_webhook_url.as_deref(),
+        }
+    }
+
+    /// Whether the alert goes anywhere at all
+    fn has_destination(&self, event: &AlertEvent) -> bool {
+        self.webhook_for(event.severity()).is_some() || self.notifications.has_destination(event)
+    }
+
+    /// POST the alert to its severity's webhook (if configured) and push it to every chat
+    /// channel
+    pub async fn notify(&self, event: &AlertEvent) -> Result<()> {
+        let webhook_result = match self.webhook_for(event.severity()) {
+            Some(url) => self.post_webhook(url, event).await,
+            None => Ok(()),
+        };
+        let channels_result = self.notifications.send(event).await;
+        webhook_result.and(channels_result)
+    }
+
+    async fn post_webhook(&self, url: &str, event: &AlertEvent) -> Result<()> {
+        let response = self
+            .client
+            .post(url)
+            .json(&alert_payload(event))
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            return Err(eyre::eyre!(
+                "Alert webhook returned status {}",
+                response.status()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Send the alert in the background so notification latency never delays the caller
+    pub fn spawn_notify(&self, event: AlertEvent) {
+        if !self.has_destination(&event) {
+            return;
+        }
+
+        let notifier = self.clone();
+        tokio::spawn(async move {
+            if let Err(e) = notifier.notify(&event).await {
+                warn!("Failed to send {} alert: {}", event.kind(), e);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+
+    #[tokio::test]
+    async fn test_alerts_route_to_webhook_by_severity() {
+        let server = MockServer::spawn(|_| MockResponse::ok()).await;
+        let notifier =
+            AlertNotifier::new(Some(server.url("/info")), Some(server.url("/critical"))).unwrap();
+
+        let events = vec![
+            (
+                AlertEvent::LiquidationSucceeded {
+                    user: Address::repeat_byte(0x01),
+                    tx_hash: "0xabc".to_string(),
+                    profit: U256::from(5_000u64),
+                },
+                "/info",
+            ),
+            (
+                AlertEvent::CircuitBreakerRecovered {
+                    message: "Circuit breaker CLOSED".to_string(),
+                },
+                "/info",
+            ),
+            (
+                AlertEvent::CircuitBreakerOpened {
+                    message: "Circuit breaker OPEN".to_string(),
+                },
+                "/critical",
+            ),
+            (
+                AlertEvent::RepeatedLiquidationFailures {
+                    consecutive_failures: REPEATED_FAILURE_ALERT_THRESHOLD,
+                    last_error: "reverted".to_string(),
+                },
+                "/critical",
+            ),
+            (
+                AlertEvent::OperatorBalanceChanged {
+                    level: BalanceAlertLevel::Low,
+                    min_balance: U256::from(10u64),
+                },
+                "/critical",
+            ),
+            (
+                AlertEvent::OperatorBalanceChanged {
+                    level: BalanceAlertLevel::Healthy,
+                    min_balance: U256::from(10u64),
+                },
+                "/info",
+            ),
+        ];
+
+        for (event, _) in &events {
+            notifier.notify(event).await.unwrap();
+        }
+
+        let requests = server.requests();
+        assert_eq!(requests.len(), events.len());
+        for ((event, expected_path), request) in events.iter().zip(&requests) {
+            assert_eq!(
+                request.path, *expected_path,
+                "{} routed to the wrong webhook",
+                event.kind()
+            );
+            assert_eq!(request.json(), alert_payload(event));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_unconfigured_severity_is_not_sent() {
+        let notifier = AlertNotifier::new(None, Some("http://127.0.0.1:1/critical".to_string()))
+            .unwrap();
+
+        // No info webhook: nothing is posted, so the unreachable critical URL is never hit
+        notifier
+            .notify(&AlertEvent::CircuitBreakerRecovered {
+                message: "recovered".to_string(),
+            })
+            .await
+            .unwrap();
+        assert!(notifier.webhook_for(AlertSeverity::Info).is_none());
+        assert_eq!(
+            notifier.webhook_for(AlertSeverity::Warning),
+            Some("http://127.0.0.1:1/critical")
+        );
+    }
+}
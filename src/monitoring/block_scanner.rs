@@ -0,0 +1,273 @@
+use alloy_primitives::{Address, B256, U256, U64};
+use alloy_provider::Provider;
+use dashmap::DashMap;
+use eyre::Result;
+use futures::{FutureExt, StreamExt};
+use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::config::SharedConfig;
+use crate::database::PositionWriter;
+use crate::events::EventSender;
+use crate::models::UserPosition;
+use crate::monitoring::metrics;
+use crate::monitoring::provider_set::{ProviderSet, ReconnectBackoff};
+use crate::monitoring::scan_tiers::{check_positions, TierSchedule};
+use crate::monitoring::scanner::PersistedPositions;
+
+/// Set while a newHeads subscription drives the at-risk scan; the timer-based scan stands
+/// down meanwhile and takes over again if the subscription drops
+static BLOCK_SCAN_ACTIVE: AtomicBool = AtomicBool::new(false);
+
+pub fn is_block_scan_active() -> bool {
+    BLOCK_SCAN_ACTIVE.load(Ordering::Relaxed)
+}
+
+/// Clears the active flag however the subscription ends
+struct ActiveGuard;
+
+impl ActiveGuard {
+    fn set() -> Self {
+        BLOCK_SCAN_ACTIVE.store(true, Ordering::Relaxed);
+        ActiveGuard
+    }
+}
+
+impl Drop for ActiveGuard {
+    fn drop(&mut self) {
+        BLOCK_SCAN_ACTIVE.store(false, Ordering::Relaxed);
+    }
+}
+
+/// The part of a newHeads notification the scan and reorg detection need
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct NewHead {
+    pub number: U64,
+    pub hash: B256,
+    pub parent_hash: B256,
+}
+
+/// Whether `block` is one the scan runs on when scanning every `every_n_blocks` blocks
+pub fn is_scan_block(block: u64, every_n_blocks: u64) -> bool {
+    every_n_blocks <= 1 || block % every_n_blocks == 0
+}
+
+/// Up to `limit` tracked users with debt and a health factor below `below`, lowest first
+pub fn riskiest_users(
+    positions: &DashMap<Address, UserPosition>,
+    limit: usize,
+    below: U256,
+) -> Vec<Address> {
+    let mut candidates: Vec<_> = positions
+        .iter()
+        .filter(|entry| !entry.total_debt_base.is_zero() && entry.health_factor < below)
+        .map(|entry| (entry.health_factor, *entry.key()))
+        .collect();
+    if candidates.len() > limit {
+        candidates.select_nth_unstable(limit);
+        candidates.truncate(limit);
+    }
+    candidates.sort_unstable();
+    candidates.into_iter().map(|(_, user)| user).collect()
+}
+
+/// Re-check the riskiest positions on every new block, so detection latency is bounded by
+/// block time rather than the scan timer. With scan tiers only the block tiers are checked.
+/// Each block checks at most BLOCK_SCAN_TOP_N users within BLOCK_SCAN_BUDGET_MS, keeping RPC
+/// usage per block predictable.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_block_scanner<P>(
+    providers: ProviderSet<P>,
+    pool_address: Address,
+    live_config: SharedConfig,
+    schedule: Arc<TierSchedule>,
+    user_positions: Arc<DashMap<Address, UserPosition>>,
+    persisted_positions: Arc<PersistedPositions>,
+    event_tx: EventSender,
+    priority_liquidation_tx: Option<mpsc::Sender<Address>>,
+    position_writer: PositionWriter,
+) -> Result<()>
+where
+    P: Provider + 'static,
+{
+    if !live_config.load().block_scan_enabled {
+        debug!("Block-driven scanning disabled - at-risk users are scanned on a timer");
+        return Ok(());
+    }
+    let Some(mut ws_provider) = providers.real_time() else {
+        warn!("Block-driven scanning needs a WebSocket provider - configure WS_URL; scanning at-risk users on a timer instead");
+        return Ok(());
+    };
+
+    let mut backoff = ReconnectBackoff::default();
+    loop {
+        match scan_new_heads(
+            &ws_provider,
+            providers.call(),
+            pool_address,
+            &live_config,
+            &schedule,
+            &user_positions,
+            &persisted_positions,
+            &event_tx,
+            &priority_liquidation_tx,
+            &position_writer,
+        )
+        .await
+        {
+            Ok(()) => {
+                warn!("🔌 New block stream ended - reconnecting");
+                backoff.reset();
+            }
+            Err(e) => error!("❌ New block subscription failed: {}", e),
+        }
+        ws_provider = providers
+            .wait_for_real_time_reconnect(&ws_provider, &mut backoff)
+            .await;
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn scan_new_heads<P>(
+    ws_provider: &Arc<dyn Provider>,
+    provider: &Arc<P>,
+    pool_address: Address,
+    live_config: &SharedConfig,
+    schedule: &TierSchedule,
+    user_positions: &Arc<DashMap<Address, UserPosition>>,
+    persisted_positions: &PersistedPositions,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    position_writer: &PositionWriter,
+) -> Result<()>
+where
+    P: Provider,
+{
+    let root = ws_provider.root();
+    let id: B256 = root
+        .client()
+        .request("eth_subscribe", ("newHeads",))
+        .await?;
+    let mut heads = root.get_subscription::<NewHead>(id).await?.into_stream();
+
+    let _active = ActiveGuard::set();
+    let config = live_config.load();
+    // Tiers hand the block scan only the positions in block tiers
+    let below = if schedule.is_enabled() {
+        schedule.block_tier_ceiling()
+    } else {
+        U256::MAX
+    };
+    info!(
+        "🧱 Scanning the {} riskiest positions every {} block(s) ({}ms budget per block)",
+        config.block_scan_top_n, config.block_scan_every_n_blocks, config.block_scan_budget_ms
+    );
+
+    while let Some(mut head) = heads.next().await {
+        // Heads that arrived while the last scan ran are already stale; only the newest counts
+        while let Some(Some(newer)) = heads.next().now_or_never() {
+            head = newer;
+        }
+
+        let config = live_config.load_full();
+        let block = head.number.to::<u64>();
+        if !is_scan_block(block, config.block_scan_every_n_blocks) {
+            continue;
+        }
+
+        let users = riskiest_users(user_positions, config.block_scan_top_n, below);
+        if users.is_empty() {
+            continue;
+        }
+        let budget = Duration::from_millis(config.block_scan_budget_ms);
+        let total = users.len();
+        let outcome = check_positions(
+            provider,
+            pool_address,
+            users,
+            config.health_check_parallelism,
+            Some(tokio::time::Instant::now() + budget),
+            config.min_hf_change_to_persist_bps,
+            user_positions,
+            persisted_positions,
+            schedule,
+            event_tx,
+            priority_liquidation_tx,
+            position_writer,
+        )
+        .await;
+
+        if outcome.over_budget {
+            metrics::record_block_scan_budget_exhausted();
+            warn!(
+                "⏳ Block {} scan hit its {:?} budget after {}/{} users",
+                block, budget, outcome.checked, total
+            );
+        }
+        metrics::record_block_scan();
+        debug!(
+            "🧱 Block {} ({}): {}/{} positions checked, {} liquidatable",
+            block, head.hash, outcome.checked, total, outcome.liquidatable
+        );
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(byte: u8, health_factor: u64, debt: u64) -> UserPosition {
+        UserPosition {
+            address: Address::repeat_byte(byte),
+            total_collateral_base: U256::from(10_000u64),
+            total_debt_base: U256::from(debt),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_500u64),
+            ltv: U256::from(8_000u64),
+            health_factor: U256::from(health_factor),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: false,
+        }
+    }
+
+    #[test]
+    fn test_riskiest_users_lowest_health_factor_first() {
+        let positions = DashMap::new();
+        for position in [
+            position(0x01, 1_300, 10),
+            position(0x02, 900, 10),
+            position(0x03, 1_050, 10),
+            position(0x04, 500, 0), // No debt: nothing to liquidate
+            position(0x05, 1_200, 10),
+        ] {
+            positions.insert(position.address, position);
+        }
+
+        assert_eq!(
+            riskiest_users(&positions, 3, U256::MAX),
+            vec![
+                Address::repeat_byte(0x02),
+                Address::repeat_byte(0x03),
+                Address::repeat_byte(0x05),
+            ]
+        );
+        assert_eq!(riskiest_users(&positions, 10, U256::MAX).len(), 4);
+        assert_eq!(
+            riskiest_users(&positions, 10, U256::from(1_100)),
+            vec![Address::repeat_byte(0x02), Address::repeat_byte(0x03)]
+        );
+    }
+
+    #[test]
+    fn test_scan_blocks() {
+        assert!(is_scan_block(7, 1));
+        assert!(is_scan_block(10, 5));
+        assert!(!is_scan_block(11, 5));
+    }
+}
@@ -0,0 +1,152 @@
+use eyre::Result;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, error, info, warn};
+
+use crate::config::{BotConfig, SharedConfig};
+
+/// Load `path` and apply its hot-reloadable settings to the live configuration. A file with
+/// any problem is rejected whole, so a typo never half-applies; changes to settings only read
+/// at startup, asset lists included, are named in a warning and wait for a restart. Returns
+/// the changed settings.
+pub fn reload_config(path: &Path, live_config: &SharedConfig) -> Result<Vec<&'static str>> {
+    let live = live_config.load();
+    let new_config = BotConfig::load_chains(Some(path))?
+        .into_iter()
+        .find(|config| config.chain_id == live.chain_id)
+        .ok_or_else(|| {
+            eyre::eyre!("{} no longer configures chain {}", path.display(), live.chain_id)
+        })?;
+
+    let mut updated = BotConfig::clone(&live);
+    let changed = updated.apply_reloadable(&new_config);
+
+    let restart_only = live.restart_only_changes(&new_config);
+    if !restart_only.is_empty() {
+        warn!(
+            "⚠️ {} changes {}, which can't be hot-reloaded; they take effect after a restart",
+            path.display(),
+            restart_only.join(", ")
+        );
+    }
+
+    if !changed.is_empty() {
+        live_config.store(std::sync::Arc::new(updated));
+    }
+    Ok(changed)
+}
+
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Poll the config file and hot-reload it whenever its modification time changes
+pub async fn run_config_watcher(
+    path: PathBuf,
+    live_config: SharedConfig,
+    interval_secs: u64,
+) -> Result<()> {
+    info!(
+        "👀 Watching {} for configuration changes every {}s",
+        path.display(),
+        interval_secs
+    );
+
+    let mut last_modified = modified_time(&path);
+    let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+    interval.tick().await; // The first tick completes immediately
+
+    loop {
+        interval.tick().await;
+
+        let modified = modified_time(&path);
+        if modified == last_modified {
+            continue;
+        }
+        last_modified = modified;
+
+        match reload_config(&path, &live_config) {
+            Ok(changed) if changed.is_empty() => {
+                debug!("{} changed but no reloadable settings differ", path.display())
+            }
+            Ok(changed) => info!("🔄 Reloaded configuration: {}", changed.join(", ")),
+            Err(e) => error!(
+                "❌ Rejected configuration reload from {}: {}",
+                path.display(),
+                e
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config;
+    use alloy_primitives::U256;
+
+    const BASE: &str = r#"
+rpc_url = "http://file-node:8545"
+private_key = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d"
+"#;
+
+    #[test]
+    fn test_reload_applies_thresholds_and_rejects_invalid_files() {
+        let path = std::env::temp_dir().join(format!("config-reload-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, format!("{}min_profit_threshold = \"1000\"\n", BASE)).unwrap();
+        let live_config = config::shared(BotConfig::load_chains(Some(&path)).unwrap().remove(0));
+
+        std::fs::write(
+            &path,
+            format!(
+                "{}min_profit_threshold = \"2000\"\nmax_liquidations_per_minute = 3\nrpc_url_typo = 1\n",
+                BASE
+            ),
+        )
+        .unwrap();
+        assert!(reload_config(&path, &live_config).is_err());
+        assert_eq!(live_config.load().min_profit_threshold, U256::from(1000u64));
+
+        std::fs::write(
+            &path,
+            format!(
+                "rpc_url = \"http://other-node:8545\"\n{}min_profit_threshold = \"2000\"\nmax_liquidations_per_minute = 3\n",
+                BASE.replace("rpc_url = \"http://file-node:8545\"\n", "")
+            ),
+        )
+        .unwrap();
+        let changed = reload_config(&path, &live_config).unwrap();
+
+        assert_eq!(changed, vec!["min_profit_threshold", "max_liquidations_per_minute"]);
+        let reloaded = BotConfig::load_chains(Some(&path)).unwrap().remove(0);
+        assert_eq!(live_config.load().restart_only_changes(&reloaded), vec!["rpc_url"]);
+        let live = live_config.load();
+        assert_eq!(live.min_profit_threshold, U256::from(2000u64));
+        assert_eq!(live.max_liquidations_per_minute, 3);
+        // Endpoints need a restart
+        assert_eq!(live.rpc_url, "http://file-node:8545");
+
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_asset_lists_and_gas_floor_wait_for_a_restart() {
+        let path = std::env::temp_dir().join(format!("config-reload-{}.toml", uuid::Uuid::new_v4()));
+        std::fs::write(&path, BASE).unwrap();
+        let mut live = BotConfig::load_chains(Some(&path)).unwrap().remove(0);
+        std::fs::remove_file(&path).ok();
+
+        let mut new = live.clone();
+        new.asset_loading_method = config::AssetLoadingMethod::Hardcoded;
+        new.min_token_balances.insert("USDC".to_string(), U256::from(1u64));
+        new.min_gas_price_multiplier += 1;
+
+        assert!(live.apply_reloadable(&new).is_empty());
+        assert_eq!(
+            live.restart_only_changes(&new),
+            vec!["asset_loading_method", "min_token_balances", "min_gas_price_multiplier"]
+        );
+    }
+}
@@ -4,25 +4,174 @@ use alloy_rpc_types::{BlockNumberOrTag, Filter};
 use alloy_sol_types::SolEvent;
 use eyre::Result;
 use crate::database::DatabasePool;
-use std::collections::HashSet;
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
-use crate::events::BotEvent;
-use crate::models::{Borrow, Repay, Supply, Withdraw};
+use crate::events::{BotEvent, EventSender};
+use crate::models::{Borrow, DiscoveryMethod, Repay, Supply, Withdraw};
+use crate::monitoring::health_engine::{HealthFactorEngine, ReserveBalance};
 use crate::monitoring::scanner;
 
 const BLOCKS_TO_SCAN: u64 = 50000; // Scan last ~50k blocks (~7 days on Base)
 const MAX_USERS_TO_PROCESS: usize = 1000; // Limit initial discovery to prevent overwhelming
+const SUBGRAPH_PAGE_SIZE: usize = 1000; // The Graph caps `first` at 1000
+
+/// Borrowers with at least one borrowed reserve, paged by id
+const BORROWERS_QUERY: &str = r#"
+query Borrowers($lastId: String!, $first: Int!) {
+  users(
+    first: $first
+    orderBy: id
+    orderDirection: asc
+    where: { id_gt: $lastId, borrowedReservesCount_gt: 0 }
+  ) {
+    id
+    reserves {
+      currentATokenBalance
+      currentTotalDebt
+      usageAsCollateralEnabledOnUser
+      reserve {
+        underlyingAsset
+      }
+    }
+  }
+}
+"#;
 
-/// Initial user discovery by scanning historical Aave events
+/// Initial user discovery, from historical Aave events or an Aave V3 subgraph. Subgraph
+/// borrowers are also cached in the local health factor engine with their reserve balances.
+#[allow(clippy::too_many_arguments)]
 pub async fn discover_initial_users<P>(
     provider: Arc<P>,
     pool_address: Address,
     db_pool: &DatabasePool,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
+    event_tx: EventSender,
+    prioritize_by_debt: bool,
+    method: DiscoveryMethod,
+    subgraph_url: Option<&str>,
+    health_engine: Option<&HealthFactorEngine>,
 ) -> Result<HashSet<Address>>
+where
+    P: Provider + 'static,
+{
+    let (discovered_users, discovery_source) = match (method, subgraph_url) {
+        (DiscoveryMethod::Subgraph, Some(url)) => match discover_users_via_subgraph(url).await {
+            Ok(borrowers) => {
+                if let Some(engine) = health_engine {
+                    for borrower in &borrowers {
+                        engine.set_user_balances(borrower.address, borrower.balances.clone());
+                    }
+                }
+                let users = borrowers.iter().map(|borrower| borrower.address).collect();
+                (users, "the subgraph".to_string())
+            }
+            Err(e) => {
+                warn!("⚠️ Subgraph discovery failed: {}. Falling back to event scanning.", e);
+                let users = scan_event_logs(&provider, pool_address).await?;
+                (users, format!("last {} blocks", BLOCKS_TO_SCAN))
+            }
+        },
+        _ => {
+            let users = scan_event_logs(&provider, pool_address).await?;
+            (users, format!("last {} blocks", BLOCKS_TO_SCAN))
+        }
+    };
+
+    // Check the most consequential positions first so at-risk whales aren't reached last
+    let catch_up_queue = if prioritize_by_debt {
+        let known_debt = match crate::database::get_all_user_positions(db_pool).await {
+            Ok(positions) => positions
+                .into_iter()
+                .map(|position| (position.address, position.total_debt_base))
+                .collect(),
+            Err(e) => {
+                warn!("Failed to load stored positions for catch-up ordering: {}", e);
+                HashMap::new()
+            }
+        };
+        order_catch_up_queue(&discovered_users, &known_debt)
+    } else {
+        discovered_users.iter().copied().collect()
+    };
+
+    // Now check health for each discovered user and populate the database
+    info!("🏥 Checking health for discovered users...");
+
+    let mut processed_count = 0;
+    let mut at_risk_count = 0;
+
+    for user_address in catch_up_queue {
+        match scanner::check_user_health(&provider, pool_address, user_address, 3).await {
+            Ok(position) => {
+                processed_count += 1;
+
+                // Save to database
+                if let Err(e) = crate::database::save_user_position(db_pool, &position).await {
+                    error!("Failed to save user position for {:?}: {}", user_address, e);
+                    continue;
+                }
+
+                // Count at-risk users
+                if position.is_at_risk {
+                    at_risk_count += 1;
+                    info!(
+                        "⚠️  Discovered at-risk user: {:?} (HF: {})",
+                        user_address,
+                        format_health_factor(position.health_factor)
+                    );
+                }
+
+                // Send event for ALL users to populate collateral mapping and trigger processing
+                // This ensures users_by_collateral mapping is populated for everyone
+                let _ = event_tx.send(BotEvent::UserPositionChanged(user_address));
+
+                // Brief delay to avoid overwhelming the RPC
+                tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
+
+                if processed_count % 50 == 0 {
+                    info!(
+                        "Processed {}/{} users...",
+                        processed_count,
+                        discovered_users.len()
+                    );
+                }
+            }
+            Err(e) => {
+                debug!("Failed to check health for user {:?}: {}", user_address, e);
+                // Continue with next user rather than failing completely
+            }
+        }
+    }
+
+    info!(
+        "✅ User discovery completed: {} users processed, {} at risk",
+        processed_count, at_risk_count
+    );
+
+    // Log the discovery event
+    if let Err(e) = crate::database::log_monitoring_event(
+        db_pool,
+        "initial_user_discovery",
+        None,
+        Some(&format!(
+            "Discovered {} users, {} at risk from {}",
+            processed_count, at_risk_count, discovery_source
+        )),
+    )
+    .await
+    {
+        error!("Failed to log discovery event: {}", e);
+    }
+
+    Ok(discovered_users)
+}
+
+/// Collect users active in the pool over the last `BLOCKS_TO_SCAN` blocks
+async fn scan_event_logs<P>(provider: &Arc<P>, pool_address: Address) -> Result<HashSet<Address>>
 where
     P: Provider + 'static,
 {
@@ -130,78 +279,28 @@ where
         discovered_users.len()
     );
 
-    // Now check health for each discovered user and populate the database
-    info!("🏥 Checking health for discovered users...");
-
-    let mut processed_count = 0;
-    let mut at_risk_count = 0;
-
-    for &user_address in &discovered_users {
-        match scanner::check_user_health(&provider, pool_address, user_address, 3).await {
-            Ok(position) => {
-                processed_count += 1;
-
-                // Save to database
-                if let Err(e) = crate::database::save_user_position(db_pool, &position).await {
-                    error!("Failed to save user position for {:?}: {}", user_address, e);
-                    continue;
-                }
-
-                // Count at-risk users
-                if position.is_at_risk {
-                    at_risk_count += 1;
-                    info!(
-                        "⚠️  Discovered at-risk user: {:?} (HF: {})",
-                        user_address,
-                        format_health_factor(position.health_factor)
-                    );
-                }
-
-                // Send event for ALL users to populate collateral mapping and trigger processing
-                // This ensures users_by_collateral mapping is populated for everyone
-                let _ = event_tx.send(BotEvent::UserPositionChanged(user_address));
-
-                // Brief delay to avoid overwhelming the RPC
-                tokio::time::sleep(tokio::time::Duration::from_millis(50)).await;
-
-                if processed_count % 50 == 0 {
-                    info!(
-                        "Processed {}/{} users...",
-                        processed_count,
-                        discovered_users.len()
-                    );
-                }
-            }
-            Err(e) => {
-                debug!("Failed to check health for user {:?}: {}", user_address, e);
-                // Continue with next user rather than failing completely
-            }
-        }
-    }
-
-    info!(
-        "✅ User discovery completed: {} users processed, {} at risk",
-        processed_count, at_risk_count
-    );
-
-    // Log the discovery event
-    if let Err(e) = crate::database::log_monitoring_event(
-        db_pool,
-        "initial_user_discovery",
-        None,
-        Some(&format!(
-            "Discovered {} users, {} at risk from last {} blocks",
-            processed_count, at_risk_count, BLOCKS_TO_SCAN
-        )),
-    )
-    .await
-    {
-        error!("Failed to log discovery event: {}", e);
-    }
-
     Ok(discovered_users)
 }
 
+/// Order discovered users for the catch-up scan: users with known debt first, largest
+/// `total_debt_base` first, then users never seen before (debt unknown), then users known to
+/// have no debt. Ties are broken by address so the order is deterministic.
+pub fn order_catch_up_queue(
+    users: &HashSet<Address>,
+    known_debt: &HashMap<Address, U256>,
+) -> Vec<Address> {
+    let mut queue: Vec<Address> = users.iter().copied().collect();
+    queue.sort_by_key(|user| {
+        let rank = match known_debt.get(user) {
+            Some(debt) if !debt.is_zero() => (0, Reverse(*debt)),
+            None => (1, Reverse(U256::ZERO)),
+            Some(_) => (2, Reverse(U256::ZERO)),
+        };
+        (rank, *user)
+    });
+    queue
+}
+
 /// Extract user address from event log based on event type
 fn extract_user_address_from_log(log: &alloy_rpc_types::Log, event_name: &str) -> Option<Address> {
     let topics = log.topics();
@@ -250,26 +349,229 @@ fn format_health_factor(hf: U256) -> String {
     }
 }
 
-/// Alternative discovery method using subgraph (if available)
-pub async fn discover_users_via_subgraph(
-    _subgraph_url: &str,
-    _max_users: usize,
-) -> Result<Vec<UserSubgraphData>> {
-    info!("🌐 Attempting to discover users via subgraph...");
+/// A borrower as reported by the subgraph, with balances per reserve in native units
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SubgraphBorrower {
+    pub address: Address,
+    pub balances: HashMap<Address, ReserveBalance>,
+}
 
-    // Note: This is a placeholder for subgraph integration
-    // The Graph's decentralized network requires an API key for most queries
-    // For now, we'll return empty and rely on event scanning
+#[derive(Debug, Deserialize)]
+struct SubgraphResponse {
+    data: Option<SubgraphUsers>,
+    #[serde(default)]
+    errors: Vec<SubgraphError>,
+}
 
-    warn!("Subgraph discovery not yet implemented - falling back to event scanning");
-    Ok(Vec::new())
+#[derive(Debug, Deserialize)]
+struct SubgraphError {
+    message: String,
 }
 
-/// User data from subgraph query
-#[derive(Debug, Clone)]
-pub struct UserSubgraphData {
-    pub address: Address,
-    pub total_collateral_eth: String,
-    pub total_debt_eth: String,
-    pub health_factor: Option<String>,
+#[derive(Debug, Deserialize)]
+struct SubgraphUsers {
+    users: Vec<SubgraphUser>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubgraphUser {
+    id: String,
+    reserves: Vec<SubgraphUserReserve>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubgraphUserReserve {
+    #[serde(rename = "currentATokenBalance")]
+    current_a_token_balance: String,
+    #[serde(rename = "currentTotalDebt")]
+    current_total_debt: String,
+    #[serde(rename = "usageAsCollateralEnabledOnUser")]
+    usage_as_collateral_enabled_on_user: bool,
+    reserve: SubgraphReserve,
+}
+
+#[derive(Debug, Deserialize)]
+struct SubgraphReserve {
+    #[serde(rename = "underlyingAsset")]
+    underlying_asset: String,
+}
+
+impl SubgraphResponse {
+    fn into_users(self) -> Result<Vec<SubgraphUser>> {
+        if let Some(error) = self.errors.first() {
+            return Err(eyre::eyre!("Subgraph query failed: {}", error.message));
+        }
+        self.data
+            .map(|data| data.users)
+            .ok_or_else(|| eyre::eyre!("Subgraph response has no data"))
+    }
+}
+
+impl SubgraphUser {
+    /// None when the address or any balance can't be parsed: partial balances would
+    /// overstate the health factor
+    fn into_borrower(self) -> Option<SubgraphBorrower> {
+        let address: Address = self.id.parse().ok()?;
+        let mut balances = HashMap::new();
+        for user_reserve in self.reserves {
+            let asset: Address = user_reserve.reserve.underlying_asset.parse().ok()?;
+            let collateral = if user_reserve.usage_as_collateral_enabled_on_user {
+                user_reserve.current_a_token_balance.parse().ok()?
+            } else {
+                U256::ZERO
+            };
+            let debt = user_reserve.current_total_debt.parse().ok()?;
+            balances.insert(asset, ReserveBalance { collateral, debt });
+        }
+        Some(SubgraphBorrower { address, balances })
+    }
+}
+
+/// Every borrower known to an Aave V3 subgraph, with their reserve balances
+pub async fn discover_users_via_subgraph(subgraph_url: &str) -> Result<Vec<SubgraphBorrower>> {
+    info!("🌐 Discovering borrowers via subgraph {}...", subgraph_url);
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(30))
+        .build()?;
+    let mut borrowers = Vec::new();
+    let mut last_id = String::new();
+
+    loop {
+        let body = serde_json::json!({
+            "query": BORROWERS_QUERY,
+            "variables": { "lastId": last_id, "first": SUBGRAPH_PAGE_SIZE },
+        });
+        let response: SubgraphResponse = client
+            .post(subgraph_url)
+            .json(&body)
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let users = response.into_users()?;
+        let page_len = users.len();
+        if let Some(last) = users.last() {
+            last_id = last.id.clone();
+        }
+
+        for user in users {
+            let id = user.id.clone();
+            match user.into_borrower() {
+                Some(borrower) => borrowers.push(borrower),
+                None => debug!("Skipping subgraph user {} with unparsable balances", id),
+            }
+        }
+        debug!("Fetched {} borrowers from the subgraph so far", borrowers.len());
+
+        if page_len < SUBGRAPH_PAGE_SIZE {
+            break;
+        }
+    }
+
+    info!("✅ Subgraph discovery found {} borrowers", borrowers.len());
+    Ok(borrowers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_catch_up_queue_ordered_by_debt_size() {
+        let whale = Address::repeat_byte(0x01);
+        let mid = Address::repeat_byte(0x02);
+        let small = Address::repeat_byte(0x03);
+        let unknown = Address::repeat_byte(0x04);
+        let debt_free = Address::repeat_byte(0x05);
+
+        let users: HashSet<Address> = [small, debt_free, unknown, whale, mid].into_iter().collect();
+        let known_debt: HashMap<Address, U256> = [
+            (small, U256::from(10u64)),
+            (whale, U256::from(5_000_000u64)),
+            (mid, U256::from(20_000u64)),
+            (debt_free, U256::ZERO),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            order_catch_up_queue(&users, &known_debt),
+            vec![whale, mid, small, unknown, debt_free]
+        );
+    }
+
+    #[test]
+    fn test_subgraph_users_parse_into_borrowers() {
+        let response: SubgraphResponse = serde_json::from_value(serde_json::json!({
+            "data": { "users": [
+                {
+                    "id": "0x00000000000000000000000000000000000000aa",
+                    "reserves": [
+                        {
+                            "currentATokenBalance": "1000000000000000000",
+                            "currentTotalDebt": "0",
+                            "usageAsCollateralEnabledOnUser": true,
+                            "reserve": {
+                                "underlyingAsset": "0x0101010101010101010101010101010101010101"
+                            }
+                        },
+                        {
+                            "currentATokenBalance": "500000",
+                            "currentTotalDebt": "1500000000",
+                            "usageAsCollateralEnabledOnUser": false,
+                            "reserve": {
+                                "underlyingAsset": "0x0202020202020202020202020202020202020202"
+                            }
+                        }
+                    ]
+                },
+                { "id": "not-an-address", "reserves": [] }
+            ]}
+        }))
+        .unwrap();
+
+        let borrowers: Vec<SubgraphBorrower> = response
+            .into_users()
+            .unwrap()
+            .into_iter()
+            .filter_map(SubgraphUser::into_borrower)
+            .collect();
+
+        assert_eq!(borrowers.len(), 1);
+        let borrower = &borrowers[0];
+        assert_eq!(borrower.address, Address::with_last_byte(0xaa));
+        assert_eq!(
+            borrower.balances[&Address::repeat_byte(0x01)],
+            ReserveBalance {
+                collateral: U256::from(10u64).pow(U256::from(18u64)),
+                debt: U256::ZERO
+            }
+        );
+        // Supplied but not enabled as collateral: only the debt counts
+        assert_eq!(
+            borrower.balances[&Address::repeat_byte(0x02)],
+            ReserveBalance { collateral: U256::ZERO, debt: U256::from(1_500_000_000u64) }
+        );
+    }
+
+    #[test]
+    fn test_subgraph_errors_are_surfaced() {
+        let response: SubgraphResponse = serde_json::from_value(serde_json::json!({
+            "errors": [{ "message": "indexing_error" }]
+        }))
+        .unwrap();
+
+        assert!(response.into_users().unwrap_err().to_string().contains("indexing_error"));
+    }
+
+    #[test]
+    fn test_catch_up_queue_without_known_debt_is_deterministic() {
+        let users: HashSet<Address> = (1..=5u8).rev().map(Address::repeat_byte).collect();
+
+        let queue = order_catch_up_queue(&users, &HashMap::new());
+
+        assert_eq!(queue, (1..=5u8).map(Address::repeat_byte).collect::<Vec<_>>());
+    }
 }
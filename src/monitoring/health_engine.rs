@@ -0,0 +1,639 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::sol;
+use dashmap::DashMap;
+use eyre::Result;
+use std::collections::{HashMap, HashSet};
+use tracing::{debug, info, warn};
+
+use crate::liquidation::assets::fetch_reserve_indices;
+use crate::liquidation::emode::{self, EModeCategory};
+use crate::models::UserReserve;
+use crate::monitoring::aave_oracle::AaveOracle;
+use crate::networks::NetworkAddresses;
+use crate::protocols::{call_contract, multicall};
+
+sol! {
+    #[allow(missing_docs)]
+    interface IReserveDataProvider {
+        function getReserveConfigurationData(address asset) external view returns (
+            uint256 decimals,
+            uint256 ltv,
+            uint256 liquidationThreshold,
+            uint256 liquidationBonus,
+            uint256 reserveFactor,
+            bool usageAsCollateralEnabled,
+            bool borrowingEnabled,
+            bool stableBorrowRateEnabled,
+            bool isActive,
+            bool isFrozen
+        );
+        function getReserveEModeCategory(address asset) external view returns (uint256);
+        function getUserReserveData(address asset, address user) external view returns (
+            uint256 currentATokenBalance,
+            uint256 currentStableDebt,
+            uint256 currentVariableDebt,
+            uint256 principalStableDebt,
+            uint256 scaledVariableDebt,
+            uint256 stableBorrowRate,
+            uint256 liquidityRate,
+            uint40 stableRateLastUpdated,
+            bool usageAsCollateralEnabled
+        );
+    }
+}
+
+/// Health factor of exactly 1.0 (18 decimals)
+const HEALTH_FACTOR_ONE: u128 = 1_000_000_000_000_000_000;
+
+/// Liquidation thresholds are expressed in basis points
+const BPS: u64 = 10_000;
+
+/// Reserve parameters that affect the health factor
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ReserveParams {
+    pub decimals: u8,
+    pub liquidation_threshold_bps: u64,
+    /// E-mode category the reserve belongs to (0 = none)
+    pub emode_category: u8,
+}
+
+/// A user's balances in one reserve, in the reserve's native units
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ReserveBalance {
+    /// aToken balance counted as collateral (zero when not enabled as collateral)
+    pub collateral: U256,
+    /// Stable plus variable debt
+    pub debt: U256,
+}
+
+/// Cached balances of one user plus the last health factor computed from them
+#[derive(Debug, Clone, Default)]
+struct CachedUser {
+    balances: HashMap<Address, ReserveBalance>,
+    emode_category: u8,
+    last_health_factor: Option<U256>,
+}
+
+/// Health factor (18 decimals) of `balances` at `prices` (base currency, 8 decimals), the way
+/// the Aave pool computes it. Collateral in the user's e-mode category counts at the
+/// category's liquidation threshold. `U256::MAX` without debt; `None` when a held reserve
+/// has no known parameters or price.
+pub fn compute_health_factor(
+    balances: &HashMap<Address, ReserveBalance>,
+    reserves: &HashMap<Address, ReserveParams>,
+    prices: &HashMap<Address, U256>,
+    emode: Option<&EModeCategory>,
+) -> Option<U256> {
+    let mut weighted_collateral = U256::ZERO;
+    let mut total_debt = U256::ZERO;
+
+    for (asset, balance) in balances {
+        if balance.collateral.is_zero() && balance.debt.is_zero() {
+            continue;
+        }
+        let params = reserves.get(asset)?;
+        let price = *prices.get(asset)?;
+        let unit = U256::from(10u64).pow(U256::from(params.decimals));
+
+        let liquidation_threshold_bps = match emode {
+            Some(category) if category.id == params.emode_category => {
+                u64::from(category.liquidation_threshold_bps)
+            }
+            _ => params.liquidation_threshold_bps,
+        };
+
+        let collateral_value = balance.collateral.saturating_mul(price) / unit;
+        weighted_collateral = weighted_collateral.saturating_add(
+            collateral_value.saturating_mul(U256::from(liquidation_threshold_bps))
+                / U256::from(BPS),
+        );
+        total_debt = total_debt.saturating_add(balance.debt.saturating_mul(price) / unit);
+    }
+
+    if total_debt.is_zero() {
+        return Some(U256::MAX);
+    }
+    Some(weighted_collateral.saturating_mul(U256::from(HEALTH_FACTOR_ONE)) / total_debt)
+}
+
+/// Local health factor engine: caches per-reserve balances of tracked users together with
+/// reserve parameters and prices, so a price tick can be evaluated without an RPC call per
+/// user. Only users whose recomputed health factor drops below 1.0 (or whose cached data
+/// is incomplete) need to be confirmed on-chain. Prices come from the Aave oracle, the same
+/// source the pool uses; Chainlink updates only tell the engine when to re-read it.
+#[derive(Debug)]
+pub struct HealthFactorEngine {
+    reserves: DashMap<Address, ReserveParams>,
+    prices: DashMap<Address, U256>,
+    users: DashMap<Address, CachedUser>,
+    emode_categories: DashMap<u8, EModeCategory>,
+    network: NetworkAddresses,
+}
+
+impl HealthFactorEngine {
+    pub fn new(network: NetworkAddresses) -> Self {
+        Self {
+            reserves: DashMap::new(),
+            prices: DashMap::new(),
+            users: DashMap::new(),
+            emode_categories: DashMap::new(),
+            network,
+        }
+    }
+
+    pub fn set_reserve(&self, asset: Address, params: ReserveParams) {
+        self.reserves.insert(asset, params);
+    }
+
+    pub fn set_price(&self, asset: Address, price: U256) {
+        self.prices.insert(asset, price);
+    }
+
+    pub fn set_emode_category(&self, category: EModeCategory) {
+        self.emode_categories.insert(category.id, category);
+    }
+
+    /// Replace a user's cached balances, keeping their e-mode category; users without any
+    /// position are dropped
+    pub fn set_user_balances(&self, user: Address, balances: HashMap<Address, ReserveBalance>) {
+        let emode_category = self.users.get(&user).map_or(0, |cached| cached.emode_category);
+        self.set_user_position(user, emode_category, balances);
+    }
+
+    /// Replace a user's cached e-mode category (0 = none) and balances
+    pub fn set_user_position(
+        &self,
+        user: Address,
+        emode_category: u8,
+        balances: HashMap<Address, ReserveBalance>,
+    ) {
+        let balances: HashMap<Address, ReserveBalance> = balances
+            .into_iter()
+            .filter(|(_, balance)| !balance.collateral.is_zero() || !balance.debt.is_zero())
+            .collect();
+        if balances.is_empty() {
+            self.users.remove(&user);
+            return;
+        }
+
+        let last_health_factor = self.compute(emode_category, &balances);
+        self.users.insert(
+            user,
+            CachedUser {
+                balances,
+                emode_category,
+                last_health_factor,
+            },
+        );
+    }
+
+    pub fn tracked_users(&self) -> usize {
+        self.users.len()
+    }
+
+    /// Locally computed health factor of a cached user
+    pub fn health_factor(&self, user: Address) -> Option<U256> {
+        self.users
+            .get(&user)
+            .and_then(|cached| self.compute(cached.emode_category, &cached.balances))
+    }
+
+    fn compute(
+        &self,
+        emode_category: u8,
+        balances: &HashMap<Address, ReserveBalance>,
+    ) -> Option<U256> {
+        // A category whose parameters aren't loaded can't be evaluated locally
+        let emode = match emode_category {
+            0 => None,
+            id => Some(self.emode_categories.get(&id)?.clone()),
+        };
+        let reserves: HashMap<Address, ReserveParams> = balances
+            .keys()
+            .filter_map(|asset| self.reserves.get(asset).map(|params| (*asset, *params)))
+            .collect();
+        let prices: HashMap<Address, U256> = balances
+            .keys()
+            .filter_map(|asset| self.prices.get(asset).map(|price| (*asset, *price)))
+            .collect();
+        compute_health_factor(balances, &reserves, &prices, emode.as_ref())
+    }
+
+    /// Apply a new price and recompute every cached user holding `asset`. Returns the users to
+    /// confirm on-chain: those whose health factor crossed below 1.0 and those whose cached
+    /// data can't produce a health factor.
+    pub fn on_price_change(&self, asset: Address, price: U256) -> HashSet<Address> {
+        self.on_prices(&HashMap::from([(asset, price)]))
+    }
+
+    /// Apply a batch of prices and recompute every cached user holding an asset whose price
+    /// changed. Returns the users to confirm on-chain, as `on_price_change` does.
+    pub fn on_prices(&self, prices: &HashMap<Address, U256>) -> HashSet<Address> {
+        let changed: HashSet<Address> = prices
+            .iter()
+            .filter(|(asset, price)| self.prices.get(*asset).map(|known| *known) != Some(**price))
+            .map(|(asset, _)| *asset)
+            .collect();
+        for asset in &changed {
+            self.set_price(*asset, prices[asset]);
+        }
+        let one = U256::from(HEALTH_FACTOR_ONE);
+
+        let mut to_confirm = HashSet::new();
+        for mut entry in self.users.iter_mut() {
+            if !entry.balances.keys().any(|asset| changed.contains(asset)) {
+                continue;
+            }
+
+            let health_factor = self.compute(entry.emode_category, &entry.balances);
+            let was_healthy = entry.last_health_factor.map_or(true, |hf| hf >= one);
+            match health_factor {
+                Some(hf) if hf < one && was_healthy => {
+                    to_confirm.insert(*entry.key());
+                }
+                None => {
+                    to_confirm.insert(*entry.key());
+                }
+                _ => {}
+            }
+            entry.last_health_factor = health_factor;
+        }
+
+        debug!(
+            "Local health factors recomputed for {} price change(s): {} users to confirm",
+            changed.len(),
+            to_confirm.len()
+        );
+        to_confirm
+    }
+
+    fn oracle(&self) -> AaveOracle {
+        AaveOracle::new(self.network.oracle)
+    }
+
+    /// Re-read every reserve's price from the Aave oracle in one call and recompute the users
+    /// affected by any change. Returns the users to confirm on-chain.
+    pub async fn refresh_prices<P>(&self, provider: &P) -> Result<HashSet<Address>>
+    where
+        P: Provider,
+    {
+        let reserves: Vec<Address> = self.reserves.iter().map(|entry| *entry.key()).collect();
+        let prices = self.oracle().get_price_map(provider, &reserves).await?;
+        Ok(self.on_prices(&prices))
+    }
+
+    /// Load parameters and current oracle prices of every pool reserve
+    pub async fn load_reserves<P>(&self, provider: &P) -> Result<()>
+    where
+        P: Provider,
+    {
+        let data_provider = self.network.data_provider;
+        let reserves: Vec<Address> = fetch_reserve_indices(provider, &self.network)
+            .await?
+            .into_keys()
+            .collect();
+
+        for asset in &reserves {
+            let config = call_contract(
+                provider,
+                data_provider,
+                &IReserveDataProvider::getReserveConfigurationDataCall { asset: *asset },
+            )
+            .await;
+            let emode_category = call_contract(
+                provider,
+                data_provider,
+                &IReserveDataProvider::getReserveEModeCategoryCall { asset: *asset },
+            )
+            .await;
+            match (config, emode_category) {
+                (Ok(config), Ok(emode_category)) => self.set_reserve(
+                    *asset,
+                    ReserveParams {
+                        decimals: config.decimals.saturating_to(),
+                        liquidation_threshold_bps: config.liquidationThreshold.saturating_to(),
+                        emode_category: emode_category._0.saturating_to(),
+                    },
+                ),
+                (Err(e), _) | (_, Err(e)) => {
+                    warn!("Failed to load reserve parameters for {}: {}", asset, e)
+                }
+            }
+        }
+
+        let categories: HashSet<u8> = self
+            .reserves
+            .iter()
+            .map(|entry| entry.emode_category)
+            .filter(|id| *id != 0)
+            .collect();
+        if !categories.is_empty() {
+            let pool = self.network.pool;
+            for id in categories {
+                match emode::fetch_emode_category(provider, pool, id).await {
+                    Ok(category) => self.set_emode_category(category),
+                    Err(e) => warn!("Failed to load e-mode category {}: {}", id, e),
+                }
+            }
+        }
+
+        let prices = self.oracle().get_price_map(provider, &reserves).await?;
+        for (asset, price) in prices {
+            self.set_price(asset, price);
+        }
+
+        info!(
+            "🧮 Local health factor engine loaded {} reserves",
+            self.reserves.len()
+        );
+        Ok(())
+    }
+
+    /// Fetch a user's per-reserve balances and cache them
+    pub async fn refresh_user<P>(&self, provider: &P, user: Address) -> Result<()>
+    where
+        P: Provider,
+    {
+        let reserves: Vec<Address> = self.reserves.iter().map(|entry| *entry.key()).collect();
+        let user_reserves = fetch_user_reserves(provider, &self.network, user, &reserves).await?;
+        self.set_user_reserves(provider, user, &user_reserves).await
+    }
+
+    /// Cache balances already read for a user, fetching their e-mode category alongside
+    pub async fn set_user_reserves<P>(
+        &self,
+        provider: &P,
+        user: Address,
+        user_reserves: &[UserReserve],
+    ) -> Result<()>
+    where
+        P: Provider,
+    {
+        let pool = self.network.pool;
+        let emode_category = emode::fetch_user_emode(provider, pool, user).await?;
+        if emode_category != 0 && !self.emode_categories.contains_key(&emode_category) {
+            let category = emode::fetch_emode_category(provider, pool, emode_category).await?;
+            self.set_emode_category(category);
+        }
+
+        let balances = user_reserves
+            .iter()
+            .map(|user_reserve| {
+                // Reserves without known parameters keep their balance, leaving the health
+                // factor to be confirmed on-chain
+                let counts_as_collateral = user_reserve.usage_as_collateral_enabled
+                    && self
+                        .reserves
+                        .get(&user_reserve.reserve)
+                        .map_or(true, |params| params.liquidation_threshold_bps > 0);
+                let balance = ReserveBalance {
+                    collateral: if counts_as_collateral {
+                        user_reserve.collateral_balance
+                    } else {
+                        U256::ZERO
+                    },
+                    debt: user_reserve.debt_balance,
+                };
+                (user_reserve.reserve, balance)
+            })
+            .collect();
+
+        self.set_user_position(user, emode_category, balances);
+        Ok(())
+    }
+}
+
+/// A user's balances in each of `reserves` read from the protocol data provider in one
+/// Multicall3 request, leaving out reserves the user neither supplies nor borrows
+pub async fn fetch_user_reserves<P>(
+    provider: &P,
+    network: &NetworkAddresses,
+    user: Address,
+    reserves: &[Address],
+) -> Result<Vec<UserReserve>>
+where
+    P: Provider,
+{
+    let calls: Vec<_> = reserves
+        .iter()
+        .map(|&asset| IReserveDataProvider::getUserReserveDataCall { asset, user })
+        .collect();
+    let results = multicall(provider, network.multicall, network.data_provider, &calls).await?;
+
+    let mut user_reserves = Vec::new();
+    for (&reserve, data) in reserves.iter().zip(results) {
+        let debt_balance = data.currentStableDebt.saturating_add(data.currentVariableDebt);
+        if data.currentATokenBalance.is_zero() && debt_balance.is_zero() {
+            continue;
+        }
+        user_reserves.push(UserReserve {
+            reserve,
+            collateral_balance: data.currentATokenBalance,
+            debt_balance,
+            usage_as_collateral_enabled: data.usageAsCollateralEnabled,
+        });
+    }
+    Ok(user_reserves)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WETH: Address = Address::repeat_byte(0x01);
+    const USDC: Address = Address::repeat_byte(0x02);
+
+    fn usd(value: u64) -> U256 {
+        U256::from(value) * U256::from(100_000_000u64)
+    }
+
+    fn engine() -> HealthFactorEngine {
+        let engine = HealthFactorEngine::new(crate::networks::base());
+        engine.set_reserve(
+            WETH,
+            ReserveParams { decimals: 18, liquidation_threshold_bps: 8_250, emode_category: 1 },
+        );
+        engine.set_reserve(
+            USDC,
+            ReserveParams { decimals: 6, liquidation_threshold_bps: 7_800, emode_category: 0 },
+        );
+        engine.set_price(WETH, usd(2_000));
+        engine.set_price(USDC, usd(1));
+        engine
+    }
+
+    /// 1 WETH of collateral against `debt_usdc` USDC of debt
+    fn weth_backed_position(debt_usdc: u64) -> HashMap<Address, ReserveBalance> {
+        HashMap::from([
+            (
+                WETH,
+                ReserveBalance {
+                    collateral: U256::from(10u64).pow(U256::from(18u64)),
+                    debt: U256::ZERO,
+                },
+            ),
+            (
+                USDC,
+                ReserveBalance {
+                    collateral: U256::ZERO,
+                    debt: U256::from(debt_usdc) * U256::from(1_000_000u64),
+                },
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_health_factor_matches_aave_formula() {
+        let engine = engine();
+        let user = Address::repeat_byte(0xaa);
+        engine.set_user_balances(user, weth_backed_position(1_500));
+
+        // 2000 * 0.825 / 1500 = 1.1
+        assert_eq!(
+            engine.health_factor(user),
+            Some(U256::from(1_100_000_000_000_000_000u128))
+        );
+
+        let no_debt = Address::repeat_byte(0xbb);
+        let mut balances = weth_backed_position(0);
+        balances.remove(&USDC);
+        engine.set_user_balances(no_debt, balances);
+        assert_eq!(engine.health_factor(no_debt), Some(U256::MAX));
+    }
+
+    #[test]
+    fn test_only_users_crossing_below_one_need_confirmation() {
+        let engine = engine();
+        let (safe, risky) = (Address::repeat_byte(0xaa), Address::repeat_byte(0xbb));
+        engine.set_user_balances(safe, weth_backed_position(500));
+        engine.set_user_balances(risky, weth_backed_position(1_500));
+
+        // -5%: risky goes from 1.1 to 1.045, nobody crosses
+        assert!(engine.on_price_change(WETH, usd(1_900)).is_empty());
+
+        // -15%: risky drops below 1.0
+        assert_eq!(engine.on_price_change(WETH, usd(1_700)), HashSet::from([risky]));
+
+        // Still underwater: already reported, not confirmed again
+        assert!(engine.on_price_change(WETH, usd(1_650)).is_empty());
+
+        // Prices of assets nobody holds don't trigger anything
+        assert!(engine.on_price_change(Address::repeat_byte(0x03), usd(5)).is_empty());
+    }
+
+    #[test]
+    fn test_batched_prices_only_recompute_changed_assets() {
+        let engine = engine();
+        let risky = Address::repeat_byte(0xbb);
+        engine.set_user_balances(risky, weth_backed_position(1_500));
+
+        // Unchanged oracle prices recompute nobody
+        let unchanged = HashMap::from([(WETH, usd(2_000)), (USDC, usd(1))]);
+        assert!(engine.on_prices(&unchanged).is_empty());
+
+        let moved = HashMap::from([(WETH, usd(1_700)), (USDC, usd(1))]);
+        assert_eq!(engine.on_prices(&moved), HashSet::from([risky]));
+        assert_eq!(engine.prices.get(&WETH).map(|price| *price), Some(usd(1_700)));
+    }
+
+    #[test]
+    fn test_emode_threshold_applies_to_category_collateral() {
+        let engine = engine();
+        let user = Address::repeat_byte(0xaa);
+        engine.set_emode_category(EModeCategory {
+            id: 1,
+            ltv_bps: 9_000,
+            liquidation_threshold_bps: 9_300,
+            liquidation_bonus_bps: 100,
+            label: "ETH correlated".to_string(),
+        });
+
+        // 2000 * 0.93 / 1500 = 1.24 instead of 1.1 outside e-mode
+        engine.set_user_position(user, 1, weth_backed_position(1_500));
+        assert_eq!(
+            engine.health_factor(user),
+            Some(U256::from(1_240_000_000_000_000_000u128))
+        );
+
+        // -15% keeps the e-mode user above 1.0 (1.054)
+        assert!(engine.on_price_change(WETH, usd(1_700)).is_empty());
+
+        // Categories without loaded parameters need on-chain confirmation
+        let unknown_category = Address::repeat_byte(0xbb);
+        engine.set_user_position(unknown_category, 2, weth_backed_position(1_500));
+        assert_eq!(engine.health_factor(unknown_category), None);
+    }
+
+    #[test]
+    fn test_incomplete_cache_falls_back_to_rpc() {
+        let engine = engine();
+        let user = Address::repeat_byte(0xaa);
+        let mut balances = weth_backed_position(1_000);
+        let unknown = Address::repeat_byte(0x09);
+        balances.insert(unknown, ReserveBalance { collateral: U256::from(1u64), debt: U256::ZERO });
+        engine.set_user_balances(user, balances);
+
+        assert_eq!(engine.health_factor(user), None);
+        assert_eq!(engine.on_price_change(WETH, usd(2_100)), HashSet::from([user]));
+    }
+
+    #[test]
+    fn test_empty_positions_are_not_cached() {
+        let engine = engine();
+        let user = Address::repeat_byte(0xaa);
+        engine.set_user_balances(user, weth_backed_position(1_000));
+        engine.set_user_balances(user, HashMap::from([(WETH, ReserveBalance::default())]));
+
+        assert_eq!(engine.tracked_users(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_user_reserves_are_read_in_one_multicall() {
+        use crate::mock_server::MockServer;
+        use crate::protocols::IMulticall3;
+        use alloy_provider::ProviderBuilder;
+        use alloy_sol_types::SolCall;
+
+        let reserve_data = |collateral: u64, variable_debt: u64| {
+            IReserveDataProvider::getUserReserveDataCall::abi_encode_returns(&(
+                U256::from(collateral),
+                U256::ZERO,
+                U256::from(variable_debt),
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                0u64,
+                collateral > 0,
+            ))
+        };
+        let returns = IMulticall3::aggregate3Call::abi_encode_returns(&(vec![
+            IMulticall3::Call3Result { success: true, returnData: reserve_data(5, 0).into() },
+            IMulticall3::Call3Result { success: true, returnData: reserve_data(0, 0).into() },
+            IMulticall3::Call3Result { success: true, returnData: reserve_data(0, 7).into() },
+        ],));
+        let server = MockServer::json_rpc(move |_| {
+            serde_json::json!(alloy_primitives::Bytes::from(returns.clone()).to_string())
+        })
+        .await;
+        let provider = ProviderBuilder::new().on_http(server.url.parse().unwrap());
+        let dai = Address::repeat_byte(0x03);
+
+        let reserves = fetch_user_reserves(
+            &provider,
+            &crate::networks::base(),
+            Address::repeat_byte(0xaa),
+            &[WETH, USDC, dai],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(server.requests().len(), 1);
+        assert_eq!(reserves.len(), 2);
+        assert_eq!(reserves[0].reserve, WETH);
+        assert!(reserves[0].usage_as_collateral_enabled);
+        assert_eq!(reserves[1].reserve, dai);
+        assert_eq!(reserves[1].debt_balance, U256::from(7u64));
+    }
+}
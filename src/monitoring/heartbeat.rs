@@ -0,0 +1,240 @@
+use alloy_provider::Provider;
+use eyre::Result;
+use parking_lot::Mutex;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Build the URL to ping for a given liveness result.
+///
+/// Healthy ticks ping the configured URL as-is, unhealthy ticks ping its `/fail`
+/// variant (the convention used by healthchecks.io and similar watchdogs).
+pub fn heartbeat_target_url(base_url: &str, healthy: bool) -> String {
+    if healthy {
+        base_url.to_string()
+    } else {
+        format!("{}/fail", base_url.trim_end_matches('/'))
+    }
+}
+
+/// When the chain head last advanced, judging the bot stalled once that is longer ago than
+/// `stall_threshold`
+#[derive(Debug)]
+pub struct HeadProgress {
+    stall_threshold: Duration,
+    last_block: u64,
+    last_advance: Instant,
+}
+
+impl HeadProgress {
+    /// Starts the clock now, so a head that never moves counts as stalled after
+    /// `stall_threshold`
+    pub fn new(stall_threshold: Duration) -> Self {
+        Self {
+            stall_threshold,
+            last_block: 0,
+            last_advance: Instant::now(),
+        }
+    }
+
+    /// Record the current head and report whether it advanced recently enough
+    pub fn observe(&mut self, block: u64) -> bool {
+        if block > self.last_block {
+            self.last_block = block;
+            self.last_advance = Instant::now();
+            return true;
+        }
+        let since_advance = self.last_advance.elapsed();
+        if since_advance > self.stall_threshold {
+            warn!(
+                "💔 Chain head stuck at block {} for {:?} (stall threshold {:?})",
+                block, since_advance, self.stall_threshold
+            );
+            return false;
+        }
+        true
+    }
+}
+
+/// Start the heartbeat task, treating the bot as unhealthy when the chain head
+/// has not advanced for longer than `stall_threshold`.
+pub async fn start_heartbeat<P>(
+    provider: Arc<P>,
+    heartbeat_url: String,
+    heartbeat_interval_secs: u64,
+    stall_threshold: Duration,
+) -> Result<()>
+where
+    P: Provider + 'static,
+{
+    info!(
+        "💓 Starting heartbeat to {} every {} seconds (stall threshold {:?})",
+        heartbeat_url, heartbeat_interval_secs, stall_threshold
+    );
+
+    let progress = Arc::new(Mutex::new(HeadProgress::new(stall_threshold)));
+
+    run_heartbeat(
+        &heartbeat_url,
+        Duration::from_secs(heartbeat_interval_secs),
+        move || {
+            let provider = provider.clone();
+            let progress = progress.clone();
+            async move {
+                match provider.get_block_number().await {
+                    Ok(block) => progress.lock().observe(block),
+                    Err(e) => {
+                        warn!("💔 Failed to fetch block number for heartbeat: {}", e);
+                        false
+                    }
+                }
+            }
+        },
+    )
+    .await
+}
+
+/// Ping the heartbeat URL every `interval`, using `check_liveness` to decide
+/// whether to signal success or failure.
+pub async fn run_heartbeat<F, Fut>(
+    heartbeat_url: &str,
+    interval: Duration,
+    mut check_liveness: F,
+) -> Result<()>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = bool>,
+{
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    let mut ticker = tokio::time::interval(interval);
+
+    loop {
+        ticker.tick().await;
+
+        let healthy = check_liveness().await;
+        let target = heartbeat_target_url(heartbeat_url, healthy);
+
+        match client.get(&target).send().await {
+            Ok(response) if response.status().is_success() => {
+                debug!("💓 Heartbeat sent to {} (healthy: {})", target, healthy);
+            }
+            Ok(response) => {
+                warn!(
+                    "Heartbeat to {} returned status {}",
+                    target,
+                    response.status()
+                );
+            }
+            Err(e) => {
+                // Never let a watchdog outage take the bot down
+                warn!("Failed to send heartbeat to {}: {}", target, e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+
+    #[test]
+    fn test_heartbeat_target_url() {
+        assert_eq!(
+            heartbeat_target_url("https://hc-ping.com/abc", true),
+            "https://hc-ping.com/abc"
+        );
+        assert_eq!(
+            heartbeat_target_url("https://hc-ping.com/abc", false),
+            "https://hc-ping.com/abc/fail"
+        );
+        assert_eq!(
+            heartbeat_target_url("https://hc-ping.com/abc/", false),
+            "https://hc-ping.com/abc/fail"
+        );
+    }
+
+    /// Let the heartbeat and the mock server run until `count` pings have arrived. Spinning
+    /// keeps the runtime busy, so paused time only moves through `tokio::time::advance`.
+    async fn wait_for_pings(server: &MockServer, count: usize) {
+        for _ in 0..10_000 {
+            if server.requests().len() >= count {
+                return;
+            }
+            tokio::task::yield_now().await;
+        }
+        panic!("expected {} heartbeats, got {}", count, server.requests().len());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_fires_at_configured_interval() {
+        let server = MockServer::spawn(|_| MockResponse::ok()).await;
+        let url = server.url("/ping");
+        let heartbeat = tokio::spawn(async move {
+            run_heartbeat(&url, Duration::from_millis(100), || async { true }).await
+        });
+
+        // Ticks at 0ms, 100ms, 200ms and 300ms, none before the next interval elapses
+        wait_for_pings(&server, 1).await;
+        for count in 2..=4 {
+            tokio::time::advance(Duration::from_millis(100)).await;
+            wait_for_pings(&server, count).await;
+        }
+        tokio::time::advance(Duration::from_millis(50)).await;
+        for _ in 0..100 {
+            tokio::task::yield_now().await;
+        }
+        heartbeat.abort();
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, vec!["/ping"; 4]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_heartbeat_signals_failure_when_unhealthy() {
+        let server = MockServer::spawn(|_| MockResponse::ok()).await;
+        let url = server.url("/ping");
+        let heartbeat = tokio::spawn(async move {
+            run_heartbeat(&url, Duration::from_millis(100), || async { false }).await
+        });
+
+        wait_for_pings(&server, 1).await;
+        tokio::time::advance(Duration::from_millis(100)).await;
+        wait_for_pings(&server, 2).await;
+        heartbeat.abort();
+
+        let paths: Vec<String> = server.requests().into_iter().map(|r| r.path).collect();
+        assert_eq!(paths, vec!["/ping/fail"; 2]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_head_progress_fails_only_after_stall_threshold() {
+        let mut progress = HeadProgress::new(Duration::from_secs(20));
+        assert!(progress.observe(100));
+
+        // A quiet spell shorter than the threshold is still healthy
+        tokio::time::advance(Duration::from_secs(15)).await;
+        assert!(progress.observe(100));
+        tokio::time::advance(Duration::from_secs(5)).await;
+        assert!(progress.observe(100));
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        assert!(!progress.observe(100));
+
+        // A new block resets the clock
+        assert!(progress.observe(101));
+        tokio::time::advance(Duration::from_secs(20)).await;
+        assert!(progress.observe(101));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_head_progress_counts_a_head_that_never_moves_as_stalled() {
+        let mut progress = HeadProgress::new(Duration::from_secs(20));
+        tokio::time::advance(Duration::from_secs(21)).await;
+        assert!(!progress.observe(0));
+    }
+}
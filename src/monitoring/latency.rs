@@ -0,0 +1,204 @@
+use alloy_primitives::Address;
+use dashmap::DashMap;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use crate::models::LatencyTrace;
+
+/// Traces of opportunities that never reached the liquidation path are dropped after this long
+const TRACE_TTL: Duration = Duration::from_secs(600);
+
+/// A point a liquidation opportunity passes on its way from detection to a mined transaction
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LatencyStage {
+    LogReceived,
+    HealthConfirmed,
+    ProfitabilityDone,
+    TxSent,
+    TxMined,
+}
+
+/// The field of `trace` that `stage` stamps
+fn stage_slot(trace: &mut LatencyTrace, stage: LatencyStage) -> &mut Option<i64> {
+    match stage {
+        LatencyStage::LogReceived => &mut trace.log_received_ms,
+        LatencyStage::HealthConfirmed => &mut trace.hf_confirmed_ms,
+        LatencyStage::ProfitabilityDone => &mut trace.profitability_done_ms,
+        LatencyStage::TxSent => &mut trace.tx_sent_ms,
+        LatencyStage::TxMined => &mut trace.tx_mined_ms,
+    }
+}
+
+/// In-progress latency traces, by the user being liquidated
+#[derive(Default)]
+pub struct LatencyTracker {
+    traces: DashMap<Address, (LatencyTrace, Instant)>,
+}
+
+impl LatencyTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stamp `stage` for `user` at `at_ms`. A log starts a fresh trace; every other stage
+    /// keeps its first stamp, so fee bumps and fallback pairs don't hide the original timing.
+    pub fn mark_at(&self, user: Address, stage: LatencyStage, at_ms: i64) {
+        if stage == LatencyStage::LogReceived {
+            let trace = LatencyTrace {
+                log_received_ms: Some(at_ms),
+                ..LatencyTrace::default()
+            };
+            self.traces.insert(user, (trace, Instant::now()));
+            return;
+        }
+
+        let mut entry = self
+            .traces
+            .entry(user)
+            .or_insert_with(|| (LatencyTrace::default(), Instant::now()));
+        stage_slot(&mut entry.value_mut().0, stage).get_or_insert(at_ms);
+    }
+
+    pub fn mark(&self, user: Address, stage: LatencyStage) {
+        self.mark_at(user, stage, chrono::Utc::now().timestamp_millis());
+    }
+
+    /// Take `user`'s trace once its opportunity has been handled
+    pub fn finish(&self, user: Address) -> Option<LatencyTrace> {
+        self.traces.remove(&user).map(|(_, (trace, _))| trace)
+    }
+
+    /// Drop traces of users whose logs never led to a liquidation attempt
+    pub fn prune(&self) {
+        self.traces
+            .retain(|_, (_, started)| started.elapsed() <= TRACE_TTL);
+    }
+}
+
+/// Process-wide tracker, so the log, scan and execution paths can stamp stages without
+/// threading a handle through
+pub fn global() -> &'static LatencyTracker {
+    static TRACKER: OnceLock<LatencyTracker> = OnceLock::new();
+    TRACKER.get_or_init(LatencyTracker::new)
+}
+
+/// Stamp `stage` for `user` on the process-wide tracker
+pub fn mark(user: Address, stage: LatencyStage) {
+    global().mark(user, stage);
+}
+
+/// p50/p95 of one pipeline segment over the traces that reached both of its ends
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SegmentLatency {
+    pub segment: &'static str,
+    pub samples: usize,
+    pub p50_ms: i64,
+    pub p95_ms: i64,
+}
+
+/// Reported segments and the stages bounding them. Slow detection means the log or the health
+/// check lags; slow submission or inclusion means races are lost in execution.
+type SegmentBounds = fn(&LatencyTrace) -> (Option<i64>, Option<i64>);
+const SEGMENTS: [(&str, SegmentBounds); 5] = [
+    ("detection", |t| (t.log_received_ms, t.hf_confirmed_ms)),
+    ("profitability", |t| {
+        (t.hf_confirmed_ms, t.profitability_done_ms)
+    }),
+    ("submission", |t| (t.profitability_done_ms, t.tx_sent_ms)),
+    ("inclusion", |t| (t.tx_sent_ms, t.tx_mined_ms)),
+    ("total", |t| (t.log_received_ms, t.tx_mined_ms)),
+];
+
+/// Nearest-rank percentile of already sorted samples
+fn percentile(sorted: &[i64], pct: usize) -> i64 {
+    let rank = (sorted.len() * pct).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+/// p50/p95 per pipeline segment; segments no trace completed are left out
+pub fn segment_latencies(traces: &[LatencyTrace]) -> Vec<SegmentLatency> {
+    SEGMENTS
+        .iter()
+        .filter_map(|(segment, bounds)| {
+            let mut durations: Vec<i64> = traces
+                .iter()
+                .filter_map(|trace| match bounds(trace) {
+                    (Some(start), Some(end)) => Some((end - start).max(0)),
+                    _ => None,
+                })
+                .collect();
+            if durations.is_empty() {
+                return None;
+            }
+            durations.sort_unstable();
+            Some(SegmentLatency {
+                segment,
+                samples: durations.len(),
+                p50_ms: percentile(&durations, 50),
+                p95_ms: percentile(&durations, 95),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_keeps_first_stamp_and_restarts_on_log() {
+        let tracker = LatencyTracker::new();
+        let user = Address::repeat_byte(0x01);
+
+        tracker.mark_at(user, LatencyStage::LogReceived, 1_000);
+        tracker.mark_at(user, LatencyStage::HealthConfirmed, 1_120);
+        tracker.mark_at(user, LatencyStage::TxSent, 1_400);
+        // A fee bump re-sends; the first send is what the race was lost or won on
+        tracker.mark_at(user, LatencyStage::TxSent, 5_000);
+
+        let trace = tracker.finish(user).unwrap();
+        assert_eq!(trace.log_received_ms, Some(1_000));
+        assert_eq!(trace.hf_confirmed_ms, Some(1_120));
+        assert_eq!(trace.profitability_done_ms, None);
+        assert_eq!(trace.tx_sent_ms, Some(1_400));
+        assert!(tracker.finish(user).is_none());
+
+        // A new log restarts the trace rather than extending a stale one
+        tracker.mark_at(user, LatencyStage::HealthConfirmed, 9_000);
+        tracker.mark_at(user, LatencyStage::LogReceived, 9_500);
+        assert_eq!(tracker.finish(user).unwrap().hf_confirmed_ms, None);
+    }
+
+    #[test]
+    fn test_segment_percentiles() {
+        let traces: Vec<LatencyTrace> = (1..=20)
+            .map(|i| LatencyTrace {
+                log_received_ms: Some(0),
+                hf_confirmed_ms: Some(i * 10),
+                profitability_done_ms: Some(i * 10 + 5),
+                tx_sent_ms: None,
+                tx_mined_ms: None,
+            })
+            .collect();
+
+        let latencies = segment_latencies(&traces);
+        assert_eq!(
+            latencies,
+            vec![
+                SegmentLatency {
+                    segment: "detection",
+                    samples: 20,
+                    p50_ms: 100,
+                    p95_ms: 190,
+                },
+                SegmentLatency {
+                    segment: "profitability",
+                    samples: 20,
+                    p50_ms: 5,
+                    p95_ms: 5,
+                },
+            ]
+        );
+        assert!(segment_latencies(&[]).is_empty());
+    }
+}
@@ -43,9 +43,7 @@ impl Default for LiquidationMonitorConfig {
         Self {
             rpc_url: String::from("http://localhost:8545"),
             ws_url: None,
-            pool_address: "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5"
-                .parse()
-                .expect("Valid address"),
+            pool_address: crate::networks::base().pool,
             max_events_stored: 1000,
             log_to_file: false,
             log_file_path: Some(String::from("liquidations.jsonl")),
@@ -81,11 +79,24 @@ impl LiquidationMonitorConfig {
                 }
             });
         
-        let pool_address = env::var("POOL_ADDRESS")
+        let pool_address = match env::var("POOL_ADDRESS")
             .or_else(|_| env::var("LIQUIDATION_MONITOR_POOL_ADDRESS"))
-            .unwrap_or_else(|_| String::from("0xA238Dd80C259a72e81d7e4664a9801593F98d1c5"))
-            .parse::<Address>()
-            .map_err(|e| eyre::eyre!("Invalid pool address: {}", e))?;
+        {
+            Ok(value) => value
+                .parse::<Address>()
+                .map_err(|e| eyre::eyre!("Invalid pool address: {}", e))?,
+            Err(_) => {
+                let chain_id = env::var("CHAIN_ID")
+                    .ok()
+                    .and_then(|s| s.parse::<u64>().ok())
+                    .unwrap_or(crate::config::DEFAULT_CHAIN_ID);
+                crate::networks::builtin(chain_id)
+                    .map(|network| network.addresses.pool)
+                    .ok_or_else(|| {
+                        eyre::eyre!("No built-in pool for chain {}; set POOL_ADDRESS", chain_id)
+                    })?
+            }
+        };
         
         let max_events_stored = env::var("LIQUIDATION_MONITOR_MAX_EVENTS")
             .ok()
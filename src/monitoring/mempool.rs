@@ -0,0 +1,321 @@
+use alloy_primitives::{Address, B256, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::Transaction;
+use alloy_sol_types::{sol, SolCall};
+use dashmap::DashMap;
+use eyre::Result;
+use futures::StreamExt;
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
+
+use crate::database::{self, DatabasePool};
+use crate::models::{CompetingLiquidation, MempoolSource};
+use crate::monitoring::provider_set::{ProviderSet, ReconnectBackoff};
+
+sol! {
+    #[allow(missing_docs)]
+    interface IPoolLiquidation {
+        function liquidationCall(
+            address collateralAsset,
+            address debtAsset,
+            address user,
+            uint256 debtToCover,
+            bool receiveAToken
+        ) external;
+    }
+
+    #[allow(missing_docs)]
+    interface IL2PoolLiquidation {
+        function liquidationCall(bytes32 args1, bytes32 args2) external;
+    }
+}
+
+/// How long a competing liquidation stays relevant after it was seen pending
+const RIVAL_TTL: Duration = Duration::from_secs(30);
+
+/// Decoded `liquidationCall` arguments
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LiquidationCallArgs {
+    pub collateral_asset: Address,
+    pub debt_asset: Address,
+    pub user: Address,
+    pub debt_to_cover: U256,
+}
+
+/// Decode a pool `liquidationCall`, either the standard ABI or the L2Pool's packed form with
+/// asset ids resolved through `assets_by_id`. None for any other calldata.
+pub fn decode_liquidation_call(
+    input: &[u8],
+    assets_by_id: &HashMap<u16, Address>,
+) -> Option<LiquidationCallArgs> {
+    if let Ok(call) = IPoolLiquidation::liquidationCallCall::abi_decode(input, true) {
+        return Some(LiquidationCallArgs {
+            collateral_asset: call.collateralAsset,
+            debt_asset: call.debtAsset,
+            user: call.user,
+            debt_to_cover: call.debtToCover,
+        });
+    }
+
+    // args1 = collateral id (16 bits) | debt id (16 bits) | user (160 bits),
+    // args2 = debtToCover (128 bits, max = whole debt) | receiveAToken
+    let call = IL2PoolLiquidation::liquidationCallCall::abi_decode(input, true).ok()?;
+    let args1 = U256::from_be_bytes(call.args1.0);
+    let args2 = U256::from_be_bytes(call.args2.0);
+    let id_mask = U256::from(0xffffu64);
+    let collateral_id = (args1 & id_mask).to::<u16>();
+    let debt_id = ((args1 >> 16) & id_mask).to::<u16>();
+    let user = Address::from_word(B256::from(args1 >> 32));
+    let shortened = (args2 & U256::from(u128::MAX)).to::<u128>();
+    let debt_to_cover = if shortened == u128::MAX {
+        U256::MAX
+    } else {
+        U256::from(shortened)
+    };
+
+    Some(LiquidationCallArgs {
+        collateral_asset: *assets_by_id.get(&collateral_id)?,
+        debt_asset: *assets_by_id.get(&debt_id)?,
+        user,
+        debt_to_cover,
+    })
+}
+
+/// A pending transaction's `liquidationCall` to `pool` from anyone but our own wallets
+pub fn competing_liquidation(
+    tx: &Transaction,
+    pool: Address,
+    own_addresses: &[Address],
+    assets_by_id: &HashMap<u16, Address>,
+) -> Option<CompetingLiquidation> {
+    if tx.to != Some(pool) || own_addresses.contains(&tx.from) {
+        return None;
+    }
+    let args = decode_liquidation_call(&tx.input, assets_by_id)?;
+    Some(CompetingLiquidation {
+        tx_hash: format!("0x{:x}", tx.hash),
+        liquidator: tx.from,
+        user: args.user,
+        collateral_asset: args.collateral_asset,
+        debt_asset: args.debt_asset,
+        debt_to_cover: args.debt_to_cover,
+        priority_fee_per_gas: tx
+            .max_priority_fee_per_gas
+            .or(tx.gas_price)
+            .unwrap_or_default(),
+    })
+}
+
+/// Competing liquidations seen pending, by the user they target
+#[derive(Default)]
+pub struct CompetitorTracker {
+    rivals: DashMap<Address, (CompetingLiquidation, Instant)>,
+}
+
+impl CompetitorTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember `rival`, keeping the highest bidder when several race for the same user
+    pub fn observe(&self, rival: CompetingLiquidation) {
+        let now = Instant::now();
+        let mut entry = self.rivals.entry(rival.user).or_insert((rival.clone(), now));
+        let (current, seen_at) = entry.value_mut();
+        let outbids = rival.priority_fee_per_gas >= current.priority_fee_per_gas;
+        if outbids || seen_at.elapsed() > RIVAL_TTL {
+            *current = rival;
+            *seen_at = now;
+        }
+    }
+
+    /// The highest-bidding liquidation racing for `user`, if one was seen recently
+    pub fn racing_for(&self, user: Address) -> Option<CompetingLiquidation> {
+        let entry = self.rivals.get(&user)?;
+        let (rival, seen_at) = entry.value();
+        (seen_at.elapsed() <= RIVAL_TTL).then(|| rival.clone())
+    }
+
+    /// Drop rivals whose transactions have long since landed or been dropped
+    pub fn prune(&self) {
+        self.rivals.retain(|_, (_, seen_at)| seen_at.elapsed() <= RIVAL_TTL);
+    }
+}
+
+/// Watch pending transactions for liquidations of Aave users by other bots, record them in
+/// the competitors table and expose them to the liquidation path through `tracker`
+pub async fn start_mempool_monitoring<P>(
+    providers: ProviderSet<P>,
+    source: MempoolSource,
+    pool: Address,
+    own_addresses: Vec<Address>,
+    assets_by_id: HashMap<u16, Address>,
+    tracker: Arc<CompetitorTracker>,
+    db_pool: DatabasePool,
+) -> Result<()>
+where
+    P: Provider + 'static,
+{
+    if source == MempoolSource::Off {
+        debug!("Mempool monitoring disabled - MEMPOOL_MONITORING not configured");
+        return Ok(());
+    }
+    let Some(mut ws_provider) = providers.real_time() else {
+        warn!("Mempool monitoring needs a WebSocket provider - configure WS_URL");
+        return Ok(());
+    };
+
+    let mut backoff = ReconnectBackoff::default();
+    loop {
+        match stream_pending_liquidations(
+            &ws_provider,
+            source,
+            pool,
+            &own_addresses,
+            &assets_by_id,
+            &tracker,
+            &db_pool,
+        )
+        .await
+        {
+            Ok(()) => {
+                warn!("🔌 Pending transaction stream ended - reconnecting");
+                backoff.reset();
+            }
+            Err(e) => error!("❌ Pending transaction subscription failed: {}", e),
+        }
+        ws_provider = providers
+            .wait_for_real_time_reconnect(&ws_provider, &mut backoff)
+            .await;
+    }
+}
+
+async fn stream_pending_liquidations(
+    ws_provider: &Arc<dyn Provider>,
+    source: MempoolSource,
+    pool: Address,
+    own_addresses: &[Address],
+    assets_by_id: &HashMap<u16, Address>,
+    tracker: &CompetitorTracker,
+    db_pool: &DatabasePool,
+) -> Result<()> {
+    let mut stream = match source {
+        MempoolSource::Alchemy => {
+            // Alchemy filters by recipient server-side, so only pool calls are streamed
+            let root = ws_provider.root();
+            let id: B256 = root
+                .client()
+                .request(
+                    "eth_subscribe",
+                    (
+                        "alchemy_pendingTransactions",
+                        json!({ "toAddress": [pool], "hashesOnly": false }),
+                    ),
+                )
+                .await?;
+            root.get_subscription::<Transaction>(id).await?.into_stream()
+        }
+        _ => ws_provider
+            .subscribe_full_pending_transactions()
+            .await?
+            .into_stream(),
+    };
+    info!(
+        "👀 Watching pending transactions for competing liquidations ({})",
+        source.as_str()
+    );
+
+    while let Some(tx) = stream.next().await {
+        let Some(rival) = competing_liquidation(&tx, pool, own_addresses, assets_by_id) else {
+            continue;
+        };
+        info!(
+            "🏎️ Competitor {} is liquidating {} ({} debt of {}) with a {} wei tip: {}",
+            rival.liquidator,
+            rival.user,
+            rival.debt_to_cover,
+            rival.debt_asset,
+            rival.priority_fee_per_gas,
+            rival.tx_hash
+        );
+        if let Err(e) = database::record_competitor_sighting(db_pool, &rival).await {
+            warn!("Failed to record competitor {}: {}", rival.liquidator, e);
+        }
+        tracker.prune();
+        tracker.observe(rival);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rival(user: Address, tip: u128) -> CompetingLiquidation {
+        CompetingLiquidation {
+            tx_hash: format!("0x{:064x}", tip),
+            liquidator: Address::repeat_byte(0xee),
+            user,
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000u64),
+            priority_fee_per_gas: tip,
+        }
+    }
+
+    #[test]
+    fn test_decodes_standard_and_l2_pool_calls() {
+        let collateral = Address::repeat_byte(0x02);
+        let debt = Address::repeat_byte(0x03);
+        let user = Address::repeat_byte(0x01);
+        let assets_by_id = HashMap::from([(4u16, collateral), (7u16, debt)]);
+
+        let standard = IPoolLiquidation::liquidationCallCall {
+            collateralAsset: collateral,
+            debtAsset: debt,
+            user,
+            debtToCover: U256::from(5_000u64),
+            receiveAToken: false,
+        }
+        .abi_encode();
+        let expected = LiquidationCallArgs {
+            collateral_asset: collateral,
+            debt_asset: debt,
+            user,
+            debt_to_cover: U256::from(5_000u64),
+        };
+        assert_eq!(decode_liquidation_call(&standard, &assets_by_id), Some(expected));
+
+        let args1 = U256::from(4u64)
+            | (U256::from(7u64) << 16)
+            | (U256::from_be_slice(user.as_slice()) << 32);
+        let packed = IL2PoolLiquidation::liquidationCallCall {
+            args1: B256::from(args1),
+            args2: B256::from(U256::from(5_000u64)),
+        }
+        .abi_encode();
+        assert_eq!(decode_liquidation_call(&packed, &assets_by_id), Some(expected));
+
+        // Unknown asset ids and unrelated calldata are ignored
+        assert_eq!(decode_liquidation_call(&packed, &HashMap::new()), None);
+        assert_eq!(decode_liquidation_call(&[0x12, 0x34, 0x56, 0x78], &assets_by_id), None);
+    }
+
+    #[test]
+    fn test_tracker_keeps_highest_bidder_per_user() {
+        let tracker = CompetitorTracker::new();
+        let user = Address::repeat_byte(0x01);
+        assert!(tracker.racing_for(user).is_none());
+
+        tracker.observe(rival(user, 100));
+        tracker.observe(rival(user, 50));
+        assert_eq!(tracker.racing_for(user).unwrap().priority_fee_per_gas, 100);
+
+        tracker.observe(rival(user, 300));
+        assert_eq!(tracker.racing_for(user).unwrap().priority_fee_per_gas, 300);
+        assert!(tracker.racing_for(Address::repeat_byte(0x09)).is_none());
+    }
+}
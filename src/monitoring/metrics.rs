@@ -0,0 +1,334 @@
+use alloy_primitives::U256;
+use axum::http::header::CONTENT_TYPE;
+use axum::routing::get;
+use axum::Router;
+use eyre::Result;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
+use tokio::net::TcpListener;
+use tracing::info;
+
+use crate::circuit_breaker::CircuitBreakerState;
+use crate::metrics::{self, REGISTRY};
+
+/// Content type of the Prometheus text exposition format
+const PROMETHEUS_CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+/// How often the event-loop lag probe wakes up
+const LAG_PROBE_INTERVAL_MS: u64 = 1000;
+
+/// Metric name plus its sorted `label="value"` pairs
+type SampleKey = (&'static str, Vec<(&'static str, String)>);
+
+/// Current value of every exported sample, rendered on `/metrics`
+#[derive(Debug, Default)]
+pub struct BotMetrics {
+    samples: Mutex<BTreeMap<SampleKey, f64>>,
+}
+
+impl BotMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn key(name: &'static str, labels: &[(&'static str, &str)]) -> SampleKey {
+        let mut labels: Vec<(&'static str, String)> = labels
+            .iter()
+            .map(|(label, value)| (*label, value.to_string()))
+            .collect();
+        labels.sort();
+        (name, labels)
+    }
+
+    pub fn set_gauge(&self, name: &'static str, value: f64) {
+        self.set_labelled_gauge(name, &[], value);
+    }
+
+    pub fn set_labelled_gauge(&self, name: &'static str, labels: &[(&'static str, &str)], value: f64) {
+        self.samples.lock().insert(Self::key(name, labels), value);
+    }
+
+    pub fn inc_counter(&self, name: &'static str, labels: &[(&'static str, &str)], by: f64) {
+        *self
+            .samples
+            .lock()
+            .entry(Self::key(name, labels))
+            .or_insert(0.0) += by;
+    }
+
+    /// Current value of a sample, if it has been recorded
+    pub fn value(&self, name: &'static str, labels: &[(&'static str, &str)]) -> Option<f64> {
+        self.samples.lock().get(&Self::key(name, labels)).copied()
+    }
+
+    /// Render every registered metric in the Prometheus text format. Unlabelled metrics that
+    /// haven't been recorded yet are reported as 0 so dashboards don't show gaps at startup.
+    pub fn render(&self) -> String {
+        let samples = self.samples.lock();
+        let mut output = String::new();
+
+        for metric in REGISTRY {
+            let _ = writeln!(output, "# HELP {} {}", metric.name, metric.help);
+            let _ = writeln!(output, "# TYPE {} {}", metric.name, metric.kind.as_str());
+
+            let mut recorded = samples
+                .iter()
+                .filter(|((name, _), _)| *name == metric.name)
+                .peekable();
+            if recorded.peek().is_none() && metric.labels.is_empty() {
+                let _ = writeln!(output, "{} 0", metric.name);
+                continue;
+            }
+
+            for ((name, labels), value) in recorded {
+                if labels.is_empty() {
+                    let _ = writeln!(output, "{} {}", name, value);
+                } else {
+                    let labels: Vec<String> = labels
+                        .iter()
+                        .map(|(label, value)| format!("{}=\"{}\"", label, escape_label_value(value)))
+                        .collect();
+                    let _ = writeln!(output, "{}{{{}}} {}", name, labels.join(","), value);
+                }
+            }
+        }
+
+        output
+    }
+}
+
+/// Escape a label value for the text format (backslash, quote and newline)
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// Numeric encoding of the circuit breaker state used by the state gauge
+pub fn circuit_breaker_state_value(state: &CircuitBreakerState) -> f64 {
+    match state {
+        CircuitBreakerState::Closed => 0.0,
+        CircuitBreakerState::Open => 1.0,
+        CircuitBreakerState::HalfOpen => 2.0,
+        CircuitBreakerState::Disabled => 3.0,
+    }
+}
+
+/// Process-wide metrics, so deep call sites can record without threading a handle through
+pub fn global() -> &'static Arc<BotMetrics> {
+    static METRICS: OnceLock<Arc<BotMetrics>> = OnceLock::new();
+    METRICS.get_or_init(|| Arc::new(BotMetrics::new()))
+}
+
+/// Count a finished liquidation attempt ("executed", "failed" or "dry_run")
+pub fn record_liquidation_outcome(outcome: &str) {
+    global().inc_counter(metrics::LIQUIDATIONS_TOTAL, &[("outcome", outcome)], 1.0);
+}
+
+/// Count a liquidation blocked by the circuit breaker
+pub fn record_liquidation_blocked() {
+    global().inc_counter(metrics::LIQUIDATIONS_BLOCKED_TOTAL, &[], 1.0);
+}
+
+/// Add recorded liquidation profit for a strategy
+pub fn record_liquidation_profit(strategy: &str, profit_wei: U256) {
+    let profit = profit_wei.to_string().parse::<f64>().unwrap_or(0.0);
+    global().inc_counter(metrics::PROFIT_WEI_TOTAL, &[("strategy", strategy)], profit);
+}
+
+/// Count a failed RPC request, labelled by the subsystem that made it
+pub fn record_rpc_error(source: &str) {
+    global().inc_counter(metrics::RPC_ERRORS_TOTAL, &[("source", source)], 1.0);
+}
+
+/// Count a request held back by an endpoint's rate limiter
+pub fn record_rpc_throttled(endpoint: &str) {
+    global().inc_counter(metrics::RPC_REQUESTS_THROTTLED_TOTAL, &[("endpoint", endpoint)], 1.0);
+}
+
+/// Publish how many position updates are waiting for a batched database write
+pub fn record_position_write_queue_depth(depth: usize) {
+    global().set_gauge(metrics::POSITION_WRITE_QUEUE_DEPTH, depth as f64);
+}
+
+/// Count an event merged into an identical one already queued in `lane`
+pub fn record_event_coalesced(lane: &str) {
+    global().inc_counter(metrics::EVENTS_COALESCED_TOTAL, &[("lane", lane)], 1.0);
+}
+
+/// Count an event dropped because `lane` was full
+pub fn record_event_dropped(lane: &str) {
+    global().inc_counter(metrics::EVENTS_DROPPED_TOTAL, &[("lane", lane)], 1.0);
+}
+
+/// Count a position refresh deferred or merged by the per-user refresh throttle
+pub fn record_refresh_throttled() {
+    global().inc_counter(metrics::POSITION_REFRESHES_THROTTLED_TOTAL, &[], 1.0);
+}
+
+/// Count a completed block-driven scan
+pub fn record_block_scan() {
+    global().inc_counter(metrics::BLOCK_SCANS_TOTAL, &[], 1.0);
+}
+
+/// Count a block-driven scan cut short by its per-block time budget
+pub fn record_block_scan_budget_exhausted() {
+    global().inc_counter(metrics::BLOCK_SCAN_BUDGET_EXHAUSTED_TOTAL, &[], 1.0);
+}
+
+/// Count a message mirrored to the message bus `topic` ("published", "dropped" or "failed")
+pub fn record_event_bus_message(topic: &str, outcome: &str) {
+    global().inc_counter(
+        metrics::EVENT_BUS_MESSAGES_TOTAL,
+        &[("topic", topic), ("outcome", outcome)],
+        1.0,
+    );
+}
+
+/// Count an event webhook delivery ("delivered", "retried" or "failed")
+pub fn record_webhook_delivery(outcome: &str) {
+    global().inc_counter(metrics::WEBHOOK_DELIVERIES_TOTAL, &[("outcome", outcome)], 1.0);
+}
+
+/// Count a failed liquidation attempt by its failure category
+pub fn record_liquidation_failure(category: &str) {
+    global().inc_counter(
+        metrics::LIQUIDATION_FAILURES_TOTAL,
+        &[("category", category)],
+        1.0,
+    );
+}
+
+/// Count a retry policy decision after a failed liquidation ("retry", "abandon" or "exhausted")
+pub fn record_liquidation_retry_decision(decision: &str) {
+    global().inc_counter(
+        metrics::LIQUIDATION_RETRY_DECISIONS_TOTAL,
+        &[("decision", decision)],
+        1.0,
+    );
+}
+
+/// Count a sweep of `asset` to the cold wallet ("swept" or "failed")
+pub fn record_treasury_sweep(asset: &str, outcome: &str) {
+    global().inc_counter(
+        metrics::TREASURY_SWEEPS_TOTAL,
+        &[("asset", asset), ("outcome", outcome)],
+        1.0,
+    );
+}
+
+/// Publish the oracle value of an inventory asset, given in 8-decimal base currency units
+pub fn record_inventory_value(asset: &str, value_base: U256) {
+    let value = value_base.to_string().parse::<f64>().unwrap_or(0.0) / 1e8;
+    global().set_labelled_gauge(metrics::INVENTORY_VALUE_USD, &[("asset", asset)], value);
+}
+
+/// Count a rebalancing swap of `asset` into the inventory target ("swapped", "no_route" or
+/// "failed")
+pub fn record_inventory_rebalance(asset: &str, outcome: &str) {
+    global().inc_counter(
+        metrics::INVENTORY_REBALANCES_TOTAL,
+        &[("asset", asset), ("outcome", outcome)],
+        1.0,
+    );
+}
+
+/// Periodically measure how late the runtime wakes a timer; a busy or blocked event loop
+/// shows up as growing lag
+pub async fn run_event_loop_lag_monitor(bot_metrics: Arc<BotMetrics>) -> Result<()> {
+    let period = Duration::from_millis(LAG_PROBE_INTERVAL_MS);
+    loop {
+        let started = Instant::now();
+        tokio::time::sleep(period).await;
+        let lag = started.elapsed().saturating_sub(period);
+        bot_metrics.set_gauge(metrics::EVENT_LOOP_LAG_SECONDS, lag.as_secs_f64());
+    }
+}
+
+/// Build the metrics router
+pub fn metrics_router(bot_metrics: Arc<BotMetrics>) -> Router {
+    Router::new().route(
+        "/metrics",
+        get(move || {
+            let bot_metrics = bot_metrics.clone();
+            async move { ([(CONTENT_TYPE, PROMETHEUS_CONTENT_TYPE)], bot_metrics.render()) }
+        }),
+    )
+}
+
+/// Bind the metrics exporter to `listen_addr` and serve until the process exits
+pub async fn start_metrics_server(listen_addr: &str, bot_metrics: Arc<BotMetrics>) -> Result<()> {
+    let listener = TcpListener::bind(listen_addr).await?;
+    info!("📊 Prometheus metrics served on http://{}/metrics", listener.local_addr()?);
+    serve_metrics(listener, bot_metrics).await
+}
+
+/// Serve the metrics router on an already bound listener
+pub async fn serve_metrics(listener: TcpListener, bot_metrics: Arc<BotMetrics>) -> Result<()> {
+    axum::serve(listener, metrics_router(bot_metrics)).await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_includes_every_registered_metric() {
+        let bot_metrics = BotMetrics::new();
+        bot_metrics.set_gauge(metrics::POSITIONS_TRACKED, 42.0);
+        bot_metrics.inc_counter(metrics::LIQUIDATIONS_TOTAL, &[("outcome", "executed")], 1.0);
+        bot_metrics.inc_counter(metrics::LIQUIDATIONS_TOTAL, &[("outcome", "executed")], 1.0);
+        bot_metrics.inc_counter(metrics::LIQUIDATIONS_TOTAL, &[("outcome", "failed")], 1.0);
+
+        let output = bot_metrics.render();
+
+        for metric in REGISTRY {
+            assert!(output.contains(&format!("# TYPE {} {}", metric.name, metric.kind.as_str())));
+        }
+        assert!(output.contains("liquidation_bot_positions_tracked 42\n"));
+        assert!(output.contains("liquidation_bot_liquidations_total{outcome=\"executed\"} 2\n"));
+        assert!(output.contains("liquidation_bot_liquidations_total{outcome=\"failed\"} 1\n"));
+        // Unrecorded unlabelled metrics default to zero; labelled ones have no samples yet
+        assert!(output.contains("liquidation_bot_positions_at_risk 0\n"));
+        assert!(!output.contains("liquidation_bot_rpc_errors_total{"));
+    }
+
+    #[test]
+    fn test_counters_only_increase_and_labels_are_escaped() {
+        let bot_metrics = BotMetrics::new();
+        bot_metrics.inc_counter(metrics::PROFIT_WEI_TOTAL, &[("strategy", "a\"b")], 5.0);
+        bot_metrics.inc_counter(metrics::PROFIT_WEI_TOTAL, &[("strategy", "a\"b")], 7.0);
+
+        assert_eq!(bot_metrics.value(metrics::PROFIT_WEI_TOTAL, &[("strategy", "a\"b")]), Some(12.0));
+        assert!(bot_metrics
+            .render()
+            .contains("liquidation_bot_profit_wei_total{strategy=\"a\\\"b\"} 12\n"));
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint_serves_text_format() {
+        let bot_metrics = Arc::new(BotMetrics::new());
+        bot_metrics.set_gauge(
+            metrics::CIRCUIT_BREAKER_STATE,
+            circuit_breaker_state_value(&CircuitBreakerState::Open),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_metrics(listener, bot_metrics));
+
+        let response = reqwest::get(format!("http://{}/metrics", addr)).await.unwrap();
+
+        assert_eq!(response.status(), reqwest::StatusCode::OK);
+        assert!(response.headers()["content-type"]
+            .to_str()
+            .unwrap()
+            .starts_with("text/plain"));
+        let body = response.text().await.unwrap();
+        assert!(body.contains("liquidation_bot_circuit_breaker_state 1\n"));
+    }
+}
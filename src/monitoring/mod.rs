@@ -1,13 +1,51 @@
+pub mod aave_oracle;
+pub mod alerts;
+pub mod block_scanner;
+pub mod config_reload;
 pub mod oracle;
 pub mod scanner;
 pub mod websocket;
 pub mod discovery;
 pub mod liquidation_monitor;
 pub mod liquidation_config;
+pub mod metrics;
+pub mod health_engine;
+pub mod heartbeat;
+pub mod latency;
+pub mod mempool;
+pub mod operator_balance;
+pub mod pool_pause;
+pub mod price_trigger;
+pub mod price_history;
+pub mod protocol_monitor;
+pub mod provider_set;
+pub mod refresh_throttle;
+pub mod reorg;
+pub mod scan_tiers;
+pub mod value_at_risk;
+pub mod wallet_balance;
 
+pub use aave_oracle::*;
+pub use alerts::*;
+pub use block_scanner::*;
+pub use config_reload::*;
 pub use oracle::*;
 pub use scanner::*;
 pub use discovery::*;
 pub use websocket::*;
 pub use liquidation_monitor::*;
-pub use liquidation_config::*;
\ No newline at end of file
+pub use liquidation_config::*;
+pub use health_engine::*;
+pub use heartbeat::*;
+pub use mempool::*;
+pub use operator_balance::*;
+pub use pool_pause::*;
+pub use price_trigger::*;
+pub use price_history::*;
+pub use protocol_monitor::*;
+pub use provider_set::*;
+pub use refresh_throttle::*;
+pub use reorg::*;
+pub use scan_tiers::*;
+pub use value_at_risk::*;
+pub use wallet_balance::*;
\ No newline at end of file
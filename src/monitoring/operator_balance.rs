@@ -0,0 +1,178 @@
+use alloy_primitives::U256;
+use parking_lot::RwLock;
+use tracing::{error, info, warn};
+
+/// Balance below `min * LOW_BALANCE_MULTIPLIER` raises the first (warning) alert
+const LOW_BALANCE_MULTIPLIER: u64 = 4;
+/// Balance below `min * CRITICAL_BALANCE_MULTIPLIER` raises the critical alert
+const CRITICAL_BALANCE_MULTIPLIER: u64 = 2;
+
+/// How close the operator's native balance is to the configured minimum, ordered by severity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum BalanceAlertLevel {
+    /// Comfortably above the minimum
+    Healthy,
+    /// Below 4x the minimum - top up soon
+    Low,
+    /// Below 2x the minimum - top up now
+    Critical,
+    /// Below the minimum - liquidations may fail for lack of gas
+    BelowMinimum,
+}
+
+/// Classify a native balance against the configured minimum
+pub fn classify_balance(balance: U256, min_balance: U256) -> BalanceAlertLevel {
+    if balance < min_balance {
+        BalanceAlertLevel::BelowMinimum
+    } else if balance < min_balance.saturating_mul(U256::from(CRITICAL_BALANCE_MULTIPLIER)) {
+        BalanceAlertLevel::Critical
+    } else if balance < min_balance.saturating_mul(U256::from(LOW_BALANCE_MULTIPLIER)) {
+        BalanceAlertLevel::Low
+    } else {
+        BalanceAlertLevel::Healthy
+    }
+}
+
+/// Tracks the signer's native balance, alerting when its level changes and optionally
+/// halting liquidations once it drops below the minimum
+#[derive(Debug)]
+pub struct OperatorBalanceGuard {
+    min_balance: U256,
+    halt_below_minimum: bool,
+    level: RwLock<BalanceAlertLevel>,
+}
+
+impl OperatorBalanceGuard {
+    pub fn new(min_balance: U256, halt_below_minimum: bool) -> Self {
+        Self {
+            min_balance,
+            halt_below_minimum,
+            level: RwLock::new(BalanceAlertLevel::Healthy),
+        }
+    }
+
+    /// A minimum of zero disables balance monitoring
+    pub fn is_enabled(&self) -> bool {
+        !self.min_balance.is_zero()
+    }
+
+    pub fn level(&self) -> BalanceAlertLevel {
+        *self.level.read()
+    }
+
+    /// Liquidations are only halted when configured to and the balance is below the minimum
+    pub fn is_liquidation_allowed(&self) -> bool {
+        !(self.halt_below_minimum && self.level() == BalanceAlertLevel::BelowMinimum)
+    }
+
+    /// Record a freshly fetched balance, alerting only when the level changes so a low
+    /// balance doesn't re-alert after every liquidation
+    pub fn record_balance(&self, balance: U256) -> BalanceAlertLevel {
+        if !self.is_enabled() {
+            return BalanceAlertLevel::Healthy;
+        }
+
+        let level = classify_balance(balance, self.min_balance);
+        let previous = std::mem::replace(&mut *self.level.write(), level);
+        if level == previous {
+            return level;
+        }
+
+        match level {
+            BalanceAlertLevel::Healthy => info!(
+                "✅ Operator balance recovered: {} wei (minimum {} wei)",
+                balance, self.min_balance
+            ),
+            BalanceAlertLevel::Low => warn!(
+                "⚠️ Operator balance low: {} wei (minimum {} wei) - top up soon",
+                balance, self.min_balance
+            ),
+            BalanceAlertLevel::Critical => error!(
+                "🚨 ALERT: Operator balance critical: {} wei (minimum {} wei) - top up now",
+                balance, self.min_balance
+            ),
+            BalanceAlertLevel::BelowMinimum if self.halt_below_minimum => error!(
+                "🚨 ALERT: Operator balance {} wei below minimum {} wei - halting liquidations",
+                balance, self.min_balance
+            ),
+            BalanceAlertLevel::BelowMinimum => error!(
+                "🚨 ALERT: Operator balance {} wei below minimum {} wei - liquidations may fail",
+                balance, self.min_balance
+            ),
+        }
+
+        level
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 0.01 ETH minimum
+    const MIN_BALANCE: u64 = 10_000_000_000_000_000;
+
+    #[test]
+    fn test_classify_balance_thresholds() {
+        let min = U256::from(MIN_BALANCE);
+
+        assert_eq!(classify_balance(min * U256::from(4u64), min), BalanceAlertLevel::Healthy);
+        assert_eq!(
+            classify_balance(min * U256::from(4u64) - U256::from(1u64), min),
+            BalanceAlertLevel::Low
+        );
+        assert_eq!(classify_balance(min, min), BalanceAlertLevel::Critical);
+        assert_eq!(
+            classify_balance(min - U256::from(1u64), min),
+            BalanceAlertLevel::BelowMinimum
+        );
+    }
+
+    #[test]
+    fn test_declining_balance_escalates_and_halts() {
+        let min = U256::from(MIN_BALANCE);
+        let guard = OperatorBalanceGuard::new(min, true);
+
+        // Each liquidation spends gas; the balance steps down through every level
+        let balances = [5u64, 3, 1, 0].map(|step| min * U256::from(step) + min / U256::from(2u64));
+        let levels: Vec<_> = balances
+            .iter()
+            .map(|balance| {
+                let level = guard.record_balance(*balance);
+                (level, guard.is_liquidation_allowed())
+            })
+            .collect();
+
+        assert_eq!(
+            levels,
+            vec![
+                (BalanceAlertLevel::Healthy, true),
+                (BalanceAlertLevel::Low, true),
+                (BalanceAlertLevel::Critical, true),
+                (BalanceAlertLevel::BelowMinimum, false),
+            ]
+        );
+
+        // Topping up resumes liquidations
+        assert_eq!(guard.record_balance(min * U256::from(10u64)), BalanceAlertLevel::Healthy);
+        assert!(guard.is_liquidation_allowed());
+    }
+
+    #[test]
+    fn test_below_minimum_only_alerts_without_halt() {
+        let min = U256::from(MIN_BALANCE);
+        let guard = OperatorBalanceGuard::new(min, false);
+
+        assert_eq!(guard.record_balance(U256::ZERO), BalanceAlertLevel::BelowMinimum);
+        assert!(guard.is_liquidation_allowed());
+    }
+
+    #[test]
+    fn test_zero_minimum_disables_monitoring() {
+        let guard = OperatorBalanceGuard::new(U256::ZERO, true);
+
+        assert!(!guard.is_enabled());
+        assert_eq!(guard.record_balance(U256::ZERO), BalanceAlertLevel::Healthy);
+        assert!(guard.is_liquidation_allowed());
+    }
+}
@@ -1,20 +1,59 @@
-use crate::events::BotEvent;
-use crate::models::{AssetConfig, PriceFeed};
-use alloy_primitives::{Address, U256};
+use crate::config::BotConfig;
+use crate::database::{self, DatabasePool};
+use crate::events::{BotEvent, EventSender};
+use crate::models::{AssetConfig, PriceFeed, SecondaryPriceFeed};
+use crate::monitoring::aave_oracle;
+use crate::monitoring::alerts::{AlertEvent, AlertNotifier};
+use crate::monitoring::metrics;
+use crate::monitoring::provider_set::{ProviderSet, ReconnectBackoff};
+use crate::networks::NetworkAddresses;
+use crate::protocols::call_contract;
+use alloy_primitives::{Address, I256, U256, U512};
 use alloy_provider::Provider;
 use alloy_rpc_types::Filter;
+use alloy_sol_types::{sol, SolEvent};
 use chrono::Utc;
 use dashmap::DashMap;
 use eyre::Result;
 use futures::StreamExt;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::mpsc;
+use std::time::Duration;
 use tracing::{debug, error, info, warn};
 
-pub fn init_asset_configs() -> HashMap<Address, AssetConfig> {
+sol! {
+    #[allow(missing_docs)]
+    interface IChainlinkFeed {
+        function latestRoundData() external view returns (
+            uint80 roundId,
+            int256 answer,
+            uint256 startedAt,
+            uint256 updatedAt,
+            uint80 answeredInRound
+        );
+        function decimals() external view returns (uint8);
+        function aggregator() external view returns (address);
+
+        event AnswerUpdated(int256 indexed current, uint256 indexed roundId, uint256 updatedAt);
+    }
+}
+
+/// Decimals prices are normalized to (Chainlink USD feeds and Aave's base currency)
+pub const PRICE_DECIMALS: u8 = 8;
+
+/// Chainlink feeds watched for price moves on `chain_id`. Only Base mainnet's feeds are known;
+/// other chains rely on the periodic scans instead.
+pub fn init_asset_configs(chain_id: u64) -> HashMap<Address, AssetConfig> {
     let mut configs = HashMap::new();
 
+    if chain_id != crate::config::DEFAULT_CHAIN_ID {
+        warn!(
+            "No Chainlink feeds known for chain {} - price moves are picked up by periodic scans",
+            chain_id
+        );
+        return configs;
+    }
+
     // Base mainnet asset configurations
     // Updated for Base mainnet deployment
 
@@ -31,6 +70,7 @@ pub fn init_asset_configs() -> HashMap<Address, AssetConfig> {
                     symbol: "WETH".to_string(),
                     chainlink_feed: weth_feed_address, // ETH/USD on Base mainnet
                     price_change_threshold: 0.005,     // 0.5% price change threshold
+                    heartbeat_secs: 1_200,             // 20 minute heartbeat
                 },
             );
         }
@@ -55,6 +95,7 @@ pub fn init_asset_configs() -> HashMap<Address, AssetConfig> {
                     symbol: "USDC".to_string(),
                     chainlink_feed: usdc_feed_address, // USDC/USD on Base mainnet
                     price_change_threshold: 0.001,     // 0.1% price change threshold (stablecoin)
+                    heartbeat_secs: 86_400,            // Daily heartbeat
                 },
             );
         }
@@ -81,39 +122,87 @@ pub fn init_asset_configs() -> HashMap<Address, AssetConfig> {
     configs
 }
 
+/// Price change reported for a feed's first observed price, in basis points (100%)
+const FIRST_PRICE_CHANGE_BPS: u64 = 10_000;
+
+/// Absolute price change between `old_price` and `new_price` in basis points of `old_price`.
+///
+/// The multiplication is done in 512 bits so it can't overflow for any pair of U256 prices;
+/// the result saturates at `U256::MAX`. A first price (old price zero) reports a 100% change,
+/// and a zero new price reports no change.
+pub fn price_change_bps(old_price: U256, new_price: U256) -> U256 {
+    if new_price.is_zero() {
+        return U256::ZERO;
+    }
+    if old_price.is_zero() {
+        return U256::from(FIRST_PRICE_CHANGE_BPS);
+    }
+
+    let diff = U512::from(old_price.abs_diff(new_price));
+    let bps = diff * U512::from(10_000u64) / U512::from(old_price);
+    U256::saturating_from(bps)
+}
+
+/// Signed price change in percent computed from the full values, for logging
+pub fn price_change_pct(old_price: U256, new_price: U256) -> f64 {
+    if old_price.is_zero() {
+        return 0.0;
+    }
+
+    let old = f64::from(old_price);
+    (f64::from(new_price) - old) / old * 100.0
+}
+
 pub async fn start_oracle_monitoring<P>(
-    provider: Arc<P>,
-    ws_provider: Arc<dyn Provider>,
-    ws_url: &str,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
+    providers: ProviderSet<P>,
+    event_tx: EventSender,
     asset_configs: HashMap<Address, AssetConfig>,
     price_feeds: Arc<DashMap<Address, PriceFeed>>,
+    verbose_per_item_logging: bool,
+    price_history_db: Option<DatabasePool>,
 ) -> Result<()>
 where
     P: Provider + 'static,
 {
     info!("🔮 Starting Chainlink oracle price monitoring...");
 
-    // Check if we're using WebSocket
-    let using_websocket = ws_url.starts_with("wss://") && !ws_url.contains("sepolia.base.org");
+    let provider = providers.call().clone();
 
-    info!("🔧 Oracle monitoring mode decision:");
-    info!("   WebSocket URL: {}", ws_url);
-    info!("   Starts with wss://: {}", ws_url.starts_with("wss://"));
-    info!(
-        "   Contains sepolia.base.org: {}",
-        ws_url.contains("sepolia.base.org")
-    );
-    info!("   Using WebSocket mode: {}", using_websocket);
-
-    if !using_websocket {
-        info!("🔄 Oracle monitoring will use periodic polling instead of real-time events");
-        return start_periodic_price_polling(provider, event_tx, asset_configs, price_feeds).await;
+    // Both polling and subscriptions keep these entries current
+    for (asset_address, asset_config) in &asset_configs {
+        price_feeds.insert(
+            *asset_address,
+            PriceFeed {
+                asset_address: *asset_address,
+                feed_address: asset_config.chainlink_feed,
+                asset_symbol: asset_config.symbol.clone(),
+                last_price: U256::ZERO,
+                last_updated: Utc::now(),
+                price_change_threshold: asset_config.price_change_threshold,
+            },
+        );
     }
 
+    // Check if we have a real-time provider to subscribe with
+    let ws_provider = match providers.real_time() {
+        Some(ws_provider) => ws_provider,
+        None => {
+            info!("🔄 Oracle monitoring will use periodic polling instead of real-time events");
+            return start_periodic_price_polling(
+                provider,
+                event_tx,
+                asset_configs,
+                price_feeds,
+                verbose_per_item_logging,
+                price_history_db,
+            )
+            .await;
+        }
+    };
+
     info!("📡 Using real-time WebSocket oracle monitoring");
     info!("⚠️ Note: Oracle events may be infrequent on testnet");
-    info!("💡 To see active monitoring, you can force polling mode by setting a non-wss:// WS_URL");
+    info!("💡 To see active monitoring, you can force polling mode with REAL_TIME_MONITORING_ENABLED=false");
 
     // Also start periodic polling as backup to show activity
     info!("🔄 Starting backup polling to show oracle activity...");
@@ -122,52 +211,59 @@ where
         event_tx.clone(),
         asset_configs.clone(),
         price_feeds.clone(),
+        verbose_per_item_logging,
+        price_history_db.clone(),
     )
     .await;
 
     // Subscribe to AnswerUpdated events from each price feed
     for (asset_address, asset_config) in &asset_configs {
-        let price_feed = PriceFeed {
-            asset_address: *asset_address,
-            feed_address: asset_config.chainlink_feed,
-            asset_symbol: asset_config.symbol.clone(),
-            last_price: U256::ZERO,
-            last_updated: Utc::now(),
-            price_change_threshold: asset_config.price_change_threshold,
-        };
-
-        price_feeds.insert(*asset_address, price_feed);
-
-        // Subscribe to AnswerUpdated events for this price feed
-        let feed_filter = Filter::new().address(asset_config.chainlink_feed);
-
         let event_tx = event_tx.clone();
+        let providers = providers.clone();
+        let provider = provider.clone();
         let ws_provider = ws_provider.clone();
+        let price_history_db = price_history_db.clone();
         let asset_addr = *asset_address;
+        let feed_address = asset_config.chainlink_feed;
         let symbol = asset_config.symbol.clone();
 
         tokio::spawn(async move {
-            info!("Subscribing to {} price feed events...", symbol);
-
-            let sub = match ws_provider.subscribe_logs(&feed_filter).await {
-                Ok(sub) => {
-                    info!("✅ Successfully subscribed to {} price feed!", symbol);
-                    sub
-                }
-                Err(e) => {
-                    error!("❌ Failed to subscribe to {} price feed: {}", symbol, e);
-                    return;
-                }
+            let source = feed_event_source(&provider, feed_address).await;
+            let feed = FeedSubscription {
+                asset_address: asset_addr,
+                decimals: feed_decimals(&provider, feed_address, &symbol).await,
+                filter: Filter::new()
+                    .address(source)
+                    .event_signature(IChainlinkFeed::AnswerUpdated::SIGNATURE_HASH),
+                symbol,
             };
+            let symbol = &feed.symbol;
 
-            let mut stream = sub.into_stream();
-            info!("👂 Listening for {} price updates...", symbol);
+            let mut ws_provider = ws_provider;
+            let mut backoff = ReconnectBackoff::default();
+            let mut last_seen_block = None;
 
-            while let Some(log) = stream.next().await {
-                if let Err(e) = handle_price_update_event(log, &event_tx, asset_addr, &symbol).await
+            // Resubscribe on a fresh connection whenever the stream drops
+            loop {
+                match stream_price_feed(
+                    &ws_provider,
+                    &provider,
+                    &feed,
+                    &mut last_seen_block,
+                    &event_tx,
+                    price_history_db.as_ref(),
+                )
+                .await
                 {
-                    error!("Error handling price update for {}: {}", symbol, e);
+                    Ok(()) => {
+                        warn!("🔌 {} price feed stream ended - reconnecting", symbol);
+                        backoff.reset();
+                    }
+                    Err(e) => error!("❌ Failed to subscribe to {} price feed: {}", symbol, e),
                 }
+                ws_provider = providers
+                    .wait_for_real_time_reconnect(&ws_provider, &mut backoff)
+                    .await;
             }
         });
     }
@@ -176,11 +272,103 @@ where
     Ok(())
 }
 
+/// A price feed's `AnswerUpdated` subscription
+struct FeedSubscription {
+    asset_address: Address,
+    symbol: String,
+    decimals: u8,
+    /// Matches the feed's `AnswerUpdated` logs at the contract that emits them
+    filter: Filter,
+}
+
+impl FeedSubscription {
+    async fn handle(
+        &self,
+        log: alloy_rpc_types::Log,
+        event_tx: &EventSender,
+        price_history_db: Option<&DatabasePool>,
+    ) {
+        if let Err(e) = handle_price_update_event(
+            log,
+            event_tx,
+            self.asset_address,
+            self.decimals,
+            &self.symbol,
+            price_history_db,
+        )
+        .await
+        {
+            error!("Error handling price update for {}: {}", self.symbol, e);
+        }
+    }
+}
+
+/// Subscribe to one price feed and handle its updates until the stream ends. After a
+/// reconnect, updates in blocks after `last_seen_block` that were missed while disconnected
+/// are fetched first.
+async fn stream_price_feed<P>(
+    ws_provider: &Arc<dyn Provider>,
+    provider: &Arc<P>,
+    feed: &FeedSubscription,
+    last_seen_block: &mut Option<u64>,
+    event_tx: &EventSender,
+    price_history_db: Option<&DatabasePool>,
+) -> Result<()>
+where
+    P: Provider,
+{
+    let symbol = &feed.symbol;
+    let feed_filter = &feed.filter;
+    info!("Subscribing to {} price feed events...", symbol);
+    let sub = ws_provider.subscribe_logs(feed_filter).await?;
+    info!("✅ Successfully subscribed to {} price feed!", symbol);
+
+    // Subscribe first, then fill the gap up to the current head
+    let head = provider.get_block_number().await?;
+    if let Some(last_seen) = *last_seen_block {
+        if last_seen < head {
+            let gap_filter = feed_filter
+                .clone()
+                .from_block(last_seen + 1)
+                .to_block(head);
+            let missed = provider.get_logs(&gap_filter).await?;
+            if !missed.is_empty() {
+                info!(
+                    "⏮️ Replaying {} {} price updates missed while disconnected",
+                    missed.len(),
+                    symbol
+                );
+            }
+            for log in missed {
+                feed.handle(log, event_tx, price_history_db).await;
+            }
+        }
+    }
+    let filled_to = head;
+    *last_seen_block = Some(head);
+
+    let mut stream = sub.into_stream();
+    info!("👂 Listening for {} price updates...", symbol);
+
+    while let Some(log) = stream.next().await {
+        let block = log.block_number.unwrap_or(filled_to + 1);
+        if log.removed || block <= filled_to {
+            continue;
+        }
+        *last_seen_block = Some(block);
+        feed.handle(log, event_tx, price_history_db).await;
+    }
+
+    Ok(())
+}
+
 pub async fn start_periodic_price_polling<P>(
     provider: Arc<P>,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
+    event_tx: EventSender,
     asset_configs: HashMap<Address, AssetConfig>,
     price_feeds: Arc<DashMap<Address, PriceFeed>>,
+    verbose_per_item_logging: bool,
+    price_history_db: Option<DatabasePool>,
 ) -> Result<()>
 where
     P: Provider + 'static,
@@ -201,91 +389,64 @@ where
     }
 
     tokio::spawn(async move {
+        let mut decimals = HashMap::new();
+        for (asset_address, asset_config) in &asset_configs {
+            let feed_decimals =
+                feed_decimals(&provider, asset_config.chainlink_feed, &asset_config.symbol).await;
+            decimals.insert(*asset_address, feed_decimals);
+        }
+
         let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(30));
 
         loop {
             interval.tick().await;
-            info!(
+            debug!(
                 "🔍 Polling oracle prices for {} assets...",
                 asset_configs.len()
             );
 
+            let mut fetched = 0;
+            let mut significant_changes = 0;
+            let mut failed = 0;
+
             for (asset_address, asset_config) in &asset_configs {
-                info!(
+                crate::per_item_log!(
+                    verbose_per_item_logging,
                     "📞 Calling {} oracle at {}",
-                    asset_config.symbol, asset_config.chainlink_feed
+                    asset_config.symbol,
+                    asset_config.chainlink_feed
                 );
 
                 match fetch_price_from_oracle(
                     &provider,
                     asset_config.chainlink_feed,
+                    decimals.get(asset_address).copied().unwrap_or(PRICE_DECIMALS),
                     &asset_config.symbol,
                 )
                 .await
                 {
                     Ok(new_price) => {
-                        info!("✅ {} price fetched: {}", asset_config.symbol, new_price);
+                        fetched += 1;
+                        crate::per_item_log!(
+                            verbose_per_item_logging,
+                            "✅ {} price fetched: {}",
+                            asset_config.symbol,
+                            new_price
+                        );
+
+                        if let Some(db_pool) = &price_history_db {
+                            let now = Utc::now().timestamp().max(0) as u64;
+                            if let Err(e) =
+                                database::record_price(db_pool, *asset_address, new_price, now).await
+                            {
+                                warn!("Failed to record {} price history: {}", asset_config.symbol, e);
+                            }
+                        }
 
                         // Check if price changed significantly
                         if let Some(mut feed) = price_feeds.get_mut(asset_address) {
                             let old_price = feed.last_price;
-                            let price_change = if old_price > U256::ZERO && new_price > U256::ZERO {
-                                let diff = if new_price > old_price {
-                                    new_price - old_price
-                                } else {
-                                    old_price - new_price
-                                };
-
-                                // Prevent overflow by checking if multiplication would overflow
-                                // Use checked arithmetic for safe calculation
-                                match diff.checked_mul(U256::from(10000)) {
-                                    Some(multiplied) => {
-                                        // We already know old_price > U256::ZERO from outer condition
-                                        multiplied / old_price // Basis points
-                                    }
-                                    None => {
-                                        // Overflow detected, calculate manually with preserved precision
-                                        warn!("Price change calculation overflow for {}, using fallback calculation", asset_config.symbol);
-
-                                        // If diff >= old_price, it's at least 100% change
-                                        if diff >= old_price {
-                                            // Calculate how many times larger diff is compared to old_price
-                                            let multiple = diff / old_price;
-                                            // Cap at a reasonable maximum (e.g., 1000% = 100,000 basis points)
-                                            // Use checked arithmetic to prevent overflow
-                                            match multiple.checked_mul(U256::from(10000)) {
-                                                Some(result) => result.min(U256::from(100000)),
-                                                None => U256::from(100000), // Cap at maximum if overflow
-                                            }
-                                        } else {
-                                            // For changes less than 100%, use step-by-step calculation to avoid truncation
-                                            // Calculate (diff * 100) / old_price first, then multiply by 100
-                                            match diff.checked_mul(U256::from(100)) {
-                                                Some(diff_100) => {
-                                                    let percentage_100 = diff_100 / old_price;
-                                                    // Use checked arithmetic to prevent overflow
-                                                    match percentage_100
-                                                        .checked_mul(U256::from(100))
-                                                    {
-                                                        Some(result) => result,
-                                                        None => U256::from(100000), // Cap at maximum if overflow
-                                                    }
-                                                }
-                                                None => {
-                                                    // Even diff * 100 overflows, this is still a massive change
-                                                    U256::from(100000) // Cap at 1000%
-                                                }
-                                            }
-                                        }
-                                    }
-                                }
-                            } else if old_price == U256::ZERO && new_price > U256::ZERO {
-                                // First price update - use a reasonable threshold instead of MAX
-                                // Set to a high but not MAX value that will trigger price change events
-                                U256::from(10000) // 100% change in basis points
-                            } else {
-                                U256::ZERO // No change if both prices are zero or new price is zero
-                            };
+                            let price_change = price_change_bps(old_price, new_price);
 
                             let threshold_bp =
                                 U256::from((asset_config.price_change_threshold * 10000.0) as u64);
@@ -296,20 +457,11 @@ where
                                 feed.last_price = new_price;
                                 feed.last_updated = Utc::now();
 
-                                let change_pct = if old_price > U256::ZERO {
-                                    // Safe conversion to f64 for display
-                                    let change_u64 = if price_change <= U256::from(u64::MAX) {
-                                        price_change.try_into().unwrap_or(0u64)
-                                    } else {
-                                        1_000_000u64 // Cap at 1M basis points for display
-                                    };
-                                    change_u64.min(1_000_000) as f64 / 100.0
-                                } else {
-                                    0.0
-                                };
+                                let change_pct = price_change_pct(old_price, new_price);
 
+                                significant_changes += 1;
                                 info!(
-                                    "🚨 SIGNIFICANT PRICE CHANGE for {}: {} → {} ({}%)",
+                                    "🚨 SIGNIFICANT PRICE CHANGE for {}: {} → {} ({:+.2}%)",
                                     asset_config.symbol, old_price, new_price, change_pct
                                 );
 
@@ -332,16 +484,23 @@ where
                                     u64::MAX
                                 };
 
-                                info!(
+                                crate::per_item_log!(
+                                    verbose_per_item_logging,
                                     "📊 {} price stable: {} (change: {}bp, need: {}bp)",
-                                    asset_config.symbol, new_price, change_bp, threshold_bp_val
+                                    asset_config.symbol,
+                                    new_price,
+                                    change_bp,
+                                    threshold_bp_val
                                 );
 
                                 // Trigger a lighter oracle price change event even for smaller movements
                                 // This ensures that at-risk users are still recalculated periodically
                                 if old_price > U256::ZERO && price_change > U256::from(10) {
                                     // At least 10bp = 0.1% change
-                                    info!("🔄 Triggering light health check due to minor price movement");
+                                    crate::per_item_log!(
+                                        verbose_per_item_logging,
+                                        "🔄 Triggering light health check due to minor price movement"
+                                    );
                                     let _ = event_tx.send(BotEvent::OraclePriceChanged(
                                         *asset_address,
                                         new_price,
@@ -353,6 +512,8 @@ where
                         }
                     }
                     Err(e) => {
+                        failed += 1;
+                        metrics::record_rpc_error("oracle");
                         error!(
                             "❌ Failed to fetch {} price from {}: {}",
                             asset_config.symbol, asset_config.chainlink_feed, e
@@ -361,78 +522,581 @@ where
                 }
             }
 
-            info!("✅ Oracle price polling round completed");
+            info!(
+                "✅ Oracle price polling round completed: {}/{} prices fetched, {} significant changes, {} failed",
+                fetched,
+                asset_configs.len(),
+                significant_changes,
+                failed
+            );
         }
     });
 
     Ok(())
 }
 
+/// Decimals of a feed's answer, falling back to the USD feed standard if the call fails
+pub async fn feed_decimals<P>(provider: &Arc<P>, feed_address: Address, symbol: &str) -> u8
+where
+    P: Provider,
+{
+    match call_contract(provider.as_ref(), feed_address, &IChainlinkFeed::decimalsCall {}).await {
+        Ok(decimals) => decimals._0,
+        Err(e) => {
+            warn!(
+                "Failed to read {} feed decimals: {}. Assuming {}.",
+                symbol, e, PRICE_DECIMALS
+            );
+            PRICE_DECIMALS
+        }
+    }
+}
+
+/// Contract that emits a feed's `AnswerUpdated` events. Chainlink proxies forward reads but
+/// the events come from the underlying aggregator; feeds that aren't proxies emit them
+/// themselves.
+pub async fn feed_event_source<P>(provider: &Arc<P>, feed_address: Address) -> Address
+where
+    P: Provider,
+{
+    call_contract(provider.as_ref(), feed_address, &IChainlinkFeed::aggregatorCall {})
+        .await
+        .map(|aggregator| aggregator._0)
+        .ok()
+        .filter(|aggregator| !aggregator.is_zero())
+        .unwrap_or(feed_address)
+}
+
+/// Scale a feed answer with `decimals` decimals to `PRICE_DECIMALS`
+pub fn normalize_price(answer: U256, decimals: u8) -> U256 {
+    let (decimals, target) = (u64::from(decimals), u64::from(PRICE_DECIMALS));
+    if decimals >= target {
+        answer / U256::from(10u64).pow(U256::from(decimals - target))
+    } else {
+        answer.saturating_mul(U256::from(10u64).pow(U256::from(target - decimals)))
+    }
+}
+
+/// A feed answer as a price; zero and negative answers are invalid
+fn answer_to_price(answer: I256, symbol: &str) -> Result<U256> {
+    if answer <= I256::ZERO {
+        return Err(eyre::eyre!("Invalid {} feed answer: {}", symbol, answer));
+    }
+    Ok(answer.into_raw())
+}
+
+/// Latest price of a feed from `latestRoundData()`, normalized to `PRICE_DECIMALS`
 pub async fn fetch_price_from_oracle<P>(
     provider: &Arc<P>,
     feed_address: Address,
+    decimals: u8,
     symbol: &str,
 ) -> Result<U256>
 where
     P: Provider,
 {
-    // Create a call to the price feed's latestRoundData() function
-    // latestRoundData() is the standard method for modern Chainlink price feeds
-    let call_data = match alloy_primitives::hex::decode("feaf968c") {
-        Ok(data) => data,
-        Err(e) => {
-            return Err(eyre::eyre!(
-                "Failed to decode latestRoundData() selector: {}",
-                e
-            ));
+    let round = call_contract(provider.as_ref(), feed_address, &IChainlinkFeed::latestRoundDataCall {})
+        .await
+        .map_err(|e| {
+            debug!("Failed to fetch price for {}: {}", symbol, e);
+            e
+        })?;
+
+    let price = normalize_price(answer_to_price(round.answer, symbol)?, decimals);
+    debug!("Fetched price for {}: {}", symbol, price);
+    Ok(price)
+}
+
+/// Decode an `AnswerUpdated` log into its normalized price and update timestamp
+pub fn decode_answer_updated(log: &alloy_rpc_types::Log, decimals: u8, symbol: &str) -> Result<(U256, u64)> {
+    let event = IChainlinkFeed::AnswerUpdated::decode_log(&log.inner, true)?;
+    let price = normalize_price(answer_to_price(event.data.current, symbol)?, decimals);
+    let updated_at = event.data.updatedAt.saturating_to::<u64>();
+    Ok((price, updated_at))
+}
+
+/// Turn an on-chain `AnswerUpdated` event into a price change: record it in the price
+/// history (when enabled) and hand it to the event processor, which updates the price feed
+/// and the circuit breaker
+pub async fn handle_price_update_event(
+    log: alloy_rpc_types::Log,
+    event_tx: &EventSender,
+    asset_address: Address,
+    decimals: u8,
+    symbol: &str,
+    price_history_db: Option<&DatabasePool>,
+) -> Result<()> {
+    let (price, updated_at) = decode_answer_updated(&log, decimals, symbol)?;
+    info!("📊 Oracle update for {}: {}", symbol, price);
+
+    if let Some(db_pool) = price_history_db {
+        if let Err(e) = database::record_price(db_pool, asset_address, price, updated_at).await {
+            warn!("Failed to record {} price history: {}", symbol, e);
         }
-    }; // latestRoundData() selector
+    }
 
-    let call_request = alloy_rpc_types::TransactionRequest {
-        to: Some(feed_address.into()),
-        input: alloy_rpc_types::TransactionInput::new(call_data.into()),
-        ..Default::default()
-    };
+    let _ = event_tx.send(BotEvent::OraclePriceChanged(asset_address, price));
 
-    match provider.call(&call_request).await {
-        Ok(result) => {
-            // latestRoundData returns (uint80 roundId, int256 answer, uint256 startedAt, uint256 updatedAt, uint80 answeredInRound)
-            // We need the answer which is at bytes 32-64 (second 32-byte slot)
-            if result.len() >= 64 {
-                let price = U256::from_be_slice(&result[32..64]);
-                debug!("Fetched price for {}: {}", symbol, price);
-                Ok(price)
-            } else {
-                Err(eyre::eyre!("Invalid price data length for {}: expected at least 64 bytes for latestRoundData(), got {}", symbol, result.len()))
+    Ok(())
+}
+
+/// Why an asset's price feed is unsafe to liquidate against
+#[derive(Debug, Clone, PartialEq)]
+pub enum OracleFault {
+    /// No new round within the feed's heartbeat plus the grace period
+    Stale { age_secs: u64 },
+    /// Zero or negative answer, or a round carried over from an earlier round
+    InvalidAnswer,
+    /// Another source disagrees with Chainlink by more than the allowed deviation
+    Deviation {
+        source: &'static str,
+        deviation_bps: u64,
+    },
+}
+
+impl OracleFault {
+    pub fn describe(&self) -> String {
+        match self {
+            OracleFault::Stale { age_secs } => format!("no update for {} seconds", age_secs),
+            OracleFault::InvalidAnswer => "zero, negative or incomplete answer".to_string(),
+            OracleFault::Deviation {
+                source,
+                deviation_bps,
+            } => format!("{} deviates {} bps from Chainlink", source, deviation_bps),
+        }
+    }
+}
+
+/// Assets whose price feeds currently fail a safety check. Liquidations are priced by the
+/// Aave oracle, so they are suspended until every feed checks out again.
+#[derive(Debug, Default)]
+pub struct OracleGuard {
+    faults: DashMap<Address, OracleFault>,
+}
+
+impl OracleGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_liquidation_allowed(&self) -> bool {
+        self.faults.is_empty()
+    }
+
+    pub fn fault(&self, asset: Address) -> Option<OracleFault> {
+        self.faults.get(&asset).map(|fault| fault.clone())
+    }
+
+    /// Record the latest check of `asset`, returning true if it became faulty, failed a
+    /// different check than before, or recovered
+    pub fn update(&self, asset: Address, fault: Option<OracleFault>) -> bool {
+        match fault {
+            Some(fault) => {
+                let kind = std::mem::discriminant(&fault);
+                let previous = self.faults.insert(asset, fault);
+                !matches!(previous, Some(previous) if std::mem::discriminant(&previous) == kind)
             }
+            None => self.faults.remove(&asset).is_some(),
         }
-        Err(e) => {
-            debug!("Failed to fetch price for {}: {}", symbol, e);
-            Err(e.into())
+    }
+}
+
+/// Thresholds the oracle guard checks feeds against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OracleGuardPolicy {
+    pub check_interval_secs: u64,
+    /// Added to each feed's heartbeat before its price counts as stale
+    pub staleness_grace_secs: u64,
+    pub max_deviation_bps: u64,
+}
+
+impl OracleGuardPolicy {
+    pub fn from_config(config: &BotConfig) -> Self {
+        Self {
+            check_interval_secs: config.oracle_guard_interval_secs,
+            staleness_grace_secs: config.oracle_staleness_grace_secs,
+            max_deviation_bps: config.oracle_max_deviation_bps,
         }
     }
 }
 
-pub async fn handle_price_update_event(
-    _log: alloy_rpc_types::Log,
-    event_tx: &mpsc::UnboundedSender<BotEvent>,
-    asset_address: Address,
-    symbol: &str,
-) -> Result<()> {
-    // For now, we'll use a simplified approach since LogData access is complex
-    // In a production environment, you would properly decode the AnswerUpdated event
-    // For demonstration, we'll just trigger a price change event
+/// Validate a Chainlink round: a positive answer, answered in its own round and updated
+/// within `max_age_secs` of `now`. Returns the answer normalized to `PRICE_DECIMALS`.
+pub fn check_round(
+    answer: I256,
+    updated_at: u64,
+    round_complete: bool,
+    decimals: u8,
+    now: u64,
+    max_age_secs: u64,
+) -> std::result::Result<U256, OracleFault> {
+    if answer <= I256::ZERO || !round_complete || updated_at == 0 {
+        return Err(OracleFault::InvalidAnswer);
+    }
+    let age_secs = now.saturating_sub(updated_at);
+    if age_secs > max_age_secs {
+        return Err(OracleFault::Stale { age_secs });
+    }
+    Ok(normalize_price(answer.into_raw(), decimals))
+}
+
+/// Flag `other_price` when it is more than `max_deviation_bps` away from the Chainlink price.
+/// A zero price from the other source counts as a full deviation.
+pub fn check_deviation(
+    chainlink_price: U256,
+    other_price: U256,
+    source: &'static str,
+    max_deviation_bps: u64,
+) -> Option<OracleFault> {
+    let deviation_bps = if other_price.is_zero() {
+        FIRST_PRICE_CHANGE_BPS
+    } else {
+        price_change_bps(chainlink_price, other_price).saturating_to::<u64>()
+    };
+    (deviation_bps > max_deviation_bps).then_some(OracleFault::Deviation {
+        source,
+        deviation_bps,
+    })
+}
+
+/// Check one asset's Chainlink round, then compare its price with the Aave oracle and the
+/// secondary feed when available. Errs only when the Chainlink feed can't be read.
+async fn check_asset_feeds<P>(
+    provider: &Arc<P>,
+    asset_config: &AssetConfig,
+    decimals: &HashMap<Address, u8>,
+    aave_price: Option<U256>,
+    secondary_feed: Option<Address>,
+    policy: &OracleGuardPolicy,
+    now: u64,
+) -> Result<Option<OracleFault>>
+where
+    P: Provider,
+{
+    let decimals_of = |feed: Address| decimals.get(&feed).copied().unwrap_or(PRICE_DECIMALS);
+    let round = call_contract(
+        provider.as_ref(),
+        asset_config.chainlink_feed,
+        &IChainlinkFeed::latestRoundDataCall {},
+    )
+    .await?;
+    let chainlink_price = match check_round(
+        round.answer,
+        round.updatedAt.saturating_to::<u64>(),
+        round.answeredInRound >= round.roundId,
+        decimals_of(asset_config.chainlink_feed),
+        now,
+        asset_config.heartbeat_secs + policy.staleness_grace_secs,
+    ) {
+        Ok(price) => price,
+        Err(fault) => return Ok(Some(fault)),
+    };
+
+    if let Some(aave_price) = aave_price {
+        let fault =
+            check_deviation(chainlink_price, aave_price, "Aave oracle", policy.max_deviation_bps);
+        if fault.is_some() {
+            return Ok(fault);
+        }
+    }
+
+    if let Some(feed) = secondary_feed {
+        match fetch_price_from_oracle(provider, feed, decimals_of(feed), &asset_config.symbol)
+            .await
+        {
+            Ok(price) => {
+                let max_deviation_bps = policy.max_deviation_bps;
+                let fault =
+                    check_deviation(chainlink_price, price, "secondary feed", max_deviation_bps);
+                if fault.is_some() {
+                    return Ok(fault);
+                }
+            }
+            Err(e) => warn!(
+                "Failed to read {} secondary feed {}: {}",
+                asset_config.symbol, feed, e
+            ),
+        }
+    }
+
+    Ok(None)
+}
+
+/// Periodically check every Chainlink feed for stale or invalid answers and compare it with
+/// the Aave oracle and any secondary feed, suspending liquidations while one fails
+#[allow(clippy::too_many_arguments)]
+pub async fn run_oracle_guard<P>(
+    provider: Arc<P>,
+    network: NetworkAddresses,
+    asset_configs: HashMap<Address, AssetConfig>,
+    secondary_feeds: Vec<SecondaryPriceFeed>,
+    guard: Arc<OracleGuard>,
+    policy: OracleGuardPolicy,
+    db_pool: DatabasePool,
+    alert_notifier: AlertNotifier,
+) -> Result<()>
+where
+    P: Provider,
+{
     info!(
-        "📊 Oracle event detected for {}, triggering price check",
-        symbol
+        "🔮 Starting oracle guard (every {} seconds, max deviation {} bps)",
+        policy.check_interval_secs, policy.max_deviation_bps
     );
 
-    // Send a dummy price change event - in production this would be the actual decoded price
-    let placeholder_price = U256::from(1000_00000000u64); // $1000 * 1e8 (Chainlink format)
-    let _ = event_tx.send(BotEvent::OraclePriceChanged(
-        asset_address,
-        placeholder_price,
-    ));
+    let mut decimals = HashMap::new();
+    for asset_config in asset_configs.values() {
+        let feed = asset_config.chainlink_feed;
+        decimals.insert(feed, feed_decimals(&provider, feed, &asset_config.symbol).await);
+    }
+    for secondary in &secondary_feeds {
+        let symbol = asset_configs
+            .get(&secondary.asset)
+            .map_or("secondary", |asset_config| asset_config.symbol.as_str());
+        decimals.insert(secondary.feed, feed_decimals(&provider, secondary.feed, symbol).await);
+    }
+    let assets: Vec<Address> = asset_configs.keys().copied().collect();
 
-    Ok(())
+    let mut interval = tokio::time::interval(Duration::from_secs(policy.check_interval_secs));
+
+    loop {
+        interval.tick().await;
+        let was_allowed = guard.is_liquidation_allowed();
+
+        let aave_prices: HashMap<Address, U256> =
+            match aave_oracle::fetch_oracle_prices(provider.as_ref(), &network, &assets).await {
+                Ok(prices) => assets.iter().copied().zip(prices).collect(),
+                Err(e) => {
+                    warn!("Failed to read Aave oracle prices for the oracle guard: {}", e);
+                    HashMap::new()
+                }
+            };
+        let now = Utc::now().timestamp().max(0) as u64;
+
+        for (asset, asset_config) in &asset_configs {
+            let secondary_feed = secondary_feeds
+                .iter()
+                .find(|secondary| secondary.asset == *asset)
+                .map(|secondary| secondary.feed);
+            let fault = match check_asset_feeds(
+                &provider,
+                asset_config,
+                &decimals,
+                aave_prices.get(asset).copied(),
+                secondary_feed,
+                &policy,
+                now,
+            )
+            .await
+            {
+                Ok(fault) => fault,
+                Err(e) => {
+                    metrics::record_rpc_error("oracle");
+                    warn!("Failed to check {} price feed: {}", asset_config.symbol, e);
+                    continue;
+                }
+            };
+
+            if !guard.update(*asset, fault.clone()) {
+                continue;
+            }
+            match fault {
+                Some(fault) => {
+                    let reason = fault.describe();
+                    error!(
+                        "🚨 ALERT: {} price feed unsafe ({}) - suspending liquidations",
+                        asset_config.symbol, reason
+                    );
+                    let message = format!("{} price feed unsafe: {}", asset_config.symbol, reason);
+                    if let Err(e) = database::log_monitoring_event(
+                        &db_pool,
+                        "oracle_unhealthy",
+                        None,
+                        Some(&message),
+                    )
+                    .await
+                    {
+                        error!("Failed to log oracle guard event: {}", e);
+                    }
+                    alert_notifier.spawn_notify(AlertEvent::OracleUnhealthy {
+                        symbol: asset_config.symbol.clone(),
+                        reason,
+                    });
+                }
+                None => info!("✅ {} price feed passes its checks again", asset_config.symbol),
+            }
+        }
+
+        if !was_allowed && guard.is_liquidation_allowed() {
+            info!("✅ All price feeds healthy - resuming liquidations");
+            if let Err(e) = database::log_monitoring_event(
+                &db_pool,
+                "oracle_recovered",
+                None,
+                Some("Price feeds healthy, liquidations resumed"),
+            )
+            .await
+            {
+                error!("Failed to log oracle guard event: {}", e);
+            }
+            alert_notifier.spawn_notify(AlertEvent::OracleRecovered);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_price_change_bps_for_regular_prices() {
+        // $2000 -> $2100 with 8 decimals is a 5% move
+        let old_price = U256::from(200_000_000_000u64);
+        let new_price = U256::from(210_000_000_000u64);
+
+        assert_eq!(price_change_bps(old_price, new_price), U256::from(500u64));
+        assert_eq!(price_change_bps(new_price, old_price), U256::from(476u64));
+        assert!((price_change_pct(old_price, new_price) - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_price_change_bps_with_extreme_prices() {
+        // diff * 10000 overflows U256 for these values
+        let old_price = U256::MAX / U256::from(4u64);
+        let new_price = U256::MAX / U256::from(2u64);
+        assert!(old_price.abs_diff(new_price).checked_mul(U256::from(10_000u64)).is_none());
+
+        // Doubling is a 100% move, computed exactly rather than capped
+        let change = price_change_bps(old_price, new_price);
+        assert!(change >= U256::from(9_999u64) && change <= U256::from(10_000u64));
+        assert!((price_change_pct(old_price, new_price) - 100.0).abs() < 1e-6);
+
+        // A tiny old price against a huge new one saturates instead of overflowing
+        assert_eq!(price_change_bps(U256::from(1u64), U256::MAX), U256::MAX);
+        assert!(price_change_pct(U256::from(1u64), U256::MAX).is_finite());
+    }
+
+    #[test]
+    fn test_price_change_bps_edge_cases() {
+        assert_eq!(
+            price_change_bps(U256::ZERO, U256::from(5u64)),
+            U256::from(FIRST_PRICE_CHANGE_BPS)
+        );
+        assert_eq!(price_change_bps(U256::from(5u64), U256::ZERO), U256::ZERO);
+        assert_eq!(price_change_bps(U256::from(5u64), U256::from(5u64)), U256::ZERO);
+        assert_eq!(price_change_pct(U256::ZERO, U256::from(5u64)), 0.0);
+    }
+
+    fn answer_updated_log(answer: I256) -> alloy_rpc_types::Log {
+        let event = IChainlinkFeed::AnswerUpdated {
+            current: answer,
+            roundId: U256::from(7u64),
+            updatedAt: U256::from(1_700_000_000u64),
+        };
+        alloy_rpc_types::Log {
+            inner: alloy_primitives::Log {
+                address: Address::repeat_byte(0xfe),
+                data: event.encode_log_data(),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_price_to_eight_decimals() {
+        let two_thousand = U256::from(2_000u64 * 100_000_000);
+
+        assert_eq!(normalize_price(two_thousand, 8), two_thousand);
+        assert_eq!(
+            normalize_price(U256::from(2_000u64) * U256::from(10u64).pow(U256::from(18u64)), 18),
+            two_thousand
+        );
+        assert_eq!(normalize_price(U256::from(2_000_000u64), 3), two_thousand);
+    }
+
+    #[test]
+    fn test_decode_answer_updated() {
+        // ETH/USD at $2000 from an 18-decimal feed
+        let answer = I256::try_from(2_000u64 * 10u64.pow(18)).unwrap();
+
+        let (price, updated_at) = decode_answer_updated(&answer_updated_log(answer), 18, "WETH").unwrap();
+
+        assert_eq!(price, U256::from(2_000u64 * 100_000_000));
+        assert_eq!(updated_at, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_check_round_flags_stale_and_invalid_answers() {
+        let answer = I256::try_from(2_000u64 * 100_000_000).unwrap();
+        let now = 1_700_001_000;
+
+        assert_eq!(
+            check_round(answer, 1_700_000_000, true, 8, now, 1_200),
+            Ok(U256::from(2_000u64 * 100_000_000))
+        );
+        assert_eq!(
+            check_round(answer, 1_700_000_000, true, 8, now, 900),
+            Err(OracleFault::Stale { age_secs: 1_000 })
+        );
+        assert_eq!(
+            check_round(I256::ZERO, 1_700_000_000, true, 8, now, 1_200),
+            Err(OracleFault::InvalidAnswer)
+        );
+        assert_eq!(
+            check_round(answer, 1_700_000_000, false, 8, now, 1_200),
+            Err(OracleFault::InvalidAnswer)
+        );
+    }
+
+    #[test]
+    fn test_oracle_guard_blocks_on_deviation_until_recovered() {
+        let chainlink = U256::from(2_000u64 * 100_000_000);
+        let usd = |price: u64| U256::from(price * 100_000_000);
+        assert_eq!(check_deviation(chainlink, usd(2_020), "Aave oracle", 200), None);
+        let fault = check_deviation(chainlink, usd(2_100), "Aave oracle", 200);
+        assert_eq!(
+            fault,
+            Some(OracleFault::Deviation {
+                source: "Aave oracle",
+                deviation_bps: 500
+            })
+        );
+        assert!(check_deviation(chainlink, U256::ZERO, "secondary feed", 200).is_some());
+
+        let guard = OracleGuard::new();
+        let asset = Address::repeat_byte(0x01);
+        assert!(guard.update(asset, fault));
+        assert!(!guard.is_liquidation_allowed());
+        // A growing deviation is the same fault, not a new alert
+        assert!(!guard.update(
+            asset,
+            check_deviation(chainlink, usd(2_200), "Aave oracle", 200)
+        ));
+        assert!(guard.update(asset, None));
+        assert!(guard.is_liquidation_allowed());
+    }
+
+    #[tokio::test]
+    async fn test_price_update_event_sends_decoded_price() {
+        let (event_tx, mut event_rx) = crate::events::channel(10, 10);
+        let asset = Address::repeat_byte(0x01);
+        let answer = I256::try_from(2_000u64 * 100_000_000).unwrap();
+
+        handle_price_update_event(answer_updated_log(answer), &event_tx, asset, 8, "WETH", None)
+            .await
+            .unwrap();
+
+        match event_rx.try_recv().unwrap() {
+            BotEvent::OraclePriceChanged(sent_asset, price) => {
+                assert_eq!(sent_asset, asset);
+                assert_eq!(price, U256::from(2_000u64 * 100_000_000));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        // Non-positive answers are rejected rather than propagated
+        assert!(handle_price_update_event(answer_updated_log(I256::MINUS_ONE), &event_tx, asset, 8, "WETH", None)
+            .await
+            .is_err());
+        assert!(event_rx.try_recv().is_err());
+    }
 }
@@ -0,0 +1,162 @@
+use crate::database::{self, DatabasePool};
+use crate::liquidation::reserve_config;
+use alloy_contract::ContractInstance;
+use alloy_primitives::U256;
+use alloy_provider::Provider;
+use eyre::Result;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+/// Bit of the reserve configuration bitmap signalling the reserve is paused
+const RESERVE_PAUSED_BIT: usize = 60;
+
+/// Check the paused flag of a reserve configuration bitmap
+pub fn is_reserve_paused(configuration: U256) -> bool {
+    configuration.bit(RESERVE_PAUSED_BIT)
+}
+
+/// A pool-wide governance pause marks every reserve as paused, so the pool is considered
+/// paused only when all of its reserves are
+pub fn is_pool_paused(reserve_configurations: &[U256]) -> bool {
+    !reserve_configurations.is_empty()
+        && reserve_configurations
+            .iter()
+            .all(|configuration| is_reserve_paused(*configuration))
+}
+
+/// Shared flag suspending all liquidation attempts while the pool is paused
+#[derive(Debug, Default)]
+pub struct PoolPauseGuard {
+    paused: AtomicBool,
+}
+
+impl PoolPauseGuard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+
+    /// Liquidations revert while the pool is paused, so they are only allowed when it isn't
+    pub fn is_liquidation_allowed(&self) -> bool {
+        !self.is_paused()
+    }
+
+    /// Update the paused state, returning true if it changed
+    pub fn set_paused(&self, paused: bool) -> bool {
+        self.paused.swap(paused, Ordering::Relaxed) != paused
+    }
+}
+
+/// Query every reserve's configuration and determine whether the pool is paused
+pub async fn fetch_pool_paused<P>(
+    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+) -> Result<bool>
+where
+    P: Provider,
+{
+    let configurations: Vec<U256> = reserve_config::fetch_reserve_configurations(pool_contract)
+        .await?
+        .into_iter()
+        .map(|(_, configuration)| configuration)
+        .collect();
+
+    Ok(is_pool_paused(&configurations))
+}
+
+/// Periodically check the pool's global pause state, suspending liquidations while paused
+pub async fn run_pool_pause_monitor<P>(
+    pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
+    db_pool: &DatabasePool,
+    guard: Arc<PoolPauseGuard>,
+    check_interval_secs: u64,
+) -> Result<()>
+where
+    P: Provider,
+{
+    info!(
+        "⏸️ Starting pool pause monitor (every {} seconds)",
+        check_interval_secs
+    );
+
+    let mut interval = tokio::time::interval(Duration::from_secs(check_interval_secs));
+
+    loop {
+        interval.tick().await;
+
+        let paused = match fetch_pool_paused(pool_contract).await {
+            Ok(paused) => paused,
+            Err(e) => {
+                warn!("Failed to check pool pause state: {}", e);
+                continue;
+            }
+        };
+
+        if !guard.set_paused(paused) {
+            debug!("Pool pause state unchanged (paused: {})", paused);
+            continue;
+        }
+
+        let (event_type, message) = if paused {
+            error!("🚨 ALERT: Aave pool is paused by governance - suspending all liquidations");
+            ("pool_paused", "Pool paused by governance, liquidations suspended")
+        } else {
+            info!("✅ Aave pool unpaused - resuming liquidations");
+            ("pool_unpaused", "Pool unpaused, liquidations resumed")
+        };
+
+        if let Err(e) = database::log_monitoring_event(db_pool, event_type, None, Some(message)).await
+        {
+            error!("Failed to log pool pause event: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn paused_configuration() -> U256 {
+        U256::from(1u8) << RESERVE_PAUSED_BIT
+    }
+
+    #[test]
+    fn test_reserve_paused_bit() {
+        assert!(!is_reserve_paused(U256::ZERO));
+        assert!(is_reserve_paused(paused_configuration()));
+        // Other flags (e.g. active bit 56, frozen bit 57) don't count as paused
+        assert!(!is_reserve_paused((U256::from(1u8) << 56) | (U256::from(1u8) << 57)));
+    }
+
+    #[test]
+    fn test_pool_paused_only_when_all_reserves_paused() {
+        assert!(!is_pool_paused(&[]));
+        assert!(!is_pool_paused(&[paused_configuration(), U256::ZERO]));
+        assert!(is_pool_paused(&[
+            paused_configuration(),
+            paused_configuration() | U256::from(1234u64),
+        ]));
+    }
+
+    #[test]
+    fn test_paused_pool_suspends_liquidations() {
+        let guard = PoolPauseGuard::new();
+        assert!(guard.is_liquidation_allowed());
+
+        // Pausing suspends liquidations and reports a state change
+        assert!(guard.set_paused(is_pool_paused(&[paused_configuration()])));
+        assert!(guard.is_paused());
+        assert!(!guard.is_liquidation_allowed());
+
+        // Repeated checks while paused don't re-alert
+        assert!(!guard.set_paused(true));
+
+        // Unpausing resumes automatically
+        assert!(guard.set_paused(false));
+        assert!(guard.is_liquidation_allowed());
+    }
+}
@@ -0,0 +1,272 @@
+use eyre::Result;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, error, info};
+
+use crate::config::BotConfig;
+use crate::database::{self, DatabasePool};
+use crate::models::{PricePoint, PriceResolution};
+
+/// How often the downsampling/retention pass runs
+const MAINTENANCE_INTERVAL_SECS: u64 = 3600;
+
+/// When recorded oracle prices are downsampled and deleted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceHistoryPolicy {
+    /// Raw samples older than this are aggregated into per-minute rows
+    pub minute_after_secs: u64,
+    /// Per-minute rows older than this are aggregated into per-hour rows
+    pub hour_after_secs: u64,
+    /// Rows older than this are deleted (`None` keeps history forever)
+    pub retention_secs: Option<u64>,
+}
+
+impl PriceHistoryPolicy {
+    pub fn from_config(config: &BotConfig) -> Self {
+        Self {
+            minute_after_secs: config.price_history_minute_after_hours * 3600,
+            hour_after_secs: config.price_history_hour_after_hours * 3600,
+            retention_secs: (config.price_history_retention_days > 0)
+                .then(|| config.price_history_retention_days * 86_400),
+        }
+    }
+}
+
+/// Rows removed by one maintenance pass
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PriceHistoryMaintenance {
+    pub downsampled: u64,
+    pub pruned: u64,
+}
+
+/// Aggregate price points (sorted by asset, then timestamp) into `resolution` buckets.
+///
+/// Each bucket keeps its closing price, the lowest and highest price seen and the total
+/// sample count, so range extremes survive downsampling. Points already at `resolution` or
+/// coarser pass through unchanged.
+pub fn aggregate_price_points(points: &[PricePoint], resolution: PriceResolution) -> Vec<PricePoint> {
+    let bucket_secs = match resolution.bucket_secs() {
+        Some(secs) => secs,
+        None => return points.to_vec(),
+    };
+
+    let mut aggregated: Vec<PricePoint> = Vec::new();
+    for point in points {
+        if point.resolution >= resolution {
+            aggregated.push(point.clone());
+            continue;
+        }
+
+        let bucket_start = point.timestamp - point.timestamp % bucket_secs;
+        match aggregated.last_mut() {
+            Some(bucket)
+                if bucket.asset == point.asset
+                    && bucket.resolution == resolution
+                    && bucket.timestamp == bucket_start =>
+            {
+                bucket.price = point.price;
+                bucket.min_price = bucket.min_price.min(point.min_price);
+                bucket.max_price = bucket.max_price.max(point.max_price);
+                bucket.sample_count += point.sample_count;
+            }
+            _ => aggregated.push(PricePoint {
+                timestamp: bucket_start,
+                resolution,
+                ..point.clone()
+            }),
+        }
+    }
+
+    aggregated
+}
+
+/// Run one downsampling and retention pass relative to `now` (unix seconds)
+pub async fn maintain_price_history(
+    db_pool: &DatabasePool,
+    policy: &PriceHistoryPolicy,
+    now: u64,
+) -> Result<PriceHistoryMaintenance> {
+    let mut result = PriceHistoryMaintenance::default();
+
+    // Coarsest first, so freshly created minute rows aren't immediately rolled up again
+    result.downsampled += database::downsample_price_history(
+        db_pool,
+        PriceResolution::Minute,
+        PriceResolution::Hour,
+        now.saturating_sub(policy.hour_after_secs),
+    )
+    .await?;
+    result.downsampled += database::downsample_price_history(
+        db_pool,
+        PriceResolution::Raw,
+        PriceResolution::Minute,
+        now.saturating_sub(policy.minute_after_secs),
+    )
+    .await?;
+
+    if let Some(retention_secs) = policy.retention_secs {
+        result.pruned =
+            database::prune_price_history(db_pool, now.saturating_sub(retention_secs)).await?;
+    }
+
+    Ok(result)
+}
+
+/// Periodically downsample and prune the recorded oracle price history
+pub async fn run_price_history_maintenance(
+    db_pool: DatabasePool,
+    policy: PriceHistoryPolicy,
+) -> Result<()> {
+    info!(
+        "📈 Price history maintenance: per-minute after {}s, per-hour after {}s, retention {}",
+        policy.minute_after_secs,
+        policy.hour_after_secs,
+        policy
+            .retention_secs
+            .map(|secs| format!("{}s", secs))
+            .unwrap_or_else(|| "unlimited".to_string())
+    );
+
+    let mut interval = tokio::time::interval(Duration::from_secs(MAINTENANCE_INTERVAL_SECS));
+    loop {
+        interval.tick().await;
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        match maintain_price_history(&db_pool, &policy, now).await {
+            Ok(result) if result.downsampled > 0 || result.pruned > 0 => info!(
+                "📈 Price history maintenance: {} rows downsampled away, {} rows pruned",
+                result.downsampled, result.pruned
+            ),
+            Ok(_) => debug!("Price history maintenance: nothing to do"),
+            Err(e) => error!("Price history maintenance failed: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tests::create_test_pool;
+    use crate::database::DatabaseBackend;
+    use alloy_primitives::{Address, U256};
+
+    async fn row_count(db_pool: &DatabasePool) -> i64 {
+        match &db_pool.backend {
+            DatabaseBackend::Sqlite(pool) => sqlx::query_scalar("SELECT COUNT(*) FROM price_feeds")
+                .fetch_one(pool)
+                .await
+                .unwrap(),
+            DatabaseBackend::Postgres(_) => unreachable!(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_downsampling_reduces_rows_and_preserves_extremes() {
+        let db_pool = create_test_pool().await;
+        let asset = Address::repeat_byte(0x01);
+        let start = 1_700_000_000 - 1_700_000_000 % 3600;
+
+        // Two hours of 10-second polls oscillating around 2000, with one spike and one dip
+        let mut samples = Vec::new();
+        for i in 0..720u64 {
+            let price = match i {
+                100 => 2_500,
+                500 => 1_500,
+                _ => 2_000 + i % 7,
+            };
+            samples.push((start + i * 10, U256::from(price)));
+        }
+        for (timestamp, price) in &samples {
+            database::record_price(&db_pool, asset, *price, *timestamp)
+                .await
+                .unwrap();
+        }
+        assert_eq!(row_count(&db_pool).await, 720);
+
+        // First pass rolls raw samples into minutes, the next one rolls minutes into hours
+        let policy = PriceHistoryPolicy {
+            minute_after_secs: 0,
+            hour_after_secs: 0,
+            retention_secs: None,
+        };
+        let now = start + 3 * 3600;
+        maintain_price_history(&db_pool, &policy, now).await.unwrap();
+        maintain_price_history(&db_pool, &policy, now).await.unwrap();
+
+        assert_eq!(row_count(&db_pool).await, 2);
+
+        let history = database::get_price_history(
+            &db_pool,
+            asset,
+            start,
+            now,
+            PriceResolution::Hour,
+        )
+        .await
+        .unwrap();
+        assert_eq!(history.len(), 2);
+        assert!(history.iter().all(|point| point.resolution == PriceResolution::Hour));
+        assert_eq!(history[0].timestamp, start);
+        assert_eq!(history[0].max_price, U256::from(2_500u64));
+        assert_eq!(history[1].min_price, U256::from(1_500u64));
+        assert_eq!(
+            history.iter().map(|point| point.sample_count).sum::<u64>(),
+            720
+        );
+        // The closing price of the last bucket is the last sample
+        assert_eq!(history[1].price, samples.last().unwrap().1);
+    }
+
+    #[tokio::test]
+    async fn test_retention_prunes_old_rows() {
+        let db_pool = create_test_pool().await;
+        let asset = Address::repeat_byte(0x02);
+
+        database::record_price(&db_pool, asset, U256::from(1u64), 1_000).await.unwrap();
+        database::record_price(&db_pool, asset, U256::from(2u64), 90_000).await.unwrap();
+
+        let policy = PriceHistoryPolicy {
+            minute_after_secs: 3600,
+            hour_after_secs: 86_400,
+            retention_secs: Some(86_400),
+        };
+        let result = maintain_price_history(&db_pool, &policy, 100_000).await.unwrap();
+
+        assert_eq!(result.pruned, 1);
+        let history =
+            database::get_price_history(&db_pool, asset, 0, 100_000, PriceResolution::Raw)
+                .await
+                .unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].price, U256::from(2u64));
+    }
+
+    #[test]
+    fn test_aggregate_keeps_coarser_rows() {
+        let asset = Address::repeat_byte(0x03);
+        let point = |timestamp, resolution, price: u64| PricePoint {
+            asset,
+            timestamp,
+            resolution,
+            price: U256::from(price),
+            min_price: U256::from(price),
+            max_price: U256::from(price),
+            sample_count: 1,
+        };
+        let points = vec![
+            point(0, PriceResolution::Hour, 10),
+            point(3600, PriceResolution::Raw, 20),
+            point(3630, PriceResolution::Raw, 30),
+        ];
+
+        let minutes = aggregate_price_points(&points, PriceResolution::Minute);
+
+        assert_eq!(minutes.len(), 2);
+        assert_eq!(minutes[0], points[0]);
+        assert_eq!(minutes[1].sample_count, 2);
+        assert_eq!(minutes[1].price, U256::from(30u64));
+        assert_eq!(minutes[1].min_price, U256::from(20u64));
+    }
+}
@@ -0,0 +1,308 @@
+use alloy_primitives::{Address, U256};
+use parking_lot::RwLock;
+use std::collections::{BTreeSet, HashMap};
+
+/// Health factor of exactly 1.0 (18 decimals)
+const HEALTH_FACTOR_ONE: u128 = 1_000_000_000_000_000_000;
+
+/// Price moves are expressed in basis points
+const BPS: u64 = 10_000;
+
+/// Price drop (bps) of one collateral asset that takes a position with `health_factor` down to
+/// 1.0, assuming all of its weighted collateral sits in that asset. Positions holding other
+/// collateral too need a deeper drop, so this never triggers late. 0 when already liquidatable.
+pub fn price_drop_to_liquidation_bps(health_factor: U256) -> u64 {
+    let one = U256::from(HEALTH_FACTOR_ONE);
+    if health_factor <= one {
+        return 0;
+    }
+    // HF scales linearly with the collateral price: HF * (1 - drop) = 1
+    ((health_factor - one).saturating_mul(U256::from(BPS)) / health_factor).saturating_to()
+}
+
+/// Price rise (bps) of one debt asset that takes a position with `health_factor` down to 1.0,
+/// assuming all of its debt is in that asset. Positions owing other assets too need a larger
+/// rise, so this never triggers late. 0 when already liquidatable.
+pub fn price_rise_to_liquidation_bps(health_factor: U256) -> u64 {
+    let one = U256::from(HEALTH_FACTOR_ONE);
+    if health_factor <= one {
+        return 0;
+    }
+    // HF scales inversely with the debt price: HF / (1 + rise) = 1
+    ((health_factor - one).saturating_mul(U256::from(BPS)) / one).saturating_to()
+}
+
+/// Price at which a `drop_bps` fall from `price` is reached
+pub fn trigger_price(price: U256, drop_bps: u64) -> U256 {
+    price.saturating_mul(U256::from(BPS.saturating_sub(drop_bps))) / U256::from(BPS)
+}
+
+/// Price at which a `rise_bps` rally from `price` is reached
+pub fn rise_trigger_price(price: U256, rise_bps: u64) -> U256 {
+    price.saturating_mul(U256::from(BPS.saturating_add(rise_bps))) / U256::from(BPS)
+}
+
+/// Which side of a position a trigger watches
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TriggerSide {
+    /// Re-check when the collateral price falls to the trigger price
+    Collateral,
+    /// Re-check when the debt price rises to the trigger price
+    Debt,
+}
+
+/// Asset price at which a user has to be re-checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PriceTrigger {
+    pub asset: Address,
+    pub side: TriggerSide,
+    pub trigger_price: U256,
+    /// Price drop (collateral) or rise (debt) needed to reach the trigger price
+    pub move_bps: u64,
+}
+
+#[derive(Debug, Default)]
+struct TriggerTables {
+    /// (asset, side) -> (trigger price, user), sorted so a tick finds its crossed users by range
+    by_asset: HashMap<(Address, TriggerSide), BTreeSet<(U256, Address)>>,
+    by_user: HashMap<Address, Vec<PriceTrigger>>,
+}
+
+/// Sorted index of the collateral price fall and debt price rise each borrower can withstand,
+/// so an oracle tick only re-checks users whose trigger price was crossed.
+#[derive(Debug, Default)]
+pub struct PriceTriggerIndex {
+    tables: RwLock<TriggerTables>,
+}
+
+impl PriceTriggerIndex {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replace a user's triggers from their latest health factor and the current price of each
+    /// collateral asset they hold and each asset they owe
+    pub fn update_user(
+        &self,
+        user: Address,
+        health_factor: U256,
+        collateral_prices: &[(Address, U256)],
+        debt_prices: &[(Address, U256)],
+    ) {
+        let drop_bps = price_drop_to_liquidation_bps(health_factor);
+        let rise_bps = price_rise_to_liquidation_bps(health_factor);
+        // Already liquidatable users get triggers any tick crosses
+        let collateral = collateral_prices.iter().map(|(asset, price)| PriceTrigger {
+            asset: *asset,
+            side: TriggerSide::Collateral,
+            trigger_price: if drop_bps == 0 {
+                U256::MAX
+            } else {
+                trigger_price(*price, drop_bps)
+            },
+            move_bps: drop_bps,
+        });
+        let debt = debt_prices.iter().map(|(asset, price)| PriceTrigger {
+            asset: *asset,
+            side: TriggerSide::Debt,
+            trigger_price: if rise_bps == 0 {
+                U256::ZERO
+            } else {
+                rise_trigger_price(*price, rise_bps)
+            },
+            move_bps: rise_bps,
+        });
+        let triggers: Vec<PriceTrigger> = collateral.chain(debt).collect();
+
+        let mut tables = self.tables.write();
+        Self::remove_locked(&mut tables, user);
+        if triggers.is_empty() {
+            return;
+        }
+        for trigger in &triggers {
+            tables
+                .by_asset
+                .entry((trigger.asset, trigger.side))
+                .or_default()
+                .insert((trigger.trigger_price, user));
+        }
+        tables.by_user.insert(user, triggers);
+    }
+
+    pub fn remove_user(&self, user: Address) {
+        Self::remove_locked(&mut self.tables.write(), user);
+    }
+
+    fn remove_locked(tables: &mut TriggerTables, user: Address) {
+        let Some(triggers) = tables.by_user.remove(&user) else {
+            return;
+        };
+        for trigger in triggers {
+            let key = (trigger.asset, trigger.side);
+            if let Some(entries) = tables.by_asset.get_mut(&key) {
+                entries.remove(&(trigger.trigger_price, user));
+                if entries.is_empty() {
+                    tables.by_asset.remove(&key);
+                }
+            }
+        }
+    }
+
+    /// Whether `user` has a trigger price for `asset` on `side`
+    pub fn has_trigger(&self, user: Address, asset: Address, side: TriggerSide) -> bool {
+        self.tables.read().by_user.get(&user).is_some_and(|triggers| {
+            triggers
+                .iter()
+                .any(|trigger| trigger.asset == asset && trigger.side == side)
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.tables.read().by_user.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Users whose collateral trigger price for `asset` is at or above `price`, then users
+    /// whose debt trigger price for it is at or below `price`
+    pub fn crossed(&self, asset: Address, price: U256) -> Vec<Address> {
+        let tables = self.tables.read();
+        let fallen = tables
+            .by_asset
+            .get(&(asset, TriggerSide::Collateral))
+            .into_iter()
+            .flat_map(|entries| entries.range((price, Address::ZERO)..));
+        let risen = tables
+            .by_asset
+            .get(&(asset, TriggerSide::Debt))
+            .into_iter()
+            .flat_map(|entries| entries.range(..=(price, Address::repeat_byte(0xff))));
+        fallen.chain(risen).map(|(_, user)| *user).collect()
+    }
+
+    /// Users closest to liquidation by required price move, smallest first
+    pub fn ranked(&self, limit: usize) -> Vec<(Address, PriceTrigger)> {
+        let tables = self.tables.read();
+        let mut ranked: Vec<(Address, PriceTrigger)> = tables
+            .by_user
+            .iter()
+            .filter_map(|(user, triggers)| {
+                triggers
+                    .iter()
+                    .min_by_key(|trigger| trigger.move_bps)
+                    .map(|trigger| (*user, *trigger))
+            })
+            .collect();
+        ranked.sort_by_key(|(user, trigger)| (trigger.move_bps, *user));
+        ranked.truncate(limit);
+        ranked
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hf(value: f64) -> U256 {
+        U256::from((value * 1e18) as u128)
+    }
+
+    #[test]
+    fn test_price_drop_to_liquidation() {
+        assert_eq!(price_drop_to_liquidation_bps(hf(2.0)), 5_000);
+        assert_eq!(price_drop_to_liquidation_bps(hf(1.25)), 2_000);
+        assert_eq!(price_drop_to_liquidation_bps(hf(1.0)), 0);
+        assert_eq!(price_drop_to_liquidation_bps(hf(0.9)), 0);
+        assert_eq!(trigger_price(U256::from(2_000u64), 2_000), U256::from(1_600u64));
+    }
+
+    #[test]
+    fn test_price_rise_to_liquidation() {
+        assert_eq!(price_rise_to_liquidation_bps(hf(2.0)), 10_000);
+        assert_eq!(price_rise_to_liquidation_bps(hf(1.25)), 2_500);
+        assert_eq!(price_rise_to_liquidation_bps(hf(1.0)), 0);
+        assert_eq!(rise_trigger_price(U256::from(2_000u64), 2_500), U256::from(2_500u64));
+    }
+
+    #[test]
+    fn test_debt_rally_crosses_borrowers() {
+        let index = PriceTriggerIndex::new();
+        let (weth, usdc) = (Address::repeat_byte(0xee), Address::repeat_byte(0xdc));
+        let (alice, bob) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+
+        // Both borrow WETH at 2000 against USDC: Alice liquidates after a 25% rally (2500),
+        // Bob after 100% (4000)
+        let collateral = [(usdc, U256::from(1u64))];
+        let debt = [(weth, U256::from(2_000u64))];
+        index.update_user(alice, hf(1.25), &collateral, &debt);
+        index.update_user(bob, hf(2.0), &collateral, &debt);
+
+        assert!(index.has_trigger(alice, weth, TriggerSide::Debt));
+        assert!(!index.has_trigger(alice, weth, TriggerSide::Collateral));
+        assert!(index.crossed(weth, U256::from(2_400u64)).is_empty());
+        assert_eq!(index.crossed(weth, U256::from(2_500u64)), vec![alice]);
+        assert_eq!(index.crossed(weth, U256::from(4_000u64)).len(), 2);
+        // A falling debt price crosses nobody
+        assert!(index.crossed(weth, U256::from(1_000u64)).is_empty());
+
+        // Liquidatable borrowers are crossed by any tick of their debt asset
+        index.update_user(bob, hf(0.95), &[], &debt);
+        assert_eq!(index.crossed(weth, U256::from(1_000u64)), vec![bob]);
+    }
+
+    #[test]
+    fn test_only_crossed_users_are_returned() {
+        let index = PriceTriggerIndex::new();
+        let weth = Address::repeat_byte(0xee);
+        let (alice, bob) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let price = U256::from(2_000u64);
+
+        // Alice liquidates after a 20% drop (1600), Bob after 50% (1000)
+        index.update_user(alice, hf(1.25), &[(weth, price)], &[]);
+        index.update_user(bob, hf(2.0), &[(weth, price)], &[]);
+
+        assert!(index.crossed(weth, U256::from(1_700u64)).is_empty());
+        assert_eq!(index.crossed(weth, U256::from(1_600u64)), vec![alice]);
+        assert_eq!(index.crossed(weth, U256::from(900u64)).len(), 2);
+        assert!(index.crossed(Address::repeat_byte(0xcc), U256::ZERO).is_empty());
+    }
+
+    #[test]
+    fn test_update_replaces_previous_triggers() {
+        let index = PriceTriggerIndex::new();
+        let (weth, cbeth) = (Address::repeat_byte(0xee), Address::repeat_byte(0xcb));
+        let user = Address::repeat_byte(0x01);
+
+        index.update_user(user, hf(1.25), &[(weth, U256::from(2_000u64))], &[]);
+        index.update_user(user, hf(2.0), &[(cbeth, U256::from(2_000u64))], &[]);
+
+        assert!(index.crossed(weth, U256::ZERO).is_empty());
+        assert!(!index.has_trigger(user, weth, TriggerSide::Collateral));
+        assert!(index.has_trigger(user, cbeth, TriggerSide::Collateral));
+        assert_eq!(index.crossed(cbeth, U256::from(1_000u64)), vec![user]);
+
+        // Liquidatable users are crossed by any tick
+        index.update_user(user, hf(0.95), &[(cbeth, U256::from(2_000u64))], &[]);
+        assert_eq!(index.crossed(cbeth, U256::from(5_000u64)), vec![user]);
+
+        index.remove_user(user);
+        assert!(index.is_empty());
+    }
+
+    #[test]
+    fn test_ranking_orders_by_required_drop() {
+        let index = PriceTriggerIndex::new();
+        let weth = Address::repeat_byte(0xee);
+        let price = U256::from(2_000u64);
+        let users: Vec<Address> = (1..=3).map(Address::repeat_byte).collect();
+
+        index.update_user(users[0], hf(2.0), &[(weth, price)], &[]);
+        index.update_user(users[1], hf(1.1), &[(weth, price)], &[]);
+        index.update_user(users[2], hf(1.5), &[(weth, price)], &[]);
+
+        let ranked: Vec<Address> = index.ranked(2).into_iter().map(|(user, _)| user).collect();
+        assert_eq!(ranked, vec![users[1], users[2]]);
+    }
+}
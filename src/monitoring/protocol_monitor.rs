@@ -0,0 +1,376 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use eyre::Result;
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{debug, error, info, warn};
+
+use crate::circuit_breaker::CircuitBreaker;
+use crate::database::{self, DatabasePool};
+use crate::liquidation::tx_manager::TxManager;
+use crate::liquidation::PositionAgeTracker;
+use crate::models::{
+    ExecutionMode, LiquidationIntent, LiquidationSettlement, ProtocolLiquidationCall,
+};
+use crate::monitoring::operator_balance::OperatorBalanceGuard;
+use crate::monitoring::oracle::OracleGuard;
+use crate::protocols::{LiquidationRequest, Protocol};
+use crate::signer::BotSigner;
+
+/// How long to wait for a protocol liquidation's receipt
+const PROTOCOL_LIQUIDATION_RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Everything needed to liquidate on a protocol outside the Aave pipeline
+pub struct ProtocolMonitorContext<P> {
+    pub provider: Arc<P>,
+    pub db_pool: DatabasePool,
+    pub circuit_breaker: Arc<CircuitBreaker>,
+    pub signer: BotSigner,
+    /// Owns the signer's nonces, shared with its liquidations
+    pub tx_manager: Arc<TxManager>,
+    pub execution_mode: ExecutionMode,
+    /// Sign and send liquidations (BotConfig::broadcasts_transactions); mocked otherwise
+    pub broadcast: bool,
+    /// Same gates as the Aave executor: position age (tracked per protocol), oracle health
+    /// and the operator's gas balance
+    pub position_age_tracker: PositionAgeTracker,
+    pub oracle_guard: Arc<OracleGuard>,
+    pub operator_balance_guard: Arc<OperatorBalanceGuard>,
+    /// Most a liquidation may cost in gas (wei). Comet pays the absorber no reward beyond
+    /// protocol points, so this is what decides whether an absorb is worth sending.
+    pub max_gas_cost_wei: U256,
+    /// Record intent and settlement rows in the liquidation audit log
+    pub audit: bool,
+    /// Blocks of history searched for borrowers on startup
+    pub discovery_blocks: u64,
+    pub interval_secs: u64,
+}
+
+/// A protocol liquidation transaction with its gas priced, ready to send
+struct PricedLiquidation {
+    tx_req: TransactionRequest,
+    gas_limit: u64,
+    max_fee_per_gas: u128,
+}
+
+impl PricedLiquidation {
+    /// Worst-case gas cost in wei
+    fn gas_cost(&self) -> U256 {
+        U256::from(self.gas_limit).saturating_mul(U256::from(self.max_fee_per_gas))
+    }
+}
+
+/// Price a protocol liquidation call from the signer. Estimating its gas also simulates it,
+/// so a call that would revert (e.g. the account was already liquidated) fails here.
+async fn price_protocol_liquidation<P>(
+    provider: &P,
+    signer: &BotSigner,
+    call: &ProtocolLiquidationCall,
+) -> Result<PricedLiquidation>
+where
+    P: Provider,
+{
+    let mut tx_req = TransactionRequest::default()
+        .from(signer.address())
+        .to(call.target)
+        .input(call.calldata.clone().into());
+    let fees = provider.estimate_eip1559_fees(None).await?;
+    tx_req.max_fee_per_gas = Some(fees.max_fee_per_gas);
+    tx_req.max_priority_fee_per_gas = Some(fees.max_priority_fee_per_gas);
+    let gas_limit = provider.estimate_gas(&tx_req).await?;
+    tx_req.gas = Some(gas_limit);
+
+    Ok(PricedLiquidation {
+        tx_req,
+        gas_limit,
+        max_fee_per_gas: fees.max_fee_per_gas,
+    })
+}
+
+/// Send a priced protocol liquidation from the signer with a nonce from `tx_manager` (mocked
+/// unless broadcasting), recording it in the audit log when enabled
+async fn execute_protocol_liquidation<P>(
+    context: &ProtocolMonitorContext<P>,
+    account: Address,
+    liquidation: PricedLiquidation,
+) -> Result<String>
+where
+    P: Provider,
+{
+    if !context.broadcast {
+        warn!(
+            "🎭 MOCK protocol liquidation of {} ({} gas) - no on-chain execution",
+            account, liquidation.gas_limit
+        );
+        return Ok("mock".to_string());
+    }
+
+    let provider = context.provider.as_ref();
+    let (nonce, tx_hash) = context
+        .tx_manager
+        .send(provider, &context.signer, liquidation.tx_req)
+        .await?;
+    let tx_hash_str = format!("0x{:x}", tx_hash);
+
+    if context.audit {
+        let intent = LiquidationIntent {
+            tx_hash: tx_hash_str.clone(),
+            user: account,
+            nonce,
+            gas_price: U256::from(liquidation.max_fee_per_gas),
+            gas_limit: U256::from(liquidation.gas_limit),
+            estimated_profit: U256::ZERO,
+        };
+        if let Err(e) = database::record_liquidation_intent(&context.db_pool, &intent).await {
+            error!("Failed to record liquidation intent for {}: {}", tx_hash_str, e);
+        }
+    }
+
+    let receipt = context
+        .tx_manager
+        .wait_for_receipt(
+            provider,
+            &context.signer,
+            nonce,
+            PROTOCOL_LIQUIDATION_RECEIPT_TIMEOUT,
+        )
+        .await?;
+
+    if context.audit {
+        let settlement = LiquidationSettlement {
+            tx_hash: tx_hash_str.clone(),
+            block_number: receipt.block_number,
+            tx_index: receipt.transaction_index,
+            gas_used: U256::from(receipt.gas_used),
+            realized_profit: U256::ZERO,
+            succeeded: receipt.status(),
+        };
+        if let Err(e) = database::record_liquidation_settlement(&context.db_pool, &settlement).await
+        {
+            error!(
+                "Failed to record liquidation settlement for {}: {}",
+                tx_hash_str, e
+            );
+        }
+    }
+
+    if !receipt.status() {
+        return Err(eyre::eyre!("Transaction {} reverted", tx_hash_str));
+    }
+    Ok(tx_hash_str)
+}
+
+/// Refresh one account, persist its snapshot and liquidate it if it is underwater and passes
+/// the same gates as an Aave liquidation. `block` is the head the round was checked at.
+async fn check_account<P>(
+    protocol: &dyn Protocol,
+    context: &ProtocolMonitorContext<P>,
+    account: Address,
+    block: Option<u64>,
+) -> Result<()>
+where
+    P: Provider,
+{
+    let position = protocol.account_data(account).await?;
+    database::save_protocol_position(&context.db_pool, &position).await?;
+
+    if let Some(block) = block.filter(|_| !position.debt_value.is_zero()) {
+        context.position_age_tracker.record_observation(account, block);
+    }
+
+    if !position.is_liquidatable {
+        return Ok(());
+    }
+
+    info!(
+        "🎯 {} account {} is liquidatable (health factor {})",
+        protocol.kind().as_str(),
+        account,
+        position.health_factor
+    );
+
+    // Without a head the age can't be judged; don't hold up the liquidation, as on the Aave path
+    if let Some(block) = block {
+        if !context.position_age_tracker.is_eligible(account, block) {
+            info!(
+                "⏳ Skipping {} liquidation of {} - position younger than the minimum age",
+                protocol.kind().as_str(),
+                account
+            );
+            return Ok(());
+        }
+    }
+
+    if !context.oracle_guard.is_liquidation_allowed() {
+        warn!(
+            "🔮 {} liquidation of {} suspended - a price feed failed its checks",
+            protocol.kind().as_str(),
+            account
+        );
+        return Ok(());
+    }
+
+    if !context.operator_balance_guard.is_liquidation_allowed() {
+        warn!(
+            "⛽ {} liquidation of {} halted - operator balance below minimum",
+            protocol.kind().as_str(),
+            account
+        );
+        return Ok(());
+    }
+
+    if !context.circuit_breaker.is_liquidation_allowed() {
+        warn!(
+            "🚫 Circuit breaker blocked {} liquidation of {}",
+            protocol.kind().as_str(),
+            account
+        );
+        context.circuit_breaker.record_blocked_liquidation();
+        return Ok(());
+    }
+
+    let call = protocol.encode_liquidation(&LiquidationRequest {
+        account,
+        collateral_asset: Address::ZERO,
+        debt_asset: Address::ZERO,
+        debt_to_cover: position.debt_value,
+        liquidator: context.signer.address(),
+    });
+
+    let liquidation = price_protocol_liquidation(context.provider.as_ref(), &context.signer, &call)
+        .await
+        .map_err(|e| {
+            eyre::eyre!(
+                "{} liquidation of {} failed simulation: {}",
+                protocol.kind().as_str(),
+                account,
+                e
+            )
+        })?;
+    if liquidation.gas_cost() > context.max_gas_cost_wei {
+        info!(
+            "💸 Skipping {} liquidation of {} - gas cost {} wei exceeds the {} wei budget",
+            protocol.kind().as_str(),
+            account,
+            liquidation.gas_cost(),
+            context.max_gas_cost_wei
+        );
+        return Ok(());
+    }
+
+    match context.execution_mode {
+        ExecutionMode::Live => {}
+        ExecutionMode::DryRun => {
+            info!(
+                "📝 Dry run: would have sent {} liquidation of {} to {}",
+                protocol.kind().as_str(),
+                account,
+                call.target
+            );
+            return Ok(());
+        }
+        ExecutionMode::Simulate => return Ok(()),
+    }
+
+    let result = execute_protocol_liquidation(context, account, liquidation).await;
+    let succeeded = result.is_ok();
+    if let Err(e) = context
+        .circuit_breaker
+        .record_liquidation_attempt(succeeded, None)
+        .await
+    {
+        warn!("Failed to record liquidation attempt with circuit breaker: {}", e);
+    }
+
+    match result {
+        Ok(tx_hash) => {
+            info!(
+                "🎉 {} liquidation of {} executed: {}",
+                protocol.kind().as_str(),
+                account,
+                tx_hash
+            );
+            Ok(())
+        }
+        Err(e) => Err(eyre::eyre!(
+            "{} liquidation of {} failed: {}",
+            protocol.kind().as_str(),
+            account,
+            e
+        )),
+    }
+}
+
+/// Discover borrowers on a protocol from its logs, then periodically refresh every known
+/// account and liquidate the underwater ones
+pub async fn run_protocol_monitoring<P>(
+    protocol: Arc<dyn Protocol>,
+    context: ProtocolMonitorContext<P>,
+) -> Result<()>
+where
+    P: Provider,
+{
+    let kind = protocol.kind();
+    info!(
+        "🏦 Monitoring {} market {} every {}s",
+        kind.as_str(),
+        protocol.market(),
+        context.interval_secs
+    );
+
+    // Accounts seen in earlier runs are tracked again immediately
+    let mut accounts: BTreeSet<Address> =
+        match database::get_protocol_positions(&context.db_pool, kind).await {
+            Ok(positions) => positions.into_iter().map(|position| position.address).collect(),
+            Err(e) => {
+                warn!("Failed to load stored {} positions: {}", kind.as_str(), e);
+                BTreeSet::new()
+            }
+        };
+
+    let head = context.provider.get_block_number().await?;
+    let mut next_block = head.saturating_sub(context.discovery_blocks);
+
+    let mut interval = tokio::time::interval(Duration::from_secs(context.interval_secs.max(1)));
+    loop {
+        interval.tick().await;
+
+        let head = context.provider.get_block_number().await;
+        match head {
+            Ok(head) if head >= next_block => {
+                match protocol.discover_accounts(next_block, head).await {
+                    Ok(discovered) => {
+                        let before = accounts.len();
+                        accounts.extend(discovered);
+                        if accounts.len() > before {
+                            info!(
+                                "🔍 Found {} new {} borrowers (blocks {}-{})",
+                                accounts.len() - before,
+                                kind.as_str(),
+                                next_block,
+                                head
+                            );
+                        }
+                        next_block = head + 1;
+                    }
+                    Err(e) => warn!("{} account discovery failed: {}", kind.as_str(), e),
+                }
+            }
+            Ok(_) => {}
+            Err(ref e) => {
+                warn!("Failed to get block number for {} discovery: {}", kind.as_str(), e)
+            }
+        }
+
+        // Position ages are judged at the same head
+        let block = head.ok();
+        for account in &accounts {
+            if let Err(e) = check_account(protocol.as_ref(), &context, *account, block).await {
+                error!("Failed to check {} account {}: {}", kind.as_str(), account, e);
+            }
+        }
+        debug!("Checked {} {} accounts", accounts.len(), kind.as_str());
+    }
+}
@@ -0,0 +1,335 @@
+use alloy_provider::Provider;
+use eyre::Result;
+use parking_lot::RwLock;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::monitoring::alerts::{AlertEvent, AlertNotifier};
+use crate::monitoring::websocket;
+
+/// First delay before reconnecting a dropped WebSocket
+const RECONNECT_BASE_DELAY: Duration = Duration::from_secs(1);
+
+/// Upper bound on the reconnect delay
+const RECONNECT_MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Jittered exponential backoff between reconnect attempts. Each delay is drawn from the upper
+/// half of the current window so subscribers that dropped together don't retry in lockstep.
+#[derive(Debug, Clone)]
+pub struct ReconnectBackoff {
+    attempt: u32,
+    base: Duration,
+    max: Duration,
+}
+
+impl Default for ReconnectBackoff {
+    fn default() -> Self {
+        Self::new(RECONNECT_BASE_DELAY, RECONNECT_MAX_DELAY)
+    }
+}
+
+impl ReconnectBackoff {
+    pub fn new(base: Duration, max: Duration) -> Self {
+        Self {
+            attempt: 0,
+            base,
+            max,
+        }
+    }
+
+    /// Delay before the next attempt; the window doubles each call up to the maximum
+    pub fn next_delay(&mut self) -> Duration {
+        let window = self
+            .base
+            .saturating_mul(2u32.saturating_pow(self.attempt))
+            .min(self.max);
+        self.attempt = self.attempt.saturating_add(1);
+
+        let jitter = RandomState::new().build_hasher().finish() % 1_000;
+        window / 2 + (window / 2).mul_f64(jitter as f64 / 1_000.0)
+    }
+
+    /// Start over from the base delay after a connection proved healthy
+    pub fn reset(&mut self) {
+        self.attempt = 0;
+    }
+}
+
+/// The current real-time provider, replaced when the connection is re-established
+struct RealTimeConnection {
+    provider: RwLock<Arc<dyn Provider>>,
+    /// Serializes reconnects so subscribers whose streams dropped together share one connection
+    reconnecting: tokio::sync::Mutex<()>,
+    /// Set once a failed reconnect has been alerted, cleared when the connection is restored
+    outage_alerted: AtomicBool,
+}
+
+/// The providers the bot talks to: one for calls (HTTP) and one for real-time subscriptions
+/// (WebSocket), plus whether real-time subscriptions are actually available.
+///
+/// In fallback mode the real-time provider is the call provider itself, so subsystems must
+/// check `is_real_time_enabled` (or use `real_time`) instead of inferring WebSocket support
+/// from the configured URL.
+pub struct ProviderSet<P> {
+    call: Arc<P>,
+    real_time: Arc<RealTimeConnection>,
+    real_time_enabled: bool,
+    ws_urls: Arc<Vec<String>>, // Endpoints tried in order when the real-time connection drops
+    alert_notifier: Option<AlertNotifier>, // Notified when reconnects fail and when they recover
+}
+
+// Manual impl: cloning only clones the handles, so `P` itself needn't be `Clone`
+impl<P> Clone for ProviderSet<P> {
+    fn clone(&self) -> Self {
+        Self {
+            call: self.call.clone(),
+            real_time: self.real_time.clone(),
+            real_time_enabled: self.real_time_enabled,
+            ws_urls: self.ws_urls.clone(),
+            alert_notifier: self.alert_notifier.clone(),
+        }
+    }
+}
+
+impl<P> ProviderSet<P>
+where
+    P: Provider + 'static,
+{
+    fn new(call: Arc<P>, real_time: Arc<dyn Provider>, real_time_enabled: bool, ws_urls: Vec<String>) -> Self {
+        Self {
+            call,
+            real_time: Arc::new(RealTimeConnection {
+                provider: RwLock::new(real_time),
+                reconnecting: tokio::sync::Mutex::new(()),
+                outage_alerted: AtomicBool::new(false),
+            }),
+            real_time_enabled,
+            ws_urls: Arc::new(ws_urls),
+            alert_notifier: None,
+        }
+    }
+
+    /// Alert when the real-time connection can't be re-established, and again once it is
+    pub fn with_alert_notifier(mut self, notifier: AlertNotifier) -> Self {
+        self.alert_notifier = Some(notifier);
+        self
+    }
+
+    fn notify(&self, event: AlertEvent) {
+        if let Some(notifier) = &self.alert_notifier {
+            notifier.spawn_notify(event);
+        }
+    }
+
+    /// Provider set with a connected real-time (WebSocket) provider
+    pub fn with_real_time(call: Arc<P>, real_time: Arc<dyn Provider>) -> Self {
+        Self::new(call, real_time, true, Vec::new())
+    }
+
+    /// Provider set without real-time subscriptions; everything goes through the call provider
+    pub fn fallback(call: Arc<P>) -> Self {
+        let real_time = call.clone() as Arc<dyn Provider>;
+        Self::new(call, real_time, false, Vec::new())
+    }
+
+    /// Connect the real-time provider to the first of `ws_urls` that accepts a connection,
+    /// falling back to polling mode when real-time monitoring is disabled, no URL is a
+    /// WebSocket URL or every connection fails
+    pub async fn connect(call: Arc<P>, ws_urls: &[String], real_time_monitoring_enabled: bool) -> Self {
+        if !real_time_monitoring_enabled {
+            info!("Real-time monitoring disabled by configuration - using HTTP polling mode");
+            return Self::fallback(call);
+        }
+
+        let ws_urls: Vec<String> = ws_urls
+            .iter()
+            .filter(|url| url.starts_with("wss://") || url.starts_with("ws://"))
+            .cloned()
+            .collect();
+        if ws_urls.is_empty() {
+            info!("⚠️ WS_URL does not use a WebSocket protocol - using HTTP polling mode");
+            info!("To enable real-time monitoring, configure WS_URL with a proper WebSocket RPC endpoint");
+            return Self::fallback(call);
+        }
+
+        match connect_first(&ws_urls).await {
+            Ok(real_time) => {
+                info!("✅ WebSocket connection established successfully!");
+                Self::new(call, real_time, true, ws_urls)
+            }
+            Err(e) => {
+                info!("⚠️ WebSocket connection failed: {}", e);
+                info!("Falling back to HTTP provider for polling mode");
+                info!("To enable real-time monitoring, configure WS_URL with a proper WebSocket RPC endpoint");
+                Self::fallback(call)
+            }
+        }
+    }
+
+    /// Provider used for calls and polling
+    pub fn call(&self) -> &Arc<P> {
+        &self.call
+    }
+
+    /// Current provider for log subscriptions, if real-time monitoring is available
+    pub fn real_time(&self) -> Option<Arc<dyn Provider>> {
+        self.real_time_enabled
+            .then(|| self.real_time.provider.read().clone())
+    }
+
+    /// Whether real-time (WebSocket) subscriptions are available
+    pub fn is_real_time_enabled(&self) -> bool {
+        self.real_time_enabled
+    }
+
+    /// Replace a real-time provider whose subscription dropped. If another subscriber already
+    /// reconnected, its connection is shared instead of opening a new one.
+    pub async fn reconnect_real_time(&self, dropped: &Arc<dyn Provider>) -> Result<Arc<dyn Provider>> {
+        if !self.real_time_enabled {
+            return Err(eyre::eyre!("Real-time monitoring is not enabled"));
+        }
+
+        let _reconnecting = self.real_time.reconnecting.lock().await;
+        let current = self.real_time.provider.read().clone();
+        if !Arc::ptr_eq(&current, dropped) {
+            return Ok(current);
+        }
+
+        let provider = match connect_first(&self.ws_urls).await {
+            Ok(provider) => provider,
+            Err(e) => {
+                if !self.real_time.outage_alerted.swap(true, Ordering::Relaxed) {
+                    self.notify(AlertEvent::ConnectionLost {
+                        endpoint: "WebSocket".to_string(),
+                        error: e.to_string(),
+                    });
+                }
+                return Err(e);
+            }
+        };
+        *self.real_time.provider.write() = provider.clone();
+        info!("✅ WebSocket connection re-established");
+        if self.real_time.outage_alerted.swap(false, Ordering::Relaxed) {
+            self.notify(AlertEvent::ConnectionRestored {
+                endpoint: "WebSocket".to_string(),
+            });
+        }
+        Ok(provider)
+    }
+
+    /// Keep trying to replace `dropped`, waiting out `backoff` before each attempt
+    pub async fn wait_for_real_time_reconnect(
+        &self,
+        dropped: &Arc<dyn Provider>,
+        backoff: &mut ReconnectBackoff,
+    ) -> Arc<dyn Provider> {
+        loop {
+            let delay = backoff.next_delay();
+            info!("🔌 Reconnecting WebSocket in {:.1}s", delay.as_secs_f64());
+            tokio::time::sleep(delay).await;
+
+            match self.reconnect_real_time(dropped).await {
+                Ok(provider) => return provider,
+                Err(e) => warn!("WebSocket reconnect failed: {}", e),
+            }
+        }
+    }
+}
+
+/// Connect to the first WebSocket URL that accepts a connection
+async fn connect_first(ws_urls: &[String]) -> Result<Arc<dyn Provider>> {
+    let mut last_error = eyre::eyre!("No WebSocket endpoints configured");
+    for ws_url in ws_urls {
+        match websocket::try_connect_websocket(ws_url).await {
+            Ok(provider) => return Ok(provider),
+            Err(e) => {
+                info!("⚠️ WebSocket connection to {} failed: {}", ws_url, e);
+                last_error = e;
+            }
+        }
+    }
+    Err(last_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_provider::ProviderBuilder;
+
+    fn http_provider() -> Arc<impl Provider + 'static> {
+        Arc::new(
+            ProviderBuilder::new()
+                .on_http("http://localhost:8545".parse().unwrap())
+                .boxed(),
+        )
+    }
+
+    #[test]
+    fn test_provider_set_with_real_time() {
+        let real_time = http_provider() as Arc<dyn Provider>;
+        let providers = ProviderSet::with_real_time(http_provider(), real_time.clone());
+
+        assert!(providers.is_real_time_enabled());
+        assert!(Arc::ptr_eq(&providers.real_time().unwrap(), &real_time));
+    }
+
+    #[test]
+    fn test_provider_set_fallback() {
+        let call = http_provider();
+        let providers = ProviderSet::fallback(call.clone());
+
+        assert!(!providers.is_real_time_enabled());
+        assert!(providers.real_time().is_none());
+        assert!(Arc::ptr_eq(providers.call(), &call));
+    }
+
+    #[tokio::test]
+    async fn test_connect_falls_back_without_websocket_url() {
+        let providers =
+            ProviderSet::connect(http_provider(), &["https://mainnet.base.org".to_string()], true).await;
+        assert!(!providers.is_real_time_enabled());
+
+        let providers =
+            ProviderSet::connect(http_provider(), &["wss://example.invalid".to_string()], false).await;
+        assert!(!providers.is_real_time_enabled());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_requires_real_time() {
+        let call = http_provider();
+        let providers = ProviderSet::fallback(call.clone());
+        let dropped = call as Arc<dyn Provider>;
+
+        assert!(providers.reconnect_real_time(&dropped).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_shares_an_existing_replacement() {
+        let dropped = http_provider() as Arc<dyn Provider>;
+        let current = http_provider() as Arc<dyn Provider>;
+        let providers = ProviderSet::with_real_time(http_provider(), current.clone());
+
+        // Another subscriber already replaced the dropped connection
+        let provider = providers.reconnect_real_time(&dropped).await.unwrap();
+        assert!(Arc::ptr_eq(&provider, &current));
+    }
+
+    #[test]
+    fn test_backoff_grows_with_jitter_and_resets() {
+        let mut backoff = ReconnectBackoff::new(Duration::from_secs(1), Duration::from_secs(8));
+        let windows = [1, 2, 4, 8, 8];
+
+        for window in windows {
+            let delay = backoff.next_delay();
+            let window = Duration::from_secs(window);
+            assert!(delay >= window / 2 && delay <= window, "{:?} outside {:?}", delay, window);
+        }
+
+        backoff.reset();
+        assert!(backoff.next_delay() <= Duration::from_secs(1));
+    }
+}
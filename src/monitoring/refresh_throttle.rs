@@ -0,0 +1,123 @@
+use alloy_primitives::Address;
+use dashmap::{DashMap, DashSet};
+use std::time::{Duration, Instant};
+
+/// What to do with a position refresh request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RefreshDecision {
+    /// Re-read the position now
+    Refresh,
+    /// Refreshed too recently: re-send the request after this long, so the latest change is
+    /// still picked up
+    Defer(Duration),
+    /// A deferred refresh for the user is already scheduled and covers this request
+    Skip,
+}
+
+/// Limits how often each user's position is re-read from the pool. Logs, price ticks and
+/// rescans often ask for the same user several times a second during volatile markets;
+/// positions near liquidation are exempt so they're never read late.
+#[derive(Debug, Default)]
+pub struct RefreshThrottle {
+    min_interval: Duration,
+    last_refresh: DashMap<Address, Instant>,
+    deferred: DashSet<Address>,
+}
+
+impl RefreshThrottle {
+    pub fn new(min_interval: Duration) -> Self {
+        Self {
+            min_interval,
+            ..Self::default()
+        }
+    }
+
+    /// Whether refreshes are throttled (an interval of 0 disables it)
+    pub fn is_enabled(&self) -> bool {
+        !self.min_interval.is_zero()
+    }
+
+    /// Decide on a refresh of `user`, recording it when it should go ahead
+    pub fn check(&self, user: Address, near_liquidation: bool) -> RefreshDecision {
+        self.check_at(user, near_liquidation, Instant::now())
+    }
+
+    fn check_at(&self, user: Address, near_liquidation: bool, now: Instant) -> RefreshDecision {
+        if self.is_enabled() && !near_liquidation {
+            if let Some(last) = self.last_refresh.get(&user).map(|last| *last) {
+                let elapsed = now.saturating_duration_since(last);
+                if elapsed < self.min_interval {
+                    if !self.deferred.insert(user) {
+                        return RefreshDecision::Skip;
+                    }
+                    return RefreshDecision::Defer(self.min_interval - elapsed);
+                }
+            }
+        }
+
+        self.deferred.remove(&user);
+        self.last_refresh.insert(user, now);
+        RefreshDecision::Refresh
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_repeated_refreshes_collapse_into_one_trailing_refresh() {
+        let throttle = RefreshThrottle::new(Duration::from_secs(10));
+        let user = Address::repeat_byte(0x01);
+        let start = Instant::now();
+
+        assert_eq!(
+            throttle.check_at(user, false, start),
+            RefreshDecision::Refresh
+        );
+        assert_eq!(
+            throttle.check_at(user, false, start + Duration::from_secs(3)),
+            RefreshDecision::Defer(Duration::from_secs(7))
+        );
+        assert_eq!(
+            throttle.check_at(user, false, start + Duration::from_secs(4)),
+            RefreshDecision::Skip
+        );
+
+        // The deferred request comes back once the interval has passed
+        assert_eq!(
+            throttle.check_at(user, false, start + Duration::from_secs(10)),
+            RefreshDecision::Refresh
+        );
+        assert_eq!(
+            throttle.check_at(user, false, start + Duration::from_secs(11)),
+            RefreshDecision::Defer(Duration::from_secs(9))
+        );
+    }
+
+    #[test]
+    fn test_positions_near_liquidation_are_never_throttled() {
+        let throttle = RefreshThrottle::new(Duration::from_secs(10));
+        let user = Address::repeat_byte(0x01);
+        let start = Instant::now();
+
+        assert_eq!(
+            throttle.check_at(user, true, start),
+            RefreshDecision::Refresh
+        );
+        assert_eq!(
+            throttle.check_at(user, true, start + Duration::from_millis(100)),
+            RefreshDecision::Refresh
+        );
+
+        let disabled = RefreshThrottle::new(Duration::ZERO);
+        assert_eq!(
+            disabled.check_at(user, false, start),
+            RefreshDecision::Refresh
+        );
+        assert_eq!(
+            disabled.check_at(user, false, start),
+            RefreshDecision::Refresh
+        );
+    }
+}
@@ -0,0 +1,250 @@
+use alloy_primitives::{Address, B256};
+use alloy_provider::Provider;
+use alloy_rpc_types::BlockNumberOrTag;
+use eyre::Result;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashSet};
+use tracing::{info, warn};
+
+use crate::database::{self, DatabasePool};
+use crate::events::{BotEvent, EventSender};
+
+/// How many recent blocks are remembered; reorgs deeper than this can't be reconciled
+const REORG_WINDOW_BLOCKS: u64 = 128;
+
+#[derive(Debug, Clone)]
+struct TrackedBlock {
+    hash: B256,
+    users: HashSet<Address>,
+}
+
+/// Hashes of recently processed blocks and the users whose logs they carried
+#[derive(Debug, Default)]
+pub struct ReorgTracker {
+    blocks: Mutex<BTreeMap<u64, TrackedBlock>>,
+}
+
+impl ReorgTracker {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Remember a processed block and the users its logs touched. Returns false when a
+    /// different hash is already recorded at that height, i.e. the chain reorganized.
+    pub fn record(
+        &self,
+        block_number: u64,
+        block_hash: B256,
+        users: impl IntoIterator<Item = Address>,
+    ) -> bool {
+        let mut blocks = self.blocks.lock();
+        let entry = blocks.entry(block_number).or_insert_with(|| TrackedBlock {
+            hash: block_hash,
+            users: HashSet::new(),
+        });
+        if entry.hash != block_hash {
+            return false;
+        }
+        entry.users.extend(users);
+
+        // Keep only the reorg window below the newest block
+        if let Some(&newest) = blocks.keys().next_back() {
+            let cutoff = newest.saturating_sub(REORG_WINDOW_BLOCKS);
+            *blocks = blocks.split_off(&cutoff);
+        }
+        true
+    }
+
+    /// Record a new chain head. Returns the height of a recorded block the head doesn't build
+    /// on: its parent when the head's parent hash differs from the recorded one, or its own
+    /// height when a different block was recorded there. None while the chain only extends.
+    pub fn record_head(
+        &self,
+        block_number: u64,
+        block_hash: B256,
+        parent_hash: B256,
+    ) -> Option<u64> {
+        if let Some(parent) = block_number.checked_sub(1) {
+            if self.hash_at(parent).is_some_and(|recorded| recorded != parent_hash) {
+                return Some(parent);
+            }
+        }
+        (!self.record(block_number, block_hash, [])).then_some(block_number)
+    }
+
+    /// Hash recorded at `block_number`, if that block was processed recently
+    pub fn hash_at(&self, block_number: u64) -> Option<B256> {
+        self.blocks.lock().get(&block_number).map(|block| block.hash)
+    }
+
+    /// Recorded heights below `block_number`, newest first
+    fn heights_below(&self, block_number: u64) -> Vec<u64> {
+        self.blocks
+            .lock()
+            .range(..block_number)
+            .rev()
+            .map(|(height, _)| *height)
+            .collect()
+    }
+
+    /// Forget every block from `fork_block` on, returning the users their logs touched
+    pub fn orphan_from(&self, fork_block: u64) -> HashSet<Address> {
+        let mut blocks = self.blocks.lock();
+        let orphaned = blocks.split_off(&fork_block);
+        orphaned
+            .into_values()
+            .flat_map(|block| block.users)
+            .collect()
+    }
+}
+
+/// Canonical hash at `block_number`
+async fn canonical_hash<P>(provider: &P, block_number: u64) -> Result<Option<B256>>
+where
+    P: Provider,
+{
+    Ok(provider
+        .get_block_by_number(BlockNumberOrTag::Number(block_number), false)
+        .await?
+        .map(|block| block.header.hash))
+}
+
+/// First orphaned block: walk back from the conflicting height until a recorded hash is
+/// still canonical
+pub async fn find_fork_block<P>(
+    provider: &P,
+    tracker: &ReorgTracker,
+    conflict_block: u64,
+) -> Result<u64>
+where
+    P: Provider,
+{
+    let mut fork_block = conflict_block;
+    for height in tracker.heights_below(conflict_block) {
+        match (tracker.hash_at(height), canonical_hash(provider, height).await?) {
+            (Some(recorded), Some(canonical)) if recorded == canonical => break,
+            _ => fork_block = height,
+        }
+    }
+    Ok(fork_block)
+}
+
+/// Recover from a reorg detected at `conflict_block`: find the fork point, queue a health
+/// recheck for every user seen in the orphaned blocks, and roll back liquidation records whose
+/// transactions didn't make it into the new chain. Returns the first orphaned block, so the
+/// caller can resume processing from there.
+pub async fn reconcile_reorg<P>(
+    provider: &P,
+    db_pool: &DatabasePool,
+    tracker: &ReorgTracker,
+    conflict_block: u64,
+    event_tx: &EventSender,
+) -> Result<u64>
+where
+    P: Provider,
+{
+    let fork_block = find_fork_block(provider, tracker, conflict_block).await?;
+    let affected_users = tracker.orphan_from(fork_block);
+
+    warn!(
+        "🔀 Chain reorg detected at block {} (fork from block {}) - rechecking {} users",
+        conflict_block,
+        fork_block,
+        affected_users.len()
+    );
+
+    for user in affected_users {
+        let _ = event_tx.send(BotEvent::UserPositionChanged(user));
+    }
+
+    for (id, tx_hash) in database::get_liquidation_events_from_block(db_pool, fork_block).await? {
+        let tx_hash = tx_hash.as_deref().and_then(|hash| hash.parse::<B256>().ok());
+        let receipt = match tx_hash {
+            Some(hash) => provider.get_transaction_receipt(hash).await?,
+            None => None,
+        };
+
+        match receipt.filter(|receipt| receipt.status()).and_then(|receipt| receipt.block_number) {
+            Some(block_number) => {
+                info!(
+                    "🔀 Liquidation {:?} was re-included in block {}",
+                    tx_hash, block_number
+                );
+                database::update_liquidation_event_block(db_pool, id, block_number).await?;
+            }
+            None => {
+                warn!(
+                    "🔀 Rolling back liquidation record {:?} - transaction orphaned by reorg",
+                    tx_hash
+                );
+                database::delete_liquidation_event(db_pool, id).await?;
+            }
+        }
+    }
+
+    Ok(fork_block)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(byte: u8) -> B256 {
+        B256::repeat_byte(byte)
+    }
+
+    #[test]
+    fn test_conflicting_hash_is_reported() {
+        let tracker = ReorgTracker::new();
+        let user = Address::repeat_byte(0x01);
+
+        assert!(tracker.record(10, hash(0xa), [user]));
+        assert!(tracker.record(10, hash(0xa), []));
+        assert!(!tracker.record(10, hash(0xb), []));
+        assert_eq!(tracker.hash_at(10), Some(hash(0xa)));
+    }
+
+    #[test]
+    fn test_orphaned_blocks_return_affected_users() {
+        let tracker = ReorgTracker::new();
+        let (alice, bob, carol) = (
+            Address::repeat_byte(0x01),
+            Address::repeat_byte(0x02),
+            Address::repeat_byte(0x03),
+        );
+        tracker.record(10, hash(0xa), [alice]);
+        tracker.record(11, hash(0xb), [bob]);
+        tracker.record(12, hash(0xc), [carol, bob]);
+
+        let affected = tracker.orphan_from(11);
+
+        assert_eq!(affected, HashSet::from([bob, carol]));
+        assert_eq!(tracker.hash_at(10), Some(hash(0xa)));
+        assert_eq!(tracker.hash_at(11), None);
+        // The new chain's blocks can be recorded at the orphaned heights
+        assert!(tracker.record(11, hash(0xd), []));
+    }
+
+    #[test]
+    fn test_head_on_a_different_parent_is_a_reorg() {
+        let tracker = ReorgTracker::new();
+        assert_eq!(tracker.record_head(10, hash(0xa), hash(0x9)), None);
+        assert_eq!(tracker.record_head(11, hash(0xb), hash(0xa)), None);
+
+        // A block carrying no pool logs, replaced by one on another parent
+        assert_eq!(tracker.record_head(12, hash(0xc), hash(0xe)), Some(11));
+        assert_eq!(tracker.hash_at(12), None);
+        // A different block at a height already seen
+        assert_eq!(tracker.record_head(11, hash(0xd), hash(0xa)), Some(11));
+    }
+
+    #[test]
+    fn test_window_keeps_recent_blocks_only() {
+        let tracker = ReorgTracker::new();
+        tracker.record(1, hash(0x1), []);
+        tracker.record(1 + REORG_WINDOW_BLOCKS + 1, hash(0x2), []);
+
+        assert_eq!(tracker.hash_at(1), None);
+        assert_eq!(tracker.heights_below(u64::MAX), vec![1 + REORG_WINDOW_BLOCKS + 1]);
+    }
+}
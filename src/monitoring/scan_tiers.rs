@@ -0,0 +1,411 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use dashmap::DashMap;
+use eyre::Result;
+use futures::{stream, StreamExt};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::config::SharedConfig;
+use crate::database::PositionWriter;
+use crate::events::{BotEvent, EventSender};
+use crate::models::{ScanTier, TierInterval, UserPosition};
+use crate::monitoring::block_scanner;
+use crate::monitoring::latency::{self, LatencyStage};
+use crate::monitoring::metrics;
+use crate::monitoring::scanner::{check_user_health, is_liquidatable, PersistedPositions};
+
+/// How often the tiers are checked for due positions; also how often block tiers are re-checked
+/// while no newHeads subscription drives them
+const TIER_TICK: Duration = Duration::from_secs(1);
+
+/// Label of the positions no tier covers, which only full rescans check
+const FULL_RESCAN_TIER: &str = "full_rescan";
+
+/// Which positions are due for a re-check, by the tier their last health factor puts them in.
+/// Positions move between tiers as soon as a check changes their health factor.
+#[derive(Debug, Default)]
+pub struct TierSchedule {
+    tiers: Vec<ScanTier>, // Ascending by max_health_factor
+    last_checked: DashMap<Address, Instant>,
+}
+
+impl TierSchedule {
+    pub fn new(mut tiers: Vec<ScanTier>) -> Self {
+        tiers.sort_by_key(|tier| tier.max_health_factor);
+        Self {
+            tiers,
+            last_checked: DashMap::new(),
+        }
+    }
+
+    /// Whether tiers replace the single at-risk scan interval
+    pub fn is_enabled(&self) -> bool {
+        !self.tiers.is_empty()
+    }
+
+    /// Tier of a position with `health_factor`; None above the last tier
+    pub fn tier_of(&self, health_factor: U256) -> Option<&ScanTier> {
+        self.tiers
+            .iter()
+            .find(|tier| health_factor < tier.max_health_factor)
+    }
+
+    /// Health factor below which positions are re-checked every block
+    pub fn block_tier_ceiling(&self) -> U256 {
+        self.tiers
+            .iter()
+            .filter(|tier| tier.interval == TierInterval::EveryBlock)
+            .map(|tier| tier.max_health_factor)
+            .max()
+            .unwrap_or(U256::ZERO)
+    }
+
+    /// Positions with debt whose tier interval has passed since their last check, lowest health
+    /// factor first. Block tiers are included only when `include_block_tiers` is set.
+    pub fn due_users(
+        &self,
+        positions: &DashMap<Address, UserPosition>,
+        include_block_tiers: bool,
+        limit: Option<usize>,
+    ) -> Vec<Address> {
+        self.due_users_at(positions, include_block_tiers, limit, Instant::now())
+    }
+
+    fn due_users_at(
+        &self,
+        positions: &DashMap<Address, UserPosition>,
+        include_block_tiers: bool,
+        limit: Option<usize>,
+        now: Instant,
+    ) -> Vec<Address> {
+        let mut due: Vec<(U256, Address)> = positions
+            .iter()
+            .filter(|entry| !entry.total_debt_base.is_zero())
+            .filter(|entry| match self.tier_of(entry.health_factor) {
+                Some(tier) => match tier.interval {
+                    TierInterval::EveryBlock => include_block_tiers,
+                    TierInterval::Every(interval) => match self.last_checked.get(entry.key()) {
+                        Some(last) => now.saturating_duration_since(*last) >= interval,
+                        None => true,
+                    },
+                },
+                None => false,
+            })
+            .map(|entry| (entry.health_factor, *entry.key()))
+            .collect();
+        due.sort_unstable();
+        if let Some(limit) = limit {
+            due.truncate(limit);
+        }
+        due.into_iter().map(|(_, user)| user).collect()
+    }
+
+    pub fn mark_checked(&self, user: Address) {
+        self.last_checked.insert(user, Instant::now());
+    }
+
+    /// Forget users no longer tracked
+    pub fn prune(&self, positions: &DashMap<Address, UserPosition>) {
+        self.last_checked
+            .retain(|user, _| positions.contains_key(user));
+    }
+
+    /// Positions with debt per tier label, the ones above every tier as `full_rescan`
+    pub fn tier_sizes(&self, positions: &DashMap<Address, UserPosition>) -> Vec<(String, usize)> {
+        let mut sizes = vec![0; self.tiers.len() + 1];
+        for entry in positions.iter() {
+            if entry.total_debt_base.is_zero() {
+                continue;
+            }
+            let index = self
+                .tiers
+                .iter()
+                .position(|tier| entry.health_factor < tier.max_health_factor)
+                .unwrap_or(self.tiers.len());
+            sizes[index] += 1;
+        }
+        self.tiers
+            .iter()
+            .map(ScanTier::label)
+            .chain(std::iter::once(FULL_RESCAN_TIER.to_string()))
+            .zip(sizes)
+            .collect()
+    }
+}
+
+/// Result of re-checking a batch of positions
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CheckOutcome {
+    pub checked: usize,
+    pub liquidatable: usize,
+    pub over_budget: bool, // The deadline passed before every position was checked
+}
+
+/// Re-check `users` with up to `parallelism` health checks in flight, stopping at `deadline`.
+/// Fresh positions replace the tracked ones (and are persisted when they moved enough), and
+/// liquidatable users go to the priority processor or, without one, the event queue.
+#[allow(clippy::too_many_arguments)]
+pub async fn check_positions<P>(
+    provider: &Arc<P>,
+    pool_address: Address,
+    users: Vec<Address>,
+    parallelism: usize,
+    deadline: Option<tokio::time::Instant>,
+    min_hf_change_to_persist_bps: u64,
+    user_positions: &DashMap<Address, UserPosition>,
+    persisted_positions: &PersistedPositions,
+    schedule: &TierSchedule,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    position_writer: &PositionWriter,
+) -> CheckOutcome
+where
+    P: Provider,
+{
+    let mut checks = stream::iter(users)
+        .map(|user| async move {
+            (
+                user,
+                check_user_health(provider, pool_address, user, 1).await,
+            )
+        })
+        .buffer_unordered(parallelism.max(1));
+    let expired = async {
+        match deadline {
+            Some(deadline) => tokio::time::sleep_until(deadline).await,
+            None => std::future::pending().await,
+        }
+    };
+    tokio::pin!(expired);
+
+    let mut outcome = CheckOutcome::default();
+    loop {
+        let (user, result) = tokio::select! {
+            next = checks.next() => match next {
+                Some(next) => next,
+                None => break,
+            },
+            _ = &mut expired => {
+                outcome.over_budget = true;
+                break;
+            }
+        };
+
+        let position = match result {
+            Ok(position) => position,
+            Err(e) => {
+                error!("Failed to check user health for {:?}: {}", user, e);
+                metrics::record_rpc_error("scanner");
+                continue;
+            }
+        };
+        outcome.checked += 1;
+        schedule.mark_checked(user);
+
+        user_positions.insert(user, position.clone());
+        if persisted_positions.should_persist(&position, min_hf_change_to_persist_bps) {
+            match position_writer.enqueue(position.clone()) {
+                Ok(()) => persisted_positions.record(&position),
+                Err(e) => error!("Failed to store user position: {}", e),
+            }
+        }
+
+        if is_liquidatable(&position) {
+            outcome.liquidatable += 1;
+            latency::mark(user, LatencyStage::HealthConfirmed);
+            send_liquidation(user, event_tx, priority_liquidation_tx);
+        }
+    }
+    outcome
+}
+
+/// Hand a liquidatable user to the priority processor, or the event queue without one
+fn send_liquidation(
+    user: Address,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+) {
+    info!(
+        "⚡ User {:?} is LIQUIDATABLE (HF < 1.0) - sending liquidation",
+        user
+    );
+    if let Some(priority_tx) = priority_liquidation_tx {
+        match priority_tx.try_send(user) {
+            Ok(()) => return,
+            Err(e) => warn!(
+                "Failed to send priority liquidation for user {:?}: {} - falling back to the event queue",
+                user, e
+            ),
+        }
+    }
+    if let Err(e) = event_tx.send(BotEvent::LiquidationOpportunity(user)) {
+        error!("❌ CRITICAL: Failed to send liquidation opportunity: {}", e);
+    }
+}
+
+/// Re-check positions on their tier's interval, in place of the single at-risk scan interval.
+/// Block tiers are left to the block scanner while its subscription is live and re-checked
+/// every tick otherwise.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_tiered_scan<P>(
+    provider: Arc<P>,
+    pool_address: Address,
+    live_config: SharedConfig,
+    schedule: Arc<TierSchedule>,
+    user_positions: Arc<DashMap<Address, UserPosition>>,
+    persisted_positions: Arc<PersistedPositions>,
+    event_tx: EventSender,
+    priority_liquidation_tx: Option<mpsc::Sender<Address>>,
+    position_writer: PositionWriter,
+) -> Result<()>
+where
+    P: Provider,
+{
+    if !schedule.is_enabled() {
+        debug!("Scan tiers disabled - at-risk users are scanned on a single interval");
+        return Ok(());
+    }
+    for tier in &schedule.tiers {
+        match tier.interval {
+            TierInterval::EveryBlock => info!("🔧 Scan tier {}: every block", tier.label()),
+            TierInterval::Every(interval) => {
+                info!("🔧 Scan tier {}: every {:?}", tier.label(), interval)
+            }
+        }
+    }
+
+    let mut interval = tokio::time::interval(TIER_TICK);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+    loop {
+        interval.tick().await;
+        let config = live_config.load_full();
+
+        let include_block_tiers = !block_scanner::is_block_scan_active();
+        let users = schedule.due_users(
+            &user_positions,
+            include_block_tiers,
+            config.at_risk_scan_limit,
+        );
+        if users.is_empty() {
+            continue;
+        }
+
+        let total = users.len();
+        let outcome = check_positions(
+            &provider,
+            pool_address,
+            users,
+            config.health_check_parallelism,
+            None,
+            config.min_hf_change_to_persist_bps,
+            &user_positions,
+            &persisted_positions,
+            &schedule,
+            &event_tx,
+            &priority_liquidation_tx,
+            &position_writer,
+        )
+        .await;
+        debug!(
+            "🔍 Tiered scan: {}/{} due positions checked, {} liquidatable",
+            outcome.checked, total, outcome.liquidatable
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn position(byte: u8, health_factor: f64) -> UserPosition {
+        UserPosition {
+            address: Address::repeat_byte(byte),
+            total_collateral_base: U256::from(10_000u64),
+            total_debt_base: U256::from(5_000u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_500u64),
+            ltv: U256::from(8_000u64),
+            health_factor: U256::from((health_factor * 1e18) as u128),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: health_factor < 1.1,
+        }
+    }
+
+    #[test]
+    fn test_scan_tier_parsing() {
+        let tier = ScanTier::parse("1.02:block").unwrap();
+        assert_eq!(
+            tier.max_health_factor,
+            U256::from(1_020_000_000_000_000_000u64)
+        );
+        assert_eq!(tier.interval, TierInterval::EveryBlock);
+        assert_eq!(tier.label(), "hf<1.02");
+        assert_eq!(
+            ScanTier::parse(" 1.5 : 60 ").unwrap().interval,
+            TierInterval::Every(Duration::from_secs(60))
+        );
+        assert!(ScanTier::parse("1.1").is_none());
+        assert!(ScanTier::parse("1.1:soon").is_none());
+        assert!(ScanTier::parse("-1:5").is_none());
+        assert_eq!(ScanTier::default_tiers().len(), 3);
+    }
+
+    #[test]
+    fn test_positions_are_due_by_tier_and_migrate_as_hf_changes() {
+        let schedule = TierSchedule::new(ScanTier::default_tiers());
+        let positions = DashMap::new();
+        for position in [
+            position(0x01, 1.01), // Every block
+            position(0x02, 1.05), // Every 5s
+            position(0x03, 1.3),  // Every minute
+            position(0x04, 2.0),  // Full rescan only
+        ] {
+            positions.insert(position.address, position);
+        }
+        let start = Instant::now();
+        let user = Address::repeat_byte;
+
+        // Never checked: every tiered position is due, lowest health factor first
+        assert_eq!(
+            schedule.due_users_at(&positions, true, None, start),
+            vec![user(0x01), user(0x02), user(0x03)]
+        );
+        assert_eq!(
+            schedule.due_users_at(&positions, false, Some(1), start),
+            vec![user(0x02)]
+        );
+
+        for byte in 1..=3 {
+            schedule.last_checked.insert(user(byte), start);
+        }
+        let later = start + Duration::from_secs(6);
+        assert_eq!(
+            schedule.due_users_at(&positions, false, None, later),
+            vec![user(0x02)]
+        );
+
+        // The minute tier's position dropped: it's due on the 5s tier's schedule now
+        positions.insert(user(0x03), position(0x03, 1.08));
+        assert_eq!(
+            schedule.due_users_at(&positions, false, None, later),
+            vec![user(0x02), user(0x03)]
+        );
+
+        assert_eq!(
+            schedule.tier_sizes(&positions),
+            vec![
+                ("hf<1.02".to_string(), 1),
+                ("hf<1.1".to_string(), 2),
+                ("hf<1.5".to_string(), 0),
+                ("full_rescan".to_string(), 1),
+            ]
+        );
+        assert_eq!(
+            schedule.block_tier_ceiling(),
+            U256::from(1_020_000_000_000_000_000u64)
+        );
+    }
+}
@@ -1,6 +1,6 @@
-use crate::database::DatabasePool;
+use crate::database::{DatabasePool, PositionWriter};
 use alloy_contract::ContractInstance;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, I256, U256, U512};
 use alloy_provider::Provider;
 use dashmap::DashMap;
 use eyre::Result;
@@ -12,15 +12,22 @@ use tokio::sync::mpsc;
 use tokio::time::sleep;
 use tracing::{debug, error, info, warn};
 
-use crate::config::BotConfig;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::config::SharedConfig;
 use crate::database;
-use crate::events::BotEvent;
+use crate::events::{BotEvent, EventSender};
+use crate::liquidation::WalletPool;
 use crate::models::{AssetConfig, UserPosition};
+use crate::monitoring::block_scanner;
+use crate::monitoring::latency::{self, LatencyStage};
+use crate::monitoring::metrics;
+use crate::monitoring::price_trigger::{PriceTriggerIndex, TriggerSide};
+use crate::monitoring::value_at_risk::{format_base_currency, ValueAtRiskGauge};
 
 // Threshold constants for health factor calculations (in 18 decimals)
 const LIQUIDATION_THRESHOLD: u64 = 1000000000000000000; // 1.0 * 1e18 - liquidation can occur
 const CRITICAL_THRESHOLD: u64 = 1100000000000000000; // 1.1 * 1e18 - critically at risk
-const CHANGE_THRESHOLD: u64 = 10000000000000000; // 0.01 * 1e18 - 1% change for logging
+const LATENCY_REPORT_WINDOW_MS: i64 = 24 * 3600 * 1000; // Latency percentiles cover the last day
 
 /// Guard to ensure user is removed from processing set when dropped
 struct ProcessingGuard {
@@ -96,6 +103,177 @@ fn parse_u256_from_result(
     Ok(uint_value.0)
 }
 
+/// Check that `pool_address` has contract code deployed so account data calls reach a real pool
+pub async fn verify_pool_has_code<P>(provider: &Arc<P>, pool_address: Address) -> Result<()>
+where
+    P: Provider,
+{
+    let code = provider.get_code_at(pool_address).await?;
+
+    if code.is_empty() {
+        return Err(eyre::eyre!(
+            "No contract code at pool address {} - check the configured pool/network",
+            pool_address
+        ));
+    }
+
+    info!("✅ Pool contract code verified at {}", pool_address);
+    Ok(())
+}
+
+/// Archival runs per zero-debt cooldown period when no explicit interval is configured
+const ARCHIVAL_RUNS_PER_COOLDOWN: u64 = 4;
+const MIN_ARCHIVAL_INTERVAL_SECS: u64 = 3600; // 1 hour
+const MAX_ARCHIVAL_INTERVAL_SECS: u64 = 7 * 24 * 3600; // 7 days
+
+/// Seconds between archival runs: the explicit interval if configured, otherwise derived
+/// from the zero-debt cooldown
+pub fn archival_interval_secs(explicit_interval_secs: Option<u64>, cooldown_hours: u64) -> u64 {
+    match explicit_interval_secs {
+        Some(interval_secs) => interval_secs,
+        None => derived_archival_interval_secs(cooldown_hours),
+    }
+}
+
+/// Derive the archival interval from the zero-debt cooldown, running several times per
+/// cooldown period and clamped between 1 hour and 7 days
+pub fn derived_archival_interval_secs(cooldown_hours: u64) -> u64 {
+    // Prevent zero duration which would cause continuous tight loop
+    if cooldown_hours == 0 {
+        warn!("zero_debt_cooldown_hours is 0, using minimum archival interval of 1 hour");
+        return MIN_ARCHIVAL_INTERVAL_SECS;
+    }
+
+    match cooldown_hours.checked_mul(3600) {
+        Some(total_secs) => (total_secs / ARCHIVAL_RUNS_PER_COOLDOWN)
+            .clamp(MIN_ARCHIVAL_INTERVAL_SECS, MAX_ARCHIVAL_INTERVAL_SECS),
+        None => {
+            error!(
+                "zero_debt_cooldown_hours ({}) is too large and would cause overflow, using maximum safe interval of 7 days",
+                cooldown_hours
+            );
+            MAX_ARCHIVAL_INTERVAL_SECS
+        }
+    }
+}
+
+/// Periodic scan interval adjusted for market volatility: the base interval while volatility
+/// is unknown or zero, shrinking linearly towards `min_secs` as volatility approaches the
+/// circuit breaker's volatility threshold, always clamped to `[min_secs, max_secs]`
+pub fn adaptive_scan_interval_secs(
+    volatility_percent: Option<f64>,
+    volatility_threshold_percent: f64,
+    base_secs: u64,
+    min_secs: u64,
+    max_secs: u64,
+) -> u64 {
+    let max_secs = max_secs.max(min_secs);
+    let interval = match volatility_percent {
+        Some(volatility) if volatility_threshold_percent > 0.0 && base_secs > min_secs => {
+            let ratio = (volatility / volatility_threshold_percent).clamp(0.0, 1.0);
+            base_secs - ((base_secs - min_secs) as f64 * ratio).round() as u64
+        }
+        _ => base_secs,
+    };
+    interval.clamp(min_secs, max_secs)
+}
+
+/// Whether a position can currently be liquidated (health factor below 1.0 with outstanding debt)
+pub fn is_liquidatable(position: &UserPosition) -> bool {
+    position.health_factor < U256::from(LIQUIDATION_THRESHOLD) && position.total_debt_base > U256::ZERO
+}
+
+/// Health factor move between two checks in basis points of the previous value.
+///
+/// Computed with a 512-bit intermediate so it can't overflow (health factors of debt-free
+/// users are `U256::MAX`); saturates at `U256::MAX`. Any move away from zero is maximal.
+pub fn health_factor_change_bps(old_hf: U256, new_hf: U256) -> U256 {
+    if old_hf == new_hf {
+        return U256::ZERO;
+    }
+    if old_hf.is_zero() {
+        return U256::MAX;
+    }
+
+    let diff = U512::from(old_hf.abs_diff(new_hf));
+    U256::saturating_from(diff * U512::from(10_000u64) / U512::from(old_hf))
+}
+
+/// Whether a health factor move is large enough to persist and log (a minimum of 0 makes
+/// every check significant)
+pub fn is_significant_hf_change(old_hf: U256, new_hf: U256, min_change_bps: u64) -> bool {
+    health_factor_change_bps(old_hf, new_hf) >= U256::from(min_change_bps)
+}
+
+/// Whether a freshly checked position should be written to the database. New users and
+/// at-risk, liquidatable or debt status flips always persist; otherwise the health factor
+/// has to move by at least `min_change_bps`.
+pub fn should_persist_position(
+    old_position: Option<&UserPosition>,
+    position: &UserPosition,
+    min_change_bps: u64,
+) -> bool {
+    let Some(old_position) = old_position else {
+        return true;
+    };
+
+    old_position.is_at_risk != position.is_at_risk
+        || is_liquidatable(old_position) != is_liquidatable(position)
+        || old_position.total_debt_base.is_zero() != position.total_debt_base.is_zero()
+        || is_significant_hf_change(
+            old_position.health_factor,
+            position.health_factor,
+            min_change_bps,
+        )
+}
+
+/// Last position written to the database for each user. Whether a fresh check is worth
+/// persisting is decided against it rather than against the previous check, which is
+/// replaced every time: a run of small moves is still written once together they add up.
+#[derive(Debug, Default)]
+pub struct PersistedPositions {
+    positions: DashMap<Address, UserPosition>,
+}
+
+impl PersistedPositions {
+    pub fn get(&self, user: Address) -> Option<UserPosition> {
+        self.positions.get(&user).map(|position| position.clone())
+    }
+
+    /// Whether `position` moved far enough from the last persisted one to be written
+    pub fn should_persist(&self, position: &UserPosition, min_change_bps: u64) -> bool {
+        should_persist_position(self.get(position.address).as_ref(), position, min_change_bps)
+    }
+
+    /// Note `position` as the user's stored position
+    pub fn record(&self, position: &UserPosition) {
+        self.positions.insert(position.address, position.clone());
+    }
+}
+
+/// Log a significant health factor move for a user who was already at risk, returning
+/// whether anything was logged
+fn log_at_risk_hf_change(user: Address, old_hf: U256, new_hf: U256, min_change_bps: u64) -> bool {
+    if !is_significant_hf_change(old_hf, new_hf, min_change_bps) {
+        return false;
+    }
+
+    let direction = if new_hf > old_hf {
+        "↗️ IMPROVED"
+    } else {
+        "↘️ WORSENED"
+    };
+
+    info!(
+        "⚠️  AT-RISK USER {} HEALTH FACTOR: {:?} (HF: {} → {})",
+        direction,
+        user,
+        format_health_factor(old_hf),
+        format_health_factor(new_hf)
+    );
+    true
+}
+
 pub async fn check_user_health<P>(
     provider: &Arc<P>,
     pool_address: Address,
@@ -196,6 +374,15 @@ where
 
     let result = provider.call(&call_request).await?;
 
+    parse_user_account_data(&result, user_address)
+}
+
+/// Parse a raw `getUserAccountData` response into a `UserPosition`.
+///
+/// Aave reports a zero-debt user's health factor as `type(uint256).max`, so a response of
+/// all zeros never comes from a real position - it means the call hit an address with no
+/// code (wrong pool, non-contract) and is rejected rather than treated as a healthy user.
+pub fn parse_user_account_data(result: &[u8], user_address: Address) -> Result<UserPosition> {
     // Parse the result - getUserAccountData returns 6 uint256 values
     if result.len() < 192 {
         // 6 * 32 bytes
@@ -205,6 +392,17 @@ where
         ));
     }
 
+    if result[..192].iter().all(|&b| b == 0) {
+        warn!(
+            "⚠️ getUserAccountData for {} returned an all-zero response - pool address may have no code",
+            user_address
+        );
+        return Err(eyre::eyre!(
+            "Empty getUserAccountData response for {}: call did not reach a pool contract",
+            user_address
+        ));
+    }
+
     // Parse the 6 uint256 values returned by getUserAccountData
     let total_collateral_base = U256::from_be_slice(&result[0..32]);
     let total_debt_base = U256::from_be_slice(&result[32..64]);
@@ -237,11 +435,11 @@ where
     Ok(position)
 }
 
-/// Helper function to get user's collateral assets from the blockchain
-async fn get_user_collateral_assets<P>(
+/// Helper function to get user's collateral and borrowed assets from the blockchain
+async fn get_user_reserve_assets<P>(
     pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
     user: Address,
-) -> Result<Vec<Address>>
+) -> Result<(Vec<Address>, Vec<Address>)>
 where
     P: Provider,
 {
@@ -299,49 +497,66 @@ where
 
     debug!("Found {} reserves in the pool", reserves.len());
 
-    // Decode user configuration bitfield to find collateral assets
+    // Decode user configuration bitfield to find collateral and borrowed assets
     let mut user_collateral_assets = Vec::new();
+    let mut user_debt_assets = Vec::new();
 
     // Each asset has 2 bits in the configuration:
     // - Bit 2*i: whether the asset is used as collateral
     // - Bit 2*i+1: whether the asset is borrowed
     for (i, &reserve_address) in reserves.iter().enumerate() {
         let collateral_bit = (config_data >> (2 * i)) & U256::from(1u8);
+        let borrowed_bit = (config_data >> (2 * i + 1)) & U256::from(1u8);
 
         if collateral_bit != U256::ZERO {
             user_collateral_assets.push(reserve_address);
             debug!("User has {} as collateral", reserve_address);
         }
+        if borrowed_bit != U256::ZERO {
+            user_debt_assets.push(reserve_address);
+            debug!("User has borrowed {}", reserve_address);
+        }
     }
 
     debug!(
-        "User {} has {} collateral assets",
+        "User {} has {} collateral assets and {} borrowed assets",
         user,
-        user_collateral_assets.len()
+        user_collateral_assets.len(),
+        user_debt_assets.len()
     );
 
-    // Note: Returning an empty vector here is valid - it means the user genuinely
-    // has no collateral assets, which is different from parsing errors above
-    Ok(user_collateral_assets)
+    // Note: Returning empty vectors here is valid - it means the user genuinely
+    // has no collateral or debt, which is different from parsing errors above
+    Ok((user_collateral_assets, user_debt_assets))
 }
 
-/// Update the users_by_collateral mapping for a specific user
+/// Update the users_by_collateral and users_by_debt mappings for a specific user
 pub async fn update_user_collateral_mapping<P>(
     pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
     user: Address,
     users_by_collateral: &Arc<DashMap<Address, HashSet<Address>>>,
+    users_by_debt: &Arc<DashMap<Address, HashSet<Address>>>,
     asset_configs: Option<&HashMap<Address, AssetConfig>>,
 ) -> Result<()>
 where
     P: Provider,
 {
-    // Get user's collateral assets
-    let collateral_assets = get_user_collateral_assets(pool_contract, user).await?;
+    // Get user's collateral and borrowed assets
+    let (collateral_assets, debt_assets) = get_user_reserve_assets(pool_contract, user).await?;
 
-    // Remove user from all existing collateral mappings first
+    // Remove user from all existing mappings first
     for mut entry in users_by_collateral.iter_mut() {
         entry.value_mut().remove(&user);
     }
+    for mut entry in users_by_debt.iter_mut() {
+        entry.value_mut().remove(&user);
+    }
+    for asset_address in &debt_assets {
+        users_by_debt
+            .entry(*asset_address)
+            .or_insert_with(HashSet::new)
+            .insert(user);
+    }
 
     // If no collateral assets found but we have asset configs, that's normal
     // (user might not have any collateral), don't use fallbacks
@@ -377,13 +592,16 @@ pub async fn update_user_position<P>(
     pool_contract: &ContractInstance<alloy_transport::BoxTransport, Arc<P>>,
     db_pool: &DatabasePool,
     user_positions: Arc<DashMap<Address, UserPosition>>,
+    persisted_positions: &PersistedPositions,
     processing_users: Arc<SyncRwLock<HashSet<Address>>>,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
+    event_tx: EventSender,
     health_factor_threshold: U256,
+    min_hf_change_to_persist_bps: u64,
     user: Address,
     users_by_collateral: Option<Arc<DashMap<Address, HashSet<Address>>>>,
+    users_by_debt: Option<Arc<DashMap<Address, HashSet<Address>>>>,
     asset_configs: Option<&HashMap<Address, AssetConfig>>,
-    priority_liquidation_tx: Option<mpsc::UnboundedSender<Address>>,
+    priority_liquidation_tx: Option<mpsc::Sender<Address>>,
 ) -> Result<()>
 where
     P: Provider,
@@ -407,10 +625,20 @@ where
             // Update in memory first (this is atomic due to DashMap's internal locking)
             user_positions.insert(user, position.clone());
 
-            // Save to database first before any events
-            let database_save_successful =
+            // Save to database first before any events, skipping moves that are still
+            // sub-threshold from what was last stored (it is still current enough to act on)
+            let database_save_successful = if !persisted_positions
+                .should_persist(&position, min_hf_change_to_persist_bps)
+            {
+                debug!(
+                    "Health factor change for {:?} below {} bps - not persisting",
+                    user, min_hf_change_to_persist_bps
+                );
+                true
+            } else {
                 match crate::database::save_user_position(db_pool, &position).await {
                     Ok(()) => {
+                        persisted_positions.record(&position);
                         debug!(
                             "✅ Successfully saved user position to database: {:?}",
                             user
@@ -421,17 +649,21 @@ where
                         error!("Failed to save user position for {:?}: {}", user, e);
                         false
                     }
-                };
+                }
+            };
 
-            // Populate users_by_collateral mapping by calling getUserConfiguration
-            // and getReservesList to determine which assets this user has as collateral
-            if let Some(users_by_collateral) = &users_by_collateral {
+            // Populate users_by_collateral and users_by_debt by calling getUserConfiguration
+            // and getReservesList to determine which assets this user supplies and owes
+            if let (Some(users_by_collateral), Some(users_by_debt)) =
+                (&users_by_collateral, &users_by_debt)
+            {
                 if position.total_collateral_base > U256::ZERO {
-                    // Update the collateral mapping for this user using all configured assets
+                    // Update the asset mappings for this user using all configured assets
                     if let Err(e) = update_user_collateral_mapping(
                         pool_contract,
                         user,
                         users_by_collateral,
+                        users_by_debt,
                         asset_configs,
                     )
                     .await
@@ -454,10 +686,11 @@ where
                 && position.health_factor < U256::from(LIQUIDATION_THRESHOLD)
                 && position.total_debt_base > U256::ZERO
             {
+                latency::mark(user, LatencyStage::HealthConfirmed);
                 // Send to priority channel if available (for immediate processing)
                 if let Some(priority_tx) = &priority_liquidation_tx {
                     debug!("⚡ Sending priority liquidation for user: {:?}", user);
-                    if let Err(e) = priority_tx.send(user) {
+                    if let Err(e) = priority_tx.try_send(user) {
                         warn!(
                             "Failed to send priority liquidation for user {:?}: {}",
                             user, e
@@ -498,31 +731,13 @@ where
                             error!("Failed to log at-risk event: {}", e);
                         }
                     } else {
-                        // User was already at-risk, check if health factor changed significantly
-                        let hf_diff = if position.health_factor > old_pos.health_factor {
-                            position.health_factor - old_pos.health_factor
-                        } else {
-                            old_pos.health_factor - position.health_factor
-                        };
-
-                        // Log if health factor changed by more than 1%
-                        let change_threshold = U256::from(CHANGE_THRESHOLD);
-
-                        if hf_diff > change_threshold {
-                            let direction = if position.health_factor > old_pos.health_factor {
-                                "↗️ IMPROVED"
-                            } else {
-                                "↘️ WORSENED"
-                            };
-
-                            info!(
-                                "⚠️  AT-RISK USER {} HEALTH FACTOR: {:?} (HF: {} → {})",
-                                direction,
-                                user,
-                                format_health_factor(old_pos.health_factor),
-                                format_health_factor(position.health_factor)
-                            );
-                        } else {
+                        // User was already at-risk, log only significant health factor moves
+                        if !log_at_risk_hf_change(
+                            user,
+                            old_pos.health_factor,
+                            position.health_factor,
+                            min_hf_change_to_persist_bps,
+                        ) {
                             // Log ongoing at-risk status if health factor is dangerously low
                             let danger_threshold = U256::from(CRITICAL_THRESHOLD);
                             if position.health_factor < danger_threshold {
@@ -561,6 +776,7 @@ where
         }
         Err(e) => {
             error!("Failed to check user health for {:?}: {}", user, e);
+            metrics::record_rpc_error("scanner");
         }
     }
 
@@ -571,16 +787,21 @@ pub async fn run_periodic_scan<P>(
     provider: Arc<P>,
     pool_address: Address,
     db_pool: DatabasePool,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
-    config: BotConfig,
+    event_tx: EventSender,
+    live_config: SharedConfig,
     _asset_configs: HashMap<Address, AssetConfig>,
     user_positions: Arc<DashMap<Address, UserPosition>>,
-    priority_liquidation_tx: Option<mpsc::UnboundedSender<Address>>,
+    persisted_positions: Arc<PersistedPositions>,
+    value_at_risk: Arc<ValueAtRiskGauge>,
+    priority_liquidation_tx: Option<mpsc::Sender<Address>>,
+    circuit_breaker: Arc<CircuitBreaker>,
+    position_writer: PositionWriter,
 ) -> Result<()>
 where
     P: Provider,
 {
     info!("Starting periodic position scan...");
+    let config = live_config.load_full();
 
     // Log configuration
     match config.at_risk_scan_limit {
@@ -595,58 +816,65 @@ where
         config.full_rescan_interval_minutes
     );
 
-    let mut interval = tokio::time::interval(
-        tokio::time::Duration::from_secs(config.monitoring_interval_secs * 6), // Slower than event-driven updates
-    );
+    let mut scan_interval_secs = config.periodic_scan_interval_secs;
+    if config.adaptive_scan_interval_enabled {
+        info!(
+            "🔧 Adaptive scan interval: {}s base, {}-{}s with volatility",
+            scan_interval_secs, config.min_scan_interval_secs, config.max_scan_interval_secs
+        );
+    }
+    let mut interval = tokio::time::interval(Duration::from_secs(scan_interval_secs));
 
     let mut full_rescan_interval = tokio::time::interval(
         tokio::time::Duration::from_secs(config.full_rescan_interval_minutes * 60), // Full rescan interval
     );
 
     // Separate archival interval to avoid timestamp conflicts
-    // Calculate archival interval with overflow protection and bounds checking
-    let archival_interval_secs = {
-        let cooldown_hours = config.zero_debt_cooldown_hours;
-
-        // Prevent zero duration which would cause continuous tight loop
-        if cooldown_hours == 0 {
-            warn!("zero_debt_cooldown_hours is 0, using minimum archival interval of 1 hour");
-            3600 // 1 hour minimum
-        } else {
-            // Check for potential overflow: cooldown_hours * 3600 should not overflow u64
-            let max_safe_hours = u64::MAX / 3600; // ~5.1 trillion hours
-            if cooldown_hours > max_safe_hours {
-                error!(
-                    "zero_debt_cooldown_hours ({}) is too large and would cause overflow, using maximum safe interval of 7 days",
-                    cooldown_hours
-                );
-                7 * 24 * 3600 // 7 days maximum
-            } else {
-                // Use checked multiplication to detect any remaining overflow edge cases
-                match cooldown_hours.checked_mul(3600) {
-                    Some(total_secs) => {
-                        let interval_secs = total_secs / 4; // Check 4x per cooldown period
-                                                            // Ensure minimum interval of 1 hour and maximum of 7 days
-                        std::cmp::max(3600, std::cmp::min(interval_secs, 7 * 24 * 3600))
-                    }
-                    None => {
-                        error!(
-                            "Integer overflow when calculating archival interval for {} hours, using 7 day default",
-                            cooldown_hours
-                        );
-                        7 * 24 * 3600 // 7 days fallback
-                    }
-                }
-            }
-        }
-    };
+    let archival_run_secs = archival_interval_secs(
+        config.archival_run_interval_secs,
+        config.zero_debt_cooldown_hours,
+    );
+    info!("🔧 Archival run interval: {} seconds", archival_run_secs);
 
     let mut archival_interval =
-        tokio::time::interval(tokio::time::Duration::from_secs(archival_interval_secs));
+        tokio::time::interval(tokio::time::Duration::from_secs(archival_run_secs));
 
     loop {
         tokio::select! {
             _ = interval.tick() => {
+                let config = live_config.load_full();
+
+                // Retune the next tick to the current market volatility
+                if config.adaptive_scan_interval_enabled {
+                    let adjusted_secs = adaptive_scan_interval_secs(
+                        circuit_breaker.current_volatility_percent(),
+                        config.max_price_volatility_threshold,
+                        config.periodic_scan_interval_secs,
+                        config.min_scan_interval_secs,
+                        config.max_scan_interval_secs,
+                    );
+                    if adjusted_secs != scan_interval_secs {
+                        info!(
+                            "⏱️ Scan interval adjusted for volatility: {}s -> {}s",
+                            scan_interval_secs, adjusted_secs
+                        );
+                        scan_interval_secs = adjusted_secs;
+                        let period = Duration::from_secs(scan_interval_secs);
+                        interval = tokio::time::interval_at(tokio::time::Instant::now() + period, period);
+                    }
+                }
+
+                // Scan tiers re-check at-risk users on their own schedule
+                if !config.scan_tiers.is_empty() {
+                    continue;
+                }
+
+                // New blocks drive the at-risk scan while subscribed; the timer covers any gap
+                if block_scanner::is_block_scan_active() {
+                    debug!("🧱 Block-driven scan active - skipping timed at-risk scan");
+                    continue;
+                }
+
                 // Regular at-risk scan with configurable limit
                 let at_risk_users = match crate::database::get_at_risk_users_with_limit(&db_pool, config.at_risk_scan_limit).await {
                     Ok(users) => users,
@@ -678,27 +906,31 @@ where
 
                             if position.is_at_risk {
                                 at_risk_users_count += 1;
-                                info!(
+                                crate::per_item_log!(
+                                    config.verbose_per_item_logging,
                                     "⚠️  At-risk user found: {:?} (HF: {})",
                                     user,
                                     format_health_factor(position.health_factor)
                                 );
 
                                                 // Store in database
-                if let Err(e) =
-                    crate::database::save_user_position(&db_pool, &position).await
-                {
-                    error!("Failed to store user position: {}", e);
+                let persisted = persisted_positions.get(user.address).unwrap_or_else(|| user.clone());
+                if should_persist_position(Some(&persisted), &position, config.min_hf_change_to_persist_bps) {
+                    match position_writer.enqueue(position.clone()) {
+                        Ok(()) => persisted_positions.record(&position),
+                        Err(e) => error!("Failed to store user position: {}", e),
+                    }
                 }
 
                 // Only send liquidation opportunity if user is actually liquidatable (HF < 1.0)
                 if position.health_factor < U256::from(LIQUIDATION_THRESHOLD) && position.total_debt_base > U256::ZERO {
+                    latency::mark(user.address, LatencyStage::HealthConfirmed);
                     // Send to priority channel if available (for immediate processing)
                     if let Some(priority_tx) = &priority_liquidation_tx {
                         info!("⚡ User {:?} is LIQUIDATABLE (HF < 1.0) - sending priority liquidation", user.address);
-                        match priority_tx.send(user.address) {
+                        match priority_tx.try_send(user.address) {
                             Ok(()) => {
-                                info!("✅ Priority liquidation sent successfully for user {:?}", user.address);
+                                crate::per_item_log!(config.verbose_per_item_logging, "✅ Priority liquidation sent successfully for user {:?}", user.address);
                             }
                             Err(e) => {
                                 warn!("❌ Failed to send priority liquidation for user {:?}: {}", user.address, e);
@@ -707,7 +939,7 @@ where
                                 if let Err(e) = event_tx.send(BotEvent::LiquidationOpportunity(user.address)) {
                                     error!("❌ CRITICAL: Failed to send liquidation opportunity fallback: {}", e);
                                 } else {
-                                    info!("✅ Fallback liquidation opportunity sent for user {:?}", user.address);
+                                    crate::per_item_log!(config.verbose_per_item_logging, "✅ Fallback liquidation opportunity sent for user {:?}", user.address);
                                 }
                             }
                         }
@@ -715,7 +947,7 @@ where
                         info!("🎯 User {:?} is LIQUIDATABLE (HF < 1.0) - sending liquidation opportunity via regular channel", user.address);
                         match event_tx.send(BotEvent::LiquidationOpportunity(user.address)) {
                             Ok(()) => {
-                                info!("✅ Liquidation opportunity sent successfully for user {:?}", user.address);
+                                crate::per_item_log!(config.verbose_per_item_logging, "✅ Liquidation opportunity sent successfully for user {:?}", user.address);
                             }
                             Err(e) => {
                                 error!("❌ CRITICAL: Failed to send liquidation opportunity: {}", e);
@@ -732,6 +964,7 @@ where
                         }
                         Err(e) => {
                             error!("Failed to check user health for {:?}: {}", user, e);
+                            metrics::record_rpc_error("scanner");
                             // Continue with next user rather than failing completely
                         }
                     }
@@ -741,8 +974,16 @@ where
                     "✅ Regular scan complete: {} checked, {} at-risk found",
                     checked_users, at_risk_users_count
                 );
+
+                value_at_risk.update(
+                    &user_positions,
+                    config.value_at_risk_bonus_bps,
+                    config.value_at_risk_alert_threshold,
+                );
             }
             _ = full_rescan_interval.tick() => {
+                let config = live_config.load_full();
+
                 // Full rescan: check all users to ensure complete coverage
                 info!("🔍 Starting full rescan: checking all users to ensure complete coverage");
 
@@ -764,16 +1005,24 @@ where
                     // Add small delay between checks to avoid rate limiting
                     if checked_users > 0 && checked_users % 50 == 0 {
                         sleep(Duration::from_millis(500)).await; // Longer pause for full scans
-                        info!("🔍 Full rescan progress: {}/{} users checked", checked_users, all_users.len());
+                        crate::per_item_log!(config.verbose_per_item_logging, "🔍 Full rescan progress: {}/{} users checked", checked_users, all_users.len());
                     }
 
                     match check_user_health(&provider, pool_address, user.address, 3).await {
                         Ok(position) => {
                             checked_users += 1;
 
+                            // Keep the tracked position current, so scan tiers pick up users
+                            // whose health factor dropped into a tier
+                            user_positions.insert(user.address, position.clone());
+
                             // Update the position in database
-                            if let Err(e) = crate::database::save_user_position(&db_pool, &position).await {
-                                error!("Failed to store user position during full rescan: {}", e);
+                            let persisted = persisted_positions.get(user.address).unwrap_or_else(|| user.clone());
+                            if should_persist_position(Some(&persisted), &position, config.min_hf_change_to_persist_bps) {
+                                match position_writer.enqueue(position.clone()) {
+                                    Ok(()) => persisted_positions.record(&position),
+                                    Err(e) => error!("Failed to store user position during full rescan: {}", e),
+                                }
                             }
 
                             if position.is_at_risk {
@@ -792,12 +1041,13 @@ where
                                 // Send liquidation opportunity for ANY user that is actually liquidatable (HF < 1.0)
                                 // regardless of whether they're newly at-risk or not
                                 if position.health_factor < U256::from(LIQUIDATION_THRESHOLD) && position.total_debt_base > U256::ZERO {
+                                    latency::mark(user.address, LatencyStage::HealthConfirmed);
                                     // Send to priority channel if available (for immediate processing)
                                     if let Some(priority_tx) = &priority_liquidation_tx {
                                         info!("⚡ User {:?} is LIQUIDATABLE (HF < 1.0) - sending priority liquidation (full rescan)", user.address);
-                                        match priority_tx.send(user.address) {
+                                        match priority_tx.try_send(user.address) {
                                             Ok(()) => {
-                                                info!("✅ Priority liquidation sent successfully for user {:?} (full rescan)", user.address);
+                                                crate::per_item_log!(config.verbose_per_item_logging, "✅ Priority liquidation sent successfully for user {:?} (full rescan)", user.address);
                                             }
                                             Err(e) => {
                                                 warn!("❌ Failed to send priority liquidation for user {:?}: {}", user.address, e);
@@ -806,7 +1056,7 @@ where
                                                 if let Err(e) = event_tx.send(BotEvent::LiquidationOpportunity(user.address)) {
                                                     error!("❌ CRITICAL: Failed to send liquidation opportunity fallback: {}", e);
                                                 } else {
-                                                    info!("✅ Fallback liquidation opportunity sent for user {:?} (full rescan)", user.address);
+                                                    crate::per_item_log!(config.verbose_per_item_logging, "✅ Fallback liquidation opportunity sent for user {:?} (full rescan)", user.address);
                                                 }
                                             }
                                         }
@@ -814,7 +1064,7 @@ where
                                         info!("🎯 User {:?} is LIQUIDATABLE (HF < 1.0) - sending liquidation opportunity via regular channel (full rescan)", user.address);
                                         match event_tx.send(BotEvent::LiquidationOpportunity(user.address)) {
                                             Ok(()) => {
-                                                info!("✅ Liquidation opportunity sent successfully for user {:?} (full rescan)", user.address);
+                                                crate::per_item_log!(config.verbose_per_item_logging, "✅ Liquidation opportunity sent successfully for user {:?} (full rescan)", user.address);
                                             }
                                             Err(e) => {
                                                 error!("❌ CRITICAL: Failed to send liquidation opportunity: {}", e);
@@ -831,6 +1081,7 @@ where
                         }
                         Err(e) => {
                             error!("Failed to check user health during full rescan for {:?}: {}", user.address, e);
+                            metrics::record_rpc_error("scanner");
                             // Continue with next user rather than failing completely
                         }
                     }
@@ -854,8 +1105,16 @@ where
                 {
                     error!("Failed to log full rescan completion: {}", e);
                 }
+
+                value_at_risk.update(
+                    &user_positions,
+                    config.value_at_risk_bonus_bps,
+                    config.value_at_risk_alert_threshold,
+                );
             }
             _ = archival_interval.tick() => {
+                let config = live_config.load_full();
+
                 // Separate archival process - runs independently from full rescan
                 if config.archive_zero_debt_users {
                     info!("🗄️ Starting user archival process...");
@@ -954,6 +1213,9 @@ where
 pub async fn start_status_reporter(
     db_pool: DatabasePool,
     user_positions: Arc<DashMap<Address, UserPosition>>,
+    value_at_risk: Arc<ValueAtRiskGauge>,
+    price_triggers: Arc<PriceTriggerIndex>,
+    wallets: Arc<WalletPool>,
 ) -> Result<()> {
     info!("Starting status reporter...");
 
@@ -971,6 +1233,7 @@ pub async fn start_status_reporter(
             .iter()
             .filter(|entry| entry.value().health_factor < U256::from(LIQUIDATION_THRESHOLD))
             .count();
+        let total_value_at_risk = format_base_currency(value_at_risk.get());
 
         // Get zero debt user count from database
         let zero_debt_count = match crate::database::get_zero_debt_user_count(&db_pool).await {
@@ -983,8 +1246,8 @@ pub async fn start_status_reporter(
 
         if zero_debt_count >= 0 {
             info!(
-                "📊 Status Report: {} positions tracked, {} at risk, {} liquidatable, {} zero debt",
-                position_count, at_risk_count, liquidatable_count, zero_debt_count
+                "📊 Status Report: {} positions tracked, {} at risk, {} liquidatable, {} zero debt, {} value at risk",
+                position_count, at_risk_count, liquidatable_count, zero_debt_count, total_value_at_risk
             );
 
             if let Err(e) = database::log_monitoring_event(
@@ -992,8 +1255,8 @@ pub async fn start_status_reporter(
                 "status_report",
                 None,
                 Some(&format!(
-                    "positions:{}, at_risk:{}, liquidatable:{}, zero_debt:{}",
-                    position_count, at_risk_count, liquidatable_count, zero_debt_count
+                    "positions:{}, at_risk:{}, liquidatable:{}, zero_debt:{}, value_at_risk:{}",
+                    position_count, at_risk_count, liquidatable_count, zero_debt_count, total_value_at_risk
                 )),
             )
             .await
@@ -1002,8 +1265,8 @@ pub async fn start_status_reporter(
             }
         } else {
             info!(
-                "📊 Status Report: {} positions tracked, {} at risk, {} liquidatable",
-                position_count, at_risk_count, liquidatable_count
+                "📊 Status Report: {} positions tracked, {} at risk, {} liquidatable, {} value at risk",
+                position_count, at_risk_count, liquidatable_count, total_value_at_risk
             );
 
             if let Err(e) = database::log_monitoring_event(
@@ -1011,8 +1274,8 @@ pub async fn start_status_reporter(
                 "status_report",
                 None,
                 Some(&format!(
-                    "positions:{}, at_risk:{}, liquidatable:{}",
-                    position_count, at_risk_count, liquidatable_count
+                    "positions:{}, at_risk:{}, liquidatable:{}, value_at_risk:{}",
+                    position_count, at_risk_count, liquidatable_count, total_value_at_risk
                 )),
             )
             .await
@@ -1020,5 +1283,266 @@ pub async fn start_status_reporter(
                 error!("Failed to log status report: {}", e);
             }
         }
+
+        // Borrowers needing the smallest price move to become liquidatable
+        for (user, trigger) in price_triggers.ranked(5) {
+            let direction = match trigger.side {
+                TriggerSide::Collateral => "drop",
+                TriggerSide::Debt => "rise",
+            };
+            info!(
+                "📉 {:?} liquidatable after a {:.2}% {} of {:?} (trigger price {})",
+                user,
+                trigger.move_bps as f64 / 100.0,
+                direction,
+                trigger.asset,
+                trigger.trigger_price
+            );
+        }
+
+        // PnL summary grouped by strategy label for A/B comparisons
+        match crate::database::get_pnl_by_strategy(&db_pool).await {
+            Ok(summary) => {
+                for entry in summary {
+                    info!(
+                        "💰 PnL [{}]: {} liquidations, {} wei total profit",
+                        entry.strategy_label, entry.liquidation_count, entry.total_profit
+                    );
+                }
+            }
+            Err(e) => error!("Failed to get PnL summary by strategy: {}", e),
+        }
+
+        // Executor wallet health, with the P&L each wallet's liquidations have made
+        let wallet_pnl: HashMap<Address, I256> = match database::get_pnl_by_wallet(&db_pool).await {
+            Ok(totals) => totals.into_iter().collect(),
+            Err(e) => {
+                error!("Failed to get PnL by wallet: {}", e);
+                HashMap::new()
+            }
+        };
+        for wallet in wallets.status() {
+            info!(
+                "👛 Wallet {}: balance {} wei{}, {} in flight, {} pending tx(s), {} liquidations, PnL {}",
+                wallet.address,
+                wallet
+                    .balance_wei
+                    .map_or("unknown".to_string(), |balance| balance.to_string()),
+                if wallet.funded { "" } else { " (below gas reserve)" },
+                wallet.in_flight,
+                wallet.pending_txs,
+                wallet.liquidations,
+                crate::accounting::format_pnl(
+                    wallet_pnl.get(&wallet.address).copied().unwrap_or(I256::ZERO)
+                )
+            );
+        }
+
+        // Where the time between a log and a mined liquidation goes, over the last day
+        latency::global().prune();
+        let since_ms = chrono::Utc::now().timestamp_millis() - LATENCY_REPORT_WINDOW_MS;
+        match database::get_latency_traces(&db_pool, since_ms).await {
+            Ok(traces) => {
+                for segment in latency::segment_latencies(&traces) {
+                    info!(
+                        "⏱️ Latency [{}]: p50 {} ms, p95 {} ms over {} opportunities (24h)",
+                        segment.segment, segment.p50_ms, segment.p95_ms, segment.samples
+                    );
+                }
+            }
+            Err(e) => error!("Failed to get liquidation latencies: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Encode six uint256 words the way getUserAccountData returns them
+    fn encode_account_data(words: [U256; 6]) -> Vec<u8> {
+        words
+            .iter()
+            .flat_map(|word| word.to_be_bytes::<32>())
+            .collect()
+    }
+
+    #[test]
+    fn test_adaptive_scan_interval_bounds() {
+        // Unknown or zero volatility keeps the base interval
+        assert_eq!(adaptive_scan_interval_secs(None, 5.0, 300, 30, 300), 300);
+        assert_eq!(adaptive_scan_interval_secs(Some(0.0), 5.0, 300, 30, 300), 300);
+        // Halfway to the threshold is halfway between base and minimum
+        assert_eq!(adaptive_scan_interval_secs(Some(2.5), 5.0, 300, 30, 300), 165);
+        // At or beyond the threshold the minimum applies
+        assert_eq!(adaptive_scan_interval_secs(Some(5.0), 5.0, 300, 30, 300), 30);
+        assert_eq!(adaptive_scan_interval_secs(Some(50.0), 5.0, 300, 30, 300), 30);
+        // A base above the configured maximum is capped
+        assert_eq!(adaptive_scan_interval_secs(None, 5.0, 600, 30, 300), 300);
+    }
+
+    #[test]
+    fn test_explicit_archival_interval_overrides_derived_default() {
+        // 24h cooldown derives a 6h interval
+        assert_eq!(archival_interval_secs(None, 24), 6 * 3600);
+        assert_eq!(archival_interval_secs(Some(900), 24), 900);
+    }
+
+    #[test]
+    fn test_derived_archival_interval_is_clamped() {
+        assert_eq!(derived_archival_interval_secs(0), 3600);
+        assert_eq!(derived_archival_interval_secs(1), 3600);
+        assert_eq!(derived_archival_interval_secs(24 * 365), 7 * 24 * 3600);
+        assert_eq!(derived_archival_interval_secs(u64::MAX), 7 * 24 * 3600);
+    }
+
+    #[test]
+    fn test_all_zero_response_is_rejected() {
+        let user = Address::from([1u8; 20]);
+
+        assert!(parse_user_account_data(&[0u8; 192], user).is_err());
+    }
+
+    #[test]
+    fn test_short_response_is_rejected() {
+        let user = Address::from([1u8; 20]);
+
+        assert!(parse_user_account_data(&[1u8; 64], user).is_err());
+    }
+
+    #[test]
+    fn test_exited_user_is_parsed() {
+        let user = Address::from([1u8; 20]);
+        // Aave reports zero-debt users with a max health factor, never zero
+        let data = encode_account_data([
+            U256::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            U256::ZERO,
+            U256::MAX,
+        ]);
+
+        let position = parse_user_account_data(&data, user).unwrap();
+        assert_eq!(position.health_factor, U256::MAX);
+        assert_eq!(position.total_debt_base, U256::ZERO);
+        assert!(!position.is_at_risk);
+    }
+
+    #[test]
+    fn test_at_risk_user_is_parsed() {
+        let user = Address::from([2u8; 20]);
+        let data = encode_account_data([
+            U256::from(2_000u64),
+            U256::from(1_500u64),
+            U256::ZERO,
+            U256::from(8_250u64),
+            U256::from(8_000u64),
+            U256::from(1_050_000_000_000_000_000u64),
+        ]);
+
+        let position = parse_user_account_data(&data, user).unwrap();
+        assert_eq!(position.total_collateral_base, U256::from(2_000u64));
+        assert_eq!(position.total_debt_base, U256::from(1_500u64));
+        assert!(position.is_at_risk);
+    }
+
+    fn at_risk_position(health_factor: u64) -> UserPosition {
+        UserPosition {
+            address: Address::from([3u8; 20]),
+            total_collateral_base: U256::from(2_000u64),
+            total_debt_base: U256::from(1_500u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_250u64),
+            ltv: U256::from(8_000u64),
+            health_factor: U256::from(health_factor),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: true,
+        }
+    }
+
+    #[test]
+    fn test_health_factor_change_bps() {
+        let hf = U256::from(1_000_000_000_000_000_000u64);
+
+        assert_eq!(health_factor_change_bps(hf, hf), U256::ZERO);
+        assert_eq!(
+            health_factor_change_bps(hf, U256::from(1_050_000_000_000_000_000u64)),
+            U256::from(500u64)
+        );
+        // Debt-free users report U256::MAX; taking on debt is a full move, not an overflow
+        assert_eq!(health_factor_change_bps(U256::MAX, hf), U256::from(9_999u64));
+        assert_eq!(health_factor_change_bps(hf, U256::MAX), U256::MAX);
+    }
+
+    #[test]
+    fn test_sub_threshold_hf_change_neither_persists_nor_logs() {
+        let old = at_risk_position(1_050_000_000_000_000_000);
+        // 0.5% move against a 1% (100 bps) threshold
+        let new = at_risk_position(1_044_750_000_000_000_000);
+
+        assert!(!should_persist_position(Some(&old), &new, 100));
+
+        let output = crate::logging::capture::capture_info_logs(|| {
+            assert!(!log_at_risk_hf_change(
+                new.address,
+                old.health_factor,
+                new.health_factor,
+                100
+            ));
+        });
+        assert!(output.is_empty(), "unexpected log output: {}", output);
+    }
+
+    #[test]
+    fn test_significant_hf_change_persists_and_logs() {
+        let old = at_risk_position(1_050_000_000_000_000_000);
+        let new = at_risk_position(1_020_000_000_000_000_000);
+
+        assert!(should_persist_position(Some(&old), &new, 100));
+        assert!(should_persist_position(None, &new, 100));
+
+        let output = crate::logging::capture::capture_info_logs(|| {
+            assert!(log_at_risk_hf_change(
+                new.address,
+                old.health_factor,
+                new.health_factor,
+                100
+            ));
+        });
+        assert!(output.contains("WORSENED"));
+    }
+
+    #[test]
+    fn test_status_flip_persists_regardless_of_threshold() {
+        let old = at_risk_position(1_000_000_000_000_000_001);
+        let new = at_risk_position(999_999_999_999_999_999);
+
+        // A tiny move across 1.0 makes the position liquidatable
+        assert!(!is_significant_hf_change(old.health_factor, new.health_factor, 100));
+        assert!(should_persist_position(Some(&old), &new, 100));
+    }
+
+    #[test]
+    fn test_small_moves_persist_once_they_add_up() {
+        let persisted = PersistedPositions::default();
+        let first = at_risk_position(1_050_000_000_000_000_000);
+        assert!(persisted.should_persist(&first, 100));
+        persisted.record(&first);
+
+        // Three 0.5% drops: each is sub-threshold from the check before it, but the third
+        // is 1.5% below the stored position
+        let checks = [
+            at_risk_position(1_044_750_000_000_000_000),
+            at_risk_position(1_039_500_000_000_000_000),
+            at_risk_position(1_034_250_000_000_000_000),
+        ];
+        assert!(!persisted.should_persist(&checks[0], 100));
+        assert!(!persisted.should_persist(&checks[1], 100));
+        assert!(persisted.should_persist(&checks[2], 100));
+
+        persisted.record(&checks[2]);
+        let stored = persisted.get(first.address).unwrap();
+        assert_eq!(stored.health_factor, checks[2].health_factor);
     }
 }
@@ -0,0 +1,139 @@
+use alloy_primitives::{Address, U256};
+use dashmap::DashMap;
+use parking_lot::RwLock;
+use tracing::{info, warn};
+
+use crate::liquidation::profitability::calculate_max_debt_to_cover;
+use crate::models::UserPosition;
+use crate::monitoring::scanner::is_liquidatable;
+
+/// Aave base currency (USD) uses 8 decimals
+const BASE_CURRENCY_DECIMALS: u32 = 8;
+
+/// Value extractable from liquidating a position: the maximum debt that can be covered
+/// times the expected liquidation bonus, in the protocol's base currency
+pub fn position_value_at_risk(position: &UserPosition, expected_bonus_bps: u64) -> U256 {
+    calculate_max_debt_to_cover(position.total_debt_base)
+        .saturating_mul(U256::from(expected_bonus_bps))
+        / U256::from(10000)
+}
+
+/// Sum the value at risk across all currently liquidatable positions
+pub fn total_value_at_risk<'a>(
+    positions: impl IntoIterator<Item = &'a UserPosition>,
+    expected_bonus_bps: u64,
+) -> U256 {
+    positions
+        .into_iter()
+        .filter(|position| is_liquidatable(position))
+        .fold(U256::ZERO, |total, position| {
+            total.saturating_add(position_value_at_risk(position, expected_bonus_bps))
+        })
+}
+
+/// Format a base currency amount as USD for display
+pub fn format_base_currency(amount: U256) -> String {
+    let unit = U256::from(10u64.pow(BASE_CURRENCY_DECIMALS));
+    let cents = (amount % unit) / U256::from(10u64.pow(BASE_CURRENCY_DECIMALS - 2));
+    format!("${}.{:02}", amount / unit, cents.to::<u64>())
+}
+
+/// Latest total value at risk, refreshed after every scan
+#[derive(Debug, Default)]
+pub struct ValueAtRiskGauge {
+    total: RwLock<U256>,
+}
+
+impl ValueAtRiskGauge {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(&self) -> U256 {
+        *self.total.read()
+    }
+
+    /// Recompute the total from the tracked positions, alerting if it exceeds `alert_threshold`
+    pub fn update(
+        &self,
+        user_positions: &DashMap<Address, UserPosition>,
+        expected_bonus_bps: u64,
+        alert_threshold: Option<U256>,
+    ) -> U256 {
+        let total = user_positions
+            .iter()
+            .filter(|entry| is_liquidatable(entry.value()))
+            .fold(U256::ZERO, |total, entry| {
+                total.saturating_add(position_value_at_risk(entry.value(), expected_bonus_bps))
+            });
+        *self.total.write() = total;
+
+        match alert_threshold {
+            Some(threshold) if total > threshold => warn!(
+                "🚨 ALERT: Total value at risk {} exceeds threshold {}",
+                format_base_currency(total),
+                format_base_currency(threshold)
+            ),
+            _ => info!("💎 Total value at risk: {}", format_base_currency(total)),
+        }
+
+        total
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    /// Position with the given debt (whole USD) and health factor (1e18 = 1.0)
+    fn create_position(byte: u8, debt_usd: u64, health_factor: u128) -> UserPosition {
+        UserPosition {
+            address: Address::from([byte; 20]),
+            total_collateral_base: U256::ZERO,
+            total_debt_base: U256::from(debt_usd) * U256::from(100_000_000u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::ZERO,
+            ltv: U256::ZERO,
+            health_factor: U256::from(health_factor),
+            last_updated: Utc::now(),
+            is_at_risk: true,
+        }
+    }
+
+    #[test]
+    fn test_total_value_at_risk_sums_liquidatable_positions() {
+        let positions = vec![
+            // Liquidatable: 50% of $10,000 debt * 5% bonus = $250
+            create_position(1, 10_000, 950_000_000_000_000_000),
+            // Liquidatable: 50% of $2,000 debt * 5% bonus = $50
+            create_position(2, 2_000, 990_000_000_000_000_000),
+            // Healthy: excluded
+            create_position(3, 50_000, 1_200_000_000_000_000_000),
+            // Liquidatable health factor but no debt: excluded
+            create_position(4, 0, 500_000_000_000_000_000),
+        ];
+
+        let total = total_value_at_risk(&positions, 500);
+        assert_eq!(total, U256::from(300u64) * U256::from(100_000_000u64));
+        assert_eq!(format_base_currency(total), "$300.00");
+    }
+
+    #[test]
+    fn test_gauge_tracks_latest_scan() {
+        let gauge = ValueAtRiskGauge::new();
+        let user_positions = DashMap::new();
+        assert_eq!(gauge.get(), U256::ZERO);
+
+        let position = create_position(1, 10_000, 950_000_000_000_000_000);
+        user_positions.insert(position.address, position);
+        let total = gauge.update(&user_positions, 500, None);
+        assert_eq!(total, U256::from(250u64) * U256::from(100_000_000u64));
+        assert_eq!(gauge.get(), total);
+
+        // Position recovers, so nothing is at risk on the next scan
+        user_positions.clear();
+        assert_eq!(gauge.update(&user_positions, 500, None), U256::ZERO);
+        assert_eq!(gauge.get(), U256::ZERO);
+    }
+}
@@ -0,0 +1,199 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use parking_lot::Mutex;
+use std::collections::{BTreeMap, HashSet};
+use tracing::{info, warn};
+
+use crate::liquidation::profit_recheck::fetch_token_balance;
+use crate::liquidation::WalletStatus;
+use crate::metrics;
+use crate::models::LiquidationAssetConfig;
+use crate::monitoring::alerts::AlertEvent;
+use crate::monitoring::metrics::BotMetrics;
+
+/// Executor wallet token balances measured against their configured minimums, so each
+/// wallet/token pair alerts once when it drops below its minimum and once when it recovers
+pub struct TokenBalanceMonitor {
+    min_balances: BTreeMap<String, U256>,    // By upper-case symbol
+    low: Mutex<HashSet<(Address, Address)>>, // (wallet, token) pairs currently below minimum
+}
+
+impl TokenBalanceMonitor {
+    pub fn new(min_balances: BTreeMap<String, U256>) -> Self {
+        Self {
+            min_balances,
+            low: Mutex::new(HashSet::new()),
+        }
+    }
+
+    /// Record a freshly read balance, returning the alert to raise if it crossed its minimum
+    pub fn record(
+        &self,
+        wallet: Address,
+        asset: &LiquidationAssetConfig,
+        balance: U256,
+    ) -> Option<AlertEvent> {
+        let min_balance = *self.min_balances.get(&asset.symbol.to_uppercase())?;
+        let mut low = self.low.lock();
+        if balance < min_balance {
+            if !low.insert((wallet, asset.address)) {
+                return None;
+            }
+            warn!(
+                "🪙 Wallet {} {} balance {} below minimum {} - top up",
+                wallet, asset.symbol, balance, min_balance
+            );
+            Some(AlertEvent::TokenBalanceLow {
+                wallet,
+                symbol: asset.symbol.clone(),
+                balance,
+                min_balance,
+            })
+        } else {
+            if !low.remove(&(wallet, asset.address)) {
+                return None;
+            }
+            info!(
+                "✅ Wallet {} {} balance {} back above minimum {}",
+                wallet, asset.symbol, balance, min_balance
+            );
+            Some(AlertEvent::TokenBalanceRecovered {
+                wallet,
+                symbol: asset.symbol.clone(),
+            })
+        }
+    }
+}
+
+/// Balance of `amount` base units in whole tokens, for gauges
+fn whole_tokens(amount: U256, decimals: u8) -> f64 {
+    amount.to_string().parse::<f64>().unwrap_or(0.0) / 10f64.powi(decimals as i32)
+}
+
+/// Publish each executor wallet's native balance and how many can still pay for gas
+pub fn record_native_balance_metrics(bot_metrics: &BotMetrics, wallets: &[WalletStatus]) {
+    for wallet in wallets {
+        if let Some(balance) = wallet.balance_wei {
+            bot_metrics.set_labelled_gauge(
+                metrics::WALLET_NATIVE_BALANCE_WEI,
+                &[("wallet", &wallet.address.to_string())],
+                balance.to_string().parse::<f64>().unwrap_or(0.0),
+            );
+        }
+    }
+    let funded = wallets.iter().filter(|wallet| wallet.funded).count();
+    bot_metrics.set_gauge(metrics::WALLETS_FUNDED, funded as f64);
+}
+
+/// Read every wallet's balance of each of `assets`, publish them as gauges and return the
+/// alerts for balances that crossed their minimum. Failed reads are skipped until next time.
+pub async fn check_token_balances<P>(
+    provider: &P,
+    bot_metrics: &BotMetrics,
+    monitor: &TokenBalanceMonitor,
+    wallets: &[Address],
+    assets: &[LiquidationAssetConfig],
+) -> Vec<AlertEvent>
+where
+    P: Provider,
+{
+    let mut alerts = Vec::new();
+    for wallet in wallets {
+        for asset in assets {
+            let balance = match fetch_token_balance(provider, asset.address, *wallet).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!(
+                        "Failed to read {} balance of wallet {}: {}",
+                        asset.symbol, wallet, e
+                    );
+                    continue;
+                }
+            };
+            bot_metrics.set_labelled_gauge(
+                metrics::WALLET_TOKEN_BALANCE,
+                &[("wallet", &wallet.to_string()), ("token", &asset.symbol)],
+                whole_tokens(balance, asset.decimals),
+            );
+            alerts.extend(monitor.record(*wallet, asset, balance));
+        }
+    }
+    alerts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usdc() -> LiquidationAssetConfig {
+        LiquidationAssetConfig {
+            address: Address::repeat_byte(0x03),
+            symbol: "USDC".to_string(),
+            decimals: 6,
+            asset_id: 3,
+            liquidation_bonus: 450,
+            is_collateral: true,
+            is_borrowable: true,
+        }
+    }
+
+    #[test]
+    fn test_token_balance_alerts_once_per_crossing() {
+        // 100 USDC minimum, configured by upper-case symbol
+        let monitor = TokenBalanceMonitor::new(BTreeMap::from([(
+            "USDC".to_string(),
+            U256::from(100_000_000u64),
+        )]));
+        let wallet = Address::repeat_byte(0x0e);
+
+        assert!(monitor
+            .record(wallet, &usdc(), U256::from(150_000_000u64))
+            .is_none());
+        assert!(matches!(
+            monitor.record(wallet, &usdc(), U256::from(99_000_000u64)),
+            Some(AlertEvent::TokenBalanceLow { .. })
+        ));
+        // Still low: no repeat alert
+        assert!(monitor
+            .record(wallet, &usdc(), U256::from(50_000_000u64))
+            .is_none());
+        assert!(matches!(
+            monitor.record(wallet, &usdc(), U256::from(100_000_000u64)),
+            Some(AlertEvent::TokenBalanceRecovered { .. })
+        ));
+
+        // Tokens without a minimum are never alerted on
+        let weth = LiquidationAssetConfig {
+            address: Address::repeat_byte(0x02),
+            symbol: "WETH".to_string(),
+            decimals: 18,
+            ..usdc()
+        };
+        assert!(monitor.record(wallet, &weth, U256::ZERO).is_none());
+    }
+
+    #[test]
+    fn test_native_balance_metrics() {
+        let bot_metrics = BotMetrics::new();
+        let wallet = |byte: u8, balance: u64, funded: bool| WalletStatus {
+            address: Address::repeat_byte(byte),
+            balance_wei: Some(U256::from(balance)),
+            funded,
+            in_flight: 0,
+            pending_txs: 0,
+            liquidations: 0,
+        };
+        record_native_balance_metrics(
+            &bot_metrics,
+            &[wallet(0x0e, 5_000, true), wallet(0x0f, 10, false)],
+        );
+
+        assert_eq!(bot_metrics.value(metrics::WALLETS_FUNDED, &[]), Some(1.0));
+        let label = Address::repeat_byte(0x0f).to_string();
+        assert_eq!(
+            bot_metrics.value(metrics::WALLET_NATIVE_BALANCE_WEI, &[("wallet", &label)]),
+            Some(10.0)
+        );
+        assert_eq!(whole_tokens(U256::from(2_500_000u64), 6), 2.5);
+    }
+}
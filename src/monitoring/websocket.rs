@@ -1,12 +1,13 @@
-use crate::events::BotEvent;
+use crate::database::{self, DatabasePool};
+use crate::events::{BotEvent, EventSender};
 use crate::monitoring::scanner;
-use alloy_primitives::{Address, U256};
+use alloy_primitives::{Address, B256, U256};
 use alloy_provider::{Provider, ProviderBuilder, WsConnect};
 use alloy_rpc_types::{BlockNumberOrTag, Filter, Log};
 use alloy_sol_types::SolEvent;
 use eyre::Result;
 use futures::StreamExt;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
@@ -14,10 +15,20 @@ use tokio::sync::mpsc;
 use tokio::time::{interval, Duration};
 use tracing::{debug, error, info, warn};
 
-use crate::models::{Borrow, Repay, Supply, Withdraw};
+use crate::models::{Borrow, Repay, ReserveDataUpdated, Supply, Withdraw};
+use crate::monitoring::latency::{self, LatencyStage};
+use crate::monitoring::block_scanner::NewHead;
+use crate::monitoring::metrics;
+use crate::monitoring::provider_set::{ProviderSet, ReconnectBackoff};
+use crate::monitoring::reorg::{self, ReorgTracker};
+use crate::replay::{EventRecorder, RecordedEntry};
+use crate::shutdown::ShutdownCoordinator;
 
-// Static variable to track last processed block for polling mode
-static LAST_PROCESSED_BLOCK: AtomicU64 = AtomicU64::new(0);
+/// `sync_state` key for the Aave pool log stream
+pub const POOL_EVENTS_SUBSCRIPTION: &str = "aave_pool_events";
+
+/// Most blocks fetched by one getLogs call while catching up
+const MAX_BLOCKS_PER_POLL: u64 = 2_000;
 
 // Dedupe mechanism for fast path liquidations
 type DedupeMap = Arc<tokio::sync::RwLock<HashMap<Address, u64>>>;
@@ -39,97 +50,373 @@ pub async fn try_connect_websocket(ws_url: &str) -> Result<Arc<dyn Provider>> {
 }
 
 pub async fn start_event_monitoring<P>(
-    provider: Arc<P>,
-    ws_provider: Arc<dyn Provider>,
-    ws_url: &str,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
-    priority_liquidation_tx: Option<mpsc::UnboundedSender<Address>>,
+    providers: ProviderSet<P>,
+    pool_address: Address,
+    db_pool: DatabasePool,
+    event_tx: EventSender,
+    priority_liquidation_tx: Option<mpsc::Sender<Address>>,
+    recorder: Option<Arc<EventRecorder>>,
+    shutdown: ShutdownCoordinator,
 ) -> Result<()>
 where
     P: Provider + 'static,
 {
-    // Check if we're using WebSocket or HTTP fallback
-    let using_websocket = ws_url.starts_with("wss://") || ws_url.starts_with("ws://");
-
-    if !using_websocket {
-        info!("Event monitoring initialized (using HTTP polling mode)");
-        warn!("WebSocket event subscriptions skipped - URL does not use WebSocket protocol");
-        warn!("For real-time monitoring, configure WS_URL with a proper WebSocket RPC endpoint");
-
-        // Instead of exiting early, start polling-based event monitoring
-        info!("🔄 Starting getLogs-based polling for continuous event discovery...");
-        return start_polling_event_monitoring(provider, event_tx, priority_liquidation_tx).await;
-    }
+    let provider = providers.call().clone();
+
+    // Check if we have a real-time provider or are in HTTP fallback mode
+    let ws_provider = match providers.real_time() {
+        Some(ws_provider) => ws_provider,
+        None => {
+            info!("Event monitoring initialized (using HTTP polling mode)");
+            warn!("WebSocket event subscriptions skipped - no real-time provider available");
+            warn!("For real-time monitoring, configure WS_URL with a proper WebSocket RPC endpoint");
+
+            // Instead of exiting early, start polling-based event monitoring
+            info!("🔄 Starting getLogs-based polling for continuous event discovery...");
+            return start_polling_event_monitoring(
+                provider,
+                pool_address,
+                db_pool,
+                event_tx,
+                priority_liquidation_tx,
+                recorder,
+                shutdown,
+            )
+            .await;
+        }
+    };
 
     info!("🚀 Starting real-time WebSocket event monitoring...");
 
-    let pool_address: Address = "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5".parse()?;
-
     // Create a general filter for all events from the Aave pool
     let pool_filter = Filter::new().address(pool_address);
 
+    let task = shutdown.track_task();
     tokio::spawn(async move {
-        info!("Subscribing to Aave Pool events...");
-        let sub = match ws_provider.subscribe_logs(&pool_filter).await {
-            Ok(sub) => {
-                info!("✅ Successfully subscribed to Aave Pool events!");
-                sub
+        let _task = task;
+        let reorg_tracker = ReorgTracker::new();
+        let mut ws_provider = ws_provider;
+        let mut backoff = ReconnectBackoff::default();
+
+        // A dropped stream is resubscribed on a fresh connection; the backfill that follows
+        // every subscription fills the gap from the last checkpoint
+        loop {
+            match stream_pool_events(
+                &ws_provider,
+                &provider,
+                &db_pool,
+                &pool_filter,
+                pool_address,
+                &reorg_tracker,
+                &event_tx,
+                &priority_liquidation_tx,
+                &recorder,
+                &shutdown,
+            )
+            .await
+            {
+                Ok(()) if shutdown.is_shutting_down() => break,
+                Ok(()) => {
+                    warn!("🔌 Aave Pool event stream ended - reconnecting");
+                    backoff.reset();
+                }
+                Err(e) => error!("❌ Aave Pool event subscription failed: {}", e),
             }
-            Err(e) => {
-                error!("❌ Failed to subscribe to logs: {}", e);
-                return;
+            let reconnect = providers.wait_for_real_time_reconnect(&ws_provider, &mut backoff);
+            ws_provider = tokio::select! {
+                reconnected = reconnect => reconnected,
+                _ = shutdown.cancelled() => break,
+            };
+        }
+    });
+
+    info!("✅ WebSocket event subscriptions established");
+    Ok(())
+}
+
+/// Subscribe to pool logs and new heads, backfill from the checkpoint and handle streamed logs
+/// until either stream ends or shutdown begins (which unsubscribes). Returns an error if the
+/// subscriptions or backfill couldn't be set up.
+#[allow(clippy::too_many_arguments)]
+async fn stream_pool_events<P>(
+    ws_provider: &Arc<dyn Provider>,
+    provider: &Arc<P>,
+    db_pool: &DatabasePool,
+    pool_filter: &Filter,
+    pool_address: Address,
+    reorg_tracker: &ReorgTracker,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    recorder: &Option<Arc<EventRecorder>>,
+    shutdown: &ShutdownCoordinator,
+) -> Result<()>
+where
+    P: Provider,
+{
+    info!("Subscribing to Aave Pool events...");
+    let sub = ws_provider.subscribe_logs(pool_filter).await?;
+    let subscription_id = *sub.local_id();
+    info!("✅ Successfully subscribed to Aave Pool events!");
+
+    // Logs only expose reorgs of blocks that carried pool logs, so every head's parent hash is
+    // checked too; an orphaned block holding nothing but our own liquidation is rolled back
+    let root = ws_provider.root();
+    let heads_id: B256 = root
+        .client()
+        .request("eth_subscribe", ("newHeads",))
+        .await?;
+    let heads_sub = root.get_subscription::<NewHead>(heads_id).await?;
+    let heads_subscription_id = *heads_sub.local_id();
+
+    // Subscribe first, then backfill up to the current head, so nothing falls in between;
+    // streamed logs at or below the backfilled block were already handled
+    let mut backfilled_to = backfill_from_checkpoint(
+        provider,
+        db_pool,
+        pool_address,
+        reorg_tracker,
+        event_tx,
+        priority_liquidation_tx,
+        recorder,
+    )
+    .await
+    .map_err(|e| eyre::eyre!("Failed to backfill missed pool events: {}", e))?;
+
+    let mut stream = sub.into_stream();
+    let mut heads = heads_sub.into_stream();
+    info!("🎧 Listening for real-time Aave events...");
+
+    // A block is complete once a log from a later block arrives
+    let mut open_block = backfilled_to;
+    loop {
+        let log = tokio::select! {
+            log = stream.next() => match log {
+                Some(log) => log,
+                None => break,
+            },
+            head = heads.next() => {
+                let Some(head) = head else { break };
+                let block = head.number.to::<u64>();
+                let conflict = reorg_tracker.record_head(block, head.hash, head.parent_hash);
+                if let Some(conflict) = conflict {
+                    let rewound =
+                        rewind_after_reorg(provider, db_pool, reorg_tracker, conflict, event_tx);
+                    if let Some(resume_after) = rewound.await {
+                        backfilled_to = backfilled_to.min(resume_after);
+                        open_block = open_block.min(resume_after);
+                    }
+                    reorg_tracker.record(block, head.hash, []);
+                }
+                continue;
             }
+            _ = shutdown.cancelled() => {
+                // The open block may still have logs coming, so the checkpoint stays before it
+                match ws_provider.unsubscribe(subscription_id) {
+                    Ok(()) => info!("🔌 Unsubscribed from Aave Pool events"),
+                    Err(e) => warn!("Failed to unsubscribe from Aave Pool events: {}", e),
+                }
+                if let Err(e) = ws_provider.unsubscribe(heads_subscription_id) {
+                    warn!("Failed to unsubscribe from new heads: {}", e);
+                }
+                break;
+            }
+        };
+        let block = log.block_number.unwrap_or(open_block);
+
+        // The node re-sends orphaned logs flagged as removed; a new hash at a height
+        // already processed means the same thing. Later removed logs of a block that was
+        // already rolled back need no further work.
+        let reorged = if log.removed {
+            log.block_hash.is_some() && reorg_tracker.hash_at(block) == log.block_hash
+        } else {
+            log.block_hash.map_or(false, |hash| {
+                !reorg_tracker.record(block, hash, extract_log_users(&log))
+            })
         };
+        if reorged {
+            if let Some(resume_after) =
+                rewind_after_reorg(provider, db_pool, reorg_tracker, block, event_tx).await
+            {
+                backfilled_to = backfilled_to.min(resume_after);
+                open_block = open_block.min(resume_after);
+            }
+            if let (false, Some(hash)) = (log.removed, log.block_hash) {
+                reorg_tracker.record(block, hash, extract_log_users(&log));
+            }
+        }
+        if log.removed {
+            continue;
+        }
 
-        let mut stream = sub.into_stream();
-        info!("🎧 Listening for real-time Aave events...");
+        if block <= backfilled_to {
+            continue;
+        }
+        if block > open_block && open_block > backfilled_to {
+            save_checkpoint(db_pool, open_block).await;
+        }
+        open_block = open_block.max(block);
 
-        while let Some(log) = stream.next().await {
-            if let Err(e) = handle_log_event(log, &event_tx, &priority_liquidation_tx, &provider, pool_address).await {
-                error!("Error handling log event: {}", e);
-            }
+        record_log(recorder, &log);
+        if let Err(e) = handle_log_event(log, event_tx, priority_liquidation_tx, provider, pool_address).await {
+            error!("Error handling log event: {}", e);
         }
-    });
+    }
 
-    info!("✅ WebSocket event subscriptions established");
     Ok(())
 }
 
+/// Block to resume after: the stored checkpoint, or the current head on first start
+/// Reconcile a reorg detected at `conflict_block` and move the checkpoint before the fork, so
+/// replacement logs from the new chain aren't skipped. Returns the last block still on the
+/// canonical chain, or None if the reorg couldn't be reconciled.
+async fn rewind_after_reorg<P>(
+    provider: &Arc<P>,
+    db_pool: &DatabasePool,
+    reorg_tracker: &ReorgTracker,
+    conflict_block: u64,
+    event_tx: &EventSender,
+) -> Option<u64>
+where
+    P: Provider,
+{
+    let reconciled =
+        reorg::reconcile_reorg(provider.as_ref(), db_pool, reorg_tracker, conflict_block, event_tx);
+    match reconciled.await {
+        Ok(fork_block) => {
+            let resume_after = fork_block.saturating_sub(1);
+            save_checkpoint(db_pool, resume_after).await;
+            Some(resume_after)
+        }
+        Err(e) => {
+            error!("Failed to reconcile chain reorg at block {}: {}", conflict_block, e);
+            None
+        }
+    }
+}
+
+async fn resume_block<P>(provider: &Arc<P>, db_pool: &DatabasePool) -> Result<u64>
+where
+    P: Provider,
+{
+    let current_block = provider.get_block_number().await?;
+
+    match database::get_last_processed_block(db_pool, POOL_EVENTS_SUBSCRIPTION).await {
+        Ok(Some(checkpoint)) if checkpoint <= current_block => {
+            info!(
+                "⏮️ Resuming pool events after checkpoint block {} ({} blocks behind)",
+                checkpoint,
+                current_block - checkpoint
+            );
+            Ok(checkpoint)
+        }
+        Ok(Some(checkpoint)) => {
+            warn!(
+                "Checkpoint block {} is ahead of the chain head {} - starting from the head",
+                checkpoint, current_block
+            );
+            Ok(current_block)
+        }
+        Ok(None) => {
+            info!("No pool events checkpoint yet - starting from block {}", current_block);
+            Ok(current_block)
+        }
+        Err(e) => {
+            warn!("Failed to read pool events checkpoint: {}. Starting from the head.", e);
+            Ok(current_block)
+        }
+    }
+}
+
+/// Record that every pool log up to `block` has been handled
+async fn save_checkpoint(db_pool: &DatabasePool, block: u64) {
+    if let Err(e) =
+        database::save_last_processed_block(db_pool, POOL_EVENTS_SUBSCRIPTION, block).await
+    {
+        warn!("Failed to checkpoint pool events at block {}: {}", block, e);
+    }
+}
+
+/// Process every pool event between the checkpoint and the current head. Returns the last
+/// block processed.
+async fn backfill_from_checkpoint<P>(
+    provider: &Arc<P>,
+    db_pool: &DatabasePool,
+    pool_address: Address,
+    reorg_tracker: &ReorgTracker,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    recorder: &Option<Arc<EventRecorder>>,
+) -> Result<u64>
+where
+    P: Provider,
+{
+    let mut last_processed = resume_block(provider, db_pool).await?;
+    let head = provider.get_block_number().await?;
+
+    while last_processed < head {
+        let to_block = head.min(last_processed + MAX_BLOCKS_PER_POLL);
+        let found = process_block_range(
+            provider,
+            pool_address,
+            last_processed + 1,
+            to_block,
+            reorg_tracker,
+            event_tx,
+            priority_liquidation_tx,
+            recorder,
+        )
+        .await?;
+        if found > 0 {
+            info!(
+                "⏮️ Backfilled {} pool events from blocks {}-{}",
+                found,
+                last_processed + 1,
+                to_block
+            );
+        }
+        last_processed = to_block;
+        save_checkpoint(db_pool, last_processed).await;
+    }
+
+    Ok(last_processed)
+}
+
 /// Polling-based event monitoring for HTTP fallback mode
 async fn start_polling_event_monitoring<P>(
     provider: Arc<P>,
-    event_tx: mpsc::UnboundedSender<BotEvent>,
-    priority_liquidation_tx: Option<mpsc::UnboundedSender<Address>>,
+    pool_address: Address,
+    db_pool: DatabasePool,
+    event_tx: EventSender,
+    priority_liquidation_tx: Option<mpsc::Sender<Address>>,
+    recorder: Option<Arc<EventRecorder>>,
+    shutdown: ShutdownCoordinator,
 ) -> Result<()>
 where
     P: Provider + 'static,
 {
-    let pool_address: Address = "0xA238Dd80C259a72e81d7e4664a9801593F98d1c5".parse()?;
-
-    // Initialize last processed block to current block
-    let current_block = provider.get_block_number().await?;
-    LAST_PROCESSED_BLOCK.store(current_block, Ordering::Relaxed);
+    // Resume after the stored checkpoint so blocks seen before a restart aren't missed
+    let start_block = resume_block(&provider, &db_pool).await?;
+    let last_processed_block = AtomicU64::new(start_block);
 
-    info!("Starting polling from block: {}", current_block);
-
-    // Event signatures for key Aave events
-    let key_events = vec![
-        ("Borrow", Borrow::SIGNATURE_HASH),
-        ("Supply", Supply::SIGNATURE_HASH),
-        ("Repay", Repay::SIGNATURE_HASH),
-        ("Withdraw", Withdraw::SIGNATURE_HASH),
-    ];
+    info!("Starting polling after block: {}", start_block);
 
     // Create interval for polling (every 10 seconds to balance real-time vs rate limits)
     let mut poll_interval = interval(Duration::from_secs(10));
+    let reorg_tracker = ReorgTracker::new();
 
+    let task = shutdown.track_task();
     tokio::spawn(async move {
+        let _task = task;
         info!("🔄 Polling loop started for event discovery");
 
+        // A round in progress when shutdown begins is finished before the loop stops
         loop {
-            poll_interval.tick().await;
+            tokio::select! {
+                _ = poll_interval.tick() => {}
+                _ = shutdown.cancelled() => break,
+            }
 
-            if let Err(e) = poll_for_events(&provider, pool_address, &key_events, &event_tx, &priority_liquidation_tx).await {
+            if let Err(e) = poll_for_events(&provider, &db_pool, pool_address, &last_processed_block, &reorg_tracker, &event_tx, &priority_liquidation_tx, &recorder).await {
                 error!("Error during event polling: {}", e);
                 // Continue polling even if one round fails
             }
@@ -140,74 +427,123 @@ where
     Ok(())
 }
 
+/// Fetch the key pool events in `from_block..=to_block` with a single query and handle them in
+/// chain order. Either the whole range is handled or (on a query error) none of it is.
+/// Each log's block is recorded for reorg detection.
+#[allow(clippy::too_many_arguments)]
+async fn process_block_range<P>(
+    provider: &Arc<P>,
+    pool_address: Address,
+    from_block: u64,
+    to_block: u64,
+    reorg_tracker: &ReorgTracker,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    recorder: &Option<Arc<EventRecorder>>,
+) -> Result<usize>
+where
+    P: Provider,
+{
+    let filter = Filter::new()
+        .address(pool_address)
+        .event_signature(vec![
+            Borrow::SIGNATURE_HASH,
+            Supply::SIGNATURE_HASH,
+            Repay::SIGNATURE_HASH,
+            Withdraw::SIGNATURE_HASH,
+        ])
+        .from_block(BlockNumberOrTag::Number(from_block))
+        .to_block(BlockNumberOrTag::Number(to_block));
+
+    let mut logs = provider.get_logs(&filter).await.map_err(|e| {
+        metrics::record_rpc_error("event_polling");
+        eyre::eyre!("Failed to get pool events for blocks {}-{}: {}", from_block, to_block, e)
+    })?;
+    logs.sort_by_key(|log| (log.block_number, log.log_index));
+
+    let found = logs.len();
+    for log in logs {
+        if let (Some(block), Some(hash)) = (log.block_number, log.block_hash) {
+            reorg_tracker.record(block, hash, extract_log_users(&log));
+        }
+        record_log(recorder, &log);
+        if let Err(e) = handle_log_event(log, event_tx, priority_liquidation_tx, provider, pool_address).await {
+            error!("Error handling pool event: {}", e);
+        }
+    }
+
+    Ok(found)
+}
+
 /// Poll for new events since last processed block
+#[allow(clippy::too_many_arguments)]
 async fn poll_for_events<P>(
     provider: &Arc<P>,
+    db_pool: &DatabasePool,
     pool_address: Address,
-    key_events: &[(&str, alloy_primitives::FixedBytes<32>)],
-    event_tx: &mpsc::UnboundedSender<BotEvent>,
-    priority_liquidation_tx: &Option<mpsc::UnboundedSender<Address>>,
+    last_processed_block: &AtomicU64,
+    reorg_tracker: &ReorgTracker,
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    recorder: &Option<Arc<EventRecorder>>,
 ) -> Result<()>
 where
     P: Provider,
 {
     let current_block = provider.get_block_number().await?;
-    let last_processed = LAST_PROCESSED_BLOCK.load(Ordering::Relaxed);
+    let last_processed = last_processed_block.load(Ordering::Relaxed);
 
     // Skip if no new blocks
     if current_block <= last_processed {
         return Ok(());
     }
 
+    // The first new block must build on the last one we processed
+    if let Some(recorded) = reorg_tracker.hash_at(last_processed) {
+        let parent = provider
+            .get_block_by_number(BlockNumberOrTag::Number(last_processed + 1), false)
+            .await?
+            .map(|block| block.header.parent_hash);
+        if parent.is_some_and(|parent| parent != recorded) {
+            let fork_block = reorg::reconcile_reorg(
+                provider.as_ref(),
+                db_pool,
+                reorg_tracker,
+                last_processed,
+                event_tx,
+            )
+            .await?;
+
+            // Reprocess the new chain from the fork point on the next round
+            let resume_after = fork_block.saturating_sub(1);
+            last_processed_block.store(resume_after, Ordering::Relaxed);
+            save_checkpoint(db_pool, resume_after).await;
+            return Ok(());
+        }
+    }
+
+    // Catch up in bounded chunks after a long outage
     let from_block = last_processed + 1;
-    let blocks_to_process = current_block - last_processed;
+    let to_block = current_block.min(last_processed + MAX_BLOCKS_PER_POLL);
+    let blocks_to_process = to_block - last_processed;
 
     debug!(
         "Polling blocks {} to {} ({} new blocks)",
-        from_block, current_block, blocks_to_process
+        from_block, to_block, blocks_to_process
     );
 
-    let mut total_events_found = 0;
-
-    // Poll for each event type
-    for (event_name, signature_hash) in key_events {
-        let filter = Filter::new()
-            .address(pool_address)
-            .event_signature(*signature_hash)
-            .from_block(BlockNumberOrTag::Number(from_block))
-            .to_block(BlockNumberOrTag::Number(current_block));
-
-        match provider.get_logs(&filter).await {
-            Ok(logs) => {
-                if !logs.is_empty() {
-                    info!(
-                        "📊 Found {} {} events in blocks {}-{}",
-                        logs.len(),
-                        event_name,
-                        from_block,
-                        current_block
-                    );
-                    total_events_found += logs.len();
-                }
-
-                for log in logs {
-                    if let Err(e) = handle_log_event(log, event_tx, priority_liquidation_tx, provider, pool_address).await {
-                        error!("Error handling {} event: {}", event_name, e);
-                    }
-                }
-            }
-            Err(e) => {
-                error!(
-                    "Failed to get {} events for blocks {}-{}: {}",
-                    event_name, from_block, current_block, e
-                );
-                // Continue with other event types
-            }
-        }
-
-        // Brief delay between event type queries to avoid rate limiting
-        tokio::time::sleep(Duration::from_millis(100)).await;
-    }
+    // On error the checkpoint stays put and the same range is retried next round
+    let total_events_found = process_block_range(
+        provider,
+        pool_address,
+        from_block,
+        to_block,
+        reorg_tracker,
+        event_tx,
+        priority_liquidation_tx,
+        recorder,
+    )
+    .await?;
 
     if total_events_found > 0 {
         info!(
@@ -216,29 +552,41 @@ where
         );
     }
 
+    // Remember the range's last block so the next round can check its successor's parent
+    if let Some(block) = provider
+        .get_block_by_number(BlockNumberOrTag::Number(to_block), false)
+        .await?
+    {
+        if !reorg_tracker.record(to_block, block.header.hash, []) {
+            warn!("Block {} changed while it was being processed", to_block);
+        }
+    }
+
     // Update last processed block
-    LAST_PROCESSED_BLOCK.store(current_block, Ordering::Relaxed);
+    last_processed_block.store(to_block, Ordering::Relaxed);
+    save_checkpoint(db_pool, to_block).await;
 
     Ok(())
 }
 
-pub async fn handle_log_event<P>(
-    log: Log, 
-    event_tx: &mpsc::UnboundedSender<BotEvent>,
-    priority_liquidation_tx: &Option<mpsc::UnboundedSender<Address>>,
-    provider: &Arc<P>,
-    pool_address: Address,
-) -> Result<()> 
-where
-    P: Provider,
-{
+/// Append a raw log to the event recording, if recording is enabled
+fn record_log(recorder: &Option<Arc<EventRecorder>>, log: &Log) {
+    if let Some(recorder) = recorder {
+        recorder.record(&RecordedEntry::Log(log.clone()));
+    }
+}
+
+/// User addresses referenced by an Aave pool log
+pub fn extract_log_users(log: &Log) -> HashSet<Address> {
     // For now, extract user addresses from log topics manually
     // In Aave events, user addresses are typically in topic[1] or topic[2]
     let topics = log.topics();
+    let mut user_addresses = HashSet::new();
 
-    debug!("Processing log event with {} topics", topics.len());
-
-    let mut user_addresses = std::collections::HashSet::new();
+    // Reserve updates name the reserve, not a user
+    if is_reserve_data_update(log) {
+        return user_addresses;
+    }
 
     // Most Aave events have user address in topic[1] (after the event signature)
     if topics.len() >= 2 {
@@ -264,9 +612,38 @@ where
         }
     }
 
+    user_addresses
+}
+
+fn is_reserve_data_update(log: &Log) -> bool {
+    log.topics().first() == Some(&ReserveDataUpdated::SIGNATURE_HASH)
+}
+
+pub async fn handle_log_event<P>(
+    log: Log, 
+    event_tx: &EventSender,
+    priority_liquidation_tx: &Option<mpsc::Sender<Address>>,
+    provider: &Arc<P>,
+    pool_address: Address,
+) -> Result<()> 
+where
+    P: Provider,
+{
+    debug!("Processing log event with {} topics", log.topics().len());
+
+    if is_reserve_data_update(&log) {
+        if let Some(reserve) = log.topics().get(1) {
+            let _ = event_tx.send(BotEvent::ReserveDataUpdated(Address::from_word(*reserve)));
+        }
+        return Ok(());
+    }
+
+    let user_addresses = extract_log_users(&log);
+
     // Process each unique user address
     for user_addr in user_addresses {
         debug!("Detected event for user: {}", user_addr);
+        latency::mark(user_addr, LatencyStage::LogReceived);
         
         // WebSocket Fast Path: If priority liquidation channel is available, 
         // immediately check user health and route liquidatable users to priority channel
@@ -310,9 +687,12 @@ where
                         {
                             info!("⚡ Fast path liquidation detected for user: {:?} (HF: {})", 
                                   user_addr, position.health_factor);
+                            latency::mark(user_addr, LatencyStage::HealthConfirmed);
                             
-                            if let Err(e) = priority_tx.send(user_addr) {
+                            if let Err(e) = priority_tx.try_send(user_addr) {
                                 warn!("Failed to send fast path liquidation for user {:?}: {}", user_addr, e);
+                                // Fallback to regular event queue
+                                let _ = event_tx.send(BotEvent::LiquidationOpportunity(user_addr));
                             }
                         } else {
                             debug!("Fast path: user {:?} not liquidatable (HF: {}, debt: {})", 
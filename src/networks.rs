@@ -0,0 +1,153 @@
+use alloy_primitives::{address, Address};
+
+/// Multicall3, deployed at the same address on every supported chain
+pub const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+/// Morpho Blue singleton on Ethereum and Base
+pub const MORPHO_BLUE: Address = address!("BBBBBbbBBb9cC5e90e3b3Af64bdAF62C37EEFFCb");
+
+/// Uniswap V3 SwapRouter02 on Ethereum, Optimism and Arbitrum
+const UNISWAP_V3_ROUTER: Address = address!("68b3465833fb72A70ecDF485E0e4C7bD8665Fc45");
+
+/// Uniswap V3 QuoterV2 on Ethereum, Optimism and Arbitrum
+const UNISWAP_V3_QUOTER: Address = address!("61fFE014bA17989E743c5F6cB21bF9697530B21e");
+
+/// Aave v3 and token contracts the bot talks to on one chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NetworkAddresses {
+    pub pool: Address,
+    pub pool_addresses_provider: Address,
+    pub oracle: Address,
+    /// AaveProtocolDataProvider (reserve configuration, user reserve balances)
+    pub data_provider: Address,
+    pub multicall: Address,
+    pub weth: Address,
+    /// Uniswap V3 SwapRouter02, used to convert received collateral
+    pub swap_router: Address,
+    /// Uniswap V3 QuoterV2, used to quote swaps and slippage
+    pub swap_quoter: Address,
+    /// Morpho Blue singleton for fee-free flash loans, where known
+    pub morpho_blue: Option<Address>,
+}
+
+/// A chain with built-in addresses
+#[derive(Debug, Clone, Copy)]
+pub struct Network {
+    pub chain_id: u64,
+    pub name: &'static str,
+    /// Typical seconds between blocks, rounded up to a whole second
+    pub block_time_secs: u64,
+    pub addresses: NetworkAddresses,
+}
+
+/// Chains whose Aave v3 deployment is known; any of their addresses can still be overridden
+/// through the config, and other chains must configure every address themselves
+pub const BUILTIN_NETWORKS: &[Network] = &[
+    Network {
+        chain_id: 8453,
+        name: "base",
+        block_time_secs: 2,
+        addresses: NetworkAddresses {
+            pool: address!("A238Dd80C259a72e81d7e4664a9801593F98d1c5"),
+            pool_addresses_provider: address!("e20fCBdBfFC4Dd138cE8b2E6FBb6CB49777ad64D"),
+            oracle: address!("2Cc0Fc26eD4563A5ce5e8bdcfe1A2878676Ae156"),
+            data_provider: address!("C4Fcf9893072d61Cc2899C0054877Cb752587981"),
+            multicall: MULTICALL3,
+            weth: address!("4200000000000000000000000000000000000006"),
+            swap_router: address!("2626664c2603336E57B271c5C0b26F421741e481"),
+            swap_quoter: address!("3d4e44Eb1374240CE5F1B871ab261CD16335B76a"),
+            morpho_blue: Some(MORPHO_BLUE),
+        },
+    },
+    Network {
+        chain_id: 1,
+        name: "ethereum",
+        block_time_secs: 12,
+        addresses: NetworkAddresses {
+            pool: address!("87870Bca3F3fD6335C3F4ce8392D69350B4fA4E2"),
+            pool_addresses_provider: address!("2f39d218133AFaB8F2B819B1066c7E434Ad94E9e"),
+            oracle: address!("54586bE62E3c3580375aE3723C145253060Ca0C2"),
+            data_provider: address!("7B4EB56E7CD4b454BA8ff71E4518426369a138a3"),
+            multicall: MULTICALL3,
+            weth: address!("C02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2"),
+            swap_router: UNISWAP_V3_ROUTER,
+            swap_quoter: UNISWAP_V3_QUOTER,
+            morpho_blue: Some(MORPHO_BLUE),
+        },
+    },
+    Network {
+        chain_id: 10,
+        name: "optimism",
+        block_time_secs: 2,
+        addresses: NetworkAddresses {
+            pool: address!("794a61358D6845594F94dc1DB02A252b5b4814aD"),
+            pool_addresses_provider: address!("a97684ead0e402dC232d5A977953DF7ECBaB3CDb"),
+            oracle: address!("D81eb3728a631871a7eBBaD631b5f424909f0c77"),
+            data_provider: address!("69FA688f1Dc47d4B5d8029D5a35FB7a548310654"),
+            multicall: MULTICALL3,
+            weth: address!("4200000000000000000000000000000000000006"),
+            swap_router: UNISWAP_V3_ROUTER,
+            swap_quoter: UNISWAP_V3_QUOTER,
+            morpho_blue: None,
+        },
+    },
+    Network {
+        chain_id: 42161,
+        name: "arbitrum",
+        block_time_secs: 1,
+        addresses: NetworkAddresses {
+            pool: address!("794a61358D6845594F94dc1DB02A252b5b4814aD"),
+            pool_addresses_provider: address!("a97684ead0e402dC232d5A977953DF7ECBaB3CDb"),
+            oracle: address!("b56c2F0B653B2e0b10C9b928C8580Ac5Df02C7C7"),
+            data_provider: address!("69FA688f1Dc47d4B5d8029D5a35FB7a548310654"),
+            multicall: MULTICALL3,
+            weth: address!("82aF49447D8a07e3bd95BD0d56f35241523fBab1"),
+            swap_router: UNISWAP_V3_ROUTER,
+            swap_quoter: UNISWAP_V3_QUOTER,
+            morpho_blue: None,
+        },
+    },
+];
+
+/// Built-in network of `chain_id`, if any
+pub fn builtin(chain_id: u64) -> Option<&'static Network> {
+    BUILTIN_NETWORKS
+        .iter()
+        .find(|network| network.chain_id == chain_id)
+}
+
+/// Built-in addresses of Base mainnet, the chain run when none is configured
+pub fn base() -> NetworkAddresses {
+    BUILTIN_NETWORKS[0].addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_builtin_networks_are_unique_and_complete() {
+        let mut chain_ids = HashSet::new();
+        for network in BUILTIN_NETWORKS {
+            assert!(chain_ids.insert(network.chain_id), "duplicate chain {}", network.chain_id);
+            let addresses = network.addresses;
+            for address in [
+                addresses.pool,
+                addresses.pool_addresses_provider,
+                addresses.oracle,
+                addresses.data_provider,
+                addresses.multicall,
+                addresses.weth,
+                addresses.swap_router,
+                addresses.swap_quoter,
+            ] {
+                assert_ne!(address, Address::ZERO, "{} has an unset address", network.name);
+            }
+            assert_ne!(addresses.morpho_blue, Some(Address::ZERO));
+        }
+        assert_eq!(builtin(8453).unwrap().name, "base");
+        assert_eq!(base(), builtin(8453).unwrap().addresses);
+        assert!(builtin(999_999).is_none());
+    }
+}
@@ -0,0 +1,524 @@
+use async_trait::async_trait;
+use eyre::Result;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+use crate::config::BotConfig;
+use crate::models::AlertRoute;
+use crate::monitoring::alerts::{AlertEvent, AlertSeverity};
+use crate::monitoring::operator_balance::BalanceAlertLevel;
+
+/// Telegram Bot API base URL
+const TELEGRAM_API_URL: &str = "https://api.telegram.org";
+
+/// PagerDuty Events API v2 endpoint
+const PAGERDUTY_EVENTS_URL: &str = "https://events.pagerduty.com/v2/enqueue";
+
+/// A chat or paging destination alerts are pushed to
+#[async_trait]
+pub trait NotificationChannel: Send + Sync {
+    /// Name used in logs and in ALERT_ROUTES
+    fn name(&self) -> &'static str;
+
+    /// Whether the channel wants `event` when no route overrides it
+    fn accepts(&self, _event: &AlertEvent) -> bool {
+        true
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<()>;
+}
+
+fn severity_icon(severity: AlertSeverity) -> &'static str {
+    match severity {
+        AlertSeverity::Info => "ℹ️",
+        AlertSeverity::Warning => "⚠️",
+        AlertSeverity::Critical => "🚨",
+    }
+}
+
+/// Plain-text rendering of an alert for chat messages
+pub fn format_alert_text(event: &AlertEvent) -> String {
+    let severity = event.severity();
+    format!(
+        "{} [{}] {}\n{}",
+        severity_icon(severity),
+        severity.as_str().to_uppercase(),
+        event.kind(),
+        event.message()
+    )
+}
+
+async fn post_json(
+    client: &reqwest::Client,
+    url: &str,
+    body: &Value,
+    channel: &str,
+) -> Result<()> {
+    let response = client.post(url).json(body).send().await?;
+    if !response.status().is_success() {
+        return Err(eyre::eyre!("{} returned status {}", channel, response.status()));
+    }
+    Ok(())
+}
+
+/// Telegram `sendMessage` body for an alert
+pub fn telegram_payload(chat_id: &str, event: &AlertEvent) -> Value {
+    json!({
+        "chat_id": chat_id,
+        "text": format_alert_text(event),
+        "disable_web_page_preview": true,
+    })
+}
+
+/// Sends alerts to a Telegram chat through a bot
+pub struct TelegramNotifier {
+    api_url: String,
+    bot_token: String,
+    chat_id: String,
+    client: reqwest::Client,
+}
+
+impl TelegramNotifier {
+    pub fn new(bot_token: String, chat_id: String, client: reqwest::Client) -> Self {
+        Self {
+            api_url: TELEGRAM_API_URL.to_string(),
+            bot_token,
+            chat_id,
+            client,
+        }
+    }
+
+    /// Talk to a Bot API server other than api.telegram.org (e.g. a self-hosted one)
+    pub fn with_api_url(mut self, api_url: String) -> Self {
+        self.api_url = api_url.trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for TelegramNotifier {
+    fn name(&self) -> &'static str {
+        "telegram"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<()> {
+        let url = format!("{}/bot{}/sendMessage", self.api_url, self.bot_token);
+        post_json(
+            &self.client,
+            &url,
+            &telegram_payload(&self.chat_id, event),
+            "Telegram",
+        )
+        .await
+    }
+}
+
+/// Discord embed colour for an alert's severity
+fn discord_color(severity: AlertSeverity) -> u32 {
+    match severity {
+        AlertSeverity::Info => 0x3498db,
+        AlertSeverity::Warning => 0xf1c40f,
+        AlertSeverity::Critical => 0xe74c3c,
+    }
+}
+
+/// Discord webhook body for an alert: one embed coloured by severity
+pub fn discord_payload(event: &AlertEvent) -> Value {
+    let severity = event.severity();
+    json!({
+        "embeds": [{
+            "title": format!("{} {}", severity_icon(severity), event.kind()),
+            "description": event.message(),
+            "color": discord_color(severity),
+            "footer": { "text": severity.as_str() },
+        }],
+    })
+}
+
+/// Sends alerts to a Discord channel webhook
+pub struct DiscordNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl DiscordNotifier {
+    pub fn new(webhook_url: String, client: reqwest::Client) -> Self {
+        Self {
+            webhook_url,
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for DiscordNotifier {
+    fn name(&self) -> &'static str {
+        "discord"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<()> {
+        post_json(&self.client, &self.webhook_url, &discord_payload(event), "Discord").await
+    }
+}
+
+/// Slack incoming webhook body for an alert
+pub fn slack_payload(event: &AlertEvent) -> Value {
+    json!({ "text": format_alert_text(event) })
+}
+
+/// Sends alerts to a Slack channel through an incoming webhook
+pub struct SlackNotifier {
+    webhook_url: String,
+    client: reqwest::Client,
+}
+
+impl SlackNotifier {
+    pub fn new(webhook_url: String, client: reqwest::Client) -> Self {
+        Self {
+            webhook_url,
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for SlackNotifier {
+    fn name(&self) -> &'static str {
+        "slack"
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<()> {
+        post_json(&self.client, &self.webhook_url, &slack_payload(event), "Slack").await
+    }
+}
+
+/// What an alert does to its PagerDuty incident
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IncidentAction {
+    Trigger,
+    Resolve,
+}
+
+impl IncidentAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            IncidentAction::Trigger => "trigger",
+            IncidentAction::Resolve => "resolve",
+        }
+    }
+}
+
+/// Incident an alert belongs to (its dedup key) and whether it opens or resolves it.
+/// Recoveries resolve the incident their failure opened; other events trigger one per kind.
+pub fn pagerduty_incident(event: &AlertEvent) -> (&'static str, IncidentAction) {
+    match event {
+        AlertEvent::CircuitBreakerOpened { .. } => ("circuit_breaker", IncidentAction::Trigger),
+        AlertEvent::CircuitBreakerRecovered { .. } => ("circuit_breaker", IncidentAction::Resolve),
+        AlertEvent::ConnectionLost { .. } => ("connection", IncidentAction::Trigger),
+        AlertEvent::ConnectionRestored { .. } => ("connection", IncidentAction::Resolve),
+        AlertEvent::DatabaseUnreachable { .. } => ("database", IncidentAction::Trigger),
+        AlertEvent::DatabaseRecovered => ("database", IncidentAction::Resolve),
+        AlertEvent::OracleUnhealthy { .. } => ("oracle", IncidentAction::Trigger),
+        AlertEvent::OracleRecovered => ("oracle", IncidentAction::Resolve),
+        AlertEvent::OperatorBalanceChanged {
+            level: BalanceAlertLevel::Healthy,
+            ..
+        } => ("operator_balance", IncidentAction::Resolve),
+        AlertEvent::OperatorBalanceChanged { .. } => ("operator_balance", IncidentAction::Trigger),
+        AlertEvent::TokenBalanceLow { .. } => ("token_balance", IncidentAction::Trigger),
+        AlertEvent::TokenBalanceRecovered { .. } => ("token_balance", IncidentAction::Resolve),
+        other => (other.kind(), IncidentAction::Trigger),
+    }
+}
+
+/// PagerDuty Events API v2 body for an alert
+pub fn pagerduty_payload(routing_key: &str, event: &AlertEvent) -> Value {
+    let (dedup_key, action) = pagerduty_incident(event);
+    let mut body = json!({
+        "routing_key": routing_key,
+        "event_action": action.as_str(),
+        "dedup_key": format!("liquidation-bot/{}", dedup_key),
+    });
+    if action == IncidentAction::Trigger {
+        body["payload"] = json!({
+            "summary": event.message(),
+            "source": "liquidation-bot",
+            "severity": event.severity().as_str(),
+            "component": event.kind(),
+        });
+    }
+    body
+}
+
+/// Opens (and resolves) PagerDuty incidents for critical alerts
+pub struct PagerDutyNotifier {
+    events_url: String,
+    routing_key: String,
+    client: reqwest::Client,
+}
+
+impl PagerDutyNotifier {
+    pub fn new(routing_key: String, client: reqwest::Client) -> Self {
+        Self {
+            events_url: PAGERDUTY_EVENTS_URL.to_string(),
+            routing_key,
+            client,
+        }
+    }
+
+    /// Send events somewhere other than the public Events API (e.g. a regional endpoint)
+    pub fn with_events_url(mut self, events_url: String) -> Self {
+        self.events_url = events_url;
+        self
+    }
+}
+
+#[async_trait]
+impl NotificationChannel for PagerDutyNotifier {
+    fn name(&self) -> &'static str {
+        "pagerduty"
+    }
+
+    /// Only critical alerts page, plus the recoveries that resolve their incidents
+    fn accepts(&self, event: &AlertEvent) -> bool {
+        event.severity() == AlertSeverity::Critical
+            || pagerduty_incident(event).1 == IncidentAction::Resolve
+    }
+
+    async fn send(&self, event: &AlertEvent) -> Result<()> {
+        post_json(
+            &self.client,
+            &self.events_url,
+            &pagerduty_payload(&self.routing_key, event),
+            "PagerDuty",
+        )
+        .await
+    }
+}
+
+/// Pushes alerts to the configured chat and paging channels: each channel's own selection by
+/// default, or the channels an ALERT_ROUTES entry names for that event kind
+#[derive(Clone, Default)]
+pub struct Notifications {
+    channels: Vec<Arc<dyn NotificationChannel>>,
+    /// Event kind -> channel names, overriding `accepts`
+    routes: HashMap<String, Vec<String>>,
+}
+
+impl Notifications {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_channel(mut self, channel: Arc<dyn NotificationChannel>) -> Self {
+        self.channels.push(channel);
+        self
+    }
+
+    pub fn with_routes(mut self, routes: &[AlertRoute]) -> Self {
+        for route in routes {
+            self.routes.insert(route.event.clone(), route.channels.clone());
+        }
+        self
+    }
+
+    /// Channels from configuration
+    pub fn from_config(config: &BotConfig, client: &reqwest::Client) -> Self {
+        let mut notifications = Self::new();
+        let telegram = (&config.telegram_bot_token, &config.telegram_chat_id);
+        if let (Some(token), Some(chat_id)) = telegram {
+            info!("📨 Alerts will be sent to Telegram chat {}", chat_id);
+            notifications = notifications.with_channel(Arc::new(TelegramNotifier::new(
+                token.clone(),
+                chat_id.clone(),
+                client.clone(),
+            )));
+        }
+        if let Some(url) = &config.discord_webhook_url {
+            info!("📨 Alerts will be sent to a Discord webhook");
+            notifications = notifications
+                .with_channel(Arc::new(DiscordNotifier::new(url.clone(), client.clone())));
+        }
+        if let Some(url) = &config.slack_webhook_url {
+            info!("📨 Alerts will be sent to a Slack webhook");
+            notifications = notifications
+                .with_channel(Arc::new(SlackNotifier::new(url.clone(), client.clone())));
+        }
+        if let Some(routing_key) = &config.pagerduty_routing_key {
+            info!("📟 Critical alerts will open PagerDuty incidents");
+            notifications = notifications.with_channel(Arc::new(PagerDutyNotifier::new(
+                routing_key.clone(),
+                client.clone(),
+            )));
+        }
+        notifications.with_routes(&config.alert_routes)
+    }
+
+    /// Whether `channel` receives `event`
+    fn routes_to(&self, channel: &dyn NotificationChannel, event: &AlertEvent) -> bool {
+        match self.routes.get(event.kind()) {
+            Some(names) => names.iter().any(|name| name == channel.name()),
+            None => channel.accepts(event),
+        }
+    }
+
+    /// Whether any channel receives `event`
+    pub fn has_destination(&self, event: &AlertEvent) -> bool {
+        self.channels
+            .iter()
+            .any(|channel| self.routes_to(channel.as_ref(), event))
+    }
+
+    /// Send to every channel routed the event; one channel failing doesn't stop the others
+    pub async fn send(&self, event: &AlertEvent) -> Result<()> {
+        let mut failed = Vec::new();
+        for channel in &self.channels {
+            if !self.routes_to(channel.as_ref(), event) {
+                continue;
+            }
+            if let Err(e) = channel.send(event).await {
+                warn!("Failed to send {} alert to {}: {}", event.kind(), channel.name(), e);
+                failed.push(channel.name());
+            }
+        }
+        if failed.is_empty() {
+            Ok(())
+        } else {
+            Err(eyre::eyre!("Notification channel(s) failed: {}", failed.join(", ")))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{Address, U256};
+    use parking_lot::Mutex;
+
+    struct RecordingChannel {
+        sent: Mutex<Vec<String>>,
+        fail: bool,
+    }
+
+    #[async_trait]
+    impl NotificationChannel for RecordingChannel {
+        fn name(&self) -> &'static str {
+            "recording"
+        }
+
+        async fn send(&self, event: &AlertEvent) -> Result<()> {
+            if self.fail {
+                return Err(eyre::eyre!("unreachable"));
+            }
+            self.sent.lock().push(event.kind().to_string());
+            Ok(())
+        }
+    }
+
+    fn liquidation() -> AlertEvent {
+        AlertEvent::LiquidationSucceeded {
+            user: Address::repeat_byte(0x01),
+            tx_hash: "0xabc".to_string(),
+            profit: U256::from(5_000u64),
+        }
+    }
+
+    #[test]
+    fn test_payloads_carry_severity_and_message() {
+        let event = AlertEvent::CircuitBreakerOpened {
+            message: "Circuit breaker OPEN".to_string(),
+        };
+
+        let telegram = telegram_payload("-100123", &event);
+        assert_eq!(telegram["chat_id"], "-100123");
+        assert_eq!(
+            telegram["text"],
+            "🚨 [CRITICAL] circuit_breaker_opened\nCircuit breaker OPEN"
+        );
+
+        let discord = discord_payload(&event);
+        assert_eq!(discord["embeds"][0]["description"], "Circuit breaker OPEN");
+        assert_eq!(discord["embeds"][0]["color"], 0xe74c3c);
+        assert_eq!(discord_payload(&liquidation())["embeds"][0]["color"], 0x3498db);
+    }
+
+    #[tokio::test]
+    async fn test_failing_channel_does_not_block_others() {
+        let failing = Arc::new(RecordingChannel {
+            sent: Mutex::new(Vec::new()),
+            fail: true,
+        });
+        let working = Arc::new(RecordingChannel {
+            sent: Mutex::new(Vec::new()),
+            fail: false,
+        });
+        let notifications = Notifications::new()
+            .with_channel(failing)
+            .with_channel(working.clone());
+
+        assert!(notifications.send(&liquidation()).await.is_err());
+        assert_eq!(*working.sent.lock(), vec!["liquidation_succeeded".to_string()]);
+        assert!(Notifications::new().send(&liquidation()).await.is_ok());
+    }
+
+    #[test]
+    fn test_pagerduty_resolves_what_it_triggered() {
+        let lost = AlertEvent::ConnectionLost {
+            endpoint: "WebSocket".to_string(),
+            error: "refused".to_string(),
+        };
+        let restored = AlertEvent::ConnectionRestored {
+            endpoint: "WebSocket".to_string(),
+        };
+
+        let trigger = pagerduty_payload("key", &lost);
+        assert_eq!(trigger["event_action"], "trigger");
+        assert_eq!(trigger["dedup_key"], "liquidation-bot/connection");
+        assert_eq!(trigger["payload"]["severity"], "critical");
+
+        let resolve = pagerduty_payload("key", &restored);
+        assert_eq!(resolve["event_action"], "resolve");
+        assert_eq!(resolve["dedup_key"], trigger["dedup_key"]);
+        assert!(resolve.get("payload").is_none());
+
+        // Only critical alerts and their recoveries reach PagerDuty by default
+        let pagerduty = PagerDutyNotifier::new("key".to_string(), reqwest::Client::new());
+        assert!(pagerduty.accepts(&lost));
+        assert!(pagerduty.accepts(&restored));
+        assert!(!pagerduty.accepts(&liquidation()));
+    }
+
+    #[tokio::test]
+    async fn test_routes_override_channel_defaults() {
+        let recording = Arc::new(RecordingChannel {
+            sent: Mutex::new(Vec::new()),
+            fail: false,
+        });
+        let notifications = Notifications::new()
+            .with_channel(recording.clone())
+            .with_routes(&[
+                AlertRoute {
+                    event: "liquidation_succeeded".to_string(),
+                    channels: Vec::new(),
+                },
+                AlertRoute {
+                    event: "database_unreachable".to_string(),
+                    channels: vec!["slack".to_string(), "recording".to_string()],
+                },
+            ]);
+        let unreachable = AlertEvent::DatabaseUnreachable {
+            error: "connection refused".to_string(),
+        };
+
+        assert!(!notifications.has_destination(&liquidation()));
+        notifications.send(&liquidation()).await.unwrap();
+        notifications.send(&unreachable).await.unwrap();
+        notifications.send(&AlertEvent::DatabaseRecovered).await.unwrap();
+        assert_eq!(
+            *recording.sent.lock(),
+            vec!["database_unreachable".to_string(), "database_recovered".to_string()]
+        );
+    }
+}
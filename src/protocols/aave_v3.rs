@@ -0,0 +1,191 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::{sol, SolCall, SolEvent};
+use async_trait::async_trait;
+use eyre::Result;
+use std::sync::Arc;
+
+use super::{accounts_from_logs, LiquidationRequest, Protocol, HEALTH_FACTOR_ONE};
+use crate::models::{ProtocolKind, ProtocolLiquidationCall, ProtocolPosition, UserPosition};
+use crate::monitoring::scanner::parse_user_account_data;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IAaveV3Pool {
+        function getUserAccountData(address user) external view returns (
+            uint256 totalCollateralBase,
+            uint256 totalDebtBase,
+            uint256 availableBorrowsBase,
+            uint256 currentLiquidationThreshold,
+            uint256 ltv,
+            uint256 healthFactor
+        );
+        function liquidationCall(
+            address collateralAsset,
+            address debtAsset,
+            address user,
+            uint256 debtToCover,
+            bool receiveAToken
+        ) external;
+
+        event Borrow(
+            address indexed reserve,
+            address user,
+            address indexed onBehalfOf,
+            uint256 amount,
+            uint8 interestRateMode,
+            uint256 borrowRate,
+            uint16 indexed referralCode
+        );
+    }
+}
+
+/// Convert Aave account data into the protocol-neutral snapshot. Collateral is weighted by
+/// the account's liquidation threshold (basis points) so it is comparable across protocols.
+pub fn position_from_account_data(position: &UserPosition) -> ProtocolPosition {
+    let collateral_value = position
+        .total_collateral_base
+        .saturating_mul(position.current_liquidation_threshold)
+        / U256::from(10_000u64);
+
+    ProtocolPosition {
+        protocol: ProtocolKind::AaveV3,
+        address: position.address,
+        collateral_value,
+        debt_value: position.total_debt_base,
+        health_factor: position.health_factor,
+        is_liquidatable: !position.total_debt_base.is_zero()
+            && position.health_factor < HEALTH_FACTOR_ONE,
+        last_updated: position.last_updated,
+    }
+}
+
+/// Aave V3 Pool adapter
+pub struct AaveV3Protocol<P> {
+    provider: Arc<P>,
+    pool: Address,
+}
+
+impl<P> AaveV3Protocol<P>
+where
+    P: Provider,
+{
+    pub fn new(provider: Arc<P>, pool: Address) -> Self {
+        Self { provider, pool }
+    }
+}
+
+#[async_trait]
+impl<P> Protocol for AaveV3Protocol<P>
+where
+    P: Provider + 'static,
+{
+    fn kind(&self) -> ProtocolKind {
+        ProtocolKind::AaveV3
+    }
+
+    fn market(&self) -> Address {
+        self.pool
+    }
+
+    async fn account_data(&self, account: Address) -> Result<ProtocolPosition> {
+        let call = IAaveV3Pool::getUserAccountDataCall { user: account };
+        let result = self
+            .provider
+            .call(
+                &alloy_rpc_types::TransactionRequest::default()
+                    .to(self.pool)
+                    .input(call.abi_encode().into()),
+            )
+            .await?;
+
+        Ok(position_from_account_data(&parse_user_account_data(&result, account)?))
+    }
+
+    async fn discover_accounts(&self, from_block: u64, to_block: u64) -> Result<Vec<Address>> {
+        // Borrow topics: [signature, reserve, onBehalfOf, referralCode]
+        accounts_from_logs(
+            self.provider.as_ref(),
+            self.pool,
+            IAaveV3Pool::Borrow::SIGNATURE_HASH,
+            2,
+            from_block,
+            to_block,
+        )
+        .await
+    }
+
+    fn encode_liquidation(&self, request: &LiquidationRequest) -> ProtocolLiquidationCall {
+        let call = IAaveV3Pool::liquidationCallCall {
+            collateralAsset: request.collateral_asset,
+            debtAsset: request.debt_asset,
+            user: request.account,
+            debtToCover: request.debt_to_cover,
+            receiveAToken: false,
+        };
+        ProtocolLiquidationCall {
+            target: self.pool,
+            calldata: call.abi_encode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_provider::ProviderBuilder;
+
+    #[test]
+    fn test_position_from_account_data() {
+        let position = UserPosition {
+            address: Address::repeat_byte(0x01),
+            total_collateral_base: U256::from(10_000u64),
+            total_debt_base: U256::from(8_500u64),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_000u64),
+            ltv: U256::from(7_500u64),
+            health_factor: U256::from(941_176_470_588_235_294u64),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: true,
+        };
+
+        let snapshot = position_from_account_data(&position);
+
+        assert_eq!(snapshot.protocol, ProtocolKind::AaveV3);
+        assert_eq!(snapshot.collateral_value, U256::from(8_000u64));
+        assert_eq!(snapshot.debt_value, U256::from(8_500u64));
+        assert!(snapshot.is_liquidatable);
+
+        // No debt is never liquidatable, whatever the reported health factor
+        let repaid = UserPosition {
+            total_debt_base: U256::ZERO,
+            health_factor: U256::ZERO,
+            ..position
+        };
+        assert!(!position_from_account_data(&repaid).is_liquidatable);
+    }
+
+    #[test]
+    fn test_liquidation_call_encoding() {
+        let provider = Arc::new(ProviderBuilder::new().on_http("http://127.0.0.1:1".parse().unwrap()));
+        let pool = Address::repeat_byte(0xaa);
+        let protocol = AaveV3Protocol::new(provider, pool);
+        let request = LiquidationRequest {
+            account: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(500u64),
+            liquidator: Address::repeat_byte(0x04),
+        };
+
+        let call = protocol.encode_liquidation(&request);
+
+        assert_eq!(call.target, pool);
+        let decoded = IAaveV3Pool::liquidationCallCall::abi_decode(&call.calldata, true).unwrap();
+        assert_eq!(decoded.user, request.account);
+        assert_eq!(decoded.collateralAsset, request.collateral_asset);
+        assert_eq!(decoded.debtAsset, request.debt_asset);
+        assert_eq!(decoded.debtToCover, request.debt_to_cover);
+        assert!(!decoded.receiveAToken);
+    }
+}
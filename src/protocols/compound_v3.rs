@@ -0,0 +1,355 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_sol_types::{sol, SolCall, SolEvent};
+use async_trait::async_trait;
+use eyre::Result;
+use std::sync::Arc;
+use tokio::sync::OnceCell;
+use tracing::debug;
+
+use super::{accounts_from_logs, call_contract, LiquidationRequest, Protocol, HEALTH_FACTOR_ONE};
+use crate::models::{ProtocolKind, ProtocolLiquidationCall, ProtocolPosition};
+
+sol! {
+    #[allow(missing_docs)]
+    interface IComet {
+        struct AssetInfo {
+            uint8 offset;
+            address asset;
+            address priceFeed;
+            uint64 scale;
+            uint64 borrowCollateralFactor;
+            uint64 liquidateCollateralFactor;
+            uint64 liquidationFactor;
+            uint128 supplyCap;
+        }
+
+        function isLiquidatable(address account) external view returns (bool);
+        function borrowBalanceOf(address account) external view returns (uint256);
+        function numAssets() external view returns (uint8);
+        function getAssetInfo(uint8 i) external view returns (AssetInfo memory);
+        function collateralBalanceOf(address account, address asset) external view returns (uint128);
+        function getPrice(address priceFeed) external view returns (uint256);
+        function baseTokenPriceFeed() external view returns (address);
+        function baseScale() external view returns (uint256);
+        function absorb(address absorber, address[] calldata accounts) external;
+
+        event Withdraw(address indexed src, address indexed to, uint256 amount);
+    }
+}
+
+/// Collateral asset parameters that never change for a deployed Comet market
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CometCollateralAsset {
+    pub asset: Address,
+    pub price_feed: Address,
+    pub scale: U256,
+    /// 18 decimals (e.g. 0.85e18)
+    pub liquidate_collateral_factor: U256,
+}
+
+/// One collateral holding of an account, priced in the market's 8-decimal USD
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CometCollateralBalance {
+    pub balance: U256,
+    pub price: U256,
+    pub scale: U256,
+    pub liquidate_collateral_factor: U256,
+}
+
+/// Static market configuration loaded once per adapter
+#[derive(Debug, Clone)]
+struct CometMarket {
+    assets: Vec<CometCollateralAsset>,
+    base_price_feed: Address,
+    base_scale: U256,
+}
+
+/// Liquidation-weighted collateral value, debt value (8-decimal USD) and health factor
+/// (18 decimals, `U256::MAX` without debt), mirroring Comet's own `isLiquidatable` math
+pub fn comet_account_values(
+    collateral: &[CometCollateralBalance],
+    borrow_balance: U256,
+    base_price: U256,
+    base_scale: U256,
+) -> (U256, U256, U256) {
+    let collateral_value = collateral
+        .iter()
+        .filter(|holding| !holding.scale.is_zero())
+        .fold(U256::ZERO, |total, holding| {
+            let value = holding.balance.saturating_mul(holding.price) / holding.scale;
+            total.saturating_add(
+                value.saturating_mul(holding.liquidate_collateral_factor) / HEALTH_FACTOR_ONE,
+            )
+        });
+
+    let debt_value = if base_scale.is_zero() {
+        U256::ZERO
+    } else {
+        borrow_balance.saturating_mul(base_price) / base_scale
+    };
+
+    let health_factor = if debt_value.is_zero() {
+        U256::MAX
+    } else {
+        collateral_value.saturating_mul(HEALTH_FACTOR_ONE) / debt_value
+    };
+
+    (collateral_value, debt_value, health_factor)
+}
+
+/// Compound V3 (Comet) market adapter. Liquidation is Comet's `absorb`, which anyone can call
+/// for an underwater account; the absorber is credited with protocol rewards.
+pub struct CompoundV3Protocol<P> {
+    provider: Arc<P>,
+    comet: Address,
+    market: OnceCell<CometMarket>,
+}
+
+impl<P> CompoundV3Protocol<P>
+where
+    P: Provider,
+{
+    pub fn new(provider: Arc<P>, comet: Address) -> Self {
+        Self {
+            provider,
+            comet,
+            market: OnceCell::new(),
+        }
+    }
+
+    async fn market_config(&self) -> Result<&CometMarket> {
+        self.market
+            .get_or_try_init(|| async {
+                let provider = self.provider.as_ref();
+                let num_assets = call_contract(provider, self.comet, &IComet::numAssetsCall {})
+                    .await?
+                    ._0;
+
+                let mut assets = Vec::with_capacity(num_assets as usize);
+                for i in 0..num_assets {
+                    let info = call_contract(provider, self.comet, &IComet::getAssetInfoCall { i })
+                        .await?
+                        ._0;
+                    assets.push(CometCollateralAsset {
+                        asset: info.asset,
+                        price_feed: info.priceFeed,
+                        scale: U256::from(info.scale),
+                        liquidate_collateral_factor: U256::from(info.liquidateCollateralFactor),
+                    });
+                }
+
+                let base_price_feed =
+                    call_contract(provider, self.comet, &IComet::baseTokenPriceFeedCall {})
+                        .await?
+                        ._0;
+                let base_scale = call_contract(provider, self.comet, &IComet::baseScaleCall {})
+                    .await?
+                    ._0;
+
+                debug!(
+                    "Loaded Comet market {}: {} collateral assets",
+                    self.comet,
+                    assets.len()
+                );
+                Ok::<_, eyre::Report>(CometMarket {
+                    assets,
+                    base_price_feed,
+                    base_scale,
+                })
+            })
+            .await
+    }
+
+    async fn price(&self, price_feed: Address) -> Result<U256> {
+        Ok(call_contract(
+            self.provider.as_ref(),
+            self.comet,
+            &IComet::getPriceCall {
+                priceFeed: price_feed,
+            },
+        )
+        .await?
+        ._0)
+    }
+}
+
+#[async_trait]
+impl<P> Protocol for CompoundV3Protocol<P>
+where
+    P: Provider + 'static,
+{
+    fn kind(&self) -> ProtocolKind {
+        ProtocolKind::CompoundV3
+    }
+
+    fn market(&self) -> Address {
+        self.comet
+    }
+
+    async fn account_data(&self, account: Address) -> Result<ProtocolPosition> {
+        let provider = self.provider.as_ref();
+        let borrow_balance =
+            call_contract(provider, self.comet, &IComet::borrowBalanceOfCall { account })
+                .await?
+                ._0;
+
+        // Suppliers without a borrow can't be liquidated; skip the per-asset reads
+        if borrow_balance.is_zero() {
+            return Ok(ProtocolPosition {
+                protocol: ProtocolKind::CompoundV3,
+                address: account,
+                collateral_value: U256::ZERO,
+                debt_value: U256::ZERO,
+                health_factor: U256::MAX,
+                is_liquidatable: false,
+                last_updated: chrono::Utc::now(),
+            });
+        }
+
+        let market = self.market_config().await?;
+        let mut collateral = Vec::new();
+        for asset in &market.assets {
+            let balance = call_contract(
+                provider,
+                self.comet,
+                &IComet::collateralBalanceOfCall {
+                    account,
+                    asset: asset.asset,
+                },
+            )
+            .await?
+            ._0;
+            if balance == 0 {
+                continue;
+            }
+            collateral.push(CometCollateralBalance {
+                balance: U256::from(balance),
+                price: self.price(asset.price_feed).await?,
+                scale: asset.scale,
+                liquidate_collateral_factor: asset.liquidate_collateral_factor,
+            });
+        }
+
+        let base_price = self.price(market.base_price_feed).await?;
+        let (collateral_value, debt_value, health_factor) =
+            comet_account_values(&collateral, borrow_balance, base_price, market.base_scale);
+
+        // Comet's own check is authoritative (it accounts for rounding we don't replicate)
+        let is_liquidatable = self.is_liquidatable(account).await?;
+
+        Ok(ProtocolPosition {
+            protocol: ProtocolKind::CompoundV3,
+            address: account,
+            collateral_value,
+            debt_value,
+            health_factor,
+            is_liquidatable,
+            last_updated: chrono::Utc::now(),
+        })
+    }
+
+    async fn is_liquidatable(&self, account: Address) -> Result<bool> {
+        Ok(call_contract(
+            self.provider.as_ref(),
+            self.comet,
+            &IComet::isLiquidatableCall { account },
+        )
+        .await?
+        ._0)
+    }
+
+    async fn discover_accounts(&self, from_block: u64, to_block: u64) -> Result<Vec<Address>> {
+        // Borrowing is withdrawing base beyond the supplied balance.
+        // Withdraw topics: [signature, src, to]
+        accounts_from_logs(
+            self.provider.as_ref(),
+            self.comet,
+            IComet::Withdraw::SIGNATURE_HASH,
+            1,
+            from_block,
+            to_block,
+        )
+        .await
+    }
+
+    fn encode_liquidation(&self, request: &LiquidationRequest) -> ProtocolLiquidationCall {
+        let call = IComet::absorbCall {
+            absorber: request.liquidator,
+            accounts: vec![request.account],
+        };
+        ProtocolLiquidationCall {
+            target: self.comet,
+            calldata: call.abi_encode(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_provider::ProviderBuilder;
+
+    fn e18(value: u64) -> U256 {
+        U256::from(value) * HEALTH_FACTOR_ONE
+    }
+
+    #[test]
+    fn test_comet_account_values() {
+        // 1 WETH at $2000 with an 0.85 liquidation factor against 1500 USDC of debt
+        let collateral = vec![CometCollateralBalance {
+            balance: e18(1),
+            price: U256::from(2_000u64 * 100_000_000),
+            scale: e18(1),
+            liquidate_collateral_factor: U256::from(850_000_000_000_000_000u64),
+        }];
+        let base_price = U256::from(100_000_000u64);
+        let base_scale = U256::from(1_000_000u64);
+
+        let (collateral_value, debt_value, health_factor) = comet_account_values(
+            &collateral,
+            U256::from(1_500u64 * 1_000_000),
+            base_price,
+            base_scale,
+        );
+
+        assert_eq!(collateral_value, U256::from(1_700u64 * 100_000_000));
+        assert_eq!(debt_value, U256::from(1_500u64 * 100_000_000));
+        assert!(health_factor > HEALTH_FACTOR_ONE);
+
+        // Same collateral against 1800 USDC of debt is underwater
+        let (_, _, health_factor) = comet_account_values(
+            &collateral,
+            U256::from(1_800u64 * 1_000_000),
+            base_price,
+            base_scale,
+        );
+        assert!(health_factor < HEALTH_FACTOR_ONE);
+
+        let (_, debt_value, health_factor) =
+            comet_account_values(&collateral, U256::ZERO, base_price, base_scale);
+        assert_eq!(debt_value, U256::ZERO);
+        assert_eq!(health_factor, U256::MAX);
+    }
+
+    #[test]
+    fn test_absorb_encoding() {
+        let provider = Arc::new(ProviderBuilder::new().on_http("http://127.0.0.1:1".parse().unwrap()));
+        let comet = Address::repeat_byte(0xcc);
+        let protocol = CompoundV3Protocol::new(provider, comet);
+        let request = LiquidationRequest {
+            account: Address::repeat_byte(0x01),
+            collateral_asset: Address::ZERO,
+            debt_asset: Address::ZERO,
+            debt_to_cover: U256::ZERO,
+            liquidator: Address::repeat_byte(0x04),
+        };
+
+        let call = protocol.encode_liquidation(&request);
+
+        assert_eq!(call.target, comet);
+        let decoded = IComet::absorbCall::abi_decode(&call.calldata, true).unwrap();
+        assert_eq!(decoded.absorber, request.liquidator);
+        assert_eq!(decoded.accounts, vec![request.account]);
+    }
+}
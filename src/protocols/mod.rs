@@ -0,0 +1,147 @@
+pub mod aave_v3;
+pub mod compound_v3;
+
+pub use aave_v3::AaveV3Protocol;
+pub use compound_v3::CompoundV3Protocol;
+
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::{Filter, TransactionRequest};
+use alloy_sol_types::{sol, SolCall};
+use async_trait::async_trait;
+use eyre::Result;
+use std::collections::BTreeSet;
+
+use crate::models::{ProtocolKind, ProtocolLiquidationCall, ProtocolPosition};
+
+sol! {
+    #[allow(missing_docs)]
+    interface IMulticall3 {
+        struct Call3 {
+            address target;
+            bool allowFailure;
+            bytes callData;
+        }
+        struct Call3Result {
+            bool success;
+            bytes returnData;
+        }
+        function aggregate3(Call3[] calldata calls) external payable returns (Call3Result[] memory returnData);
+    }
+}
+
+/// Health factor at and above which a position is safe (1.0 with 18 decimals)
+pub const HEALTH_FACTOR_ONE: U256 = U256::from_limbs([1_000_000_000_000_000_000u64, 0, 0, 0]);
+
+/// Liquidation parameters common to every protocol; adapters ignore what they don't need
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LiquidationRequest {
+    pub account: Address,
+    pub collateral_asset: Address,
+    pub debt_asset: Address,
+    pub debt_to_cover: U256,
+    /// Address credited with the liquidation (collateral recipient / absorber)
+    pub liquidator: Address,
+}
+
+/// A lending protocol the bot can monitor and liquidate on
+#[async_trait]
+pub trait Protocol: Send + Sync {
+    fn kind(&self) -> ProtocolKind;
+
+    /// Main market contract (Aave Pool, Compound Comet)
+    fn market(&self) -> Address;
+
+    /// Current health of `account` read from chain
+    async fn account_data(&self, account: Address) -> Result<ProtocolPosition>;
+
+    /// Whether `account` can be liquidated right now
+    async fn is_liquidatable(&self, account: Address) -> Result<bool> {
+        Ok(self.account_data(account).await?.is_liquidatable)
+    }
+
+    /// Accounts that opened or increased debt between the two blocks (inclusive)
+    async fn discover_accounts(&self, from_block: u64, to_block: u64) -> Result<Vec<Address>>;
+
+    /// Transaction target and calldata liquidating `request.account`
+    fn encode_liquidation(&self, request: &LiquidationRequest) -> ProtocolLiquidationCall;
+}
+
+/// `eth_call` a typed contract function
+pub(crate) async fn call_contract<P, C>(provider: &P, to: Address, call: &C) -> Result<C::Return>
+where
+    P: Provider,
+    C: SolCall,
+{
+    let result = provider
+        .call(
+            &TransactionRequest::default()
+                .to(to)
+                .input(call.abi_encode().into()),
+        )
+        .await
+        .map_err(|e| eyre::eyre!("{} call to {} failed: {}", C::SIGNATURE, to, e))?;
+
+    Ok(C::abi_decode_returns(&result, true)?)
+}
+
+/// `eth_call` one typed contract function on `to` for each of `calls` in a single Multicall3
+/// `aggregate3` request at `multicall`. Fails if any of the calls reverts.
+pub(crate) async fn multicall<P, C>(
+    provider: &P,
+    multicall: Address,
+    to: Address,
+    calls: &[C],
+) -> Result<Vec<C::Return>>
+where
+    P: Provider,
+    C: SolCall,
+{
+    if calls.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let aggregate = IMulticall3::aggregate3Call {
+        calls: calls
+            .iter()
+            .map(|call| IMulticall3::Call3 {
+                target: to,
+                allowFailure: false,
+                callData: call.abi_encode().into(),
+            })
+            .collect(),
+    };
+    call_contract(provider, multicall, &aggregate)
+        .await?
+        .returnData
+        .iter()
+        .map(|result| Ok(C::abi_decode_returns(&result.returnData, true)?))
+        .collect()
+}
+
+/// Distinct accounts found in topic `topic_index` of `event_signature` logs emitted by `market`
+pub(crate) async fn accounts_from_logs<P>(
+    provider: &P,
+    market: Address,
+    event_signature: alloy_primitives::B256,
+    topic_index: usize,
+    from_block: u64,
+    to_block: u64,
+) -> Result<Vec<Address>>
+where
+    P: Provider,
+{
+    let filter = Filter::new()
+        .address(market)
+        .event_signature(event_signature)
+        .from_block(from_block)
+        .to_block(to_block);
+    let logs = provider.get_logs(&filter).await?;
+
+    let accounts: BTreeSet<Address> = logs
+        .iter()
+        .filter_map(|log| log.topics().get(topic_index))
+        .map(|topic| Address::from_word(*topic))
+        .collect();
+    Ok(accounts.into_iter().collect())
+}
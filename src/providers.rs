@@ -0,0 +1,632 @@
+use alloy_json_rpc::{RequestPacket, ResponsePacket, ResponsePayload};
+use alloy_network::EthereumWallet;
+use alloy_provider::{Provider, ProviderBuilder, RootProvider};
+use alloy_transport::{RpcError, TransportError, TransportErrorKind, TransportFut};
+use alloy_transport_http::{Client, Http};
+use eyre::Result;
+use parking_lot::Mutex;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tower::Service;
+use tracing::{info, warn};
+
+use crate::monitoring::metrics;
+use crate::signer::BotSigner;
+
+/// Consecutive transport failures before an endpoint is taken out of rotation
+const MAX_CONSECUTIVE_FAILURES: u32 = 3;
+
+/// How long a rate-limited (HTTP 429) endpoint is skipped
+const RATE_LIMIT_COOLDOWN: Duration = Duration::from_secs(30);
+
+/// Health check requests slower than this count as failures
+const HEALTH_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Endpoints this many blocks behind the best one are treated as unhealthy
+const MAX_BLOCK_LAG: u64 = 10;
+
+/// JSON-RPC error codes providers use for rate limiting
+const RATE_LIMIT_ERROR_CODES: &[i64] = &[429, -32005];
+
+/// Methods that must not be spread across endpoints; they go to the first available one
+const WRITE_METHODS: &[&str] = &["eth_sendRawTransaction", "eth_sendTransaction"];
+
+/// Methods that follow the chain head or live on one node (filters). They all go to the pinned
+/// endpoint, so a block number read from one node is never used to query logs from another
+/// that hasn't reached it yet; the pin only moves when that endpoint fails.
+const PINNED_METHODS: &[&str] = &[
+    "eth_blockNumber",
+    "eth_getBlockByNumber",
+    "eth_getBlockByHash",
+    "eth_getLogs",
+    "eth_newFilter",
+    "eth_newBlockFilter",
+    "eth_getFilterChanges",
+    "eth_getFilterLogs",
+    "eth_uninstallFilter",
+    "eth_getTransactionCount",
+    "eth_getTransactionReceipt",
+];
+
+/// How a request is routed over the endpoints
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Route {
+    /// Round-robin over the healthy endpoints
+    Spread,
+    /// The pinned endpoint first
+    Pinned,
+    /// Endpoints in configured order
+    Write,
+}
+
+impl Route {
+    fn of(request: &RequestPacket) -> Self {
+        let methods: Vec<&str> = match request {
+            RequestPacket::Single(req) => vec![req.method()],
+            RequestPacket::Batch(reqs) => reqs.iter().map(|req| req.method()).collect(),
+        };
+        if methods.iter().any(|method| WRITE_METHODS.contains(method)) {
+            Route::Write
+        } else if methods.iter().any(|method| PINNED_METHODS.contains(method)) {
+            Route::Pinned
+        } else {
+            Route::Spread
+        }
+    }
+}
+
+/// A rate-limited endpoint's request rate is halved on every 429, down to this fraction of
+/// its configured rate...
+const MIN_RATE_FRACTION: f64 = 0.1;
+
+/// ...and climbs back by this fraction of the configured rate per interval without one
+const RATE_RECOVERY_STEP: f64 = 0.1;
+const RATE_RECOVERY_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Token bucket pacing the requests sent to one endpoint. Requests reserve tokens ahead of
+/// time, so a burst queues up behind the bucket instead of reaching the provider at once.
+#[derive(Debug)]
+struct TokenBucket {
+    configured_rps: f64, // 0 = unlimited
+    rate: f64,           // Current rate; below the configured one after 429s
+    tokens: f64,         // Negative while requests wait for future tokens
+    refilled_at: Instant,
+    rate_changed_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(rps: u32, now: Instant) -> Self {
+        let rps = rps as f64;
+        Self {
+            configured_rps: rps,
+            rate: rps,
+            tokens: rps,
+            refilled_at: now,
+            rate_changed_at: now,
+        }
+    }
+
+    fn is_limited(&self) -> bool {
+        self.configured_rps > 0.0
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.refilled_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(self.rate.max(1.0));
+        self.refilled_at = now;
+
+        if self.rate < self.configured_rps
+            && now.saturating_duration_since(self.rate_changed_at) >= RATE_RECOVERY_INTERVAL
+        {
+            self.rate = (self.rate + self.configured_rps * RATE_RECOVERY_STEP).min(self.configured_rps);
+            self.rate_changed_at = now;
+        }
+    }
+
+    /// Take a token, returning how long the request has to wait for it
+    fn reserve(&mut self, now: Instant) -> Duration {
+        if !self.is_limited() {
+            return Duration::ZERO;
+        }
+        self.refill(now);
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / self.rate)
+        }
+    }
+
+    /// Slow down after the endpoint rate limited us, returning the new rate
+    fn back_off(&mut self, now: Instant) -> Option<f64> {
+        if !self.is_limited() {
+            return None;
+        }
+        self.refill(now);
+        self.rate = (self.rate / 2.0).max(self.configured_rps * MIN_RATE_FRACTION);
+        self.tokens = self.tokens.min(0.0);
+        self.rate_changed_at = now;
+        Some(self.rate)
+    }
+}
+
+#[derive(Debug, Default)]
+struct EndpointHealth {
+    consecutive_failures: u32,
+    rate_limited_until: Option<Instant>,
+    last_block: Option<u64>,
+}
+
+impl EndpointHealth {
+    fn is_available(&self, now: Instant) -> bool {
+        self.consecutive_failures < MAX_CONSECUTIVE_FAILURES
+            && self.rate_limited_until.map_or(true, |until| now >= until)
+    }
+}
+
+struct Endpoint {
+    url: String,
+    host: String, // Metric label; the full URL may carry an API key
+    transport: Http<Client>,
+    /// Direct provider used by health checks, bypassing the pool
+    probe: RootProvider<Http<Client>>,
+    health: Mutex<EndpointHealth>,
+    bucket: Mutex<TokenBucket>,
+}
+
+/// Health of one endpoint, as reported to operators
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EndpointStatus {
+    pub url: String,
+    pub available: bool,
+    pub consecutive_failures: u32,
+    pub rate_limited: bool,
+    pub last_block: Option<u64>,
+    pub rate_limit_rps: Option<f64>, // Current request budget; None = unlimited
+}
+
+/// A set of HTTP RPC endpoints behind a single transport. Read calls are spread round-robin
+/// over the healthy endpoints, except block, log, filter, nonce and receipt queries, which
+/// stay on one pinned endpoint; a request that fails at the transport level or is rate
+/// limited is retried on the next endpoint, so one flaky provider doesn't take the bot down.
+/// Transactions always go to the first available endpoint in configured order.
+#[derive(Clone)]
+pub struct ProviderPool {
+    endpoints: Arc<Vec<Endpoint>>,
+    next: Arc<AtomicUsize>,
+    pinned: Arc<AtomicUsize>,
+}
+
+impl ProviderPool {
+    /// Pool over `urls`, the first being the preferred endpoint
+    pub fn new(urls: &[String]) -> Result<Self> {
+        if urls.is_empty() {
+            return Err(eyre::eyre!("Provider pool needs at least one RPC endpoint"));
+        }
+
+        let endpoints = urls
+            .iter()
+            .map(|url| {
+                let parsed: url::Url = url.parse()?;
+                Ok(Endpoint {
+                    url: url.clone(),
+                    host: parsed.host_str().unwrap_or("unknown").to_string(),
+                    transport: Http::new(parsed.clone()),
+                    probe: ProviderBuilder::new().on_http(parsed),
+                    health: Mutex::new(EndpointHealth::default()),
+                    bucket: Mutex::new(TokenBucket::new(0, Instant::now())),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            endpoints: Arc::new(endpoints),
+            next: Arc::new(AtomicUsize::new(0)),
+            pinned: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// Pace each endpoint to `default_rps` requests per second (0 = unlimited), or to its own
+    /// rate when `endpoint_rps` has one for its URL
+    pub fn with_rate_limits(self, default_rps: u32, endpoint_rps: &BTreeMap<String, u32>) -> Self {
+        let now = Instant::now();
+        for endpoint in self.endpoints.iter() {
+            let rps = endpoint_rps.get(&endpoint.url).copied().unwrap_or(default_rps);
+            if rps > 0 {
+                info!("🚦 Limiting RPC endpoint {} to {} requests/s", endpoint.host, rps);
+            }
+            *endpoint.bucket.lock() = TokenBucket::new(rps, now);
+        }
+        self
+    }
+
+    /// Provider that sends every request through the pool
+    pub fn provider(&self) -> RootProvider<alloy_transport::BoxTransport> {
+        ProviderBuilder::new()
+            .on_client(alloy_rpc_client::RpcClient::new(self.clone(), false))
+            .boxed()
+    }
+
+    pub fn len(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.endpoints.is_empty()
+    }
+
+    /// Current health of every endpoint, in configured order
+    pub fn status(&self) -> Vec<EndpointStatus> {
+        let now = Instant::now();
+        self.endpoints
+            .iter()
+            .map(|endpoint| {
+                let health = endpoint.health.lock();
+                EndpointStatus {
+                    url: endpoint.url.clone(),
+                    available: health.is_available(now),
+                    consecutive_failures: health.consecutive_failures,
+                    rate_limited: health.rate_limited_until.map_or(false, |until| now < until),
+                    last_block: health.last_block,
+                    rate_limit_rps: {
+                        let bucket = endpoint.bucket.lock();
+                        bucket.is_limited().then_some(bucket.rate)
+                    },
+                }
+            })
+            .collect()
+    }
+
+    /// Order in which endpoints are tried: available ones first (round-robin for spread
+    /// reads, the pinned endpoint first for head-following reads, configured order for
+    /// writes), then the rest as a last resort
+    fn attempt_order(&self, route: Route) -> Vec<usize> {
+        let count = self.endpoints.len();
+        let start = match route {
+            Route::Spread => self.next.fetch_add(1, Ordering::Relaxed) % count,
+            Route::Pinned => self.pinned.load(Ordering::Relaxed) % count,
+            Route::Write => 0,
+        };
+
+        let now = Instant::now();
+        let (mut available, unavailable): (Vec<usize>, Vec<usize>) = (0..count)
+            .map(|offset| (start + offset) % count)
+            .partition(|&index| self.endpoints[index].health.lock().is_available(now));
+        available.extend(unavailable);
+        available
+    }
+
+    fn record_success(&self, index: usize) {
+        let mut health = self.endpoints[index].health.lock();
+        if health.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+            info!("✅ RPC endpoint {} recovered", self.endpoints[index].url);
+        }
+        health.consecutive_failures = 0;
+    }
+
+    /// Move head-following reads to `index` after the pinned endpoint failed them
+    fn repin(&self, index: usize) {
+        let previous = self.pinned.swap(index, Ordering::Relaxed);
+        if previous != index {
+            info!(
+                "📌 Pinning block and log queries to RPC endpoint {}",
+                self.endpoints[index].host
+            );
+        }
+    }
+
+    fn record_failure(&self, index: usize, rate_limited: bool) {
+        let endpoint = &self.endpoints[index];
+        let mut health = endpoint.health.lock();
+        if rate_limited {
+            let now = Instant::now();
+            health.rate_limited_until = Some(now + RATE_LIMIT_COOLDOWN);
+            warn!(
+                "🚦 RPC endpoint {} is rate limiting - skipping it for {}s",
+                endpoint.url,
+                RATE_LIMIT_COOLDOWN.as_secs()
+            );
+            if let Some(rate) = endpoint.bucket.lock().back_off(now) {
+                warn!(
+                    "🚦 Slowing RPC endpoint {} down to {:.1} requests/s",
+                    endpoint.host, rate
+                );
+            }
+        } else {
+            health.consecutive_failures += 1;
+            if health.consecutive_failures == MAX_CONSECUTIVE_FAILURES {
+                warn!("🔌 RPC endpoint {} marked unhealthy", endpoint.url);
+            }
+        }
+        metrics::record_rpc_error("provider_pool");
+    }
+
+    async fn dispatch(self, request: RequestPacket) -> Result<ResponsePacket, TransportError> {
+        let route = Route::of(&request);
+
+        let mut last_error = None;
+        for index in self.attempt_order(route) {
+            let endpoint = &self.endpoints[index];
+            let wait = endpoint.bucket.lock().reserve(Instant::now());
+            if !wait.is_zero() {
+                metrics::record_rpc_throttled(&endpoint.host);
+                tokio::time::sleep(wait).await;
+            }
+
+            let mut transport = endpoint.transport.clone();
+            match transport.call(request.clone()).await {
+                Ok(response) if is_rate_limit_response(&response) => {
+                    self.record_failure(index, true);
+                    last_error = Some(TransportErrorKind::custom_str("rate limited"));
+                }
+                Ok(response) => {
+                    self.record_success(index);
+                    if route == Route::Pinned {
+                        self.repin(index);
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    self.record_failure(index, is_rate_limit_error(&e));
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        Err(last_error.unwrap_or_else(|| TransportErrorKind::custom_str("no RPC endpoints")))
+    }
+
+    /// Probe every endpoint's block number. Unreachable endpoints and ones lagging the best
+    /// block by more than `MAX_BLOCK_LAG` are taken out of rotation; responsive ones are put back.
+    pub async fn check_health(&self) {
+        let mut blocks = Vec::with_capacity(self.endpoints.len());
+        for endpoint in self.endpoints.iter() {
+            let block = match tokio::time::timeout(HEALTH_CHECK_TIMEOUT, endpoint.probe.get_block_number()).await {
+                Ok(Ok(block)) => Some(block),
+                Ok(Err(e)) => {
+                    warn!("RPC endpoint {} failed health check: {}", endpoint.url, e);
+                    None
+                }
+                Err(_) => {
+                    warn!("RPC endpoint {} timed out during health check", endpoint.url);
+                    None
+                }
+            };
+            blocks.push(block);
+        }
+
+        let best_block = blocks.iter().flatten().max().copied();
+        for (endpoint, block) in self.endpoints.iter().zip(blocks) {
+            let mut health = endpoint.health.lock();
+            match (block, best_block) {
+                (Some(block), Some(best)) if best - block > MAX_BLOCK_LAG => {
+                    warn!(
+                        "🐢 RPC endpoint {} is {} blocks behind - taking it out of rotation",
+                        endpoint.url,
+                        best - block
+                    );
+                    health.consecutive_failures = MAX_CONSECUTIVE_FAILURES;
+                    health.last_block = Some(block);
+                }
+                (Some(block), _) => {
+                    if health.consecutive_failures >= MAX_CONSECUTIVE_FAILURES {
+                        info!("✅ RPC endpoint {} back in rotation at block {}", endpoint.url, block);
+                    }
+                    health.consecutive_failures = 0;
+                    health.last_block = Some(block);
+                }
+                (None, _) => {
+                    health.consecutive_failures = health.consecutive_failures.max(MAX_CONSECUTIVE_FAILURES);
+                }
+            }
+        }
+    }
+
+    /// Health-check the endpoints every `interval_secs` until the process exits
+    pub async fn run_health_checks(self, interval_secs: u64) -> Result<()> {
+        let mut interval = tokio::time::interval(Duration::from_secs(interval_secs.max(1)));
+        loop {
+            interval.tick().await;
+            self.check_health().await;
+        }
+    }
+}
+
+impl Service<RequestPacket> for ProviderPool {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        Box::pin(self.clone().dispatch(request))
+    }
+}
+
+/// Signing provider for `signer` that sends through `provider`'s transport, so transactions
+/// and their receipts get the same failover as reads when `provider` is backed by the pool
+pub fn signer_provider<P>(provider: &P, signer: &BotSigner) -> impl Provider
+where
+    P: Provider,
+{
+    ProviderBuilder::new()
+        .with_recommended_fillers()
+        .wallet(EthereumWallet::from(signer.clone()))
+        .on_provider(provider.root().clone())
+}
+
+/// Whether a transport error is the endpoint refusing us for sending too many requests
+fn is_rate_limit_error(error: &TransportError) -> bool {
+    matches!(error, RpcError::Transport(TransportErrorKind::HttpError(e)) if e.status == 429)
+}
+
+/// Whether a JSON-RPC response is a rate-limit error rather than an answer
+fn is_rate_limit_response(response: &ResponsePacket) -> bool {
+    let is_rate_limited = |payload: &ResponsePayload| {
+        matches!(payload, ResponsePayload::Failure(e) if RATE_LIMIT_ERROR_CODES.contains(&e.code))
+    };
+    match response {
+        ResponsePacket::Single(response) => is_rate_limited(&response.payload),
+        ResponsePacket::Batch(responses) => responses.iter().any(|r| is_rate_limited(&r.payload)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+
+    /// JSON-RPC server answering `eth_blockNumber` with `block`, or failing with `status`
+    async fn spawn_rpc(block: u64, status: &'static str) -> String {
+        let server = MockServer::spawn(move |request| {
+            let body = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request.json()["id"],
+                "result": format!("0x{:x}", block),
+            });
+            MockResponse::json(&body).with_status(status)
+        })
+        .await;
+        server.url
+    }
+
+    fn pool(urls: &[&str]) -> ProviderPool {
+        ProviderPool::new(&urls.iter().map(|url| url.to_string()).collect::<Vec<_>>()).unwrap()
+    }
+
+    #[test]
+    fn test_reads_rotate_and_writes_prefer_primary() {
+        let pool = pool(&["http://127.0.0.1:1", "http://127.0.0.1:2", "http://127.0.0.1:3"]);
+
+        assert_eq!(pool.attempt_order(Route::Spread), vec![0, 1, 2]);
+        assert_eq!(pool.attempt_order(Route::Spread), vec![1, 2, 0]);
+        assert_eq!(pool.attempt_order(Route::Write), vec![0, 1, 2]);
+    }
+
+    #[test]
+    fn test_head_reads_stay_on_the_pinned_endpoint() {
+        let pool = pool(&["http://127.0.0.1:1", "http://127.0.0.1:2", "http://127.0.0.1:3"]);
+        let request = |method: &'static str| {
+            RequestPacket::Single(
+                alloy_json_rpc::Request::new(method, alloy_json_rpc::Id::Number(1), ())
+                    .serialize()
+                    .unwrap(),
+            )
+        };
+        assert_eq!(Route::of(&request("eth_getLogs")), Route::Pinned);
+        assert_eq!(Route::of(&request("eth_call")), Route::Spread);
+        assert_eq!(Route::of(&request("eth_sendRawTransaction")), Route::Write);
+
+        // Spread reads don't move the pin
+        pool.attempt_order(Route::Spread);
+        assert_eq!(pool.attempt_order(Route::Pinned), vec![0, 1, 2]);
+        assert_eq!(pool.attempt_order(Route::Pinned), vec![0, 1, 2]);
+
+        pool.repin(2);
+        assert_eq!(pool.attempt_order(Route::Pinned), vec![2, 0, 1]);
+    }
+
+    #[tokio::test]
+    async fn test_pin_follows_failover() {
+        let healthy = spawn_rpc(42, "200 OK").await;
+        let pool = pool(&["http://127.0.0.1:1", &healthy]);
+        let provider = pool.provider();
+
+        assert_eq!(provider.get_block_number().await.unwrap(), 42);
+        // The next head read goes straight to the endpoint that answered
+        assert_eq!(pool.attempt_order(Route::Pinned)[0], 1);
+    }
+
+    #[test]
+    fn test_failing_endpoints_are_tried_last() {
+        let pool = pool(&["http://127.0.0.1:1", "http://127.0.0.1:2", "http://127.0.0.1:3"]);
+        for _ in 0..MAX_CONSECUTIVE_FAILURES {
+            pool.record_failure(0, false);
+        }
+        pool.record_failure(1, true);
+
+        assert_eq!(pool.attempt_order(Route::Write), vec![2, 0, 1]);
+        let status = pool.status();
+        assert!(!status[0].available);
+        assert!(status[1].rate_limited);
+        assert!(status[2].available);
+
+        pool.record_success(0);
+        assert_eq!(pool.attempt_order(Route::Write), vec![0, 2, 1]);
+    }
+
+    #[test]
+    fn test_token_bucket_paces_bursts_and_backs_off_on_rate_limits() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(10, start);
+
+        // A second's worth of requests goes straight out, the rest wait their turn
+        for _ in 0..10 {
+            assert_eq!(bucket.reserve(start), Duration::ZERO);
+        }
+        assert_eq!(bucket.reserve(start), Duration::from_millis(100));
+        assert_eq!(bucket.reserve(start), Duration::from_millis(200));
+
+        // A 429 halves the rate, which recovers step by step once they stop
+        assert_eq!(bucket.back_off(start), Some(5.0));
+        assert_eq!(bucket.back_off(start), Some(2.5));
+        assert_eq!(bucket.back_off(start), Some(1.25));
+        assert_eq!(bucket.back_off(start), Some(1.0));
+        bucket.refill(start + RATE_RECOVERY_INTERVAL);
+        assert_eq!(bucket.rate, 2.0);
+
+        let mut unlimited = TokenBucket::new(0, start);
+        assert_eq!(unlimited.reserve(start), Duration::ZERO);
+        assert_eq!(unlimited.back_off(start), None);
+    }
+
+    #[test]
+    fn test_endpoint_rate_limits_override_the_default() {
+        let limited = pool(&["http://127.0.0.1:1", "http://127.0.0.1:2"]).with_rate_limits(
+            20,
+            &BTreeMap::from([("http://127.0.0.1:2".to_string(), 5)]),
+        );
+
+        let status = limited.status();
+        assert_eq!(status[0].rate_limit_rps, Some(20.0));
+        assert_eq!(status[1].rate_limit_rps, Some(5.0));
+        assert_eq!(pool(&["http://127.0.0.1:1"]).status()[0].rate_limit_rps, None);
+    }
+
+    #[test]
+    fn test_empty_pool_is_rejected() {
+        assert!(ProviderPool::new(&[]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fails_over_from_rate_limited_and_down_endpoints() {
+        let rate_limited = spawn_rpc(1, "429 Too Many Requests").await;
+        let healthy = spawn_rpc(42, "200 OK").await;
+        let pool = pool(&[&rate_limited, "http://127.0.0.1:1", &healthy]);
+        let provider = pool.provider();
+
+        assert_eq!(provider.get_block_number().await.unwrap(), 42);
+        assert!(pool.status()[0].rate_limited);
+        assert_eq!(pool.status()[1].consecutive_failures, 1);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_removes_lagging_endpoints() {
+        let behind = spawn_rpc(100, "200 OK").await;
+        let head = spawn_rpc(100 + MAX_BLOCK_LAG + 1, "200 OK").await;
+        let pool = pool(&[&behind, &head]);
+
+        pool.check_health().await;
+
+        let status = pool.status();
+        assert!(!status[0].available);
+        assert_eq!(status[0].last_block, Some(100));
+        assert!(status[1].available);
+    }
+}
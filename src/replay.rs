@@ -0,0 +1,359 @@
+use alloy_primitives::{Address, U256};
+use alloy_rpc_types::Log;
+use eyre::Result;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use tracing::{debug, info, warn};
+
+use crate::database::{self, DatabasePool};
+use crate::events::BotEvent;
+use crate::models::UserPosition;
+use crate::monitoring::scanner::{self, PersistedPositions};
+use crate::monitoring::websocket::extract_log_users;
+
+/// One line of an event recording: a raw pool log, a position read from chain, or an event
+/// seen by the processor
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecordedEntry {
+    Log(Log),
+    /// A user's position as the processor read it, so replays never read the chain
+    PositionRead { position: UserPosition },
+    UserPositionChanged { user: Address },
+    PriceUpdate { asset: Address, old_price: U256, new_price: U256 },
+    LiquidationOpportunity { user: Address },
+    OraclePriceChanged { asset: Address, new_price: U256 },
+    ExternalLiquidationSignal { user: Address },
+}
+
+impl RecordedEntry {
+    /// Recorded form of a processor event. Database syncs are internal bookkeeping and
+    /// aren't recorded; full rescans are recorded as the per-user events they fan out into,
+    /// and reserve updates are already part of the recorded pool logs.
+    pub fn from_event(event: &BotEvent) -> Option<Self> {
+        match event {
+            BotEvent::UserPositionChanged(user) => Some(Self::UserPositionChanged { user: *user }),
+            BotEvent::PriceUpdate(asset, old_price, new_price) => Some(Self::PriceUpdate {
+                asset: *asset,
+                old_price: *old_price,
+                new_price: *new_price,
+            }),
+            BotEvent::LiquidationOpportunity(user) => {
+                Some(Self::LiquidationOpportunity { user: *user })
+            }
+            BotEvent::OraclePriceChanged(asset, new_price) => Some(Self::OraclePriceChanged {
+                asset: *asset,
+                new_price: *new_price,
+            }),
+            BotEvent::ExternalLiquidationSignal(user) => {
+                Some(Self::ExternalLiquidationSignal { user: *user })
+            }
+            BotEvent::DatabaseSync(_)
+            | BotEvent::FullRescan
+            | BotEvent::ReserveDataUpdated(_) => None,
+        }
+    }
+
+    /// The processor event this entry was recorded from (`None` for logs and position reads)
+    pub fn into_event(self) -> Option<BotEvent> {
+        match self {
+            Self::Log(_) | Self::PositionRead { .. } => None,
+            Self::UserPositionChanged { user } => Some(BotEvent::UserPositionChanged(user)),
+            Self::PriceUpdate {
+                asset,
+                old_price,
+                new_price,
+            } => Some(BotEvent::PriceUpdate(asset, old_price, new_price)),
+            Self::LiquidationOpportunity { user } => Some(BotEvent::LiquidationOpportunity(user)),
+            Self::OraclePriceChanged { asset, new_price } => {
+                Some(BotEvent::OraclePriceChanged(asset, new_price))
+            }
+            Self::ExternalLiquidationSignal { user } => {
+                Some(BotEvent::ExternalLiquidationSignal(user))
+            }
+        }
+    }
+}
+
+/// Appends recorded entries to a JSON-lines file
+pub struct EventRecorder {
+    writer: Mutex<BufWriter<File>>,
+}
+
+impl EventRecorder {
+    /// Open `path` for appending, creating it if needed
+    pub fn create(path: &Path) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            writer: Mutex::new(BufWriter::new(file)),
+        })
+    }
+
+    /// Append an entry. Each line is flushed immediately so a crash loses at most one entry.
+    pub fn record(&self, entry: &RecordedEntry) {
+        let line = match serde_json::to_string(entry) {
+            Ok(line) => line,
+            Err(e) => {
+                warn!("Failed to serialize recorded entry: {}", e);
+                return;
+            }
+        };
+
+        let mut writer = self.writer.lock();
+        if let Err(e) = writeln!(writer, "{}", line).and_then(|_| writer.flush()) {
+            warn!("Failed to write event recording: {}", e);
+        }
+    }
+
+    pub fn record_event(&self, event: &BotEvent) {
+        if let Some(entry) = RecordedEntry::from_event(event) {
+            self.record(&entry);
+        }
+    }
+}
+
+/// Read every entry of a recording, in order
+pub fn read_recording(path: &Path) -> Result<Vec<RecordedEntry>> {
+    let reader = BufReader::new(File::open(path)?);
+    let mut entries = Vec::new();
+
+    for (index, line) in reader.lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let entry = serde_json::from_str(&line)
+            .map_err(|e| eyre::eyre!("Invalid recording entry on line {}: {}", index + 1, e))?;
+        entries.push(entry);
+    }
+
+    Ok(entries)
+}
+
+/// What replaying one recorded entry decided
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReplayDecision {
+    /// A recorded read updated the user's position, and whether it was worth persisting
+    PositionUpdated {
+        user: Address,
+        health_factor: U256,
+        at_risk: bool,
+        persisted: bool,
+    },
+    /// A read left the position liquidatable
+    Liquidatable { user: Address },
+    /// The recorded run raised a liquidation opportunity; `reproduced` when the replayed
+    /// reads agree the position was liquidatable at that point
+    RecordedOpportunity { user: Address, reproduced: bool },
+}
+
+/// Reproduces the decision path of a recording from its recorded inputs alone. Position
+/// reads are applied from the recording rather than fetched, positions are persisted only
+/// to the database it was given (see database::init_in_memory_database), and nothing is
+/// executed or sent anywhere, so the same recording always yields the same decisions.
+///
+/// The processor recorded the events each log produced, so logs are only decoded and
+/// reported to show which log led to which events.
+pub struct Replayer {
+    db_pool: DatabasePool,
+    min_hf_change_to_persist_bps: u64,
+    positions: HashMap<Address, UserPosition>,
+    persisted_positions: PersistedPositions,
+}
+
+impl Replayer {
+    pub fn new(db_pool: DatabasePool, min_hf_change_to_persist_bps: u64) -> Self {
+        Self {
+            db_pool,
+            min_hf_change_to_persist_bps,
+            positions: HashMap::new(),
+            persisted_positions: PersistedPositions::default(),
+        }
+    }
+
+    /// Replay `entries` in order, returning every decision they led to
+    pub async fn replay(&mut self, entries: Vec<RecordedEntry>) -> Result<Vec<ReplayDecision>> {
+        let mut decisions = Vec::new();
+        for entry in entries {
+            match entry {
+                RecordedEntry::Log(log) => info!(
+                    "📼 Replayed log (block {:?}, tx {:?}) touching users {:?}",
+                    log.block_number,
+                    log.transaction_hash,
+                    extract_log_users(&log)
+                ),
+                RecordedEntry::PositionRead { position } => {
+                    self.apply_read(position, &mut decisions).await?
+                }
+                RecordedEntry::LiquidationOpportunity { user } => {
+                    let reproduced =
+                        self.positions.get(&user).is_some_and(scanner::is_liquidatable);
+                    if !reproduced {
+                        warn!(
+                            "📼 Recorded opportunity for {:?} not reproduced by its reads",
+                            user
+                        );
+                    }
+                    decisions.push(ReplayDecision::RecordedOpportunity { user, reproduced });
+                }
+                // Triggers only: the reads they caused were recorded as entries of their own
+                other => debug!("📼 Replayed {:?}", other),
+            }
+        }
+        Ok(decisions)
+    }
+
+    async fn apply_read(
+        &mut self,
+        position: UserPosition,
+        decisions: &mut Vec<ReplayDecision>,
+    ) -> Result<()> {
+        let user = position.address;
+        let persisted = self
+            .persisted_positions
+            .should_persist(&position, self.min_hf_change_to_persist_bps);
+        if persisted {
+            database::save_user_position(&self.db_pool, &position).await?;
+            self.persisted_positions.record(&position);
+        }
+        decisions.push(ReplayDecision::PositionUpdated {
+            user,
+            health_factor: position.health_factor,
+            at_risk: position.is_at_risk,
+            persisted,
+        });
+        if scanner::is_liquidatable(&position) {
+            decisions.push(ReplayDecision::Liquidatable { user });
+        }
+        self.positions.insert(user, position);
+        Ok(())
+    }
+}
+
+/// Replay the recording at `path` against a fresh in-memory database
+pub async fn replay_file(
+    path: &Path,
+    chain_id: u64,
+    min_hf_change_to_persist_bps: u64,
+) -> Result<Vec<ReplayDecision>> {
+    let entries = read_recording(path)?;
+    info!("📼 Replaying {} recorded entries from {}", entries.len(), path.display());
+    let db_pool = database::init_in_memory_database(chain_id).await?;
+    Replayer::new(db_pool, min_hf_change_to_persist_bps)
+        .replay(entries)
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tests::create_test_pool;
+    use alloy_primitives::{Bytes, B256};
+
+    fn user_log(pool: Address, user: Address) -> Log {
+        Log {
+            inner: alloy_primitives::Log::new_unchecked(
+                pool,
+                vec![B256::repeat_byte(0x11), user.into_word()],
+                Bytes::new(),
+            ),
+            block_number: Some(42),
+            ..Default::default()
+        }
+    }
+
+    fn position(user: Address, health_factor: u64, debt: u64) -> UserPosition {
+        UserPosition {
+            address: user,
+            total_collateral_base: U256::from(10_000u64),
+            total_debt_base: U256::from(debt),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_500u64),
+            ltv: U256::from(8_000u64),
+            health_factor: U256::from(health_factor),
+            last_updated: chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap(),
+            is_at_risk: health_factor < 1_100_000_000_000_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_recorded_stream_round_trips_through_replay() {
+        let pool = Address::repeat_byte(0xaa);
+        let (alice, bob) = (Address::repeat_byte(0x01), Address::repeat_byte(0x02));
+        let asset = Address::repeat_byte(0x03);
+        let log = user_log(pool, alice);
+        let underwater = position(alice, 900_000_000_000_000_000, 5_000);
+        let healthy = position(bob, 2_000_000_000_000_000_000, 1_000);
+
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", uuid::Uuid::new_v4()));
+        let recorder = EventRecorder::create(&path).unwrap();
+        recorder.record(&RecordedEntry::Log(log.clone()));
+        recorder.record_event(&BotEvent::UserPositionChanged(alice));
+        recorder.record(&RecordedEntry::PositionRead { position: underwater.clone() });
+        recorder.record_event(&BotEvent::OraclePriceChanged(asset, U256::from(2_000u64)));
+        recorder.record_event(&BotEvent::DatabaseSync(Vec::new()));
+        recorder.record_event(&BotEvent::LiquidationOpportunity(alice));
+        recorder.record(&RecordedEntry::PositionRead { position: healthy.clone() });
+        recorder.record_event(&BotEvent::LiquidationOpportunity(bob));
+        drop(recorder);
+
+        let entries = read_recording(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        // The database sync isn't recorded
+        assert_eq!(entries.len(), 7);
+        assert_eq!(entries[0], RecordedEntry::Log(log.clone()));
+        assert_eq!(entries[2], RecordedEntry::PositionRead { position: underwater });
+        assert!(extract_log_users(&log).contains(&alice));
+
+        let expected = vec![
+            ReplayDecision::PositionUpdated {
+                user: alice,
+                health_factor: U256::from(900_000_000_000_000_000u64),
+                at_risk: true,
+                persisted: true,
+            },
+            ReplayDecision::Liquidatable { user: alice },
+            ReplayDecision::RecordedOpportunity {
+                user: alice,
+                reproduced: true,
+            },
+            ReplayDecision::PositionUpdated {
+                user: bob,
+                health_factor: U256::from(2_000_000_000_000_000_000u64),
+                at_risk: false,
+                persisted: true,
+            },
+            ReplayDecision::RecordedOpportunity {
+                user: bob,
+                reproduced: false,
+            },
+        ];
+
+        // Replays are deterministic and only write to the database they are given
+        for _ in 0..2 {
+            let db_pool = create_test_pool().await;
+            let decisions = Replayer::new(db_pool.clone(), 100)
+                .replay(entries.clone())
+                .await
+                .unwrap();
+            assert_eq!(decisions, expected);
+            assert_eq!(database::get_all_user_positions(&db_pool).await.unwrap().len(), 2);
+        }
+    }
+
+    #[test]
+    fn test_read_recording_reports_bad_line() {
+        let path = std::env::temp_dir().join(format!("replay-{}.jsonl", uuid::Uuid::new_v4()));
+        std::fs::write(&path, "\n{\"not_an_entry\":1}\n").unwrap();
+
+        let err = read_recording(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(err.to_string().contains("line 2"));
+    }
+}
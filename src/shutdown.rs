@@ -0,0 +1,175 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+use tracing::info;
+
+/// Coordinates a clean stop: long-running loops watch the cancellation token, and
+/// liquidations started before the stop (plus tasks that clean up after it) hold guards
+/// that shutdown waits on
+#[derive(Clone, Default)]
+pub struct ShutdownCoordinator {
+    token: CancellationToken,
+    liquidations: Arc<InFlight>,
+    tasks: Arc<InFlight>,
+}
+
+/// Count of guards held, with a wakeup when it drops to zero
+#[derive(Default)]
+struct InFlight {
+    count: AtomicUsize,
+    idle: Notify,
+}
+
+impl InFlight {
+    fn enter(self: &Arc<Self>) -> InFlightGuard {
+        self.count.fetch_add(1, Ordering::AcqRel);
+        InFlightGuard {
+            in_flight: self.clone(),
+        }
+    }
+
+    fn count(&self) -> usize {
+        self.count.load(Ordering::Acquire)
+    }
+
+    async fn wait_idle(&self, timeout: Duration) -> bool {
+        tokio::time::timeout(timeout, async {
+            loop {
+                let idle = self.idle.notified();
+                tokio::pin!(idle);
+                // Register before checking so a guard dropped in between still wakes us
+                idle.as_mut().enable();
+                if self.count() == 0 {
+                    return;
+                }
+                idle.await;
+            }
+        })
+        .await
+        .is_ok()
+    }
+}
+
+/// Held while a liquidation (or tracked task) runs; shutdown waits until every guard is dropped
+pub struct InFlightGuard {
+    in_flight: Arc<InFlight>,
+}
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        if self.in_flight.count.fetch_sub(1, Ordering::AcqRel) == 1 {
+            self.in_flight.idle.notify_waiters();
+        }
+    }
+}
+
+impl ShutdownCoordinator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Token cancelled when shutdown begins, for loops that should stop on their own
+    pub fn token(&self) -> CancellationToken {
+        self.token.clone()
+    }
+
+    /// Begin shutting down: no new liquidations start and watching loops wind down
+    pub fn trigger(&self) {
+        if !self.token.is_cancelled() {
+            info!("🛑 Shutdown requested - no new liquidations will be started");
+        }
+        self.token.cancel();
+    }
+
+    pub fn is_shutting_down(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Resolves once shutdown has been triggered
+    pub async fn cancelled(&self) {
+        self.token.cancelled().await
+    }
+
+    /// Register a liquidation about to start. None once shutdown has begun.
+    pub fn begin_liquidation(&self) -> Option<InFlightGuard> {
+        let guard = self.liquidations.enter();
+        // Checked after registering so a concurrent shutdown either sees this liquidation
+        // or this liquidation sees the shutdown
+        if self.token.is_cancelled() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Register a background task that winds down by itself once the token is cancelled
+    /// (e.g. unsubscribing from the node); shutdown waits for its guard to drop
+    pub fn track_task(&self) -> InFlightGuard {
+        self.tasks.enter()
+    }
+
+    pub fn in_flight_liquidations(&self) -> usize {
+        self.liquidations.count()
+    }
+
+    /// Wait until no liquidation is in flight, for at most `timeout`. Returns whether every
+    /// liquidation finished in time.
+    pub async fn wait_for_in_flight(&self, timeout: Duration) -> bool {
+        self.liquidations.wait_idle(timeout).await
+    }
+
+    /// Wait until every tracked task has wound down, for at most `timeout`
+    pub async fn wait_for_tasks(&self, timeout: Duration) -> bool {
+        self.tasks.wait_idle(timeout).await
+    }
+}
+
+/// Resolves on SIGTERM or Ctrl-C with the name of the signal received
+pub async fn wait_for_signal() -> std::io::Result<&'static str> {
+    #[cfg(unix)]
+    {
+        use tokio::signal::unix::{signal, SignalKind};
+
+        let mut sigterm = signal(SignalKind::terminate())?;
+        tokio::select! {
+            _ = sigterm.recv() => Ok("SIGTERM"),
+            result = tokio::signal::ctrl_c() => result.map(|()| "SIGINT"),
+        }
+    }
+    #[cfg(not(unix))]
+    {
+        tokio::signal::ctrl_c().await.map(|()| "Ctrl-C")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_shutdown_refuses_new_work_and_waits_for_in_flight() {
+        let shutdown = ShutdownCoordinator::new();
+        let guard = shutdown.begin_liquidation().expect("liquidations start before shutdown");
+
+        shutdown.trigger();
+        assert!(shutdown.begin_liquidation().is_none());
+        assert_eq!(shutdown.in_flight_liquidations(), 1);
+        assert!(!shutdown.wait_for_in_flight(Duration::from_millis(50)).await);
+
+        let waiter = {
+            let shutdown = shutdown.clone();
+            tokio::spawn(async move { shutdown.wait_for_in_flight(Duration::from_secs(5)).await })
+        };
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        drop(guard);
+        assert!(waiter.await.unwrap());
+        assert_eq!(shutdown.in_flight_liquidations(), 0);
+
+        // Tracked tasks are waited on separately and may still register during shutdown
+        let task = shutdown.track_task();
+        assert!(!shutdown.wait_for_tasks(Duration::from_millis(20)).await);
+        drop(task);
+        assert!(shutdown.wait_for_tasks(Duration::from_millis(20)).await);
+    }
+}
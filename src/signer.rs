@@ -0,0 +1,260 @@
+use alloy_consensus::SignableTransaction;
+use alloy_network::TxSigner;
+use alloy_primitives::{Address, Signature};
+use alloy_signer::Signer;
+use alloy_signer_local::PrivateKeySigner;
+use async_trait::async_trait;
+use eyre::Result;
+use std::path::Path;
+use tracing::info;
+
+use crate::config::SignerBackend;
+
+#[cfg(feature = "aws-kms")]
+use alloy_signer_aws::AwsSigner;
+#[cfg(feature = "ledger")]
+use alloy_signer_ledger::{HDPath, LedgerSigner};
+
+/// Key the bot signs transactions and relay requests with, whichever backend holds it
+#[derive(Clone)]
+pub enum BotSigner {
+    /// Key held in memory (plaintext PRIVATE_KEY or a decrypted keystore)
+    Local(PrivateKeySigner),
+    #[cfg(feature = "aws-kms")]
+    AwsKms(std::sync::Arc<AwsSigner>),
+    #[cfg(feature = "ledger")]
+    Ledger(std::sync::Arc<LedgerSigner>),
+}
+
+impl std::fmt::Debug for BotSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BotSigner")
+            .field("backend", &self.backend_name())
+            .field("address", &self.address())
+            .finish()
+    }
+}
+
+impl From<PrivateKeySigner> for BotSigner {
+    fn from(signer: PrivateKeySigner) -> Self {
+        BotSigner::Local(signer)
+    }
+}
+
+impl BotSigner {
+    pub fn address(&self) -> Address {
+        match self {
+            BotSigner::Local(signer) => signer.address(),
+            #[cfg(feature = "aws-kms")]
+            BotSigner::AwsKms(signer) => Signer::address(signer.as_ref()),
+            #[cfg(feature = "ledger")]
+            BotSigner::Ledger(signer) => Signer::address(signer.as_ref()),
+        }
+    }
+
+    pub fn backend_name(&self) -> &'static str {
+        match self {
+            BotSigner::Local(_) => "local",
+            #[cfg(feature = "aws-kms")]
+            BotSigner::AwsKms(_) => "aws_kms",
+            #[cfg(feature = "ledger")]
+            BotSigner::Ledger(_) => "ledger",
+        }
+    }
+
+    /// EIP-191 signature over `message`
+    pub async fn sign_message(&self, message: &[u8]) -> Result<Signature> {
+        let signature = match self {
+            BotSigner::Local(signer) => signer.sign_message(message).await?,
+            #[cfg(feature = "aws-kms")]
+            BotSigner::AwsKms(signer) => signer.sign_message(message).await?,
+            #[cfg(feature = "ledger")]
+            BotSigner::Ledger(signer) => signer.sign_message(message).await?,
+        };
+        Ok(signature)
+    }
+}
+
+#[async_trait]
+impl TxSigner<Signature> for BotSigner {
+    fn address(&self) -> Address {
+        BotSigner::address(self)
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy_signer::Result<Signature> {
+        match self {
+            BotSigner::Local(signer) => TxSigner::sign_transaction(signer, tx).await,
+            #[cfg(feature = "aws-kms")]
+            BotSigner::AwsKms(signer) => TxSigner::sign_transaction(signer.as_ref(), tx).await,
+            #[cfg(feature = "ledger")]
+            BotSigner::Ledger(signer) => TxSigner::sign_transaction(signer.as_ref(), tx).await,
+        }
+    }
+}
+
+/// Open every signer configured by `backend`, one per executor wallet. `private_key` (a
+/// comma-separated list) is only read by the private_key backend; remote signers are bound
+/// to `chain_id`.
+pub async fn build_signers(
+    backend: &SignerBackend,
+    private_key: &str,
+    chain_id: u64,
+) -> Result<Vec<BotSigner>> {
+    let mut signers = Vec::new();
+    match backend {
+        SignerBackend::PrivateKey => {
+            for key in private_key
+                .split(',')
+                .map(str::trim)
+                .filter(|key| !key.is_empty())
+            {
+                signers.push(BotSigner::Local(key.parse()?));
+            }
+        }
+        SignerBackend::Keystore { paths, password } => {
+            for path in paths {
+                signers.push(BotSigner::Local(decrypt_keystore(
+                    path,
+                    password.as_deref(),
+                )?));
+            }
+        }
+        SignerBackend::AwsKms { key_ids } => {
+            for key_id in key_ids {
+                signers.push(aws_kms_signer(key_id, chain_id).await?);
+            }
+        }
+        SignerBackend::Ledger { indices } => {
+            for index in indices {
+                signers.push(ledger_signer(*index, chain_id).await?);
+            }
+        }
+    }
+
+    if signers.is_empty() {
+        return Err(eyre::eyre!("No signing key configured"));
+    }
+    for (i, signer) in signers.iter().enumerate() {
+        if signers[..i]
+            .iter()
+            .any(|other| other.address() == signer.address())
+        {
+            return Err(eyre::eyre!(
+                "Wallet {} is configured more than once",
+                signer.address()
+            ));
+        }
+        info!(
+            "🔑 Signer {} ready ({} backend)",
+            signer.address(),
+            backend.as_str()
+        );
+    }
+    Ok(signers)
+}
+
+/// Decrypt a JSON keystore, prompting for its password when none is configured
+fn decrypt_keystore(path: &Path, password: Option<&str>) -> Result<PrivateKeySigner> {
+    let password = match password {
+        Some(password) => password.to_string(),
+        None => rpassword::prompt_password(format!("Password for keystore {}: ", path.display()))
+            .map_err(|e| eyre::eyre!("Failed to read keystore password: {}", e))?,
+    };
+    PrivateKeySigner::decrypt_keystore(path, password)
+        .map_err(|e| eyre::eyre!("Failed to decrypt keystore {}: {}", path.display(), e))
+}
+
+#[cfg(feature = "aws-kms")]
+async fn aws_kms_signer(key_id: &str, chain_id: u64) -> Result<BotSigner> {
+    let aws_config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let client = aws_sdk_kms::Client::new(&aws_config);
+    let signer = AwsSigner::new(client, key_id.to_string(), Some(chain_id))
+        .await
+        .map_err(|e| eyre::eyre!("Failed to open AWS KMS key {}: {}", key_id, e))?;
+    Ok(BotSigner::AwsKms(std::sync::Arc::new(signer)))
+}
+
+#[cfg(not(feature = "aws-kms"))]
+async fn aws_kms_signer(_key_id: &str, _chain_id: u64) -> Result<BotSigner> {
+    Err(eyre::eyre!(
+        "SIGNER_BACKEND=aws_kms needs a build with the aws-kms feature"
+    ))
+}
+
+#[cfg(feature = "ledger")]
+async fn ledger_signer(index: usize, chain_id: u64) -> Result<BotSigner> {
+    let signer = LedgerSigner::new(HDPath::LedgerLive(index), Some(chain_id))
+        .await
+        .map_err(|e| eyre::eyre!("Failed to open Ledger account {}: {}", index, e))?;
+    Ok(BotSigner::Ledger(std::sync::Arc::new(signer)))
+}
+
+#[cfg(not(feature = "ledger"))]
+async fn ledger_signer(_index: usize, _chain_id: u64) -> Result<BotSigner> {
+    Err(eyre::eyre!(
+        "SIGNER_BACKEND=ledger needs a build with the ledger feature"
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TEST_KEY: &str = "0x59c6995e998f97a5a0044966f0945389dc9e86dae88c7a8412f4603b6b78690d";
+
+    #[tokio::test]
+    async fn test_keystore_backend_matches_private_key() {
+        let dir = std::env::temp_dir().join(format!("bot-keystore-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let key: PrivateKeySigner = TEST_KEY.parse().unwrap();
+        let mut rng = rand::thread_rng();
+        let (_, file_name) =
+            PrivateKeySigner::encrypt_keystore(&dir, &mut rng, key.to_bytes(), "hunter2", None)
+                .unwrap();
+
+        let path = dir.join(file_name);
+        let backend = SignerBackend::Keystore {
+            paths: vec![path.clone()],
+            password: Some("hunter2".to_string()),
+        };
+        let signers = build_signers(&backend, "", 8453).await.unwrap();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].address(), key.address());
+
+        let wrong_password = SignerBackend::Keystore {
+            paths: vec![path],
+            password: Some("wrong".to_string()),
+        };
+        assert!(build_signers(&wrong_password, "", 8453).await.is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[tokio::test]
+    async fn test_private_key_list_builds_one_wallet_per_key() {
+        let other = PrivateKeySigner::random();
+        let keys = format!(
+            "{}, 0x{}",
+            TEST_KEY,
+            alloy_primitives::hex::encode(other.to_bytes())
+        );
+
+        let signers = build_signers(&SignerBackend::PrivateKey, &keys, 8453)
+            .await
+            .unwrap();
+        assert_eq!(signers.len(), 2);
+        assert_eq!(signers[1].address(), other.address());
+
+        // The same wallet twice would share nonces
+        let duplicated = format!("{},{}", TEST_KEY, TEST_KEY);
+        assert!(build_signers(&SignerBackend::PrivateKey, &duplicated, 8453)
+            .await
+            .is_err());
+        assert!(build_signers(&SignerBackend::PrivateKey, " , ", 8453)
+            .await
+            .is_err());
+    }
+}
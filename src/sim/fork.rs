@@ -0,0 +1,386 @@
+use alloy_network::TransactionBuilder;
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_provider::{Provider, ProviderBuilder, RootProvider};
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall, SolValue};
+use alloy_transport::BoxTransport;
+use eyre::Result;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::info;
+
+use super::AnvilFork;
+use crate::liquidation::flash_loan::base_to_token_amount;
+use crate::models::UserPosition;
+use crate::monitoring::aave_oracle::fetch_oracle_prices;
+use crate::monitoring::scanner::check_user_health;
+use crate::networks::{self, NetworkAddresses};
+use crate::protocols::call_contract;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IForkToken {
+        function approve(address spender, uint256 amount) external returns (bool);
+        function balanceOf(address account) external view returns (uint256);
+        function decimals() external view returns (uint8);
+    }
+
+    #[allow(missing_docs)]
+    interface IForkPool {
+        function supply(
+            address asset,
+            uint256 amount,
+            address onBehalfOf,
+            uint16 referralCode
+        ) external;
+        function borrow(
+            address asset,
+            uint256 amount,
+            uint256 interestRateMode,
+            uint16 referralCode,
+            address onBehalfOf
+        ) external;
+    }
+
+    #[allow(missing_docs)]
+    interface IForkPriceOracle {
+        function getSourceOfAsset(address asset) external view returns (address);
+    }
+}
+
+/// Uniswap V3 SwapRouter02 on Base, which the liquidator contract swaps collateral through
+pub const BASE_SWAP_ROUTER: &str = "0x2626664c2603336E57B271c5C0b26F421741e481";
+
+pub const BASE_USDC: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+
+/// First of Anvil's prefunded development accounts
+pub const ANVIL_DEV_PRIVATE_KEY: &str =
+    "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80";
+
+/// Storage slots probed for an ERC20 balance mapping
+const MAX_BALANCE_SLOT: u64 = 64;
+
+/// Aave's variable interest rate mode
+const VARIABLE_RATE_MODE: u64 = 2;
+
+const BPS: u64 = 10_000;
+
+/// Function selectors answered specially by the fixed price feed
+const LATEST_ROUND_DATA_SELECTOR: [u8; 4] = [0xfe, 0xaf, 0x96, 0x8c];
+const DECIMALS_SELECTOR: [u8; 4] = [0x31, 0x3c, 0xe5, 0x67];
+
+/// Decimals of the Aave oracle's USD base currency
+const FEED_DECIMALS: u8 = 8;
+
+/// How to fabricate a position that can be liquidated on the fork
+#[derive(Debug, Clone)]
+pub struct UnhealthyPositionSpec {
+    pub collateral_asset: Address,
+    pub collateral_amount: U256,
+    pub debt_asset: Address,
+    /// Share of the available borrowing power to borrow
+    pub borrow_share_bps: u64,
+    /// Collateral price drop pushed through the oracle after borrowing
+    pub price_drop_bps: u64,
+}
+
+impl UnhealthyPositionSpec {
+    /// 10 WETH of collateral, 95% of its borrowing power in USDC, then a 20% WETH price drop
+    pub fn weth_usdc() -> Result<Self> {
+        Ok(Self {
+            collateral_asset: networks::base().weth,
+            collateral_amount: U256::from(10u64) * U256::from(10u64).pow(U256::from(18u64)),
+            debt_asset: BASE_USDC.parse()?,
+            borrow_share_bps: 9_500,
+            price_drop_bps: 2_000,
+        })
+    }
+}
+
+/// Anvil fork of Base mainnet with helpers to fabricate Aave positions for end-to-end tests
+pub struct ForkHarness {
+    fork: AnvilFork,
+    network: NetworkAddresses,
+}
+
+impl ForkHarness {
+    pub async fn spawn(anvil_path: &str, fork_url: &str, port: u16) -> Result<Self> {
+        Ok(Self {
+            fork: AnvilFork::spawn(anvil_path, fork_url, port).await?,
+            network: networks::base(),
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        self.fork.url()
+    }
+
+    pub fn ws_url(&self) -> String {
+        self.fork.ws_url()
+    }
+
+    pub fn provider(&self) -> Result<RootProvider<BoxTransport>> {
+        Ok(ProviderBuilder::new().on_http(self.url().parse()?).boxed())
+    }
+
+    /// Set the native ETH balance of `account`
+    pub async fn set_eth_balance(&self, account: Address, amount: U256) -> Result<()> {
+        self.provider()?
+            .raw_request::<_, serde_json::Value>("anvil_setBalance".into(), (account, amount))
+            .await?;
+        Ok(())
+    }
+
+    /// Give `holder` exactly `amount` of `token` by writing its balance slot directly. The slot
+    /// of the balance mapping is found by probing until `balanceOf` reflects the write.
+    pub async fn deal(&self, token: Address, holder: Address, amount: U256) -> Result<()> {
+        let provider = self.provider()?;
+        for slot in 0..MAX_BALANCE_SLOT {
+            let key = keccak256((holder, U256::from(slot)).abi_encode());
+            let original = provider.get_storage_at(token, U256::from_be_bytes(key.0)).await?;
+
+            self.set_storage(token, key, B256::from(amount)).await?;
+            let call = IForkToken::balanceOfCall { account: holder };
+            let balance = call_contract(&provider, token, &call).await?._0;
+            if balance == amount {
+                return Ok(());
+            }
+            self.set_storage(token, key, B256::from(original)).await?;
+        }
+        Err(eyre::eyre!(
+            "No balance mapping found in the first {} slots of {}",
+            MAX_BALANCE_SLOT,
+            token
+        ))
+    }
+
+    async fn set_storage(&self, contract: Address, slot: B256, value: B256) -> Result<()> {
+        let params = (contract, slot, value);
+        self.provider()?
+            .raw_request::<_, serde_json::Value>("anvil_setStorageAt".into(), params)
+            .await?;
+        Ok(())
+    }
+
+    /// Send `call` to `to` as `from`, impersonating it, and require the transaction to succeed
+    pub async fn send_as<C: SolCall>(&self, from: Address, to: Address, call: &C) -> Result<()> {
+        let provider = self.provider()?;
+        provider
+            .raw_request::<_, serde_json::Value>("anvil_impersonateAccount".into(), [from])
+            .await?;
+
+        let tx = TransactionRequest::default()
+            .from(from)
+            .to(to)
+            .input(call.abi_encode().into());
+        let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+        if !receipt.status() {
+            return Err(eyre::eyre!(
+                "{} from {} reverted on the fork (tx 0x{:x})",
+                C::SIGNATURE,
+                from,
+                receipt.transaction_hash
+            ));
+        }
+        Ok(())
+    }
+
+    /// Approve the pool and supply `amount` of `asset` as collateral for `user`
+    pub async fn supply(&self, user: Address, asset: Address, amount: U256) -> Result<()> {
+        let pool = self.network.pool;
+        self.send_as(user, asset, &IForkToken::approveCall { spender: pool, amount }).await?;
+        self.send_as(
+            user,
+            pool,
+            &IForkPool::supplyCall {
+                asset,
+                amount,
+                onBehalfOf: user,
+                referralCode: 0,
+            },
+        )
+        .await
+    }
+
+    /// Borrow `amount` of `asset` at the variable rate against `user`'s collateral
+    pub async fn borrow(&self, user: Address, asset: Address, amount: U256) -> Result<()> {
+        self.send_as(
+            user,
+            self.network.pool,
+            &IForkPool::borrowCall {
+                asset,
+                amount,
+                interestRateMode: U256::from(VARIABLE_RATE_MODE),
+                referralCode: 0,
+                onBehalfOf: user,
+            },
+        )
+        .await
+    }
+
+    /// Pin the Aave oracle price of `asset` (base currency, 8 decimals) by replacing the code of
+    /// its price source with a feed that always answers `price`. Returns the source address.
+    pub async fn set_asset_price(&self, asset: Address, price: U256) -> Result<Address> {
+        let provider = self.provider()?;
+        let oracle = self.network.oracle;
+        let source_call = IForkPriceOracle::getSourceOfAssetCall { asset };
+        let source = call_contract(&provider, oracle, &source_call).await?._0;
+
+        provider
+            .raw_request::<_, serde_json::Value>(
+                "anvil_setCode".into(),
+                (source, fixed_price_feed_code(price)),
+            )
+            .await?;
+        info!("🧪 Pinned oracle price of {} to {} (source {})", asset, price, source);
+        Ok(source)
+    }
+
+    /// Current Aave position of `user`
+    pub async fn position(&self, user: Address) -> Result<UserPosition> {
+        let provider = Arc::new(self.provider()?);
+        check_user_health(&provider, self.network.pool, user, 3).await
+    }
+
+    /// Supply collateral, borrow against it and push the collateral price down so `user` ends
+    /// up with a health factor below 1.0. Returns the resulting position.
+    pub async fn fabricate_unhealthy_position(
+        &self,
+        user: Address,
+        spec: &UnhealthyPositionSpec,
+    ) -> Result<UserPosition> {
+        let provider = self.provider()?;
+        let one_eth = U256::from(10u64).pow(U256::from(18u64));
+        self.set_eth_balance(user, one_eth).await?;
+        self.deal(spec.collateral_asset, user, spec.collateral_amount).await?;
+        self.supply(user, spec.collateral_asset, spec.collateral_amount).await?;
+
+        let prices = fetch_oracle_prices(
+            &provider,
+            &self.network,
+            &[spec.collateral_asset, spec.debt_asset],
+        )
+        .await?;
+        let debt_decimals = call_contract(&provider, spec.debt_asset, &IForkToken::decimalsCall {})
+            .await?
+            ._0;
+        let available_base = self.position(user).await?.available_borrows_base
+            * U256::from(spec.borrow_share_bps)
+            / U256::from(BPS);
+        let borrow_amount = base_to_token_amount(available_base, prices[1], debt_decimals)
+            .ok_or_else(|| eyre::eyre!("Oracle has no price for {}", spec.debt_asset))?;
+        self.borrow(user, spec.debt_asset, borrow_amount).await?;
+
+        let dropped_price =
+            prices[0] * U256::from(BPS.saturating_sub(spec.price_drop_bps)) / U256::from(BPS);
+        self.set_asset_price(spec.collateral_asset, dropped_price).await?;
+
+        let position = self.position(user).await?;
+        info!(
+            "🧪 Fabricated position for {}: collateral {} / debt {} (base), health factor {}",
+            user, position.total_collateral_base, position.total_debt_base, position.health_factor
+        );
+        Ok(position)
+    }
+
+    /// Deploy the liquidator contract from its Foundry artifact (`forge build` output), owned by
+    /// `owner`. Returns the contract address.
+    pub async fn deploy_liquidator(&self, artifact_path: &Path, owner: Address) -> Result<Address> {
+        let artifact: serde_json::Value = serde_json::from_str(
+            &std::fs::read_to_string(artifact_path).map_err(|e| {
+                eyre::eyre!("Failed to read {} (run forge build): {}", artifact_path.display(), e)
+            })?,
+        )?;
+        let bytecode: Bytes = artifact["bytecode"]["object"]
+            .as_str()
+            .ok_or_else(|| eyre::eyre!("{} has no bytecode", artifact_path.display()))?
+            .parse()?;
+
+        let pool = self.network.pool;
+        let addresses_provider = self.network.pool_addresses_provider;
+        let router: Address = BASE_SWAP_ROUTER.parse()?;
+        let mut code = bytecode.to_vec();
+        code.extend((pool, addresses_provider, router).abi_encode_params());
+
+        let provider = self.provider()?;
+        provider
+            .raw_request::<_, serde_json::Value>("anvil_impersonateAccount".into(), [owner])
+            .await?;
+        let tx = TransactionRequest::default().from(owner).with_deploy_code(code);
+        let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+        let address = receipt
+            .contract_address
+            .filter(|_| receipt.status())
+            .ok_or_else(|| eyre::eyre!("Liquidator deployment failed on the fork"))?;
+        info!("🧪 Liquidator deployed on the fork at {}", address);
+        Ok(address)
+    }
+}
+
+/// Runtime code of a price feed that always answers `price`: `latestRoundData()` returns it as
+/// a fresh round, `decimals()` returns 8 and every other call (`latestAnswer()`) returns it as
+/// a single word
+pub fn fixed_price_feed_code(price: U256) -> Bytes {
+    let price = price.to_be_bytes::<32>();
+
+    // Dispatch on the selector
+    let mut code = vec![0x60, 0x00, 0x35, 0x60, 0xe0, 0x1c]; // PUSH1 0 CALLDATALOAD PUSH1 224 SHR
+    code.extend([0x80, 0x63]); // DUP1 PUSH4
+    code.extend(LATEST_ROUND_DATA_SELECTOR);
+    code.extend([0x14, 0x61, 0x00, 0x00, 0x57]); // EQ PUSH2 <round data> JUMPI
+    let round_data_jump = code.len() - 3;
+    code.push(0x63); // PUSH4
+    code.extend(DECIMALS_SELECTOR);
+    code.extend([0x14, 0x61, 0x00, 0x00, 0x57]); // EQ PUSH2 <decimals> JUMPI
+    let decimals_jump = code.len() - 3;
+
+    // Default: return the price as one word
+    code.push(0x7f); // PUSH32
+    code.extend(price);
+    code.extend([0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]); // MSTORE at 0, RETURN 32 bytes
+
+    let decimals_target = code.len();
+    code.extend([0x5b, 0x60, FEED_DECIMALS, 0x60, 0x00, 0x52]); // JUMPDEST, MSTORE decimals at 0
+    code.extend([0x60, 0x20, 0x60, 0x00, 0xf3]); // RETURN 32 bytes
+
+    // (roundId, answer, startedAt, updatedAt, answeredInRound) = (1, price, now, now, 1)
+    let round_data_target = code.len();
+    code.extend([0x5b, 0x60, 0x01, 0x60, 0x00, 0x52, 0x7f]); // JUMPDEST, MSTORE 1 at 0, PUSH32
+    code.extend(price);
+    code.extend([0x60, 0x20, 0x52]); // MSTORE at 32
+    code.extend([0x42, 0x60, 0x40, 0x52, 0x42, 0x60, 0x60, 0x52]); // TIMESTAMP at 64 and 96
+    code.extend([0x60, 0x01, 0x60, 0x80, 0x52]); // MSTORE 1 at 128
+    code.extend([0x60, 0xa0, 0x60, 0x00, 0xf3]); // RETURN 160 bytes
+
+    code[round_data_jump..round_data_jump + 2]
+        .copy_from_slice(&(round_data_target as u16).to_be_bytes());
+    code[decimals_jump..decimals_jump + 2].copy_from_slice(&(decimals_target as u16).to_be_bytes());
+    code.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fixed_price_feed_jumps_land_on_jumpdests() {
+        let price = U256::from(250_000_000_000u64);
+        let code = fixed_price_feed_code(price);
+
+        // Both PUSH2 jump targets point at JUMPDEST opcodes
+        let jumps: Vec<usize> = code
+            .windows(4)
+            .filter(|window| window[0] == 0x61 && window[3] == 0x57)
+            .map(|window| u16::from_be_bytes([window[1], window[2]]) as usize)
+            .collect();
+        assert_eq!(jumps.len(), 2);
+        for target in jumps {
+            assert_eq!(code[target], 0x5b);
+        }
+
+        // The price is embedded for both the single-word and the round data answers
+        let price_bytes = price.to_be_bytes::<32>();
+        let embedded = code.windows(32).filter(|window| *window == price_bytes).count();
+        assert_eq!(embedded, 2);
+    }
+}
@@ -0,0 +1,284 @@
+pub mod fork;
+
+pub use fork::{ForkHarness, UnhealthyPositionSpec};
+
+use alloy_primitives::U256;
+use alloy_provider::{Provider, ProviderBuilder};
+use alloy_rpc_types::TransactionRequest;
+use eyre::Result;
+use serde_json::json;
+use std::process::Stdio;
+use std::time::Duration;
+use tokio::process::{Child, Command};
+use tokio::sync::Mutex;
+use tracing::{debug, info};
+
+use crate::liquidation::executor::revert_reason_from_error;
+use crate::liquidation::fill;
+use crate::models::{LiquidationFill, LiquidationOpportunity, SimulationMode};
+
+/// How long a freshly spawned Anvil gets to start answering RPC requests
+const ANVIL_STARTUP_ATTEMPTS: u32 = 50;
+const ANVIL_STARTUP_POLL: Duration = Duration::from_millis(200);
+
+/// Result of simulating a liquidation transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SimulationOutcome {
+    pub gas_used: u64,
+    pub gas_cost: U256,
+    /// Fill decoded from the simulated LiquidationCall log (None when the backend has no logs)
+    pub fill: Option<LiquidationFill>,
+    /// Estimated profit re-priced with the simulated fill and gas cost
+    pub profit_after_gas: U256,
+}
+
+/// Profit of `opportunity` with its estimated gas replaced by `gas_cost` and, when known,
+/// its amounts replaced by the simulated fill
+pub fn simulated_profit(
+    opportunity: &LiquidationOpportunity,
+    fill: Option<&LiquidationFill>,
+    gas_cost: U256,
+) -> U256 {
+    fill.map_or(opportunity.estimated_profit, |fill| fill.realized_profit)
+        .saturating_add(opportunity.gas_cost)
+        .saturating_sub(gas_cost)
+}
+
+/// A local Anvil node forking the bot's RPC, killed when dropped
+pub struct AnvilFork {
+    _child: Child,
+    url: String,
+    fork_url: String,
+}
+
+impl AnvilFork {
+    pub async fn spawn(anvil_path: &str, fork_url: &str, port: u16) -> Result<Self> {
+        let child = Command::new(anvil_path)
+            .arg("--fork-url")
+            .arg(fork_url)
+            .arg("--port")
+            .arg(port.to_string())
+            .arg("--silent")
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| eyre::eyre!("Failed to start Anvil ({}): {}", anvil_path, e))?;
+
+        let url = format!("http://127.0.0.1:{}", port);
+        let provider = ProviderBuilder::new().on_http(url.parse()?);
+        for _ in 0..ANVIL_STARTUP_ATTEMPTS {
+            if provider.get_chain_id().await.is_ok() {
+                info!("🧪 Anvil fork of {} listening on {}", fork_url, url);
+                return Ok(Self {
+                    _child: child,
+                    url,
+                    fork_url: fork_url.to_string(),
+                });
+            }
+            tokio::time::sleep(ANVIL_STARTUP_POLL).await;
+        }
+        Err(eyre::eyre!("Anvil did not start listening on {}", url))
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// WebSocket endpoint Anvil serves on the same port
+    pub fn ws_url(&self) -> String {
+        self.url.replacen("http://", "ws://", 1)
+    }
+}
+
+/// Pre-flight simulation of liquidation transactions
+pub struct Simulator {
+    mode: SimulationMode,
+    /// One fork shared by every simulation; each one resets it to the latest upstream block
+    anvil: Option<Mutex<AnvilFork>>,
+}
+
+impl Simulator {
+    pub fn eth_call() -> Self {
+        Self {
+            mode: SimulationMode::EthCall,
+            anvil: None,
+        }
+    }
+
+    pub fn anvil(fork: AnvilFork) -> Self {
+        Self {
+            mode: SimulationMode::AnvilFork,
+            anvil: Some(Mutex::new(fork)),
+        }
+    }
+
+    pub fn mode(&self) -> SimulationMode {
+        self.mode
+    }
+
+    /// Simulate `tx` (a liquidation of `opportunity` sent from its signer) and re-price the
+    /// opportunity with the outcome. Reverts come back as errors carrying the decoded reason.
+    pub async fn simulate<P>(
+        &self,
+        provider: &P,
+        tx: &TransactionRequest,
+        opportunity: &LiquidationOpportunity,
+    ) -> Result<SimulationOutcome>
+    where
+        P: Provider,
+    {
+        match &self.anvil {
+            Some(fork) => self.simulate_on_fork(&*fork.lock().await, tx, opportunity).await,
+            None => self.simulate_with_call(provider, tx, opportunity).await,
+        }
+    }
+
+    /// `eth_call` for reverts and `eth_estimateGas` for gas; amounts stay as estimated
+    async fn simulate_with_call<P>(
+        &self,
+        provider: &P,
+        tx: &TransactionRequest,
+        opportunity: &LiquidationOpportunity,
+    ) -> Result<SimulationOutcome>
+    where
+        P: Provider,
+    {
+        if let Err(e) = provider.call(tx).await {
+            return Err(simulation_error(&e));
+        }
+        let gas_used = provider
+            .estimate_gas(tx)
+            .await
+            .map_err(|e| simulation_error(&e))?;
+        let gas_used = u64::try_from(gas_used).unwrap_or(u64::MAX);
+
+        let gas_price = match tx.max_fee_per_gas.or(tx.gas_price) {
+            Some(price) => price,
+            None => provider.get_gas_price().await?,
+        };
+        let gas_cost = U256::from(gas_used).saturating_mul(U256::from(gas_price));
+
+        Ok(SimulationOutcome {
+            gas_used,
+            gas_cost,
+            fill: None,
+            profit_after_gas: simulated_profit(opportunity, None, gas_cost),
+        })
+    }
+
+    /// Execute on the Anvil fork, impersonating the sender, and read the fill from the receipt
+    async fn simulate_on_fork(
+        &self,
+        fork: &AnvilFork,
+        tx: &TransactionRequest,
+        opportunity: &LiquidationOpportunity,
+    ) -> Result<SimulationOutcome> {
+        let provider = ProviderBuilder::new().on_http(fork.url().parse()?);
+        let from = tx
+            .from
+            .ok_or_else(|| eyre::eyre!("Simulated transaction has no sender"))?;
+
+        provider
+            .raw_request::<_, serde_json::Value>(
+                "anvil_reset".into(),
+                [json!({ "forking": { "jsonRpcUrl": fork.fork_url } })],
+            )
+            .await?;
+        provider
+            .raw_request::<_, serde_json::Value>("anvil_impersonateAccount".into(), [from])
+            .await?;
+
+        // The fork's nonce and fees may differ from what the live transaction was built with
+        let mut tx = tx.clone();
+        tx.nonce = None;
+        tx.gas_price = None;
+        tx.max_fee_per_gas = None;
+        tx.max_priority_fee_per_gas = None;
+
+        if let Err(e) = provider.call(&tx).await {
+            return Err(simulation_error(&e));
+        }
+
+        let receipt = provider.send_transaction(tx).await?.get_receipt().await?;
+        if !receipt.status() {
+            return Err(eyre::eyre!(
+                "Simulated liquidation reverted on the fork (tx 0x{:x})",
+                receipt.transaction_hash
+            ));
+        }
+
+        let gas_used = u64::try_from(receipt.gas_used).unwrap_or(u64::MAX);
+        let gas_cost = U256::from(receipt.gas_used)
+            .saturating_mul(U256::from(receipt.effective_gas_price));
+        let fill = fill::fill_from_logs(receipt.inner.logs(), opportunity);
+        debug!(
+            "Fork simulation for {}: gas used {}, fill {:?}",
+            opportunity.user, gas_used, fill
+        );
+
+        Ok(SimulationOutcome {
+            gas_used,
+            gas_cost,
+            profit_after_gas: simulated_profit(opportunity, fill.as_ref(), gas_cost),
+            fill,
+        })
+    }
+}
+
+fn simulation_error(error: &alloy_transport::TransportError) -> eyre::Report {
+    match revert_reason_from_error(error) {
+        Some(reason) => eyre::eyre!("Simulated liquidation reverted: {}", reason),
+        None => eyre::eyre!("Liquidation simulation failed: {}", error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FlashLoanSource;
+    use alloy_primitives::Address;
+
+    fn opportunity() -> LiquidationOpportunity {
+        LiquidationOpportunity {
+            user: Address::repeat_byte(0x01),
+            collateral_asset: Address::repeat_byte(0x02),
+            debt_asset: Address::repeat_byte(0x03),
+            debt_to_cover: U256::from(1_000u64),
+            expected_collateral_received: U256::from(1_050u64),
+            liquidation_bonus: U256::from(50u64),
+            flash_loan_fee: U256::ZERO,
+            flash_loan_source: FlashLoanSource::AaveV3,
+            gas_cost: U256::from(10u64),
+            swap_slippage: U256::ZERO,
+            estimated_profit: U256::from(40u64),
+            profit_threshold_met: true,
+        }
+    }
+
+    #[test]
+    fn test_simulated_gas_replaces_estimate() {
+        let opportunity = opportunity();
+
+        assert_eq!(simulated_profit(&opportunity, None, U256::from(10u64)), U256::from(40u64));
+        assert_eq!(simulated_profit(&opportunity, None, U256::from(25u64)), U256::from(25u64));
+        assert_eq!(simulated_profit(&opportunity, None, U256::from(100u64)), U256::ZERO);
+    }
+
+    #[test]
+    fn test_simulated_fill_replaces_estimated_amounts() {
+        let opportunity = opportunity();
+        let half_fill = LiquidationFill {
+            requested_debt: U256::from(1_000u64),
+            debt_covered: U256::from(500u64),
+            expected_collateral: U256::from(1_050u64),
+            collateral_received: U256::from(525u64),
+            realized_profit: U256::from(15u64),
+        };
+
+        assert_eq!(
+            simulated_profit(&opportunity, Some(&half_fill), U256::from(5u64)),
+            U256::from(20u64)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use alloy_primitives::{Address, U256};
+use alloy_provider::Provider;
+use alloy_rpc_types::TransactionRequest;
+use alloy_sol_types::{sol, SolCall};
+use eyre::Result;
+use std::collections::BTreeMap;
+use std::time::Duration;
+use tracing::{info, warn};
+
+use crate::liquidation::profit_recheck::fetch_token_balance;
+use crate::liquidation::tx_manager::TxManager;
+use crate::models::LiquidationAssetConfig;
+use crate::monitoring::metrics;
+use crate::signer::BotSigner;
+
+sol! {
+    #[allow(missing_docs)]
+    interface IERC20Transfer {
+        function transfer(address to, uint256 amount) external returns (bool);
+    }
+}
+
+/// Symbol native ETH is configured and recorded under
+pub const NATIVE_SYMBOL: &str = "ETH";
+
+/// Gas a plain native transfer costs
+const NATIVE_TRANSFER_GAS: u64 = 21_000;
+
+/// How long to wait for a sweep's receipt
+const SWEEP_RECEIPT_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A transfer of surplus funds from an executor wallet to the cold wallet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sweep {
+    pub wallet: Address,
+    pub cold_wallet: Address,
+    pub symbol: String,
+    /// None for native ETH
+    pub token: Option<Address>,
+    pub amount: U256,
+    pub tx_hash: String,
+}
+
+/// Amount to sweep once `balance` exceeds `threshold`: everything above `retain`
+pub fn sweep_amount(balance: U256, threshold: U256, retain: U256) -> Option<U256> {
+    (balance > threshold && balance > retain).then(|| balance - retain)
+}
+
+/// Moves liquidation profits that pile up in executor wallets to a cold wallet. A wallet's
+/// balance of an asset is swept once it exceeds the asset's threshold, keeping back the gas
+/// reserve for native ETH and the configured minimum (working capital) for tokens.
+pub struct Treasury {
+    cold_wallet: Address,
+    thresholds: BTreeMap<String, U256>,     // By upper-case symbol
+    token_reserves: BTreeMap<String, U256>, // By upper-case symbol
+    gas_reserve_wei: U256,
+    broadcast: bool, // BotConfig::broadcasts_transactions
+}
+
+impl Treasury {
+    pub fn new(
+        cold_wallet: Address,
+        thresholds: BTreeMap<String, U256>,
+        token_reserves: BTreeMap<String, U256>,
+        gas_reserve_wei: U256,
+        broadcast: bool,
+    ) -> Self {
+        Self {
+            cold_wallet,
+            thresholds,
+            token_reserves,
+            gas_reserve_wei,
+            broadcast,
+        }
+    }
+
+    pub fn cold_wallet(&self) -> Address {
+        self.cold_wallet
+    }
+
+    /// Sweep `signer`'s native ETH and its balances of `assets` that are over their thresholds,
+    /// with nonces from `tx_manager` (mocked unless broadcasting). A failed sweep is logged and
+    /// the remaining assets are still tried.
+    pub async fn sweep_wallet<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+        assets: &[LiquidationAssetConfig],
+    ) -> Vec<Sweep>
+    where
+        P: Provider,
+    {
+        let wallet = signer.address();
+        let mut sweeps = Vec::new();
+
+        for asset in assets {
+            let symbol = asset.symbol.to_uppercase();
+            let Some(threshold) = self.thresholds.get(&symbol) else {
+                continue;
+            };
+            let balance = match fetch_token_balance(provider, asset.address, wallet).await {
+                Ok(balance) => balance,
+                Err(e) => {
+                    warn!(
+                        "Failed to read {} balance of {} for sweeping: {}",
+                        symbol, wallet, e
+                    );
+                    continue;
+                }
+            };
+            let retain = self
+                .token_reserves
+                .get(&symbol)
+                .copied()
+                .unwrap_or_default();
+            let Some(amount) = sweep_amount(balance, *threshold, retain) else {
+                continue;
+            };
+            let transfer = IERC20Transfer::transferCall {
+                to: self.cold_wallet,
+                amount,
+            };
+            let tx_req = TransactionRequest::default()
+                .from(wallet)
+                .to(asset.address)
+                .input(transfer.abi_encode().into());
+            sweeps.extend(
+                self.send_sweep(
+                    provider,
+                    signer,
+                    tx_manager,
+                    &symbol,
+                    Some(asset.address),
+                    amount,
+                    tx_req,
+                )
+                .await,
+            );
+        }
+
+        if let Some(threshold) = self.thresholds.get(NATIVE_SYMBOL) {
+            match self.native_sweep_amount(provider, wallet, *threshold).await {
+                Ok(Some(amount)) => {
+                    let tx_req = TransactionRequest::default()
+                        .from(wallet)
+                        .to(self.cold_wallet)
+                        .value(amount);
+                    sweeps.extend(
+                        self.send_sweep(
+                            provider,
+                            signer,
+                            tx_manager,
+                            NATIVE_SYMBOL,
+                            None,
+                            amount,
+                            tx_req,
+                        )
+                        .await,
+                    );
+                }
+                Ok(None) => {}
+                Err(e) => warn!(
+                    "Failed to read native balance of {} for sweeping: {}",
+                    wallet, e
+                ),
+            }
+        }
+        sweeps
+    }
+
+    /// Native ETH to sweep, keeping back the gas reserve and the transfer's own fee
+    async fn native_sweep_amount<P>(
+        &self,
+        provider: &P,
+        wallet: Address,
+        threshold: U256,
+    ) -> Result<Option<U256>>
+    where
+        P: Provider,
+    {
+        let balance = provider.get_balance(wallet).await?;
+        // Double the current gas price, so a base fee rise doesn't leave the transfer unfunded
+        let transfer_fee = U256::from(provider.get_gas_price().await?)
+            .saturating_mul(U256::from(NATIVE_TRANSFER_GAS * 2));
+        Ok(sweep_amount(
+            balance,
+            threshold,
+            self.gas_reserve_wei.saturating_add(transfer_fee),
+        ))
+    }
+
+    async fn send_sweep<P>(
+        &self,
+        provider: &P,
+        signer: &BotSigner,
+        tx_manager: &TxManager,
+        symbol: &str,
+        token: Option<Address>,
+        amount: U256,
+        tx_req: TransactionRequest,
+    ) -> Option<Sweep>
+    where
+        P: Provider,
+    {
+        let wallet = signer.address();
+        let tx_hash = if self.broadcast {
+            match tx_manager
+                .send_and_confirm(provider, signer, tx_req, SWEEP_RECEIPT_TIMEOUT)
+                .await
+            {
+                Ok(tx_hash) => format!("0x{:x}", tx_hash),
+                Err(e) => {
+                    metrics::record_treasury_sweep(symbol, "failed");
+                    warn!(
+                        "🏦 Failed to sweep {} {} from {} to {}: {}",
+                        amount, symbol, wallet, self.cold_wallet, e
+                    );
+                    return None;
+                }
+            }
+        } else {
+            warn!(
+                "🎭 MOCK sweep of {} {} from {} to {} - no on-chain execution",
+                amount, symbol, wallet, self.cold_wallet
+            );
+            "mock".to_string()
+        };
+
+        metrics::record_treasury_sweep(symbol, "swept");
+        info!(
+            "🏦 Swept {} {} from {} to cold wallet {}: {}",
+            amount, symbol, wallet, self.cold_wallet, tx_hash
+        );
+        Some(Sweep {
+            wallet,
+            cold_wallet: self.cold_wallet,
+            symbol: symbol.to_string(),
+            token,
+            amount,
+            tx_hash,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_balances_over_the_threshold_are_swept() {
+        let threshold = U256::from(1_000u64);
+        let retain = U256::from(200u64);
+
+        assert_eq!(sweep_amount(U256::from(1_000u64), threshold, retain), None);
+        assert_eq!(
+            sweep_amount(U256::from(1_500u64), threshold, retain),
+            Some(U256::from(1_300u64))
+        );
+        assert_eq!(
+            sweep_amount(U256::from(1_500u64), threshold, U256::ZERO),
+            Some(U256::from(1_500u64))
+        );
+        // A reserve above the balance leaves nothing to sweep
+        assert_eq!(
+            sweep_amount(U256::from(1_500u64), threshold, U256::from(2_000u64)),
+            None
+        );
+    }
+}
@@ -0,0 +1,505 @@
+use alloy_primitives::{Address, U256};
+use axum::extract::{Query, State};
+use axum::http::StatusCode;
+use axum::response::Html;
+use axum::routing::get;
+use axum::{Json, Router};
+use dashmap::DashMap;
+use eyre::Result;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tracing::{info, warn};
+
+use crate::circuit_breaker::{CircuitBreaker, CircuitBreakerStatusReport};
+use crate::database::{self, DatabasePool};
+use crate::models::{
+    LiquidationRecord, PositionSnapshot, PricePoint, PriceResolution, UserPosition,
+};
+use crate::monitoring::scanner;
+
+/// Single-page UI polling the JSON endpoints below
+const INDEX_HTML: &str = include_str!("../static/dashboard.html");
+
+const DEFAULT_POSITION_LIMIT: usize = 100;
+const DEFAULT_LIQUIDATION_LIMIT: u32 = 50;
+const DEFAULT_PRICE_WINDOW_SECS: u64 = 24 * 60 * 60;
+const DEFAULT_POSITION_HISTORY_WINDOW_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Shared state for the web dashboard server
+#[derive(Clone)]
+pub struct WebDashboardState {
+    db_pool: DatabasePool,
+    user_positions: Option<Arc<DashMap<Address, UserPosition>>>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+}
+
+impl WebDashboardState {
+    /// Dashboard reading everything from the database (positions as last persisted)
+    pub fn new(db_pool: DatabasePool) -> Self {
+        Self {
+            db_pool,
+            user_positions: None,
+            circuit_breaker: None,
+        }
+    }
+
+    /// Show the bot's live tracked positions instead of the persisted snapshot
+    pub fn with_user_positions(
+        mut self,
+        user_positions: Arc<DashMap<Address, UserPosition>>,
+    ) -> Self {
+        self.user_positions = Some(user_positions);
+        self
+    }
+
+    /// Show circuit breaker state (only available when served from the bot)
+    pub fn with_circuit_breaker(mut self, circuit_breaker: Arc<CircuitBreaker>) -> Self {
+        self.circuit_breaker = Some(circuit_breaker);
+        self
+    }
+}
+
+/// One tracked position; amounts are decimal strings in base currency units
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PositionView {
+    pub address: Address,
+    pub health_factor: String,
+    pub total_collateral_base: String,
+    pub total_debt_base: String,
+    pub is_at_risk: bool,
+    pub liquidatable: bool,
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&UserPosition> for PositionView {
+    fn from(position: &UserPosition) -> Self {
+        Self {
+            address: position.address,
+            health_factor: position.health_factor.to_string(),
+            total_collateral_base: position.total_collateral_base.to_string(),
+            total_debt_base: position.total_debt_base.to_string(),
+            is_at_risk: position.is_at_risk,
+            liquidatable: scanner::is_liquidatable(position),
+            last_updated: position.last_updated,
+        }
+    }
+}
+
+/// One price sample of `GET /api/prices`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PricePointView {
+    pub timestamp: u64,
+    pub price: String,
+    pub min_price: String,
+    pub max_price: String,
+}
+
+impl From<&PricePoint> for PricePointView {
+    fn from(point: &PricePoint) -> Self {
+        Self {
+            timestamp: point.timestamp,
+            price: point.price.to_string(),
+            min_price: point.min_price.to_string(),
+            max_price: point.max_price.to_string(),
+        }
+    }
+}
+
+/// One health factor snapshot of `GET /api/position-history`
+#[derive(Debug, Serialize, Deserialize)]
+pub struct PositionSnapshotView {
+    pub timestamp: u64,
+    pub health_factor: String,
+    pub total_collateral_base: String,
+    pub total_debt_base: String,
+}
+
+impl From<&PositionSnapshot> for PositionSnapshotView {
+    fn from(snapshot: &PositionSnapshot) -> Self {
+        Self {
+            timestamp: snapshot.timestamp,
+            health_factor: snapshot.health_factor.to_string(),
+            total_collateral_base: snapshot.total_collateral_base.to_string(),
+            total_debt_base: snapshot.total_debt_base.to_string(),
+        }
+    }
+}
+
+/// One entry of `GET /api/liquidations`; amounts are decimal strings in wei
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiquidationView {
+    pub user: Address,
+    pub collateral_asset: String,
+    pub debt_asset: String,
+    pub debt_covered: String,
+    pub profit: String,
+    pub tx_hash: Option<String>,
+    pub block_number: Option<u64>,
+    pub strategy_label: String,
+    pub succeeded: Option<bool>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&LiquidationRecord> for LiquidationView {
+    fn from(record: &LiquidationRecord) -> Self {
+        Self {
+            user: record.user,
+            collateral_asset: record.collateral_asset.clone(),
+            debt_asset: record.debt_asset.clone(),
+            debt_covered: record.debt_covered.to_string(),
+            profit: record.profit.to_string(),
+            tx_hash: record.tx_hash.clone(),
+            block_number: record.block_number,
+            strategy_label: record.strategy_label.clone(),
+            succeeded: record.succeeded,
+            timestamp: record.timestamp,
+        }
+    }
+}
+
+/// Liquidation history plus realized profit totals
+#[derive(Debug, Serialize, Deserialize)]
+pub struct LiquidationHistory {
+    pub liquidations: Vec<LiquidationView>,
+    pub total_profit: String,
+    pub strategies: Vec<StrategyPnlView>,
+}
+
+/// Realized profit of one strategy label over all recorded liquidations
+#[derive(Debug, Serialize, Deserialize)]
+pub struct StrategyPnlView {
+    pub strategy_label: String,
+    pub liquidation_count: u64,
+    pub total_profit: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LimitQuery {
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PriceQuery {
+    pub asset: Address,
+    /// Unix seconds; defaults to 24 hours before `to`
+    pub from: Option<u64>,
+    /// Unix seconds; defaults to now
+    pub to: Option<u64>,
+    /// raw, minute or hour (default minute)
+    pub resolution: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct PositionHistoryQuery {
+    pub user: Address,
+    /// Unix seconds; defaults to 7 days before `to`
+    pub from: Option<u64>,
+    /// Unix seconds; defaults to now
+    pub to: Option<u64>,
+}
+
+type DashboardError = (StatusCode, String);
+
+fn internal_error(context: &str, error: eyre::Report) -> DashboardError {
+    warn!("⚠️ Dashboard failed to load {}: {}", context, error);
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        format!("failed to load {}", context),
+    )
+}
+
+/// Build the dashboard router
+pub fn web_dashboard_router(state: WebDashboardState) -> Router {
+    Router::new()
+        .route("/", get(index))
+        .route("/api/positions", get(positions))
+        .route("/api/price-assets", get(price_assets))
+        .route("/api/prices", get(prices))
+        .route("/api/position-history", get(position_history))
+        .route("/api/liquidations", get(liquidations))
+        .route("/api/circuit-breaker", get(circuit_breaker_status))
+        .with_state(state)
+}
+
+/// Bind the dashboard to `listen_addr` and serve until the process exits
+pub async fn start_web_dashboard(listen_addr: &str, state: WebDashboardState) -> Result<()> {
+    let listener = TcpListener::bind(listen_addr).await?;
+    info!("📊 Web dashboard listening on http://{}", listener.local_addr()?);
+    serve_web_dashboard(listener, state).await
+}
+
+/// Serve the dashboard router on an already bound listener
+pub async fn serve_web_dashboard(listener: TcpListener, state: WebDashboardState) -> Result<()> {
+    axum::serve(listener, web_dashboard_router(state)).await?;
+    Ok(())
+}
+
+async fn index() -> Html<&'static str> {
+    Html(INDEX_HTML)
+}
+
+/// Positions with debt, lowest health factor first
+async fn positions(
+    State(state): State<WebDashboardState>,
+    Query(query): Query<LimitQuery>,
+) -> Result<Json<Vec<PositionView>>, DashboardError> {
+    let mut positions: Vec<UserPosition> = match &state.user_positions {
+        Some(user_positions) => user_positions.iter().map(|entry| entry.value().clone()).collect(),
+        None => database::get_all_user_positions(&state.db_pool)
+            .await
+            .map_err(|e| internal_error("positions", e))?,
+    };
+
+    positions.retain(|position| position.total_debt_base > U256::ZERO);
+    positions.sort_by_key(|position| position.health_factor);
+    positions.truncate(query.limit.unwrap_or(DEFAULT_POSITION_LIMIT));
+
+    Ok(Json(positions.iter().map(PositionView::from).collect()))
+}
+
+async fn price_assets(
+    State(state): State<WebDashboardState>,
+) -> Result<Json<Vec<Address>>, DashboardError> {
+    database::get_price_feed_assets(&state.db_pool)
+        .await
+        .map(Json)
+        .map_err(|e| internal_error("price assets", e))
+}
+
+async fn prices(
+    State(state): State<WebDashboardState>,
+    Query(query): Query<PriceQuery>,
+) -> Result<Json<Vec<PricePointView>>, DashboardError> {
+    let resolution = match query.resolution.as_deref() {
+        Some(name) => PriceResolution::from_name(name).ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                format!("unknown resolution '{}'", name),
+            )
+        })?,
+        None => PriceResolution::Minute,
+    };
+    let to = query
+        .to
+        .unwrap_or_else(|| chrono::Utc::now().timestamp().max(0) as u64);
+    let from = query
+        .from
+        .unwrap_or_else(|| to.saturating_sub(DEFAULT_PRICE_WINDOW_SECS));
+
+    let points = database::get_price_history(&state.db_pool, query.asset, from, to, resolution)
+        .await
+        .map_err(|e| internal_error("price history", e))?;
+    Ok(Json(points.iter().map(PricePointView::from).collect()))
+}
+
+/// Health factor snapshots of one user, oldest first
+async fn position_history(
+    State(state): State<WebDashboardState>,
+    Query(query): Query<PositionHistoryQuery>,
+) -> Result<Json<Vec<PositionSnapshotView>>, DashboardError> {
+    let to = query
+        .to
+        .unwrap_or_else(|| chrono::Utc::now().timestamp().max(0) as u64);
+    let from = query
+        .from
+        .unwrap_or_else(|| to.saturating_sub(DEFAULT_POSITION_HISTORY_WINDOW_SECS));
+
+    let snapshots = database::get_position_history(&state.db_pool, query.user, from, to)
+        .await
+        .map_err(|e| internal_error("position history", e))?;
+    Ok(Json(snapshots.iter().map(PositionSnapshotView::from).collect()))
+}
+
+async fn liquidations(
+    State(state): State<WebDashboardState>,
+    Query(query): Query<LimitQuery>,
+) -> Result<Json<LiquidationHistory>, DashboardError> {
+    let limit = query
+        .limit
+        .map(|limit| limit.min(u32::MAX as usize) as u32)
+        .unwrap_or(DEFAULT_LIQUIDATION_LIMIT);
+    let records = database::get_recent_liquidations(&state.db_pool, limit)
+        .await
+        .map_err(|e| internal_error("liquidations", e))?;
+    let strategies = database::get_pnl_by_strategy(&state.db_pool)
+        .await
+        .map_err(|e| internal_error("strategy PnL", e))?;
+
+    let total_profit = strategies
+        .iter()
+        .fold(U256::ZERO, |total, entry| total.saturating_add(entry.total_profit));
+
+    Ok(Json(LiquidationHistory {
+        liquidations: records.iter().map(LiquidationView::from).collect(),
+        total_profit: total_profit.to_string(),
+        strategies: strategies
+            .into_iter()
+            .map(|entry| StrategyPnlView {
+                strategy_label: entry.strategy_label,
+                liquidation_count: entry.liquidation_count,
+                total_profit: entry.total_profit.to_string(),
+            })
+            .collect(),
+    }))
+}
+
+/// Circuit breaker status, or `null` when the dashboard runs outside the bot
+async fn circuit_breaker_status(
+    State(state): State<WebDashboardState>,
+) -> Json<Option<CircuitBreakerStatusReport>> {
+    Json(
+        state
+            .circuit_breaker
+            .as_ref()
+            .map(|circuit_breaker| circuit_breaker.get_status_report()),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::database::tests::create_test_pool;
+    use crate::circuit_breaker::tests::create_test_config;
+    use crate::circuit_breaker::CircuitBreakerState;
+
+    async fn spawn_dashboard(state: WebDashboardState) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(serve_web_dashboard(listener, state));
+        format!("http://{}", addr)
+    }
+
+    fn test_position(byte: u8, health_factor: u64, total_debt_base: u64) -> UserPosition {
+        UserPosition {
+            address: Address::from([byte; 20]),
+            total_collateral_base: U256::from(10_000u64),
+            total_debt_base: U256::from(total_debt_base),
+            available_borrows_base: U256::ZERO,
+            current_liquidation_threshold: U256::from(8_500u64),
+            ltv: U256::from(8_000u64),
+            health_factor: U256::from(health_factor),
+            last_updated: chrono::Utc::now(),
+            is_at_risk: health_factor < 1_100_000_000_000_000_000,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_positions_sorted_by_health_factor() {
+        let user_positions = Arc::new(DashMap::new());
+        for position in [
+            test_position(1, 1_050_000_000_000_000_000, 8_000),
+            test_position(2, 2_000_000_000_000_000_000, 8_000),
+            test_position(3, 950_000_000_000_000_000, 8_000),
+            test_position(4, 500_000_000_000_000_000, 0),
+        ] {
+            user_positions.insert(position.address, position);
+        }
+        let state = WebDashboardState::new(create_test_pool().await)
+            .with_user_positions(user_positions);
+        let base_url = spawn_dashboard(state).await;
+
+        let positions: Vec<PositionView> = reqwest::get(format!("{}/api/positions", base_url))
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+
+        let addresses: Vec<Address> = positions.iter().map(|p| p.address).collect();
+        assert_eq!(
+            addresses,
+            vec![
+                Address::from([3u8; 20]),
+                Address::from([1u8; 20]),
+                Address::from([2u8; 20]),
+            ]
+        );
+        assert!(positions[0].liquidatable);
+        assert!(!positions[1].liquidatable);
+    }
+
+    #[tokio::test]
+    async fn test_position_history_endpoint() {
+        let db_pool = create_test_pool().await;
+        let mut position = test_position(5, 1_200_000_000_000_000_000, 8_000);
+        position.last_updated -= chrono::Duration::minutes(10);
+        database::save_user_position(&db_pool, &position).await.unwrap();
+        position.health_factor = U256::from(980_000_000_000_000_000u64);
+        position.last_updated += chrono::Duration::minutes(5);
+        database::save_user_position(&db_pool, &position).await.unwrap();
+        let base_url = spawn_dashboard(WebDashboardState::new(db_pool)).await;
+
+        let url = format!("{}/api/position-history?user={}", base_url, position.address);
+        let history: Vec<PositionSnapshotView> =
+            reqwest::get(url).await.unwrap().json().await.unwrap();
+        let health_factors: Vec<&str> = history.iter().map(|s| s.health_factor.as_str()).collect();
+        assert_eq!(health_factors, vec!["1200000000000000000", "980000000000000000"]);
+    }
+
+    #[tokio::test]
+    async fn test_liquidation_history_and_standalone_breaker_status() {
+        let db_pool = create_test_pool().await;
+        let user = Address::repeat_byte(0x01);
+        for (tx_hash, profit, strategy) in [("0xaa", "10", "default"), ("0xbb", "25", "aggressive")]
+        {
+            database::record_liquidation_event(
+                &db_pool, &user, "0x01", "0x02", "5", None, "6", profit, Some(tx_hash), None,
+                strategy,
+            )
+            .await
+            .unwrap();
+        }
+        let base_url = spawn_dashboard(WebDashboardState::new(db_pool)).await;
+
+        let history: LiquidationHistory =
+            reqwest::get(format!("{}/api/liquidations?limit=1", base_url))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+        assert_eq!(history.liquidations.len(), 1);
+        assert_eq!(history.liquidations[0].tx_hash.as_deref(), Some("0xbb"));
+        assert_eq!(history.total_profit, "35");
+        assert_eq!(history.strategies.len(), 2);
+
+        let status: Option<CircuitBreakerStatusReport> =
+            reqwest::get(format!("{}/api/circuit-breaker", base_url))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+        assert!(status.is_none());
+
+        let index = reqwest::get(&base_url).await.unwrap().text().await.unwrap();
+        assert!(index.contains("/api/positions"));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_status_when_served_from_bot() {
+        let circuit_breaker = Arc::new(CircuitBreaker::new(create_test_config()));
+        let state = WebDashboardState::new(create_test_pool().await)
+            .with_circuit_breaker(circuit_breaker);
+        let base_url = spawn_dashboard(state).await;
+
+        let status: Option<CircuitBreakerStatusReport> =
+            reqwest::get(format!("{}/api/circuit-breaker", base_url))
+                .await
+                .unwrap()
+                .json()
+                .await
+                .unwrap();
+        assert_eq!(status.unwrap().state, CircuitBreakerState::Closed);
+
+        let response = reqwest::get(format!(
+            "{}/api/prices?asset={}&resolution=weekly",
+            base_url,
+            Address::repeat_byte(0x02)
+        ))
+        .await
+        .unwrap();
+        assert_eq!(response.status(), reqwest::StatusCode::BAD_REQUEST);
+    }
+}
@@ -0,0 +1,279 @@
+use alloy_primitives::{hex, Address};
+use eyre::Result;
+use hmac::{Hmac, Mac};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, warn};
+
+use crate::feed::{self, FeedEvent, FeedEventKind};
+use crate::monitoring::metrics;
+
+/// Header carrying `sha256=<hex HMAC-SHA256 of the body>` when a signing secret is configured
+pub const SIGNATURE_HEADER: &str = "X-Signature-256";
+
+/// Delay before the first retry; it doubles with every further attempt up to the maximum
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// A user found liquidatable again within this window isn't posted again
+const OPPORTUNITY_REPEAT_WINDOW: Duration = Duration::from_secs(60);
+
+/// Signature of `body` under `secret`, as sent in the signature header
+pub fn sign(secret: &str, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(body.as_bytes());
+    format!("sha256={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// JSON body posted for a feed event; circuit breaker changes aren't posted. Amounts are
+/// decimal strings and the health factor has 18 decimals.
+pub fn webhook_payload(event: &FeedEvent) -> Option<Value> {
+    match &event.kind {
+        FeedEventKind::Opportunity {
+            user,
+            health_factor,
+        } => Some(json!({
+            "type": "liquidatable_position",
+            "chain_id": event.chain_id,
+            "timestamp_ms": event.timestamp_ms,
+            "user": user.to_string(),
+            "health_factor": health_factor.map(|hf| hf.to_string()),
+        })),
+        FeedEventKind::Liquidation {
+            user,
+            tx_hash,
+            profit,
+        } => Some(json!({
+            "type": "liquidation_executed",
+            "chain_id": event.chain_id,
+            "timestamp_ms": event.timestamp_ms,
+            "user": user.to_string(),
+            "tx_hash": tx_hash,
+            "profit_wei": profit.to_string(),
+        })),
+        FeedEventKind::CircuitBreaker { .. } => None,
+    }
+}
+
+/// How a single POST went
+enum Attempt {
+    Delivered,
+    /// Transport error, 429 or 5xx: worth trying again
+    Retry(String),
+    /// Any other status: the endpoint won't accept it however often it's sent
+    Rejected(String),
+}
+
+/// Posts liquidatable positions and executed liquidations to an operator's endpoint, for
+/// teams without message bus infrastructure
+pub struct EventWebhook {
+    url: String,
+    secret: Option<String>,
+    max_retries: u32,
+    initial_retry_delay: Duration,
+    client: reqwest::Client,
+}
+
+impl EventWebhook {
+    pub fn new(url: String, secret: Option<String>, max_retries: u32) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()?;
+        Ok(Self {
+            url,
+            secret,
+            max_retries,
+            initial_retry_delay: INITIAL_RETRY_DELAY,
+            client,
+        })
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    async fn attempt(&self, body: &str) -> Attempt {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.to_string());
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign(secret, body));
+        }
+
+        match request.send().await {
+            Ok(response) if response.status().is_success() => Attempt::Delivered,
+            Ok(response)
+                if response.status().is_server_error()
+                    || response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS =>
+            {
+                Attempt::Retry(format!("status {}", response.status()))
+            }
+            Ok(response) => Attempt::Rejected(format!("status {}", response.status())),
+            Err(e) => Attempt::Retry(e.to_string()),
+        }
+    }
+
+    /// POST `body`, retrying transient failures with exponential backoff. Returns the number of
+    /// attempts it took.
+    pub async fn deliver(&self, body: &str) -> Result<u32> {
+        let mut delay = self.initial_retry_delay;
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            match self.attempt(body).await {
+                Attempt::Delivered => return Ok(attempts),
+                Attempt::Rejected(reason) => {
+                    return Err(eyre::eyre!("endpoint rejected the delivery: {}", reason))
+                }
+                Attempt::Retry(reason) if attempts > self.max_retries => {
+                    return Err(eyre::eyre!(
+                        "giving up after {} attempts: {}",
+                        attempts,
+                        reason
+                    ))
+                }
+                Attempt::Retry(reason) => {
+                    metrics::record_webhook_delivery("retried");
+                    debug!(
+                        "Webhook delivery attempt {} failed ({}) - retrying in {:?}",
+                        attempts, reason, delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_RETRY_DELAY);
+                }
+            }
+        }
+    }
+}
+
+/// Post this chain's liquidatable positions and executed liquidations to the webhook as they
+/// happen. Each delivery retries on its own task, so a slow endpoint doesn't hold up the next.
+pub async fn run_event_webhook(webhook: Arc<EventWebhook>, chain_id: u64) -> Result<()> {
+    let mut receiver = feed::subscribe();
+    let mut last_posted: HashMap<Address, Instant> = HashMap::new();
+
+    loop {
+        let event = match receiver.recv().await {
+            Ok(event) => event,
+            Err(RecvError::Lagged(missed)) => {
+                warn!("📤 Event webhook fell behind and skipped {} events", missed);
+                continue;
+            }
+            Err(RecvError::Closed) => return Ok(()),
+        };
+        if event.chain_id != chain_id {
+            continue;
+        }
+
+        // Rescans report a liquidatable user on every pass until it's liquidated
+        if let FeedEventKind::Opportunity { user, .. } = &event.kind {
+            let now = Instant::now();
+            last_posted
+                .retain(|_, posted_at| now.duration_since(*posted_at) < OPPORTUNITY_REPEAT_WINDOW);
+            if last_posted.contains_key(user) {
+                continue;
+            }
+            last_posted.insert(*user, now);
+        }
+
+        let Some(payload) = webhook_payload(&event) else {
+            continue;
+        };
+        let webhook = webhook.clone();
+        tokio::spawn(async move {
+            let kind = payload["type"].as_str().unwrap_or_default().to_string();
+            match webhook.deliver(&payload.to_string()).await {
+                Ok(attempts) => {
+                    metrics::record_webhook_delivery("delivered");
+                    debug!(
+                        "📤 Delivered {} webhook to {} ({} attempt(s))",
+                        kind,
+                        webhook.url(),
+                        attempts
+                    );
+                }
+                Err(e) => {
+                    metrics::record_webhook_delivery("failed");
+                    warn!(
+                        "📤 Failed to deliver {} webhook to {}: {}",
+                        kind,
+                        webhook.url(),
+                        e
+                    );
+                }
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_server::{MockResponse, MockServer};
+    use alloy_primitives::U256;
+
+    /// Webhook endpoint answering with `statuses` in turn, then 200
+    async fn spawn_scripted_server(statuses: Vec<&'static str>) -> MockServer {
+        let mut statuses = statuses.into_iter();
+        MockServer::spawn(move |_| MockResponse::status(statuses.next().unwrap_or("200 OK"))).await
+    }
+
+    #[test]
+    fn test_payloads_are_signed_with_hmac_sha256() {
+        assert_eq!(
+            sign("key", "The quick brown fox jumps over the lazy dog"),
+            "sha256=f7bc83f430538424b13298e6aa6fb143ef4d59a14946175997479dbc2d1a3cd8"
+        );
+
+        let payload = webhook_payload(&FeedEvent {
+            chain_id: 8453,
+            timestamp_ms: 1_000,
+            kind: FeedEventKind::Liquidation {
+                user: Address::repeat_byte(0x01),
+                tx_hash: "0xabc".to_string(),
+                profit: U256::from(5_000u64),
+            },
+        })
+        .unwrap();
+        assert_eq!(payload["type"], "liquidation_executed");
+        assert_eq!(payload["profit_wei"], "5000");
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let server = spawn_scripted_server(vec![
+            "503 Service Unavailable",
+            "429 Too Many Requests",
+            "200 OK",
+        ])
+        .await;
+        let mut webhook =
+            EventWebhook::new(server.url("/hooks"), Some("secret".to_string()), 3).unwrap();
+        webhook.initial_retry_delay = Duration::from_millis(1);
+
+        assert_eq!(webhook.deliver("{}").await.unwrap(), 3);
+        let requests = server.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0].header(SIGNATURE_HEADER),
+            Some(sign("secret", "{}").as_str())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rejected_deliveries_are_not_retried() {
+        let server = spawn_scripted_server(vec!["400 Bad Request", "200 OK"]).await;
+        let mut webhook = EventWebhook::new(server.url("/hooks"), None, 3).unwrap();
+        webhook.initial_retry_delay = Duration::from_millis(1);
+
+        assert!(webhook.deliver("{}").await.is_err());
+        assert_eq!(server.requests().len(), 1);
+    }
+}
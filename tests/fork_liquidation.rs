@@ -0,0 +1,93 @@
+use alloy_primitives::{Address, U256};
+use alloy_signer_local::PrivateKeySigner;
+use eyre::{eyre, Result};
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+
+use liquidation_bot::sim::fork::{ForkHarness, UnhealthyPositionSpec, ANVIL_DEV_PRIVATE_KEY};
+use liquidation_bot::{BotConfig, LiquidationBot, UserPosition};
+
+const ANVIL_PORT: u16 = 8547;
+const LIQUIDATOR_ARTIFACT: &str = "artifacts/AaveLiquidator.sol/AaveLiquidator.json";
+const LIQUIDATION_TIMEOUT: Duration = Duration::from_secs(180);
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+// Forks Base mainnet, fabricates an unhealthy WETH/USDC position and runs the whole bot
+// until it liquidates it. Run with `forge build` output present and
+// `FORK_TEST_RPC_URL=<base rpc> cargo test --test fork_liquidation -- --ignored`.
+#[tokio::test]
+#[ignore = "needs anvil, forge build artifacts and FORK_TEST_RPC_URL"]
+async fn test_bot_liquidates_fabricated_position_on_fork() -> Result<()> {
+    let fork_url = std::env::var("FORK_TEST_RPC_URL")
+        .map_err(|_| eyre!("FORK_TEST_RPC_URL must point at a Base mainnet RPC"))?;
+    let anvil_path = std::env::var("ANVIL_PATH").unwrap_or_else(|_| "anvil".to_string());
+    let harness = ForkHarness::spawn(&anvil_path, &fork_url, ANVIL_PORT).await?;
+
+    let signer: PrivateKeySigner = ANVIL_DEV_PRIVATE_KEY.parse()?;
+    let liquidator = harness
+        .deploy_liquidator(Path::new(LIQUIDATOR_ARTIFACT), signer.address())
+        .await?;
+
+    let user = Address::repeat_byte(0x42);
+    let spec = UnhealthyPositionSpec::weth_usdc()?;
+    let unhealthy = harness.fabricate_unhealthy_position(user, &spec).await?;
+    assert!(
+        unhealthy.health_factor < U256::from(10u64).pow(U256::from(18u64)),
+        "fabricated position should be liquidatable, got HF {}",
+        unhealthy.health_factor
+    );
+
+    let run_id = std::process::id();
+    let database_path = std::env::temp_dir().join(format!("fork-liquidation-{}.db", run_id));
+    let config_path = std::env::temp_dir().join(format!("fork-liquidation-{}.toml", run_id));
+    std::fs::write(
+        &config_path,
+        format!(
+            "rpc_url = \"{}\"\nws_url = \"{}\"\nprivate_key = \"{}\"\n\
+             database_url = \"sqlite:{}?mode=rwc\"\nliquidator_contract = \"{}\"\n\
+             target_user = \"{}\"\n",
+            harness.url(),
+            harness.ws_url(),
+            ANVIL_DEV_PRIVATE_KEY,
+            database_path.display(),
+            liquidator,
+            user
+        ),
+    )?;
+    let config = BotConfig::load_chains(Some(&config_path))?.remove(0);
+    let bot = LiquidationBot::new(Arc::new(harness.provider()?), config, vec![signer.into()]).await?;
+
+    let outcome = tokio::time::timeout(LIQUIDATION_TIMEOUT, async {
+        tokio::select! {
+            result = bot.run() => Err(eyre!("bot stopped before liquidating: {:?}", result)),
+            position = wait_for_debt_reduction(&harness, user, unhealthy.total_debt_base) => {
+                position
+            }
+        }
+    })
+    .await;
+
+    let _ = std::fs::remove_file(&config_path);
+    let _ = std::fs::remove_file(&database_path);
+
+    let liquidated = outcome
+        .map_err(|_| eyre!("position was not liquidated within {:?}", LIQUIDATION_TIMEOUT))??;
+    assert!(liquidated.total_debt_base < unhealthy.total_debt_base);
+    assert!(liquidated.total_collateral_base < unhealthy.total_collateral_base);
+    Ok(())
+}
+
+async fn wait_for_debt_reduction(
+    harness: &ForkHarness,
+    user: Address,
+    initial_debt: U256,
+) -> Result<UserPosition> {
+    loop {
+        let position = harness.position(user).await?;
+        if position.total_debt_base < initial_debt {
+            return Ok(position);
+        }
+        tokio::time::sleep(POLL_INTERVAL).await;
+    }
+}